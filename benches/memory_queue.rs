@@ -0,0 +1,99 @@
+//! Memory queue backend sharding benchmark.
+//!
+//! Compares a single `DashMap` against the default 64-shard layout under a
+//! 16-thread concurrent `enqueue` workload spread over 10,000 user IDs.
+//!
+//! Run with: `cargo bench --bench memory_queue`
+
+use std::sync::Arc;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use serde_json::json;
+
+use ara_notification_service::notification::NotificationEvent;
+use ara_notification_service::queue::{
+    MemoryQueueBackend, MemoryQueueBackendConfig, MessageQueueBackend, QueueConfig,
+};
+
+const THREADS: usize = 16;
+const UNIQUE_USERS: usize = 10_000;
+
+fn create_backend(shard_count: usize) -> Arc<MemoryQueueBackend> {
+    let config = QueueConfig {
+        enabled: true,
+        max_queue_size_per_user: 100,
+        message_ttl_seconds: 3600,
+        cleanup_interval_seconds: 300,
+    };
+    Arc::new(MemoryQueueBackend::with_backend_config(
+        config,
+        MemoryQueueBackendConfig { shard_count },
+    ))
+}
+
+/// User IDs with a long shared prefix, as produced by typical ID schemes
+fn user_ids() -> Arc<Vec<String>> {
+    Arc::new(
+        (0..UNIQUE_USERS)
+            .map(|i| format!("tenant-acme-user-{:08}", i))
+            .collect(),
+    )
+}
+
+fn bench_concurrent_enqueue(c: &mut Criterion) {
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(THREADS)
+        .build()
+        .expect("failed to build runtime");
+    let users = user_ids();
+    let event = NotificationEvent::builder("bench.event", "bench")
+        .payload(json!({"key": "value"}))
+        .build();
+
+    let mut group = c.benchmark_group("memory_queue_enqueue_16_threads");
+    group.throughput(Throughput::Elements(UNIQUE_USERS as u64));
+
+    for shard_count in [1usize, 64] {
+        group.bench_with_input(
+            BenchmarkId::new("shards", shard_count),
+            &shard_count,
+            |b, &shard_count| {
+                b.iter_batched(
+                    || create_backend(shard_count),
+                    |backend| {
+                        runtime.block_on(async {
+                            let chunk = UNIQUE_USERS / THREADS;
+                            let handles: Vec<_> = (0..THREADS)
+                                .map(|t| {
+                                    let backend = backend.clone();
+                                    let users = users.clone();
+                                    let event = event.clone();
+                                    tokio::spawn(async move {
+                                        let end = if t == THREADS - 1 {
+                                            UNIQUE_USERS
+                                        } else {
+                                            (t + 1) * chunk
+                                        };
+                                        for user_id in &users[t * chunk..end] {
+                                            backend.enqueue(user_id, event.clone()).await.unwrap();
+                                        }
+                                    })
+                                })
+                                .collect();
+                            for handle in handles {
+                                handle.await.unwrap();
+                            }
+                        });
+                        backend
+                    },
+                    criterion::BatchSize::LargeInput,
+                );
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_concurrent_enqueue);
+criterion_main!(benches);
@@ -0,0 +1,124 @@
+//! gRPC client example
+//!
+//! Publishes notifications through the gRPC API. Start the service with
+//! `GRPC_ENABLED=true`, then run with a JWT whose `scope` claim includes `publish`:
+//!
+//! ```bash
+//! GRPC_TOKEN=<jwt> cargo run --example client_example
+//! ```
+//!
+//! `GRPC_ENDPOINT` defaults to `http://127.0.0.1:50051`.
+
+use std::env;
+
+use tonic::metadata::{Ascii, MetadataValue};
+use tonic::transport::Channel;
+use tonic::{Request, Status};
+
+use ara_notification_service::triggers::grpc_proto::notification_service_client::NotificationServiceClient;
+use ara_notification_service::triggers::grpc_proto::{
+    batch_target, notification_content, BatchItem, BatchSendRequest, BatchTarget, BroadcastRequest,
+    DirectContent, NotificationContent, Priority, SendOptions, SendToChannelRequest,
+    SendToUserRequest, StringList,
+};
+
+fn direct(event_type: &str, payload: serde_json::Value) -> Option<NotificationContent> {
+    Some(NotificationContent {
+        content: Some(notification_content::Content::Direct(DirectContent {
+            event_type: event_type.to_string(),
+            payload_json: payload.to_string(),
+        })),
+    })
+}
+
+/// Interceptor attaching the bearer token to every call
+// `tonic::Status` is the interceptor's error type, so returning it directly is intended
+#[allow(clippy::result_large_err)]
+fn bearer_auth(
+    token: MetadataValue<Ascii>,
+) -> impl FnMut(Request<()>) -> Result<Request<()>, Status> + Clone {
+    move |mut req: Request<()>| {
+        req.metadata_mut().insert("authorization", token.clone());
+        Ok(req)
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let endpoint = env::var("GRPC_ENDPOINT").unwrap_or_else(|_| "http://127.0.0.1:50051".into());
+    let token: MetadataValue<_> = format!("Bearer {}", env::var("GRPC_TOKEN")?).parse()?;
+
+    let channel = Channel::from_shared(endpoint)?.connect().await?;
+    let mut client = NotificationServiceClient::with_interceptor(channel, bearer_auth(token));
+
+    let response = client
+        .send_to_user(SendToUserRequest {
+            target_user_id: "user-123".to_string(),
+            content: direct("order.created", serde_json::json!({"order_id": "ORD-001"})),
+            options: Some(SendOptions {
+                priority: Priority::High as i32,
+                ttl: Some(3600),
+                correlation_id: Some("example-1".to_string()),
+                notification_id: None,
+            }),
+        })
+        .await?
+        .into_inner();
+    println!("SendToUser: delivered_to={} id={}", response.delivered_to, response.notification_id);
+
+    let response = client
+        .send_to_channel(SendToChannelRequest {
+            channel: "orders".to_string(),
+            content: direct("order.updated", serde_json::json!({"status": "shipped"})),
+            options: None,
+            exclude_user_ids: vec![],
+        })
+        .await?
+        .into_inner();
+    println!("SendToChannel: delivered_to={}", response.delivered_to);
+
+    let response = client
+        .broadcast(BroadcastRequest {
+            content: direct("system.maintenance", serde_json::json!({"in_minutes": 10})),
+            options: None,
+            audience_json: None,
+        })
+        .await?
+        .into_inner();
+    println!("Broadcast: delivered_to={}", response.delivered_to);
+
+    let response = client
+        .batch_send(BatchSendRequest {
+            notifications: vec![
+                BatchItem {
+                    target: Some(BatchTarget {
+                        target: Some(batch_target::Target::Users(StringList {
+                            values: vec!["user-1".to_string(), "user-2".to_string()],
+                        })),
+                    }),
+                    content: direct("promo.started", serde_json::json!({"code": "SPRING"})),
+                    options: None,
+                },
+                BatchItem {
+                    target: Some(BatchTarget {
+                        target: Some(batch_target::Target::Channel("announcements".to_string())),
+                    }),
+                    content: direct("promo.started", serde_json::json!({"code": "SPRING"})),
+                    options: None,
+                },
+            ],
+            stop_on_error: false,
+            deduplicate: true,
+            dry_run: false,
+        })
+        .await?
+        .into_inner();
+    if let Some(summary) = response.summary {
+        println!(
+            "BatchSend: {} succeeded, {} failed, {} delivered",
+            summary.succeeded, summary.failed, summary.total_delivered
+        );
+    }
+
+    Ok(())
+}
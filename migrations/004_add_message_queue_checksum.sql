@@ -0,0 +1,4 @@
+-- CRC32 of event_data, verified when messages are read back; NULL for rows
+-- queued before checksums were recorded, which are not verified
+ALTER TABLE message_queue
+    ADD COLUMN IF NOT EXISTS checksum BIGINT;
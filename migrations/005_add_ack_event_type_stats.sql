@@ -0,0 +1,40 @@
+-- Per-event-type ACK rates for SLA monitoring.
+-- Pending rows are deleted on ACK and expiry, so the rates are kept in their
+-- own counters table rather than derived from pending_acks.
+
+-- Event type of each pending ACK ('' for rows tracked before it was recorded)
+ALTER TABLE pending_acks
+    ADD COLUMN IF NOT EXISTS event_type VARCHAR(255) NOT NULL DEFAULT '';
+
+-- Per-tenant, per-event-type ACK statistics
+CREATE TABLE IF NOT EXISTS ack_event_type_stats (
+    tenant_id VARCHAR(255) NOT NULL,
+    event_type VARCHAR(255) NOT NULL,
+    total_tracked BIGINT NOT NULL DEFAULT 0,
+    total_acked BIGINT NOT NULL DEFAULT 0,
+    total_expired BIGINT NOT NULL DEFAULT 0,
+    total_latency_ms BIGINT NOT NULL DEFAULT 0,
+    last_updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (tenant_id, event_type)
+);
+
+-- Upsert function for atomic per-event-type stats updates
+CREATE OR REPLACE FUNCTION upsert_ack_event_type_stats(
+    p_tenant_id VARCHAR(255),
+    p_event_type VARCHAR(255),
+    p_tracked BIGINT DEFAULT 0,
+    p_acked BIGINT DEFAULT 0,
+    p_expired BIGINT DEFAULT 0,
+    p_latency_ms BIGINT DEFAULT 0
+) RETURNS VOID AS $$
+BEGIN
+    INSERT INTO ack_event_type_stats (tenant_id, event_type, total_tracked, total_acked, total_expired, total_latency_ms, last_updated_at)
+    VALUES (p_tenant_id, p_event_type, p_tracked, p_acked, p_expired, p_latency_ms, NOW())
+    ON CONFLICT (tenant_id, event_type) DO UPDATE SET
+        total_tracked = ack_event_type_stats.total_tracked + EXCLUDED.total_tracked,
+        total_acked = ack_event_type_stats.total_acked + EXCLUDED.total_acked,
+        total_expired = ack_event_type_stats.total_expired + EXCLUDED.total_expired,
+        total_latency_ms = ack_event_type_stats.total_latency_ms + EXCLUDED.total_latency_ms,
+        last_updated_at = NOW();
+END;
+$$ LANGUAGE plpgsql;
@@ -0,0 +1,11 @@
+-- A notification is queued at most once per user: enqueue skips an event whose
+-- ID is already in the user's queue (ON CONFLICT DO NOTHING)
+DELETE FROM message_queue a
+    USING message_queue b
+    WHERE a.tenant_id = b.tenant_id
+    AND a.user_id = b.user_id
+    AND a.event_data->>'id' = b.event_data->>'id'
+    AND (a.queued_at, a.id) > (b.queued_at, b.id);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_message_queue_user_notification
+    ON message_queue (tenant_id, user_id, (event_data->>'id'));
@@ -0,0 +1,3 @@
+-- Number of redelivery attempts for a pending ACK
+ALTER TABLE pending_acks
+    ADD COLUMN IF NOT EXISTS retry_count INT DEFAULT 0;
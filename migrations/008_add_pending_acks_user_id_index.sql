@@ -0,0 +1,3 @@
+-- Index for per-user lookups that span tenants
+CREATE INDEX IF NOT EXISTS idx_pending_acks_user_id
+    ON pending_acks(user_id);
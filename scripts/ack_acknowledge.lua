@@ -0,0 +1,30 @@
+-- Validate and consume a pending ACK in one atomic step.
+--
+-- Reading, checking and deleting the pending hash inside one script ensures
+-- that two concurrent ACKs for the same notification (e.g. from two
+-- connections of the same user) cannot both succeed.
+--
+-- KEYS[1] pending ACK hash   ({prefix}:{tenant_id}:pending:{notification_id})
+-- KEYS[2] timeout sorted set ({prefix}:{tenant_id}:timeout)
+-- KEYS[3] user index hash    ({prefix}:{tenant_id}:user:{user_id})
+-- ARGV[1] acknowledging user ID
+-- ARGV[2] timeout set member (notification ID)
+--
+-- Returns:
+--   nil          unknown or already acknowledged notification
+--   {0, data}    user mismatch, pending ACK left in place
+--   {1, data}    acknowledged, pending ACK removed
+local data = redis.call('HGET', KEYS[1], 'data')
+if not data then
+    return nil
+end
+
+local ok, pending = pcall(cjson.decode, data)
+if not ok or pending['user_id'] ~= ARGV[1] then
+    return {0, data}
+end
+
+redis.call('DEL', KEYS[1])
+redis.call('ZREM', KEYS[2], ARGV[2])
+redis.call('HDEL', KEYS[3], ARGV[2])
+return {1, data}
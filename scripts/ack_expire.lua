@@ -0,0 +1,15 @@
+-- Remove an expired pending ACK and its index entries in one atomic step.
+--
+-- KEYS[1] pending ACK hash   ({prefix}:{tenant_id}:pending:{notification_id})
+-- KEYS[2] timeout sorted set ({prefix}:{tenant_id}:timeout)
+-- KEYS[3] user index hash    ({prefix}:{tenant_id}:user:{user_id}), optional;
+--         omitted when the pending info is already gone and the user is unknown
+-- ARGV[1] notification ID    (timeout set member and user index field)
+--
+-- Returns 1.
+redis.call('DEL', KEYS[1])
+redis.call('ZREM', KEYS[2], ARGV[1])
+if KEYS[3] then
+    redis.call('HDEL', KEYS[3], ARGV[1])
+end
+return 1
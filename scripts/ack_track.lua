@@ -0,0 +1,20 @@
+-- Store a pending ACK and set its expiry in one atomic step.
+--
+-- Replaces the separate HSET + EXPIRE calls, which left a window where the
+-- pending hash existed without a TTL if the service died in between. The
+-- pending info is also indexed in a per-user hash so a user's pending ACKs
+-- can be listed with HSCAN.
+--
+-- KEYS[1] pending ACK hash   ({prefix}:{tenant_id}:pending:{notification_id})
+-- KEYS[2] user index hash    ({prefix}:{tenant_id}:user:{user_id})
+-- ARGV[1] hash field         ("data")
+-- ARGV[2] hash value         (serialized PendingAckInfo JSON)
+-- ARGV[3] TTL in seconds
+-- ARGV[4] notification ID    (field in the user index hash)
+--
+-- Returns 1.
+redis.call('HSET', KEYS[1], ARGV[1], ARGV[2])
+redis.call('EXPIRE', KEYS[1], ARGV[3])
+redis.call('HSET', KEYS[2], ARGV[4], ARGV[2])
+redis.call('EXPIRE', KEYS[2], ARGV[3])
+return 1
@@ -0,0 +1,26 @@
+-- Append a message to a user's queue stream unless its notification is already queued.
+--
+-- KEYS[1] queue stream          ({prefix}:{tenant_id}:{user_id})
+-- KEYS[2] queued notification   ({prefix}-ids:{tenant_id}:{user_id}),
+--         IDs (sorted set, score = enqueue time in Unix seconds)
+-- ARGV[1] notification ID
+-- ARGV[2] serialized message
+-- ARGV[3] max queue size per user
+-- ARGV[4] message TTL in seconds
+-- ARGV[5] current Unix time in seconds
+--
+-- Returns 1 if the message was queued, 0 if the notification was already queued.
+local now = tonumber(ARGV[5])
+local cutoff = now - tonumber(ARGV[4])
+local queued_at = redis.call('ZSCORE', KEYS[2], ARGV[1])
+if queued_at and tonumber(queued_at) > cutoff then
+    return 0
+end
+
+redis.call('XADD', KEYS[1], 'MAXLEN', ARGV[3], '*', 'data', ARGV[2])
+redis.call('ZADD', KEYS[2], now, ARGV[1])
+-- Forget IDs of expired messages and of messages trimmed from the stream
+redis.call('ZREMRANGEBYSCORE', KEYS[2], '-inf', cutoff)
+redis.call('ZREMRANGEBYRANK', KEYS[2], 0, -(tonumber(ARGV[3]) + 1))
+redis.call('EXPIRE', KEYS[2], ARGV[4])
+return 1
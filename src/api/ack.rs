@@ -0,0 +1,154 @@
+//! ACK status endpoints for authenticated clients.
+
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderMap, StatusCode},
+    Json,
+};
+use serde::Serialize;
+
+use crate::notification::{AckEventTypeSummary, PendingAckInfo};
+use crate::server::AppState;
+
+use super::connection::{error_response, ChannelErrorResponse};
+
+#[derive(Debug, Serialize)]
+pub struct PendingAcksResponse {
+    pub user_id: String,
+    pub pending: Vec<PendingAckInfo>,
+    pub total: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AckSummaryResponse {
+    pub event_types: Vec<AckEventTypeSummary>,
+    pub total: usize,
+}
+
+/// GET /users/{user_id}/pending-acks - List the caller's unacknowledged notifications
+///
+/// Requires a user JWT (`Authorization: Bearer <token>`); users may only query their own ACKs.
+#[tracing::instrument(name = "http.get_user_pending_acks", skip(state, headers))]
+pub async fn get_user_pending_acks(
+    State(state): State<AppState>,
+    Path(user_id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Json<PendingAcksResponse>, (StatusCode, Json<ChannelErrorResponse>)> {
+    let token = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .ok_or_else(|| {
+            error_response(
+                StatusCode::UNAUTHORIZED,
+                "UNAUTHORIZED",
+                "Missing authentication token",
+            )
+        })?;
+
+    let claims = state.jwt_validator.validate(token).map_err(|e| {
+        tracing::warn!(error = %e, "JWT validation failed");
+        error_response(StatusCode::UNAUTHORIZED, "UNAUTHORIZED", "Invalid token")
+    })?;
+
+    if claims.sub != user_id {
+        return Err(error_response(
+            StatusCode::FORBIDDEN,
+            "FORBIDDEN",
+            "Users can only query their own pending ACKs",
+        ));
+    }
+
+    // Pending ACKs are keyed by the tenant-namespaced user ID
+    let user_key = crate::auth::tenant_scoped_key(claims.tenant_id(), &user_id);
+    let mut pending = state
+        .ack_backend
+        .get_pending_by_user(&user_key)
+        .await
+        .map_err(|e| {
+            tracing::warn!(user_id = %user_id, error = %e, "Failed to load pending ACKs");
+            error_response(
+                StatusCode::SERVICE_UNAVAILABLE,
+                "ACK_BACKEND_UNAVAILABLE",
+                "Failed to load pending ACKs",
+            )
+        })?;
+
+    for info in &mut pending {
+        info.user_id.clone_from(&user_id);
+    }
+
+    Ok(Json(PendingAcksResponse {
+        user_id,
+        total: pending.len(),
+        pending,
+    }))
+}
+
+/// GET /admin/ack/summary - ACK rates and latency per event type, for SLA monitoring
+pub async fn ack_summary(
+    State(state): State<AppState>,
+) -> Result<Json<AckSummaryResponse>, (StatusCode, Json<ChannelErrorResponse>)> {
+    let event_types = state
+        .ack_backend
+        .summary_by_event_type()
+        .await
+        .map_err(|e| {
+            tracing::warn!(error = %e, "Failed to load ACK summary");
+            error_response(
+                StatusCode::SERVICE_UNAVAILABLE,
+                "ACK_BACKEND_UNAVAILABLE",
+                "Failed to load ACK summary",
+            )
+        })?;
+
+    Ok(Json(AckSummaryResponse {
+        total: event_types.len(),
+        event_types,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::StatusCode;
+    use serde_json::json;
+    use tower::ServiceExt;
+    use uuid::Uuid;
+
+    use crate::api::test_support::{admin_request, response_json, test_state_with};
+    use crate::server::create_app;
+
+    #[tokio::test]
+    async fn test_ack_summary_reports_rates_per_event_type() {
+        let state = test_state_with(json!({ "ack": { "enabled": true } })).await;
+        let acked = Uuid::new_v4();
+        state
+            .ack_backend
+            .track(acked, "user-1", Uuid::new_v4(), "order.created")
+            .await;
+        state
+            .ack_backend
+            .track(Uuid::new_v4(), "user-1", Uuid::new_v4(), "order.created")
+            .await;
+        state
+            .ack_backend
+            .track(Uuid::new_v4(), "user-2", Uuid::new_v4(), "chat.message")
+            .await;
+        assert!(state.ack_backend.acknowledge(acked, "user-1").await);
+        let app = create_app(state);
+
+        let response = app
+            .oneshot(admin_request("GET", "/admin/ack/summary", json!({})))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response_json(response).await;
+        assert_eq!(body["total"], 2);
+        assert_eq!(body["event_types"][0]["event_type"], "chat.message");
+        assert_eq!(body["event_types"][0]["acked"], 0);
+        let orders = &body["event_types"][1];
+        assert_eq!(orders["event_type"], "order.created");
+        assert_eq!(orders["total"], 2);
+        assert_eq!(orders["acked"], 1);
+    }
+}
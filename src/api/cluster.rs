@@ -1,14 +1,21 @@
 //! Cluster management endpoints.
 
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
+    http::StatusCode,
     Extension, Json,
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use crate::server::middleware::RequestTenantContext;
 use crate::server::AppState;
 
+use super::connection::{error_response, ChannelErrorResponse};
+
+/// Default and maximum page sizes for the admin user sessions listing
+const DEFAULT_SESSIONS_PAGE_SIZE: usize = 100;
+const MAX_SESSIONS_PAGE_SIZE: usize = 1000;
+
 #[derive(Debug, Serialize)]
 pub struct ClusterStatusResponse {
     pub enabled: bool,
@@ -31,21 +38,18 @@ pub async fn cluster_status(State(state): State<AppState>) -> Json<ClusterStatus
 
     let conn_stats = state.connection_manager.stats();
 
-    let (cluster_connections, cluster_users) = if enabled {
-        match state.session_store.get_all_sessions().await {
-            Ok(sessions) => {
-                let unique_users: std::collections::HashSet<_> = sessions.iter()
-                    .map(|s| s.user_id.clone())
-                    .collect();
-                (Some(sessions.len()), Some(unique_users.len()))
-            }
-            Err(e) => {
-                tracing::warn!(error = %e, "Failed to get cluster sessions");
-                (None, None)
-            }
+    // In standalone mode the local session store holds this server's sessions
+    let (cluster_connections, cluster_users) = match state.session_store.get_all_sessions().await {
+        Ok(sessions) => {
+            let unique_users: std::collections::HashSet<_> = sessions.iter()
+                .map(|s| s.user_id.clone())
+                .collect();
+            (Some(sessions.len()), Some(unique_users.len()))
+        }
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to get cluster sessions");
+            (None, None)
         }
-    } else {
-        (None, None)
     };
 
     crate::metrics::ClusterMetrics::set_enabled(enabled);
@@ -89,30 +93,6 @@ pub async fn cluster_user_location(
     tenant_ctx: Option<Extension<RequestTenantContext>>,
     Path(user_id): Path<String>,
 ) -> Json<UserLocationResponse> {
-    if !state.session_store.is_enabled() {
-        let connections = state.connection_manager.get_user_connections(&user_id);
-        // Filter connections by tenant when multi-tenancy is enabled
-        let filtered: Vec<_> = match tenant_ctx.as_ref() {
-            Some(t) => {
-                let tid = t.0.tenant_id();
-                connections.into_iter().filter(|c| c.tenant_id == tid).collect()
-            }
-            None => connections,
-        };
-        let sessions: Vec<UserSessionInfo> = filtered.iter().map(|c| UserSessionInfo {
-            connection_id: c.id.to_string(),
-            server_id: state.session_store.server_id().to_string(),
-            connected_at: c.connected_at.timestamp(),
-            channels: vec![],
-        }).collect();
-
-        return Json(UserLocationResponse {
-            user_id,
-            found: !sessions.is_empty(),
-            sessions,
-        });
-    }
-
     match state.session_store.get_user_sessions(&user_id).await {
         Ok(sessions) => {
             // Filter sessions by tenant when multi-tenancy is enabled
@@ -146,3 +126,135 @@ pub async fn cluster_user_location(
         }
     }
 }
+
+#[derive(Debug, Deserialize)]
+pub struct UserSessionsQuery {
+    /// Cursor returned by the previous page, 0 for the first page
+    #[serde(default)]
+    pub cursor: u64,
+    /// Page size hint (1-1000), defaults to 100
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UserSessionsPageResponse {
+    pub user_id: String,
+    pub sessions: Vec<UserSessionInfo>,
+    /// Cursor for the next page, 0 once all sessions have been listed
+    pub next_cursor: u64,
+}
+
+/// GET /admin/users/:id/sessions?cursor=&limit= - Page through a user's sessions across the cluster
+#[tracing::instrument(name = "http.list_user_sessions", skip(state))]
+pub async fn list_user_sessions(
+    State(state): State<AppState>,
+    Path(user_id): Path<String>,
+    Query(query): Query<UserSessionsQuery>,
+) -> Result<Json<UserSessionsPageResponse>, (StatusCode, Json<ChannelErrorResponse>)> {
+    let limit = query.limit.unwrap_or(DEFAULT_SESSIONS_PAGE_SIZE);
+    if !(1..=MAX_SESSIONS_PAGE_SIZE).contains(&limit) {
+        return Err(error_response(
+            StatusCode::BAD_REQUEST,
+            "INVALID_LIMIT",
+            format!("limit must be between 1 and {}", MAX_SESSIONS_PAGE_SIZE),
+        ));
+    }
+
+    let (sessions, next_cursor) = state
+        .session_store
+        .get_user_sessions_page(&user_id, query.cursor, limit)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, user_id = %user_id, "Failed to list user sessions");
+            error_response(
+                StatusCode::SERVICE_UNAVAILABLE,
+                "SESSION_STORE_UNAVAILABLE",
+                "Session store is unavailable",
+            )
+        })?;
+
+    Ok(Json(UserSessionsPageResponse {
+        user_id,
+        sessions: sessions
+            .iter()
+            .map(|s| UserSessionInfo {
+                connection_id: s.connection_id.to_string(),
+                server_id: s.server_id.clone(),
+                connected_at: s.connected_at,
+                channels: s.channels.clone(),
+            })
+            .collect(),
+        next_cursor,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::StatusCode;
+    use serde_json::json;
+    use tower::ServiceExt;
+    use uuid::Uuid;
+
+    use crate::api::test_support::{admin_request, response_json, test_state};
+    use crate::cluster::SessionInfo;
+    use crate::server::create_app;
+
+    fn session(user_id: &str, connected_at: i64) -> SessionInfo {
+        SessionInfo {
+            connection_id: Uuid::new_v4(),
+            user_id: user_id.to_string(),
+            tenant_id: "default".to_string(),
+            server_id: "test-server".to_string(),
+            connected_at,
+            channels: vec!["orders".to_string()],
+            server_tags: Default::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_list_user_sessions_pages() {
+        let state = test_state().await;
+        for connected_at in 0..5 {
+            state.session_store.register_session(&session("user-1", connected_at)).await.unwrap();
+        }
+        state.session_store.register_session(&session("user-2", 0)).await.unwrap();
+        let app = create_app(state);
+
+        // Single page holding every session
+        let response = app
+            .clone()
+            .oneshot(admin_request("GET", "/admin/users/user-1/sessions", json!({})))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response_json(response).await;
+        assert_eq!(body["sessions"].as_array().unwrap().len(), 5);
+        assert_eq!(body["sessions"][0]["channels"], json!(["orders"]));
+        assert_eq!(body["next_cursor"], 0);
+
+        // Multiple pages, following the cursor until exhausted
+        let mut cursor = 0;
+        let mut seen = Vec::new();
+        loop {
+            let uri = format!("/admin/users/user-1/sessions?cursor={}&limit=2", cursor);
+            let response =
+                app.clone().oneshot(admin_request("GET", &uri, json!({}))).await.unwrap();
+            let body = response_json(response).await;
+            let page = body["sessions"].as_array().unwrap();
+            assert!(page.len() <= 2);
+            seen.extend(page.iter().map(|s| s["connected_at"].as_i64().unwrap()));
+            cursor = body["next_cursor"].as_u64().unwrap();
+            if cursor == 0 {
+                break;
+            }
+        }
+        assert_eq!(seen, vec![0, 1, 2, 3, 4]);
+
+        let response = app
+            .oneshot(admin_request("GET", "/admin/users/user-1/sessions?limit=0", json!({})))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(response_json(response).await["error"]["code"], "INVALID_LIMIT");
+    }
+}
@@ -1,15 +1,21 @@
 //! Connection and channel management endpoints.
 
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
     Extension, Json,
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
-use crate::connection_manager::ChannelInfo;
-use crate::server::middleware::RequestTenantContext;
+use crate::auth::{tenant_scoped_key, AdminAuth, DEFAULT_TENANT_ID};
+use crate::connection_manager::{ChannelInfo, Transport};
+use crate::metrics::USER_CONNECTION_QUERIES_TOTAL;
+use crate::notification::PayloadEncoding;
+use crate::server::middleware::{is_valid_tenant_id, RequestTenantContext};
 use crate::server::AppState;
+use crate::websocket::ServerMessage;
 
 // ============================================================================
 // Channel Endpoints
@@ -25,6 +31,9 @@ pub struct ChannelListResponse {
 pub struct ChannelDetailResponse {
     pub name: String,
     pub subscriber_count: usize,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub peak_subscribers: usize,
+    pub total_subscriber_events: u64,
 }
 
 #[derive(Debug, Serialize)]
@@ -38,6 +47,23 @@ pub struct ChannelError {
     pub message: String,
 }
 
+/// Build an error response in the `{ "error": { code, message } }` shape
+pub(super) fn error_response(
+    status: StatusCode,
+    code: &str,
+    message: impl Into<String>,
+) -> (StatusCode, Json<ChannelErrorResponse>) {
+    (
+        status,
+        Json(ChannelErrorResponse {
+            error: ChannelError {
+                code: code.to_string(),
+                message: message.into(),
+            },
+        }),
+    )
+}
+
 /// GET /api/v1/channels - List channels with subscriber counts (tenant-filtered)
 pub async fn list_channels(
     State(state): State<AppState>,
@@ -79,6 +105,9 @@ pub async fn get_channel(
         Some(info) => Ok(Json(ChannelDetailResponse {
             name: info.name,
             subscriber_count: info.subscriber_count,
+            created_at: info.created_at,
+            peak_subscribers: info.peak_subscribers,
+            total_subscriber_events: info.total_subscriber_events,
         })),
         None => Err((
             StatusCode::NOT_FOUND,
@@ -109,33 +138,17 @@ pub async fn get_user_subscriptions(
     tenant_ctx: Option<Extension<RequestTenantContext>>,
     Path(user_id): Path<String>,
 ) -> Result<Json<UserSubscriptionsResponse>, (StatusCode, Json<ChannelErrorResponse>)> {
-    match state.connection_manager.get_user_subscriptions(&user_id).await {
-        Some(info) => {
-            // Filter connections and subscriptions by tenant when multi-tenancy is enabled
-            let (connection_count, subscriptions) = match tenant_ctx.as_ref() {
-                Some(t) if !t.0 .0.is_default => {
-                    let tenant_id = t.0.tenant_id();
-                    let connections = state.connection_manager.get_user_connections(&user_id);
-                    let tenant_conn_count = connections
-                        .iter()
-                        .filter(|c| c.tenant_id == tenant_id)
-                        .count();
-                    let prefix = format!("{}:", tenant_id);
-                    let tenant_subs: Vec<String> = info
-                        .subscriptions
-                        .into_iter()
-                        .filter(|s| s.starts_with(&prefix))
-                        .collect();
-                    (tenant_conn_count, tenant_subs)
-                }
-                _ => (info.connection_count, info.subscriptions),
-            };
-            Ok(Json(UserSubscriptionsResponse {
-                user_id: info.user_id,
-                connection_count,
-                subscriptions,
-            }))
-        }
+    // Connections are indexed per tenant, so only this tenant's connections are found
+    let user_key = match tenant_ctx.as_ref() {
+        Some(t) => t.namespace_user_id(&user_id),
+        None => user_id.clone(),
+    };
+    match state.connection_manager.get_user_subscriptions(&user_key).await {
+        Some(info) => Ok(Json(UserSubscriptionsResponse {
+            user_id: info.user_id,
+            connection_count: info.connection_count,
+            subscriptions: info.subscriptions,
+        })),
         None => Err((
             StatusCode::NOT_FOUND,
             Json(ChannelErrorResponse {
@@ -147,3 +160,582 @@ pub async fn get_user_subscriptions(
         )),
     }
 }
+
+// ============================================================================
+// User Connection Endpoints
+// ============================================================================
+
+/// Default page size for `/users/{user_id}/connections`
+const DEFAULT_CONNECTIONS_PER_PAGE: usize = 20;
+/// Largest accepted `per_page` for `/users/{user_id}/connections`
+const MAX_CONNECTIONS_PER_PAGE: usize = 100;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct UserConnectionsQuery {
+    /// 1-based page of the connection list
+    pub page: Option<usize>,
+    pub per_page: Option<usize>,
+    /// Tenant of the user for admin callers (user tokens always use their own tenant)
+    pub tenant_id: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UserConnectionsResponse {
+    pub user_id: String,
+    /// Active connections across all pages
+    pub total: usize,
+    pub page: usize,
+    pub per_page: usize,
+    pub connections: Vec<UserConnectionInfo>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UserConnectionInfo {
+    pub connection_id: Uuid,
+    pub transport: Transport,
+    pub connected_at: chrono::DateTime<chrono::Utc>,
+    pub subscribed_channels: Vec<String>,
+    pub ping_miss_count: u32,
+    pub metadata: UserConnectionMetadata,
+}
+
+/// Client preferences negotiated when the connection was opened
+#[derive(Debug, Serialize)]
+pub struct UserConnectionMetadata {
+    pub payload_encoding: PayloadEncoding,
+    pub protocol_version: u8,
+}
+
+/// GET /users/{user_id}/connections?page=&per_page= - List a user's active connections on
+/// this server, oldest first
+///
+/// Requires either a user JWT whose `sub` is `user_id` or the admin API key. Admin callers
+/// pick the user's tenant with `?tenant_id=` (default tenant otherwise).
+#[tracing::instrument(name = "http.get_user_connections", skip(state, headers, query))]
+pub async fn get_user_connections(
+    State(state): State<AppState>,
+    Path(user_id): Path<String>,
+    Query(query): Query<UserConnectionsQuery>,
+    headers: HeaderMap,
+) -> Result<Json<UserConnectionsResponse>, (StatusCode, Json<ChannelErrorResponse>)> {
+    USER_CONNECTION_QUERIES_TOTAL.inc();
+
+    let token = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    let tenant_id = match token.map(|token| state.jwt_validator.validate(token)) {
+        Some(Ok(claims)) => {
+            if claims.sub != user_id {
+                return Err(error_response(
+                    StatusCode::FORBIDDEN,
+                    "FORBIDDEN",
+                    "Users can only list their own connections",
+                ));
+            }
+            claims.tenant_id().to_string()
+        }
+        // Not a valid user token: only the admin API key is accepted
+        _ => {
+            AdminAuth::verify(&state.settings, &headers).map_err(|_| {
+                error_response(
+                    StatusCode::UNAUTHORIZED,
+                    "UNAUTHORIZED",
+                    "A user token or the admin API key is required",
+                )
+            })?;
+            let tenant_id = query.tenant_id.as_deref().unwrap_or(DEFAULT_TENANT_ID);
+            if !is_valid_tenant_id(tenant_id) {
+                return Err(error_response(
+                    StatusCode::BAD_REQUEST,
+                    "INVALID_TENANT_ID",
+                    "tenant_id must be 1-64 alphanumeric, '-', '_' or '.' characters",
+                ));
+            }
+            tenant_id.to_string()
+        }
+    };
+
+    let per_page = query.per_page.unwrap_or(DEFAULT_CONNECTIONS_PER_PAGE);
+    if per_page == 0 || per_page > MAX_CONNECTIONS_PER_PAGE {
+        return Err(error_response(
+            StatusCode::BAD_REQUEST,
+            "INVALID_PAGINATION",
+            format!("per_page must be between 1 and {}", MAX_CONNECTIONS_PER_PAGE),
+        ));
+    }
+    let page = query.page.unwrap_or(1);
+    if page == 0 {
+        return Err(error_response(
+            StatusCode::BAD_REQUEST,
+            "INVALID_PAGINATION",
+            "page starts at 1",
+        ));
+    }
+
+    // A default-tenant user ID containing ':' can share a key with another tenant's user
+    let mut handles: Vec<_> = state
+        .connection_manager
+        .get_user_connections(&tenant_scoped_key(&tenant_id, &user_id))
+        .into_iter()
+        .filter(|handle| handle.tenant_id == tenant_id)
+        .collect();
+    if handles.is_empty() {
+        return Err(error_response(
+            StatusCode::NOT_FOUND,
+            "USER_NOT_CONNECTED",
+            format!("User '{}' has no active connections", user_id),
+        ));
+    }
+    handles.sort_by_key(|handle| (handle.connected_at, handle.id));
+
+    let total = handles.len();
+    let start = (page - 1).saturating_mul(per_page).min(total);
+    let end = start.saturating_add(per_page).min(total);
+    let mut connections = Vec::with_capacity(end - start);
+    for handle in &handles[start..end] {
+        let mut subscribed_channels: Vec<String> =
+            handle.subscriptions.read().await.iter().cloned().collect();
+        subscribed_channels.sort();
+        connections.push(UserConnectionInfo {
+            connection_id: handle.id,
+            transport: handle.metadata.transport,
+            connected_at: handle.connected_at,
+            subscribed_channels,
+            ping_miss_count: handle.missed_pings(),
+            metadata: UserConnectionMetadata {
+                payload_encoding: handle.metadata.payload_encoding,
+                protocol_version: handle.metadata.protocol_version,
+            },
+        });
+    }
+
+    Ok(Json(UserConnectionsResponse {
+        user_id,
+        total,
+        page,
+        per_page,
+        connections,
+    }))
+}
+
+// ============================================================================
+// Targeted Connection Endpoints
+// ============================================================================
+
+#[derive(Debug, Serialize)]
+pub struct ConnectionSendResponse {
+    pub connection_id: Uuid,
+    pub user_id: String,
+    pub delivered: bool,
+}
+
+/// POST /admin/connections/:id/send - Send a raw `ServerMessage` to a single connection
+#[tracing::instrument(name = "http.send_to_connection", skip(state, message))]
+pub async fn send_to_connection(
+    State(state): State<AppState>,
+    Path(connection_id): Path<Uuid>,
+    Json(message): Json<ServerMessage>,
+) -> Result<Json<ConnectionSendResponse>, (StatusCode, Json<ChannelErrorResponse>)> {
+    let Some(handle) = state.connection_manager.get_connection(connection_id) else {
+        return Err(error_response(
+            StatusCode::NOT_FOUND,
+            "CONNECTION_NOT_FOUND",
+            format!("Connection '{}' not found", connection_id),
+        ));
+    };
+
+    if handle.send(message).await.is_err() {
+        return Err(error_response(
+            StatusCode::GONE,
+            "CONNECTION_CLOSED",
+            format!("Connection '{}' is no longer accepting messages", connection_id),
+        ));
+    }
+
+    Ok(Json(ConnectionSendResponse {
+        connection_id,
+        user_id: handle.user_id.clone(),
+        delivered: true,
+    }))
+}
+
+#[derive(Debug, Serialize)]
+pub struct ChannelDeleteResponse {
+    pub subscribers_notified: usize,
+}
+
+/// DELETE /admin/channels/:name - Delete a channel, unsubscribing every subscriber.
+/// The name is used as-is, so tenant channels are addressed as `{tenant_id}:{channel}`.
+#[tracing::instrument(name = "http.delete_channel", skip(state))]
+pub async fn delete_channel(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Json<ChannelDeleteResponse>, (StatusCode, Json<ChannelErrorResponse>)> {
+    let subscribers = state.connection_manager.delete_channel(&name).await;
+    if subscribers.is_empty() {
+        return Err(error_response(
+            StatusCode::NOT_FOUND,
+            "CHANNEL_NOT_FOUND",
+            format!("Channel '{}' not found or has no subscribers", name),
+        ));
+    }
+
+    // Keep cross-server routing from sending the channel to these connections
+    for handle in &subscribers {
+        let channels: Vec<String> = handle.subscriptions.read().await.iter().cloned().collect();
+        if let Err(e) = state.session_store.update_session_channels(handle.id, channels).await {
+            tracing::warn!(
+                connection_id = %handle.id,
+                error = %e,
+                "Failed to update session channels in cluster store"
+            );
+        }
+    }
+
+    Ok(Json(ChannelDeleteResponse {
+        subscribers_notified: subscribers.len(),
+    }))
+}
+
+/// GET /admin/connections/snapshot - Point-in-time copy of every connection, for debugging.
+/// Snapshots larger than `admin.max_snapshot_bytes` are rejected with 413.
+pub async fn connections_snapshot(
+    State(state): State<AppState>,
+) -> Result<Response, (StatusCode, Json<ChannelErrorResponse>)> {
+    let snapshot = state.connection_manager.snapshot().await;
+    let body = serde_json::to_vec(&snapshot).map_err(|e| {
+        error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "SNAPSHOT_FAILED",
+            format!("Failed to serialize snapshot: {}", e),
+        )
+    })?;
+
+    let max_bytes = state.settings.admin.max_snapshot_bytes;
+    if body.len() > max_bytes {
+        return Err(error_response(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            "SNAPSHOT_TOO_LARGE",
+            format!(
+                "Snapshot of {} connections is {} bytes, above the {} byte limit",
+                snapshot.connections.len(),
+                body.len(),
+                max_bytes
+            ),
+        ));
+    }
+
+    Ok(([(header::CONTENT_TYPE, "application/json")], body).into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::StatusCode;
+    use serde_json::json;
+    use tokio::sync::mpsc;
+    use tower::ServiceExt;
+
+    use crate::api::test_support::{
+        admin_request, bearer_token, json_request, response_json, test_claims, test_state,
+        test_state_with,
+    };
+    use crate::connection_manager::{ConnectionMetadata, Transport};
+    use crate::notification::PayloadEncoding;
+    use crate::server::create_app;
+    use crate::websocket::{OutboundMessage, ServerMessage};
+
+    const ADMIN_KEY: &str = "admin-key-for-connection-tests-0123";
+
+    fn get_with_auth(uri: &str, authorization: Option<&str>) -> axum::http::Request<axum::body::Body> {
+        let mut request = json_request("GET", uri, json!({}));
+        if let Some(authorization) = authorization {
+            request
+                .headers_mut()
+                .insert(axum::http::header::AUTHORIZATION, authorization.parse().unwrap());
+        }
+        request
+    }
+
+    #[tokio::test]
+    async fn test_get_user_connections() {
+        let state = test_state_with(json!({ "api": { "admin_key": ADMIN_KEY } })).await;
+        let mut receivers = Vec::new();
+        for metadata in [
+            ConnectionMetadata {
+                payload_encoding: PayloadEncoding::Msgpack,
+                ..Default::default()
+            },
+            ConnectionMetadata {
+                transport: Transport::Sse,
+                ..Default::default()
+            },
+        ] {
+            let (tx, rx) = mpsc::channel(8);
+            receivers.push(rx);
+            state
+                .connection_manager
+                .register_with_metadata("user-1".to_string(), "default".to_string(), vec![], tx, metadata)
+                .unwrap();
+        }
+        let first = state.connection_manager.get_user_connections("user-1");
+        let websocket = first.iter().find(|h| h.metadata.transport == Transport::WebSocket).unwrap();
+        state
+            .connection_manager
+            .subscribe_to_channel(websocket.id, "orders")
+            .await
+            .unwrap();
+        let user_token = bearer_token(&state, &test_claims("user-1"));
+        let other_token = bearer_token(&state, &test_claims("user-2"));
+        let admin = format!("Bearer {}", ADMIN_KEY);
+        let app = create_app(state);
+
+        let response = app
+            .clone()
+            .oneshot(get_with_auth("/users/user-1/connections", Some(&user_token)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response_json(response).await;
+        assert_eq!(body["user_id"], "user-1");
+        assert_eq!(body["total"], 2);
+        assert_eq!(body["page"], 1);
+        assert_eq!(body["per_page"], 20);
+        let connections = body["connections"].as_array().unwrap();
+        assert_eq!(connections.len(), 2);
+        let ws = connections
+            .iter()
+            .find(|c| c["transport"] == "websocket")
+            .unwrap();
+        assert_eq!(ws["connection_id"], websocket.id.to_string());
+        assert_eq!(ws["subscribed_channels"], json!(["orders"]));
+        assert_eq!(ws["ping_miss_count"], 0);
+        assert_eq!(ws["metadata"]["payload_encoding"], "msgpack");
+        assert!(ws["metadata"]["protocol_version"].is_u64());
+        assert!(ws["connected_at"].is_string());
+        assert!(connections.iter().any(|c| c["transport"] == "sse"));
+
+        // Pages hold `per_page` connections
+        let response = app
+            .clone()
+            .oneshot(get_with_auth("/users/user-1/connections?page=2&per_page=1", Some(&admin)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response_json(response).await;
+        assert_eq!(body["total"], 2);
+        assert_eq!(body["connections"].as_array().unwrap().len(), 1);
+        assert_eq!(body["connections"][0], connections[1]);
+
+        for (uri, authorization, expected) in [
+            ("/users/user-1/connections", Some(other_token.as_str()), StatusCode::FORBIDDEN),
+            ("/users/user-1/connections", None, StatusCode::UNAUTHORIZED),
+            ("/users/user-1/connections", Some("Bearer wrong"), StatusCode::UNAUTHORIZED),
+            ("/users/user-1/connections?per_page=0", Some(admin.as_str()), StatusCode::BAD_REQUEST),
+            ("/users/user-9/connections", Some(admin.as_str()), StatusCode::NOT_FOUND),
+        ] {
+            let response = app
+                .clone()
+                .oneshot(get_with_auth(uri, authorization))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), expected, "{uri} {authorization:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_user_connections_requires_a_configured_key() {
+        // No admin or API key configured: the admin fallback must not fail open
+        let state = test_state_with(json!({ "api": {} })).await;
+        let (tx, _rx) = mpsc::channel(8);
+        state
+            .connection_manager
+            .register("user-1".to_string(), "default".to_string(), vec![], tx)
+            .unwrap();
+        let app = create_app(state);
+
+        for authorization in [None, Some("Bearer anything")] {
+            let response = app
+                .clone()
+                .oneshot(get_with_auth("/users/user-1/connections", authorization))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED, "{authorization:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_send_to_connection() {
+        let state = test_state().await;
+        let (tx, mut rx) = mpsc::channel(8);
+        let handle = state
+            .connection_manager
+            .register("user-1".to_string(), "default".to_string(), vec![], tx)
+            .unwrap();
+        let app = create_app(state);
+
+        let response = app
+            .clone()
+            .oneshot(admin_request(
+                "POST",
+                &format!("/admin/connections/{}/send", handle.id),
+                json!({ "type": "error", "code": "RETRY", "message": "please re-ack" }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response_json(response).await;
+        assert_eq!(body["user_id"], "user-1");
+        assert_eq!(body["delivered"], true);
+
+        match rx.try_recv().unwrap() {
+            OutboundMessage::Raw(ServerMessage::Error { code, .. }) => assert_eq!(code, "RETRY"),
+            other => panic!("unexpected message: {:?}", other),
+        }
+
+        let response = app
+            .oneshot(admin_request(
+                "POST",
+                &format!("/admin/connections/{}/send", uuid::Uuid::new_v4()),
+                json!({ "type": "heartbeat" }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_delete_channel_notifies_subscribers() {
+        let state = test_state().await;
+        let mut receivers = Vec::new();
+        for user in ["user-1", "user-2", "user-3"] {
+            let (tx, rx) = mpsc::channel(8);
+            receivers.push(rx);
+            let handle = state
+                .connection_manager
+                .register(user.to_string(), "default".to_string(), vec![], tx)
+                .unwrap();
+            for channel in ["promo", "orders"] {
+                state
+                    .connection_manager
+                    .subscribe_to_channel(handle.id, channel)
+                    .await
+                    .unwrap();
+            }
+        }
+        let before = crate::metrics::CHANNELS_DELETED_TOTAL.get();
+        let app = create_app(state.clone());
+
+        let response = app
+            .clone()
+            .oneshot(admin_request("DELETE", "/admin/channels/promo", json!({})))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response_json(response).await, json!({ "subscribers_notified": 3 }));
+        assert!(crate::metrics::CHANNELS_DELETED_TOTAL.get() > before);
+
+        for rx in &mut receivers {
+            match rx.try_recv().unwrap() {
+                OutboundMessage::Raw(ServerMessage::ChannelDeleted { channel }) => {
+                    assert_eq!(channel, "promo")
+                }
+                other => panic!("unexpected message: {:?}", other),
+            }
+            assert!(rx.try_recv().is_err());
+        }
+        let manager = &state.connection_manager;
+        assert!(!manager.channel_exists("promo"));
+        assert_eq!(manager.get_channel_connections("orders").len(), 3);
+        for handle in manager.get_all_connections() {
+            assert!(!handle.subscriptions.read().await.contains("promo"));
+        }
+
+        let response = app
+            .oneshot(admin_request("DELETE", "/admin/channels/promo", json!({})))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_connections_snapshot() {
+        let state = test_state().await;
+        let (tx, _rx) = mpsc::channel(8);
+        let handle = state
+            .connection_manager
+            .register("user-1".to_string(), "acme".to_string(), vec![], tx)
+            .unwrap();
+        let app = create_app(state);
+
+        let response = app
+            .oneshot(admin_request("GET", "/admin/connections/snapshot", json!({})))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response_json(response).await;
+        assert_eq!(body["connections"][0]["connection_id"], handle.id.to_string());
+        assert_eq!(body["connections"][0]["tenant_id"], "acme");
+        assert!(body["taken_at"].is_string());
+    }
+
+    #[tokio::test]
+    async fn test_connections_snapshot_size_limit() {
+        let state = test_state_with(json!({ "admin": { "max_snapshot_bytes": 100 } })).await;
+        let mut receivers = Vec::new();
+        for user in ["user-1", "user-2"] {
+            let (tx, rx) = mpsc::channel(8);
+            receivers.push(rx);
+            state
+                .connection_manager
+                .register(user.to_string(), "default".to_string(), vec![], tx)
+                .unwrap();
+        }
+        let app = create_app(state);
+
+        let response = app
+            .oneshot(admin_request("GET", "/admin/connections/snapshot", json!({})))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        let body = response_json(response).await;
+        assert_eq!(body["error"]["code"], "SNAPSHOT_TOO_LARGE");
+    }
+
+    #[tokio::test]
+    async fn test_get_channel_reports_history() {
+        let state = test_state().await;
+        let mut ids = Vec::new();
+        let mut receivers = Vec::new();
+        for user in ["user-1", "user-2"] {
+            let (tx, rx) = mpsc::channel(8);
+            receivers.push(rx);
+            let handle = state
+                .connection_manager
+                .register(user.to_string(), "default".to_string(), vec![], tx)
+                .unwrap();
+            state
+                .connection_manager
+                .subscribe_to_channel(handle.id, "orders")
+                .await
+                .unwrap();
+            ids.push(handle.id);
+        }
+        state
+            .connection_manager
+            .unsubscribe_from_channel(ids[0], "orders")
+            .await;
+        let app = create_app(state);
+
+        let response = app
+            .oneshot(json_request("GET", "/api/v1/channels/orders", json!({})))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response_json(response).await;
+        assert_eq!(body["subscriber_count"], 1);
+        assert_eq!(body["peak_subscribers"], 2);
+        assert_eq!(body["total_subscriber_events"], 3);
+        assert!(body["created_at"].is_string());
+    }
+}
@@ -1,10 +1,28 @@
 //! Health check and statistics endpoints.
 
-use axum::{extract::State, Json};
-use serde::Serialize;
+use std::time::Duration;
 
+use axum::{
+    extract::{Query, State},
+    http::{header, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
+use serde::{Deserialize, Serialize};
+use tokio::time::timeout;
+
+use crate::config::Settings;
+use crate::connection_manager::TenantConnectionStats;
+use crate::server::middleware::{is_valid_tenant_id, RequestTenantContext};
 use crate::server::AppState;
 
+use super::connection::{error_response, ChannelErrorResponse};
+
+/// Default page size for the tenant list in `/health/stats`
+const DEFAULT_TENANTS_PER_PAGE: usize = 20;
+/// Largest accepted `per_page` for the tenant list in `/health/stats`
+const MAX_TENANTS_PER_PAGE: usize = 100;
+
 #[derive(Debug, Serialize)]
 pub struct HealthResponse {
     pub status: String,
@@ -17,6 +35,67 @@ pub struct HealthResponse {
     pub queue: QueueHealthResponse,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cluster: Option<ClusterHealthResponse>,
+    pub checks: HealthChecks,
+}
+
+/// Outcome of a single dependency probe
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Ok,
+    Unhealthy,
+}
+
+impl CheckStatus {
+    fn from_healthy(healthy: bool) -> Self {
+        if healthy {
+            Self::Ok
+        } else {
+            Self::Unhealthy
+        }
+    }
+}
+
+/// Active dependency probes; only dependencies the configured backends need are listed
+#[derive(Debug, Default, Serialize)]
+pub struct HealthChecks {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redis: Option<CheckStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub postgres: Option<CheckStatus>,
+}
+
+impl HealthChecks {
+    pub fn is_healthy(&self) -> bool {
+        self.redis != Some(CheckStatus::Unhealthy) && self.postgres != Some(CheckStatus::Unhealthy)
+    }
+
+    fn status_code(&self) -> StatusCode {
+        if self.is_healthy() {
+            StatusCode::OK
+        } else {
+            StatusCode::SERVICE_UNAVAILABLE
+        }
+    }
+
+    fn status(&self) -> &'static str {
+        if self.is_healthy() {
+            "healthy"
+        } else {
+            "degraded"
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct LivenessResponse {
+    pub status: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReadinessResponse {
+    pub status: String,
+    pub checks: HealthChecks,
 }
 
 #[derive(Debug, Serialize)]
@@ -29,6 +108,7 @@ pub struct RedisHealthResponse {
 pub struct PostgresHealthResponse {
     pub status: String,
     pub connected: bool,
+    pub circuit_breaker_state: String,
     pub pool_size: u32,
     pub idle_connections: u32,
 }
@@ -58,6 +138,7 @@ pub struct ClusterHealthResponse {
 pub struct StatsResponse {
     pub connections: ConnectionStats,
     pub notifications: NotificationStats,
+    pub notification_types: std::collections::HashMap<String, NotificationTypeStats>,
     pub redis: RedisStats,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub ack: Option<AckStats>,
@@ -80,6 +161,12 @@ pub struct NotificationStats {
     pub channel_notifications: u64,
 }
 
+#[derive(Debug, Serialize)]
+pub struct NotificationTypeStats {
+    pub sent: u64,
+    pub delivered: u64,
+}
+
 #[derive(Debug, Serialize)]
 pub struct RedisStats {
     pub status: String,
@@ -101,18 +188,63 @@ pub struct AckStats {
     pub avg_latency_ms: u64,
 }
 
-pub async fn health(State(state): State<AppState>) -> Json<HealthResponse> {
-    let redis_required = (state.settings.queue.enabled && state.settings.queue.backend == "redis")
-        || (state.settings.ack.enabled && state.settings.ack.backend == "redis")
-        || state.settings.cluster.enabled;
+/// Whether the configured queue, ACK or cluster backends depend on Redis
+fn redis_required(settings: &Settings) -> bool {
+    (settings.queue.enabled && settings.queue.backend == "redis")
+        || (settings.ack.enabled && settings.ack.backend == "redis")
+        || settings.cluster.enabled
+}
 
-    let redis_health = state.redis_health.stats();
-    let redis_status = if redis_required {
-        redis_health.status
+fn circuit_state_name(state: crate::redis::CircuitState) -> &'static str {
+    match state {
+        crate::redis::CircuitState::Closed => "closed",
+        crate::redis::CircuitState::Open => "open",
+        crate::redis::CircuitState::HalfOpen => "half_open",
+    }
+}
+
+/// Ping Redis and PostgreSQL (when in use), each bounded by `health.probe_timeout_seconds`
+async fn probe_dependencies(state: &AppState) -> HealthChecks {
+    let probe_timeout = Duration::from_secs(state.settings.health.probe_timeout_seconds);
+
+    let redis = async {
+        if !redis_required(&state.settings) {
+            return None;
+        }
+        let healthy = match state.redis_pool {
+            // An open circuit means Redis is known to be failing; don't wait on another attempt
+            Some(ref pool)
+                if pool.circuit_state(crate::redis::OperationType::Read)
+                    != crate::redis::CircuitState::Open =>
+            {
+                matches!(timeout(probe_timeout, pool.ping()).await, Ok(Ok(())))
+            }
+            _ => false,
+        };
+        Some(CheckStatus::from_healthy(healthy))
+    };
+
+    let postgres = async {
+        let pool = state.postgres_pool.as_ref()?;
+        // Same as Redis: an open circuit is reported without another attempt
+        let healthy = pool.circuit_breaker().state() != crate::redis::CircuitState::Open
+            && matches!(timeout(probe_timeout, pool.ping()).await, Ok(Ok(())));
+        Some(CheckStatus::from_healthy(healthy))
+    };
+
+    let (redis, postgres) = tokio::join!(redis, postgres);
+    HealthChecks { redis, postgres }
+}
+
+/// Full health report; returns 503 when a required dependency fails its probe
+pub async fn health(State(state): State<AppState>) -> (StatusCode, Json<HealthResponse>) {
+    let checks = probe_dependencies(&state).await;
+
+    let redis_status = if redis_required(&state.settings) {
+        state.redis_health.stats().status
     } else {
         crate::redis::RedisHealthStatus::Disabled
     };
-    let is_redis_healthy = redis_status == crate::redis::RedisHealthStatus::Healthy;
 
     let uptime_seconds = state.start_time.elapsed().as_secs();
     let conn_stats = state.connection_manager.stats();
@@ -120,9 +252,11 @@ pub async fn health(State(state): State<AppState>) -> Json<HealthResponse> {
 
     let postgres = if let Some(ref pool) = state.postgres_pool {
         let inner_pool = pool.pool();
+        let connected = checks.postgres == Some(CheckStatus::Ok);
         Some(PostgresHealthResponse {
-            status: "connected".to_string(),
-            connected: pool.is_available(),
+            status: if connected { "connected" } else { "unreachable" }.to_string(),
+            connected,
+            circuit_breaker_state: circuit_state_name(pool.circuit_breaker().state()).to_string(),
             pool_size: inner_pool.size(),
             idle_connections: inner_pool.num_idle() as u32,
         })
@@ -139,19 +273,13 @@ pub async fn health(State(state): State<AppState>) -> Json<HealthResponse> {
         None
     };
 
-    let status = if !redis_required || is_redis_healthy {
-        "healthy"
-    } else {
-        "degraded"
-    };
-
-    Json(HealthResponse {
-        status: status.to_string(),
+    let response = HealthResponse {
+        status: checks.status().to_string(),
         version: env!("CARGO_PKG_VERSION").to_string(),
         uptime_seconds,
         redis: RedisHealthResponse {
             status: redis_status.as_str().to_string(),
-            connected: is_redis_healthy,
+            connected: checks.redis == Some(CheckStatus::Ok),
         },
         postgres,
         connections: ConnectionHealthResponse {
@@ -166,20 +294,38 @@ pub async fn health(State(state): State<AppState>) -> Json<HealthResponse> {
             users_with_queue: queue_stats.users_with_queue,
         },
         cluster,
+        checks,
+    };
+
+    (response.checks.status_code(), Json(response))
+}
+
+/// Liveness probe: the process is up and serving HTTP (no dependency checks)
+pub async fn health_live() -> Json<LivenessResponse> {
+    Json(LivenessResponse {
+        status: "ok".to_string(),
     })
 }
 
+/// Readiness probe: 503 until every required dependency answers its probe
+pub async fn health_ready(State(state): State<AppState>) -> (StatusCode, Json<ReadinessResponse>) {
+    let checks = probe_dependencies(&state).await;
+    (
+        checks.status_code(),
+        Json(ReadinessResponse {
+            status: checks.status().to_string(),
+            checks,
+        }),
+    )
+}
+
 pub async fn stats(State(state): State<AppState>) -> Json<StatsResponse> {
     let conn_stats = state.connection_manager.stats();
     let dispatcher_stats = state.dispatcher.stats();
     let redis_health = state.redis_health.stats();
     let circuit_breaker = state.redis_circuit_breaker.stats();
 
-    let circuit_state = match circuit_breaker.state {
-        crate::redis::CircuitState::Closed => "closed",
-        crate::redis::CircuitState::Open => "open",
-        crate::redis::CircuitState::HalfOpen => "half_open",
-    };
+    let circuit_state = circuit_state_name(circuit_breaker.state);
 
     let ack_stats = if state.ack_backend.is_enabled() {
         let ack = state.ack_backend.stats().await;
@@ -196,6 +342,25 @@ pub async fn stats(State(state): State<AppState>) -> Json<StatsResponse> {
         None
     };
 
+    let notification_types = dispatcher_stats
+        .event_type_counts
+        .iter()
+        .map(|(event_type, sent)| {
+            let delivered = dispatcher_stats
+                .event_type_delivered
+                .get(event_type)
+                .copied()
+                .unwrap_or(0);
+            (
+                event_type.clone(),
+                NotificationTypeStats {
+                    sent: *sent,
+                    delivered,
+                },
+            )
+        })
+        .collect();
+
     Json(StatsResponse {
         connections: ConnectionStats {
             total_connections: conn_stats.total_connections,
@@ -210,6 +375,7 @@ pub async fn stats(State(state): State<AppState>) -> Json<StatsResponse> {
             broadcast_notifications: dispatcher_stats.broadcast_notifications,
             channel_notifications: dispatcher_stats.channel_notifications,
         },
+        notification_types,
         redis: RedisStats {
             status: redis_health.status.as_str().to_string(),
             connected: redis_health.status == crate::redis::RedisHealthStatus::Healthy,
@@ -221,3 +387,428 @@ pub async fn stats(State(state): State<AppState>) -> Json<StatsResponse> {
         ack: ack_stats,
     })
 }
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ConnectionStatsQuery {
+    /// Restrict the statistics to a single tenant
+    pub tenant_id: Option<String>,
+    /// 1-based page of the tenant list
+    pub page: Option<usize>,
+    pub per_page: Option<usize>,
+    /// Cursor: list tenants whose ID sorts after this one
+    pub after: Option<String>,
+}
+
+impl ConnectionStatsQuery {
+    /// The tenant list is only included when the caller asks for a page of it
+    fn wants_tenant_list(&self) -> bool {
+        self.page.is_some() || self.per_page.is_some() || self.after.is_some()
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ConnectionStatsResponse {
+    #[serde(flatten)]
+    pub stats: crate::connection_manager::ConnectionStats,
+    /// Connection counts tracked by the tenant manager, summed across tenants unless
+    /// the request is scoped to one
+    pub connections: TenantConnectionTotals,
+    /// Message counts tracked by the tenant manager, scoped like `connections`
+    pub messages: TenantMessageTotals,
+    /// One page of active tenants, sorted by tenant ID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tenants: Option<Vec<TenantConnectionStats>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TenantConnectionTotals {
+    pub active: u64,
+    pub total: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TenantMessageTotals {
+    pub sent: u64,
+    pub delivered: u64,
+}
+
+/// Detailed connection statistics (age, subscription density, heaviest users).
+///
+/// `?tenant_id=` scopes the statistics to one tenant; tenant-scoped callers (with an
+/// `X-Tenant-ID`) always get their own tenant's statistics. `?per_page=`, `?page=` or the
+/// `?after=<tenant_id>` cursor add a page of the active-tenant list, with the total in
+/// `X-Total-Count` and the next cursor in a `Link: <...>; rel="next"` header.
+pub async fn connection_stats(
+    State(state): State<AppState>,
+    tenant_ctx: Option<Extension<RequestTenantContext>>,
+    Query(query): Query<ConnectionStatsQuery>,
+) -> Result<Response, (StatusCode, Json<ChannelErrorResponse>)> {
+    let mut conn_stats = match query.tenant_id.as_deref() {
+        Some(tenant_id) => {
+            if !is_valid_tenant_id(tenant_id) {
+                return Err(error_response(
+                    StatusCode::BAD_REQUEST,
+                    "INVALID_TENANT_ID",
+                    "tenant_id must be 1-64 alphanumeric, '-', '_' or '.' characters",
+                ));
+            }
+            if let Some(ref t) = tenant_ctx {
+                if t.0.tenant_id() != tenant_id {
+                    return Err(error_response(
+                        StatusCode::FORBIDDEN,
+                        "TENANT_ACCESS_DENIED",
+                        "Cannot access another tenant's stats",
+                    ));
+                }
+            }
+            state.connection_manager.tenant_detailed_stats(tenant_id)
+        }
+        None => match tenant_ctx {
+            Some(ref t) => state.connection_manager.tenant_detailed_stats(t.0.tenant_id()),
+            None => state.connection_manager.stats(),
+        },
+    };
+    // Per-channel details omitted for the same reason as in `stats`
+    conn_stats.channels.clear();
+
+    let tenant_totals = match (query.tenant_id.as_deref(), &tenant_ctx) {
+        (Some(tenant_id), _) => state.tenant_manager.get_stats(tenant_id),
+        (None, Some(t)) => state.tenant_manager.get_stats(t.0.tenant_id()),
+        (None, None) => state.tenant_manager.get_aggregate_stats(),
+    };
+    let connections = TenantConnectionTotals {
+        active: tenant_totals.active_connections,
+        total: tenant_totals.total_connections,
+    };
+    let messages = TenantMessageTotals {
+        sent: tenant_totals.messages_sent,
+        delivered: tenant_totals.messages_delivered,
+    };
+
+    if !query.wants_tenant_list() {
+        return Ok(Json(ConnectionStatsResponse {
+            stats: conn_stats,
+            connections,
+            messages,
+            tenants: None,
+        })
+        .into_response());
+    }
+
+    let per_page = query.per_page.unwrap_or(DEFAULT_TENANTS_PER_PAGE);
+    if per_page == 0 || per_page > MAX_TENANTS_PER_PAGE {
+        return Err(error_response(
+            StatusCode::BAD_REQUEST,
+            "INVALID_PAGINATION",
+            format!("per_page must be between 1 and {}", MAX_TENANTS_PER_PAGE),
+        ));
+    }
+    if query.page == Some(0) {
+        return Err(error_response(
+            StatusCode::BAD_REQUEST,
+            "INVALID_PAGINATION",
+            "page starts at 1",
+        ));
+    }
+    if query.page.is_some() && query.after.is_some() {
+        return Err(error_response(
+            StatusCode::BAD_REQUEST,
+            "INVALID_PAGINATION",
+            "page and after cannot be combined",
+        ));
+    }
+
+    // Tenant-scoped callers only ever see their own tenant
+    let mut tenant_ids = state.connection_manager.list_tenants();
+    if let Some(ref t) = tenant_ctx {
+        tenant_ids.retain(|id| id == t.0.tenant_id());
+    }
+    tenant_ids.sort();
+    let total = tenant_ids.len();
+
+    let start = match query.after.as_deref() {
+        Some(after) => tenant_ids.partition_point(|id| id.as_str() <= after),
+        None => (query.page.unwrap_or(1) - 1).saturating_mul(per_page).min(total),
+    };
+    let end = start.saturating_add(per_page).min(total);
+    let page = &tenant_ids[start..end];
+
+    let next_link = if end < total {
+        page.last().and_then(|last| {
+            let mut params = vec![("per_page", per_page.to_string()), ("after", last.clone())];
+            if let Some(ref tenant_id) = query.tenant_id {
+                params.insert(0, ("tenant_id", tenant_id.clone()));
+            }
+            let query_string = serde_urlencoded::to_string(&params).ok()?;
+            HeaderValue::from_str(&format!("</health/stats?{}>; rel=\"next\"", query_string)).ok()
+        })
+    } else {
+        None
+    };
+
+    let tenants = page
+        .iter()
+        .map(|id| state.connection_manager.tenant_stats(id))
+        .collect();
+
+    let mut response = Json(ConnectionStatsResponse {
+        stats: conn_stats,
+        connections,
+        messages,
+        tenants: Some(tenants),
+    })
+    .into_response();
+    let headers = response.headers_mut();
+    headers.insert("x-total-count", HeaderValue::from(total));
+    if let Some(link) = next_link {
+        headers.insert(header::LINK, link);
+    }
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{header, StatusCode};
+    use serde_json::json;
+    use tokio::sync::mpsc;
+    use tower::ServiceExt;
+
+    use crate::api::test_support::{json_request, response_json, test_state, test_state_with};
+    use crate::server::{create_app, AppState};
+
+    /// Register `(user_id, tenant_id)` connections, keeping their receivers alive
+    fn register_connections(
+        state: &AppState,
+        connections: &[(&str, &str)],
+    ) -> Vec<mpsc::Receiver<crate::websocket::OutboundMessage>> {
+        connections
+            .iter()
+            .map(|(user_id, tenant_id)| {
+                let (tx, rx) = mpsc::channel(8);
+                state
+                    .connection_manager
+                    .register(user_id.to_string(), tenant_id.to_string(), vec![], tx)
+                    .unwrap();
+                rx
+            })
+            .collect()
+    }
+
+    const STATS_CONNECTIONS: [(&str, &str); 4] = [
+        ("alice", "acme"),
+        ("alice", "acme"),
+        ("bob", "beta"),
+        ("carol", "gamma"),
+    ];
+
+    #[tokio::test]
+    async fn test_health_probes_without_dependencies() {
+        let app = create_app(test_state().await);
+
+        for uri in ["/health", "/health/ready"] {
+            let response = app.clone().oneshot(json_request("GET", uri, json!({}))).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK, "{}", uri);
+            let body = response_json(response).await;
+            assert_eq!(body["status"], "healthy");
+            assert_eq!(body["checks"], json!({}));
+        }
+
+        let response = app.oneshot(json_request("GET", "/health/live", json!({}))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response_json(response).await, json!({ "status": "ok" }));
+    }
+
+    #[tokio::test]
+    async fn test_health_unreachable_redis_returns_503() {
+        let state = test_state_with(json!({
+            "redis": { "url": "redis://127.0.0.1:1" },
+            "queue": { "enabled": true, "backend": "redis" },
+            "health": { "probe_timeout_seconds": 1 }
+        }))
+        .await;
+        let app = create_app(state);
+
+        for uri in ["/health", "/health/ready"] {
+            let response = app.clone().oneshot(json_request("GET", uri, json!({}))).await.unwrap();
+            assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE, "{}", uri);
+            let body = response_json(response).await;
+            assert_eq!(body["status"], "degraded");
+            assert_eq!(body["checks"], json!({ "redis": "unhealthy" }));
+        }
+
+        // Liveness never depends on Redis
+        let response = app.oneshot(json_request("GET", "/health/live", json!({}))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_health_ready_reports_open_postgres_circuit() {
+        let mut state = test_state().await;
+        let pool = crate::postgres::PostgresPool::new_lazy(
+            &state.settings.database,
+            state.postgres_circuit_breaker.clone(),
+            state.postgres_health.clone(),
+        )
+        .unwrap();
+        for _ in 0..5 {
+            pool.record_failure();
+        }
+        state.postgres_pool = Some(std::sync::Arc::new(pool));
+        let app = create_app(state);
+
+        let response = app.oneshot(json_request("GET", "/health/ready", json!({}))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(
+            response_json(response).await["checks"],
+            json!({ "postgres": "unhealthy" })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_connection_stats_default_is_flat() {
+        let state = test_state().await;
+        let _receivers = register_connections(&state, &STATS_CONNECTIONS);
+        let app = create_app(state);
+
+        let response = app.oneshot(json_request("GET", "/health/stats", json!({}))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers().get("x-total-count").is_none());
+        assert!(response.headers().get(header::LINK).is_none());
+        let body = response_json(response).await;
+        assert_eq!(body["total_connections"], 4);
+        assert_eq!(body["unique_users"], 3);
+        assert!(body.get("tenants").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_connection_stats_tenant_totals() {
+        let state = test_state().await;
+        for (tenant, connections, sent) in [("acme", 2, 3), ("beta", 1, 0), ("gamma", 4, 2)] {
+            for _ in 0..connections {
+                state.tenant_manager.record_connection(tenant);
+            }
+            for _ in 0..sent {
+                state.tenant_manager.record_message_sent(tenant);
+            }
+        }
+        state.tenant_manager.record_disconnection("gamma");
+        state.tenant_manager.record_message_delivered("acme", 5);
+        let app = create_app(state);
+
+        let response = app
+            .clone()
+            .oneshot(json_request("GET", "/health/stats", json!({})))
+            .await
+            .unwrap();
+        let body = response_json(response).await;
+        assert_eq!(body["connections"], json!({"active": 6, "total": 7}));
+        assert_eq!(body["messages"], json!({"sent": 5, "delivered": 5}));
+
+        let response = app
+            .oneshot(json_request("GET", "/health/stats?tenant_id=gamma", json!({})))
+            .await
+            .unwrap();
+        let body = response_json(response).await;
+        assert_eq!(body["connections"], json!({"active": 3, "total": 4}));
+        assert_eq!(body["messages"], json!({"sent": 2, "delivered": 0}));
+    }
+
+    #[tokio::test]
+    async fn test_connection_stats_filtered_by_tenant() {
+        let state = test_state().await;
+        let _receivers = register_connections(&state, &STATS_CONNECTIONS);
+        let app = create_app(state);
+
+        let response = app
+            .clone()
+            .oneshot(json_request("GET", "/health/stats?tenant_id=acme", json!({})))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response_json(response).await;
+        assert_eq!(body["total_connections"], 2);
+        assert_eq!(body["unique_users"], 1);
+        assert_eq!(body["top_n_users_by_connections"], json!([["alice", 2]]));
+
+        let response = app
+            .oneshot(json_request("GET", "/health/stats?tenant_id=bad%20id", json!({})))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_connection_stats_defaults_to_request_tenant() {
+        let state = test_state_with(json!({
+            "api": { "key": "test-api-key" },
+            "tenant": { "enabled": true }
+        }))
+        .await;
+        let _receivers = register_connections(&state, &STATS_CONNECTIONS);
+        let app = create_app(state);
+
+        let mut request = json_request("GET", "/health/stats", json!({}));
+        request.headers_mut().insert("x-api-key", header::HeaderValue::from_static("test-api-key"));
+        request.headers_mut().insert("x-tenant-id", header::HeaderValue::from_static("beta"));
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response_json(response).await;
+        assert_eq!(body["total_connections"], 1);
+        assert_eq!(body["unique_users"], 1);
+        assert_eq!(body["top_n_users_by_connections"], json!([["bob", 1]]));
+    }
+
+    #[tokio::test]
+    async fn test_connection_stats_tenant_pagination() {
+        let state = test_state().await;
+        let _receivers = register_connections(&state, &STATS_CONNECTIONS);
+        let app = create_app(state);
+
+        let response = app
+            .clone()
+            .oneshot(json_request("GET", "/health/stats?per_page=2", json!({})))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["x-total-count"], "3");
+        assert_eq!(
+            response.headers()[header::LINK],
+            "</health/stats?per_page=2&after=beta>; rel=\"next\""
+        );
+        let body = response_json(response).await;
+        assert_eq!(body["total_connections"], 4);
+        let tenants = body["tenants"].as_array().unwrap();
+        assert_eq!(tenants.len(), 2);
+        assert_eq!(tenants[0]["tenant_id"], "acme");
+        assert_eq!(tenants[0]["total_connections"], 2);
+        assert_eq!(tenants[1]["tenant_id"], "beta");
+
+        // Following the cursor reaches the last page, which has no next link
+        let response = app
+            .clone()
+            .oneshot(json_request("GET", "/health/stats?per_page=2&after=beta", json!({})))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers().get(header::LINK).is_none());
+        let body = response_json(response).await;
+        assert_eq!(
+            body["tenants"],
+            json!([{ "tenant_id": "gamma", "total_connections": 1, "unique_users": 1 }])
+        );
+
+        let response = app
+            .clone()
+            .oneshot(json_request("GET", "/health/stats?page=2&per_page=2", json!({})))
+            .await
+            .unwrap();
+        let body = response_json(response).await;
+        assert_eq!(body["tenants"][0]["tenant_id"], "gamma");
+
+        let response = app
+            .oneshot(json_request("GET", "/health/stats?per_page=0", json!({})))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+}
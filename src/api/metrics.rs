@@ -12,8 +12,31 @@ use crate::server::AppState;
 /// GET /metrics - Prometheus metrics endpoint
 pub async fn prometheus_metrics(State(state): State<AppState>) -> impl IntoResponse {
     update_metrics_from_state(&state).await;
+    metrics_response(metrics::encode_metrics())
+}
+
+/// GET /metrics/connections - Connection metrics only (`ara_connections_*`)
+pub async fn prometheus_connection_metrics(State(state): State<AppState>) -> impl IntoResponse {
+    update_metrics_from_state(&state).await;
+    metrics_response(metrics::encode_metrics_filtered("ara_connections_"))
+}
+
+/// GET /metrics/queue - Offline queue metrics only (`ara_queue_*`)
+pub async fn prometheus_queue_metrics(State(state): State<AppState>) -> impl IntoResponse {
+    update_metrics_from_state(&state).await;
+    metrics_response(metrics::encode_metrics_filtered("ara_queue_"))
+}
 
-    match metrics::encode_metrics() {
+/// GET /metrics/redis - Redis metrics only (`ara_redis_*`)
+pub async fn prometheus_redis_metrics(State(state): State<AppState>) -> impl IntoResponse {
+    update_metrics_from_state(&state).await;
+    metrics_response(metrics::encode_metrics_filtered("ara_redis_"))
+}
+
+fn metrics_response(
+    encoded: Result<String, prometheus::Error>,
+) -> (StatusCode, [(axum::http::HeaderName, &'static str); 1], String) {
+    match encoded {
         Ok(output) => (
             StatusCode::OK,
             [(axum::http::header::CONTENT_TYPE, "text/plain; version=0.0.4; charset=utf-8")],
@@ -36,6 +59,7 @@ async fn update_metrics_from_state(state: &AppState) {
     let conn_stats = state.connection_manager.stats();
     metrics::CONNECTIONS_TOTAL.set(conn_stats.total_connections as i64);
     metrics::USERS_CONNECTED.set(conn_stats.unique_users as i64);
+    metrics::CONNECTION_IDS_TRACKED.set(state.connection_manager.tracked_connection_ids() as i64);
     metrics::CHANNELS_ACTIVE.set(conn_stats.channels.len() as i64);
 
     // Per-channel subscription metrics omitted to prevent cross-tenant
@@ -52,7 +76,23 @@ async fn update_metrics_from_state(state: &AppState) {
         crate::redis::CircuitState::Open => 1,
         crate::redis::CircuitState::HalfOpen => 2,
     };
-    metrics::REDIS_CIRCUIT_BREAKER_STATE.set(cb_state);
+    metrics::REDIS_CIRCUIT_BREAKER_STATE
+        .with_label_values(&["subscribe"])
+        .set(cb_state);
+    if let Some(ref pool) = state.redis_pool {
+        metrics::RedisMetrics::update_pool_stats(pool);
+    }
+
+    // PostgreSQL metrics
+    if let Some(ref pool) = state.postgres_pool {
+        metrics::POSTGRES_CONNECTION_STATUS.set(if pool.health().is_healthy() { 1 } else { 0 });
+        let cb_state = match pool.circuit_breaker().state() {
+            crate::redis::CircuitState::Closed => 0,
+            crate::redis::CircuitState::Open => 1,
+            crate::redis::CircuitState::HalfOpen => 2,
+        };
+        metrics::POSTGRES_CIRCUIT_BREAKER_STATE.set(cb_state);
+    }
 
     // Queue metrics
     if state.queue_backend.is_enabled() {
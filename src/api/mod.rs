@@ -1,17 +1,38 @@
 //! API layer - HTTP endpoint handlers organized by domain.
 
+mod ack;
 mod cluster;
 mod connection;
 mod health;
 mod metrics;
+mod quarantine;
+mod queue;
 mod template;
 mod tenant;
 
+#[cfg(test)]
+pub(crate) mod test_support;
+
 // Re-export all handlers for use in server/app.rs
-pub use cluster::{cluster_status, cluster_user_location};
-pub use connection::{get_channel, get_user_subscriptions, list_channels};
+pub use ack::{ack_summary, get_user_pending_acks};
+pub use cluster::{cluster_status, cluster_user_location, list_user_sessions};
+pub use connection::{
+    connections_snapshot, delete_channel, get_channel, get_user_connections,
+    get_user_subscriptions, list_channels, send_to_connection,
+};
 pub use connection::{ChannelError, ChannelErrorResponse};
-pub use health::{health, stats};
-pub use metrics::prometheus_metrics;
-pub use template::{create_template, delete_template, get_template, list_templates, update_template};
-pub use tenant::{get_tenant_stats, list_tenants};
+pub use health::{connection_stats, health, health_live, health_ready, stats};
+pub use metrics::{
+    prometheus_connection_metrics, prometheus_metrics, prometheus_queue_metrics,
+    prometheus_redis_metrics,
+};
+pub use quarantine::{list_quarantine, reprocess_quarantined};
+pub use queue::{dropped_notifications, queue_migration_status, start_queue_migration};
+pub use template::{
+    create_template, delete_template, export_templates, get_template, import_templates,
+    list_templates, preview_template, update_template,
+};
+pub use tenant::{
+    get_tenant_detail, get_tenant_metrics, get_tenant_stats, list_tenants, register_tenant,
+    unregister_tenant,
+};
@@ -0,0 +1,181 @@
+//! Quarantined Redis message administration endpoints.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use serde::Serialize;
+
+use crate::notification::DeliveryResult;
+use crate::server::AppState;
+use crate::triggers::{QuarantineError, QuarantinedMessage, RedisSubscriber};
+
+use super::connection::{error_response, ChannelErrorResponse};
+
+#[derive(Debug, Serialize)]
+pub struct QuarantineEntry {
+    /// Position in the quarantine (0 = most recent); used to reprocess the entry
+    pub index: usize,
+    #[serde(flatten)]
+    pub message: QuarantinedMessage,
+}
+
+#[derive(Debug, Serialize)]
+pub struct QuarantineListResponse {
+    pub total: usize,
+    pub messages: Vec<QuarantineEntry>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct QuarantineReprocessResponse {
+    pub channel: String,
+    #[serde(flatten)]
+    pub result: DeliveryResult,
+}
+
+/// GET /admin/quarantine - List Redis messages that failed to deserialize, newest first
+pub async fn list_quarantine(
+    State(state): State<AppState>,
+) -> Result<Json<QuarantineListResponse>, (StatusCode, Json<ChannelErrorResponse>)> {
+    let messages = state.quarantine.list().await.map_err(unavailable)?;
+
+    Ok(Json(QuarantineListResponse {
+        total: messages.len(),
+        messages: messages
+            .into_iter()
+            .enumerate()
+            .map(|(index, message)| QuarantineEntry { index, message })
+            .collect(),
+    }))
+}
+
+/// POST /admin/quarantine/{index}/reprocess - Re-parse a quarantined message and dispatch it
+///
+/// On success the message is removed from the quarantine; otherwise it is left in place.
+#[tracing::instrument(name = "http.reprocess_quarantined", skip(state))]
+pub async fn reprocess_quarantined(
+    State(state): State<AppState>,
+    Path(index): Path<usize>,
+) -> Result<Json<QuarantineReprocessResponse>, (StatusCode, Json<ChannelErrorResponse>)> {
+    let message = state
+        .quarantine
+        .get(index)
+        .await
+        .map_err(unavailable)?
+        .ok_or_else(|| {
+            error_response(
+                StatusCode::NOT_FOUND,
+                "QUARANTINE_ENTRY_NOT_FOUND",
+                format!("No quarantined message at index {}", index),
+            )
+        })?;
+
+    let result = RedisSubscriber::process_payload(&state.dispatcher, &message.channel, &message.payload)
+        .await
+        .map_err(|e| {
+            error_response(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "INVALID_MESSAGE",
+                format!("Message still fails to deserialize: {}", e),
+            )
+        })?
+        .ok_or_else(|| {
+            error_response(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "INVALID_TARGET",
+                "Message has an unknown target type",
+            )
+        })?;
+
+    if let Err(e) = state.quarantine.remove(&message).await {
+        // Already dispatched; the entry stays listed until removed
+        tracing::error!(error = %e, index, "Failed to remove reprocessed message from quarantine");
+    }
+    tracing::info!(index, channel = %message.channel, "Quarantined message reprocessed");
+
+    Ok(Json(QuarantineReprocessResponse {
+        channel: message.channel,
+        result,
+    }))
+}
+
+fn unavailable(e: QuarantineError) -> (StatusCode, Json<ChannelErrorResponse>) {
+    tracing::error!(error = %e, "Quarantine store unavailable");
+    error_response(
+        StatusCode::SERVICE_UNAVAILABLE,
+        "QUARANTINE_UNAVAILABLE",
+        "Quarantine store is unavailable",
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use axum::http::StatusCode;
+    use serde_json::json;
+    use tower::ServiceExt;
+
+    use crate::api::test_support::{admin_request, response_json, test_state};
+    use crate::server::create_app;
+    use crate::triggers::{MemoryQuarantineStore, QuarantineStore, QuarantinedMessage};
+
+    #[tokio::test]
+    async fn test_list_and_reprocess_quarantine() {
+        let mut state = test_state().await;
+        let quarantine = Arc::new(MemoryQuarantineStore::new());
+        quarantine
+            .push(QuarantinedMessage::new(
+                "notification:broadcast",
+                r#"{"type": "broadcast", "event": {"event_type": "system.ping", "payload": {}}}"#,
+                "missing field `event`",
+            ))
+            .await
+            .unwrap();
+        quarantine
+            .push(QuarantinedMessage::new("notification:broadcast", "{oops", "key must be a string"))
+            .await
+            .unwrap();
+        state.quarantine = quarantine.clone();
+        let app = create_app(state);
+
+        let response = app
+            .clone()
+            .oneshot(admin_request("GET", "/admin/quarantine", json!({})))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response_json(response).await;
+        assert_eq!(body["total"], 2);
+        assert_eq!(body["messages"][0]["index"], 0);
+        assert_eq!(body["messages"][0]["payload"], "{oops");
+        assert!(body["messages"][0]["quarantined_at"].is_string());
+
+        // Still malformed: stays quarantined
+        let response = app
+            .clone()
+            .oneshot(admin_request("POST", "/admin/quarantine/0/reprocess", json!({})))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(response_json(response).await["error"]["code"], "INVALID_MESSAGE");
+
+        let response = app
+            .clone()
+            .oneshot(admin_request("POST", "/admin/quarantine/1/reprocess", json!({})))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response_json(response).await;
+        assert_eq!(body["channel"], "notification:broadcast");
+        assert!(body["notification_id"].is_string());
+        assert_eq!(quarantine.list().await.unwrap().len(), 1);
+
+        let response = app
+            .oneshot(admin_request("POST", "/admin/quarantine/5/reprocess", json!({})))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,195 @@
+//! Offline queue administration endpoints.
+
+use axum::{extract::State, http::StatusCode, Json};
+use serde::{Deserialize, Serialize};
+
+use crate::notification::DroppedNotification;
+use crate::queue::{create_queue_backend, migrate_backend_with_progress, MigrationStatus};
+use crate::server::AppState;
+
+use super::connection::{error_response, ChannelErrorResponse};
+
+/// Default number of users migrated per batch
+const DEFAULT_MIGRATION_BATCH_SIZE: usize = 100;
+
+#[derive(Debug, Serialize)]
+pub struct DroppedNotificationsResponse {
+    /// Whether dropped notifications are being recorded (`dispatcher.queue_fallback_on_offline`)
+    pub enabled: bool,
+    pub total: usize,
+    /// Most recent first
+    pub notifications: Vec<DroppedNotification>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct QueueMigrationRequest {
+    /// Target backend: "redis" or "postgres"
+    pub target: String,
+    /// Users per batch (default: 100)
+    #[serde(default)]
+    pub batch_size: Option<usize>,
+}
+
+/// POST /admin/queue/migrate - Start migrating queued messages to another backend
+#[tracing::instrument(name = "http.start_queue_migration", skip(state))]
+pub async fn start_queue_migration(
+    State(state): State<AppState>,
+    Json(request): Json<QueueMigrationRequest>,
+) -> Result<(StatusCode, Json<MigrationStatus>), (StatusCode, Json<ChannelErrorResponse>)> {
+    let target = request.target.trim().to_lowercase();
+    let available = match target.as_str() {
+        // A new memory backend is never read by this instance and dies with it, so
+        // messages moved there would be lost
+        "memory" => {
+            return Err(error_response(
+                StatusCode::BAD_REQUEST,
+                "INVALID_BACKEND",
+                "Cannot migrate to the 'memory' backend; migrate to 'redis' or 'postgres'",
+            ));
+        }
+        "redis" => state.redis_pool.is_some(),
+        "postgres" => state.postgres_pool.is_some(),
+        _ => {
+            return Err(error_response(
+                StatusCode::BAD_REQUEST,
+                "INVALID_BACKEND",
+                format!("Unknown queue backend '{}'", request.target),
+            ));
+        }
+    };
+    if !available {
+        return Err(error_response(
+            StatusCode::BAD_REQUEST,
+            "BACKEND_UNAVAILABLE",
+            format!("No connection pool configured for '{}' backend", target),
+        ));
+    }
+
+    let current = state.queue_backend.stats().await.backend_type;
+    if current == target {
+        return Err(error_response(
+            StatusCode::BAD_REQUEST,
+            "SAME_BACKEND",
+            format!("Queue is already using the '{}' backend", target),
+        ));
+    }
+
+    if !state.queue_migration.try_start() {
+        return Err(error_response(
+            StatusCode::CONFLICT,
+            "MIGRATION_IN_PROGRESS",
+            "A queue migration is already running",
+        ));
+    }
+
+    let mut target_settings = state.settings.queue.clone();
+    target_settings.backend = target.clone();
+    let to = create_queue_backend(
+        &target_settings,
+        state.redis_pool.clone(),
+        state.postgres_pool.clone(),
+        None,
+    );
+    let from = state.queue_backend.clone();
+    let progress = state.queue_migration.clone();
+    let batch_size = request
+        .batch_size
+        .unwrap_or(DEFAULT_MIGRATION_BATCH_SIZE)
+        .max(1);
+
+    tracing::info!(from = %current, to = %target, batch_size, "Queue migration requested");
+
+    tokio::spawn(async move {
+        migrate_backend_with_progress(from.as_ref(), to.as_ref(), batch_size, &progress).await;
+    });
+
+    Ok((StatusCode::ACCEPTED, Json(state.queue_migration.snapshot())))
+}
+
+/// GET /admin/queue/migrate/status - Poll progress of the current or last queue migration
+pub async fn queue_migration_status(
+    State(state): State<AppState>,
+) -> Result<Json<MigrationStatus>, (StatusCode, Json<ChannelErrorResponse>)> {
+    Ok(Json(state.queue_migration.snapshot()))
+}
+
+/// GET /admin/dropped-notifications - Recent notifications for offline users dropped while the queue is disabled
+pub async fn dropped_notifications(
+    State(state): State<AppState>,
+) -> Json<DroppedNotificationsResponse> {
+    let notifications = state.drop_log.recent();
+    Json(DroppedNotificationsResponse {
+        enabled: state.settings.dispatcher.queue_fallback_on_offline,
+        total: notifications.len(),
+        notifications,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::StatusCode;
+    use serde_json::json;
+    use tower::ServiceExt;
+
+    use crate::api::test_support::{admin_request, response_json, test_state_with};
+    use crate::notification::NotificationBuilder;
+    use crate::server::create_app;
+
+    #[tokio::test]
+    async fn test_dropped_notifications_recorded_when_queue_disabled() {
+        let state = test_state_with(json!({
+            "dispatcher": { "queue_fallback_on_offline": true }
+        }))
+        .await;
+        let event = NotificationBuilder::new("order.created", "test").build();
+        let notification_id = event.id;
+        state.dispatcher.send_to_user("offline-user", event).await;
+        let app = create_app(state);
+
+        let response = app
+            .oneshot(admin_request("GET", "/admin/dropped-notifications", json!({})))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response_json(response).await;
+        assert_eq!(body["enabled"], true);
+        assert_eq!(body["total"], 1);
+        let entry = &body["notifications"][0];
+        assert_eq!(entry["notification_id"], notification_id.to_string());
+        assert_eq!(entry["user_id"], "offline-user");
+        assert_eq!(entry["event_type"], "order.created");
+        assert_eq!(entry["reason"], "queue_disabled");
+        assert!(entry["timestamp"].is_string());
+    }
+
+    #[tokio::test]
+    async fn test_queue_migration_rejects_memory_target() {
+        let state = test_state_with(json!({})).await;
+        let app = create_app(state.clone());
+
+        let response = app
+            .oneshot(admin_request(
+                "POST",
+                "/admin/queue/migrate",
+                json!({ "target": "memory" }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(response_json(response).await["error"]["code"], "INVALID_BACKEND");
+        assert!(state.queue_migration.try_start());
+    }
+
+    #[tokio::test]
+    async fn test_dropped_notifications_not_recorded_by_default() {
+        let state = test_state_with(json!({})).await;
+        state
+            .dispatcher
+            .send_to_users(
+                &["offline-user".to_string()],
+                NotificationBuilder::new("order.created", "test").build(),
+            )
+            .await;
+        assert!(state.drop_log.is_empty());
+    }
+}
@@ -1,16 +1,24 @@
 //! Template CRUD endpoints.
 
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
     Extension, Json,
 };
-use serde::Serialize;
+use axum_extra::{
+    headers::{ETag, IfNoneMatch},
+    TypedHeader,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
+use crate::metrics::{TEMPLATE_PREVIEWS_TOTAL, TEMPLATE_SEARCHES_TOTAL};
 use crate::server::middleware::RequestTenantContext;
 use crate::server::AppState;
 use crate::template::{
-    CreateTemplateRequest, Template, TemplateError, TemplateListResponse, UpdateTemplateRequest,
+    CreateTemplateRequest, ImportConflictStrategy, ImportResult, RenderedTemplate, Template,
+    TemplateError, TemplateListResponse, UpdateTemplateRequest,
 };
 
 /// Prefix a template ID with tenant scope for isolation
@@ -81,6 +89,9 @@ pub async fn create_template(
         return Err(e.into());
     }
     template.id = tenant_template_id(&tenant_ctx, &template.id);
+    template.extends = template
+        .extends
+        .map(|parent| tenant_template_id(&tenant_ctx, &parent));
 
     match state.template_store.create(template) {
         Ok(created) => Ok((StatusCode::CREATED, Json(created))),
@@ -88,13 +99,28 @@ pub async fn create_template(
     }
 }
 
-/// GET /api/v1/templates - List all templates
+#[derive(Debug, Deserialize)]
+pub struct ListTemplatesQuery {
+    /// Search query matched against template IDs, names, event types and descriptions
+    pub q: Option<String>,
+}
+
+/// GET /api/v1/templates - List all templates, or search them with `?q=`
 #[tracing::instrument(name = "http.list_templates", skip(state))]
 pub async fn list_templates(
     State(state): State<AppState>,
     tenant_ctx: Option<Extension<RequestTenantContext>>,
+    Query(query): Query<ListTemplatesQuery>,
 ) -> Json<TemplateListResponse> {
-    let all_templates = state.template_store.list();
+    let search = query.q.as_deref().map(str::trim).filter(|q| !q.is_empty());
+    let all_templates = match search {
+        Some(q) => {
+            TEMPLATE_SEARCHES_TOTAL.inc();
+            let prefix = tenant_prefix(&tenant_ctx).unwrap_or_default();
+            state.template_store.search_with_prefix(q, &prefix)
+        }
+        None => state.template_store.list(),
+    };
     let templates: Vec<Template> = match tenant_prefix(&tenant_ctx) {
         Some(prefix) => all_templates
             .into_iter()
@@ -114,16 +140,156 @@ pub async fn list_templates(
     Json(TemplateListResponse { templates, total })
 }
 
-/// GET /api/v1/templates/:id - Get a specific template
-#[tracing::instrument(name = "http.get_template", skip(state))]
+#[derive(Debug, Deserialize)]
+pub struct ImportTemplatesQuery {
+    /// Conflict handling: "skip" (default), "overwrite" or "fail"
+    #[serde(default)]
+    pub on_conflict: ImportConflictStrategy,
+}
+
+/// GET /api/v1/templates/export - Export all (tenant-visible) templates as a JSON array
+#[tracing::instrument(name = "http.export_templates", skip(state))]
+pub async fn export_templates(
+    State(state): State<AppState>,
+    tenant_ctx: Option<Extension<RequestTenantContext>>,
+) -> Json<serde_json::Value> {
+    let exported = state.template_store.export_all();
+    let entries = exported.as_array().cloned().unwrap_or_default();
+
+    // Only export the caller's templates, with tenant prefixes stripped so the
+    // output can be imported into another tenant or environment
+    let prefix = tenant_prefix(&tenant_ctx);
+    let filtered: Vec<serde_json::Value> = entries
+        .into_iter()
+        .filter_map(|mut entry| {
+            let id = entry.get("id")?.as_str()?.to_string();
+            let local_id = match &prefix {
+                Some(prefix) => id.strip_prefix(prefix.as_str())?.to_string(),
+                None if state.tenant_manager.is_enabled() && id.contains(':') => return None,
+                None => id,
+            };
+            entry["id"] = serde_json::Value::String(local_id);
+            if let (Some(prefix), Some(parent)) =
+                (&prefix, entry.get("extends").and_then(|v| v.as_str()))
+            {
+                let local_parent = parent.strip_prefix(prefix.as_str()).unwrap_or(parent);
+                entry["extends"] = serde_json::Value::String(local_parent.to_string());
+            }
+            Some(entry)
+        })
+        .collect();
+
+    Json(serde_json::Value::Array(filtered))
+}
+
+/// POST /api/v1/templates/import - Import templates from a JSON array
+#[tracing::instrument(name = "http.import_templates", skip(state, body))]
+pub async fn import_templates(
+    State(state): State<AppState>,
+    tenant_ctx: Option<Extension<RequestTenantContext>>,
+    Query(query): Query<ImportTemplatesQuery>,
+    Json(body): Json<serde_json::Value>,
+) -> Result<Json<ImportResult>, (StatusCode, Json<TemplateErrorResponse>)> {
+    let Some(entries) = body.as_array() else {
+        return Err(TemplateError::InvalidTemplate(
+            "Request body must be a JSON array of templates".to_string(),
+        )
+        .into());
+    };
+
+    // Reject tenant-prefixed IDs up front, then scope the rest to the caller's tenant
+    let mut scoped = Vec::with_capacity(entries.len());
+    let mut rejected = Vec::new();
+    for entry in entries {
+        match entry.get("id").and_then(|v| v.as_str()) {
+            Some(id) if id.contains(':') => rejected.push((
+                id.to_string(),
+                TemplateError::InvalidId("ID must not contain ':'".to_string()).to_string(),
+            )),
+            Some(id) => {
+                let mut entry = entry.clone();
+                entry["id"] = serde_json::Value::String(tenant_template_id(&tenant_ctx, id));
+                if let Some(parent) = entry.get("extends").and_then(|v| v.as_str()) {
+                    entry["extends"] =
+                        serde_json::Value::String(tenant_template_id(&tenant_ctx, parent));
+                }
+                scoped.push(entry);
+            }
+            None => scoped.push(entry.clone()),
+        }
+    }
+
+    let mut result = state
+        .template_store
+        .import_from_json(&serde_json::Value::Array(scoped), query.on_conflict);
+    result.failed.extend(rejected);
+
+    Ok(Json(result))
+}
+
+/// Cache policy for template reads: short-lived, always revalidated via ETag
+const TEMPLATE_CACHE_CONTROL: &str = "max-age=60, must-revalidate";
+
+/// Strong ETag for a template, derived from its last update time
+fn template_etag(t: &Template) -> String {
+    let nanos = t.updated_at.timestamp_nanos_opt().unwrap_or_default();
+    let digest = Sha256::digest(nanos.to_string().as_bytes());
+    let hex: String = digest.iter().map(|b| format!("{:02x}", b)).collect();
+    format!("\"{}\"", hex)
+}
+
+/// GET /api/v1/templates/:id - Get a specific template (304 when `If-None-Match` matches)
+#[tracing::instrument(name = "http.get_template", skip(state, if_none_match))]
 pub async fn get_template(
     State(state): State<AppState>,
     tenant_ctx: Option<Extension<RequestTenantContext>>,
     Path(id): Path<String>,
-) -> Result<Json<Template>, (StatusCode, Json<TemplateErrorResponse>)> {
+    if_none_match: Option<TypedHeader<IfNoneMatch>>,
+) -> Result<Response, (StatusCode, Json<TemplateErrorResponse>)> {
     let scoped_id = tenant_template_id(&tenant_ctx, &id);
-    match state.template_store.get(&scoped_id) {
-        Ok(template) => Ok(Json(template)),
+    let template = state.template_store.get(&scoped_id)?;
+
+    let etag = template_etag(&template);
+    let headers = [
+        (header::ETAG, etag.clone()),
+        (header::CACHE_CONTROL, TEMPLATE_CACHE_CONTROL.to_string()),
+    ];
+
+    let not_modified = match (if_none_match, etag.parse::<ETag>()) {
+        (Some(TypedHeader(if_none_match)), Ok(etag)) => !if_none_match.precondition_passes(&etag),
+        _ => false,
+    };
+    if not_modified {
+        return Ok((StatusCode::NOT_MODIFIED, headers).into_response());
+    }
+
+    Ok((headers, Json(template)).into_response())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PreviewTemplateRequest {
+    /// Variables to substitute into the template
+    #[serde(default = "empty_variables")]
+    pub variables: serde_json::Value,
+}
+
+fn empty_variables() -> serde_json::Value {
+    serde_json::Value::Object(serde_json::Map::new())
+}
+
+/// GET /api/v1/templates/:id/preview - Render a template without sending a notification
+#[tracing::instrument(name = "http.preview_template", skip(state, request))]
+pub async fn preview_template(
+    State(state): State<AppState>,
+    tenant_ctx: Option<Extension<RequestTenantContext>>,
+    Path(id): Path<String>,
+    Json(request): Json<PreviewTemplateRequest>,
+) -> Result<Json<RenderedTemplate>, (StatusCode, Json<TemplateErrorResponse>)> {
+    TEMPLATE_PREVIEWS_TOTAL.inc();
+
+    let scoped_id = tenant_template_id(&tenant_ctx, &id);
+    match state.template_store.render(&scoped_id, &request.variables) {
+        Ok(rendered) => Ok(Json(rendered)),
         Err(e) => Err(e.into()),
     }
 }
@@ -134,9 +300,12 @@ pub async fn update_template(
     State(state): State<AppState>,
     tenant_ctx: Option<Extension<RequestTenantContext>>,
     Path(id): Path<String>,
-    Json(request): Json<UpdateTemplateRequest>,
+    Json(mut request): Json<UpdateTemplateRequest>,
 ) -> Result<Json<Template>, (StatusCode, Json<TemplateErrorResponse>)> {
     let scoped_id = tenant_template_id(&tenant_ctx, &id);
+    if let Some(Some(parent)) = request.extends.as_mut() {
+        *parent = tenant_template_id(&tenant_ctx, parent);
+    }
     match state.template_store.update(&scoped_id, request) {
         Ok(updated) => Ok(Json(updated)),
         Err(e) => Err(e.into()),
@@ -156,3 +325,188 @@ pub async fn delete_template(
         Err(e) => Err(e.into()),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use axum::http::StatusCode;
+    use serde_json::json;
+    use tower::ServiceExt;
+
+    use crate::api::test_support::{json_request, response_json, test_state};
+    use crate::server::create_app;
+
+    #[tokio::test]
+    async fn test_preview_template_renders_without_sending() {
+        let state = test_state().await;
+        let app = create_app(state.clone());
+
+        let response = app
+            .clone()
+            .oneshot(json_request(
+                "POST",
+                "/api/v1/templates",
+                json!({
+                    "id": "order-shipped",
+                    "name": "Order Shipped",
+                    "event_type": "order.shipped",
+                    "payload_template": {
+                        "title": "Order {{order_id}} shipped",
+                        "carrier": "{{carrier}}"
+                    },
+                    "default_priority": "High",
+                    "default_ttl": 3600
+                }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        let response = app
+            .clone()
+            .oneshot(json_request(
+                "GET",
+                "/api/v1/templates/order-shipped/preview",
+                json!({ "variables": { "order_id": "ORD-42", "carrier": "DHL" } }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response_json(response).await;
+        assert_eq!(body["event_type"], "order.shipped");
+        assert_eq!(body["payload"]["title"], "Order ORD-42 shipped");
+        assert_eq!(body["payload"]["carrier"], "DHL");
+        assert_eq!(body["ttl"], 3600);
+
+        // Nothing was dispatched
+        assert_eq!(state.dispatcher.stats().total_sent, 0);
+
+        let response = app
+            .oneshot(json_request(
+                "GET",
+                "/api/v1/templates/missing/preview",
+                json!({ "variables": {} }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_get_template_etag_revalidation() {
+        use axum_extra::headers::{CacheControl, ETag, HeaderMapExt, IfNoneMatch};
+
+        let state = test_state().await;
+        let app = create_app(state);
+
+        let response = app
+            .clone()
+            .oneshot(json_request(
+                "POST",
+                "/api/v1/templates",
+                json!({
+                    "id": "welcome",
+                    "name": "Welcome",
+                    "event_type": "user.welcome",
+                    "payload_template": { "title": "Hi {{name}}" }
+                }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        // Cache miss: full body with validators
+        let response = app
+            .clone()
+            .oneshot(json_request("GET", "/api/v1/templates/welcome", json!({})))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let etag: ETag = response.headers().typed_get().unwrap();
+        let cache_control: CacheControl = response.headers().typed_get().unwrap();
+        assert_eq!(cache_control.max_age(), Some(std::time::Duration::from_secs(60)));
+        assert!(cache_control.must_revalidate());
+
+        // Cache hit: matching If-None-Match yields 304 without a body
+        let mut request = json_request("GET", "/api/v1/templates/welcome", json!({}));
+        request
+            .headers_mut()
+            .typed_insert(IfNoneMatch::from(etag.clone()));
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers().typed_get::<ETag>(), Some(etag.clone()));
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert!(body.is_empty());
+
+        // Updating the template invalidates the old ETag
+        let response = app
+            .clone()
+            .oneshot(json_request(
+                "PUT",
+                "/api/v1/templates/welcome",
+                json!({ "name": "Welcome back" }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let mut request = json_request("GET", "/api/v1/templates/welcome", json!({}));
+        request
+            .headers_mut()
+            .typed_insert(IfNoneMatch::from(etag.clone()));
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let new_etag: ETag = response.headers().typed_get().unwrap();
+        assert_ne!(new_etag, etag);
+        let body = response_json(response).await;
+        assert_eq!(body["name"], "Welcome back");
+    }
+
+    #[tokio::test]
+    async fn test_search_templates_ranks_id_prefix_first() {
+        let state = test_state().await;
+        let app = create_app(state);
+
+        for (id, event_type) in [
+            ("invoice-with-order-ref", "invoice.created"),
+            ("order-shipped", "order.shipped"),
+            ("welcome", "user.welcome"),
+        ] {
+            let response = app
+                .clone()
+                .oneshot(json_request(
+                    "POST",
+                    "/api/v1/templates",
+                    json!({
+                        "id": id,
+                        "name": id,
+                        "event_type": event_type,
+                        "payload_template": {}
+                    }),
+                ))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::CREATED);
+        }
+
+        let response = app
+            .clone()
+            .oneshot(json_request("GET", "/api/v1/templates?q=order", json!({})))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response_json(response).await;
+        assert_eq!(body["total"], 2);
+        assert_eq!(body["templates"][0]["id"], "order-shipped");
+        assert_eq!(body["templates"][1]["id"], "invoice-with-order-ref");
+
+        // An empty query lists every template
+        let response = app
+            .oneshot(json_request("GET", "/api/v1/templates?q=", json!({})))
+            .await
+            .unwrap();
+        assert_eq!(response_json(response).await["total"], 3);
+    }
+}
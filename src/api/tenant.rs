@@ -1,17 +1,20 @@
 //! Multi-tenant management endpoints.
 
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     Extension, Json,
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-use crate::server::middleware::RequestTenantContext;
+use crate::server::middleware::{is_valid_tenant_id, RequestTenantContext};
 use crate::server::AppState;
-use crate::tenant::{TenantInfo, TenantStatsSnapshot};
+use crate::tenant::{
+    TenantInfo, TenantLimitsConfig, TenantRegistrationError, TenantStatsSnapshot,
+    TimestampedSample,
+};
 
-use super::connection::ChannelErrorResponse;
+use super::connection::{error_response, ChannelError, ChannelErrorResponse};
 
 #[derive(Debug, Serialize)]
 pub struct TenantListResponse {
@@ -69,8 +72,6 @@ pub async fn get_tenant_stats(
     tenant_ctx: Option<Extension<RequestTenantContext>>,
     Path(tenant_id): Path<String>,
 ) -> Result<Json<TenantStatsResponse>, (StatusCode, Json<ChannelErrorResponse>)> {
-    use super::connection::ChannelError;
-
     // When tenant context is present, only allow querying the caller's own tenant
     if let Some(ref t) = tenant_ctx {
         if t.0.tenant_id() != tenant_id {
@@ -110,3 +111,136 @@ pub async fn get_tenant_stats(
         },
     }))
 }
+
+// ============================================================================
+// Tenant Registration (admin)
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct RegisterTenantRequest {
+    pub tenant_id: String,
+    #[serde(default = "crate::tenant::default_tenant_limits")]
+    pub limits: TenantLimitsConfig,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RegisterTenantResponse {
+    pub tenant_id: String,
+    pub limits: TenantLimitsConfig,
+}
+
+impl From<TenantRegistrationError> for (StatusCode, Json<ChannelErrorResponse>) {
+    fn from(err: TenantRegistrationError) -> Self {
+        let (status, code) = match &err {
+            TenantRegistrationError::AlreadyRegistered(_) => (StatusCode::CONFLICT, "TENANT_ALREADY_REGISTERED"),
+            TenantRegistrationError::NotFound(_) => (StatusCode::NOT_FOUND, "TENANT_NOT_FOUND"),
+            TenantRegistrationError::HasActiveConnections(_) => (StatusCode::CONFLICT, "TENANT_HAS_ACTIVE_CONNECTIONS"),
+        };
+        error_response(status, code, err.to_string())
+    }
+}
+
+/// POST /admin/tenants - Register a tenant with its own limits at runtime
+#[tracing::instrument(name = "http.register_tenant", skip(state, request))]
+pub async fn register_tenant(
+    State(state): State<AppState>,
+    Json(request): Json<RegisterTenantRequest>,
+) -> Result<(StatusCode, Json<RegisterTenantResponse>), (StatusCode, Json<ChannelErrorResponse>)> {
+    let tenant_id = request.tenant_id.trim().to_string();
+    if !is_valid_tenant_id(&tenant_id) {
+        return Err(error_response(
+            StatusCode::BAD_REQUEST,
+            "INVALID_TENANT_ID",
+            format!("Invalid tenant ID '{}'", request.tenant_id),
+        ));
+    }
+
+    state
+        .tenant_manager
+        .register_tenant(&tenant_id, request.limits.clone())?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(RegisterTenantResponse {
+            tenant_id,
+            limits: request.limits,
+        }),
+    ))
+}
+
+/// DELETE /admin/tenants/:id - Unregister a tenant (fails while it has active connections)
+#[tracing::instrument(name = "http.unregister_tenant", skip(state))]
+pub async fn unregister_tenant(
+    State(state): State<AppState>,
+    Path(tenant_id): Path<String>,
+) -> Result<StatusCode, (StatusCode, Json<ChannelErrorResponse>)> {
+    state.tenant_manager.unregister_tenant(&tenant_id)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Window covered by `metrics_24h` in the tenant detail response
+const METRICS_WINDOW_SECS: i64 = 24 * 60 * 60;
+
+#[derive(Debug, Serialize)]
+pub struct TenantDetailResponse {
+    pub tenant_id: String,
+    pub registered: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limits: Option<TenantLimitsConfig>,
+    pub stats: TenantStatsSnapshot,
+    pub metrics_24h: Vec<TimestampedSample>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TenantMetricsQuery {
+    /// Range start (Unix epoch seconds), defaults to 24h before `end`
+    pub start: Option<i64>,
+    /// Range end (Unix epoch seconds), defaults to now
+    pub end: Option<i64>,
+}
+
+/// GET /admin/tenants/:id - Tenant registration, stats and 24h metrics history
+#[tracing::instrument(name = "http.get_tenant_detail", skip(state))]
+pub async fn get_tenant_detail(
+    State(state): State<AppState>,
+    Path(tenant_id): Path<String>,
+) -> Result<Json<TenantDetailResponse>, (StatusCode, Json<ChannelErrorResponse>)> {
+    let limits = state.tenant_manager.registered_limits(&tenant_id);
+    let stats = state.tenant_manager.get_stats(&tenant_id);
+    let now = chrono::Utc::now().timestamp();
+    let metrics_24h = state
+        .tenant_manager
+        .metrics_history(&tenant_id, now - METRICS_WINDOW_SECS, now);
+
+    if limits.is_none() && stats.total_connections == 0 && metrics_24h.is_empty() {
+        return Err(TenantRegistrationError::NotFound(tenant_id).into());
+    }
+
+    Ok(Json(TenantDetailResponse {
+        tenant_id,
+        registered: limits.is_some(),
+        limits,
+        stats,
+        metrics_24h,
+    }))
+}
+
+/// GET /admin/tenants/:id/metrics?start=&end= - Tenant metrics samples within a time range
+#[tracing::instrument(name = "http.get_tenant_metrics", skip(state))]
+pub async fn get_tenant_metrics(
+    State(state): State<AppState>,
+    Path(tenant_id): Path<String>,
+    Query(query): Query<TenantMetricsQuery>,
+) -> Result<Json<Vec<TimestampedSample>>, (StatusCode, Json<ChannelErrorResponse>)> {
+    let end = query.end.unwrap_or_else(|| chrono::Utc::now().timestamp());
+    let start = query.start.unwrap_or(end - METRICS_WINDOW_SECS);
+    if start > end {
+        return Err(error_response(
+            StatusCode::BAD_REQUEST,
+            "INVALID_TIME_RANGE",
+            "start must not be after end",
+        ));
+    }
+
+    Ok(Json(state.tenant_manager.metrics_history(&tenant_id, start, end)))
+}
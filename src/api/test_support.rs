@@ -0,0 +1,86 @@
+//! Shared helpers for API handler tests.
+
+use std::net::SocketAddr;
+
+use axum::body::Body;
+use axum::extract::ConnectInfo;
+use axum::http::Request;
+use jsonwebtoken::{encode, EncodingKey, Header};
+use serde_json::json;
+
+use crate::auth::Claims;
+use crate::config::Settings;
+use crate::server::AppState;
+
+/// Admin API key configured by `test_state` and sent by `admin_request`
+pub(crate) const TEST_ADMIN_KEY: &str = "admin-key-for-api-handler-tests";
+
+/// Build an `AppState` with default (in-memory) backends
+pub(crate) async fn test_state() -> AppState {
+    test_state_with(json!({})).await
+}
+
+/// Build an `AppState` with default backends and the given top-level settings sections
+pub(crate) async fn test_state_with(overrides: serde_json::Value) -> AppState {
+    let mut config = json!({
+        "jwt": { "secret": "test-secret-key-for-api-handler-tests" },
+        "api": { "admin_key": TEST_ADMIN_KEY }
+    });
+    if let (Some(config), Some(overrides)) = (config.as_object_mut(), overrides.as_object()) {
+        config.extend(overrides.clone());
+    }
+    let settings: Settings = serde_json::from_value(config).unwrap();
+    AppState::new(settings).await.unwrap()
+}
+
+/// Build a JSON request carrying the `ConnectInfo` the rate limit middleware expects
+pub(crate) fn json_request(method: &str, uri: &str, body: serde_json::Value) -> Request<Body> {
+    let mut request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("content-type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap();
+    request
+        .extensions_mut()
+        .insert(ConnectInfo(SocketAddr::from(([127, 0, 0, 1], 0))));
+    request
+}
+
+/// `json_request` carrying the `TEST_ADMIN_KEY` bearer token
+pub(crate) fn admin_request(method: &str, uri: &str, body: serde_json::Value) -> Request<Body> {
+    let mut request = json_request(method, uri, body);
+    request.headers_mut().insert(
+        axum::http::header::AUTHORIZATION,
+        format!("Bearer {}", TEST_ADMIN_KEY).parse().unwrap(),
+    );
+    request
+}
+
+/// Claims for `sub` valid for an hour, with no roles, tenant or scope
+pub(crate) fn test_claims(sub: &str) -> Claims {
+    let now = chrono::Utc::now().timestamp();
+    Claims {
+        sub: sub.to_string(),
+        exp: now + 3600,
+        iat: now,
+        roles: vec![],
+        tenant_id: None,
+        scope: None,
+        extra: Default::default(),
+    }
+}
+
+/// `Authorization` header value carrying `claims` signed with the state's JWT secret
+pub(crate) fn bearer_token(state: &AppState, claims: &Claims) -> String {
+    let key = EncodingKey::from_secret(state.settings.jwt.secret.as_bytes());
+    format!("Bearer {}", encode(&Header::default(), claims, &key).unwrap())
+}
+
+/// Read a response body as JSON
+pub(crate) async fn response_json(response: axum::response::Response) -> serde_json::Value {
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    serde_json::from_slice(&bytes).unwrap()
+}
@@ -49,17 +49,27 @@ pub struct PendingAckInfo {
     /// Connection ID that received the notification
     pub connection_id: Uuid,
 
+    /// Event type of the notification (empty for ACKs tracked before it was recorded)
+    #[serde(default)]
+    pub event_type: String,
+
     /// Timestamp when the notification was sent
     pub sent_at: DateTime<Utc>,
 }
 
 impl PendingAckInfo {
     /// Create a new pending ACK info.
-    pub fn new(notification_id: Uuid, user_id: String, connection_id: Uuid) -> Self {
+    pub fn new(
+        notification_id: Uuid,
+        user_id: String,
+        connection_id: Uuid,
+        event_type: String,
+    ) -> Self {
         Self {
             notification_id,
             user_id,
             connection_id,
+            event_type,
             sent_at: Utc::now(),
         }
     }
@@ -128,6 +138,75 @@ impl AckBackendStats {
     }
 }
 
+/// ACK rates for a single event type, for SLA monitoring.
+#[derive(Debug, Clone, Serialize)]
+pub struct AckEventTypeSummary {
+    /// Notification event type
+    pub event_type: String,
+
+    /// Notifications of this type tracked for ACK
+    pub total: u64,
+
+    /// ACKs received for this type
+    pub acked: u64,
+
+    /// ACK rate (acked / (acked + expired)), as in `AckBackendStats`
+    pub ack_rate: f64,
+
+    /// Average ACK latency in seconds
+    pub avg_latency_secs: f64,
+}
+
+impl AckEventTypeSummary {
+    /// Build a summary from the per-type counters kept by a backend.
+    pub fn from_counts(
+        event_type: String,
+        tracked: u64,
+        acked: u64,
+        expired: u64,
+        total_latency_ms: u64,
+    ) -> Self {
+        let avg_latency_secs = if acked > 0 {
+            total_latency_ms as f64 / acked as f64 / 1000.0
+        } else {
+            0.0
+        };
+        Self {
+            event_type,
+            total: tracked,
+            acked,
+            ack_rate: AckBackendStats::calculate_ack_rate(acked, expired),
+            avg_latency_secs,
+        }
+    }
+}
+
+/// Position of the last entry of a page of expired pending ACKs.
+///
+/// Pages are ordered by `position` and then by notification ID. The position is
+/// backend-defined: the send time in microseconds for the memory and PostgreSQL
+/// backends, and the expiry score of the timeout set for Redis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ExpiredPendingCursor {
+    pub(crate) position: i64,
+    pub(crate) notification_id: Uuid,
+}
+
+/// Split the first `limit` of `entries`, sorted by cursor and fetched with one extra
+/// row, into a page and the cursor for the next one
+pub(crate) fn into_page(
+    mut entries: Vec<(ExpiredPendingCursor, PendingAckInfo)>,
+    limit: usize,
+) -> (Vec<PendingAckInfo>, Option<ExpiredPendingCursor>) {
+    let cursor = if entries.len() > limit {
+        entries.truncate(limit);
+        entries.last().map(|(cursor, _)| *cursor)
+    } else {
+        None
+    };
+    (entries.into_iter().map(|(_, pending)| pending).collect(), cursor)
+}
+
 /// Backend trait for ACK tracking storage.
 ///
 /// This trait abstracts the storage layer for ACK tracking,
@@ -165,7 +244,8 @@ pub trait AckTrackerBackend: Send + Sync {
     /// * `notification_id` - The unique notification ID
     /// * `user_id` - The user ID who should acknowledge
     /// * `connection_id` - The connection ID that received the notification
-    async fn track(&self, notification_id: Uuid, user_id: &str, connection_id: Uuid);
+    /// * `event_type` - The notification's event type, for per-type ACK rates
+    async fn track(&self, notification_id: Uuid, user_id: &str, connection_id: Uuid, event_type: &str);
 
     /// Acknowledge a notification.
     ///
@@ -183,6 +263,31 @@ pub trait AckTrackerBackend: Send + Sync {
     /// Useful for debugging and validation.
     async fn get_pending(&self, notification_id: Uuid) -> Result<Option<PendingAckInfo>, AckBackendError>;
 
+    /// Get all pending (unacknowledged, unexpired) ACKs for a user.
+    ///
+    /// Lets clients re-ACK notifications after a reconnection.
+    async fn get_pending_by_user(&self, user_id: &str) -> Result<Vec<PendingAckInfo>, AckBackendError>;
+
+    /// Get one page of pending ACKs sent at or before `before`, ordered by send
+    /// time and then notification ID.
+    ///
+    /// Pass the returned cursor back to fetch the next page; it is `None` once every
+    /// matching entry has been returned. The cursor records the position of the last
+    /// entry rather than the entry itself, so pages stay stable while entries are
+    /// acknowledged or cleaned up between calls.
+    ///
+    /// # Arguments
+    ///
+    /// * `before` - Only entries with `sent_at <= before` are returned
+    /// * `cursor` - Cursor returned with the previous page
+    /// * `limit` - Maximum number of entries in the page
+    async fn get_expired_pending_page(
+        &self,
+        before: DateTime<Utc>,
+        cursor: Option<ExpiredPendingCursor>,
+        limit: usize,
+    ) -> Result<(Vec<PendingAckInfo>, Option<ExpiredPendingCursor>), AckBackendError>;
+
     /// Clean up expired pending ACKs.
     ///
     /// # Returns
@@ -195,6 +300,9 @@ pub trait AckTrackerBackend: Send + Sync {
 
     /// Get ACK tracking statistics.
     async fn stats(&self) -> AckBackendStats;
+
+    /// Get ACK rates and latency broken down by event type, sorted by event type.
+    async fn summary_by_event_type(&self) -> Result<Vec<AckEventTypeSummary>, AckBackendError>;
 }
 
 #[cfg(test)]
@@ -206,7 +314,12 @@ mod tests {
         let notif_id = Uuid::new_v4();
         let conn_id = Uuid::new_v4();
 
-        let info = PendingAckInfo::new(notif_id, "user-123".to_string(), conn_id);
+        let info = PendingAckInfo::new(
+            notif_id,
+            "user-123".to_string(),
+            conn_id,
+            "order.created".to_string(),
+        );
 
         assert_eq!(info.notification_id, notif_id);
         assert_eq!(info.user_id, "user-123");
@@ -219,6 +332,7 @@ mod tests {
             Uuid::new_v4(),
             "user-123".to_string(),
             Uuid::new_v4(),
+            "order.created".to_string(),
         );
 
         // With 30 second timeout, should not be expired
@@ -231,6 +345,7 @@ mod tests {
             Uuid::new_v4(),
             "user-123".to_string(),
             Uuid::new_v4(),
+            "order.created".to_string(),
         );
 
         // With 0 timeout, should be expired immediately
@@ -243,6 +358,7 @@ mod tests {
             Uuid::new_v4(),
             "user-123".to_string(),
             Uuid::new_v4(),
+            "order.created".to_string(),
         );
 
         // Latency should be very small immediately after creation
@@ -254,7 +370,12 @@ mod tests {
         let notif_id = Uuid::new_v4();
         let conn_id = Uuid::new_v4();
 
-        let info = PendingAckInfo::new(notif_id, "user-123".to_string(), conn_id);
+        let info = PendingAckInfo::new(
+            notif_id,
+            "user-123".to_string(),
+            conn_id,
+            "order.created".to_string(),
+        );
 
         // Should serialize to JSON
         let json = serde_json::to_string(&info).unwrap();
@@ -3,16 +3,45 @@
 //! This module provides a memory-based implementation of the `AckTrackerBackend` trait.
 //! ACK tracking state is stored in memory and will be lost on service restart.
 
+use std::collections::BinaryHeap;
 use std::sync::atomic::{AtomicU64, Ordering};
 
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use dashmap::DashMap;
 use uuid::Uuid;
 
 use crate::metrics::{ACK_EXPIRED_TOTAL, ACK_LATENCY, ACK_RECEIVED_TOTAL, ACK_TRACKED_TOTAL};
 use super::ack::AckConfig;
 
-use super::ack_backend::{AckBackendError, AckBackendStats, AckTrackerBackend, PendingAckInfo};
+use super::ack_backend::{
+    into_page, AckBackendError, AckBackendStats, AckEventTypeSummary, AckTrackerBackend,
+    ExpiredPendingCursor, PendingAckInfo,
+};
+
+/// Pending ACK ordered by its page position, for selecting a page without sorting
+/// every entry.
+struct PagedEntry(ExpiredPendingCursor, PendingAckInfo);
+
+impl PartialEq for PagedEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl Eq for PagedEntry {}
+
+impl PartialOrd for PagedEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PagedEntry {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.0.cmp(&other.0)
+    }
+}
 
 /// Statistics for ACK tracking (atomic counters for thread safety).
 #[derive(Debug, Default)]
@@ -37,6 +66,9 @@ pub struct MemoryAckBackend {
     pending: DashMap<Uuid, PendingAckInfo>,
     /// Statistics
     stats: AckStats,
+    /// Statistics per event type (pending entries are removed on ACK, so rates
+    /// cannot be derived from `pending`)
+    stats_by_event_type: DashMap<String, AckStats>,
 }
 
 impl MemoryAckBackend {
@@ -46,6 +78,7 @@ impl MemoryAckBackend {
             config,
             pending: DashMap::new(),
             stats: AckStats::default(),
+            stats_by_event_type: DashMap::new(),
         }
     }
 }
@@ -64,14 +97,24 @@ impl AckTrackerBackend for MemoryAckBackend {
         self.config.cleanup_interval_seconds
     }
 
-    async fn track(&self, notification_id: Uuid, user_id: &str, connection_id: Uuid) {
+    async fn track(&self, notification_id: Uuid, user_id: &str, connection_id: Uuid, event_type: &str) {
         if !self.config.enabled {
             return;
         }
 
-        let pending = PendingAckInfo::new(notification_id, user_id.to_string(), connection_id);
+        let pending = PendingAckInfo::new(
+            notification_id,
+            user_id.to_string(),
+            connection_id,
+            event_type.to_string(),
+        );
         self.pending.insert(notification_id, pending);
         self.stats.total_tracked.fetch_add(1, Ordering::Relaxed);
+        self.stats_by_event_type
+            .entry(event_type.to_string())
+            .or_default()
+            .total_tracked
+            .fetch_add(1, Ordering::Relaxed);
         ACK_TRACKED_TOTAL.inc();
 
         tracing::trace!(
@@ -106,6 +149,10 @@ impl AckTrackerBackend for MemoryAckBackend {
 
             self.stats.total_acked.fetch_add(1, Ordering::Relaxed);
             self.stats.total_latency_ms.fetch_add(latency_ms, Ordering::Relaxed);
+            if let Some(type_stats) = self.stats_by_event_type.get(&pending.event_type) {
+                type_stats.total_acked.fetch_add(1, Ordering::Relaxed);
+                type_stats.total_latency_ms.fetch_add(latency_ms, Ordering::Relaxed);
+            }
             ACK_RECEIVED_TOTAL.inc();
             ACK_LATENCY.observe(latency_ms as f64 / 1000.0);
 
@@ -131,6 +178,46 @@ impl AckTrackerBackend for MemoryAckBackend {
         Ok(self.pending.get(&notification_id).map(|r| r.value().clone()))
     }
 
+    async fn get_pending_by_user(&self, user_id: &str) -> Result<Vec<PendingAckInfo>, AckBackendError> {
+        let timeout = self.config.timeout_seconds;
+        let mut pending: Vec<PendingAckInfo> = self
+            .pending
+            .iter()
+            .filter(|r| r.user_id == user_id && !r.is_expired(timeout))
+            .map(|r| r.value().clone())
+            .collect();
+        pending.sort_by_key(|p| p.sent_at);
+        Ok(pending)
+    }
+
+    async fn get_expired_pending_page(
+        &self,
+        before: DateTime<Utc>,
+        cursor: Option<ExpiredPendingCursor>,
+        limit: usize,
+    ) -> Result<(Vec<PendingAckInfo>, Option<ExpiredPendingCursor>), AckBackendError> {
+        // Keep only the first `limit + 1` positions, so memory stays bounded by the page
+        let wanted = limit.saturating_add(1);
+        let mut first: BinaryHeap<PagedEntry> = BinaryHeap::new();
+        for entry in self.pending.iter().filter(|r| r.sent_at <= before) {
+            let position = ExpiredPendingCursor {
+                position: entry.sent_at.timestamp_micros(),
+                notification_id: *entry.key(),
+            };
+            if cursor.is_some_and(|c| position <= c) {
+                continue;
+            }
+            if first.len() < wanted {
+                first.push(PagedEntry(position, entry.value().clone()));
+            } else if first.peek().is_some_and(|last| position < last.0) {
+                first.pop();
+                first.push(PagedEntry(position, entry.value().clone()));
+            }
+        }
+        let sorted = first.into_sorted_vec().into_iter().map(|e| (e.0, e.1)).collect();
+        Ok(into_page(sorted, limit))
+    }
+
     async fn cleanup_expired(&self) -> usize {
         if !self.config.enabled {
             return 0;
@@ -142,6 +229,9 @@ impl AckTrackerBackend for MemoryAckBackend {
         self.pending.retain(|_, pending| {
             if pending.is_expired(timeout) {
                 expired_count += 1;
+                if let Some(type_stats) = self.stats_by_event_type.get(&pending.event_type) {
+                    type_stats.total_expired.fetch_add(1, Ordering::Relaxed);
+                }
                 false
             } else {
                 true
@@ -182,6 +272,24 @@ impl AckTrackerBackend for MemoryAckBackend {
             avg_latency_ms: AckBackendStats::calculate_avg_latency(total_latency_ms, total_acked),
         }
     }
+
+    async fn summary_by_event_type(&self) -> Result<Vec<AckEventTypeSummary>, AckBackendError> {
+        let mut summary: Vec<AckEventTypeSummary> = self
+            .stats_by_event_type
+            .iter()
+            .map(|r| {
+                AckEventTypeSummary::from_counts(
+                    r.key().clone(),
+                    r.total_tracked.load(Ordering::Relaxed),
+                    r.total_acked.load(Ordering::Relaxed),
+                    r.total_expired.load(Ordering::Relaxed),
+                    r.total_latency_ms.load(Ordering::Relaxed),
+                )
+            })
+            .collect();
+        summary.sort_by(|a, b| a.event_type.cmp(&b.event_type));
+        Ok(summary)
+    }
 }
 
 #[cfg(test)]
@@ -207,7 +315,7 @@ mod tests {
         let notif_id = Uuid::new_v4();
         let conn_id = Uuid::new_v4();
 
-        backend.track(notif_id, "user-1", conn_id).await;
+        backend.track(notif_id, "user-1", conn_id, "order.created").await;
 
         // Should not track when disabled
         assert_eq!(backend.pending_count().await, 0);
@@ -220,7 +328,7 @@ mod tests {
         let notif_id = Uuid::new_v4();
         let conn_id = Uuid::new_v4();
 
-        backend.track(notif_id, "user-1", conn_id).await;
+        backend.track(notif_id, "user-1", conn_id, "order.created").await;
 
         assert_eq!(backend.pending_count().await, 1);
         assert_eq!(backend.stats().await.total_tracked, 1);
@@ -233,7 +341,7 @@ mod tests {
         let notif_id = Uuid::new_v4();
         let conn_id = Uuid::new_v4();
 
-        backend.track(notif_id, "user-1", conn_id).await;
+        backend.track(notif_id, "user-1", conn_id, "order.created").await;
         assert!(backend.acknowledge(notif_id, "user-1").await);
 
         assert_eq!(backend.pending_count().await, 0);
@@ -247,7 +355,7 @@ mod tests {
         let notif_id = Uuid::new_v4();
         let conn_id = Uuid::new_v4();
 
-        backend.track(notif_id, "user-1", conn_id).await;
+        backend.track(notif_id, "user-1", conn_id, "order.created").await;
 
         // Wrong user should fail
         assert!(!backend.acknowledge(notif_id, "user-2").await);
@@ -275,7 +383,7 @@ mod tests {
         let notif_id = Uuid::new_v4();
         let conn_id = Uuid::new_v4();
 
-        backend.track(notif_id, "user-1", conn_id).await;
+        backend.track(notif_id, "user-1", conn_id, "order.created").await;
 
         let pending = backend.get_pending(notif_id).await.unwrap();
         assert!(pending.is_some());
@@ -308,7 +416,7 @@ mod tests {
         let notif_id = Uuid::new_v4();
         let conn_id = Uuid::new_v4();
 
-        backend.track(notif_id, "user-1", conn_id).await;
+        backend.track(notif_id, "user-1", conn_id, "order.created").await;
 
         // Wait a bit for expiry
         tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
@@ -328,7 +436,7 @@ mod tests {
         for _ in 0..3 {
             let notif_id = Uuid::new_v4();
             let conn_id = Uuid::new_v4();
-            backend.track(notif_id, "user-1", conn_id).await;
+            backend.track(notif_id, "user-1", conn_id, "order.created").await;
             notif_ids.push(notif_id);
         }
 
@@ -354,7 +462,7 @@ mod tests {
         let notif_id = Uuid::new_v4();
         let conn_id = Uuid::new_v4();
 
-        backend.track(notif_id, "user-1", conn_id).await;
+        backend.track(notif_id, "user-1", conn_id, "order.created").await;
 
         // Immediate ACK should have very low latency
         backend.acknowledge(notif_id, "user-1").await;
@@ -373,8 +481,8 @@ mod tests {
         let conn1 = Uuid::new_v4();
         let conn2 = Uuid::new_v4();
 
-        backend.track(notif1, "user-1", conn1).await;
-        backend.track(notif2, "user-2", conn2).await;
+        backend.track(notif1, "user-1", conn1, "order.created").await;
+        backend.track(notif2, "user-2", conn2, "order.created").await;
 
         assert_eq!(backend.pending_count().await, 2);
 
@@ -393,4 +501,126 @@ mod tests {
         let stats = backend.stats().await;
         assert_eq!(stats.backend_type, "memory");
     }
+
+    #[tokio::test]
+    async fn test_get_pending_by_user() {
+        let backend = MemoryAckBackend::new(create_enabled_config());
+        let conn_id = Uuid::new_v4();
+
+        let first = Uuid::new_v4();
+        let second = Uuid::new_v4();
+        backend.track(first, "user-1", conn_id, "order.created").await;
+        backend.track(second, "user-1", conn_id, "order.created").await;
+        backend.track(Uuid::new_v4(), "user-2", conn_id, "order.created").await;
+
+        let pending = backend.get_pending_by_user("user-1").await.unwrap();
+        assert_eq!(pending.len(), 2);
+        assert!(pending.iter().all(|p| p.user_id == "user-1"));
+
+        assert!(backend.acknowledge(first, "user-1").await);
+        let pending = backend.get_pending_by_user("user-1").await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].notification_id, second);
+
+        assert!(backend.get_pending_by_user("user-3").await.unwrap().is_empty());
+    }
+
+    /// Track `count` ACKs sent a minute ago, plus one sent just now
+    async fn backend_with_expired(count: usize) -> MemoryAckBackend {
+        let backend = MemoryAckBackend::new(create_enabled_config());
+        for _ in 0..count {
+            let id = Uuid::new_v4();
+            backend.track(id, "user-1", Uuid::new_v4(), "order.created").await;
+            backend.pending.get_mut(&id).unwrap().sent_at -= chrono::Duration::minutes(1);
+        }
+        backend.track(Uuid::new_v4(), "user-1", Uuid::new_v4(), "order.created").await;
+        backend
+    }
+
+    #[tokio::test]
+    async fn test_get_expired_pending_single_page() {
+        let backend = backend_with_expired(3).await;
+        let before = Utc::now() - chrono::Duration::seconds(30);
+
+        let (page, cursor) = backend.get_expired_pending_page(before, None, 3).await.unwrap();
+        assert_eq!(page.len(), 3);
+        assert_eq!(cursor, None);
+        assert!(page.iter().all(|p| p.sent_at <= before));
+        assert!(page.windows(2).all(|w| w[0].sent_at <= w[1].sent_at));
+    }
+
+    #[tokio::test]
+    async fn test_get_expired_pending_multiple_pages() {
+        let backend = backend_with_expired(5).await;
+        let before = Utc::now() - chrono::Duration::seconds(30);
+
+        let (first, cursor) = backend.get_expired_pending_page(before, None, 2).await.unwrap();
+        assert_eq!(first.len(), 2);
+        assert_eq!(cursor.map(|c| c.notification_id), Some(first[1].notification_id));
+
+        let (second, cursor) = backend.get_expired_pending_page(before, cursor, 2).await.unwrap();
+        assert_eq!(second.len(), 2);
+        assert!(second[0].sent_at >= first[1].sent_at);
+
+        // Acknowledging an entry between pages does not disturb the cursor
+        let (remaining, _) = backend.get_expired_pending_page(before, cursor, 2).await.unwrap();
+        assert!(backend.acknowledge(remaining[0].notification_id, "user-1").await);
+        let (third, cursor) = backend.get_expired_pending_page(before, cursor, 2).await.unwrap();
+        assert_eq!(third.len(), 0);
+        assert_eq!(cursor, None);
+    }
+
+    #[tokio::test]
+    async fn test_get_expired_pending_pages_through_same_send_time() {
+        let backend = backend_with_expired(5).await;
+        let before = Utc::now() - chrono::Duration::seconds(30);
+        let sent_at = Utc::now() - chrono::Duration::minutes(1);
+        for mut entry in backend.pending.iter_mut() {
+            if entry.sent_at <= before {
+                entry.sent_at = sent_at;
+            }
+        }
+
+        let mut seen = Vec::new();
+        let mut cursor = None;
+        loop {
+            let (page, next) = backend.get_expired_pending_page(before, cursor, 2).await.unwrap();
+            seen.extend(page.into_iter().map(|p| p.notification_id));
+            match next {
+                Some(next) => cursor = Some(next),
+                None => break,
+            }
+        }
+        let mut expected = seen.clone();
+        expected.sort();
+        assert_eq!(seen, expected);
+        assert_eq!(seen.len(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_summary_by_event_type() {
+        let backend = MemoryAckBackend::new(AckConfig {
+            enabled: true,
+            timeout_seconds: 0, // Immediate expiry
+            cleanup_interval_seconds: 60,
+        });
+        let conn_id = Uuid::new_v4();
+
+        let acked = Uuid::new_v4();
+        backend.track(acked, "user-1", conn_id, "order.created").await;
+        backend.track(Uuid::new_v4(), "user-1", conn_id, "order.created").await;
+        backend.track(Uuid::new_v4(), "user-1", conn_id, "chat.message").await;
+        assert!(backend.acknowledge(acked, "user-1").await);
+        assert_eq!(backend.cleanup_expired().await, 2);
+
+        let summary = backend.summary_by_event_type().await.unwrap();
+        assert_eq!(summary.len(), 2);
+        assert_eq!(summary[0].event_type, "chat.message");
+        assert_eq!(summary[0].acked, 0);
+        assert_eq!(summary[0].ack_rate, 0.0);
+        assert_eq!(summary[1].event_type, "order.created");
+        assert_eq!(summary[1].total, 2);
+        assert_eq!(summary[1].acked, 1);
+        assert!((summary[1].ack_rate - 0.5).abs() < 0.001);
+    }
 }
@@ -0,0 +1,122 @@
+//! No-op ACK tracking backend.
+//!
+//! Used when ACK tracking is switched off with the `ARA_FEATURE_ACK_TRACKING`
+//! feature flag, regardless of the configured backend. Nothing is tracked and
+//! every acknowledgment is rejected.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use super::ack_backend::{
+    AckBackendError, AckBackendStats, AckEventTypeSummary, AckTrackerBackend,
+    ExpiredPendingCursor, PendingAckInfo,
+};
+
+/// ACK backend that tracks nothing.
+#[derive(Debug, Default)]
+pub struct NoopAckBackend;
+
+impl NoopAckBackend {
+    /// Create a new no-op ACK backend.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl AckTrackerBackend for NoopAckBackend {
+    fn is_enabled(&self) -> bool {
+        false
+    }
+
+    fn timeout_seconds(&self) -> u64 {
+        0
+    }
+
+    fn cleanup_interval_seconds(&self) -> u64 {
+        0
+    }
+
+    async fn track(
+        &self,
+        _notification_id: Uuid,
+        _user_id: &str,
+        _connection_id: Uuid,
+        _event_type: &str,
+    ) {
+    }
+
+    async fn acknowledge(&self, _notification_id: Uuid, _user_id: &str) -> bool {
+        false
+    }
+
+    async fn get_pending(
+        &self,
+        _notification_id: Uuid,
+    ) -> Result<Option<PendingAckInfo>, AckBackendError> {
+        Ok(None)
+    }
+
+    async fn get_pending_by_user(
+        &self,
+        _user_id: &str,
+    ) -> Result<Vec<PendingAckInfo>, AckBackendError> {
+        Ok(Vec::new())
+    }
+
+    async fn get_expired_pending_page(
+        &self,
+        _before: DateTime<Utc>,
+        _cursor: Option<ExpiredPendingCursor>,
+        _limit: usize,
+    ) -> Result<(Vec<PendingAckInfo>, Option<ExpiredPendingCursor>), AckBackendError> {
+        Ok((Vec::new(), None))
+    }
+
+    async fn cleanup_expired(&self) -> usize {
+        0
+    }
+
+    async fn pending_count(&self) -> usize {
+        0
+    }
+
+    async fn stats(&self) -> AckBackendStats {
+        AckBackendStats {
+            backend_type: "noop".to_string(),
+            enabled: false,
+            total_tracked: 0,
+            total_acked: 0,
+            total_expired: 0,
+            pending_count: 0,
+            ack_rate: AckBackendStats::calculate_ack_rate(0, 0),
+            avg_latency_ms: 0,
+        }
+    }
+
+    async fn summary_by_event_type(&self) -> Result<Vec<AckEventTypeSummary>, AckBackendError> {
+        Ok(Vec::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_noop_backend_tracks_nothing() {
+        let backend = NoopAckBackend::new();
+        let notification_id = Uuid::new_v4();
+
+        backend.track(notification_id, "user-1", Uuid::new_v4(), "order.created").await;
+
+        assert!(!backend.is_enabled());
+        assert!(!backend.acknowledge(notification_id, "user-1").await);
+        assert!(backend.get_pending(notification_id).await.unwrap().is_none());
+        assert!(backend.get_pending_by_user("user-1").await.unwrap().is_empty());
+        assert_eq!(backend.pending_count().await, 0);
+        assert_eq!(backend.stats().await.backend_type, "noop");
+        assert!(backend.summary_by_event_type().await.unwrap().is_empty());
+    }
+}
@@ -4,14 +4,20 @@
 //! using PostgreSQL for storage. ACK tracking state survives service restarts.
 
 use async_trait::async_trait;
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Utc};
 use sqlx::PgPool;
 use uuid::Uuid;
 
 use crate::metrics::{ACK_EXPIRED_TOTAL, ACK_LATENCY, ACK_RECEIVED_TOTAL, ACK_TRACKED_TOTAL};
 use super::ack::AckConfig;
 
-use super::ack_backend::{AckBackendError, AckBackendStats, AckTrackerBackend, PendingAckInfo};
+use super::ack_backend::{
+    into_page, AckBackendError, AckBackendStats, AckEventTypeSummary, AckTrackerBackend,
+    ExpiredPendingCursor, PendingAckInfo,
+};
+
+/// Columns selected for a pending ACK row
+type PendingAckRow = (Uuid, String, Uuid, String, DateTime<Utc>);
 
 /// PostgreSQL-based ACK tracking backend.
 ///
@@ -20,6 +26,7 @@ use super::ack_backend::{AckBackendError, AckBackendStats, AckTrackerBackend, Pe
 /// Table structure:
 /// - `pending_acks` - Pending ACK tracking with expiration
 /// - `ack_stats` - Per-tenant statistics
+/// - `ack_event_type_stats` - Per-tenant, per-event-type statistics
 pub struct PostgresAckBackend {
     /// PostgreSQL connection pool
     pool: PgPool,
@@ -49,6 +56,80 @@ impl PostgresAckBackend {
             tenant_id,
         }
     }
+
+    /// Apply the schema migrations embedded from `migrations/`.
+    ///
+    /// Safe to call on every startup: applied versions are recorded in
+    /// `_sqlx_migrations` and skipped on subsequent runs. Every migration is
+    /// idempotent, so databases set up by hand with `psql` are adopted as-is.
+    pub async fn run_migrations(pool: &PgPool) -> Result<(), sqlx::Error> {
+        Self::forget_legacy_ack_migrations(pool).await?;
+        sqlx::migrate!().run(pool).await?;
+        Ok(())
+    }
+
+    /// Drop the records left by the former `migrations/ack/` history, whose versions
+    /// collide with `migrations/`. Their schema is recreated idempotently by the
+    /// single history, which is then recorded in their place.
+    async fn forget_legacy_ack_migrations(pool: &PgPool) -> Result<(), sqlx::Error> {
+        let recorded: bool =
+            sqlx::query_scalar("SELECT to_regclass('_sqlx_migrations') IS NOT NULL")
+                .fetch_one(pool)
+                .await?;
+        if !recorded {
+            return Ok(());
+        }
+
+        sqlx::query(
+            r#"
+            DELETE FROM _sqlx_migrations
+            WHERE (version, description) IN (
+                (1, 'create ack notifications'),
+                (2, 'add retry count'),
+                (3, 'add index user id'),
+                (4, 'add event type stats')
+            )
+            "#
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Best-effort update of the per-event-type counters.
+    async fn upsert_event_type_stats(
+        &self,
+        event_type: &str,
+        tracked: i64,
+        acked: i64,
+        expired: i64,
+        latency_ms: i64,
+    ) {
+        if let Err(e) = sqlx::query("SELECT upsert_ack_event_type_stats($1, $2, $3, $4, $5, $6)")
+            .bind(&self.tenant_id)
+            .bind(event_type)
+            .bind(tracked)
+            .bind(acked)
+            .bind(expired)
+            .bind(latency_ms)
+            .execute(&self.pool)
+            .await
+        {
+            tracing::warn!(error = %e, event_type = %event_type, "Failed to update per-event-type ACK stats");
+        }
+    }
+}
+
+fn pending_from_row(
+    (notification_id, user_id, connection_id, event_type, sent_at): PendingAckRow,
+) -> PendingAckInfo {
+    PendingAckInfo {
+        notification_id,
+        user_id,
+        connection_id,
+        event_type,
+        sent_at,
+    }
 }
 
 #[async_trait]
@@ -65,7 +146,7 @@ impl AckTrackerBackend for PostgresAckBackend {
         self.config.cleanup_interval_seconds
     }
 
-    async fn track(&self, notification_id: Uuid, user_id: &str, connection_id: Uuid) {
+    async fn track(&self, notification_id: Uuid, user_id: &str, connection_id: Uuid, event_type: &str) {
         if !self.config.enabled {
             return;
         }
@@ -75,8 +156,8 @@ impl AckTrackerBackend for PostgresAckBackend {
         // Insert pending ACK record
         let result = sqlx::query(
             r#"
-            INSERT INTO pending_acks (notification_id, tenant_id, user_id, connection_id, sent_at, expires_at)
-            VALUES ($1, $2, $3, $4, NOW(), $5)
+            INSERT INTO pending_acks (notification_id, tenant_id, user_id, connection_id, sent_at, expires_at, event_type)
+            VALUES ($1, $2, $3, $4, NOW(), $5, $6)
             ON CONFLICT (notification_id) DO NOTHING
             "#
         )
@@ -85,6 +166,7 @@ impl AckTrackerBackend for PostgresAckBackend {
         .bind(user_id)
         .bind(connection_id)
         .bind(expires_at)
+        .bind(event_type)
         .execute(&self.pool)
         .await;
 
@@ -105,6 +187,7 @@ impl AckTrackerBackend for PostgresAckBackend {
         {
             tracing::warn!(error = %e, "Failed to update ACK stats after track");
         }
+        self.upsert_event_type_stats(event_type, 1, 0, 0, 0).await;
 
         ACK_TRACKED_TOTAL.inc();
 
@@ -126,7 +209,7 @@ impl AckTrackerBackend for PostgresAckBackend {
             r#"
             DELETE FROM pending_acks
             WHERE notification_id = $1 AND user_id = $2 AND tenant_id = $3
-            RETURNING sent_at, user_id
+            RETURNING sent_at, event_type
             "#
         )
         .bind(notification_id)
@@ -138,7 +221,7 @@ impl AckTrackerBackend for PostgresAckBackend {
         .flatten();
 
         match pending {
-            Some((sent_at, _)) => {
+            Some((sent_at, event_type)) => {
                 // Calculate latency
                 let latency_ms = Utc::now()
                     .signed_duration_since(sent_at)
@@ -154,6 +237,8 @@ impl AckTrackerBackend for PostgresAckBackend {
                 {
                     tracing::warn!(error = %e, "Failed to update ACK stats after acknowledge");
                 }
+                self.upsert_event_type_stats(&event_type, 0, 1, 0, latency_ms as i64)
+                    .await;
 
                 ACK_RECEIVED_TOTAL.inc();
                 ACK_LATENCY.observe(latency_ms as f64 / 1000.0);
@@ -179,9 +264,9 @@ impl AckTrackerBackend for PostgresAckBackend {
     }
 
     async fn get_pending(&self, notification_id: Uuid) -> Result<Option<PendingAckInfo>, AckBackendError> {
-        let pending: Option<(Uuid, String, Uuid, chrono::DateTime<Utc>)> = sqlx::query_as(
+        let pending: Option<PendingAckRow> = sqlx::query_as(
             r#"
-            SELECT notification_id, user_id, connection_id, sent_at
+            SELECT notification_id, user_id, connection_id, event_type, sent_at
             FROM pending_acks
             WHERE notification_id = $1 AND tenant_id = $2
             "#
@@ -192,14 +277,70 @@ impl AckTrackerBackend for PostgresAckBackend {
         .await
         .map_err(AckBackendError::Postgres)?;
 
-        Ok(pending.map(|(notification_id, user_id, connection_id, sent_at)| {
-            PendingAckInfo {
-                notification_id,
-                user_id,
-                connection_id,
-                sent_at,
-            }
-        }))
+        Ok(pending.map(pending_from_row))
+    }
+
+    async fn get_pending_by_user(&self, user_id: &str) -> Result<Vec<PendingAckInfo>, AckBackendError> {
+        // Acknowledged rows are deleted, so every unexpired row is still pending
+        let rows: Vec<PendingAckRow> = sqlx::query_as(
+            r#"
+            SELECT notification_id, user_id, connection_id, event_type, sent_at
+            FROM pending_acks
+            WHERE tenant_id = $1 AND user_id = $2 AND expires_at > NOW()
+            ORDER BY sent_at ASC
+            "#
+        )
+        .bind(&self.tenant_id)
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AckBackendError::Postgres)?;
+
+        Ok(rows.into_iter().map(pending_from_row).collect())
+    }
+
+    async fn get_expired_pending_page(
+        &self,
+        before: DateTime<Utc>,
+        cursor: Option<ExpiredPendingCursor>,
+        limit: usize,
+    ) -> Result<(Vec<PendingAckInfo>, Option<ExpiredPendingCursor>), AckBackendError> {
+        // The cursor position is the send time in microseconds, PostgreSQL's precision
+        let after_sent_at = cursor.and_then(|c| DateTime::from_timestamp_micros(c.position));
+        let after_id = cursor.map(|c| c.notification_id);
+
+        // One extra row tells whether another page follows
+        let rows: Vec<PendingAckRow> = sqlx::query_as(
+            r#"
+            SELECT notification_id, user_id, connection_id, event_type, sent_at
+            FROM pending_acks
+            WHERE tenant_id = $1 AND sent_at <= $2
+              AND ($3::timestamptz IS NULL OR (sent_at, notification_id) > ($3, $4::uuid))
+            ORDER BY sent_at, notification_id
+            LIMIT $5
+            "#
+        )
+        .bind(&self.tenant_id)
+        .bind(before)
+        .bind(after_sent_at)
+        .bind(after_id)
+        .bind(limit.saturating_add(1).min(i64::MAX as usize) as i64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AckBackendError::Postgres)?;
+
+        let entries = rows
+            .into_iter()
+            .map(pending_from_row)
+            .map(|pending| {
+                let position = ExpiredPendingCursor {
+                    position: pending.sent_at.timestamp_micros(),
+                    notification_id: pending.notification_id,
+                };
+                (position, pending)
+            })
+            .collect();
+        Ok(into_page(entries, limit))
     }
 
     async fn cleanup_expired(&self) -> usize {
@@ -207,16 +348,23 @@ impl AckTrackerBackend for PostgresAckBackend {
             return 0;
         }
 
-        // Delete expired pending ACKs and count (tenant-scoped)
-        let result = sqlx::query(
-            "DELETE FROM pending_acks WHERE tenant_id = $1 AND expires_at <= NOW()"
+        // Delete expired pending ACKs and count them per event type (tenant-scoped)
+        let result: Result<Vec<(String, i64)>, sqlx::Error> = sqlx::query_as(
+            r#"
+            WITH expired AS (
+                DELETE FROM pending_acks
+                WHERE tenant_id = $1 AND expires_at <= NOW()
+                RETURNING event_type
+            )
+            SELECT event_type, COUNT(*) FROM expired GROUP BY event_type
+            "#
         )
         .bind(&self.tenant_id)
-        .execute(&self.pool)
+        .fetch_all(&self.pool)
         .await;
 
-        let count = match result {
-            Ok(r) => r.rows_affected() as usize,
+        let expired_by_type = match result {
+            Ok(rows) => rows,
             Err(e) => {
                 tracing::warn!(
                     error = %e,
@@ -226,6 +374,11 @@ impl AckTrackerBackend for PostgresAckBackend {
             }
         };
 
+        let count: usize = expired_by_type.iter().map(|(_, n)| *n as usize).sum();
+        for (event_type, expired) in &expired_by_type {
+            self.upsert_event_type_stats(event_type, 0, 0, *expired, 0).await;
+        }
+
         if count > 0 {
             // Update stats
             if let Err(e) = sqlx::query("SELECT upsert_ack_stats($1, 0, 0, $2, 0)")
@@ -307,6 +460,39 @@ impl AckTrackerBackend for PostgresAckBackend {
             },
         }
     }
+
+    async fn summary_by_event_type(&self) -> Result<Vec<AckEventTypeSummary>, AckBackendError> {
+        let rows: Vec<(String, i64, i64, i64, i64)> = sqlx::query_as(
+            r#"
+            SELECT event_type,
+                   SUM(total_tracked)::BIGINT,
+                   SUM(total_acked)::BIGINT,
+                   SUM(total_expired)::BIGINT,
+                   SUM(total_latency_ms)::BIGINT
+            FROM ack_event_type_stats
+            WHERE tenant_id = $1
+            GROUP BY event_type
+            ORDER BY event_type
+            "#
+        )
+        .bind(&self.tenant_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AckBackendError::Postgres)?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(event_type, tracked, acked, expired, latency_ms)| {
+                AckEventTypeSummary::from_counts(
+                    event_type,
+                    tracked as u64,
+                    acked as u64,
+                    expired as u64,
+                    latency_ms as u64,
+                )
+            })
+            .collect())
+    }
 }
 
 #[cfg(test)]
@@ -335,4 +521,49 @@ mod tests {
         let config = create_test_config();
         assert!(config.enabled);
     }
+
+    #[test]
+    fn test_embedded_migrations_are_not_duplicated() {
+        let migrator = sqlx::migrate!();
+        let checksums: std::collections::HashSet<_> =
+            migrator.iter().map(|m| m.checksum.clone()).collect();
+        assert_eq!(checksums.len(), migrator.iter().count());
+    }
+
+    /// Requires a PostgreSQL instance:
+    /// `DATABASE_URL=postgres://... cargo test -- --ignored`
+    #[tokio::test]
+    #[ignore]
+    async fn test_run_migrations_idempotent() {
+        let url = std::env::var("DATABASE_URL")
+            .unwrap_or_else(|_| "postgres://localhost:5432/ara_notification".to_string());
+        let pool = PgPool::connect(&url).await.unwrap();
+
+        PostgresAckBackend::run_migrations(&pool).await.unwrap();
+        PostgresAckBackend::run_migrations(&pool).await.unwrap();
+
+        let applied: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM _sqlx_migrations WHERE success")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(applied as usize, sqlx::migrate!().iter().count());
+
+        let retry_count_columns: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM information_schema.columns \
+             WHERE table_name = 'pending_acks' AND column_name = 'retry_count'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(retry_count_columns, 1);
+
+        let user_id_index: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM pg_indexes WHERE indexname = 'idx_pending_acks_user_id'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(user_id_index, 1);
+    }
 }
@@ -4,17 +4,39 @@
 //! using Redis Hash for pending ACK storage and Sorted Set for timeout tracking.
 //! ACK tracking state survives service restarts.
 
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
 use async_trait::async_trait;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
+use lazy_static::lazy_static;
 use uuid::Uuid;
 
 use crate::metrics::{ACK_EXPIRED_TOTAL, ACK_LATENCY, ACK_RECEIVED_TOTAL, ACK_TRACKED_TOTAL};
 use super::ack::AckConfig;
 use crate::redis::pool::{PoolError, RedisPool, RedisPoolExt};
 
-use super::ack_backend::{AckBackendError, AckBackendStats, AckTrackerBackend, PendingAckInfo};
+use super::ack_backend::{
+    into_page, AckBackendError, AckBackendStats, AckEventTypeSummary, AckTrackerBackend,
+    ExpiredPendingCursor, PendingAckInfo,
+};
+
+/// Number of timeout set entries read per `ZRANGEBYSCORE` when scanning expired ACKs
+const EXPIRED_BATCH_SIZE: usize = 500;
+
+lazy_static! {
+    /// Atomic HSET + EXPIRE for a new pending ACK (see `scripts/ack_track.lua`)
+    static ref TRACK_SCRIPT: redis::Script =
+        redis::Script::new(include_str!("../../../scripts/ack_track.lua"));
+
+    /// Atomic check-and-consume of a pending ACK (see `scripts/ack_acknowledge.lua`)
+    static ref ACKNOWLEDGE_SCRIPT: redis::Script =
+        redis::Script::new(include_str!("../../../scripts/ack_acknowledge.lua"));
+
+    /// Atomic removal of an expired pending ACK from every index (see `scripts/ack_expire.lua`)
+    static ref EXPIRE_SCRIPT: redis::Script =
+        redis::Script::new(include_str!("../../../scripts/ack_expire.lua"));
+}
 
 /// Redis-based ACK tracking backend.
 ///
@@ -23,10 +45,12 @@ use super::ack_backend::{AckBackendError, AckBackendStats, AckTrackerBackend, Pe
 /// Key structure:
 /// - `{prefix}:{tenant_id}:pending:{notification_id}` - Pending ACK info (Hash)
 /// - `{prefix}:{tenant_id}:timeout` - Timeout tracking (Sorted Set, score = expiry timestamp)
+/// - `{prefix}:{tenant_id}:user:{user_id}` - Per-user index of pending ACKs (Hash, notification_id -> info)
 /// - `{prefix}:{tenant_id}:stats` - Statistics counters (Hash)
+/// - `{prefix}:{tenant_id}:stats:event_types` - Per-event-type counters (Hash, `{event_type}:{counter}` -> value)
 pub struct RedisAckBackend {
     /// Redis connection pool
-    pool: Arc<RedisPool>,
+    pool: Arc<dyn RedisPoolExt + Send + Sync>,
 
     /// Configuration
     config: AckConfig,
@@ -41,12 +65,7 @@ pub struct RedisAckBackend {
 impl RedisAckBackend {
     /// Create a new Redis ACK backend.
     pub fn new(config: AckConfig, pool: Arc<RedisPool>, prefix: String) -> Self {
-        Self {
-            pool,
-            config,
-            prefix,
-            tenant_id: "default".to_string(),
-        }
+        Self::with_pool(config, pool, prefix, "default".to_string())
     }
 
     /// Create a new Redis ACK backend with a specific tenant ID.
@@ -55,6 +74,16 @@ impl RedisAckBackend {
         pool: Arc<RedisPool>,
         prefix: String,
         tenant_id: String,
+    ) -> Self {
+        Self::with_pool(config, pool, prefix, tenant_id)
+    }
+
+    /// Create a backend over any `RedisPoolExt` implementation.
+    fn with_pool(
+        config: AckConfig,
+        pool: Arc<dyn RedisPoolExt + Send + Sync>,
+        prefix: String,
+        tenant_id: String,
     ) -> Self {
         Self {
             pool,
@@ -72,6 +101,11 @@ impl RedisAckBackend {
         )
     }
 
+    /// Generate the Redis key for a user's pending ACK index.
+    fn user_key(&self, user_id: &str) -> String {
+        format!("{}:{}:user:{}", self.prefix, self.tenant_id, user_id)
+    }
+
     /// Generate the Redis key for the timeout sorted set.
     fn timeout_key(&self) -> String {
         format!("{}:{}:timeout", self.prefix, self.tenant_id)
@@ -82,6 +116,86 @@ impl RedisAckBackend {
         format!("{}:{}:stats", self.prefix, self.tenant_id)
     }
 
+    /// Generate the Redis key for per-event-type statistics.
+    fn event_type_stats_key(&self) -> String {
+        format!("{}:{}:stats:event_types", self.prefix, self.tenant_id)
+    }
+
+    /// Every Redis key this backend uses for a notification sent to `user_id`, so tests
+    /// can check that no two tenants' keys overlap.
+    #[cfg(test)]
+    pub(crate) fn key_for_tenant_isolation_test(
+        &self,
+        notification_id: &Uuid,
+        user_id: &str,
+    ) -> Vec<String> {
+        vec![
+            self.pending_key(notification_id),
+            self.user_key(user_id),
+            self.timeout_key(),
+            self.stats_key(),
+            self.event_type_stats_key(),
+        ]
+    }
+
+    /// Best-effort increment of one per-event-type counter.
+    async fn incr_event_type_stat(&self, event_type: &str, counter: &str, increment: i64) {
+        let field = format!("{}:{}", event_type, counter);
+        if let Err(e) = self
+            .pool
+            .hincrby(&self.event_type_stats_key(), &field, increment)
+            .await
+        {
+            tracing::debug!(
+                error = %Self::map_error(e),
+                event_type = %event_type,
+                "Failed to update per-event-type ACK stats"
+            );
+        }
+    }
+
+    /// Remove one expired pending ACK from every index, counting it as expired for its
+    /// event type. Returns `false` if it was left in place.
+    async fn expire_pending(&self, timeout_key: &str, notification_id_str: &str) -> bool {
+        let Ok(notification_id) = Uuid::parse_str(notification_id_str) else {
+            return false;
+        };
+
+        // Read the owner and event type before deleting the pending ACK info
+        let pending_key = self.pending_key(&notification_id);
+        let pending = match self.pool.hget(&pending_key, "data").await {
+            Ok(Some(json)) => serde_json::from_str::<PendingAckInfo>(&json).ok(),
+            _ => None,
+        };
+
+        // Drop the pending info, timeout entry and user index entry together
+        let user_key = pending.as_ref().map(|pending| self.user_key(&pending.user_id));
+        let mut keys = vec![pending_key.as_str(), timeout_key];
+        if let Some(user_key) = &user_key {
+            keys.push(user_key);
+        }
+        match self
+            .pool
+            .eval_script(&EXPIRE_SCRIPT, &keys, &[notification_id_str])
+            .await
+        {
+            Ok(_) => {
+                if let Some(pending) = pending {
+                    self.incr_event_type_stat(&pending.event_type, "expired", 1).await;
+                }
+                true
+            }
+            Err(e) => {
+                tracing::warn!(
+                    error = %Self::map_error(e),
+                    notification_id = %notification_id,
+                    "Failed to remove expired pending ACK from Redis"
+                );
+                false
+            }
+        }
+    }
+
     /// Convert pool error to ACK backend error.
     fn map_error(err: PoolError) -> AckBackendError {
         match err {
@@ -90,6 +204,7 @@ impl RedisAckBackend {
                 AckBackendError::Unavailable("Circuit breaker is open".to_string())
             }
             PoolError::ConnectionUnavailable(msg) => AckBackendError::Unavailable(msg),
+            e @ PoolError::Timeout(_) => AckBackendError::Unavailable(e.to_string()),
         }
     }
 }
@@ -108,15 +223,22 @@ impl AckTrackerBackend for RedisAckBackend {
         self.config.cleanup_interval_seconds
     }
 
-    async fn track(&self, notification_id: Uuid, user_id: &str, connection_id: Uuid) {
+    async fn track(&self, notification_id: Uuid, user_id: &str, connection_id: Uuid, event_type: &str) {
         if !self.config.enabled {
             return;
         }
 
-        let pending = PendingAckInfo::new(notification_id, user_id.to_string(), connection_id);
+        let pending = PendingAckInfo::new(
+            notification_id,
+            user_id.to_string(),
+            connection_id,
+            event_type.to_string(),
+        );
         let pending_key = self.pending_key(&notification_id);
+        let user_key = self.user_key(user_id);
         let timeout_key = self.timeout_key();
         let stats_key = self.stats_key();
+        let member = notification_id.to_string();
 
         // Serialize the pending info
         let pending_json = match serde_json::to_string(&pending) {
@@ -134,9 +256,17 @@ impl AckTrackerBackend for RedisAckBackend {
         // Calculate expiry timestamp
         let expiry_timestamp = Utc::now().timestamp() + self.config.timeout_seconds as i64;
 
-        // Store pending ACK info in Hash
-        let fields = [("data", pending_json.as_str())];
-        if let Err(e) = self.pool.hset_multiple(&pending_key, &fields).await {
+        // Store pending ACK info with its TTL atomically (TTL slightly longer than timeout)
+        let ttl = (self.config.timeout_seconds as i64 + 60).to_string(); // Extra minute buffer
+        if let Err(e) = self
+            .pool
+            .eval_script(
+                &TRACK_SCRIPT,
+                &[&pending_key, &user_key],
+                &["data", &pending_json, &ttl, &member],
+            )
+            .await
+        {
             tracing::warn!(
                 error = %Self::map_error(e),
                 notification_id = %notification_id,
@@ -145,18 +275,7 @@ impl AckTrackerBackend for RedisAckBackend {
             return;
         }
 
-        // Set expiration on the pending key (TTL slightly longer than timeout)
-        let ttl = (self.config.timeout_seconds as i64) + 60; // Extra minute buffer
-        if let Err(e) = self.pool.expire(&pending_key, ttl).await {
-            tracing::warn!(
-                error = %Self::map_error(e),
-                notification_id = %notification_id,
-                "Failed to set TTL on pending ACK key"
-            );
-        }
-
         // Add to timeout sorted set for cleanup
-        let member = notification_id.to_string();
         if let Err(e) = self
             .pool
             .zadd(&timeout_key, expiry_timestamp as f64, &member)
@@ -176,6 +295,7 @@ impl AckTrackerBackend for RedisAckBackend {
                 "Failed to update ACK stats"
             );
         }
+        self.incr_event_type_stat(event_type, "tracked", 1).await;
 
         ACK_TRACKED_TOTAL.inc();
 
@@ -194,11 +314,23 @@ impl AckTrackerBackend for RedisAckBackend {
 
         let pending_key = self.pending_key(&notification_id);
         let timeout_key = self.timeout_key();
+        let user_key = self.user_key(user_id);
         let stats_key = self.stats_key();
 
-        // Get pending ACK info
-        let pending_json = match self.pool.hget(&pending_key, "data").await {
-            Ok(Some(json)) => json,
+        // Check ownership and consume the pending ACK atomically, so concurrent
+        // ACKs for the same notification cannot both succeed
+        let member = notification_id.to_string();
+        let reply: (i64, String) = match self
+            .pool
+            .eval_script(
+                &ACKNOWLEDGE_SCRIPT,
+                &[&pending_key, &timeout_key, &user_key],
+                &[user_id, &member],
+            )
+            .await
+            .and_then(|value| redis::from_owned_redis_value(value).map_err(PoolError::from))
+        {
+            Ok(Some(reply)) => reply,
             Ok(None) => {
                 tracing::debug!(
                     notification_id = %notification_id,
@@ -211,65 +343,51 @@ impl AckTrackerBackend for RedisAckBackend {
                 tracing::warn!(
                     error = %Self::map_error(e),
                     notification_id = %notification_id,
-                    "Failed to get pending ACK from Redis"
+                    "Failed to acknowledge pending ACK in Redis"
                 );
                 return false;
             }
         };
 
-        // Deserialize and validate
-        let pending: PendingAckInfo = match serde_json::from_str(&pending_json) {
-            Ok(p) => p,
+        let (acked, pending_json) = reply;
+        let pending = serde_json::from_str::<PendingAckInfo>(&pending_json);
+
+        if acked != 1 {
+            if let Ok(pending) = pending {
+                tracing::warn!(
+                    notification_id = %notification_id,
+                    expected_user = %pending.user_id,
+                    actual_user = %user_id,
+                    "ACK user mismatch"
+                );
+            }
+            return false;
+        }
+
+        // Latency and event type come from the consumed pending info
+        let (latency_ms, event_type) = match pending {
+            Ok(pending) => (pending.latency_ms(), Some(pending.event_type)),
             Err(e) => {
                 tracing::warn!(
                     error = %e,
                     notification_id = %notification_id,
                     "Failed to deserialize pending ACK"
                 );
-                return false;
+                (0, None)
             }
         };
 
-        // Verify user_id matches
-        if pending.user_id != user_id {
-            tracing::warn!(
-                notification_id = %notification_id,
-                expected_user = %pending.user_id,
-                actual_user = %user_id,
-                "ACK user mismatch"
-            );
-            return false;
-        }
-
-        // Calculate latency before deleting
-        let latency_ms = pending.latency_ms();
-
-        // Delete pending ACK info
-        if let Err(e) = self.pool.del(&pending_key).await {
-            tracing::warn!(
-                error = %Self::map_error(e),
-                notification_id = %notification_id,
-                "Failed to delete pending ACK from Redis"
-            );
-            // Continue anyway since ACK was valid
-        }
-
-        // Remove from timeout sorted set
-        let member = notification_id.to_string();
-        if let Err(e) = self.pool.zrem(&timeout_key, &member).await {
-            tracing::warn!(
-                error = %Self::map_error(e),
-                notification_id = %notification_id,
-                "Failed to remove from timeout set"
-            );
-        }
-
         // Update stats
         let _ = self.pool.hincrby(&stats_key, "total_acked", 1).await;
         let _ = self
             .pool
             .hincrby(&stats_key, "total_latency_ms", latency_ms as i64)
             .await;
+        if let Some(event_type) = event_type {
+            self.incr_event_type_stat(&event_type, "acked", 1).await;
+            self.incr_event_type_stat(&event_type, "latency_ms", latency_ms as i64)
+                .await;
+        }
 
         ACK_RECEIVED_TOTAL.inc();
         ACK_LATENCY.observe(latency_ms as f64 / 1000.0);
@@ -305,6 +423,82 @@ impl AckTrackerBackend for RedisAckBackend {
         }
     }
 
+    async fn get_pending_by_user(&self, user_id: &str) -> Result<Vec<PendingAckInfo>, AckBackendError> {
+        let entries = self
+            .pool
+            .hscan_all(&self.user_key(user_id))
+            .await
+            .map_err(Self::map_error)?;
+
+        // Index entries are only removed on ACK; expired ones age out with the hash TTL
+        let timeout = self.config.timeout_seconds;
+        let mut pending: Vec<PendingAckInfo> = entries
+            .into_iter()
+            .filter_map(|(_, json)| serde_json::from_str::<PendingAckInfo>(&json).ok())
+            .filter(|p| !p.is_expired(timeout))
+            .collect();
+        pending.sort_by_key(|p| p.sent_at);
+        Ok(pending)
+    }
+
+    async fn get_expired_pending_page(
+        &self,
+        before: DateTime<Utc>,
+        cursor: Option<ExpiredPendingCursor>,
+        limit: usize,
+    ) -> Result<(Vec<PendingAckInfo>, Option<ExpiredPendingCursor>), AckBackendError> {
+        // The timeout set is scored by expiry, which is sent_at plus the timeout. Members
+        // sharing a score are ordered by their UUID string, which matches `Uuid` ordering.
+        let timeout_key = self.timeout_key();
+        let min_score = cursor.map_or(0.0, |c| c.position as f64);
+        let max_score = (before.timestamp() + self.config.timeout_seconds as i64) as f64;
+        let wanted = limit.saturating_add(1);
+        let batch = wanted.min(EXPIRED_BATCH_SIZE);
+
+        let mut expired = Vec::new();
+        let mut offset = 0;
+        while expired.len() < wanted {
+            let members = self
+                .pool
+                .zrangebyscore_limit_withscores(&timeout_key, min_score, max_score, offset, batch)
+                .await
+                .map_err(Self::map_error)?;
+            offset += members.len();
+            let exhausted = members.len() < batch;
+
+            for (member, score) in members {
+                if expired.len() >= wanted {
+                    break;
+                }
+                let Ok(notification_id) = Uuid::parse_str(&member) else {
+                    continue;
+                };
+                let position = ExpiredPendingCursor { position: score as i64, notification_id };
+                if cursor.is_some_and(|c| position <= c) {
+                    continue;
+                }
+
+                // Entries acknowledged since being added to the timeout set are skipped
+                let json = self
+                    .pool
+                    .hget(&self.pending_key(&notification_id), "data")
+                    .await
+                    .map_err(Self::map_error)?;
+                if let Some(json) = json {
+                    let pending: PendingAckInfo = serde_json::from_str(&json)?;
+                    if pending.sent_at <= before {
+                        expired.push((position, pending));
+                    }
+                }
+            }
+
+            if exhausted {
+                break;
+            }
+        }
+        Ok(into_page(expired, limit))
+    }
+
     async fn cleanup_expired(&self) -> usize {
         if !self.config.enabled {
             return 0;
@@ -313,41 +507,40 @@ impl AckTrackerBackend for RedisAckBackend {
         let timeout_key = self.timeout_key();
         let stats_key = self.stats_key();
 
-        // Get all notification IDs that have expired
         let now = Utc::now().timestamp() as f64;
-
-        let expired_ids = match self.pool.zrangebyscore(&timeout_key, 0.0, now).await {
-            Ok(ids) => ids,
-            Err(e) => {
-                tracing::warn!(
-                    error = %Self::map_error(e),
-                    "Failed to get expired ACKs from Redis"
-                );
-                return 0;
-            }
-        };
-
-        if expired_ids.is_empty() {
-            return 0;
-        }
-
         let mut cleaned_count = 0;
 
-        for notification_id_str in &expired_ids {
-            // Parse notification ID
-            let notification_id = match Uuid::parse_str(notification_id_str) {
-                Ok(id) => id,
-                Err(_) => continue,
+        // Expired IDs are fetched a batch at a time. Removed entries leave the set, so
+        // only the ones that could not be removed are skipped on the next fetch.
+        let mut skipped = 0;
+        loop {
+            let expired_ids = match self
+                .pool
+                .zrangebyscore_limit(&timeout_key, 0.0, now, skipped, EXPIRED_BATCH_SIZE)
+                .await
+            {
+                Ok(ids) => ids,
+                Err(e) => {
+                    tracing::warn!(
+                        error = %Self::map_error(e),
+                        "Failed to get expired ACKs from Redis"
+                    );
+                    break;
+                }
             };
-
-            // Delete pending ACK info
-            let pending_key = self.pending_key(&notification_id);
-            if self.pool.del(&pending_key).await.is_ok() {
-                cleaned_count += 1;
+            let exhausted = expired_ids.len() < EXPIRED_BATCH_SIZE;
+
+            for notification_id_str in &expired_ids {
+                if self.expire_pending(&timeout_key, notification_id_str).await {
+                    cleaned_count += 1;
+                } else {
+                    skipped += 1;
+                }
             }
 
-            // Remove from timeout set
-            let _ = self.pool.zrem(&timeout_key, notification_id_str).await;
+            if exhausted {
+                break;
+            }
         }
 
         if cleaned_count > 0 {
@@ -420,11 +613,47 @@ impl AckTrackerBackend for RedisAckBackend {
             avg_latency_ms: AckBackendStats::calculate_avg_latency(total_latency_ms, total_acked),
         }
     }
+
+    async fn summary_by_event_type(&self) -> Result<Vec<AckEventTypeSummary>, AckBackendError> {
+        let values = self
+            .pool
+            .hgetall(&self.event_type_stats_key())
+            .await
+            .map_err(Self::map_error)?;
+
+        // Event types may contain ':', so split the counter name off the end
+        let mut counts: BTreeMap<String, [u64; 4]> = BTreeMap::new();
+        for (field, value) in values {
+            let Some((event_type, counter)) = field.rsplit_once(':') else {
+                continue;
+            };
+            let index = match counter {
+                "tracked" => 0,
+                "acked" => 1,
+                "expired" => 2,
+                "latency_ms" => 3,
+                _ => continue,
+            };
+            counts.entry(event_type.to_string()).or_default()[index] = value.parse().unwrap_or(0);
+        }
+
+        Ok(counts
+            .into_iter()
+            .map(|(event_type, [tracked, acked, expired, latency_ms])| {
+                AckEventTypeSummary::from_counts(event_type, tracked, acked, expired, latency_ms)
+            })
+            .collect())
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::collections::{HashMap, HashSet};
+
+    use proptest::prelude::*;
+
     use super::*;
+    use crate::redis::pool::MockRedisPoolExt;
 
     fn create_test_config() -> AckConfig {
         AckConfig {
@@ -519,21 +748,356 @@ mod tests {
         assert_eq!(backend.cleanup_interval_seconds(), 120);
     }
 
+    fn create_backend_with(pool: MockRedisPoolExt) -> RedisAckBackend {
+        RedisAckBackend::with_pool(
+            create_test_config(),
+            Arc::new(pool),
+            "ara:ack".to_string(),
+            "default".to_string(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_track_uses_single_script_invocation() {
+        let mut pool = MockRedisPoolExt::new();
+        pool.expect_eval_script()
+            .withf(|_, keys, args| {
+                keys.len() == 2
+                    && keys[0].contains(":pending:")
+                    && keys[1] == "ara:ack:default:user:user-1"
+                    && args[0] == "data"
+                    && args[2] == "90"
+            })
+            .times(1)
+            .returning(|_, _, _| Ok(redis::Value::Int(1)));
+        pool.expect_zadd().times(1).returning(|_, _, _| Ok(()));
+        pool.expect_hincrby()
+            .withf(|key, field, _| key == "ara:ack:default:stats" && field == "total_tracked")
+            .times(1)
+            .returning(|_, _, _| Ok(1));
+        pool.expect_hincrby()
+            .withf(|key, field, _| {
+                key == "ara:ack:default:stats:event_types" && field == "order.created:tracked"
+            })
+            .times(1)
+            .returning(|_, _, _| Ok(1));
+        // HSET/EXPIRE must not be issued as separate commands
+        pool.expect_hset_multiple().never();
+        pool.expect_expire().never();
+
+        let backend = create_backend_with(pool);
+        backend.track(Uuid::new_v4(), "user-1", Uuid::new_v4(), "order.created").await;
+    }
+
+    /// Tenant IDs accepted by the tenant middleware (`is_valid_tenant_id`)
+    const TENANT_ID: &str = "[A-Za-z0-9_.-]{1,64}";
+
+    /// Backend for `tenant_id` over a mock pool in which only `stored_key` holds a
+    /// pending ACK (owned by `owner`)
+    fn tenant_backend_with_pending(
+        tenant_id: &str,
+        stored_key: String,
+        owner: &str,
+        notification_id: Uuid,
+    ) -> RedisAckBackend {
+        let pending = PendingAckInfo::new(
+            notification_id,
+            owner.to_string(),
+            Uuid::new_v4(),
+            "order.created".to_string(),
+        );
+        let data = serde_json::to_string(&pending).unwrap();
+
+        let mut pool = MockRedisPoolExt::new();
+        pool.expect_eval_script().returning(move |_, keys, _| {
+            if keys[0] == stored_key {
+                Ok(redis::Value::Array(vec![
+                    redis::Value::Int(1),
+                    redis::Value::BulkString(data.clone().into_bytes()),
+                ]))
+            } else {
+                Ok(redis::Value::Nil)
+            }
+        });
+        pool.expect_hincrby().returning(|_, _, _| Ok(1));
+
+        RedisAckBackend::with_pool(
+            create_test_config(),
+            Arc::new(pool),
+            "ara:ack".to_string(),
+            tenant_id.to_string(),
+        )
+    }
+
+    proptest! {
+        #[test]
+        fn prop_tenants_cannot_acknowledge_each_others_notifications(
+            tenant_a in TENANT_ID,
+            other_tenant in TENANT_ID,
+            suffix in "[A-Za-z0-9_.-]{1,8}",
+            use_suffix in any::<bool>(),
+            id in any::<u128>(),
+            user_a in "[A-Za-z0-9_.:-]{1,32}",
+            user_b in "[A-Za-z0-9_.:-]{1,32}",
+        ) {
+            // Tenant B's ID either extends tenant A's, so its keys share a prefix, or
+            // is unrelated
+            let tenant_b = if use_suffix { format!("{}{}", tenant_a, suffix) } else { other_tenant };
+            prop_assume!(tenant_a != tenant_b);
+            let notification_id = Uuid::from_u128(id);
+
+            let backend_a = RedisAckBackend::with_pool(
+                create_test_config(),
+                Arc::new(MockRedisPoolExt::new()),
+                "ara:ack".to_string(),
+                tenant_a.clone(),
+            );
+            let keys_a: HashSet<String> = backend_a
+                .key_for_tenant_isolation_test(&notification_id, &user_a)
+                .into_iter()
+                .collect();
+            let pending_key_a = backend_a.pending_key(&notification_id);
+
+            let backend_b = tenant_backend_with_pending(
+                &tenant_b,
+                pending_key_a.clone(),
+                &user_a,
+                notification_id,
+            );
+            for user in [&user_a, &user_b] {
+                for key in backend_b.key_for_tenant_isolation_test(&notification_id, user) {
+                    prop_assert!(!keys_a.contains(&key), "tenant {} reuses key {}", tenant_b, key);
+                }
+            }
+
+            let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+            prop_assert!(!runtime.block_on(backend_b.acknowledge(notification_id, &user_a)));
+            prop_assert!(!runtime.block_on(backend_b.acknowledge(notification_id, &user_b)));
+
+            // The same pending ACK is acknowledged under its own tenant
+            let backend_a =
+                tenant_backend_with_pending(&tenant_a, pending_key_a, &user_a, notification_id);
+            prop_assert!(runtime.block_on(backend_a.acknowledge(notification_id, &user_a)));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_acknowledge_consumed_only_once() {
+        let notification_id = Uuid::new_v4();
+        let pending = PendingAckInfo::new(
+            notification_id,
+            "user-1".to_string(),
+            Uuid::new_v4(),
+            "order.created".to_string(),
+        );
+        let data = serde_json::to_string(&pending).unwrap();
+
+        let mut pool = MockRedisPoolExt::new();
+        let mut seq = mockall::Sequence::new();
+        pool.expect_eval_script()
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(move |_, _, _| {
+                Ok(redis::Value::Array(vec![
+                    redis::Value::Int(1),
+                    redis::Value::BulkString(data.clone().into_bytes()),
+                ]))
+            });
+        pool.expect_eval_script()
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|_, _, _| Ok(redis::Value::Nil));
+        pool.expect_hincrby().times(4).returning(|_, _, _| Ok(1));
+
+        let backend = create_backend_with(pool);
+        assert!(backend.acknowledge(notification_id, "user-1").await);
+        assert!(!backend.acknowledge(notification_id, "user-1").await);
+    }
+
+    #[tokio::test]
+    async fn test_acknowledge_user_mismatch() {
+        let notification_id = Uuid::new_v4();
+        let pending = PendingAckInfo::new(
+            notification_id,
+            "user-1".to_string(),
+            Uuid::new_v4(),
+            "order.created".to_string(),
+        );
+        let data = serde_json::to_string(&pending).unwrap();
+
+        let mut pool = MockRedisPoolExt::new();
+        pool.expect_eval_script()
+            .withf(|_, _, args| args[0] == "user-2")
+            .times(1)
+            .returning(move |_, _, _| {
+                Ok(redis::Value::Array(vec![
+                    redis::Value::Int(0),
+                    redis::Value::BulkString(data.clone().into_bytes()),
+                ]))
+            });
+        pool.expect_hincrby().never();
+
+        let backend = create_backend_with(pool);
+        assert!(!backend.acknowledge(notification_id, "user-2").await);
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_expired_removes_user_index_entry() {
+        let notification_id = Uuid::new_v4();
+        let pending = PendingAckInfo::new(
+            notification_id,
+            "user-1".to_string(),
+            Uuid::new_v4(),
+            "order.created".to_string(),
+        );
+        let data = serde_json::to_string(&pending).unwrap();
+        let member = notification_id.to_string();
+
+        let mut pool = MockRedisPoolExt::new();
+        let expired = member.clone();
+        pool.expect_zrangebyscore_limit()
+            .withf(|_, _, _, offset, count| *offset == 0 && *count == EXPIRED_BATCH_SIZE)
+            .times(1)
+            .returning(move |_, _, _, _, _| Ok(vec![expired.clone()]));
+        pool.expect_hget()
+            .times(1)
+            .returning(move |_, _| Ok(Some(data.clone())));
+        pool.expect_eval_script()
+            .withf(move |_, keys, args| {
+                keys.len() == 3
+                    && keys[2] == "ara:ack:default:user:user-1"
+                    && args == [member.as_str()]
+            })
+            .times(1)
+            .returning(|_, _, _| Ok(redis::Value::Int(1)));
+        pool.expect_hincrby().times(2).returning(|_, _, _| Ok(1));
+
+        let backend = create_backend_with(pool);
+        assert_eq!(backend.cleanup_expired().await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_get_expired_pending_page_resumes_after_cursor() {
+        let mut ids: Vec<Uuid> = (0..3).map(|_| Uuid::new_v4()).collect();
+        ids.sort();
+        let sent_at = Utc::now() - chrono::Duration::minutes(5);
+        let score = (sent_at.timestamp() + 30) as f64;
+        let members: Vec<(String, f64)> = ids.iter().map(|id| (id.to_string(), score)).collect();
+        let data: HashMap<String, String> = ids
+            .iter()
+            .map(|id| {
+                let mut pending = PendingAckInfo::new(
+                    *id,
+                    "user-1".to_string(),
+                    Uuid::new_v4(),
+                    "order.created".to_string(),
+                );
+                pending.sent_at = sent_at;
+                (id.to_string(), serde_json::to_string(&pending).unwrap())
+            })
+            .collect();
+
+        let mut pool = MockRedisPoolExt::new();
+        // The cursor's score is the lower bound, and the page is read in bounded batches
+        pool.expect_zrangebyscore_limit_withscores()
+            .withf(move |_, min, _, offset, count| *min == score && *offset == 0 && *count == 2)
+            .times(1)
+            .returning(move |_, _, _, _, _| Ok(members[..2].to_vec()));
+        pool.expect_zrangebyscore_limit_withscores()
+            .withf(|_, _, _, offset, _| *offset == 2)
+            .times(1)
+            .returning(|_, _, _, _, _| Ok(Vec::new()));
+        pool.expect_hget().times(1).returning(move |key, _| {
+            let id = key.rsplit(':').next().unwrap();
+            Ok(data.get(id).cloned())
+        });
+
+        let backend = create_backend_with(pool);
+        let cursor = ExpiredPendingCursor { position: score as i64, notification_id: ids[0] };
+        let before = Utc::now() - chrono::Duration::minutes(1);
+        let (page, next) =
+            backend.get_expired_pending_page(before, Some(cursor), 1).await.unwrap();
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].notification_id, ids[1]);
+        assert_eq!(next, None);
+    }
+
+    #[tokio::test]
+    async fn test_get_pending_by_user_skips_expired() {
+        let fresh = PendingAckInfo::new(
+            Uuid::new_v4(),
+            "user-1".to_string(),
+            Uuid::new_v4(),
+            "order.created".to_string(),
+        );
+        let mut stale = PendingAckInfo::new(
+            Uuid::new_v4(),
+            "user-1".to_string(),
+            Uuid::new_v4(),
+            "order.created".to_string(),
+        );
+        stale.sent_at = Utc::now() - chrono::Duration::seconds(120);
+        let entries = vec![
+            (fresh.notification_id.to_string(), serde_json::to_string(&fresh).unwrap()),
+            (stale.notification_id.to_string(), serde_json::to_string(&stale).unwrap()),
+        ];
+
+        let mut pool = MockRedisPoolExt::new();
+        pool.expect_hscan_all()
+            .withf(|key| key == "ara:ack:default:user:user-1")
+            .times(1)
+            .returning(move |_| Ok(entries.clone()));
+
+        let backend = create_backend_with(pool);
+        let pending = backend.get_pending_by_user("user-1").await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].notification_id, fresh.notification_id);
+    }
+
+    #[tokio::test]
+    async fn test_summary_by_event_type_parses_counters() {
+        let mut pool = MockRedisPoolExt::new();
+        pool.expect_hgetall()
+            .withf(|key| key == "ara:ack:default:stats:event_types")
+            .times(1)
+            .returning(|_| {
+                Ok(vec![
+                    ("order.created:tracked".to_string(), "4".to_string()),
+                    ("order.created:acked".to_string(), "3".to_string()),
+                    ("order.created:expired".to_string(), "1".to_string()),
+                    ("order.created:latency_ms".to_string(), "1500".to_string()),
+                    ("ns:chat.message:tracked".to_string(), "2".to_string()),
+                ])
+            });
+
+        let backend = create_backend_with(pool);
+        let summary = backend.summary_by_event_type().await.unwrap();
+        assert_eq!(summary.len(), 2);
+        assert_eq!(summary[0].event_type, "ns:chat.message");
+        assert_eq!(summary[0].total, 2);
+        assert_eq!(summary[1].event_type, "order.created");
+        assert_eq!(summary[1].acked, 3);
+        assert!((summary[1].ack_rate - 0.75).abs() < 0.001);
+        assert!((summary[1].avg_latency_secs - 0.5).abs() < 0.001);
+    }
+
     fn create_mock_pool() -> Arc<RedisPool> {
         use crate::config::RedisConfig;
-        use crate::redis::{CircuitBreaker, RedisHealth};
+        use crate::redis::{BulkheadCircuitBreaker, RedisHealth};
 
         let config = RedisConfig {
             url: "redis://localhost:6379".to_string(),
             channels: vec![],
+            subscribe_patterns: vec![],
             circuit_breaker_failure_threshold: 5,
             circuit_breaker_success_threshold: 2,
             circuit_breaker_reset_timeout_seconds: 30,
             backoff_initial_delay_ms: 100,
             backoff_max_delay_ms: 30000,
+            backoff_max_total_wait_ms: None,
         };
 
-        let cb = Arc::new(CircuitBreaker::new());
+        let cb = Arc::new(BulkheadCircuitBreaker::new());
         let health = Arc::new(RedisHealth::new());
 
         Arc::new(RedisPool::new(config, cb, health).unwrap())
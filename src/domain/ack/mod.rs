@@ -10,12 +10,14 @@
 //! - `MemoryAckBackend`: In-memory storage using DashMap (default)
 //! - `RedisAckBackend`: Persistent storage using Redis Hash + Sorted Set
 //! - `PostgresAckBackend`: Persistent storage using PostgreSQL
+//! - `NoopAckBackend`: Tracks nothing (ACK tracking disabled by feature flag)
 //!
 //! Use `create_ack_backend()` to create the appropriate backend based on configuration.
 
 mod ack;
 mod ack_backend;
 mod ack_memory_backend;
+mod ack_noop_backend;
 mod ack_postgres_backend;
 mod ack_redis_backend;
 
@@ -26,8 +28,12 @@ use crate::infrastructure::postgres::PostgresPool;
 use crate::infrastructure::redis::pool::RedisPool;
 
 pub use ack::{AckConfig, AckStatsSnapshot, AckTracker};
-pub use ack_backend::{AckBackendError, AckBackendStats, AckTrackerBackend, PendingAckInfo};
+pub use ack_backend::{
+    AckBackendError, AckBackendStats, AckEventTypeSummary, AckTrackerBackend,
+    ExpiredPendingCursor, PendingAckInfo,
+};
 pub use ack_memory_backend::MemoryAckBackend;
+pub use ack_noop_backend::NoopAckBackend;
 pub use ack_postgres_backend::PostgresAckBackend;
 pub use ack_redis_backend::RedisAckBackend;
 
@@ -1,6 +1,12 @@
 //! Local-only session store (no distributed tracking)
+//!
+//! Sessions are kept in memory so single-server deployments get the same session
+//! visibility (admin endpoints, counts) as the Redis store, without routing.
+
+use std::collections::HashSet;
 
 use async_trait::async_trait;
+use dashmap::DashMap;
 use uuid::Uuid;
 
 use super::traits::SessionStore;
@@ -9,11 +15,25 @@ use super::types::{RoutedMessage, SessionInfo, SessionStoreBackend, SessionStore
 /// Local-only session store (no distributed tracking)
 pub struct LocalSessionStore {
     server_id: String,
+    /// Sessions on this server by connection ID
+    sessions: DashMap<Uuid, SessionInfo>,
 }
 
 impl LocalSessionStore {
     pub fn new(server_id: String) -> Self {
-        Self { server_id }
+        Self {
+            server_id,
+            sessions: DashMap::new(),
+        }
+    }
+
+    /// This server if any session matches `predicate`, otherwise nothing
+    fn servers_where(&self, predicate: impl Fn(&SessionInfo) -> bool) -> Vec<String> {
+        if self.sessions.iter().any(|entry| predicate(entry.value())) {
+            vec![self.server_id.clone()]
+        } else {
+            vec![]
+        }
     }
 }
 
@@ -31,38 +51,38 @@ impl SessionStore for LocalSessionStore {
         SessionStoreBackend::Local
     }
 
-    async fn register_session(&self, _session: &SessionInfo) -> Result<(), SessionStoreError> {
-        // No-op for local store
+    async fn register_session(&self, session: &SessionInfo) -> Result<(), SessionStoreError> {
+        self.sessions.insert(session.connection_id, session.clone());
         Ok(())
     }
 
-    async fn unregister_session(&self, _connection_id: Uuid) -> Result<(), SessionStoreError> {
-        // No-op for local store
+    async fn unregister_session(&self, connection_id: Uuid) -> Result<(), SessionStoreError> {
+        self.sessions.remove(&connection_id);
         Ok(())
     }
 
     async fn update_session_channels(
         &self,
-        _connection_id: Uuid,
-        _channels: Vec<String>,
+        connection_id: Uuid,
+        channels: Vec<String>,
     ) -> Result<(), SessionStoreError> {
-        // No-op for local store
+        if let Some(mut session) = self.sessions.get_mut(&connection_id) {
+            session.channels = channels;
+        }
         Ok(())
     }
 
     async fn refresh_sessions(&self) -> Result<usize, SessionStoreError> {
-        // No-op for local store
+        // Local sessions don't expire; they live until unregistered
         Ok(0)
     }
 
-    async fn find_user_servers(&self, _user_id: &str) -> Result<Vec<String>, SessionStoreError> {
-        // Only this server in local mode
-        Ok(vec![self.server_id.clone()])
+    async fn find_user_servers(&self, user_id: &str) -> Result<Vec<String>, SessionStoreError> {
+        Ok(self.servers_where(|session| session.user_id == user_id))
     }
 
-    async fn find_channel_servers(&self, _channel: &str) -> Result<Vec<String>, SessionStoreError> {
-        // Only this server in local mode
-        Ok(vec![self.server_id.clone()])
+    async fn find_channel_servers(&self, channel: &str) -> Result<Vec<String>, SessionStoreError> {
+        Ok(self.servers_where(|session| session.channels.iter().any(|c| c == channel)))
     }
 
     async fn publish_routed_message(
@@ -74,25 +94,52 @@ impl SessionStore for LocalSessionStore {
     }
 
     async fn cluster_connection_count(&self) -> Result<usize, SessionStoreError> {
-        // Not applicable in local mode
-        Err(SessionStoreError::Disabled)
+        Ok(self.sessions.len())
     }
 
     async fn cluster_user_count(&self) -> Result<usize, SessionStoreError> {
-        // Not applicable in local mode
-        Err(SessionStoreError::Disabled)
+        let users: HashSet<String> = self
+            .sessions
+            .iter()
+            .map(|entry| entry.user_id.clone())
+            .collect();
+        Ok(users.len())
+    }
+
+    async fn try_acquire_metrics_leader(
+        &self,
+        _ttl_seconds: u64,
+    ) -> Result<bool, SessionStoreError> {
+        // The only server is always the leader
+        Ok(true)
     }
 
     async fn get_all_sessions(&self) -> Result<Vec<SessionInfo>, SessionStoreError> {
-        // Local mode returns empty - no distributed tracking
-        Ok(vec![])
+        Ok(self
+            .sessions
+            .iter()
+            .map(|entry| entry.value().clone())
+            .collect())
     }
 
-    async fn get_user_sessions(
+    async fn get_user_sessions_page(
         &self,
-        _user_id: &str,
-    ) -> Result<Vec<SessionInfo>, SessionStoreError> {
-        // Local mode returns empty - no distributed tracking
-        Ok(vec![])
+        user_id: &str,
+        cursor: u64,
+        page_size: usize,
+    ) -> Result<(Vec<SessionInfo>, u64), SessionStoreError> {
+        // The cursor is an offset into the user's sessions in connection order
+        let mut sessions: Vec<SessionInfo> = self
+            .sessions
+            .iter()
+            .filter(|entry| entry.user_id == user_id)
+            .map(|entry| entry.value().clone())
+            .collect();
+        sessions.sort_by_key(|session| (session.connected_at, session.connection_id));
+
+        let start = (cursor as usize).min(sessions.len());
+        let end = start.saturating_add(page_size.max(1)).min(sessions.len());
+        let next_cursor = if end < sessions.len() { end as u64 } else { 0 };
+        Ok((sessions.drain(start..end).collect(), next_cursor))
     }
 }
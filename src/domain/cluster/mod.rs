@@ -7,6 +7,7 @@ mod factory;
 mod local;
 mod redis_store;
 mod router;
+mod signature;
 mod traits;
 mod types;
 
@@ -15,4 +16,7 @@ pub use local::LocalSessionStore;
 pub use redis_store::RedisSessionStore;
 pub use router::{ClusterRouter, RouteResult, RoutedMessageSubscriber};
 pub use traits::SessionStore;
-pub use types::{ClusterConfig, RoutedMessage, SessionInfo, SessionStoreBackend, SessionStoreError};
+pub use types::{
+    ClusterConfig, RouteStrategy, RoutedMessage, SessionInfo, SessionStoreBackend,
+    SessionStoreError,
+};
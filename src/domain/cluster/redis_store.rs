@@ -5,13 +5,29 @@ use redis::AsyncCommands;
 use std::sync::Arc;
 use uuid::Uuid;
 
-use crate::redis::pool::RedisPool;
+use crate::redis::pool::{PoolError, RedisPool, RedisPoolExt};
+use crate::redis::OperationType;
 
 use super::traits::SessionStore;
 use super::types::{
     ClusterConfig, RoutedMessage, SessionInfo, SessionStoreBackend, SessionStoreError,
 };
 
+/// Key holding the server ID of the current cluster metrics leader
+const METRICS_LEADER_KEY: &str = "ara:cluster:metrics-leader";
+
+/// Acquire the leader key, or extend it if this server already holds it
+const METRICS_LEADER_SCRIPT: &str = r#"
+if redis.call('SET', KEYS[1], ARGV[1], 'NX', 'EX', ARGV[2]) then
+    return 1
+end
+if redis.call('GET', KEYS[1]) == ARGV[1] then
+    redis.call('EXPIRE', KEYS[1], ARGV[2])
+    return 1
+end
+return 0
+"#;
+
 /// Redis-backed distributed session store
 pub struct RedisSessionStore {
     server_id: String,
@@ -41,6 +57,11 @@ impl RedisSessionStore {
         format!("{}:user:{}", self.config.session_prefix, user_id)
     }
 
+    /// Generate Redis key for user -> connection IDs mapping (cluster-wide)
+    fn user_sessions_key(&self, user_id: &str) -> String {
+        format!("{}:user_sessions:{}", self.config.session_prefix, user_id)
+    }
+
     /// Generate Redis key for channel -> servers mapping
     fn channel_servers_key(&self, channel: &str) -> String {
         format!("{}:channel:{}", self.config.session_prefix, channel)
@@ -56,38 +77,46 @@ impl RedisSessionStore {
         format!("{}:users", self.config.session_prefix)
     }
 
-    /// Scan Redis keys matching a pattern using SCAN (non-blocking alternative to KEYS)
-    async fn scan_keys(
+    /// Fetch and decode a session, treating undecodable data as missing
+    async fn get_session(&self, session_key: &str) -> Result<Option<SessionInfo>, SessionStoreError> {
+        let session_json: Option<String> = self
+            .pool
+            .execute(OperationType::Read, |mut conn| async move { conn.get(session_key).await })
+            .await
+            .map_err(store_error)?;
+
+        Ok(session_json.and_then(|json| serde_json::from_str(&json).ok()))
+    }
+
+    /// Get the values of every key matching a pattern, found with SCAN (non-blocking
+    /// alternative to KEYS)
+    async fn get_all<T: redis::FromRedisValue>(
         &self,
-        conn: &mut redis::aio::MultiplexedConnection,
         pattern: &str,
-    ) -> Result<Vec<String>, SessionStoreError> {
-        let mut keys = Vec::new();
-        let mut cursor: u64 = 0;
-
-        loop {
-            let (next_cursor, batch): (u64, Vec<String>) = redis::cmd("SCAN")
-                .arg(cursor)
-                .arg("MATCH")
-                .arg(pattern)
-                .arg("COUNT")
-                .arg(100)
-                .query_async(conn)
-                .await
-                .map_err(|e| SessionStoreError::RedisError(e.to_string()))?;
-
-            keys.extend(batch);
-            cursor = next_cursor;
+    ) -> Result<Vec<T>, SessionStoreError> {
+        let keys = self.pool.scan_keys(pattern).await.map_err(store_error)?;
 
-            if cursor == 0 {
-                break;
-            }
+        if keys.is_empty() {
+            return Ok(vec![]);
         }
 
-        Ok(keys)
+        self.pool
+            .execute(OperationType::Read, |mut conn| async move {
+                let mut values = Vec::with_capacity(keys.len());
+                for key in &keys {
+                    values.push(conn.get(key).await?);
+                }
+                Ok(values)
+            })
+            .await
+            .map_err(store_error)
     }
 }
 
+fn store_error(e: PoolError) -> SessionStoreError {
+    SessionStoreError::RedisError(e.to_string())
+}
+
 #[async_trait]
 impl SessionStore for RedisSessionStore {
     fn server_id(&self) -> &str {
@@ -103,48 +132,59 @@ impl SessionStore for RedisSessionStore {
     }
 
     async fn register_session(&self, session: &SessionInfo) -> Result<(), SessionStoreError> {
-        let mut conn = self.pool.get_connection().await.map_err(|e| {
-            SessionStoreError::RedisError(format!("Failed to get connection: {}", e))
-        })?;
-
+        // Sessions always carry this server's tags so routers can score it
+        let session = &SessionInfo {
+            server_tags: self.config.server_tags.clone(),
+            ..session.clone()
+        };
         let session_json = serde_json::to_string(session)
             .map_err(|e| SessionStoreError::SerializationError(e.to_string()))?;
 
         let ttl = self.config.session_ttl_seconds as i64;
 
         // Use a pipeline for atomic operations
-        let _: () = redis::pipe()
+        let mut pipe = redis::pipe();
+        pipe
             // Store session data
             .cmd("SET")
-            .arg(&self.session_key(session.connection_id))
+            .arg(self.session_key(session.connection_id))
             .arg(&session_json)
             .arg("EX")
             .arg(ttl)
             // Add server to user's server set
             .cmd("SADD")
-            .arg(&self.user_servers_key(&session.user_id))
+            .arg(self.user_servers_key(&session.user_id))
             .arg(&self.server_id)
             // Set TTL on user's server set
             .cmd("EXPIRE")
-            .arg(&self.user_servers_key(&session.user_id))
+            .arg(self.user_servers_key(&session.user_id))
+            .arg(ttl)
+            // Add connection to user's session set
+            .cmd("SADD")
+            .arg(self.user_sessions_key(&session.user_id))
+            .arg(session.connection_id.to_string())
+            .cmd("EXPIRE")
+            .arg(self.user_sessions_key(&session.user_id))
             .arg(ttl)
             // Add user to global users set (with TTL to prevent unbounded growth)
             .cmd("SADD")
-            .arg(&self.all_users_key())
+            .arg(self.all_users_key())
             .arg(&session.user_id)
             .cmd("EXPIRE")
-            .arg(&self.all_users_key())
+            .arg(self.all_users_key())
             .arg(ttl * 2) // 2x session TTL to allow for refresh cycles
             // Increment server connection count
             .cmd("INCR")
-            .arg(&self.server_connections_key(&self.server_id))
+            .arg(self.server_connections_key(&self.server_id))
             // Set TTL on server count
             .cmd("EXPIRE")
-            .arg(&self.server_connections_key(&self.server_id))
-            .arg(ttl)
-            .query_async(&mut conn)
+            .arg(self.server_connections_key(&self.server_id))
+            .arg(ttl);
+
+        self.pool
+            .execute(OperationType::Write, |mut conn| async move { pipe.query_async::<()>(&mut conn).await })
             .await
-            .map_err(|e| SessionStoreError::RedisError(e.to_string()))?;
+            .map_err(store_error)?;
 
         // Track locally for refresh and SREM checks
         self.local_connections
@@ -164,65 +204,58 @@ impl SessionStore for RedisSessionStore {
         // Remove from local tracking
         self.local_connections.remove(&connection_id);
 
-        let mut conn = self.pool.get_connection().await.map_err(|e| {
-            SessionStoreError::RedisError(format!("Failed to get connection: {}", e))
-        })?;
-
         // First, get the session to know the user_id
         let session_key = self.session_key(connection_id);
-        let session_json: Option<String> = conn
-            .get(&session_key)
-            .await
-            .map_err(|e| SessionStoreError::RedisError(e.to_string()))?;
-
-        if let Some(json) = session_json {
-            if let Ok(session) = serde_json::from_str::<SessionInfo>(&json) {
-                // Check if this server still has other connections for the same user
-                let user_has_other_connections = self.local_connections.iter().any(|entry| {
-                    *entry.key() != connection_id && *entry.value() == session.user_id
-                });
-
-                // Build pipeline: always delete session and decrement count
-                let mut pipe = redis::pipe();
-                pipe.cmd("DEL").arg(&session_key);
-                pipe.cmd("DECR")
-                    .arg(&self.server_connections_key(&self.server_id));
-
-                // Only SREM server from user set if no other connections for this user on this server
-                // Note: This is a best-effort check using local_connections count.
-                // For a precise check we'd need to verify user_id for each remaining connection,
-                // but that would require additional Redis lookups. We err on the side of keeping
-                // the mapping (avoiding false removal) by only removing when local_connections is empty.
-                if !user_has_other_connections {
-                    pipe.cmd("SREM")
-                        .arg(&self.user_servers_key(&session.user_id))
-                        .arg(&self.server_id);
-                    // Note: We do NOT remove from all_users_key here because the user
-                    // may still be connected on other servers. The global user set is
-                    // maintained via TTL on the user_servers_key entries -- when all
-                    // servers' entries expire, the user is effectively gone.
-                }
-
-                let _: () = pipe
-                    .query_async(&mut conn)
-                    .await
-                    .map_err(|e| SessionStoreError::RedisError(e.to_string()))?;
-
-                // Remove from channel indices
-                for channel in &session.channels {
-                    let _: () = conn
-                        .srem(&self.channel_servers_key(channel), &self.server_id)
-                        .await
-                        .map_err(|e| SessionStoreError::RedisError(e.to_string()))?;
-                }
+        let session = self.get_session(&session_key).await?;
+
+        if let Some(session) = session {
+            // Check if this server still has other connections for the same user
+            let user_has_other_connections = self.local_connections.iter().any(|entry| {
+                *entry.key() != connection_id && *entry.value() == session.user_id
+            });
+
+            // Build pipeline: always delete session and decrement count
+            let mut pipe = redis::pipe();
+            pipe.cmd("DEL").arg(&session_key);
+            pipe.cmd("DECR")
+                .arg(self.server_connections_key(&self.server_id));
+            pipe.cmd("SREM")
+                .arg(self.user_sessions_key(&session.user_id))
+                .arg(connection_id.to_string());
+
+            // Only SREM server from user set if no other connections for this user on this server
+            // Note: This is a best-effort check using local_connections count.
+            // For a precise check we'd need to verify user_id for each remaining connection,
+            // but that would require additional Redis lookups. We err on the side of keeping
+            // the mapping (avoiding false removal) by only removing when local_connections is empty.
+            if !user_has_other_connections {
+                pipe.cmd("SREM")
+                    .arg(self.user_servers_key(&session.user_id))
+                    .arg(&self.server_id);
+                // Note: We do NOT remove from all_users_key here because the user
+                // may still be connected on other servers. The global user set is
+                // maintained via TTL on the user_servers_key entries -- when all
+                // servers' entries expire, the user is effectively gone.
+            }
 
-                tracing::debug!(
-                    connection_id = %connection_id,
-                    user_id = %session.user_id,
-                    server_id = %self.server_id,
-                    "Session unregistered from cluster"
-                );
+            // Remove from channel indices
+            for channel in &session.channels {
+                pipe.cmd("SREM")
+                    .arg(self.channel_servers_key(channel))
+                    .arg(&self.server_id);
             }
+
+            self.pool
+                .execute(OperationType::Write, |mut conn| async move { pipe.query_async::<()>(&mut conn).await })
+                .await
+                .map_err(store_error)?;
+
+            tracing::debug!(
+                connection_id = %connection_id,
+                user_id = %session.user_id,
+                server_id = %self.server_id,
+                "Session unregistered from cluster"
+            );
         }
 
         Ok(())
@@ -233,82 +266,82 @@ impl SessionStore for RedisSessionStore {
         connection_id: Uuid,
         channels: Vec<String>,
     ) -> Result<(), SessionStoreError> {
-        let mut conn = self.pool.get_connection().await.map_err(|e| {
-            SessionStoreError::RedisError(format!("Failed to get connection: {}", e))
-        })?;
-
         let session_key = self.session_key(connection_id);
         let ttl = self.config.session_ttl_seconds as i64;
 
         // Get current session
-        let session_json: Option<String> = conn
-            .get(&session_key)
-            .await
-            .map_err(|e| SessionStoreError::RedisError(e.to_string()))?;
-
-        if let Some(json) = session_json {
-            if let Ok(mut session) = serde_json::from_str::<SessionInfo>(&json) {
-                let old_channels = std::mem::replace(&mut session.channels, channels.clone());
-
-                // Update session data
-                let updated_json = serde_json::to_string(&session)
-                    .map_err(|e| SessionStoreError::SerializationError(e.to_string()))?;
-
-                let _: () = conn
-                    .set_ex(&session_key, &updated_json, ttl as u64)
-                    .await
-                    .map_err(|e| SessionStoreError::RedisError(e.to_string()))?;
-
-                // Update channel indices
-                // Remove from old channels not in new set
-                for channel in &old_channels {
-                    if !channels.contains(channel) {
-                        let _: () = conn
-                            .srem(&self.channel_servers_key(channel), &self.server_id)
-                            .await
-                            .map_err(|e| SessionStoreError::RedisError(e.to_string()))?;
-                    }
-                }
+        let Some(mut session) = self.get_session(&session_key).await? else {
+            return Ok(());
+        };
 
-                // Add to new channels
-                for channel in &channels {
-                    let key = self.channel_servers_key(channel);
-                    let _: () = redis::pipe()
-                        .cmd("SADD")
-                        .arg(&key)
-                        .arg(&self.server_id)
-                        .cmd("EXPIRE")
-                        .arg(&key)
-                        .arg(ttl)
-                        .query_async(&mut conn)
-                        .await
-                        .map_err(|e| SessionStoreError::RedisError(e.to_string()))?;
-                }
-            }
+        let old_channels = std::mem::replace(&mut session.channels, channels.clone());
+
+        // Update session data
+        let updated_json = serde_json::to_string(&session)
+            .map_err(|e| SessionStoreError::SerializationError(e.to_string()))?;
+
+        let mut pipe = redis::pipe();
+        pipe.cmd("SET")
+            .arg(&session_key)
+            .arg(&updated_json)
+            .arg("EX")
+            .arg(ttl);
+
+        // Update channel indices
+        // Remove from old channels not in new set
+        for channel in old_channels.iter().filter(|c| !channels.contains(c)) {
+            pipe.cmd("SREM")
+                .arg(self.channel_servers_key(channel))
+                .arg(&self.server_id);
         }
 
-        Ok(())
+        // Add to new channels
+        for channel in &channels {
+            let key = self.channel_servers_key(channel);
+            pipe.cmd("SADD")
+                .arg(&key)
+                .arg(&self.server_id)
+                .cmd("EXPIRE")
+                .arg(&key)
+                .arg(ttl);
+        }
+
+        self.pool
+            .execute(OperationType::Write, |mut conn| async move { pipe.query_async::<()>(&mut conn).await })
+            .await
+            .map_err(store_error)
     }
 
     async fn refresh_sessions(&self) -> Result<usize, SessionStoreError> {
-        let mut conn = self.pool.get_connection().await.map_err(|e| {
-            SessionStoreError::RedisError(format!("Failed to get connection: {}", e))
-        })?;
-
         let ttl = self.config.session_ttl_seconds as i64;
-        let mut refreshed = 0;
+        let session_ids: Vec<Uuid> = self.local_connections.iter().map(|e| *e.key()).collect();
 
-        // Refresh all local connections
-        for entry in self.local_connections.iter() {
-            let connection_id = *entry.key();
-            let session_key = self.session_key(connection_id);
+        // Refresh all local connections, then the server connection count
+        let mut pipe = redis::pipe();
+        for connection_id in &session_ids {
+            pipe.cmd("EXPIRE").arg(self.session_key(*connection_id)).arg(ttl);
+        }
+        pipe.cmd("EXPIRE")
+            .arg(self.server_connections_key(&self.server_id))
+            .arg(ttl)
+            .ignore();
+        let user_ids: std::collections::HashSet<String> =
+            self.local_connections.iter().map(|e| e.value().clone()).collect();
+        for user_id in &user_ids {
+            pipe.cmd("EXPIRE")
+                .arg(self.user_sessions_key(user_id))
+                .arg(ttl)
+                .ignore();
+        }
 
-            // Refresh TTL
-            let result: i32 = conn
-                .expire(&session_key, ttl)
-                .await
-                .map_err(|e| SessionStoreError::RedisError(e.to_string()))?;
+        let results: Vec<i32> = self
+            .pool
+            .execute(OperationType::Write, |mut conn| async move { pipe.query_async(&mut conn).await })
+            .await
+            .map_err(store_error)?;
 
+        let mut refreshed = 0;
+        for (connection_id, result) in session_ids.into_iter().zip(results) {
             if result == 1 {
                 refreshed += 1;
             } else {
@@ -317,12 +350,6 @@ impl SessionStore for RedisSessionStore {
             }
         }
 
-        // Also refresh server connection count
-        let _: () = conn
-            .expire(&self.server_connections_key(&self.server_id), ttl)
-            .await
-            .map_err(|e| SessionStoreError::RedisError(e.to_string()))?;
-
         if refreshed > 0 {
             tracing::debug!(
                 server_id = %self.server_id,
@@ -335,40 +362,28 @@ impl SessionStore for RedisSessionStore {
     }
 
     async fn find_user_servers(&self, user_id: &str) -> Result<Vec<String>, SessionStoreError> {
-        let mut conn = self.pool.get_connection().await.map_err(|e| {
-            SessionStoreError::RedisError(format!("Failed to get connection: {}", e))
-        })?;
-
-        let servers: Vec<String> = conn
-            .smembers(&self.user_servers_key(user_id))
+        let key = self.user_servers_key(user_id);
+        self.pool
+            .execute(OperationType::Read, |mut conn| async move { conn.smembers(&key).await })
             .await
-            .map_err(|e| SessionStoreError::RedisError(e.to_string()))?;
-
-        Ok(servers)
+            .map_err(store_error)
     }
 
     async fn find_channel_servers(&self, channel: &str) -> Result<Vec<String>, SessionStoreError> {
-        let mut conn = self.pool.get_connection().await.map_err(|e| {
-            SessionStoreError::RedisError(format!("Failed to get connection: {}", e))
-        })?;
-
-        let servers: Vec<String> = conn
-            .smembers(&self.channel_servers_key(channel))
+        let key = self.channel_servers_key(channel);
+        self.pool
+            .execute(OperationType::Read, |mut conn| async move { conn.smembers(&key).await })
             .await
-            .map_err(|e| SessionStoreError::RedisError(e.to_string()))?;
-
-        Ok(servers)
+            .map_err(store_error)
     }
 
     async fn publish_routed_message(
         &self,
         message: &RoutedMessage,
     ) -> Result<(), SessionStoreError> {
-        let mut conn = self.pool.get_connection().await.map_err(|e| {
-            SessionStoreError::RedisError(format!("Failed to get connection: {}", e))
-        })?;
-
-        let message_json = serde_json::to_string(message)
+        let mut message = message.clone();
+        message.sign(&self.config.cluster_secret);
+        let message_json = serde_json::to_string(&message)
             .map_err(|e| SessionStoreError::SerializationError(e.to_string()))?;
 
         // Determine routing channel
@@ -379,10 +394,10 @@ impl SessionStore for RedisSessionStore {
             self.config.routing_channel.clone()
         };
 
-        let _: () = conn
-            .publish(&channel, &message_json)
+        self.pool
+            .execute(OperationType::Write, |mut conn| async move { conn.publish::<_, _, ()>(&channel, &message_json).await })
             .await
-            .map_err(|e| SessionStoreError::RedisError(e.to_string()))?;
+            .map_err(store_error)?;
 
         tracing::debug!(
             from_server = %message.from_server,
@@ -395,109 +410,100 @@ impl SessionStore for RedisSessionStore {
     }
 
     async fn cluster_connection_count(&self) -> Result<usize, SessionStoreError> {
-        let mut conn = self.pool.get_connection().await.map_err(|e| {
-            SessionStoreError::RedisError(format!("Failed to get connection: {}", e))
-        })?;
-
         // Get all server keys and sum their connection counts
         let pattern = format!("{}:server:*", self.config.session_prefix);
-        let keys = self.scan_keys(&mut conn, &pattern).await?;
-
-        if keys.is_empty() {
-            return Ok(0);
-        }
-
-        let mut total: usize = 0;
-        for key in keys {
-            let count: Option<i64> = conn
-                .get(&key)
-                .await
-                .map_err(|e| SessionStoreError::RedisError(e.to_string()))?;
-            if let Some(c) = count {
-                if c > 0 {
-                    total += c as usize;
-                }
-            }
-        }
-
-        Ok(total)
+        let counts: Vec<Option<i64>> = self.get_all(&pattern).await?;
+
+        Ok(counts
+            .into_iter()
+            .flatten()
+            .filter(|&c| c > 0)
+            .map(|c| c as usize)
+            .sum())
     }
 
     async fn cluster_user_count(&self) -> Result<usize, SessionStoreError> {
-        let mut conn = self.pool.get_connection().await.map_err(|e| {
-            SessionStoreError::RedisError(format!("Failed to get connection: {}", e))
-        })?;
+        let key = self.all_users_key();
+        self.pool
+            .execute(OperationType::Read, |mut conn| async move { conn.scard(&key).await })
+            .await
+            .map_err(store_error)
+    }
 
-        let count: usize = conn
-            .scard(&self.all_users_key())
+    async fn try_acquire_metrics_leader(
+        &self,
+        ttl_seconds: u64,
+    ) -> Result<bool, SessionStoreError> {
+        let script = redis::Script::new(METRICS_LEADER_SCRIPT);
+        let mut invocation = script.prepare_invoke();
+        invocation
+            .key(METRICS_LEADER_KEY)
+            .arg(&self.server_id)
+            .arg(ttl_seconds);
+
+        let acquired: i32 = self
+            .pool
+            .execute(OperationType::Write, |mut conn| async move { invocation.invoke_async(&mut conn).await })
             .await
-            .map_err(|e| SessionStoreError::RedisError(e.to_string()))?;
+            .map_err(store_error)?;
 
-        Ok(count)
+        Ok(acquired == 1)
     }
 
     async fn get_all_sessions(&self) -> Result<Vec<SessionInfo>, SessionStoreError> {
-        let mut conn = self.pool.get_connection().await.map_err(|e| {
-            SessionStoreError::RedisError(format!("Failed to get connection: {}", e))
-        })?;
-
-        // Get all session keys using SCAN (non-blocking)
         let pattern = format!("{}:conn:*", self.config.session_prefix);
-        let keys = self.scan_keys(&mut conn, &pattern).await?;
-
-        if keys.is_empty() {
-            return Ok(vec![]);
-        }
+        let sessions: Vec<Option<String>> = self.get_all(&pattern).await?;
 
-        // Get all session data
-        let mut sessions = Vec::with_capacity(keys.len());
-        for key in keys {
-            let json: Option<String> = conn
-                .get(&key)
-                .await
-                .map_err(|e| SessionStoreError::RedisError(e.to_string()))?;
-            if let Some(data) = json {
-                if let Ok(session) = serde_json::from_str::<SessionInfo>(&data) {
-                    sessions.push(session);
-                }
-            }
-        }
-
-        Ok(sessions)
+        Ok(sessions
+            .iter()
+            .flatten()
+            .filter_map(|data| serde_json::from_str::<SessionInfo>(data).ok())
+            .collect())
     }
 
-    async fn get_user_sessions(
+    async fn get_user_sessions_page(
         &self,
         user_id: &str,
-    ) -> Result<Vec<SessionInfo>, SessionStoreError> {
-        let mut conn = self.pool.get_connection().await.map_err(|e| {
-            SessionStoreError::RedisError(format!("Failed to get connection: {}", e))
-        })?;
-
-        // Get all session keys using SCAN (non-blocking)
-        let pattern = format!("{}:conn:*", self.config.session_prefix);
-        let keys = self.scan_keys(&mut conn, &pattern).await?;
-
-        if keys.is_empty() {
-            return Ok(vec![]);
-        }
-
-        // Filter sessions by user_id
-        let mut sessions = Vec::new();
-        for key in keys {
-            let json: Option<String> = conn
-                .get(&key)
-                .await
-                .map_err(|e| SessionStoreError::RedisError(e.to_string()))?;
-            if let Some(data) = json {
-                if let Ok(session) = serde_json::from_str::<SessionInfo>(&data) {
-                    if session.user_id == user_id {
-                        sessions.push(session);
-                    }
+        cursor: u64,
+        page_size: usize,
+    ) -> Result<(Vec<SessionInfo>, u64), SessionStoreError> {
+        let key = self.user_sessions_key(user_id);
+        let prefix = self.config.session_prefix.clone();
+
+        // SSCAN the user's connection IDs, then fetch their sessions in one MGET
+        let (next_cursor, sessions): (u64, Vec<Option<String>>) = self
+            .pool
+            .execute(OperationType::Read, |mut conn| async move {
+                let (next_cursor, connection_ids): (u64, Vec<String>) = redis::cmd("SSCAN")
+                    .arg(&key)
+                    .arg(cursor)
+                    .arg("COUNT")
+                    .arg(page_size.max(1))
+                    .query_async(&mut conn)
+                    .await?;
+                if connection_ids.is_empty() {
+                    return Ok((next_cursor, vec![]));
                 }
-            }
-        }
 
-        Ok(sessions)
+                let session_keys: Vec<String> = connection_ids
+                    .iter()
+                    .map(|id| format!("{}:conn:{}", prefix, id))
+                    .collect();
+                let sessions = redis::cmd("MGET")
+                    .arg(&session_keys)
+                    .query_async(&mut conn)
+                    .await?;
+                Ok((next_cursor, sessions))
+            })
+            .await
+            .map_err(store_error)?;
+
+        // Connection IDs whose session expired are skipped
+        let sessions = sessions
+            .iter()
+            .flatten()
+            .filter_map(|data| serde_json::from_str::<SessionInfo>(data).ok())
+            .collect();
+        Ok((sessions, next_cursor))
     }
 }
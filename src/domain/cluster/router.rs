@@ -2,15 +2,33 @@
 //!
 //! This module handles routing notifications to users connected to other
 //! server instances in a distributed deployment.
+//!
+//! `ClusterRouter::route_to_user` follows the configured [`RouteStrategy`]:
+//!
+//! - `LocalFirst` (default): deliver to the user's connections on this server, and only
+//!   look up the session store when there are none. Avoids a Redis round trip for
+//!   local users, but a user connected both here and elsewhere is only reached here.
+//! - `Replicate`: deliver locally and also route to every other server the user is
+//!   connected to. Reaches every connection at the cost of a session store lookup
+//!   per notification.
+//! - `RemoteOnly`: skip local delivery and always route through the session store.
+//!   Suits instances that accept API traffic but hold no client connections; users
+//!   connected to this server are not delivered to.
+//!
+//! `ClusterRouter::route_preferred` instead picks a single server hosting the user,
+//! preferring the one whose `cluster.server_tags` best match the caller's preferred
+//! tags (e.g. the same `region`), to keep delivery within one AZ or region.
 
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 
 use futures::StreamExt;
 use tokio::sync::broadcast;
 
-use crate::cluster::{ClusterConfig, RoutedMessage, SessionStore, SessionStoreError};
-use crate::connection_manager::ConnectionManager;
+use crate::cluster::{ClusterConfig, RouteStrategy, RoutedMessage, SessionStore, SessionStoreError};
+use crate::auth::tenant_scoped_key;
+use crate::connection_manager::{ConnectionHandle, ConnectionManager};
 use crate::metrics::ClusterMetrics;
 use crate::redis::pool::RedisPool;
 use crate::websocket::{OutboundMessage, ServerMessage};
@@ -19,6 +37,7 @@ use crate::websocket::{OutboundMessage, ServerMessage};
 pub struct ClusterRouter {
     connection_manager: Arc<ConnectionManager>,
     session_store: Arc<dyn SessionStore>,
+    route_strategy: RouteStrategy,
 }
 
 impl ClusterRouter {
@@ -29,95 +48,70 @@ impl ClusterRouter {
         Self {
             connection_manager,
             session_store,
+            route_strategy: RouteStrategy::default(),
         }
     }
 
-    /// Check if a user is connected locally (filtered by tenant)
+    /// Choose how user notifications are split between local and remote delivery
+    pub fn with_route_strategy(mut self, route_strategy: RouteStrategy) -> Self {
+        self.route_strategy = route_strategy;
+        self
+    }
+
+    /// Check if a user is connected locally within the tenant
     pub fn is_user_local(&self, user_id: &str, tenant_id: &str) -> bool {
+        !self.tenant_user_connections(user_id, tenant_id).is_empty()
+    }
+
+    /// The user's connections on this server within the tenant. Filters on the
+    /// tenant too, since a default-tenant user ID containing ':' can share a key
+    /// with another tenant's user.
+    fn tenant_user_connections(&self, user_id: &str, tenant_id: &str) -> Vec<Arc<ConnectionHandle>> {
         self.connection_manager
-            .get_user_connections(user_id)
-            .iter()
-            .any(|c| c.tenant_id == tenant_id)
+            .get_user_connections(&tenant_scoped_key(tenant_id, user_id))
+            .into_iter()
+            .filter(|c| c.tenant_id == tenant_id)
+            .collect()
     }
 
-    /// Route a message to a user across the cluster
+    /// Route a message to a user across the cluster according to the route strategy
     /// Returns the number of connections that received the message locally
-    /// and whether the message was also routed to other servers
+    /// and the number of other servers the message was routed to
+    #[tracing::instrument(
+        name = "cluster.route_to_user",
+        skip(self, message),
+        fields(correlation_id = tracing::field::Empty)
+    )]
     pub async fn route_to_user(
         &self,
         user_id: &str,
         tenant_id: &str,
         message: ServerMessage,
     ) -> Result<RouteResult, SessionStoreError> {
-        // First, try local delivery (filtered by tenant)
-        let local_connections: Vec<_> = self
-            .connection_manager
-            .get_user_connections(user_id)
-            .into_iter()
-            .filter(|c| c.tenant_id == tenant_id)
-            .collect();
-
-        let mut local_delivered = 0;
-        if !local_connections.is_empty() {
-            let outbound = OutboundMessage::Raw(message.clone());
-            for conn in local_connections {
-                if conn.send_preserialized(outbound.clone()).await.is_ok() {
-                    local_delivered += 1;
-                }
+        if let ServerMessage::Notification { event } = &message {
+            if let Some(correlation_id) = event.correlation_id() {
+                tracing::Span::current().record("correlation_id", correlation_id);
             }
         }
+        ClusterMetrics::record_route_strategy(self.route_strategy.as_str());
 
-        // If cluster mode is enabled, check if user might be on other servers
-        let routed_to_servers = if self.session_store.is_enabled() {
-            match self.session_store.find_user_servers(user_id).await {
-                Ok(servers) => {
-                    let other_servers: Vec<_> = servers
-                        .into_iter()
-                        .filter(|s| s != self.session_store.server_id())
-                        .collect();
-
-                    if !other_servers.is_empty() {
-                        // Route to other servers
-                        let payload = serde_json::to_string(&message)
-                            .map_err(|e| SessionStoreError::SerializationError(e.to_string()))?;
-
-                        for target_server in &other_servers {
-                            let routed_msg = RoutedMessage {
-                                user_id: user_id.to_string(),
-                                tenant_id: tenant_id.to_string(),
-                                connection_id: None,
-                                payload: payload.clone(),
-                                from_server: self.session_store.server_id().to_string(),
-                                to_server: Some(target_server.clone()),
-                            };
-
-                            if let Err(e) = self.session_store.publish_routed_message(&routed_msg).await {
-                                tracing::warn!(
-                                    error = %e,
-                                    target_server = %target_server,
-                                    user_id = %user_id,
-                                    "Failed to route message to server"
-                                );
-                            } else {
-                                ClusterMetrics::record_message_routed();
-                            }
-                        }
-                        other_servers.len()
-                    } else {
-                        0
-                    }
-                }
-                Err(e) => {
-                    tracing::warn!(
-                        error = %e,
-                        user_id = %user_id,
-                        "Failed to find user servers for routing"
-                    );
+        let (local_delivered, routed_to_servers) = match self.route_strategy {
+            RouteStrategy::LocalFirst => {
+                let (found, delivered) = self.deliver_locally(user_id, tenant_id, &message).await;
+                let routed = if found == 0 {
+                    self.route_remotely(user_id, tenant_id, &message).await?
+                } else {
                     0
-                }
+                };
+                (delivered, routed)
+            }
+            RouteStrategy::RemoteOnly => {
+                (0, self.route_remotely(user_id, tenant_id, &message).await?)
+            }
+            RouteStrategy::Replicate => {
+                let (_, delivered) = self.deliver_locally(user_id, tenant_id, &message).await;
+                (delivered, self.route_remotely(user_id, tenant_id, &message).await?)
             }
-        } else {
-            0
         };
 
         Ok(RouteResult {
@@ -126,6 +120,173 @@ impl ClusterRouter {
         })
     }
 
+    /// Send to the user's connections on this server within the tenant
+    /// Returns how many connections were found and how many received the message
+    async fn deliver_locally(
+        &self,
+        user_id: &str,
+        tenant_id: &str,
+        message: &ServerMessage,
+    ) -> (usize, usize) {
+        let local_connections = self.tenant_user_connections(user_id, tenant_id);
+
+        let mut delivered = 0;
+        let outbound = OutboundMessage::Raw(message.clone());
+        for conn in &local_connections {
+            if conn.send_preserialized(outbound.clone()).await.is_ok() {
+                delivered += 1;
+            }
+        }
+        (local_connections.len(), delivered)
+    }
+
+    /// Publish the message to every other server the session store lists for the user
+    /// Returns the number of servers it was routed to (0 when cluster mode is disabled)
+    async fn route_remotely(
+        &self,
+        user_id: &str,
+        tenant_id: &str,
+        message: &ServerMessage,
+    ) -> Result<usize, SessionStoreError> {
+        if !self.session_store.is_enabled() {
+            return Ok(0);
+        }
+
+        let other_servers: Vec<_> = match self.session_store.find_user_servers(user_id).await {
+            Ok(servers) => servers
+                .into_iter()
+                .filter(|s| s != self.session_store.server_id())
+                .collect(),
+            Err(e) => {
+                tracing::warn!(
+                    error = %e,
+                    user_id = %user_id,
+                    "Failed to find user servers for routing"
+                );
+                return Ok(0);
+            }
+        };
+        if other_servers.is_empty() {
+            return Ok(0);
+        }
+
+        let payload = serde_json::to_string(message)
+            .map_err(|e| SessionStoreError::SerializationError(e.to_string()))?;
+
+        for target_server in &other_servers {
+            self.publish_to_server(user_id, tenant_id, &payload, target_server)
+                .await;
+        }
+        Ok(other_servers.len())
+    }
+
+    /// Publish a serialized message for the user to one other server
+    async fn publish_to_server(
+        &self,
+        user_id: &str,
+        tenant_id: &str,
+        payload: &str,
+        target_server: &str,
+    ) {
+        let routed_msg = RoutedMessage {
+            user_id: user_id.to_string(),
+            tenant_id: tenant_id.to_string(),
+            connection_id: None,
+            payload: payload.to_string(),
+            from_server: self.session_store.server_id().to_string(),
+            to_server: Some(target_server.to_string()),
+            signature: String::new(),
+        };
+
+        if let Err(e) = self.session_store.publish_routed_message(&routed_msg).await {
+            tracing::warn!(
+                error = %e,
+                target_server = %target_server,
+                user_id = %user_id,
+                "Failed to route message to server"
+            );
+        } else {
+            ClusterMetrics::record_message_routed();
+        }
+    }
+
+    /// Route a message to the one server hosting the user whose tags best match
+    /// `preferred_tags` (one point per matching key/value pair).
+    ///
+    /// Ties go to this server, then to the lowest server ID. When no server matches
+    /// any preferred tag the message still goes to an available server (counted as a
+    /// fallback). Without cluster mode, or when the user's sessions cannot be looked
+    /// up, this behaves like [`route_to_user`](Self::route_to_user).
+    pub async fn route_preferred(
+        &self,
+        user_id: &str,
+        tenant_id: &str,
+        preferred_tags: &HashMap<String, String>,
+        message: ServerMessage,
+    ) -> Result<RouteResult, SessionStoreError> {
+        if !self.session_store.is_enabled() {
+            return self.route_to_user(user_id, tenant_id, message).await;
+        }
+
+        let sessions = match self.session_store.get_user_sessions(user_id).await {
+            Ok(sessions) => sessions,
+            Err(e) => {
+                tracing::warn!(
+                    error = %e,
+                    user_id = %user_id,
+                    "Failed to look up user sessions for preferred routing"
+                );
+                return self.route_to_user(user_id, tenant_id, message).await;
+            }
+        };
+
+        let local_server = self.session_store.server_id();
+        let mut candidates: Vec<(&str, usize)> = sessions
+            .iter()
+            .filter(|session| session.tenant_id == tenant_id)
+            .map(|session| {
+                let score = preferred_tags
+                    .iter()
+                    .filter(|(key, value)| session.server_tags.get(*key) == Some(*value))
+                    .count();
+                (session.server_id.as_str(), score)
+            })
+            .collect();
+        candidates.sort_unstable();
+        candidates.dedup();
+
+        let best = candidates.into_iter().max_by(|(a, a_score), (b, b_score)| {
+            a_score
+                .cmp(b_score)
+                .then_with(|| (*a == local_server).cmp(&(*b == local_server)))
+                .then_with(|| b.cmp(a))
+        });
+        let Some((target_server, score)) = best else {
+            return Ok(RouteResult {
+                local_delivered: 0,
+                routed_to_servers: 0,
+            });
+        };
+        ClusterMetrics::record_preferred_route(score > 0);
+
+        if target_server == local_server {
+            let (_, delivered) = self.deliver_locally(user_id, tenant_id, &message).await;
+            return Ok(RouteResult {
+                local_delivered: delivered,
+                routed_to_servers: 0,
+            });
+        }
+
+        let payload = serde_json::to_string(&message)
+            .map_err(|e| SessionStoreError::SerializationError(e.to_string()))?;
+        self.publish_to_server(user_id, tenant_id, &payload, target_server)
+            .await;
+        Ok(RouteResult {
+            local_delivered: 0,
+            routed_to_servers: 1,
+        })
+    }
+
     /// Handle a routed message received from another server
     pub async fn handle_routed_message(&self, message: RoutedMessage) -> usize {
         // Only process if targeted to this server or broadcast
@@ -150,13 +311,8 @@ impl ClusterRouter {
             }
         };
 
-        // Deliver locally (filtered by tenant)
-        let connections: Vec<_> = self
-            .connection_manager
-            .get_user_connections(&message.user_id)
-            .into_iter()
-            .filter(|c| c.tenant_id == message.tenant_id)
-            .collect();
+        // Deliver locally within the message's tenant
+        let connections = self.tenant_user_connections(&message.user_id, &message.tenant_id);
         let mut delivered = 0;
 
         let outbound = OutboundMessage::Raw(server_message);
@@ -341,6 +497,18 @@ impl RoutedMessageSubscriber {
             }
         }
 
+        // Drop anything not signed with the shared cluster secret
+        if !message.verify_signature(&self.config.cluster_secret) {
+            ClusterMetrics::record_signature_failed();
+            tracing::warn!(
+                from_server = %message.from_server,
+                user_id = %message.user_id,
+                channel = %channel,
+                "Dropping routed message with invalid signature"
+            );
+            return;
+        }
+
         tracing::debug!(
             from_server = %message.from_server,
             user_id = %message.user_id,
@@ -362,7 +530,11 @@ impl RoutedMessageSubscriber {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::cluster::{create_session_store, ClusterConfig, RoutedMessage};
+    use uuid::Uuid;
+
+    use crate::cluster::{
+        create_session_store, ClusterConfig, RoutedMessage, SessionInfo, SessionStoreBackend,
+    };
 
     fn create_test_components() -> (Arc<ConnectionManager>, Arc<dyn SessionStore>) {
         let connection_manager = Arc::new(ConnectionManager::new());
@@ -389,6 +561,20 @@ mod tests {
         assert!(!router.is_user_local("nonexistent-user", "default"));
     }
 
+    #[tokio::test]
+    async fn test_is_user_local_ignores_colliding_default_tenant_user() {
+        let (connection_manager, session_store) = create_test_components();
+        let (tx, _rx) = tokio::sync::mpsc::channel(8);
+        // Keyed "acme:1", the same key as tenant acme's user "1"
+        connection_manager
+            .register("acme:1".to_string(), "default".to_string(), vec![], tx)
+            .unwrap();
+        let router = ClusterRouter::new(connection_manager, session_store);
+
+        assert!(router.is_user_local("acme:1", "default"));
+        assert!(!router.is_user_local("1", "acme"));
+    }
+
     #[tokio::test]
     async fn test_route_to_user_no_connections() {
         let (connection_manager, session_store) = create_test_components();
@@ -416,6 +602,7 @@ mod tests {
             payload: r#"{"type":"Heartbeat"}"#.to_string(),
             from_server: "other-server".to_string(),
             to_server: None,
+            signature: String::new(),
         };
 
         let delivered = router.handle_routed_message(message).await;
@@ -434,6 +621,7 @@ mod tests {
             payload: r#"{"type":"Heartbeat"}"#.to_string(),
             from_server: "other-server".to_string(),
             to_server: Some("different-server".to_string()), // Not our server
+            signature: String::new(),
         };
 
         // Should return 0 because message is not for this server
@@ -450,6 +638,7 @@ mod tests {
             payload: r#"{"type":"notification"}"#.to_string(),
             from_server: "server1".to_string(),
             to_server: Some("server2".to_string()),
+            signature: String::new(),
         };
 
         let json = serde_json::to_string(&message).unwrap();
@@ -460,6 +649,381 @@ mod tests {
         assert_eq!(parsed.to_server, message.to_server);
     }
 
+    /// Cluster-enabled store that reports `servers` for every user and records publishes
+    struct StubClusterStore {
+        servers: Vec<String>,
+        server_tags: HashMap<String, HashMap<String, String>>,
+        published: std::sync::Mutex<Vec<RoutedMessage>>,
+    }
+
+    impl StubClusterStore {
+        fn new(servers: &[&str]) -> Arc<Self> {
+            Self::tagged(&servers.iter().map(|s| (*s, &[][..])).collect::<Vec<_>>())
+        }
+
+        /// Store whose servers carry the given topology tags
+        fn tagged(servers: &[(&str, &[(&str, &str)])]) -> Arc<Self> {
+            Arc::new(Self {
+                servers: servers.iter().map(|(s, _)| s.to_string()).collect(),
+                server_tags: servers
+                    .iter()
+                    .map(|(server, tags)| {
+                        let tags = tags
+                            .iter()
+                            .map(|(k, v)| (k.to_string(), v.to_string()))
+                            .collect();
+                        (server.to_string(), tags)
+                    })
+                    .collect(),
+                published: std::sync::Mutex::new(Vec::new()),
+            })
+        }
+
+        fn published_to(&self) -> Vec<String> {
+            let published = self.published.lock().unwrap();
+            published.iter().filter_map(|m| m.to_server.clone()).collect()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl SessionStore for StubClusterStore {
+        fn server_id(&self) -> &str {
+            "server-a"
+        }
+
+        fn is_enabled(&self) -> bool {
+            true
+        }
+
+        fn backend_type(&self) -> SessionStoreBackend {
+            SessionStoreBackend::Redis
+        }
+
+        async fn register_session(&self, _: &SessionInfo) -> Result<(), SessionStoreError> {
+            Ok(())
+        }
+
+        async fn unregister_session(&self, _: Uuid) -> Result<(), SessionStoreError> {
+            Ok(())
+        }
+
+        async fn update_session_channels(
+            &self,
+            _: Uuid,
+            _: Vec<String>,
+        ) -> Result<(), SessionStoreError> {
+            Ok(())
+        }
+
+        async fn refresh_sessions(&self) -> Result<usize, SessionStoreError> {
+            Ok(0)
+        }
+
+        async fn find_user_servers(&self, _: &str) -> Result<Vec<String>, SessionStoreError> {
+            Ok(self.servers.clone())
+        }
+
+        async fn find_channel_servers(&self, _: &str) -> Result<Vec<String>, SessionStoreError> {
+            Ok(self.servers.clone())
+        }
+
+        async fn publish_routed_message(
+            &self,
+            message: &RoutedMessage,
+        ) -> Result<(), SessionStoreError> {
+            self.published.lock().unwrap().push(message.clone());
+            Ok(())
+        }
+
+        async fn cluster_connection_count(&self) -> Result<usize, SessionStoreError> {
+            Ok(0)
+        }
+
+        async fn cluster_user_count(&self) -> Result<usize, SessionStoreError> {
+            Ok(0)
+        }
+
+        async fn try_acquire_metrics_leader(&self, _: u64) -> Result<bool, SessionStoreError> {
+            Ok(true)
+        }
+
+        async fn get_all_sessions(&self) -> Result<Vec<SessionInfo>, SessionStoreError> {
+            Ok(vec![])
+        }
+
+        async fn get_user_sessions_page(
+            &self,
+            user_id: &str,
+            _: u64,
+            _: usize,
+        ) -> Result<(Vec<SessionInfo>, u64), SessionStoreError> {
+            let sessions = self
+                .servers
+                .iter()
+                .map(|server| SessionInfo {
+                    connection_id: Uuid::new_v4(),
+                    user_id: user_id.to_string(),
+                    tenant_id: "default".to_string(),
+                    server_id: server.clone(),
+                    connected_at: 0,
+                    channels: vec![],
+                    server_tags: self.server_tags[server].clone(),
+                })
+                .collect();
+            Ok((sessions, 0))
+        }
+    }
+
+    /// Router on "server-a" where user-1 is connected locally and also on server-b
+    fn strategy_router(
+        strategy: RouteStrategy,
+    ) -> (ClusterRouter, Arc<StubClusterStore>, tokio::sync::mpsc::Receiver<OutboundMessage>) {
+        let connection_manager = Arc::new(ConnectionManager::new());
+        let (tx, rx) = tokio::sync::mpsc::channel(8);
+        connection_manager
+            .register("user-1".to_string(), "default".to_string(), vec![], tx)
+            .unwrap();
+        let store = StubClusterStore::new(&["server-a", "server-b"]);
+        let router = ClusterRouter::new(connection_manager, store.clone())
+            .with_route_strategy(strategy);
+        (router, store, rx)
+    }
+
+    #[tokio::test]
+    async fn test_local_first_skips_remote_for_local_user() {
+        let (router, store, mut rx) = strategy_router(RouteStrategy::LocalFirst);
+
+        let result = router
+            .route_to_user("user-1", "default", ServerMessage::Heartbeat)
+            .await
+            .unwrap();
+
+        assert_eq!(result.local_delivered, 1);
+        assert_eq!(result.routed_to_servers, 0);
+        assert!(rx.try_recv().is_ok());
+        assert!(store.published_to().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_local_first_routes_remotely_without_local_connections() {
+        let (router, store, _rx) = strategy_router(RouteStrategy::LocalFirst);
+
+        let result = router
+            .route_to_user("user-2", "default", ServerMessage::Heartbeat)
+            .await
+            .unwrap();
+
+        assert_eq!(result.local_delivered, 0);
+        assert_eq!(result.routed_to_servers, 1);
+        assert_eq!(store.published_to(), vec!["server-b".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_remote_only_skips_local_delivery() {
+        let (router, store, mut rx) = strategy_router(RouteStrategy::RemoteOnly);
+
+        let result = router
+            .route_to_user("user-1", "default", ServerMessage::Heartbeat)
+            .await
+            .unwrap();
+
+        assert_eq!(result.local_delivered, 0);
+        assert_eq!(result.routed_to_servers, 1);
+        assert!(rx.try_recv().is_err());
+        assert_eq!(store.published_to(), vec!["server-b".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_replicate_delivers_locally_and_remotely() {
+        let (router, store, mut rx) = strategy_router(RouteStrategy::Replicate);
+        let before = crate::metrics::CLUSTER_ROUTE_STRATEGY_USED
+            .with_label_values(&["replicate"])
+            .get();
+
+        let result = router
+            .route_to_user("user-1", "default", ServerMessage::Heartbeat)
+            .await
+            .unwrap();
+
+        assert_eq!(result.local_delivered, 1);
+        assert_eq!(result.routed_to_servers, 1);
+        assert!(rx.try_recv().is_ok());
+        assert_eq!(store.published_to(), vec!["server-b".to_string()]);
+        assert!(
+            crate::metrics::CLUSTER_ROUTE_STRATEGY_USED
+                .with_label_values(&["replicate"])
+                .get()
+                > before
+        );
+    }
+
+    fn tags(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_route_preferred_picks_best_matching_server() {
+        let store = StubClusterStore::tagged(&[
+            ("server-a", &[("region", "eu-west-1"), ("az", "eu-west-1a")]),
+            ("server-b", &[("region", "us-east-1"), ("az", "us-east-1b")]),
+            ("server-c", &[("region", "us-east-1"), ("az", "us-east-1a")]),
+        ]);
+        let router = ClusterRouter::new(Arc::new(ConnectionManager::new()), store.clone());
+        let matched_before = crate::metrics::CLUSTER_ROUTING_PREFERRED_MATCH_TOTAL.get();
+
+        // Both us-east-1 servers match the region; server-c also matches the AZ
+        let result = router
+            .route_preferred(
+                "user-1",
+                "default",
+                &tags(&[("region", "us-east-1"), ("az", "us-east-1a")]),
+                ServerMessage::Heartbeat,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(result.routed_to_servers, 1);
+        assert_eq!(store.published_to(), vec!["server-c".to_string()]);
+        assert!(crate::metrics::CLUSTER_ROUTING_PREFERRED_MATCH_TOTAL.get() > matched_before);
+    }
+
+    #[tokio::test]
+    async fn test_route_preferred_prefers_local_on_tie() {
+        let connection_manager = Arc::new(ConnectionManager::new());
+        let (tx, mut rx) = tokio::sync::mpsc::channel(8);
+        connection_manager
+            .register("user-1".to_string(), "default".to_string(), vec![], tx)
+            .unwrap();
+        let store = StubClusterStore::tagged(&[
+            ("server-a", &[("region", "us-east-1")]),
+            ("server-b", &[("region", "us-east-1")]),
+        ]);
+        let router = ClusterRouter::new(connection_manager, store.clone());
+
+        let result = router
+            .route_preferred(
+                "user-1",
+                "default",
+                &tags(&[("region", "us-east-1")]),
+                ServerMessage::Heartbeat,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(result.local_delivered, 1);
+        assert_eq!(result.routed_to_servers, 0);
+        assert!(rx.try_recv().is_ok());
+        assert!(store.published_to().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_route_preferred_falls_back_without_match() {
+        let store = StubClusterStore::tagged(&[
+            ("server-c", &[("region", "eu-west-1")]),
+            ("server-b", &[]),
+        ]);
+        let router = ClusterRouter::new(Arc::new(ConnectionManager::new()), store.clone());
+        let fallback_before = crate::metrics::CLUSTER_ROUTING_FALLBACK_TOTAL.get();
+
+        let result = router
+            .route_preferred(
+                "user-1",
+                "default",
+                &tags(&[("region", "ap-south-1")]),
+                ServerMessage::Heartbeat,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(result.routed_to_servers, 1);
+        assert_eq!(store.published_to(), vec!["server-b".to_string()]);
+        assert!(crate::metrics::CLUSTER_ROUTING_FALLBACK_TOTAL.get() > fallback_before);
+
+        // A user on another tenant has no candidate servers
+        let result = router
+            .route_preferred("user-1", "other", &HashMap::new(), ServerMessage::Heartbeat)
+            .await
+            .unwrap();
+        assert_eq!(result.routed_to_servers, 0);
+    }
+
+    #[test]
+    fn test_server_tags_deserialize_from_map_or_string() {
+        let config: ClusterConfig =
+            serde_json::from_str(r#"{"server_tags": {"az": "us-east-1a"}}"#).unwrap();
+        assert_eq!(config.server_tags, tags(&[("az", "us-east-1a")]));
+
+        let config: ClusterConfig =
+            serde_json::from_str(r#"{"server_tags": "az=us-east-1a, region=us-east-1"}"#).unwrap();
+        assert_eq!(config.server_tags, tags(&[("az", "us-east-1a"), ("region", "us-east-1")]));
+
+        assert!(serde_json::from_str::<ClusterConfig>(r#"{"server_tags": "us-east-1a"}"#).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_subscriber_drops_unsigned_messages() {
+        use crate::config::RedisConfig;
+        use crate::redis::{BulkheadCircuitBreaker, RedisHealth};
+
+        let connection_manager = Arc::new(ConnectionManager::new());
+        let (tx, mut rx) = tokio::sync::mpsc::channel(8);
+        connection_manager
+            .register("user-1".to_string(), "tenant-1".to_string(), vec![], tx)
+            .unwrap();
+        let config = ClusterConfig {
+            enabled: true,
+            server_id: "server-a".to_string(),
+            cluster_secret: "shared-secret".to_string(),
+            ..Default::default()
+        };
+        let router = Arc::new(ClusterRouter::new(
+            connection_manager,
+            create_session_store(&ClusterConfig::default(), None),
+        ));
+        let redis_pool = Arc::new(
+            RedisPool::new(
+                RedisConfig::default(),
+                Arc::new(BulkheadCircuitBreaker::default()),
+                Arc::new(RedisHealth::new()),
+            )
+            .unwrap(),
+        );
+        let (_shutdown_tx, shutdown_rx) = broadcast::channel(1);
+        let subscriber = RoutedMessageSubscriber::new(config, redis_pool, router, shutdown_rx);
+
+        let mut message = RoutedMessage {
+            user_id: "user-1".to_string(),
+            tenant_id: "tenant-1".to_string(),
+            connection_id: None,
+            payload: serde_json::to_string(&ServerMessage::Heartbeat).unwrap(),
+            from_server: "server-b".to_string(),
+            to_server: None,
+            signature: String::new(),
+        };
+        let failed_before = crate::metrics::CLUSTER_MESSAGES_SIGNATURE_FAILED_TOTAL.get();
+
+        message.sign("attacker-secret");
+        let payload = serde_json::to_string(&message).unwrap();
+        subscriber.handle_routed_message("route", &payload).await;
+        assert!(rx.try_recv().is_err());
+        assert!(crate::metrics::CLUSTER_MESSAGES_SIGNATURE_FAILED_TOTAL.get() > failed_before);
+
+        message.sign("shared-secret");
+        let payload = serde_json::to_string(&message).unwrap();
+        subscriber.handle_routed_message("route", &payload).await;
+        assert!(rx.try_recv().is_ok());
+    }
+
+    #[test]
+    fn test_route_strategy_deserialize() {
+        let strategy: RouteStrategy = serde_json::from_str(r#""remote_only""#).unwrap();
+        assert_eq!(strategy, RouteStrategy::RemoteOnly);
+        assert_eq!(RouteStrategy::default(), RouteStrategy::LocalFirst);
+    }
+
     #[test]
     fn test_cluster_config_defaults() {
         let config = ClusterConfig::default();
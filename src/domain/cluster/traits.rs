@@ -5,6 +5,9 @@ use uuid::Uuid;
 
 use super::types::{RoutedMessage, SessionInfo, SessionStoreBackend, SessionStoreError};
 
+/// Page size used when `get_user_sessions` walks every page
+const USER_SESSIONS_PAGE_SIZE: usize = 100;
+
 /// Trait for distributed session tracking
 #[async_trait]
 pub trait SessionStore: Send + Sync {
@@ -49,12 +52,40 @@ pub trait SessionStore: Send + Sync {
     /// Get cluster-wide user count
     async fn cluster_user_count(&self) -> Result<usize, SessionStoreError>;
 
+    /// Claim or renew the cluster metrics leadership for `ttl_seconds`.
+    /// Returns whether this server is the leader afterwards.
+    async fn try_acquire_metrics_leader(&self, ttl_seconds: u64)
+        -> Result<bool, SessionStoreError>;
+
     /// Get all sessions across the cluster
     async fn get_all_sessions(&self) -> Result<Vec<SessionInfo>, SessionStoreError>;
 
-    /// Get all sessions for a specific user
+    /// Get one page of a user's sessions starting at `cursor` (0 for the first page).
+    /// Returns the sessions and the cursor of the next page, 0 once exhausted.
+    /// `page_size` is a hint: a page may hold more or fewer sessions.
+    async fn get_user_sessions_page(
+        &self,
+        user_id: &str,
+        cursor: u64,
+        page_size: usize,
+    ) -> Result<(Vec<SessionInfo>, u64), SessionStoreError>;
+
+    /// Get all sessions for a specific user, following pages until exhausted
     async fn get_user_sessions(
         &self,
         user_id: &str,
-    ) -> Result<Vec<SessionInfo>, SessionStoreError>;
+    ) -> Result<Vec<SessionInfo>, SessionStoreError> {
+        let mut sessions = Vec::new();
+        let mut cursor = 0;
+        loop {
+            let (page, next_cursor) = self
+                .get_user_sessions_page(user_id, cursor, USER_SESSIONS_PAGE_SIZE)
+                .await?;
+            sessions.extend(page);
+            if next_cursor == 0 {
+                return Ok(sessions);
+            }
+            cursor = next_cursor;
+        }
+    }
 }
@@ -1,10 +1,12 @@
 //! Cluster-related types and configuration
 
-use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use serde::{Deserialize, Deserializer, Serialize};
 use uuid::Uuid;
 
 /// Configuration for cluster mode
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Clone, Deserialize)]
 pub struct ClusterConfig {
     /// Whether cluster mode is enabled
     #[serde(default)]
@@ -21,6 +23,87 @@ pub struct ClusterConfig {
     /// Channel for routing messages between instances
     #[serde(default = "default_routing_channel")]
     pub routing_channel: String,
+    /// How `ClusterRouter::route_to_user` splits delivery between local and remote servers
+    #[serde(default)]
+    pub route_strategy: RouteStrategy,
+    /// Shared secret signing routed messages (required when cluster mode is enabled)
+    #[serde(default)]
+    pub cluster_secret: String,
+    /// Topology tags of this server (e.g. `az`, `region`), used by
+    /// `ClusterRouter::route_preferred`. Accepts a map or `key=value,key=value`.
+    #[serde(default, deserialize_with = "deserialize_server_tags")]
+    pub server_tags: HashMap<String, String>,
+}
+
+/// Deserialize server tags from a map or a `key=value,key=value` string (env vars)
+fn deserialize_server_tags<'de, D>(deserializer: D) -> Result<HashMap<String, String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Tags {
+        Map(HashMap<String, String>),
+        Text(String),
+    }
+
+    match Option::<Tags>::deserialize(deserializer)? {
+        None => Ok(HashMap::new()),
+        Some(Tags::Map(tags)) => Ok(tags),
+        Some(Tags::Text(text)) => text
+            .split(',')
+            .map(str::trim)
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| match pair.split_once('=') {
+                Some((key, value)) if !key.trim().is_empty() => {
+                    Ok((key.trim().to_string(), value.trim().to_string()))
+                }
+                _ => Err(serde::de::Error::custom(format!(
+                    "invalid server tag '{}', expected key=value",
+                    pair
+                ))),
+            })
+            .collect(),
+    }
+}
+
+impl std::fmt::Debug for ClusterConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ClusterConfig")
+            .field("enabled", &self.enabled)
+            .field("server_id", &self.server_id)
+            .field("session_prefix", &self.session_prefix)
+            .field("session_ttl_seconds", &self.session_ttl_seconds)
+            .field("routing_channel", &self.routing_channel)
+            .field("route_strategy", &self.route_strategy)
+            .field("cluster_secret", &"[REDACTED]")
+            .field("server_tags", &self.server_tags)
+            .finish()
+    }
+}
+
+/// Strategy for delivering a user notification in cluster mode
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RouteStrategy {
+    /// Deliver locally; route remotely only if the user has no local connections
+    #[default]
+    LocalFirst,
+    /// Never deliver locally; always route to the servers in the session store
+    RemoteOnly,
+    /// Deliver locally and route to every other server the user is connected to
+    Replicate,
+}
+
+impl RouteStrategy {
+    /// Label used in metrics and logs
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::LocalFirst => "local_first",
+            Self::RemoteOnly => "remote_only",
+            Self::Replicate => "replicate",
+        }
+    }
 }
 
 fn default_server_id() -> String {
@@ -48,6 +131,9 @@ impl Default for ClusterConfig {
             session_prefix: default_session_prefix(),
             session_ttl_seconds: default_session_ttl(),
             routing_channel: default_routing_channel(),
+            route_strategy: RouteStrategy::default(),
+            cluster_secret: String::new(),
+            server_tags: HashMap::new(),
         }
     }
 }
@@ -61,6 +147,9 @@ pub struct SessionInfo {
     pub server_id: String,
     pub connected_at: i64,
     pub channels: Vec<String>,
+    /// Topology tags of the server holding the connection
+    #[serde(default)]
+    pub server_tags: HashMap<String, String>,
 }
 
 /// Message to be routed to another server instance
@@ -78,6 +167,9 @@ pub struct RoutedMessage {
     pub from_server: String,
     /// Target server ID (if known)
     pub to_server: Option<String>,
+    /// Hex HMAC-SHA256 of the message under the shared cluster secret
+    #[serde(default)]
+    pub signature: String,
 }
 
 /// Error type for session store operations
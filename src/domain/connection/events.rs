@@ -0,0 +1,93 @@
+//! Connection lifecycle events published by the `ConnectionManager`
+//!
+//! Components that react to users coming and going (or to subscription changes)
+//! subscribe to the [`ConnectionEventBus`] instead of polling the manager.
+
+use serde::Serialize;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+use crate::metrics::CONNECTION_EVENTS_PUBLISHED_TOTAL;
+
+/// Events buffered per subscriber; slower subscribers miss the oldest events
+/// (`RecvError::Lagged`)
+pub const CONNECTION_EVENT_CAPACITY: usize = 1024;
+
+/// A change in connection or subscription state
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ConnectionEvent {
+    /// A connection was registered
+    UserConnected {
+        user_id: String,
+        connection_id: Uuid,
+        tenant_id: String,
+    },
+    /// A connection was unregistered. Its channel subscriptions end with it; no
+    /// `ChannelUnsubscribed` events are published for them.
+    UserDisconnected {
+        user_id: String,
+        connection_id: Uuid,
+        tenant_id: String,
+        /// Whether this was the user's last connection (the user is now offline)
+        last_connection: bool,
+    },
+    /// A connection subscribed to a channel
+    ChannelSubscribed { connection_id: Uuid, channel: String },
+    /// A connection unsubscribed from a channel
+    ChannelUnsubscribed { connection_id: Uuid, channel: String },
+}
+
+impl ConnectionEvent {
+    /// Metric label value
+    pub fn event_type(&self) -> &'static str {
+        match self {
+            ConnectionEvent::UserConnected { .. } => "user_connected",
+            ConnectionEvent::UserDisconnected { .. } => "user_disconnected",
+            ConnectionEvent::ChannelSubscribed { .. } => "channel_subscribed",
+            ConnectionEvent::ChannelUnsubscribed { .. } => "channel_unsubscribed",
+        }
+    }
+}
+
+/// Broadcast channel for [`ConnectionEvent`]s. Cloning shares the same channel.
+#[derive(Debug, Clone)]
+pub struct ConnectionEventBus {
+    sender: broadcast::Sender<ConnectionEvent>,
+}
+
+impl ConnectionEventBus {
+    pub fn new() -> Self {
+        Self::with_capacity(CONNECTION_EVENT_CAPACITY)
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity.max(1));
+        Self { sender }
+    }
+
+    /// Receive every event published from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<ConnectionEvent> {
+        self.sender.subscribe()
+    }
+
+    /// Publish `event` to current subscribers; never blocks, and is a no-op apart
+    /// from the metric when nobody is subscribed
+    pub fn publish(&self, event: ConnectionEvent) {
+        CONNECTION_EVENTS_PUBLISHED_TOTAL
+            .with_label_values(&[event.event_type()])
+            .inc();
+        let _ = self.sender.send(event);
+    }
+
+    /// Number of active subscribers
+    pub fn subscriber_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+}
+
+impl Default for ConnectionEventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
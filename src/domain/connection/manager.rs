@@ -1,20 +1,54 @@
 //! Core connection manager implementation
 
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use dashmap::DashMap;
 use smallvec::SmallVec;
 use std::collections::{HashMap, HashSet};
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 use tokio::sync::mpsc;
 use uuid::Uuid;
 
 /// Inline capacity for user connections (most users have 1-4 devices)
 type UserConnections = SmallVec<[Uuid; 4]>;
 
-use crate::websocket::OutboundMessage;
+/// Number of users reported in `ConnectionStats::top_n_users_by_connections`
+const TOP_USERS_LIMIT: usize = 10;
+
+use crate::metrics::{ChannelMetrics, CHANNELS_DELETED_TOTAL, CONNECTIONS_PER_USER};
+use crate::ratelimit::glob_match;
+use crate::websocket::{OutboundMessage, ServerMessage};
+
+use super::events::{ConnectionEvent, ConnectionEventBus};
+use super::stats::{
+    ChannelInfo, ConnectionManagerSnapshot, ConnectionSnapshot, ConnectionStats,
+    TenantConnectionStats, UserSubscriptionInfo,
+};
+use super::types::{
+    ConnectionAlert, ConnectionError, ConnectionHandle, ConnectionLimits, ConnectionMetadata,
+};
+
+/// Destination for per-user connection count alerts
+struct ConnectionAlertHook {
+    threshold: usize,
+    sender: mpsc::Sender<ConnectionAlert>,
+}
+
+/// Lifetime bookkeeping for a channel, kept until its last subscriber leaves
+struct ChannelMeta {
+    created_at: DateTime<Utc>,
+    peak_subscribers: usize,
+    total_subscriber_events: u64,
+}
 
-use super::stats::{ChannelInfo, ConnectionStats, TenantConnectionStats, UserSubscriptionInfo};
-use super::types::{ConnectionError, ConnectionHandle, ConnectionLimits};
+impl ChannelMeta {
+    fn new() -> Self {
+        Self {
+            created_at: Utc::now(),
+            peak_subscribers: 0,
+            total_subscriber_events: 0,
+        }
+    }
+}
 
 /// Manages all active WebSocket connections
 pub struct ConnectionManager {
@@ -24,10 +58,16 @@ pub struct ConnectionManager {
     pub(crate) user_index: DashMap<String, UserConnections>,
     /// channel_name -> Set<connection_id>
     pub(crate) channel_index: DashMap<String, HashSet<Uuid>>,
+    /// channel_name -> creation time and subscriber history
+    channel_meta: DashMap<String, ChannelMeta>,
     /// tenant_id -> Set<connection_id> (for multi-tenant support)
     pub(crate) tenant_index: DashMap<String, HashSet<Uuid>>,
     /// Connection limits
     pub(crate) limits: ConnectionLimits,
+    /// Optional alert hook for users exceeding a connection count threshold
+    connection_alert: RwLock<Option<ConnectionAlertHook>>,
+    /// Lifecycle events for registrations, unregistrations and subscription changes
+    events: ConnectionEventBus,
 }
 
 impl ConnectionManager {
@@ -40,8 +80,51 @@ impl ConnectionManager {
             connections: DashMap::new(),
             user_index: DashMap::new(),
             channel_index: DashMap::new(),
+            channel_meta: DashMap::new(),
             tenant_index: DashMap::new(),
             limits,
+            connection_alert: RwLock::new(None),
+            events: ConnectionEventBus::new(),
+        }
+    }
+
+    /// Bus on which this manager publishes connection lifecycle events
+    pub fn events(&self) -> &ConnectionEventBus {
+        &self.events
+    }
+
+    /// Send a `ConnectionAlert` on `sender` whenever a user's connection count exceeds
+    /// `threshold` after a registration. Replaces any previously configured hook.
+    pub fn set_connection_count_alert(
+        &self,
+        threshold: usize,
+        sender: mpsc::Sender<ConnectionAlert>,
+    ) {
+        let mut hook = self
+            .connection_alert
+            .write()
+            .unwrap_or_else(|e| e.into_inner());
+        *hook = Some(ConnectionAlertHook { threshold, sender });
+    }
+
+    /// Record a user's current connection count and raise an alert if it exceeds the threshold
+    fn record_user_connection_count(&self, user_id: &str, count: usize) {
+        CONNECTIONS_PER_USER.observe(count as f64);
+
+        let hook = self
+            .connection_alert
+            .read()
+            .unwrap_or_else(|e| e.into_inner());
+        if let Some(hook) = hook.as_ref().filter(|h| count > h.threshold) {
+            let alert = ConnectionAlert {
+                user_id: user_id.to_string(),
+                count,
+                timestamp: Utc::now(),
+            };
+            // Never block registration on a slow alert consumer
+            if hook.sender.try_send(alert).is_err() {
+                tracing::debug!(user_id = %user_id, count = count, "Connection alert dropped");
+            }
         }
     }
 
@@ -56,6 +139,18 @@ impl ConnectionManager {
         self.register_with_limits(user_id, tenant_id, roles, sender, &self.limits)
     }
 
+    /// Register a new connection carrying client preferences, with limit checking
+    pub fn register_with_metadata(
+        &self,
+        user_id: String,
+        tenant_id: String,
+        roles: Vec<String>,
+        sender: mpsc::Sender<OutboundMessage>,
+        metadata: ConnectionMetadata,
+    ) -> Result<Arc<ConnectionHandle>, ConnectionError> {
+        self.register_inner(user_id, tenant_id, roles, sender, metadata, &self.limits)
+    }
+
     /// Register a new connection with custom limits (for per-tenant limits)
     pub fn register_with_limits(
         &self,
@@ -64,6 +159,18 @@ impl ConnectionManager {
         roles: Vec<String>,
         sender: mpsc::Sender<OutboundMessage>,
         limits: &ConnectionLimits,
+    ) -> Result<Arc<ConnectionHandle>, ConnectionError> {
+        self.register_inner(user_id, tenant_id, roles, sender, ConnectionMetadata::default(), limits)
+    }
+
+    fn register_inner(
+        &self,
+        user_id: String,
+        tenant_id: String,
+        roles: Vec<String>,
+        sender: mpsc::Sender<OutboundMessage>,
+        metadata: ConnectionMetadata,
+        limits: &ConnectionLimits,
     ) -> Result<Arc<ConnectionHandle>, ConnectionError> {
         // Check total connection limit
         if limits.max_connections > 0 && self.connections.len() >= limits.max_connections {
@@ -80,11 +187,15 @@ impl ConnectionManager {
             });
         }
 
+        // Users are indexed per tenant, so equal user IDs in different tenants
+        // have separate connection lists and limits
+        let user_key = crate::auth::tenant_scoped_key(&tenant_id, &user_id);
+
         // Check per-user connection limit
         if limits.max_connections_per_user > 0 {
             let user_conn_count = self
                 .user_index
-                .get(&user_id)
+                .get(&user_key)
                 .map(|c| c.len())
                 .unwrap_or(0);
 
@@ -104,19 +215,21 @@ impl ConnectionManager {
             }
         }
 
-        let handle = Arc::new(ConnectionHandle::new(
-            user_id.clone(),
-            tenant_id.clone(),
-            roles,
-            sender,
-        ));
+        let handle = Arc::new(
+            ConnectionHandle::new(user_id.clone(), tenant_id.clone(), roles, sender)
+                .with_metadata(metadata),
+        );
         let conn_id = handle.id;
 
         // Add to connections map
         self.connections.insert(conn_id, handle.clone());
 
         // Update user index (SmallVec optimized for 1-4 connections per user)
-        self.user_index.entry(user_id).or_default().push(conn_id);
+        let user_conn_count = {
+            let mut user_conns = self.user_index.entry(user_key).or_default();
+            user_conns.push(conn_id);
+            user_conns.len()
+        };
 
         // Update tenant index
         self.tenant_index
@@ -131,6 +244,13 @@ impl ConnectionManager {
             "Connection registered"
         );
 
+        self.record_user_connection_count(&handle.user_id, user_conn_count);
+        self.events.publish(ConnectionEvent::UserConnected {
+            user_id: handle.user_id.clone(),
+            connection_id: conn_id,
+            tenant_id,
+        });
+
         Ok(handle)
     }
 
@@ -138,13 +258,17 @@ impl ConnectionManager {
     pub async fn unregister(&self, connection_id: Uuid) {
         if let Some((_, handle)) = self.connections.remove(&connection_id) {
             // Remove from user index (SmallVec - use retain for removal)
-            if let Some(mut user_conns) = self.user_index.get_mut(&handle.user_id) {
+            let mut remaining = 0;
+            let user_key = handle.namespaced_user_id();
+            if let Some(mut user_conns) = self.user_index.get_mut(&user_key) {
                 user_conns.retain(|id| *id != connection_id);
+                remaining = user_conns.len();
                 if user_conns.is_empty() {
                     drop(user_conns);
-                    self.user_index.remove(&handle.user_id);
+                    self.user_index.remove(&user_key);
                 }
             }
+            CONNECTIONS_PER_USER.observe(remaining as f64);
 
             // Remove from tenant index
             if let Some(mut tenant_conns) = self.tenant_index.get_mut(&handle.tenant_id) {
@@ -158,13 +282,7 @@ impl ConnectionManager {
             // Remove only from channels this connection was subscribed to (optimized)
             let subscribed_channels = handle.subscriptions.read().await.clone();
             for channel in subscribed_channels {
-                if let Some(mut channel_conns) = self.channel_index.get_mut(&channel) {
-                    channel_conns.remove(&connection_id);
-                    if channel_conns.is_empty() {
-                        drop(channel_conns);
-                        self.channel_index.remove(&channel);
-                    }
-                }
+                self.remove_channel_subscriber(&channel, connection_id);
             }
 
             tracing::info!(
@@ -173,6 +291,12 @@ impl ConnectionManager {
                 tenant_id = %handle.tenant_id,
                 "Connection unregistered"
             );
+            self.events.publish(ConnectionEvent::UserDisconnected {
+                user_id: handle.user_id.clone(),
+                connection_id,
+                tenant_id: handle.tenant_id.clone(),
+                last_connection: remaining == 0,
+            });
         }
     }
 
@@ -202,12 +326,29 @@ impl ConnectionManager {
                 .insert(channel.to_string());
 
             // Update channel index
-            self.channel_index
-                .entry(channel.to_string())
-                .or_default()
-                .insert(connection_id);
+            let (inserted, subscriber_count) = {
+                let mut channel_conns = self.channel_index.entry(channel.to_string()).or_default();
+                (channel_conns.insert(connection_id), channel_conns.len())
+            };
+            if inserted {
+                let mut meta = self
+                    .channel_meta
+                    .entry(channel.to_string())
+                    .or_insert_with(ChannelMeta::new);
+                meta.total_subscriber_events += 1;
+                if subscriber_count > meta.peak_subscribers {
+                    meta.peak_subscribers = subscriber_count;
+                }
+                ChannelMetrics::set_peak_subscribers(channel, meta.peak_subscribers);
+            }
 
             tracing::debug!(connection_id = %connection_id, channel = %channel, "Subscribed to channel");
+            if inserted {
+                self.events.publish(ConnectionEvent::ChannelSubscribed {
+                    connection_id,
+                    channel: channel.to_string(),
+                });
+            }
             Ok(())
         } else {
             Err("Connection not found".to_string())
@@ -218,25 +359,85 @@ impl ConnectionManager {
     pub async fn unsubscribe_from_channel(&self, connection_id: Uuid, channel: &str) {
         if let Some(handle) = self.connections.get(&connection_id) {
             // Update connection's subscriptions
-            handle.subscriptions.write().await.remove(channel);
+            let removed = handle.subscriptions.write().await.remove(channel);
 
             // Update channel index
-            if let Some(mut channel_conns) = self.channel_index.get_mut(channel) {
-                channel_conns.remove(&connection_id);
-                if channel_conns.is_empty() {
-                    drop(channel_conns);
-                    self.channel_index.remove(channel);
-                }
-            }
+            self.remove_channel_subscriber(channel, connection_id);
 
             tracing::debug!(connection_id = %connection_id, channel = %channel, "Unsubscribed from channel");
+            if removed {
+                self.events.publish(ConnectionEvent::ChannelUnsubscribed {
+                    connection_id,
+                    channel: channel.to_string(),
+                });
+            }
+        }
+    }
+
+    /// Delete a channel, unsubscribing every connection from it and sending each one
+    /// `ChannelDeleted`. Returns the connections that were subscribed; empty if the
+    /// channel did not exist.
+    pub async fn delete_channel(&self, channel: &str) -> Vec<Arc<ConnectionHandle>> {
+        let Some((_, connection_ids)) = self.channel_index.remove(channel) else {
+            return Vec::new();
+        };
+        self.channel_meta.remove(channel);
+
+        let handles: Vec<Arc<ConnectionHandle>> = connection_ids
+            .iter()
+            .filter_map(|id| self.connections.get(id).map(|h| h.clone()))
+            .collect();
+        for handle in &handles {
+            handle.subscriptions.write().await.remove(channel);
+            self.events.publish(ConnectionEvent::ChannelUnsubscribed {
+                connection_id: handle.id,
+                channel: channel.to_string(),
+            });
+        }
+
+        let message = ServerMessage::channel_deleted(channel);
+        let sends = handles.iter().map(|handle| handle.send(message.clone()));
+        let failed = futures::future::join_all(sends)
+            .await
+            .iter()
+            .filter(|result| result.is_err())
+            .count();
+
+        CHANNELS_DELETED_TOTAL.inc();
+        tracing::info!(
+            channel = %channel,
+            subscribers = handles.len(),
+            failed = failed,
+            "Channel deleted"
+        );
+        handles
+    }
+
+    /// Remove a connection from a channel's index, dropping the channel once it is empty
+    fn remove_channel_subscriber(&self, channel: &str, connection_id: Uuid) {
+        let Some(mut channel_conns) = self.channel_index.get_mut(channel) else {
+            return;
+        };
+        if !channel_conns.remove(&connection_id) {
+            return;
+        }
+        if channel_conns.is_empty() {
+            drop(channel_conns);
+            self.channel_index.remove(channel);
+            self.channel_meta.remove(channel);
+        } else {
+            drop(channel_conns);
+            if let Some(mut meta) = self.channel_meta.get_mut(channel) {
+                meta.total_subscriber_events += 1;
+            }
         }
     }
 
-    /// Get all connections for a user
-    pub fn get_user_connections(&self, user_id: &str) -> Vec<Arc<ConnectionHandle>> {
+    /// Get all connections for a user, keyed by the tenant-namespaced user ID
+    /// (see `TenantContext::namespace_user_id`)
+    pub fn get_user_connections(&self, namespaced_user_id: &str) -> Vec<Arc<ConnectionHandle>> {
         self.user_index
-            .get(user_id)
+            .get(namespaced_user_id)
             .map(|conn_ids| {
                 conn_ids
                     .iter()
@@ -259,16 +460,74 @@ impl ConnectionManager {
             .unwrap_or_default()
     }
 
+    /// Get all connections subscribed to a channel, except those belonging to
+    /// `exclude_user_ids` (matched against the raw user ID of each connection)
+    pub fn get_channel_connections_excluding(
+        &self,
+        channel: &str,
+        exclude_user_ids: &HashSet<String>,
+    ) -> Vec<Arc<ConnectionHandle>> {
+        let mut connections = self.get_channel_connections(channel);
+        if !exclude_user_ids.is_empty() {
+            connections.retain(|conn| !exclude_user_ids.contains(&conn.user_id));
+        }
+        connections
+    }
+
+    /// Get all connections subscribed to any channel matching the glob `pattern`
+    /// (`*` matches any run of characters, `?` one character). A connection
+    /// subscribed to several matching channels is returned once.
+    ///
+    /// Channels only match patterns of the same tenant: a `tenant:`-prefixed pattern
+    /// matches that tenant's channels, and an unprefixed (default tenant) pattern
+    /// never matches another tenant's namespaced channels.
+    pub fn get_connections_for_pattern(&self, pattern: &str) -> Vec<Arc<ConnectionHandle>> {
+        let pattern_tenant = pattern.split_once(':').map(|(tenant, _)| tenant);
+        let mut seen = HashSet::new();
+        for entry in self.channel_index.iter() {
+            let channel = entry.key();
+            if channel.split_once(':').map(|(tenant, _)| tenant) == pattern_tenant
+                && glob_match(pattern.as_bytes(), channel.as_bytes())
+            {
+                seen.extend(entry.value().iter().copied());
+            }
+        }
+        seen.into_iter()
+            .filter_map(|id| self.connections.get(&id).map(|h| h.clone()))
+            .collect()
+    }
+
+    /// Get all connections whose JWT roles, cached at connection time, include `role`
+    pub fn get_connections_by_role(&self, role: &str) -> Vec<Arc<ConnectionHandle>> {
+        self.connections
+            .iter()
+            .filter(|entry| entry.value().has_role(role))
+            .map(|entry| entry.value().clone())
+            .collect()
+    }
+
     /// Get all connections
     pub fn get_all_connections(&self) -> Vec<Arc<ConnectionHandle>> {
         self.connections.iter().map(|r| r.value().clone()).collect()
     }
 
-    /// Get connection by ID
+    /// Get connection by ID (for targeted single-connection delivery, e.g. ACK retries).
+    /// The ID index is the `connections` map itself, so entries disappear on `unregister`
+    /// and the index is bounded by `max_connections`.
     pub fn get_connection(&self, connection_id: Uuid) -> Option<Arc<ConnectionHandle>> {
         self.connections.get(&connection_id).map(|h| h.clone())
     }
 
+    /// Number of connection IDs currently addressable via `get_connection`
+    pub fn tracked_connection_ids(&self) -> usize {
+        self.connections.len()
+    }
+
+    /// Number of users with at least one connection
+    pub fn user_count(&self) -> usize {
+        self.user_index.len()
+    }
+
     /// Get statistics
     pub fn stats(&self) -> ConnectionStats {
         let mut channel_counts = HashMap::new();
@@ -276,10 +535,57 @@ impl ConnectionManager {
             channel_counts.insert(entry.key().clone(), entry.value().len());
         }
 
+        let total_connections = self.connections.len();
+        let oldest_connection_age_secs = self
+            .connections
+            .iter()
+            .map(|entry| entry.value().age().as_secs_f64())
+            .fold(0.0, f64::max);
+        let avg_subscriptions_per_connection = if total_connections > 0 {
+            self.total_subscriptions() as f64 / total_connections as f64
+        } else {
+            0.0
+        };
+
+        let top_users = top_users_by_connections(
+            self.user_index
+                .iter()
+                .map(|entry| (entry.key().clone(), entry.value().len())),
+        );
+
         ConnectionStats {
-            total_connections: self.connections.len(),
+            total_connections,
             unique_users: self.user_index.len(),
             channels: channel_counts,
+            oldest_connection_age_secs,
+            avg_subscriptions_per_connection,
+            top_n_users_by_connections: top_users,
+        }
+    }
+
+    /// Copy the current state of every connection (IDs, owners, subscriptions, ping
+    /// misses) so it can be inspected or serialized after the fact
+    pub async fn snapshot(&self) -> ConnectionManagerSnapshot {
+        let taken_at = Utc::now();
+        let mut connections = Vec::with_capacity(self.connections.len());
+        for handle in self.get_all_connections() {
+            let mut subscribed_channels: Vec<String> =
+                handle.subscriptions.read().await.iter().cloned().collect();
+            subscribed_channels.sort();
+            connections.push(ConnectionSnapshot {
+                connection_id: handle.id,
+                user_id: handle.user_id.clone(),
+                tenant_id: handle.tenant_id.clone(),
+                subscribed_channels,
+                connected_at: handle.connected_at,
+                ping_miss_count: handle.missed_pings(),
+            });
+        }
+        connections.sort_by_key(|c| (c.connected_at, c.connection_id));
+
+        ConnectionManagerSnapshot {
+            taken_at,
+            connections,
         }
     }
 
@@ -318,6 +624,16 @@ impl ConnectionManager {
         count
     }
 
+    /// Forcibly remove a connection, logging why. Returns false if it was already gone.
+    pub async fn evict_connection(&self, connection_id: Uuid, reason: &str) -> bool {
+        if !self.connections.contains_key(&connection_id) {
+            return false;
+        }
+        tracing::info!(connection_id = %connection_id, reason = %reason, "Evicting connection");
+        self.unregister(connection_id).await;
+        true
+    }
+
     /// Get all connection IDs (for heartbeat sending)
     pub fn get_all_connection_ids(&self) -> Vec<Uuid> {
         self.connections.iter().map(|r| *r.key()).collect()
@@ -327,19 +643,37 @@ impl ConnectionManager {
     pub fn list_channels(&self) -> Vec<ChannelInfo> {
         self.channel_index
             .iter()
-            .map(|entry| ChannelInfo {
-                name: entry.key().clone(),
-                subscriber_count: entry.value().len(),
-            })
+            .map(|entry| self.channel_info(entry.key(), entry.value().len()))
             .collect()
     }
 
     /// Get info for a specific channel
     pub fn get_channel_info(&self, channel: &str) -> Option<ChannelInfo> {
-        self.channel_index.get(channel).map(|entry| ChannelInfo {
+        let subscriber_count = self.channel_index.get(channel).map(|entry| entry.len())?;
+        Some(self.channel_info(channel, subscriber_count))
+    }
+
+    /// Build a `ChannelInfo` from the channel's metadata and the given subscriber count
+    fn channel_info(&self, channel: &str, subscriber_count: usize) -> ChannelInfo {
+        let (created_at, peak_subscribers, total_subscriber_events) = self
+            .channel_meta
+            .get(channel)
+            .map(|meta| {
+                (
+                    meta.created_at,
+                    meta.peak_subscribers,
+                    meta.total_subscriber_events,
+                )
+            })
+            .unwrap_or_else(|| (Utc::now(), subscriber_count, 0));
+
+        ChannelInfo {
             name: channel.to_string(),
-            subscriber_count: entry.len(),
-        })
+            subscriber_count,
+            created_at,
+            peak_subscribers,
+            total_subscriber_events,
+        }
     }
 
     /// Check if a channel exists
@@ -347,12 +681,14 @@ impl ConnectionManager {
         self.channel_index.contains_key(channel)
     }
 
-    /// Get all subscriptions for a user (across all their connections)
-    pub async fn get_user_subscriptions(&self, user_id: &str) -> Option<UserSubscriptionInfo> {
-        let connections = self.get_user_connections(user_id);
-        if connections.is_empty() {
-            return None;
-        }
+    /// Get all subscriptions for a user (across all their connections), keyed by the
+    /// tenant-namespaced user ID
+    pub async fn get_user_subscriptions(
+        &self,
+        namespaced_user_id: &str,
+    ) -> Option<UserSubscriptionInfo> {
+        let connections = self.get_user_connections(namespaced_user_id);
+        let user_id = connections.first()?.user_id.clone();
 
         let mut all_subscriptions = HashSet::new();
         for conn in &connections {
@@ -361,7 +697,7 @@ impl ConnectionManager {
         }
 
         Some(UserSubscriptionInfo {
-            user_id: user_id.to_string(),
+            user_id,
             connection_count: connections.len(),
             subscriptions: all_subscriptions.into_iter().collect(),
         })
@@ -399,6 +735,49 @@ impl ConnectionManager {
         }
     }
 
+    /// Get the detailed `stats()` view restricted to a single tenant's connections
+    pub fn tenant_detailed_stats(&self, tenant_id: &str) -> ConnectionStats {
+        let tenant_connections = self.get_tenant_connections(tenant_id);
+        let connection_ids: HashSet<Uuid> = tenant_connections.iter().map(|c| c.id).collect();
+
+        let mut channel_counts = HashMap::new();
+        for entry in self.channel_index.iter() {
+            let count = entry
+                .value()
+                .iter()
+                .filter(|id| connection_ids.contains(id))
+                .count();
+            if count > 0 {
+                channel_counts.insert(entry.key().clone(), count);
+            }
+        }
+
+        let mut user_counts: HashMap<String, usize> = HashMap::new();
+        for conn in &tenant_connections {
+            *user_counts.entry(conn.user_id.clone()).or_default() += 1;
+        }
+
+        let total_connections = tenant_connections.len();
+        let total_subscriptions: usize = channel_counts.values().sum();
+        let avg_subscriptions_per_connection = if total_connections > 0 {
+            total_subscriptions as f64 / total_connections as f64
+        } else {
+            0.0
+        };
+
+        ConnectionStats {
+            total_connections,
+            unique_users: user_counts.len(),
+            channels: channel_counts,
+            oldest_connection_age_secs: tenant_connections
+                .iter()
+                .map(|c| c.age().as_secs_f64())
+                .fold(0.0, f64::max),
+            avg_subscriptions_per_connection,
+            top_n_users_by_connections: top_users_by_connections(user_counts.into_iter()),
+        }
+    }
+
     /// Get connection count for a specific tenant
     pub fn tenant_connection_count(&self, tenant_id: &str) -> usize {
         self.tenant_index
@@ -433,10 +812,7 @@ impl ConnectionManager {
                     .filter(|id| tenant_connections.contains(id))
                     .count();
                 if subscriber_count > 0 {
-                    Some(ChannelInfo {
-                        name: entry.key().clone(),
-                        subscriber_count,
-                    })
+                    Some(self.channel_info(entry.key(), subscriber_count))
                 } else {
                     None
                 }
@@ -445,6 +821,14 @@ impl ConnectionManager {
     }
 }
 
+/// Sort `(user_id, connection_count)` pairs largest first and keep the top `TOP_USERS_LIMIT`
+fn top_users_by_connections(counts: impl Iterator<Item = (String, usize)>) -> Vec<(String, usize)> {
+    let mut top_users: Vec<(String, usize)> = counts.collect();
+    top_users.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    top_users.truncate(TOP_USERS_LIMIT);
+    top_users
+}
+
 impl Default for ConnectionManager {
     fn default() -> Self {
         Self::new()
@@ -465,6 +849,127 @@ mod tests {
         })
     }
 
+    #[tokio::test]
+    async fn test_register_and_unregister_publish_events() {
+        let manager = create_test_manager();
+        let mut events = manager.events().subscribe();
+        let (tx, _rx) = mpsc::channel(8);
+
+        let first = manager
+            .register("user-1".to_string(), "acme".to_string(), vec![], tx.clone())
+            .unwrap();
+        let second = manager
+            .register("user-1".to_string(), "acme".to_string(), vec![], tx)
+            .unwrap();
+        manager.subscribe_to_channel(first.id, "orders").await.unwrap();
+        manager.unsubscribe_from_channel(first.id, "orders").await;
+        manager.unregister(first.id).await;
+        manager.unregister(second.id).await;
+
+        let mut received = Vec::new();
+        while let Ok(event) = events.try_recv() {
+            received.push(event);
+        }
+        assert_eq!(
+            received,
+            vec![
+                ConnectionEvent::UserConnected {
+                    user_id: "user-1".to_string(),
+                    connection_id: first.id,
+                    tenant_id: "acme".to_string(),
+                },
+                ConnectionEvent::UserConnected {
+                    user_id: "user-1".to_string(),
+                    connection_id: second.id,
+                    tenant_id: "acme".to_string(),
+                },
+                ConnectionEvent::ChannelSubscribed {
+                    connection_id: first.id,
+                    channel: "orders".to_string(),
+                },
+                ConnectionEvent::ChannelUnsubscribed {
+                    connection_id: first.id,
+                    channel: "orders".to_string(),
+                },
+                ConnectionEvent::UserDisconnected {
+                    user_id: "user-1".to_string(),
+                    connection_id: first.id,
+                    tenant_id: "acme".to_string(),
+                    last_connection: false,
+                },
+                ConnectionEvent::UserDisconnected {
+                    user_id: "user-1".to_string(),
+                    connection_id: second.id,
+                    tenant_id: "acme".to_string(),
+                    last_connection: true,
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_captures_every_connection() {
+        let manager = create_test_manager();
+        let mut receivers = Vec::new();
+        let mut handles = Vec::new();
+        for (user, tenant) in [
+            ("user-1", "acme"),
+            ("user-1", "acme"),
+            ("user-2", "acme"),
+            ("user-3", DEFAULT_TENANT),
+            ("user-4", "globex"),
+        ] {
+            let (tx, rx) = mpsc::channel(8);
+            receivers.push(rx);
+            handles.push(
+                manager
+                    .register(user.to_string(), tenant.to_string(), vec![], tx)
+                    .unwrap(),
+            );
+        }
+        manager.subscribe_to_channel(handles[0].id, "orders").await.unwrap();
+        manager.subscribe_to_channel(handles[0].id, "alerts").await.unwrap();
+        manager.subscribe_to_channel(handles[4].id, "orders").await.unwrap();
+        handles[2].record_missed_ping();
+        handles[2].record_missed_ping();
+
+        let snapshot = manager.snapshot().await;
+        assert_eq!(snapshot.connections.len(), 5);
+        for handle in &handles {
+            let conn = snapshot
+                .connections
+                .iter()
+                .find(|c| c.connection_id == handle.id)
+                .unwrap();
+            assert_eq!(conn.user_id, handle.user_id);
+            assert_eq!(conn.tenant_id, handle.tenant_id);
+            assert_eq!(conn.connected_at, handle.connected_at);
+        }
+        let by_id = |id: Uuid| snapshot.connections.iter().find(|c| c.connection_id == id).unwrap();
+        assert_eq!(by_id(handles[0].id).subscribed_channels, vec!["alerts", "orders"]);
+        assert_eq!(by_id(handles[4].id).subscribed_channels, vec!["orders"]);
+        assert!(by_id(handles[1].id).subscribed_channels.is_empty());
+        assert_eq!(by_id(handles[2].id).ping_miss_count, 2);
+        assert_eq!(by_id(handles[3].id).ping_miss_count, 0);
+
+        // Later changes don't affect a snapshot already taken
+        manager.unregister(handles[0].id).await;
+        assert_eq!(snapshot.connections.len(), 5);
+
+        let json = serde_json::to_value(&snapshot).unwrap();
+        let first = &json["connections"][0];
+        for field in [
+            "connection_id",
+            "user_id",
+            "tenant_id",
+            "subscribed_channels",
+            "connected_at",
+            "ping_miss_count",
+        ] {
+            assert!(first.get(field).is_some(), "missing {}", field);
+        }
+    }
+
     #[tokio::test]
     async fn test_list_channels_empty() {
         let manager = create_test_manager();
@@ -499,6 +1004,31 @@ mod tests {
         assert_eq!(order_channel.unwrap().subscriber_count, 1);
     }
 
+    #[tokio::test]
+    async fn test_same_user_id_is_indexed_per_tenant() {
+        let manager = ConnectionManager::with_limits(ConnectionLimits {
+            max_connections: 100,
+            max_connections_per_user: 1,
+            max_subscriptions_per_connection: 10,
+        });
+        let (tx1, _rx1) = mpsc::channel(32);
+        let (tx2, _rx2) = mpsc::channel(32);
+
+        let acme = manager.register("1".to_string(), "acme".to_string(), vec![], tx1).unwrap();
+        // The per-user limit of one is not shared with another tenant's user "1"
+        let globex = manager.register("1".to_string(), "globex".to_string(), vec![], tx2).unwrap();
+
+        let acme_conns = manager.get_user_connections("acme:1");
+        assert_eq!(acme_conns.len(), 1);
+        assert_eq!(acme_conns[0].id, acme.id);
+        assert!(manager.get_user_connections("1").is_empty());
+        assert_eq!(manager.user_count(), 2);
+
+        manager.unregister(globex.id).await;
+        assert!(manager.get_user_connections("globex:1").is_empty());
+        assert_eq!(manager.get_user_connections("acme:1").len(), 1);
+    }
+
     #[tokio::test]
     async fn test_list_channels_multiple_subscribers() {
         let manager = create_test_manager();
@@ -665,4 +1195,270 @@ mod tests {
         // Channel should be removed
         assert!(!manager.channel_exists("orders"));
     }
+
+    #[tokio::test]
+    async fn test_get_connections_for_pattern() {
+        let manager = create_test_manager();
+        let mut ids = Vec::new();
+        for (user, channels) in [
+            ("user-1", vec!["orders.new", "orders.status"]),
+            ("user-2", vec!["orders.status"]),
+            ("user-3", vec!["ordersx", "chat"]),
+        ] {
+            let (tx, _rx) = mpsc::channel(32);
+            let handle = manager
+                .register(user.to_string(), DEFAULT_TENANT.to_string(), vec![], tx)
+                .unwrap();
+            for channel in channels {
+                manager.subscribe_to_channel(handle.id, channel).await.unwrap();
+            }
+            ids.push(handle.id);
+        }
+
+        let mut matched: Vec<Uuid> = manager
+            .get_connections_for_pattern("orders.*")
+            .iter()
+            .map(|conn| conn.id)
+            .collect();
+        matched.sort();
+        let mut expected = vec![ids[0], ids[1]];
+        expected.sort();
+        // user-1 matches two channels but is returned once
+        assert_eq!(matched, expected);
+
+        assert_eq!(manager.get_connections_for_pattern("*").len(), 3);
+        assert!(manager.get_connections_for_pattern("billing.*").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_connections_for_pattern_stays_within_tenant() {
+        let manager = create_test_manager();
+        let mut ids = Vec::new();
+        for (tenant, channel) in [
+            (DEFAULT_TENANT, "orders.new"),
+            ("acme", "acme:orders.new"),
+            ("globex", "globex:orders.new"),
+        ] {
+            let (tx, _rx) = mpsc::channel(32);
+            let handle = manager
+                .register("user-1".to_string(), tenant.to_string(), vec![], tx)
+                .unwrap();
+            manager.subscribe_to_channel(handle.id, channel).await.unwrap();
+            ids.push(handle.id);
+        }
+
+        let ids_for = |pattern: &str| -> Vec<Uuid> {
+            manager
+                .get_connections_for_pattern(pattern)
+                .iter()
+                .map(|conn| conn.id)
+                .collect()
+        };
+        // Default tenant patterns don't reach namespaced channels
+        assert_eq!(ids_for("*"), vec![ids[0]]);
+        assert_eq!(ids_for("orders.*"), vec![ids[0]]);
+        assert_eq!(ids_for("acme:*"), vec![ids[1]]);
+        // A wildcard in the tenant position matches no tenant
+        assert!(ids_for("*:orders.*").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_connections_by_role() {
+        let manager = create_test_manager();
+        let mut ids = Vec::new();
+        for (user, roles) in [
+            ("alice", vec!["admin", "operator"]),
+            ("bob", vec!["operator"]),
+            ("carol", vec![]),
+        ] {
+            let (tx, _rx) = mpsc::channel(32);
+            let roles = roles.into_iter().map(str::to_string).collect();
+            let handle = manager
+                .register(user.to_string(), DEFAULT_TENANT.to_string(), roles, tx)
+                .unwrap();
+            ids.push(handle.id);
+        }
+
+        let ids_for = |role: &str| {
+            let mut found: Vec<Uuid> = manager
+                .get_connections_by_role(role)
+                .iter()
+                .map(|conn| conn.id)
+                .collect();
+            found.sort();
+            found
+        };
+        let mut operators = vec![ids[0], ids[1]];
+        operators.sort();
+        assert_eq!(ids_for("admin"), vec![ids[0]]);
+        assert_eq!(ids_for("operator"), operators);
+        assert!(ids_for("Admin").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_connection_follows_register_and_unregister() {
+        let manager = ConnectionManager::with_limits(ConnectionLimits {
+            max_connections: 3,
+            max_connections_per_user: 0,
+            max_subscriptions_per_connection: 10,
+        });
+
+        let mut ids = Vec::new();
+        for i in 0..3 {
+            let (tx, _rx) = mpsc::channel(32);
+            let handle = manager
+                .register(format!("user-{}", i), DEFAULT_TENANT.to_string(), vec![], tx)
+                .unwrap();
+            assert_eq!(manager.get_connection(handle.id).unwrap().user_id, format!("user-{}", i));
+            ids.push(handle.id);
+        }
+        assert_eq!(manager.tracked_connection_ids(), 3);
+
+        // The index cannot grow past max_connections
+        let (tx, _rx) = mpsc::channel(32);
+        assert!(manager
+            .register("user-x".to_string(), DEFAULT_TENANT.to_string(), vec![], tx)
+            .is_err());
+        assert_eq!(manager.tracked_connection_ids(), 3);
+
+        for id in &ids {
+            manager.unregister(*id).await;
+            assert!(manager.get_connection(*id).is_none());
+        }
+        assert_eq!(manager.tracked_connection_ids(), 0);
+        assert!(manager.get_connection(Uuid::new_v4()).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_connection_count_histogram_and_alert() {
+        let manager = ConnectionManager::with_limits(ConnectionLimits {
+            max_connections: 100,
+            max_connections_per_user: 10,
+            max_subscriptions_per_connection: 10,
+        });
+        let (alert_tx, mut alert_rx) = mpsc::channel(16);
+        manager.set_connection_count_alert(5, alert_tx);
+
+        let observed_before = CONNECTIONS_PER_USER.get_sample_count();
+
+        let mut receivers = Vec::new();
+        for _ in 0..6 {
+            let (tx, rx) = mpsc::channel(32);
+            receivers.push(rx);
+            manager
+                .register("hoarder".to_string(), DEFAULT_TENANT.to_string(), vec![], tx)
+                .unwrap();
+        }
+
+        // Histogram is global, so other tests may observe concurrently
+        assert!(CONNECTIONS_PER_USER.get_sample_count() >= observed_before + 6);
+
+        // Only the 6th connection exceeds the threshold of 5
+        let alert = alert_rx.try_recv().unwrap();
+        assert_eq!(alert.user_id, "hoarder");
+        assert_eq!(alert.count, 6);
+        assert!(alert_rx.try_recv().is_err());
+    }
+
+    /// Insert a connection that appears to have been open for `age`
+    fn insert_aged_connection(
+        manager: &ConnectionManager,
+        user_id: &str,
+        age: std::time::Duration,
+    ) -> Uuid {
+        let (tx, _rx) = mpsc::channel(32);
+        let mut handle = ConnectionHandle::new(
+            user_id.to_string(),
+            DEFAULT_TENANT.to_string(),
+            vec![],
+            tx,
+        );
+        handle.connected_instant = std::time::Instant::now() - age;
+        let id = handle.id;
+        manager.connections.insert(id, Arc::new(handle));
+        manager
+            .user_index
+            .entry(user_id.to_string())
+            .or_default()
+            .push(id);
+        id
+    }
+
+    #[tokio::test]
+    async fn test_stats_connection_age_subscriptions_and_top_users() {
+        let manager = create_test_manager();
+        let connections = [
+            ("alice", 10, vec!["a", "b", "c"]),
+            ("alice", 300, vec!["a"]),
+            ("alice", 45, vec![]),
+            ("bob", 120, vec!["a", "b"]),
+            ("carol", 5, vec!["c", "d", "e", "f"]),
+        ];
+        for (user, age_secs, channels) in connections {
+            let id = insert_aged_connection(&manager, user, std::time::Duration::from_secs(age_secs));
+            for channel in channels {
+                manager.subscribe_to_channel(id, channel).await.unwrap();
+            }
+        }
+
+        let stats = manager.stats();
+        assert_eq!(stats.total_connections, 5);
+        assert_eq!(stats.unique_users, 3);
+        // Oldest connection is 300s old; allow slack for test execution time
+        assert!(stats.oldest_connection_age_secs >= 300.0);
+        assert!(stats.oldest_connection_age_secs < 310.0);
+        // 10 subscriptions across 5 connections
+        assert!((stats.avg_subscriptions_per_connection - 2.0).abs() < f64::EPSILON);
+        assert_eq!(
+            stats.top_n_users_by_connections,
+            vec![
+                ("alice".to_string(), 3),
+                ("bob".to_string(), 1),
+                ("carol".to_string(), 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stats_empty() {
+        let stats = create_test_manager().stats();
+        assert_eq!(stats.oldest_connection_age_secs, 0.0);
+        assert_eq!(stats.avg_subscriptions_per_connection, 0.0);
+        assert!(stats.top_n_users_by_connections.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_channel_info_tracks_peak_and_events() {
+        let manager = create_test_manager();
+        let mut ids = Vec::new();
+        let mut receivers = Vec::new();
+        for i in 0..3 {
+            let (tx, rx) = mpsc::channel(32);
+            receivers.push(rx);
+            let handle = manager
+                .register(format!("user-{}", i), DEFAULT_TENANT.to_string(), vec![], tx)
+                .unwrap();
+            manager.subscribe_to_channel(handle.id, "orders").await.unwrap();
+            ids.push(handle.id);
+        }
+        // Re-subscribing an existing member is not a new event
+        manager.subscribe_to_channel(ids[0], "orders").await.unwrap();
+        manager.unsubscribe_from_channel(ids[0], "orders").await;
+        manager.unregister(ids[1]).await;
+
+        let info = manager.get_channel_info("orders").unwrap();
+        assert_eq!(info.subscriber_count, 1);
+        assert_eq!(info.peak_subscribers, 3);
+        assert_eq!(info.total_subscriber_events, 5);
+        let created_at = info.created_at;
+
+        // History resets once the channel empties
+        manager.unsubscribe_from_channel(ids[2], "orders").await;
+        assert!(manager.get_channel_info("orders").is_none());
+        manager.subscribe_to_channel(ids[2], "orders").await.unwrap();
+        let info = manager.get_channel_info("orders").unwrap();
+        assert_eq!(info.peak_subscribers, 1);
+        assert_eq!(info.total_subscriber_events, 1);
+        assert!(info.created_at >= created_at);
+    }
 }
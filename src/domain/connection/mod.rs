@@ -5,11 +5,20 @@
 //! - User and channel indexing
 //! - Tenant isolation
 //! - Connection statistics
+//! - Connection lifecycle events
 
+mod events;
 mod manager;
 mod stats;
 mod types;
 
+pub use events::{ConnectionEvent, ConnectionEventBus, CONNECTION_EVENT_CAPACITY};
 pub use manager::ConnectionManager;
-pub use stats::{ChannelInfo, ConnectionStats, TenantConnectionStats, UserSubscriptionInfo};
-pub use types::{ConnectionError, ConnectionHandle, ConnectionLimits};
+pub use stats::{
+    ChannelInfo, ConnectionManagerSnapshot, ConnectionSnapshot, ConnectionStats,
+    TenantConnectionStats, UserSubscriptionInfo,
+};
+pub use types::{
+    ConnectionAlert, ConnectionError, ConnectionHandle, ConnectionLimits, ConnectionMetadata,
+    Transport,
+};
@@ -1,7 +1,9 @@
 //! Connection statistics and info structures
 
+use chrono::{DateTime, Utc};
 use serde::Serialize;
 use std::collections::HashMap;
+use uuid::Uuid;
 
 /// Connection statistics
 #[derive(Debug, Clone, Serialize)]
@@ -9,6 +11,12 @@ pub struct ConnectionStats {
     pub total_connections: usize,
     pub unique_users: usize,
     pub channels: HashMap<String, usize>,
+    /// Age of the longest-lived connection in seconds (0 when there are none)
+    pub oldest_connection_age_secs: f64,
+    /// Mean number of channel subscriptions per connection
+    pub avg_subscriptions_per_connection: f64,
+    /// Users holding the most connections, largest first (at most 10)
+    pub top_n_users_by_connections: Vec<(String, usize)>,
 }
 
 /// Tenant-specific connection statistics
@@ -24,6 +32,33 @@ pub struct TenantConnectionStats {
 pub struct ChannelInfo {
     pub name: String,
     pub subscriber_count: usize,
+    /// When the channel's first current subscription was registered
+    pub created_at: DateTime<Utc>,
+    /// Highest subscriber count seen since the channel was created
+    pub peak_subscribers: usize,
+    /// Number of subscribe/unsubscribe events since the channel was created
+    pub total_subscriber_events: u64,
+}
+
+/// Point-in-time copy of every connection, for debugging
+#[derive(Debug, Clone, Serialize)]
+pub struct ConnectionManagerSnapshot {
+    pub taken_at: DateTime<Utc>,
+    /// Connections ordered by connect time
+    pub connections: Vec<ConnectionSnapshot>,
+}
+
+/// State of a single connection at snapshot time
+#[derive(Debug, Clone, Serialize)]
+pub struct ConnectionSnapshot {
+    pub connection_id: Uuid,
+    pub user_id: String,
+    pub tenant_id: String,
+    /// Channel subscriptions, sorted
+    pub subscribed_channels: Vec<String>,
+    pub connected_at: DateTime<Utc>,
+    /// Consecutive heartbeats that could not be delivered
+    pub ping_miss_count: u32,
 }
 
 /// User subscription information
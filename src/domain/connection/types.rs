@@ -1,18 +1,50 @@
 //! Connection handle and related types
 
 use chrono::{DateTime, Utc};
+use serde::Serialize;
 use std::collections::HashSet;
-use std::sync::atomic::{AtomicI64, Ordering};
-use std::time::Duration;
+use std::sync::atomic::{AtomicI64, AtomicU32, Ordering};
+use std::time::{Duration, Instant};
 use tokio::sync::{mpsc, RwLock};
 use uuid::Uuid;
 
-use crate::websocket::{OutboundMessage, ServerMessage};
+use crate::notification::PayloadEncoding;
+use crate::websocket::{OutboundMessage, ServerMessage, PROTOCOL_VERSION};
 
 /// Timeout for sending messages to a connection's channel.
 /// Prevents indefinite blocking when a consumer is slow or stalled.
 const SEND_TIMEOUT: Duration = Duration::from_secs(5);
 
+/// Protocol a connection was opened with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Transport {
+    #[default]
+    WebSocket,
+    Sse,
+}
+
+/// Client preferences negotiated when the connection was opened
+#[derive(Debug, Clone)]
+pub struct ConnectionMetadata {
+    /// Transport the client connected over
+    pub transport: Transport,
+    /// Encoding requested with `?encoding=` (`json` unless the client opted in to `msgpack`)
+    pub payload_encoding: PayloadEncoding,
+    /// Server message protocol version negotiated with `?protocol_version=`
+    pub protocol_version: u8,
+}
+
+impl Default for ConnectionMetadata {
+    fn default() -> Self {
+        Self {
+            transport: Transport::default(),
+            payload_encoding: PayloadEncoding::default(),
+            protocol_version: PROTOCOL_VERSION,
+        }
+    }
+}
+
 /// Handle for a single WebSocket connection
 pub struct ConnectionHandle {
     pub id: Uuid,
@@ -21,9 +53,14 @@ pub struct ConnectionHandle {
     pub roles: Vec<String>,
     pub sender: mpsc::Sender<OutboundMessage>,
     pub connected_at: DateTime<Utc>,
+    /// Monotonic connect time, used for connection age statistics
+    pub connected_instant: Instant,
     /// Last activity timestamp (Unix seconds) - using AtomicI64 for lock-free updates
     last_activity: AtomicI64,
+    /// Heartbeats sent since the client was last heard from
+    ping_miss_count: AtomicU32,
     pub subscriptions: RwLock<HashSet<String>>,
+    pub metadata: ConnectionMetadata,
 }
 
 impl ConnectionHandle {
@@ -41,14 +78,36 @@ impl ConnectionHandle {
             roles,
             sender,
             connected_at: now,
+            connected_instant: Instant::now(),
             last_activity: AtomicI64::new(now.timestamp()),
+            ping_miss_count: AtomicU32::new(0),
             subscriptions: RwLock::new(HashSet::new()),
+            metadata: ConnectionMetadata::default(),
         }
     }
 
+    /// Attach client preferences to a new handle
+    pub fn with_metadata(mut self, metadata: ConnectionMetadata) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    /// Whether MessagePack notifications should be sent to this connection as binary frames
+    pub fn accepts_msgpack(&self) -> bool {
+        self.metadata.payload_encoding == PayloadEncoding::Msgpack
+    }
+
+    /// Time elapsed since the connection was registered
+    pub fn age(&self) -> Duration {
+        self.connected_instant.elapsed()
+    }
+
+    /// Record that the client was heard from (any frame, including a pong), which
+    /// also clears its missed-heartbeat count
     pub fn update_activity(&self) {
         self.last_activity
             .store(Utc::now().timestamp(), Ordering::Relaxed);
+        self.reset_missed_pings();
     }
 
     pub fn last_activity(&self) -> DateTime<Utc> {
@@ -56,6 +115,22 @@ impl ConnectionHandle {
             .unwrap_or_else(Utc::now)
     }
 
+    /// Record a heartbeat the client has not answered yet, returning the number sent
+    /// since it was last heard from
+    pub fn record_missed_ping(&self) -> u32 {
+        self.ping_miss_count.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    /// Reset the miss count after client activity, or a delivered heartbeat on
+    /// transports without an upstream channel (SSE)
+    pub fn reset_missed_pings(&self) {
+        self.ping_miss_count.store(0, Ordering::Relaxed);
+    }
+
+    pub fn missed_pings(&self) -> u32 {
+        self.ping_miss_count.load(Ordering::Relaxed)
+    }
+
     /// Send a ServerMessage (will be serialized when sent to WebSocket).
     /// Times out after SEND_TIMEOUT to prevent blocking on stalled consumers.
     pub async fn send(
@@ -83,6 +158,12 @@ impl ConnectionHandle {
             ))))
     }
 
+    /// User ID namespaced by tenant, the key for per-user state
+    /// (see `TenantContext::namespace_user_id`)
+    pub fn namespaced_user_id(&self) -> String {
+        crate::auth::tenant_scoped_key(&self.tenant_id, &self.user_id)
+    }
+
     /// Check if user has a specific role
     pub fn has_role(&self, role: &str) -> bool {
         self.roles.iter().any(|r| r == role)
@@ -122,6 +203,14 @@ impl std::fmt::Display for ConnectionError {
     }
 }
 
+/// Raised when a user holds more connections than the configured alert threshold
+#[derive(Debug, Clone)]
+pub struct ConnectionAlert {
+    pub user_id: String,
+    pub count: usize,
+    pub timestamp: DateTime<Utc>,
+}
+
 /// Limits for connection management
 #[derive(Debug, Clone, Copy)]
 pub struct ConnectionLimits {
@@ -0,0 +1,175 @@
+//! Circuit breaker for sends to slow connections.
+//!
+//! Connections are grouped into [`DISPATCH_CIRCUIT_SHARDS`] shards by a hash of the
+//! user ID. When more than half of the sends to a shard fail within
+//! [`DISPATCH_CIRCUIT_WINDOW`], the shard's circuit opens for
+//! [`DISPATCH_CIRCUIT_OPEN_DURATION`] and the dispatcher skips its connections
+//! instead of waiting out their send timeouts, so slow consumers don't hold up
+//! dispatches to healthy users.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::metrics::DISPATCH_CIRCUIT_OPEN;
+use crate::queue::memory_backend::fnv1a_hash;
+
+/// Number of shards connections are grouped into
+pub const DISPATCH_CIRCUIT_SHARDS: usize = 16;
+
+/// Window over which a shard's send failures are counted
+pub const DISPATCH_CIRCUIT_WINDOW: Duration = Duration::from_secs(10);
+
+/// How long an open shard's connections are skipped
+pub const DISPATCH_CIRCUIT_OPEN_DURATION: Duration = Duration::from_secs(30);
+
+/// Sends a shard needs within the window before its failure ratio is considered,
+/// so a single evicted connection doesn't cut off the rest of its shard
+const MIN_SENDS: u32 = 10;
+
+/// Send outcomes of one shard in the current window
+#[derive(Debug)]
+struct ShardState {
+    window_start: Instant,
+    sends: u32,
+    failures: u32,
+    /// Set while the circuit is open
+    open_until: Option<Instant>,
+}
+
+impl ShardState {
+    fn new(now: Instant) -> Self {
+        Self {
+            window_start: now,
+            sends: 0,
+            failures: 0,
+            open_until: None,
+        }
+    }
+}
+
+/// Per-shard circuit breaker for dispatcher sends
+#[derive(Debug)]
+pub struct DispatchCircuitBreaker {
+    shards: Vec<Mutex<ShardState>>,
+    window: Duration,
+    open_duration: Duration,
+}
+
+impl DispatchCircuitBreaker {
+    pub fn new() -> Self {
+        Self::with_timing(DISPATCH_CIRCUIT_WINDOW, DISPATCH_CIRCUIT_OPEN_DURATION)
+    }
+
+    /// Create a breaker with a custom failure window and open duration
+    pub fn with_timing(window: Duration, open_duration: Duration) -> Self {
+        let now = Instant::now();
+        Self {
+            shards: (0..DISPATCH_CIRCUIT_SHARDS)
+                .map(|_| Mutex::new(ShardState::new(now)))
+                .collect(),
+            window,
+            open_duration,
+        }
+    }
+
+    /// Shard a user's connections belong to
+    pub fn shard_for(user_id: &str) -> usize {
+        (fnv1a_hash(user_id.as_bytes()) % DISPATCH_CIRCUIT_SHARDS as u64) as usize
+    }
+
+    /// Whether sends to `user_id`'s connections should be attempted. Closes the
+    /// shard's circuit again once its open duration has passed.
+    pub fn allows(&self, user_id: &str) -> bool {
+        let shard = Self::shard_for(user_id);
+        let mut state = self.shards[shard].lock().unwrap();
+        match state.open_until {
+            Some(until) if Instant::now() < until => false,
+            Some(_) => {
+                *state = ShardState::new(Instant::now());
+                DISPATCH_CIRCUIT_OPEN
+                    .with_label_values(&[&shard.to_string()])
+                    .set(0);
+                tracing::info!(shard, "Dispatch circuit closed");
+                true
+            }
+            None => true,
+        }
+    }
+
+    /// Record the outcome of a send to one of `user_id`'s connections, opening the
+    /// shard's circuit if more than half of its recent sends failed
+    pub fn record(&self, user_id: &str, success: bool) {
+        let shard = Self::shard_for(user_id);
+        let mut state = self.shards[shard].lock().unwrap();
+        if state.open_until.is_some() {
+            return;
+        }
+
+        let now = Instant::now();
+        if now.duration_since(state.window_start) >= self.window {
+            *state = ShardState::new(now);
+        }
+        state.sends += 1;
+        if !success {
+            state.failures += 1;
+        }
+
+        if state.sends >= MIN_SENDS && state.failures * 2 > state.sends {
+            tracing::warn!(
+                shard,
+                sends = state.sends,
+                failures = state.failures,
+                open_for_secs = self.open_duration.as_secs(),
+                "Dispatch circuit opened"
+            );
+            state.open_until = Some(now + self.open_duration);
+            DISPATCH_CIRCUIT_OPEN
+                .with_label_values(&[&shard.to_string()])
+                .set(1);
+        }
+    }
+
+    /// Whether a shard's circuit is currently open
+    pub fn is_open(&self, shard: usize) -> bool {
+        self.shards[shard]
+            .lock()
+            .unwrap()
+            .open_until
+            .is_some_and(|until| Instant::now() < until)
+    }
+}
+
+impl Default for DispatchCircuitBreaker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_opens_on_majority_failures_and_recovers() {
+        let breaker =
+            DispatchCircuitBreaker::with_timing(Duration::from_secs(10), Duration::from_millis(20));
+        let shard = DispatchCircuitBreaker::shard_for("slow-user");
+
+        for _ in 0..MIN_SENDS / 2 {
+            breaker.record("slow-user", true);
+        }
+        for _ in 0..MIN_SENDS / 2 {
+            breaker.record("slow-user", false);
+        }
+        // Exactly half failed
+        assert!(breaker.allows("slow-user"));
+
+        breaker.record("slow-user", false);
+        assert!(breaker.is_open(shard));
+        assert!(!breaker.allows("slow-user"));
+
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(breaker.allows("slow-user"));
+        assert!(!breaker.is_open(shard));
+    }
+}
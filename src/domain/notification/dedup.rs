@@ -0,0 +1,139 @@
+//! Deduplication of sends that carry a caller-provided notification ID.
+//!
+//! Callers that retry a send after a timeout can pass the same `notification_id` again.
+//! The first send for an ID is delivered and its result remembered for
+//! `DEDUP_WINDOW`; repeats within the window get the remembered result back without
+//! being delivered again. A repeat that arrives while the first send is still in
+//! flight waits for it to finish.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use dashmap::mapref::entry::Entry;
+use dashmap::DashMap;
+use tokio::sync::OnceCell;
+
+use super::DeliveryResult;
+
+/// How long the result of a send with a caller-provided ID is remembered
+pub const DEDUP_WINDOW: Duration = Duration::from_secs(600);
+
+/// Number of remembered IDs above which expired entries are swept on insert
+const SWEEP_THRESHOLD: usize = 10_000;
+
+struct DedupEntry {
+    result: Arc<OnceCell<DeliveryResult>>,
+    inserted_at: Instant,
+}
+
+/// Results of recent sends, keyed by tenant-scoped notification ID
+pub struct DeduplicationCache {
+    entries: DashMap<String, DedupEntry>,
+    window: Duration,
+}
+
+impl Default for DeduplicationCache {
+    fn default() -> Self {
+        Self::new(DEDUP_WINDOW)
+    }
+}
+
+impl DeduplicationCache {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            entries: DashMap::new(),
+            window,
+        }
+    }
+
+    /// Claim `key` for a send.
+    ///
+    /// Returns the cell holding the send's result and whether the key was already
+    /// claimed within the window (i.e. this send is a duplicate).
+    pub fn claim(&self, key: String) -> (Arc<OnceCell<DeliveryResult>>, bool) {
+        if self.entries.len() >= SWEEP_THRESHOLD {
+            self.cleanup_expired();
+        }
+
+        let fresh = || DedupEntry {
+            result: Arc::new(OnceCell::new()),
+            inserted_at: Instant::now(),
+        };
+        match self.entries.entry(key) {
+            Entry::Occupied(entry) if entry.get().inserted_at.elapsed() < self.window => {
+                (entry.get().result.clone(), true)
+            }
+            Entry::Occupied(mut entry) => {
+                entry.insert(fresh());
+                (entry.get().result.clone(), false)
+            }
+            Entry::Vacant(entry) => (entry.insert(fresh()).result.clone(), false),
+        }
+    }
+
+    /// Forget results older than the window, returning how many were removed
+    pub fn cleanup_expired(&self) -> usize {
+        let before = self.entries.len();
+        self.entries
+            .retain(|_, entry| entry.inserted_at.elapsed() < self.window);
+        before.saturating_sub(self.entries.len())
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use uuid::Uuid;
+
+    #[tokio::test]
+    async fn test_claim_detects_duplicates() {
+        let cache = DeduplicationCache::default();
+        let id = Uuid::new_v4();
+
+        let (first, duplicate) = cache.claim(id.to_string());
+        assert!(!duplicate);
+        first
+            .get_or_init(|| async {
+                DeliveryResult {
+                    notification_id: id,
+                    delivered_to: 2,
+                    failed: 0,
+                    success: true,
+                    per_user_status: None,
+                    failure_reason: None,
+                }
+            })
+            .await;
+
+        let (second, duplicate) = cache.claim(id.to_string());
+        assert!(duplicate);
+        assert_eq!(second.get().unwrap().delivered_to, 2);
+
+        let (_, duplicate) = cache.claim(Uuid::new_v4().to_string());
+        assert!(!duplicate);
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_expired_entries_are_forgotten() {
+        let cache = DeduplicationCache::new(Duration::ZERO);
+        let (_, duplicate) = cache.claim("id".to_string());
+        assert!(!duplicate);
+
+        // An expired claim is replaced rather than reported as a duplicate
+        let (_, duplicate) = cache.claim("id".to_string());
+        assert!(!duplicate);
+
+        assert_eq!(cache.cleanup_expired(), 1);
+        assert!(cache.is_empty());
+    }
+}
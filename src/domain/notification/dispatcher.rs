@@ -1,19 +1,48 @@
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use futures::stream::{FuturesUnordered, StreamExt};
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
 use serde::Serialize;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+use tracing::Instrument;
 use uuid::Uuid;
 
+use crate::audit::{audit_enabled, AuditEvent};
 use crate::connection_manager::{ConnectionHandle, ConnectionManager};
-use crate::metrics::MessageMetrics;
+use crate::metrics::{
+    BackendMetrics, MessageMetrics, BROADCAST_FANOUT_INFLIGHT, DEDUP_CALLER_DUPLICATES_TOTAL,
+    DISPATCHER_BACKPRESSURE_TOTAL,
+    DEDUP_CALLER_PROVIDED_IDS_TOTAL, NOTIFICATIONS_DROPPED_QUEUE_DISABLED_TOTAL,
+    NOTIFICATIONS_EXPIRED_AT_DELIVERY_TOTAL, WS_SEND_TIMEOUTS_TOTAL,
+};
 use crate::queue::MessageQueueBackend;
 use crate::websocket::{OutboundMessage, ServerMessage};
 
-use super::{AckTrackerBackend, NotificationEvent, NotificationTarget};
+use crate::queue::QueueBackendError;
 
-/// Maximum number of concurrent message sends
-const MAX_CONCURRENT_SENDS: usize = 100;
+use super::{
+    AckTrackerBackend, DeduplicationCache, DispatchCircuitBreaker, DropLog, DropReason, DroppedNotification, NotificationEvent,
+    NotificationTarget,
+};
+
+/// Default maximum number of concurrent sends per fan-out
+const DEFAULT_MAX_FANOUT_CONCURRENCY: usize = 1000;
+
+/// Default time a connection's outbound channel has to accept a notification
+const DEFAULT_SEND_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Default maximum number of dispatches in progress at once
+const DEFAULT_MAX_CONCURRENT_DISPATCHES: usize = 500;
+
+/// Default time a dispatch waits for a free slot before failing
+const DEFAULT_DISPATCH_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// `DeliveryResult::failure_reason` when no dispatch slot freed up in time
+pub const DISPATCH_QUEUE_FULL: &str = "dispatch_queue_full";
 
 /// Threshold for using pre-serialization (saves serialization overhead for larger sends)
 const PRESERIALIZATION_THRESHOLD: usize = 4;
@@ -21,6 +50,12 @@ const PRESERIALIZATION_THRESHOLD: usize = 4;
 /// Batch size for processing multiple users (reduces memory pressure and allows progress reporting)
 const USER_BATCH_SIZE: usize = 100;
 
+/// Maximum number of distinct event types tracked individually in dispatcher stats
+const MAX_TRACKED_EVENT_TYPES: usize = 200;
+
+/// Bucket used for event types beyond `MAX_TRACKED_EVENT_TYPES`
+const OTHER_EVENT_TYPE: &str = "__other__";
+
 /// Result of a notification delivery attempt
 #[derive(Debug, Clone, Serialize)]
 pub struct DeliveryResult {
@@ -32,6 +67,12 @@ pub struct DeliveryResult {
     pub failed: usize,
     /// Whether any delivery was successful
     pub success: bool,
+    /// Outcome for each target user (multi-user sends only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub per_user_status: Option<HashMap<String, UserDeliveryStatus>>,
+    /// Why the notification was not dispatched at all (e.g. `dispatch_queue_full`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failure_reason: Option<&'static str>,
 }
 
 impl DeliveryResult {
@@ -41,15 +82,39 @@ impl DeliveryResult {
             delivered_to: delivered,
             failed,
             success: delivered > 0,
+            per_user_status: None,
+            failure_reason: None,
+        }
+    }
+
+    /// Result of a dispatch that was rejected before reaching any connection
+    fn rejected(notification_id: Uuid, reason: &'static str) -> Self {
+        Self {
+            failure_reason: Some(reason),
+            ..Self::new(notification_id, 0, 0)
         }
     }
 }
 
+/// Outcome of a multi-user send for one user
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UserDeliveryStatus {
+    /// Sent to at least one of the user's connections
+    Delivered,
+    /// The user was offline and the notification was queued
+    Queued,
+    /// The user was not reached and the notification was not queued
+    NoConnection,
+    /// The user was offline and their offline queue refused the notification as full
+    RateLimited,
+}
+
 /// Statistics for the notification dispatcher
 #[derive(Debug, Default)]
 pub struct DispatcherStats {
-    /// Total notifications sent
-    pub total_sent: AtomicU64,
+    /// Total notifications sent (shared so background tasks can observe dispatch activity)
+    pub total_sent: Arc<AtomicU64>,
     /// Total successful deliveries (connection count)
     pub total_delivered: AtomicU64,
     /// Total failed deliveries
@@ -60,6 +125,12 @@ pub struct DispatcherStats {
     pub broadcast_notifications: AtomicU64,
     /// Channel notifications
     pub channel_notifications: AtomicU64,
+    /// Notifications dispatched per event type
+    pub event_type_counts: DashMap<String, AtomicU64>,
+    /// Successful deliveries (connection count) per event type
+    pub event_type_delivered: DashMap<String, AtomicU64>,
+    /// Highest number of concurrent sends observed in a single fan-out
+    pub peak_fanout_inflight: AtomicUsize,
 }
 
 impl DispatcherStats {
@@ -71,8 +142,42 @@ impl DispatcherStats {
             user_notifications: self.user_notifications.load(Ordering::Relaxed),
             broadcast_notifications: self.broadcast_notifications.load(Ordering::Relaxed),
             channel_notifications: self.channel_notifications.load(Ordering::Relaxed),
+            event_type_counts: Self::collect_counts(&self.event_type_counts),
+            event_type_delivered: Self::collect_counts(&self.event_type_delivered),
+            peak_fanout_inflight: self.peak_fanout_inflight.load(Ordering::Relaxed),
         }
     }
+
+    /// Record a dispatch of the given event type and its delivered connection count.
+    /// Once `MAX_TRACKED_EVENT_TYPES` distinct types are tracked, new types are
+    /// aggregated under `OTHER_EVENT_TYPE` to bound memory usage.
+    pub fn record_event_type(&self, event_type: &str, delivered: usize) {
+        let key = if self.event_type_counts.contains_key(event_type)
+            || self.event_type_counts.len() < MAX_TRACKED_EVENT_TYPES
+        {
+            event_type
+        } else {
+            OTHER_EVENT_TYPE
+        };
+
+        self.event_type_counts
+            .entry(key.to_string())
+            .or_default()
+            .fetch_add(1, Ordering::Relaxed);
+        self.event_type_delivered
+            .entry(key.to_string())
+            .or_default()
+            .fetch_add(delivered as u64, Ordering::Relaxed);
+
+        MessageMetrics::set_event_types_tracked(self.event_type_counts.len());
+    }
+
+    fn collect_counts(counts: &DashMap<String, AtomicU64>) -> HashMap<String, u64> {
+        counts
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().load(Ordering::Relaxed)))
+            .collect()
+    }
 }
 
 /// Snapshot of dispatcher statistics
@@ -84,6 +189,9 @@ pub struct DispatcherStatsSnapshot {
     pub user_notifications: u64,
     pub broadcast_notifications: u64,
     pub channel_notifications: u64,
+    pub event_type_counts: HashMap<String, u64>,
+    pub event_type_delivered: HashMap<String, u64>,
+    pub peak_fanout_inflight: usize,
 }
 
 /// Dispatches notifications to connected clients
@@ -92,6 +200,20 @@ pub struct NotificationDispatcher {
     queue_backend: Option<Arc<dyn MessageQueueBackend>>,
     ack_backend: Option<Arc<dyn AckTrackerBackend>>,
     stats: DispatcherStats,
+    /// Maximum concurrent sends when fanning out to many connections
+    max_fanout_concurrency: usize,
+    /// Connections that don't accept a send within this time are evicted
+    send_timeout: Duration,
+    /// Records notifications for offline users dropped while the queue is disabled
+    drop_log: Option<Arc<DropLog>>,
+    /// Results of recent sends with caller-provided notification IDs
+    dedup_cache: Arc<DeduplicationCache>,
+    /// Slots for dispatches in progress, so bursts don't all contend on the connection manager
+    dispatch_permits: Semaphore,
+    /// How long a dispatch waits for a slot before failing
+    dispatch_timeout: Duration,
+    /// Skips shards of connections whose sends keep failing
+    circuit_breaker: Arc<DispatchCircuitBreaker>,
 }
 
 impl NotificationDispatcher {
@@ -102,6 +224,13 @@ impl NotificationDispatcher {
             queue_backend: None,
             ack_backend: None,
             stats: DispatcherStats::default(),
+            max_fanout_concurrency: DEFAULT_MAX_FANOUT_CONCURRENCY,
+            send_timeout: DEFAULT_SEND_TIMEOUT,
+            drop_log: None,
+            dedup_cache: Arc::new(DeduplicationCache::default()),
+            dispatch_permits: Semaphore::new(DEFAULT_MAX_CONCURRENT_DISPATCHES),
+            dispatch_timeout: DEFAULT_DISPATCH_TIMEOUT,
+            circuit_breaker: Arc::new(DispatchCircuitBreaker::new()),
         }
     }
 
@@ -115,6 +244,13 @@ impl NotificationDispatcher {
             queue_backend: Some(queue_backend),
             ack_backend: None,
             stats: DispatcherStats::default(),
+            max_fanout_concurrency: DEFAULT_MAX_FANOUT_CONCURRENCY,
+            send_timeout: DEFAULT_SEND_TIMEOUT,
+            drop_log: None,
+            dedup_cache: Arc::new(DeduplicationCache::default()),
+            dispatch_permits: Semaphore::new(DEFAULT_MAX_CONCURRENT_DISPATCHES),
+            dispatch_timeout: DEFAULT_DISPATCH_TIMEOUT,
+            circuit_breaker: Arc::new(DispatchCircuitBreaker::new()),
         }
     }
 
@@ -129,9 +265,52 @@ impl NotificationDispatcher {
             queue_backend: Some(queue_backend),
             ack_backend: Some(ack_backend),
             stats: DispatcherStats::default(),
+            max_fanout_concurrency: DEFAULT_MAX_FANOUT_CONCURRENCY,
+            send_timeout: DEFAULT_SEND_TIMEOUT,
+            drop_log: None,
+            dedup_cache: Arc::new(DeduplicationCache::default()),
+            dispatch_permits: Semaphore::new(DEFAULT_MAX_CONCURRENT_DISPATCHES),
+            dispatch_timeout: DEFAULT_DISPATCH_TIMEOUT,
+            circuit_breaker: Arc::new(DispatchCircuitBreaker::new()),
         }
     }
 
+    /// Limit the number of concurrent sends when fanning out to many connections
+    pub fn with_max_fanout_concurrency(mut self, max_fanout_concurrency: usize) -> Self {
+        self.max_fanout_concurrency = max_fanout_concurrency.max(1);
+        self
+    }
+
+    /// Evict connections whose outbound channel stays full for longer than `send_timeout`
+    pub fn with_send_timeout(mut self, send_timeout: Duration) -> Self {
+        self.send_timeout = send_timeout;
+        self
+    }
+
+    /// Limit the number of dispatches in progress at once
+    pub fn with_max_concurrent_dispatches(mut self, max_concurrent_dispatches: usize) -> Self {
+        self.dispatch_permits = Semaphore::new(max_concurrent_dispatches.max(1));
+        self
+    }
+
+    /// Fail dispatches that can't get a slot within `dispatch_timeout`
+    pub fn with_dispatch_timeout(mut self, dispatch_timeout: Duration) -> Self {
+        self.dispatch_timeout = dispatch_timeout;
+        self
+    }
+
+    /// Use `circuit_breaker` to decide which connection shards to skip
+    pub fn with_circuit_breaker(mut self, circuit_breaker: DispatchCircuitBreaker) -> Self {
+        self.circuit_breaker = Arc::new(circuit_breaker);
+        self
+    }
+
+    /// Record notifications for offline users in `drop_log` when the queue is disabled
+    pub fn with_drop_log(mut self, drop_log: Arc<DropLog>) -> Self {
+        self.drop_log = Some(drop_log);
+        self
+    }
+
     /// Set the queue backend (for deferred initialization)
     pub fn set_queue_backend(&mut self, queue_backend: Arc<dyn MessageQueueBackend>) {
         self.queue_backend = Some(queue_backend);
@@ -147,16 +326,17 @@ impl NotificationDispatcher {
         self.stats.snapshot()
     }
 
+    /// Cache used to deduplicate sends with caller-provided notification IDs
+    pub fn dedup_cache(&self) -> Arc<DeduplicationCache> {
+        self.dedup_cache.clone()
+    }
+
+    /// Shared counter of notifications sent, incremented on every dispatch
+    pub fn sent_counter(&self) -> Arc<AtomicU64> {
+        self.stats.total_sent.clone()
+    }
+
     /// Dispatch a notification to the specified target
-    #[tracing::instrument(
-        name = "dispatcher.dispatch",
-        skip(self, event),
-        fields(
-            notification_id = %event.id,
-            event_type = %event.event_type,
-            target_type = ?std::mem::discriminant(&target)
-        )
-    )]
     pub async fn dispatch(&self, target: NotificationTarget, event: NotificationEvent) -> DeliveryResult {
         self.dispatch_for_tenant(target, event, None).await
     }
@@ -169,22 +349,164 @@ impl NotificationDispatcher {
         event: NotificationEvent,
         tenant_id: Option<&str>,
     ) -> DeliveryResult {
+        self.dispatch_for_tenant_excluding(target, event, tenant_id, &HashSet::new())
+            .await
+    }
+
+    /// Dispatch a notification scoped to a specific tenant, skipping the connections of
+    /// `exclude_user_ids` for channel targets (e.g. so a sender does not receive its own
+    /// broadcast). Exclusions are ignored for user and broadcast targets.
+    ///
+    /// The event's `correlation_id`, if any, is recorded on the dispatch span and
+    /// carried by every span and log entry below it.
+    #[tracing::instrument(
+        name = "dispatcher.dispatch",
+        skip(self, target, event, exclude_user_ids),
+        fields(
+            notification_id = %event.id,
+            event_type = %event.event_type,
+            target_type = ?std::mem::discriminant(&target),
+            tenant_id = ?tenant_id,
+            correlation_id = tracing::field::Empty
+        )
+    )]
+    pub async fn dispatch_for_tenant_excluding(
+        &self,
+        target: NotificationTarget,
+        event: NotificationEvent,
+        tenant_id: Option<&str>,
+        exclude_user_ids: &HashSet<String>,
+    ) -> DeliveryResult {
+        if let Some(correlation_id) = event.correlation_id() {
+            tracing::Span::current().record("correlation_id", correlation_id);
+        }
+
+        let _permit =
+            match tokio::time::timeout(self.dispatch_timeout, self.dispatch_permits.acquire()).await {
+                Ok(Ok(permit)) => permit,
+                _ => {
+                    DISPATCHER_BACKPRESSURE_TOTAL.inc();
+                    tracing::warn!(
+                        notification_id = %event.id,
+                        correlation_id = event.correlation_id(),
+                        "No dispatch slot available, rejecting notification"
+                    );
+                    return DeliveryResult::rejected(event.id, DISPATCH_QUEUE_FULL);
+                }
+            };
+
+        // Only build the audit entry when an audit layer will write it
+        let audit = audit_enabled().then(|| audit_event(&target, &event, tenant_id));
+        let started = Instant::now();
+        let occurred_at = event.occurred_at;
+
         // Skip expired notifications
-        if event.is_expired() {
+        let result = if event.is_expired() {
             tracing::debug!(
                 notification_id = %event.id,
                 "Skipping expired notification"
             );
-            return DeliveryResult::new(event.id, 0, 0);
+            DeliveryResult::new(event.id, 0, 0)
+        } else {
+            MessageMetrics::record_tags(&event.tags);
+            match target {
+                NotificationTarget::User(user_id) => self.send_to_user_for_tenant(&user_id, event, tenant_id).await,
+                NotificationTarget::Users(user_ids) => self.send_to_users_for_tenant(&user_ids, event, tenant_id).await,
+                NotificationTarget::Broadcast => self.broadcast_for_tenant(event, tenant_id).await,
+                NotificationTarget::Channel(channel) => {
+                    self.send_to_channel_excluding(&channel, event, exclude_user_ids).await
+                }
+                NotificationTarget::Channels(channels) => {
+                    self.send_to_channels_excluding(&channels, event, exclude_user_ids).await
+                }
+                NotificationTarget::ChannelPattern(pattern) => {
+                    self.send_to_channel_pattern_excluding(&pattern, event, exclude_user_ids).await
+                }
+                NotificationTarget::Role(role) => self.send_to_role_for_tenant(&role, event, tenant_id).await,
+            }
+        };
+
+        BackendMetrics::record_latency("dispatcher", "deliver", started.elapsed().as_secs_f64());
+        if result.delivered_to > 0 {
+            MessageMetrics::record_delivery_latency(occurred_at);
         }
 
+        if let Some(mut audit) = audit {
+            audit.delivered_to = result.delivered_to;
+            audit.record();
+        }
+        result
+    }
+
+    /// Number of connections a dispatch to `target` would currently reach, without sending
+    pub fn count_target_connections(&self, target: &NotificationTarget, tenant_id: Option<&str>) -> usize {
         match target {
-            NotificationTarget::User(user_id) => self.send_to_user_for_tenant(&user_id, event, tenant_id).await,
-            NotificationTarget::Users(user_ids) => self.send_to_users_for_tenant(&user_ids, event, tenant_id).await,
-            NotificationTarget::Broadcast => self.broadcast_for_tenant(event, tenant_id).await,
-            NotificationTarget::Channel(channel) => self.send_to_channel(&channel, event).await,
-            NotificationTarget::Channels(channels) => self.send_to_channels(&channels, event).await,
+            NotificationTarget::User(user_id) => self.get_tenant_user_connections(user_id, tenant_id).len(),
+            NotificationTarget::Users(user_ids) => user_ids
+                .iter()
+                .map(|user_id| self.get_tenant_user_connections(user_id, tenant_id).len())
+                .sum(),
+            NotificationTarget::Broadcast => match tenant_id {
+                Some(tid) => self.connection_manager.get_tenant_connections(tid).len(),
+                None => self.connection_manager.get_all_connections().len(),
+            },
+            NotificationTarget::Channel(channel) => {
+                self.connection_manager.get_channel_connections(channel).len()
+            }
+            NotificationTarget::Channels(channels) => channels
+                .iter()
+                .flat_map(|channel| self.connection_manager.get_channel_connections(channel))
+                .map(|conn| conn.id)
+                .collect::<HashSet<_>>()
+                .len(),
+            NotificationTarget::ChannelPattern(pattern) => {
+                self.connection_manager.get_connections_for_pattern(pattern).len()
+            }
+            NotificationTarget::Role(role) => self.get_tenant_role_connections(role, tenant_id).len(),
+        }
+    }
+
+    /// Dispatch a notification whose ID was provided by the caller.
+    ///
+    /// A repeated send of the same ID (per tenant) within the deduplication window is
+    /// not delivered again; it returns the result of the first send instead.
+    pub async fn dispatch_idempotent(
+        &self,
+        target: NotificationTarget,
+        event: NotificationEvent,
+        tenant_id: Option<&str>,
+    ) -> DeliveryResult {
+        let notification_id = event.id;
+        self.deduplicate(notification_id, tenant_id, self.dispatch_for_tenant(target, event, tenant_id))
+            .await
+    }
+
+    /// Run `send` for a notification whose ID was provided by the caller, unless a send
+    /// with the same ID (per tenant) already ran within the deduplication window, in
+    /// which case the result of that first send is returned instead.
+    pub async fn deduplicate<F>(&self, notification_id: Uuid, tenant_id: Option<&str>, send: F) -> DeliveryResult
+    where
+        F: std::future::Future<Output = DeliveryResult>,
+    {
+        DEDUP_CALLER_PROVIDED_IDS_TOTAL.inc();
+        let key = crate::auth::tenant_scoped_key(
+            tenant_id.unwrap_or(crate::auth::DEFAULT_TENANT_ID),
+            &notification_id.to_string(),
+        );
+
+        let (result, duplicate) = self.dedup_cache.claim(key);
+        if duplicate {
+            DEDUP_CALLER_DUPLICATES_TOTAL.inc();
+            tracing::debug!(
+                notification_id = %notification_id,
+                "Duplicate notification ID, returning result of the first send"
+            );
         }
+
+        result
+            .get_or_init(|| send)
+            .await
+            .clone()
     }
 
     /// Send notification to a specific user (all their connections)
@@ -206,14 +528,17 @@ impl NotificationDispatcher {
         tenant_id: Option<&str>,
     ) -> DeliveryResult {
         let notification_id = event.id;
-        let connections = self.get_user_connections_filtered(user_id, tenant_id);
+        let connections = self.get_tenant_user_connections(user_id, tenant_id);
 
         // If user has no connections and queue is enabled, queue the message
         if connections.is_empty() {
             if let Some(ref queue) = self.queue_backend {
                 if queue.is_enabled() {
-                    let queue_key = Self::tenant_queue_key(tenant_id, user_id);
-                    match queue.enqueue(&queue_key, event.clone()).await {
+                    let queue_key = Self::tenant_user_key(tenant_id, user_id);
+                    let enqueue = queue
+                        .enqueue(&queue_key, event.clone())
+                        .instrument(enqueue_span(&event));
+                    match enqueue.await {
                         Ok(()) => {
                             tracing::debug!(
                                 user_id = %user_id,
@@ -223,12 +548,14 @@ impl NotificationDispatcher {
                             // Update stats - message was queued, not delivered yet
                             self.stats.total_sent.fetch_add(1, Ordering::Relaxed);
                             self.stats.user_notifications.fetch_add(1, Ordering::Relaxed);
+                            self.stats.record_event_type(&event.event_type, 0);
                             return DeliveryResult::new(notification_id, 0, 0);
                         }
                         Err(e) => {
                             tracing::warn!(
                                 user_id = %user_id,
                                 notification_id = %notification_id,
+                                correlation_id = event.correlation_id(),
                                 error = %e,
                                 "Failed to queue message for offline user"
                             );
@@ -236,16 +563,21 @@ impl NotificationDispatcher {
                     }
                 }
             }
+            if !self.queue_enabled() {
+                self.record_queue_disabled_drop(user_id, &event);
+            }
         }
 
+        let event_type = event.event_type.clone();
         let message = ServerMessage::Notification { event };
-        let (delivered, failed) = self.send_to_connections(&connections, &message, Some(notification_id)).await;
+        let (delivered, failed) = self.send_to_connections(&connections, &message, Some(notification_id), None).await;
 
         // Update stats
         self.stats.total_sent.fetch_add(1, Ordering::Relaxed);
         self.stats.total_delivered.fetch_add(delivered as u64, Ordering::Relaxed);
         self.stats.total_failed.fetch_add(failed as u64, Ordering::Relaxed);
         self.stats.user_notifications.fetch_add(1, Ordering::Relaxed);
+        self.stats.record_event_type(&event_type, delivered);
 
         // Update Prometheus metrics
         MessageMetrics::record_user_sent();
@@ -292,6 +624,8 @@ impl NotificationDispatcher {
         let mut total_delivered = 0;
         let mut total_failed = 0;
         let mut queued_count = 0;
+        let mut per_user_status = HashMap::with_capacity(user_ids.len());
+        let mut delivered_users = HashSet::new();
 
         // Process users in batches to reduce memory pressure
         for batch in user_ids.chunks(USER_BATCH_SIZE) {
@@ -300,7 +634,9 @@ impl NotificationDispatcher {
             let mut offline_users: Vec<&str> = Vec::new();
 
             for user_id in batch {
-                let connections = self.get_user_connections_filtered(user_id, tenant_id);
+                // Users not reached below keep this status
+                per_user_status.insert(user_id.clone(), UserDeliveryStatus::NoConnection);
+                let connections = self.get_tenant_user_connections(user_id, tenant_id);
                 if connections.is_empty() {
                     offline_users.push(user_id);
                 } else {
@@ -308,15 +644,32 @@ impl NotificationDispatcher {
                 }
             }
 
+            if !offline_users.is_empty() && !self.queue_enabled() {
+                for user_id in &offline_users {
+                    self.record_queue_disabled_drop(user_id, &event);
+                }
+            }
+
             // Queue messages for offline users (if queue is enabled)
             if !offline_users.is_empty() {
                 if let Some(ref queue) = self.queue_backend {
                     if queue.is_enabled() {
                         for user_id in offline_users {
-                            let queue_key = Self::tenant_queue_key(tenant_id, user_id);
-                            if queue.enqueue(&queue_key, event.clone()).await.is_ok() {
-                                queued_count += 1;
-                            }
+                            let queue_key = Self::tenant_user_key(tenant_id, user_id);
+                            let enqueue = queue
+                                .enqueue(&queue_key, event.clone())
+                                .instrument(enqueue_span(&event));
+                            let status = match enqueue.await {
+                                Ok(()) => {
+                                    queued_count += 1;
+                                    UserDeliveryStatus::Queued
+                                }
+                                Err(QueueBackendError::QueueFull { .. }) => {
+                                    UserDeliveryStatus::RateLimited
+                                }
+                                Err(_) => UserDeliveryStatus::NoConnection,
+                            };
+                            per_user_status.insert(user_id.to_string(), status);
                         }
                     }
                 }
@@ -324,17 +677,29 @@ impl NotificationDispatcher {
 
             // Send to all connections in this batch concurrently
             if !batch_connections.is_empty() {
-                let (delivered, failed) = self.send_to_connections(&batch_connections, &message, Some(notification_id)).await;
+                let (delivered, failed) = self
+                    .send_to_connections(
+                        &batch_connections,
+                        &message,
+                        Some(notification_id),
+                        Some(&mut delivered_users),
+                    )
+                    .await;
                 total_delivered += delivered;
                 total_failed += failed;
             }
         }
 
+        for user_id in delivered_users {
+            per_user_status.insert(user_id, UserDeliveryStatus::Delivered);
+        }
+
         // Update stats
         self.stats.total_sent.fetch_add(1, Ordering::Relaxed);
         self.stats.total_delivered.fetch_add(total_delivered as u64, Ordering::Relaxed);
         self.stats.total_failed.fetch_add(total_failed as u64, Ordering::Relaxed);
         self.stats.user_notifications.fetch_add(1, Ordering::Relaxed);
+        self.stats.record_event_type(&event.event_type, total_delivered);
 
         // Update Prometheus metrics
         MessageMetrics::record_users_sent();
@@ -350,7 +715,10 @@ impl NotificationDispatcher {
             "Sent notification to multiple users"
         );
 
-        DeliveryResult::new(notification_id, total_delivered, total_failed)
+        DeliveryResult {
+            per_user_status: Some(per_user_status),
+            ..DeliveryResult::new(notification_id, total_delivered, total_failed)
+        }
     }
 
     /// Broadcast notification to all connected users
@@ -375,15 +743,17 @@ impl NotificationDispatcher {
             Some(tid) => self.connection_manager.get_tenant_connections(tid),
             None => self.connection_manager.get_all_connections(),
         };
+        let event_type = event.event_type.clone();
         let message = ServerMessage::Notification { event };
 
-        let (delivered, failed) = self.send_to_connections(&connections, &message, Some(notification_id)).await;
+        let (delivered, failed) = self.send_to_connections(&connections, &message, Some(notification_id), None).await;
 
         // Update stats
         self.stats.total_sent.fetch_add(1, Ordering::Relaxed);
         self.stats.total_delivered.fetch_add(delivered as u64, Ordering::Relaxed);
         self.stats.total_failed.fetch_add(failed as u64, Ordering::Relaxed);
         self.stats.broadcast_notifications.fetch_add(1, Ordering::Relaxed);
+        self.stats.record_event_type(&event_type, delivered);
 
         // Update Prometheus metrics
         MessageMetrics::record_broadcast_sent();
@@ -400,24 +770,94 @@ impl NotificationDispatcher {
         DeliveryResult::new(notification_id, delivered, failed)
     }
 
-    /// Send notification to a specific channel
+    /// Send notification to every connected user holding `role`
     #[tracing::instrument(
-        name = "dispatcher.send_to_channel",
+        name = "dispatcher.send_to_role",
         skip(self, event),
         fields(notification_id = %event.id, event_type = %event.event_type)
     )]
+    pub async fn send_to_role(&self, role: &str, event: NotificationEvent) -> DeliveryResult {
+        self.send_to_role_for_tenant(role, event, None).await
+    }
+
+    /// Send notification to the users holding `role`, filtered by tenant
+    async fn send_to_role_for_tenant(
+        &self,
+        role: &str,
+        event: NotificationEvent,
+        tenant_id: Option<&str>,
+    ) -> DeliveryResult {
+        let notification_id = event.id;
+        let connections = self.get_tenant_role_connections(role, tenant_id);
+        let event_type = event.event_type.clone();
+        let message = ServerMessage::Notification { event };
+
+        let (delivered, failed) = self.send_to_connections(&connections, &message, Some(notification_id), None).await;
+
+        // Update stats (a role send is a broadcast filtered by role)
+        self.stats.total_sent.fetch_add(1, Ordering::Relaxed);
+        self.stats.total_delivered.fetch_add(delivered as u64, Ordering::Relaxed);
+        self.stats.total_failed.fetch_add(failed as u64, Ordering::Relaxed);
+        self.stats.broadcast_notifications.fetch_add(1, Ordering::Relaxed);
+        self.stats.record_event_type(&event_type, delivered);
+
+        // Update Prometheus metrics
+        MessageMetrics::record_role_sent();
+        MessageMetrics::record_delivered(delivered as u64);
+        MessageMetrics::record_failed(failed as u64);
+
+        tracing::debug!(
+            role = %role,
+            notification_id = %notification_id,
+            delivered = delivered,
+            failed = failed,
+            "Sent notification to role"
+        );
+
+        DeliveryResult::new(notification_id, delivered, failed)
+    }
+
+    /// Get the connections holding `role`, limited to the tenant if one is given
+    fn get_tenant_role_connections(&self, role: &str, tenant_id: Option<&str>) -> Vec<Arc<ConnectionHandle>> {
+        let mut connections = self.connection_manager.get_connections_by_role(role);
+        if let Some(tid) = tenant_id {
+            connections.retain(|conn| conn.tenant_id == tid);
+        }
+        connections
+    }
+
+    /// Send notification to a specific channel
     pub async fn send_to_channel(&self, channel: &str, event: NotificationEvent) -> DeliveryResult {
+        self.send_to_channel_excluding(channel, event, &HashSet::new()).await
+    }
+
+    /// Send notification to a specific channel, skipping connections of excluded users
+    #[tracing::instrument(
+        name = "dispatcher.send_to_channel",
+        skip(self, event, exclude_user_ids),
+        fields(notification_id = %event.id, event_type = %event.event_type)
+    )]
+    pub async fn send_to_channel_excluding(
+        &self,
+        channel: &str,
+        event: NotificationEvent,
+        exclude_user_ids: &HashSet<String>,
+    ) -> DeliveryResult {
         let notification_id = event.id;
-        let connections = self.connection_manager.get_channel_connections(channel);
+        let connections = self
+            .connection_manager
+            .get_channel_connections_excluding(channel, exclude_user_ids);
+        let event_type = event.event_type.clone();
         let message = ServerMessage::Notification { event };
 
-        let (delivered, failed) = self.send_to_connections(&connections, &message, Some(notification_id)).await;
+        let (delivered, failed) = self.send_to_connections(&connections, &message, Some(notification_id), None).await;
 
         // Update stats
         self.stats.total_sent.fetch_add(1, Ordering::Relaxed);
         self.stats.total_delivered.fetch_add(delivered as u64, Ordering::Relaxed);
         self.stats.total_failed.fetch_add(failed as u64, Ordering::Relaxed);
         self.stats.channel_notifications.fetch_add(1, Ordering::Relaxed);
+        self.stats.record_event_type(&event_type, delivered);
 
         // Update Prometheus metrics
         MessageMetrics::record_channel_sent();
@@ -436,38 +876,53 @@ impl NotificationDispatcher {
     }
 
     /// Send notification to multiple channels
+    pub async fn send_to_channels(&self, channels: &[String], event: NotificationEvent) -> DeliveryResult {
+        self.send_to_channels_excluding(channels, event, &HashSet::new()).await
+    }
+
+    /// Send notification to multiple channels, skipping connections of excluded users
     #[tracing::instrument(
         name = "dispatcher.send_to_channels",
-        skip(self, event, channels),
+        skip(self, event, channels, exclude_user_ids),
         fields(
             notification_id = %event.id,
             event_type = %event.event_type,
             channel_count = channels.len()
         )
     )]
-    pub async fn send_to_channels(&self, channels: &[String], event: NotificationEvent) -> DeliveryResult {
+    pub async fn send_to_channels_excluding(
+        &self,
+        channels: &[String],
+        event: NotificationEvent,
+        exclude_user_ids: &HashSet<String>,
+    ) -> DeliveryResult {
         let notification_id = event.id;
+        let event_type = event.event_type.clone();
         let message = ServerMessage::Notification { event };
 
         // Collect unique connections from all channels
-        let mut seen_connections = std::collections::HashSet::new();
+        let mut seen_connections = HashSet::new();
         let mut all_connections = Vec::new();
 
         for channel in channels {
-            for conn in self.connection_manager.get_channel_connections(channel) {
+            for conn in self
+                .connection_manager
+                .get_channel_connections_excluding(channel, exclude_user_ids)
+            {
                 if seen_connections.insert(conn.id) {
                     all_connections.push(conn);
                 }
             }
         }
 
-        let (delivered, failed) = self.send_to_connections(&all_connections, &message, Some(notification_id)).await;
+        let (delivered, failed) = self.send_to_connections(&all_connections, &message, Some(notification_id), None).await;
 
         // Update stats
         self.stats.total_sent.fetch_add(1, Ordering::Relaxed);
         self.stats.total_delivered.fetch_add(delivered as u64, Ordering::Relaxed);
         self.stats.total_failed.fetch_add(failed as u64, Ordering::Relaxed);
         self.stats.channel_notifications.fetch_add(1, Ordering::Relaxed);
+        self.stats.record_event_type(&event_type, delivered);
 
         // Update Prometheus metrics
         MessageMetrics::record_channels_sent();
@@ -485,60 +940,152 @@ impl NotificationDispatcher {
         DeliveryResult::new(notification_id, delivered, failed)
     }
 
-    /// Build a queue key that includes tenant scope when provided.
-    fn tenant_queue_key(tenant_id: Option<&str>, user_id: &str) -> String {
+    /// Send notification to every channel matching a glob pattern
+    pub async fn send_to_channel_pattern(&self, pattern: &str, event: NotificationEvent) -> DeliveryResult {
+        self.send_to_channel_pattern_excluding(pattern, event, &HashSet::new()).await
+    }
+
+    /// Send notification to every channel matching a glob pattern, skipping
+    /// connections of excluded users
+    #[tracing::instrument(
+        name = "dispatcher.send_to_channel_pattern",
+        skip(self, event, exclude_user_ids),
+        fields(notification_id = %event.id, event_type = %event.event_type)
+    )]
+    pub async fn send_to_channel_pattern_excluding(
+        &self,
+        pattern: &str,
+        event: NotificationEvent,
+        exclude_user_ids: &HashSet<String>,
+    ) -> DeliveryResult {
+        let notification_id = event.id;
+        let event_type = event.event_type.clone();
+        let message = ServerMessage::Notification { event };
+
+        let mut connections = self.connection_manager.get_connections_for_pattern(pattern);
+        if !exclude_user_ids.is_empty() {
+            connections.retain(|conn| !exclude_user_ids.contains(&conn.user_id));
+        }
+
+        let (delivered, failed) = self.send_to_connections(&connections, &message, Some(notification_id), None).await;
+
+        // Update stats
+        self.stats.total_sent.fetch_add(1, Ordering::Relaxed);
+        self.stats.total_delivered.fetch_add(delivered as u64, Ordering::Relaxed);
+        self.stats.total_failed.fetch_add(failed as u64, Ordering::Relaxed);
+        self.stats.channel_notifications.fetch_add(1, Ordering::Relaxed);
+        self.stats.record_event_type(&event_type, delivered);
+
+        // Update Prometheus metrics
+        MessageMetrics::record_channel_pattern_sent(pattern);
+        MessageMetrics::record_delivered(delivered as u64);
+        MessageMetrics::record_failed(failed as u64);
+
+        tracing::debug!(
+            pattern = %pattern,
+            notification_id = %notification_id,
+            delivered = delivered,
+            failed = failed,
+            "Sent notification to channel pattern"
+        );
+
+        DeliveryResult::new(notification_id, delivered, failed)
+    }
+
+    /// Key for per-user state (connections, queue), namespaced by tenant.
+    /// Without a tenant the default tenant is used.
+    fn tenant_user_key(tenant_id: Option<&str>, user_id: &str) -> String {
         crate::auth::tenant_scoped_key(
             tenant_id.unwrap_or(crate::auth::DEFAULT_TENANT_ID),
             user_id,
         )
     }
 
-    /// Get user connections optionally filtered by tenant_id
-    fn get_user_connections_filtered(
+    /// Get the user's connections within the tenant (default tenant if none given).
+    /// Filters on the tenant too, since a default-tenant user ID containing ':' can
+    /// share a key with another tenant's user.
+    fn get_tenant_user_connections(
         &self,
         user_id: &str,
         tenant_id: Option<&str>,
     ) -> Vec<Arc<ConnectionHandle>> {
-        let connections = self.connection_manager.get_user_connections(user_id);
-        match tenant_id {
-            Some(tid) => connections
-                .into_iter()
-                .filter(|c| c.tenant_id == tid)
-                .collect(),
-            None => connections,
-        }
+        let tenant_id = tenant_id.unwrap_or(crate::auth::DEFAULT_TENANT_ID);
+        self.connection_manager
+            .get_user_connections(&Self::tenant_user_key(Some(tenant_id), user_id))
+            .into_iter()
+            .filter(|c| c.tenant_id == tenant_id)
+            .collect()
     }
 
     /// Send message to a list of connections concurrently
-    /// Uses bounded parallelism to avoid overwhelming the system
+    /// Uses bounded parallelism (`max_fanout_concurrency`) to avoid overwhelming the system
     /// Pre-serializes the message once for larger sends to avoid repeated serialization
     /// If notification_id is provided and ack_tracker is configured, tracks pending ACKs
+    /// Sends that exceed `send_timeout` evict the connection and count as failed
+    /// Connections reached after the notification's TTL has passed are skipped
+    /// Connections in a shard whose dispatch circuit is open are skipped and count as failed
     async fn send_to_connections(
         &self,
         connections: &[Arc<ConnectionHandle>],
         message: &ServerMessage,
         notification_id: Option<Uuid>,
+        mut delivered_users: Option<&mut HashSet<String>>,
     ) -> (usize, usize) {
         if connections.is_empty() {
             return (0, 0);
         }
 
+        // Notifications can expire while waiting on slow connections or fan-out permits
+        let (expires_at, occurred_at, event_type, correlation_id) = match message {
+            ServerMessage::Notification { event } => (
+                event.expires_at(),
+                Some(event.occurred_at),
+                event.event_type.as_str(),
+                event.correlation_id(),
+            ),
+            _ => (None, None, "", None),
+        };
+        let mut expired = 0;
+        // End-to-end latency is recorded once per dispatch, so a multi-user send whose
+        // earlier batches already delivered does not record it again
+        let first_delivery_recorded = Arc::new(AtomicBool::new(
+            delivered_users.as_ref().is_some_and(|users| !users.is_empty()),
+        ));
+
         // For small number of connections, use simple sequential sending without pre-serialization
         if connections.len() <= 3 {
             let mut delivered = 0;
             let mut failed = 0;
             for conn in connections {
-                match conn.send(message.clone()).await {
-                    Ok(_) => {
-                        delivered += 1;
-                        // Track ACK if enabled
-                        if let (Some(notif_id), Some(tracker)) = (notification_id, &self.ack_backend) {
-                            tracker.track(notif_id, &conn.user_id, conn.id).await;
-                        }
+                if is_past(expires_at) {
+                    expired += 1;
+                    continue;
+                }
+                if !self.circuit_breaker.allows(&conn.user_id) {
+                    failed += 1;
+                    continue;
+                }
+                let msg = OutboundMessage::Raw(message.clone());
+                let sent = send_or_evict(&self.connection_manager, conn, msg, self.send_timeout).await;
+                self.circuit_breaker.record(&conn.user_id, sent);
+                if sent {
+                    record_first_delivery(occurred_at, &first_delivery_recorded);
+                    delivered += 1;
+                    if let Some(users) = delivered_users.as_deref_mut() {
+                        users.insert(conn.user_id.clone());
                     }
-                    Err(_) => failed += 1,
+                    // Track ACK if enabled
+                    if let (Some(notif_id), Some(tracker)) = (notification_id, &self.ack_backend) {
+                        tracker
+                            .track(notif_id, &conn.namespaced_user_id(), conn.id, event_type)
+                            .instrument(ack_track_span(correlation_id))
+                            .await;
+                    }
+                } else {
+                    failed += 1;
                 }
             }
+            record_expired_at_delivery(notification_id, expired);
             return (delivered, failed);
         }
 
@@ -556,59 +1103,255 @@ impl NotificationDispatcher {
             OutboundMessage::Raw(message.clone())
         };
 
-        // For larger number of connections, use concurrent sending with bounded parallelism
-        // We need to track which connections succeeded for ACK tracking
-        let mut futures = FuturesUnordered::new();
+        // Pre-serialized notifications lose the event, so MessagePack ones are also
+        // encoded once for the connections that opted in to binary frames
+        let binary_outbound = if matches!(outbound, OutboundMessage::Serialized(_))
+            && message.prefers_msgpack()
+            && connections.iter().any(|conn| conn.accepts_msgpack())
+        {
+            match OutboundMessage::msgpack(message) {
+                Ok(msg) => Some(msg),
+                Err(e) => {
+                    tracing::error!(error = %e, "Failed to MessagePack-encode message, sending JSON instead");
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        // For larger number of connections, send concurrently with at most
+        // `max_fanout_concurrency` sends in flight; each task holds a semaphore permit
+        let semaphore = Arc::new(Semaphore::new(self.max_fanout_concurrency));
+        let inflight = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let mut tasks = JoinSet::new();
         let mut delivered = 0;
         let mut failed = 0;
-        let mut pending = 0;
 
         for conn in connections {
+            let permit = semaphore
+                .clone()
+                .acquire_owned()
+                .await
+                .expect("fan-out semaphore is never closed");
+            if is_past(expires_at) {
+                expired += 1;
+                continue;
+            }
+            if !self.circuit_breaker.allows(&conn.user_id) {
+                failed += 1;
+                continue;
+            }
+            let msg = match binary_outbound {
+                Some(ref binary) if conn.accepts_msgpack() => binary.clone(),
+                _ => outbound.clone(),
+            };
             let conn = conn.clone();
-            let msg = outbound.clone();
+            let manager = self.connection_manager.clone();
+            let send_timeout = self.send_timeout;
+            let circuit_breaker = self.circuit_breaker.clone();
+            let inflight = inflight.clone();
+            let peak = peak.clone();
+            let first_delivery_recorded = first_delivery_recorded.clone();
             // Return the connection on success so we can track ACKs
-            futures.push(async move {
-                match conn.send_preserialized(msg).await {
-                    Ok(_) => Some(conn),
-                    Err(_) => None,
+            tasks.spawn(async move {
+                let _permit = permit;
+                peak.fetch_max(inflight.fetch_add(1, Ordering::Relaxed) + 1, Ordering::Relaxed);
+                BROADCAST_FANOUT_INFLIGHT.inc();
+                let sent = send_or_evict(&manager, &conn, msg, send_timeout).await;
+                circuit_breaker.record(&conn.user_id, sent);
+                BROADCAST_FANOUT_INFLIGHT.dec();
+                inflight.fetch_sub(1, Ordering::Relaxed);
+                if sent {
+                    record_first_delivery(occurred_at, &first_delivery_recorded);
                 }
-            });
-            pending += 1;
-
-            // Process completed futures when we hit the concurrency limit
-            while pending >= MAX_CONCURRENT_SENDS {
-                if let Some(result) = futures.next().await {
-                    pending -= 1;
-                    match result {
-                        Some(conn) => {
-                            delivered += 1;
-                            if let (Some(notif_id), Some(tracker)) = (notification_id, &self.ack_backend) {
-                                tracker.track(notif_id, &conn.user_id, conn.id).await;
-                            }
-                        }
-                        None => failed += 1,
+                sent.then_some(conn)
+            }.in_current_span());
+
+            // Collect finished sends as we go so results don't pile up
+            while let Some(result) = tasks.try_join_next() {
+                if let Some(conn) = self.record_fanout_result(result, notification_id, event_type, correlation_id, &mut delivered, &mut failed).await {
+                    if let Some(users) = delivered_users.as_deref_mut() {
+                        users.insert(conn.user_id.clone());
                     }
-                } else {
-                    break;
                 }
             }
         }
 
-        // Process remaining futures
-        while let Some(result) = futures.next().await {
-            match result {
-                Some(conn) => {
-                    delivered += 1;
-                    if let (Some(notif_id), Some(tracker)) = (notification_id, &self.ack_backend) {
-                        tracker.track(notif_id, &conn.user_id, conn.id).await;
-                    }
+        while let Some(result) = tasks.join_next().await {
+            if let Some(conn) = self.record_fanout_result(result, notification_id, event_type, correlation_id, &mut delivered, &mut failed).await {
+                if let Some(users) = delivered_users.as_deref_mut() {
+                    users.insert(conn.user_id.clone());
                 }
-                None => failed += 1,
             }
         }
 
+        self.stats
+            .peak_fanout_inflight
+            .fetch_max(peak.load(Ordering::Relaxed), Ordering::Relaxed);
+        record_expired_at_delivery(notification_id, expired);
+
         (delivered, failed)
     }
+
+    /// Whether an enabled offline queue is configured
+    fn queue_enabled(&self) -> bool {
+        self.queue_backend.as_ref().is_some_and(|queue| queue.is_enabled())
+    }
+
+    /// Count a notification for an offline user dropped because the queue is disabled,
+    /// and record it in the drop log if one is configured
+    fn record_queue_disabled_drop(&self, user_id: &str, event: &NotificationEvent) {
+        NOTIFICATIONS_DROPPED_QUEUE_DISABLED_TOTAL.inc();
+        let Some(ref drop_log) = self.drop_log else {
+            return;
+        };
+        tracing::warn!(
+            user_id = %user_id,
+            notification_id = %event.id,
+            event_type = %event.event_type,
+            correlation_id = event.correlation_id(),
+            "User offline and queue disabled, notification dropped"
+        );
+        drop_log.record(DroppedNotification {
+            notification_id: event.id,
+            user_id: user_id.to_string(),
+            event_type: event.event_type.clone(),
+            timestamp: chrono::Utc::now(),
+            reason: DropReason::QueueDisabled,
+        });
+    }
+
+    /// Count one fan-out send and track its ACK if it was delivered,
+    /// returning the connection it was delivered to
+    async fn record_fanout_result(
+        &self,
+        result: Result<Option<Arc<ConnectionHandle>>, tokio::task::JoinError>,
+        notification_id: Option<Uuid>,
+        event_type: &str,
+        correlation_id: Option<&str>,
+        delivered: &mut usize,
+        failed: &mut usize,
+    ) -> Option<Arc<ConnectionHandle>> {
+        match result {
+            Ok(Some(conn)) => {
+                *delivered += 1;
+                if let (Some(notif_id), Some(tracker)) = (notification_id, &self.ack_backend) {
+                    tracker
+                        .track(notif_id, &conn.namespaced_user_id(), conn.id, event_type)
+                        .instrument(ack_track_span(correlation_id))
+                        .await;
+                }
+                Some(conn)
+            }
+            Ok(None) => {
+                *failed += 1;
+                None
+            }
+            Err(e) => {
+                tracing::error!(error = %e, "Fan-out send task failed");
+                *failed += 1;
+                None
+            }
+        }
+    }
+}
+
+/// Send to one connection, evicting it if its outbound channel stays full past `timeout`
+async fn send_or_evict(
+    manager: &ConnectionManager,
+    conn: &ConnectionHandle,
+    message: OutboundMessage,
+    timeout: Duration,
+) -> bool {
+    match tokio::time::timeout(timeout, conn.send_preserialized(message)).await {
+        Ok(result) => result.is_ok(),
+        Err(_) => {
+            WS_SEND_TIMEOUTS_TOTAL.inc();
+            tracing::warn!(
+                connection_id = %conn.id,
+                user_id = %conn.user_id,
+                timeout_ms = timeout.as_millis() as u64,
+                "Connection send timed out"
+            );
+            manager.evict_connection(conn.id, "send_timeout").await;
+            false
+        }
+    }
+}
+
+/// Span for queueing a notification for an offline user
+fn enqueue_span(event: &NotificationEvent) -> tracing::Span {
+    tracing::info_span!(
+        "queue.enqueue",
+        notification_id = %event.id,
+        correlation_id = event.correlation_id()
+    )
+}
+
+/// Span for registering a delivered notification with the ACK tracker
+fn ack_track_span(correlation_id: Option<&str>) -> tracing::Span {
+    tracing::info_span!("ack.track", correlation_id = correlation_id)
+}
+
+/// Whether an expiry time, if any, has passed
+fn is_past(expires_at: Option<DateTime<Utc>>) -> bool {
+    expires_at.is_some_and(|expiry| Utc::now() > expiry)
+}
+
+/// Record the end-to-end latency of a notification's first successful delivery
+fn record_first_delivery(occurred_at: Option<DateTime<Utc>>, recorded: &AtomicBool) {
+    if let Some(occurred_at) = occurred_at {
+        if !recorded.swap(true, Ordering::Relaxed) {
+            MessageMetrics::record_e2e_latency("live", occurred_at);
+        }
+    }
+}
+
+/// Count connections skipped because the notification expired before reaching them
+fn record_expired_at_delivery(notification_id: Option<Uuid>, expired: usize) {
+    if expired == 0 {
+        return;
+    }
+    NOTIFICATIONS_EXPIRED_AT_DELIVERY_TOTAL.inc_by(expired as u64);
+    tracing::debug!(
+        notification_id = ?notification_id,
+        skipped_connections = expired,
+        "Notification expired at delivery time"
+    );
+}
+
+/// Audit entry for a dispatch; `delivered_to` is filled in once delivery completes
+fn audit_event(
+    target: &NotificationTarget,
+    event: &NotificationEvent,
+    tenant_id: Option<&str>,
+) -> AuditEvent {
+    let (target_type, user_id_or_channel) = match target {
+        NotificationTarget::User(user_id) => ("user", user_id.clone()),
+        NotificationTarget::Users(user_ids) => ("users", user_ids.join(",")),
+        NotificationTarget::Broadcast => ("broadcast", "*".to_string()),
+        NotificationTarget::Channel(channel) => ("channel", channel.clone()),
+        NotificationTarget::Channels(channels) => ("channels", channels.join(",")),
+        NotificationTarget::ChannelPattern(pattern) => ("channel_pattern", pattern.clone()),
+        NotificationTarget::Role(role) => ("role", role.clone()),
+    };
+
+    AuditEvent {
+        notification_id: event.id,
+        target_type: target_type.to_string(),
+        user_id_or_channel,
+        source: event.metadata.source.clone(),
+        event_type: event.event_type.clone(),
+        priority: format!("{:?}", event.metadata.priority),
+        delivered_to: 0,
+        tenant_id: tenant_id.map(str::to_string),
+        timestamp: chrono::Utc::now(),
+        correlation_id: event.metadata.correlation_id.clone(),
+        caller_id: event.caller_id().map(str::to_string),
+        on_behalf_of: event.metadata.impersonated_by.clone(),
+    }
 }
 
 #[cfg(test)]
@@ -636,4 +1379,839 @@ mod tests {
         assert_eq!(snapshot.total_sent, 10);
         assert_eq!(snapshot.total_delivered, 25);
     }
+
+    #[test]
+    fn test_event_type_stats_capped() {
+        let stats = DispatcherStats::default();
+        for i in 0..MAX_TRACKED_EVENT_TYPES {
+            stats.record_event_type(&format!("type.{}", i), 1);
+        }
+        stats.record_event_type("overflow.a", 2);
+        stats.record_event_type("overflow.b", 3);
+        stats.record_event_type("type.0", 1);
+
+        let snapshot = stats.snapshot();
+        assert_eq!(snapshot.event_type_counts.len(), MAX_TRACKED_EVENT_TYPES + 1);
+        assert_eq!(snapshot.event_type_counts[OTHER_EVENT_TYPE], 2);
+        assert_eq!(snapshot.event_type_delivered[OTHER_EVENT_TYPE], 5);
+        assert_eq!(snapshot.event_type_counts["type.0"], 2);
+        assert!(!snapshot.event_type_counts.contains_key("overflow.a"));
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_writes_audit_event() {
+        use std::sync::Mutex;
+
+        use tokio::sync::mpsc;
+        use tracing_subscriber::{layer::SubscriberExt, EnvFilter, Layer};
+
+        use crate::audit::AuditLogger;
+        use crate::notification::{NotificationBuilder, Priority};
+
+        /// Appends audit lines to a shared buffer
+        struct Capture(Arc<Mutex<Vec<u8>>>);
+
+        impl std::io::Write for Capture {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.lock().unwrap().extend_from_slice(buf);
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let buffer = Arc::new(Mutex::new(Vec::new()));
+        let writer = {
+            let buffer = buffer.clone();
+            move || Capture(buffer.clone())
+        };
+        // A RUST_LOG filter that silences the application log must not suppress audit entries
+        let subscriber = tracing_subscriber::registry()
+            .with(AuditLogger::with_writer(writer).with_filter(AuditLogger::filter()))
+            .with(
+                tracing_subscriber::fmt::layer()
+                    .with_writer(std::io::sink)
+                    .with_filter(EnvFilter::new("off")),
+            );
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let manager = Arc::new(ConnectionManager::new());
+        let (tx, _rx) = mpsc::channel(8);
+        manager
+            .register("user-1".to_string(), "acme".to_string(), vec![], tx)
+            .unwrap();
+        let dispatcher = NotificationDispatcher::new(manager);
+
+        let event = NotificationBuilder::new("order.created", "orders-service")
+            .priority(Priority::High)
+            .correlation_id("req-123")
+            .build();
+        let notification_id = event.id;
+        let result = dispatcher
+            .dispatch_for_tenant(NotificationTarget::User("user-1".to_string()), event, Some("acme"))
+            .await;
+        assert_eq!(result.delivered_to, 1);
+
+        let output = String::from_utf8(buffer.lock().unwrap().clone()).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 1);
+        let entry: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(entry["notification_id"], notification_id.to_string());
+        assert_eq!(entry["target_type"], "user");
+        assert_eq!(entry["user_id_or_channel"], "user-1");
+        assert_eq!(entry["source"], "orders-service");
+        assert_eq!(entry["event_type"], "order.created");
+        assert_eq!(entry["priority"], "High");
+        assert_eq!(entry["delivered_to"], 1);
+        assert_eq!(entry["tenant_id"], "acme");
+        assert_eq!(entry["correlation_id"], "req-123");
+        assert!(entry["timestamp"].as_str().unwrap().parse::<chrono::DateTime<chrono::Utc>>().is_ok());
+        assert!(entry.get("caller_id").is_none());
+        assert!(entry.get("on_behalf_of").is_none());
+    }
+
+    #[test]
+    fn test_audit_skipped_without_audit_layer() {
+        use tracing_subscriber::{layer::SubscriberExt, EnvFilter, Layer};
+
+        use crate::audit::AuditLogger;
+
+        let console_only = tracing_subscriber::registry().with(
+            tracing_subscriber::fmt::layer()
+                .with_writer(std::io::sink)
+                .with_filter(EnvFilter::new("info,audit=off")),
+        );
+        tracing::subscriber::with_default(console_only, || assert!(!audit_enabled()));
+
+        let with_audit = tracing_subscriber::registry()
+            .with(AuditLogger::with_writer(std::io::sink).with_filter(AuditLogger::filter()));
+        tracing::subscriber::with_default(with_audit, || assert!(audit_enabled()));
+    }
+
+    #[test]
+    fn test_audit_event_records_impersonation() {
+        use crate::notification::{NotificationBuilder, CALLER_ID_HEADER};
+
+        let event = NotificationBuilder::new("invoice.paid", "http-api")
+            .impersonated_by("alice")
+            .header(CALLER_ID_HEADER, "billing-service")
+            .build();
+        let audit = audit_event(&NotificationTarget::User("alice".to_string()), &event, None);
+
+        assert_eq!(audit.caller_id.as_deref(), Some("billing-service"));
+        assert_eq!(audit.on_behalf_of.as_deref(), Some("alice"));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_fanout_concurrency_is_bounded() {
+        use std::time::Duration;
+
+        use tokio::sync::mpsc;
+
+        use crate::notification::NotificationBuilder;
+
+        let manager = Arc::new(ConnectionManager::new());
+        for i in 0..2000 {
+            // Full single-slot channels: each send waits until its reader drains the backlog
+            let (tx, mut rx) = mpsc::channel(1);
+            tx.try_send(OutboundMessage::Raw(ServerMessage::Pong)).unwrap();
+            manager
+                .register(format!("user-{}", i), "default".to_string(), vec![], tx)
+                .unwrap();
+            tokio::spawn(async move {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                while rx.recv().await.is_some() {}
+            });
+        }
+        let dispatcher = NotificationDispatcher::new(manager).with_max_fanout_concurrency(100);
+
+        let result = dispatcher
+            .broadcast(NotificationBuilder::new("system.announcement", "test").build())
+            .await;
+
+        assert_eq!(result.delivered_to, 2000);
+        assert_eq!(result.failed, 0);
+        let peak = dispatcher.stats().peak_fanout_inflight;
+        assert!(peak > 1, "sends should run concurrently (peak {})", peak);
+        assert!(peak <= 100, "peak in-flight sends {} exceeded the limit", peak);
+    }
+
+    #[tokio::test]
+    async fn test_circuit_opens_for_saturated_connections() {
+        use std::time::Duration;
+
+        use tokio::sync::mpsc;
+
+        use crate::metrics::DISPATCH_CIRCUIT_OPEN;
+        use crate::notification::NotificationBuilder;
+
+        let manager = Arc::new(ConnectionManager::new());
+        let shard = DispatchCircuitBreaker::shard_for("slow-consumer");
+        let slow_users: Vec<String> = (0..)
+            .map(|i| format!("slow-consumer-{}", i))
+            .filter(|user| DispatchCircuitBreaker::shard_for(user) == shard)
+            .take(12)
+            .collect();
+        // Full single-slot channels nobody reads from
+        let mut receivers = Vec::new();
+        for user in &slow_users {
+            let (tx, rx) = mpsc::channel(1);
+            tx.try_send(OutboundMessage::Raw(ServerMessage::Pong)).unwrap();
+            let conn = manager
+                .register(user.clone(), "default".to_string(), vec![], tx)
+                .unwrap();
+            manager.subscribe_to_channel(conn.id, "feed").await.unwrap();
+            receivers.push(rx);
+        }
+        let (healthy_tx, mut healthy_rx) = mpsc::channel(8);
+        manager
+            .register("healthy-user".to_string(), "default".to_string(), vec![], healthy_tx)
+            .unwrap();
+        let dispatcher = NotificationDispatcher::new(manager.clone())
+            .with_send_timeout(Duration::from_millis(10));
+        let event = || NotificationBuilder::new("order.created", "test").build();
+
+        let result = dispatcher
+            .dispatch(NotificationTarget::Channel("feed".to_string()), event())
+            .await;
+        assert_eq!(result.failed, 12);
+        assert!(dispatcher.circuit_breaker.is_open(shard));
+        assert_eq!(
+            DISPATCH_CIRCUIT_OPEN.with_label_values(&[&shard.to_string()]).get(),
+            1
+        );
+
+        // A fresh connection in the open shard is skipped without being sent to
+        let (tx, mut rx) = mpsc::channel(8);
+        let conn = manager
+            .register(slow_users[0].clone(), "default".to_string(), vec![], tx)
+            .unwrap();
+        manager.subscribe_to_channel(conn.id, "feed").await.unwrap();
+        let result = dispatcher
+            .dispatch(NotificationTarget::Channel("feed".to_string()), event())
+            .await;
+        assert_eq!(result.delivered_to, 0);
+        assert_eq!(result.failed, 1);
+        assert!(rx.try_recv().is_err());
+
+        // Users in other shards are unaffected
+        let result = dispatcher
+            .dispatch(NotificationTarget::User("healthy-user".to_string()), event())
+            .await;
+        assert_eq!(result.delivered_to, 1);
+        assert!(healthy_rx.try_recv().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_rejected_when_slots_exhausted() {
+        use std::time::Duration;
+
+        use tokio::sync::mpsc;
+
+        use crate::notification::NotificationBuilder;
+
+        let manager = Arc::new(ConnectionManager::new());
+        let (tx, _rx) = mpsc::channel(8);
+        manager
+            .register("user-1".to_string(), "default".to_string(), vec![], tx)
+            .unwrap();
+        let dispatcher = NotificationDispatcher::new(manager)
+            .with_max_concurrent_dispatches(2)
+            .with_dispatch_timeout(Duration::from_millis(20));
+        let event = || NotificationBuilder::new("order.created", "test").build();
+
+        // Saturate the dispatch slots as two in-progress dispatches would
+        let held = dispatcher.dispatch_permits.acquire_many(2).await.unwrap();
+        let rejections_before = DISPATCHER_BACKPRESSURE_TOTAL.get();
+        let sent_before = dispatcher.stats().total_sent;
+
+        for _ in 0..3 {
+            let result = dispatcher
+                .dispatch(NotificationTarget::User("user-1".to_string()), event())
+                .await;
+            assert!(!result.success);
+            assert_eq!(result.delivered_to, 0);
+            assert_eq!(result.failure_reason, Some(DISPATCH_QUEUE_FULL));
+        }
+        assert!(DISPATCHER_BACKPRESSURE_TOTAL.get() >= rejections_before + 3);
+        assert_eq!(dispatcher.stats().total_sent, sent_before);
+
+        // Slots freed: dispatches go through again
+        drop(held);
+        let result = dispatcher
+            .dispatch(NotificationTarget::User("user-1".to_string()), event())
+            .await;
+        assert!(result.success);
+        assert_eq!(result.failure_reason, None);
+    }
+
+    #[tokio::test]
+    async fn test_ack_cannot_be_spoofed_across_tenants() {
+        use tokio::sync::mpsc;
+
+        use crate::notification::{AckConfig, MemoryAckBackend, NotificationBuilder};
+        use crate::queue::{MemoryQueueBackend, QueueConfig};
+        use crate::tenant::TenantContext;
+
+        let manager = Arc::new(ConnectionManager::new());
+        let (acme_tx, mut acme_rx) = mpsc::channel(8);
+        let (globex_tx, mut globex_rx) = mpsc::channel(8);
+        manager
+            .register("1".to_string(), "acme".to_string(), vec![], acme_tx)
+            .unwrap();
+        manager
+            .register("1".to_string(), "globex".to_string(), vec![], globex_tx)
+            .unwrap();
+        let ack_backend = Arc::new(MemoryAckBackend::new(AckConfig {
+            enabled: true,
+            ..Default::default()
+        }));
+        let dispatcher = NotificationDispatcher::with_backends(
+            manager,
+            Arc::new(MemoryQueueBackend::new(QueueConfig::default())),
+            ack_backend.clone(),
+        );
+
+        let event = NotificationBuilder::new("order.created", "test").build();
+        let notification_id = event.id;
+        let result = dispatcher
+            .dispatch_for_tenant(NotificationTarget::User("1".to_string()), event, Some("acme"))
+            .await;
+        assert_eq!(result.delivered_to, 1);
+        assert!(acme_rx.try_recv().is_ok());
+        assert!(globex_rx.try_recv().is_err());
+
+        // globex's user "1" has the same raw user ID but cannot acknowledge acme's notification
+        let acme = TenantContext::new("acme");
+        let globex = TenantContext::new("globex");
+        assert!(!ack_backend.acknowledge(notification_id, &globex.namespace_user_id("1")).await);
+        assert!(!ack_backend.acknowledge(notification_id, "1").await);
+        assert!(ack_backend.acknowledge(notification_id, &acme.namespace_user_id("1")).await);
+    }
+
+    #[tokio::test]
+    async fn test_default_tenant_colon_user_cannot_spoof_tenant_user() {
+        use tokio::sync::mpsc;
+
+        use crate::notification::{AckConfig, MemoryAckBackend, NotificationBuilder};
+        use crate::queue::{MemoryQueueBackend, QueueConfig};
+        use crate::tenant::TenantContext;
+
+        let manager = Arc::new(ConnectionManager::new());
+        let (acme_tx, _acme_rx) = mpsc::channel(8);
+        manager
+            .register("1".to_string(), "acme".to_string(), vec![], acme_tx)
+            .unwrap();
+        let ack_backend = Arc::new(MemoryAckBackend::new(AckConfig {
+            enabled: true,
+            ..Default::default()
+        }));
+        let queue_backend = Arc::new(MemoryQueueBackend::new(QueueConfig {
+            enabled: true,
+            ..Default::default()
+        }));
+        let dispatcher =
+            NotificationDispatcher::with_backends(manager, queue_backend.clone(), ack_backend.clone());
+        let acme = TenantContext::new("acme");
+        // A default-tenant subject spelled like acme's user "1"
+        let spoofer = TenantContext::default_tenant();
+
+        let event = NotificationBuilder::new("order.created", "test").build();
+        let notification_id = event.id;
+        dispatcher
+            .dispatch_for_tenant(NotificationTarget::User("1".to_string()), event, Some("acme"))
+            .await;
+        assert!(!ack_backend.acknowledge(notification_id, &spoofer.namespace_user_id("acme:1")).await);
+        assert!(ack_backend.acknowledge(notification_id, &acme.namespace_user_id("1")).await);
+
+        // acme's offline user "2" has a queued message the spoofer can't reach
+        let event = NotificationBuilder::new("order.created", "test").build();
+        dispatcher
+            .dispatch_for_tenant(NotificationTarget::User("2".to_string()), event, Some("acme"))
+            .await;
+        let spoofed_key = spoofer.namespace_user_id("acme:2");
+        assert_eq!(queue_backend.queue_size(&spoofed_key).await.unwrap(), 0);
+        let (tx, _rx) = mpsc::channel(8);
+        assert_eq!(queue_backend.replay(&spoofed_key, &tx).await.unwrap().replayed, 0);
+        assert_eq!(
+            queue_backend.queue_size(&acme.namespace_user_id("2")).await.unwrap(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_default_tenant_user_id_with_colon_does_not_collide() {
+        use tokio::sync::mpsc;
+
+        use crate::notification::NotificationBuilder;
+
+        let manager = Arc::new(ConnectionManager::new());
+        let (default_tx, mut default_rx) = mpsc::channel(8);
+        let (acme_tx, mut acme_rx) = mpsc::channel(8);
+        // Both connections are keyed "acme:1"
+        manager
+            .register("acme:1".to_string(), "default".to_string(), vec![], default_tx)
+            .unwrap();
+        manager
+            .register("1".to_string(), "acme".to_string(), vec![], acme_tx)
+            .unwrap();
+        let dispatcher = NotificationDispatcher::new(manager);
+
+        let event = NotificationBuilder::new("order.created", "test").build();
+        let result = dispatcher
+            .dispatch_for_tenant(NotificationTarget::User("1".to_string()), event, Some("acme"))
+            .await;
+        assert_eq!(result.delivered_to, 1);
+        assert!(acme_rx.try_recv().is_ok());
+        assert!(default_rx.try_recv().is_err());
+
+        let event = NotificationBuilder::new("order.created", "test").build();
+        let result = dispatcher
+            .dispatch(NotificationTarget::User("acme:1".to_string()), event)
+            .await;
+        assert_eq!(result.delivered_to, 1);
+        assert!(default_rx.try_recv().is_ok());
+        assert!(acme_rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_idempotent_delivers_once() {
+        use tokio::sync::mpsc;
+
+        use crate::notification::NotificationBuilder;
+
+        let manager = Arc::new(ConnectionManager::new());
+        let (acme_tx, mut acme_rx) = mpsc::channel(8);
+        let (globex_tx, mut globex_rx) = mpsc::channel(8);
+        manager
+            .register("1".to_string(), "acme".to_string(), vec![], acme_tx)
+            .unwrap();
+        manager
+            .register("1".to_string(), "globex".to_string(), vec![], globex_tx)
+            .unwrap();
+        let dispatcher = NotificationDispatcher::new(manager);
+
+        let notification_id = Uuid::new_v4();
+        let send = |tenant_id: &'static str| {
+            let event = NotificationBuilder::new("order.created", "test")
+                .id(notification_id)
+                .build();
+            dispatcher.dispatch_idempotent(
+                NotificationTarget::User("1".to_string()),
+                event,
+                Some(tenant_id),
+            )
+        };
+
+        let duplicates_before = DEDUP_CALLER_DUPLICATES_TOTAL.get();
+        let first = send("acme").await;
+        let retry = send("acme").await;
+        assert_eq!(first.notification_id, notification_id);
+        assert_eq!(retry.notification_id, notification_id);
+        assert_eq!(retry.delivered_to, 1);
+        assert!(DEDUP_CALLER_DUPLICATES_TOTAL.get() > duplicates_before);
+        assert!(acme_rx.try_recv().is_ok());
+        assert!(acme_rx.try_recv().is_err(), "retry must not be delivered again");
+
+        // The same ID from another tenant is a different notification
+        assert_eq!(send("globex").await.delivered_to, 1);
+        assert!(globex_rx.try_recv().is_ok());
+        assert_eq!(dispatcher.stats().total_sent, 2);
+    }
+
+    #[tokio::test]
+    async fn test_expired_notification_is_not_delivered() {
+        use tokio::sync::mpsc;
+
+        use crate::notification::NotificationBuilder;
+
+        let manager = Arc::new(ConnectionManager::new());
+        let (tx, mut rx) = mpsc::channel(8);
+        manager
+            .register("user-1".to_string(), "default".to_string(), vec![], tx)
+            .unwrap();
+        let dispatcher = NotificationDispatcher::new(manager);
+        let expired_event = || NotificationBuilder::new("order.created", "test").ttl(0).build();
+
+        let event = expired_event();
+        tokio::time::sleep(Duration::from_millis(1)).await;
+        let result = dispatcher
+            .dispatch(NotificationTarget::User("user-1".to_string()), event)
+            .await;
+        assert_eq!(result.delivered_to, 0);
+
+        // Sending directly skips the dispatch-level check; the connection is skipped instead
+        let before = NOTIFICATIONS_EXPIRED_AT_DELIVERY_TOTAL.get();
+        let event = expired_event();
+        tokio::time::sleep(Duration::from_millis(1)).await;
+        let result = dispatcher.send_to_user("user-1", event).await;
+        assert_eq!(result.delivered_to, 0);
+        assert_eq!(result.failed, 0);
+        assert!(NOTIFICATIONS_EXPIRED_AT_DELIVERY_TOTAL.get() > before);
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_correlation_id_propagates_to_downstream_spans() {
+        use tokio::sync::mpsc;
+
+        use crate::notification::{AckConfig, MemoryAckBackend, NotificationBuilder};
+        use crate::queue::{MemoryQueueBackend, QueueConfig};
+
+        let manager = Arc::new(ConnectionManager::new());
+        let (tx, _rx) = mpsc::channel(8);
+        manager
+            .register("online".to_string(), "default".to_string(), vec![], tx)
+            .unwrap();
+        let dispatcher = NotificationDispatcher::with_backends(
+            manager,
+            Arc::new(MemoryQueueBackend::new(QueueConfig {
+                enabled: true,
+                ..Default::default()
+            })),
+            Arc::new(MemoryAckBackend::new(AckConfig {
+                enabled: true,
+                ..Default::default()
+            })),
+        );
+
+        let event = NotificationBuilder::new("order.created", "test")
+            .correlation_id("req-42")
+            .build();
+        let result = dispatcher
+            .dispatch(
+                NotificationTarget::Users(vec!["online".to_string(), "offline".to_string()]),
+                event,
+            )
+            .await;
+        assert_eq!(result.delivered_to, 1);
+
+        logs_assert(|lines: &[&str]| {
+            let spans = ["dispatcher.dispatch{", "queue.enqueue{", "ack.track{"];
+            for span in spans {
+                let recorded: Vec<&&str> = lines.iter().filter(|line| line.contains(span)).collect();
+                if recorded.is_empty() {
+                    return Err(format!("no log lines recorded in {}", span));
+                }
+                for line in recorded {
+                    let fields = &line[line.find(span).unwrap()..];
+                    let fields = &fields[..fields.find('}').unwrap()];
+                    if !fields.contains("correlation_id=\"req-42\"") {
+                        return Err(format!("{} is missing correlation_id: {}", span, line));
+                    }
+                }
+            }
+            Ok(())
+        });
+    }
+
+    #[tokio::test]
+    async fn test_send_to_users_reports_per_user_status() {
+        use tokio::sync::mpsc;
+
+        use crate::notification::NotificationBuilder;
+        use crate::queue::{MemoryQueueBackend, QueueConfig};
+
+        let manager = Arc::new(ConnectionManager::new());
+        let (tx, mut rx) = mpsc::channel(8);
+        manager
+            .register("online".to_string(), "default".to_string(), vec![], tx)
+            .unwrap();
+        let queue = Arc::new(MemoryQueueBackend::new(QueueConfig {
+            enabled: true,
+            ..Default::default()
+        }));
+        let queued_dispatcher = NotificationDispatcher::with_queue(manager.clone(), queue.clone());
+        let unqueued_dispatcher = NotificationDispatcher::new(manager);
+        let event = || NotificationBuilder::new("order.created", "test").build();
+
+        let result = queued_dispatcher
+            .send_to_users(&["online".to_string(), "offline-queued".to_string()], event())
+            .await;
+        let status = result.per_user_status.unwrap();
+        assert_eq!(status["online"], UserDeliveryStatus::Delivered);
+        assert_eq!(status["offline-queued"], UserDeliveryStatus::Queued);
+        assert_eq!(queue.stats().await.total_messages, 1);
+        assert!(rx.try_recv().is_ok());
+
+        let result = unqueued_dispatcher
+            .send_to_users(&["offline-unqueued".to_string()], event())
+            .await;
+        let status = result.per_user_status.unwrap();
+        assert_eq!(status["offline-unqueued"], UserDeliveryStatus::NoConnection);
+        assert_eq!(
+            serde_json::to_value(status["offline-unqueued"]).unwrap(),
+            "no_connection"
+        );
+
+        // Other targets do not report per-user status
+        let result = unqueued_dispatcher.send_to_user("online", event()).await;
+        assert!(result.per_user_status.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_channel_send_skips_excluded_sender() {
+        use tokio::sync::mpsc;
+
+        use crate::notification::NotificationBuilder;
+
+        let manager = Arc::new(ConnectionManager::new());
+        let (sender_tx, mut sender_rx) = mpsc::channel(8);
+        let sender = manager
+            .register("sender".to_string(), "default".to_string(), vec![], sender_tx)
+            .unwrap();
+        let (other_tx, mut other_rx) = mpsc::channel(8);
+        let other = manager
+            .register("other".to_string(), "default".to_string(), vec![], other_tx)
+            .unwrap();
+        for conn in [&sender, &other] {
+            manager.subscribe_to_channel(conn.id, "chat").await.unwrap();
+        }
+        let dispatcher = NotificationDispatcher::new(manager);
+
+        let exclude = HashSet::from(["sender".to_string()]);
+        let result = dispatcher
+            .dispatch_for_tenant_excluding(
+                NotificationTarget::Channel("chat".to_string()),
+                NotificationBuilder::new("chat.message", "test").build(),
+                None,
+                &exclude,
+            )
+            .await;
+
+        assert_eq!(result.delivered_to, 1);
+        assert!(sender_rx.try_recv().is_err());
+        assert!(other_rx.try_recv().is_ok());
+
+        // Without exclusions the sender receives its own broadcast
+        let result = dispatcher
+            .send_to_channel("chat", NotificationBuilder::new("chat.message", "test").build())
+            .await;
+        assert_eq!(result.delivered_to, 2);
+        assert!(sender_rx.try_recv().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_role_target_reaches_only_role_holders_in_tenant() {
+        use tokio::sync::mpsc;
+
+        use crate::notification::NotificationBuilder;
+
+        let manager = Arc::new(ConnectionManager::new());
+        let mut receivers = Vec::new();
+        for (user, tenant, roles) in [
+            ("alice", "acme", vec!["admin", "operator"]),
+            ("bob", "acme", vec!["operator"]),
+            ("carol", "acme", vec!["viewer"]),
+            ("dave", "globex", vec!["operator"]),
+        ] {
+            let (tx, rx) = mpsc::channel(8);
+            let roles = roles.into_iter().map(str::to_string).collect();
+            manager
+                .register(user.to_string(), tenant.to_string(), roles, tx)
+                .unwrap();
+            receivers.push(rx);
+        }
+        let dispatcher = NotificationDispatcher::new(manager);
+        let event = || NotificationBuilder::new("ops.alert", "test").build();
+        let target = NotificationTarget::Role("operator".to_string());
+
+        assert_eq!(dispatcher.count_target_connections(&target, Some("acme")), 2);
+        let result = dispatcher.dispatch_for_tenant(target, event(), Some("acme")).await;
+        assert_eq!(result.delivered_to, 2);
+        assert!(receivers[0].try_recv().is_ok());
+        assert!(receivers[1].try_recv().is_ok());
+        assert!(receivers[2].try_recv().is_err());
+        assert!(receivers[3].try_recv().is_err());
+
+        let result = dispatcher.send_to_role("admin", event()).await;
+        assert_eq!(result.delivered_to, 1);
+        assert!(receivers[0].try_recv().is_ok());
+        assert!(receivers[1].try_recv().is_err());
+
+        // Without a tenant every role holder is reached
+        let result = dispatcher.send_to_role("operator", event()).await;
+        assert_eq!(result.delivered_to, 3);
+    }
+
+    #[tokio::test]
+    async fn test_channel_pattern_reaches_every_matching_channel() {
+        use tokio::sync::mpsc;
+
+        use crate::metrics::CHANNEL_PATTERN_DISPATCH_TOTAL;
+        use crate::notification::NotificationBuilder;
+
+        let manager = Arc::new(ConnectionManager::new());
+        let mut receivers = Vec::new();
+        for (user, channel) in [("user-1", "orders.new"), ("user-2", "orders.status"), ("user-3", "chat")] {
+            let (tx, rx) = mpsc::channel(8);
+            let conn = manager
+                .register(user.to_string(), "default".to_string(), vec![], tx)
+                .unwrap();
+            manager.subscribe_to_channel(conn.id, channel).await.unwrap();
+            receivers.push(rx);
+        }
+        let dispatcher = NotificationDispatcher::new(manager);
+        let target = NotificationTarget::ChannelPattern("orders.*".to_string());
+        let dispatched_before = CHANNEL_PATTERN_DISPATCH_TOTAL.with_label_values(&["orders.*"]).get();
+
+        assert_eq!(dispatcher.count_target_connections(&target, None), 2);
+        let result = dispatcher
+            .dispatch(target, NotificationBuilder::new("order.updated", "test").build())
+            .await;
+
+        assert_eq!(result.delivered_to, 2);
+        assert!(receivers[0].try_recv().is_ok());
+        assert!(receivers[1].try_recv().is_ok());
+        assert!(receivers[2].try_recv().is_err());
+        assert_eq!(
+            CHANNEL_PATTERN_DISPATCH_TOTAL.with_label_values(&["orders.*"]).get(),
+            dispatched_before + 1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_delivery_records_latency_histograms() {
+        use tokio::sync::mpsc;
+
+        use crate::metrics::{
+            BACKEND_OPERATION_LATENCY, MESSAGE_DELIVERY_LATENCY, NOTIFICATION_E2E_LATENCY_SECONDS,
+        };
+        use crate::notification::NotificationBuilder;
+
+        let manager = Arc::new(ConnectionManager::new());
+        let mut receivers = Vec::new();
+        for _ in 0..2 {
+            let (tx, rx) = mpsc::channel(8);
+            manager
+                .register("user-1".to_string(), "default".to_string(), vec![], tx)
+                .unwrap();
+            receivers.push(rx);
+        }
+        let dispatcher = NotificationDispatcher::new(manager);
+
+        let e2e = NOTIFICATION_E2E_LATENCY_SECONDS.with_label_values(&["live"]);
+        let deliver = BACKEND_OPERATION_LATENCY.with_label_values(&["dispatcher", "deliver"]);
+        let (e2e_count, e2e_sum) = (e2e.get_sample_count(), e2e.get_sample_sum());
+        let (latency_count, latency_sum) = (
+            MESSAGE_DELIVERY_LATENCY.get_sample_count(),
+            MESSAGE_DELIVERY_LATENCY.get_sample_sum(),
+        );
+        let deliver_count = deliver.get_sample_count();
+
+        let mut event = NotificationBuilder::new("order.created", "test").build();
+        event.occurred_at = Utc::now() - chrono::Duration::seconds(2);
+        let result = dispatcher
+            .dispatch(NotificationTarget::User("user-1".to_string()), event)
+            .await;
+        assert_eq!(result.delivered_to, 2);
+        for rx in &mut receivers {
+            assert!(rx.try_recv().is_ok());
+        }
+
+        // Histograms are process-global, so only lower bounds are asserted
+        assert!(e2e.get_sample_count() > e2e_count);
+        assert!(e2e.get_sample_sum() - e2e_sum >= 2.0);
+        assert!(MESSAGE_DELIVERY_LATENCY.get_sample_count() > latency_count);
+        assert!(MESSAGE_DELIVERY_LATENCY.get_sample_sum() - latency_sum >= 2.0);
+        assert!(deliver.get_sample_count() > deliver_count);
+    }
+
+    #[tokio::test]
+    async fn test_send_timeout_evicts_stalled_connections() {
+        use tokio::sync::mpsc;
+
+        use crate::notification::NotificationBuilder;
+
+        let manager = Arc::new(ConnectionManager::new());
+        let mut receivers = Vec::new();
+        let mut stalled = Vec::new();
+        for i in 0..5 {
+            // tokio channels need a capacity of at least one, so a filled single-slot
+            // channel stands in for a client that never reads
+            let (tx, rx) = mpsc::channel(1);
+            tx.try_send(OutboundMessage::Raw(ServerMessage::Pong)).unwrap();
+            let handle = manager
+                .register(format!("user-{}", i), "default".to_string(), vec![], tx)
+                .unwrap();
+            stalled.push(handle.id);
+            receivers.push(rx);
+        }
+        let dispatcher = NotificationDispatcher::new(manager.clone())
+            .with_send_timeout(Duration::from_millis(20));
+        let timeouts_before = WS_SEND_TIMEOUTS_TOTAL.get();
+
+        // Sequential path
+        let result = dispatcher
+            .send_to_user("user-0", NotificationBuilder::new("test.event", "test").build())
+            .await;
+        assert_eq!(result.delivered_to, 0);
+        assert_eq!(result.failed, 1);
+        assert!(manager.get_connection(stalled[0]).is_none());
+
+        // Fan-out path
+        let result = dispatcher
+            .broadcast(NotificationBuilder::new("test.event", "test").build())
+            .await;
+        assert_eq!(result.delivered_to, 0);
+        assert_eq!(result.failed, 4);
+        assert!(stalled.iter().all(|id| manager.get_connection(*id).is_none()));
+        assert!(WS_SEND_TIMEOUTS_TOTAL.get() >= timeouts_before + 5);
+    }
+
+    #[tokio::test]
+    async fn test_msgpack_fanout_only_to_opted_in_connections() {
+        use tokio::sync::mpsc;
+
+        use crate::connection_manager::ConnectionMetadata;
+        use crate::notification::{NotificationBuilder, PayloadEncoding};
+
+        let manager = Arc::new(ConnectionManager::new());
+        let mut receivers = Vec::new();
+        for i in 0..6 {
+            let (tx, rx) = mpsc::channel(8);
+            let payload_encoding = if i % 2 == 0 {
+                PayloadEncoding::Msgpack
+            } else {
+                PayloadEncoding::Json
+            };
+            manager
+                .register_with_metadata(
+                    format!("user-{}", i),
+                    "default".to_string(),
+                    vec![],
+                    tx,
+                    ConnectionMetadata {
+                        payload_encoding,
+                        ..Default::default()
+                    },
+                )
+                .unwrap();
+            receivers.push((payload_encoding, rx));
+        }
+        let dispatcher = NotificationDispatcher::new(manager);
+
+        let event = NotificationBuilder::new("telemetry.sample", "test")
+            .payload(serde_json::json!({"values": [1, 2, 3]}))
+            .payload_encoding(PayloadEncoding::Msgpack)
+            .build();
+        let result = dispatcher.broadcast(event).await;
+        assert_eq!(result.delivered_to, 6);
+
+        for (encoding, mut rx) in receivers {
+            match (encoding, rx.try_recv().unwrap()) {
+                (PayloadEncoding::Msgpack, OutboundMessage::Binary(bytes)) => {
+                    let value: serde_json::Value = rmp_serde::from_slice(&bytes).unwrap();
+                    assert_eq!(value["payload"]["values"][2], 3);
+                }
+                (PayloadEncoding::Json, OutboundMessage::Serialized(json)) => {
+                    assert!(json.contains("telemetry.sample"));
+                }
+                (encoding, other) => panic!("{:?} connection received {:?}", encoding, other),
+            }
+        }
+    }
 }
@@ -0,0 +1,96 @@
+//! In-memory log of notifications that could not be delivered or queued.
+//!
+//! When the offline queue is disabled, notifications for offline users are discarded.
+//! With `dispatcher.queue_fallback_on_offline` enabled, the dispatcher records each of
+//! them here so operators can see missed deliveries without running the full queue.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+/// Number of most recent dropped notifications kept
+pub const DROP_LOG_CAPACITY: usize = 500;
+
+/// Why a notification was dropped
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DropReason {
+    /// The user was offline and the offline queue is disabled
+    QueueDisabled,
+}
+
+/// A dropped notification
+#[derive(Debug, Clone, Serialize)]
+pub struct DroppedNotification {
+    pub notification_id: Uuid,
+    pub user_id: String,
+    pub event_type: String,
+    pub timestamp: DateTime<Utc>,
+    pub reason: DropReason,
+}
+
+/// Ring buffer of the last `DROP_LOG_CAPACITY` dropped notifications
+#[derive(Debug, Default)]
+pub struct DropLog {
+    entries: Mutex<VecDeque<DroppedNotification>>,
+}
+
+impl DropLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a dropped notification, evicting the oldest entry when full
+    pub fn record(&self, entry: DroppedNotification) {
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() == DROP_LOG_CAPACITY {
+            entries.pop_front();
+        }
+        entries.push_back(entry);
+    }
+
+    /// Dropped notifications, newest first
+    pub fn recent(&self) -> Vec<DroppedNotification> {
+        self.entries.lock().unwrap().iter().rev().cloned().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(user_id: &str) -> DroppedNotification {
+        DroppedNotification {
+            notification_id: Uuid::new_v4(),
+            user_id: user_id.to_string(),
+            event_type: "order.created".to_string(),
+            timestamp: Utc::now(),
+            reason: DropReason::QueueDisabled,
+        }
+    }
+
+    #[test]
+    fn test_drop_log_keeps_most_recent_entries() {
+        let log = DropLog::new();
+        for i in 0..DROP_LOG_CAPACITY + 10 {
+            log.record(entry(&format!("user-{}", i)));
+        }
+
+        let recent = log.recent();
+        assert_eq!(recent.len(), DROP_LOG_CAPACITY);
+        assert_eq!(recent[0].user_id, format!("user-{}", DROP_LOG_CAPACITY + 9));
+        assert_eq!(recent[DROP_LOG_CAPACITY - 1].user_id, "user-10");
+        assert_eq!(serde_json::to_value(&recent[0]).unwrap()["reason"], "queue_disabled");
+    }
+}
@@ -1,23 +1,37 @@
 //! Notification domain module.
 //!
 //! This module provides notification dispatching and triggers:
+//! - `circuit`: Circuit breaker skipping shards of connections whose sends keep failing
+//! - `dedup`: Deduplication of sends with caller-provided notification IDs
 //! - `dispatcher`: Core notification dispatch logic
+//! - `drop_log`: Recently dropped notifications for offline users
 //! - `types`: Notification event types and builders
 //! - `triggers`: HTTP and Redis Pub/Sub notification triggers
 
+mod circuit;
+mod dedup;
 mod dispatcher;
+mod drop_log;
 mod types;
 pub mod triggers;
 
-pub use dispatcher::{DeliveryResult, NotificationDispatcher};
+pub use circuit::{
+    DispatchCircuitBreaker, DISPATCH_CIRCUIT_OPEN_DURATION, DISPATCH_CIRCUIT_SHARDS,
+    DISPATCH_CIRCUIT_WINDOW,
+};
+pub use dedup::{DeduplicationCache, DEDUP_WINDOW};
+pub use dispatcher::{
+    DeliveryResult, NotificationDispatcher, UserDeliveryStatus, DISPATCH_QUEUE_FULL,
+};
+pub use drop_log::{DropLog, DropReason, DroppedNotification, DROP_LOG_CAPACITY};
 pub use types::{
     Audience, NotificationBuilder, NotificationEvent, NotificationMetadata, NotificationTarget,
-    Priority,
+    PayloadEncoding, Priority, CALLER_ID_HEADER,
 };
 
 // Re-export ACK types from domain module for backward compatibility
 pub use crate::domain::ack::{
     create_ack_backend, AckConfig, AckStatsSnapshot, AckTracker,
-    AckBackendError, AckBackendStats, AckTrackerBackend, PendingAckInfo,
-    MemoryAckBackend, PostgresAckBackend, RedisAckBackend,
+    AckBackendError, AckBackendStats, AckEventTypeSummary, AckTrackerBackend, PendingAckInfo,
+    MemoryAckBackend, NoopAckBackend, PostgresAckBackend, RedisAckBackend,
 };
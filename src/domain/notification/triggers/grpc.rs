@@ -0,0 +1,473 @@
+//! gRPC notification trigger
+//!
+//! Serves `ara.notification.v1.NotificationService` (see `proto/notification.proto`) for
+//! high-throughput publishers. Each RPC converts its request into the matching HTTP API
+//! request and runs the same handler, so validation, templates and tenant scoping behave
+//! identically. Calls authenticate either with the service API key (`x-api-key`
+//! metadata, tenant from `x-tenant-id`) or with `authorization: Bearer <jwt>` metadata
+//! whose token carries the [`PUBLISH_SCOPE`] scope, so end-user WebSocket tokens cannot
+//! publish; the token's `tenant_id` claim then scopes the dispatch when multi-tenancy
+//! is enabled.
+
+// `tonic::Status` is the error type of every RPC, so returning it directly is intended
+#![allow(clippy::result_large_err)]
+
+use axum::{extract::State, http::HeaderMap, Extension, Json};
+use subtle::ConstantTimeEq;
+use tonic::{metadata::MetadataMap, Request, Response, Status};
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::metrics::GRPC_REQUESTS_TOTAL;
+use crate::notification::{Audience, Priority};
+use crate::server::middleware::{is_valid_tenant_id, RequestTenantContext};
+use crate::server::AppState;
+
+use super::http::{
+    self, BatchNotificationItem, BatchOptions, BatchTarget, BroadcastNotificationRequest,
+    ChannelNotificationRequest, NotificationContent, SendNotificationRequest,
+    SendNotificationResponse, SendToUsersRequest,
+};
+
+/// Scope (in the space-separated `scope` claim) a JWT needs to publish over gRPC
+pub const PUBLISH_SCOPE: &str = "publish";
+
+/// Generated protobuf types and service stubs
+pub mod proto {
+    tonic::include_proto!("ara.notification.v1");
+}
+
+use proto::notification_service_server::{NotificationService, NotificationServiceServer};
+
+/// gRPC implementation of the notification publishing API
+#[derive(Clone)]
+pub struct GrpcNotificationService {
+    state: AppState,
+}
+
+impl GrpcNotificationService {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+
+    /// Wrap the service for `tonic::transport::Server::add_service`
+    pub fn into_server(self) -> NotificationServiceServer<Self> {
+        NotificationServiceServer::new(self)
+    }
+
+    /// Authenticate the caller as a publisher and resolve the tenant context for the call
+    fn authorize(&self, metadata: &MetadataMap) -> Result<Option<RequestTenantContext>, Status> {
+        if let Some(key) = metadata.get("x-api-key").and_then(|v| v.to_str().ok()) {
+            return self.authorize_api_key(key, metadata);
+        }
+
+        let token = metadata
+            .get("authorization")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| {
+                v.strip_prefix("Bearer ")
+                    .or_else(|| v.strip_prefix("bearer "))
+            })
+            .ok_or_else(|| Status::unauthenticated("Missing bearer token"))?;
+
+        let claims = self.state.jwt_validator.validate(token.trim())?;
+        if !claims.has_scope(PUBLISH_SCOPE) {
+            return Err(Status::permission_denied(format!(
+                "Token lacks the '{}' scope required to publish",
+                PUBLISH_SCOPE
+            )));
+        }
+
+        if !self.state.tenant_manager.is_enabled() {
+            return Ok(None);
+        }
+        let ctx = self.state.tenant_manager.create_context(claims.tenant_id());
+        Ok(Some(RequestTenantContext(ctx)))
+    }
+
+    /// Check the service API key; the tenant comes from `x-tenant-id` as over HTTP
+    fn authorize_api_key(
+        &self,
+        key: &str,
+        metadata: &MetadataMap,
+    ) -> Result<Option<RequestTenantContext>, Status> {
+        let expected = self
+            .state
+            .settings
+            .api
+            .key
+            .as_deref()
+            .ok_or_else(|| Status::unauthenticated("API key authentication is not configured"))?;
+        if !bool::from(key.as_bytes().ct_eq(expected.as_bytes())) {
+            return Err(Status::unauthenticated("Invalid API key"));
+        }
+
+        if !self.state.tenant_manager.is_enabled() {
+            return Ok(None);
+        }
+        match metadata.get("x-tenant-id").and_then(|v| v.to_str().ok()).map(str::trim) {
+            Some(tenant_id) if is_valid_tenant_id(tenant_id) => {
+                let ctx = self.state.tenant_manager.create_context(tenant_id);
+                Ok(Some(RequestTenantContext(ctx)))
+            }
+            _ => Err(Status::invalid_argument("Missing or invalid x-tenant-id")),
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl NotificationService for GrpcNotificationService {
+    #[tracing::instrument(name = "grpc.send_to_user", skip(self, request))]
+    async fn send_to_user(
+        &self,
+        request: Request<proto::SendToUserRequest>,
+    ) -> Result<Response<proto::SendResponse>, Status> {
+        GRPC_REQUESTS_TOTAL.with_label_values(&["SendToUser"]).inc();
+        let tenant_ctx = self.authorize(request.metadata())?;
+        let request = request.into_inner();
+        let options = request.options.unwrap_or_default();
+
+        let Json(response) = http::send_notification(
+            State(self.state.clone()),
+            tenant_ctx.map(Extension),
+            HeaderMap::new(),
+            Json(SendNotificationRequest {
+                target_user_id: request.target_user_id,
+                content: content_from_proto(request.content)?,
+                priority: priority_from_proto(options.priority)?,
+                ttl: options.ttl,
+                correlation_id: options.correlation_id,
+                notification_id: notification_id_from_proto(options.notification_id)?,
+                on_behalf_of: None,
+            }),
+        )
+        .await?;
+
+        Ok(Response::new(send_response(response)))
+    }
+
+    #[tracing::instrument(name = "grpc.send_to_users", skip(self, request))]
+    async fn send_to_users(
+        &self,
+        request: Request<proto::SendToUsersRequest>,
+    ) -> Result<Response<proto::SendResponse>, Status> {
+        GRPC_REQUESTS_TOTAL.with_label_values(&["SendToUsers"]).inc();
+        let tenant_ctx = self.authorize(request.metadata())?;
+        let request = request.into_inner();
+        let options = request.options.unwrap_or_default();
+
+        let Json(response) = http::send_to_users(
+            State(self.state.clone()),
+            tenant_ctx.map(Extension),
+            HeaderMap::new(),
+            Json(SendToUsersRequest {
+                target_user_ids: request.target_user_ids,
+                include_per_user_status: false,
+                content: content_from_proto(request.content)?,
+                priority: priority_from_proto(options.priority)?,
+                ttl: options.ttl,
+                correlation_id: options.correlation_id,
+                notification_id: notification_id_from_proto(options.notification_id)?,
+                on_behalf_of: None,
+            }),
+        )
+        .await?;
+
+        Ok(Response::new(send_response(response)))
+    }
+
+    #[tracing::instrument(name = "grpc.broadcast", skip(self, request))]
+    async fn broadcast(
+        &self,
+        request: Request<proto::BroadcastRequest>,
+    ) -> Result<Response<proto::SendResponse>, Status> {
+        GRPC_REQUESTS_TOTAL.with_label_values(&["Broadcast"]).inc();
+        let tenant_ctx = self.authorize(request.metadata())?;
+        let request = request.into_inner();
+        let options = request.options.unwrap_or_default();
+
+        let audience = request
+            .audience_json
+            .map(|json| serde_json::from_str::<Audience>(&json))
+            .transpose()
+            .map_err(|e| Status::invalid_argument(format!("Invalid audience_json: {}", e)))?;
+
+        let Json(response) = http::broadcast_notification(
+            State(self.state.clone()),
+            tenant_ctx.map(Extension),
+            HeaderMap::new(),
+            Json(BroadcastNotificationRequest {
+                content: content_from_proto(request.content)?,
+                priority: priority_from_proto(options.priority)?,
+                ttl: options.ttl,
+                audience,
+                correlation_id: options.correlation_id,
+                notification_id: notification_id_from_proto(options.notification_id)?,
+                on_behalf_of: None,
+            }),
+        )
+        .await?;
+
+        Ok(Response::new(send_response(response)))
+    }
+
+    #[tracing::instrument(name = "grpc.send_to_channel", skip(self, request))]
+    async fn send_to_channel(
+        &self,
+        request: Request<proto::SendToChannelRequest>,
+    ) -> Result<Response<proto::SendResponse>, Status> {
+        GRPC_REQUESTS_TOTAL.with_label_values(&["SendToChannel"]).inc();
+        let tenant_ctx = self.authorize(request.metadata())?;
+        let request = request.into_inner();
+        let options = request.options.unwrap_or_default();
+
+        let Json(response) = http::channel_notification(
+            State(self.state.clone()),
+            tenant_ctx.map(Extension),
+            HeaderMap::new(),
+            Json(ChannelNotificationRequest {
+                channel: request.channel,
+                content: content_from_proto(request.content)?,
+                priority: priority_from_proto(options.priority)?,
+                ttl: options.ttl,
+                correlation_id: options.correlation_id,
+                notification_id: notification_id_from_proto(options.notification_id)?,
+                on_behalf_of: None,
+                exclude_user_ids: (!request.exclude_user_ids.is_empty())
+                    .then(|| request.exclude_user_ids.into_iter().collect()),
+            }),
+        )
+        .await?;
+
+        Ok(Response::new(send_response(response)))
+    }
+
+    #[tracing::instrument(name = "grpc.batch_send", skip(self, request))]
+    async fn batch_send(
+        &self,
+        request: Request<proto::BatchSendRequest>,
+    ) -> Result<Response<proto::BatchSendResponse>, Status> {
+        GRPC_REQUESTS_TOTAL.with_label_values(&["BatchSend"]).inc();
+        let tenant_ctx = self.authorize(request.metadata())?;
+        let request = request.into_inner();
+
+        let notifications = request
+            .notifications
+            .into_iter()
+            .enumerate()
+            .map(|(index, item)| {
+                let options = item.options.unwrap_or_default();
+                Ok(BatchNotificationItem {
+                    target: target_from_proto(item.target).map_err(|status| {
+                        Status::invalid_argument(format!(
+                            "notifications[{}]: {}",
+                            index,
+                            status.message()
+                        ))
+                    })?,
+                    content: content_from_proto(item.content)?,
+                    priority: priority_from_proto(options.priority)?,
+                    ttl: options.ttl,
+                    correlation_id: options.correlation_id,
+                    notification_id: notification_id_from_proto(options.notification_id)?,
+                    on_behalf_of: None,
+                })
+            })
+            .collect::<Result<Vec<_>, Status>>()?;
+
+        let Json(response) = http::batch_send(
+            State(self.state.clone()),
+            tenant_ctx.map(Extension),
+            HeaderMap::new(),
+            Json(http::BatchSendRequest {
+                notifications,
+                options: BatchOptions {
+                    stop_on_error: request.stop_on_error,
+                    deduplicate: request.deduplicate,
+                    dry_run: request.dry_run,
+                },
+            }),
+        )
+        .await?;
+
+        Ok(Response::new(proto::BatchSendResponse {
+            batch_id: response.batch_id.to_string(),
+            results: response
+                .results
+                .into_iter()
+                .map(|r| proto::BatchItemResult {
+                    index: r.index as u32,
+                    notification_id: r.notification_id.to_string(),
+                    delivered_to: r.delivered_to as u64,
+                    failed: r.failed as u64,
+                    success: r.success,
+                    error: r.error,
+                    skipped: r.skipped.unwrap_or(false),
+                })
+                .collect(),
+            summary: Some(proto::BatchSummary {
+                total: response.summary.total as u32,
+                succeeded: response.summary.succeeded as u32,
+                failed: response.summary.failed as u32,
+                skipped: response.summary.skipped as u32,
+                total_delivered: response.summary.total_delivered as u64,
+                dry_run: response.summary.dry_run,
+            }),
+            timestamp: response.timestamp.to_rfc3339(),
+        }))
+    }
+}
+
+fn content_from_proto(
+    content: Option<proto::NotificationContent>,
+) -> Result<NotificationContent, Status> {
+    use proto::notification_content::Content;
+
+    match content.and_then(|c| c.content) {
+        Some(Content::Direct(direct)) => {
+            let payload = serde_json::from_str(&direct.payload_json)
+                .map_err(|e| Status::invalid_argument(format!("Invalid payload_json: {}", e)))?;
+            Ok(NotificationContent::Direct {
+                event_type: direct.event_type,
+                payload,
+            })
+        }
+        Some(Content::Template(template)) => {
+            let variables = if template.variables_json.trim().is_empty() {
+                serde_json::json!({})
+            } else {
+                serde_json::from_str(&template.variables_json).map_err(|e| {
+                    Status::invalid_argument(format!("Invalid variables_json: {}", e))
+                })?
+            };
+            Ok(NotificationContent::Template {
+                template_id: template.template_id,
+                variables,
+            })
+        }
+        None => Err(Status::invalid_argument("Missing notification content")),
+    }
+}
+
+fn priority_from_proto(priority: i32) -> Result<Option<Priority>, Status> {
+    match proto::Priority::try_from(priority) {
+        Ok(proto::Priority::Unspecified) => Ok(None),
+        Ok(proto::Priority::Low) => Ok(Some(Priority::Low)),
+        Ok(proto::Priority::Normal) => Ok(Some(Priority::Normal)),
+        Ok(proto::Priority::High) => Ok(Some(Priority::High)),
+        Ok(proto::Priority::Critical) => Ok(Some(Priority::Critical)),
+        Err(_) => Err(Status::invalid_argument(format!("Unknown priority {}", priority))),
+    }
+}
+
+fn notification_id_from_proto(notification_id: Option<String>) -> Result<Option<Uuid>, Status> {
+    notification_id
+        .map(|id| Uuid::parse_str(&id))
+        .transpose()
+        .map_err(|e| Status::invalid_argument(format!("Invalid notification_id: {}", e)))
+}
+
+fn target_from_proto(target: Option<proto::BatchTarget>) -> Result<BatchTarget, Status> {
+    use proto::batch_target::Target;
+
+    match target.and_then(|t| t.target) {
+        Some(Target::User(id)) => Ok(BatchTarget::User(id)),
+        Some(Target::Users(list)) => Ok(BatchTarget::Users(list.values)),
+        Some(Target::Broadcast(true)) => Ok(BatchTarget::Broadcast),
+        Some(Target::Channel(name)) => Ok(BatchTarget::Channel(name)),
+        Some(Target::Channels(list)) => Ok(BatchTarget::Channels(list.values)),
+        Some(Target::Broadcast(false)) | None => Err(Status::invalid_argument("Missing target")),
+    }
+}
+
+fn send_response(response: SendNotificationResponse) -> proto::SendResponse {
+    proto::SendResponse {
+        success: response.success,
+        notification_id: response.notification_id.to_string(),
+        delivered_to: response.delivered_to as u64,
+        failed: response.failed as u64,
+        timestamp: response.timestamp.to_rfc3339(),
+    }
+}
+
+impl From<AppError> for Status {
+    fn from(err: AppError) -> Self {
+        match err {
+            AppError::Auth(msg) => Status::unauthenticated(msg),
+            AppError::Forbidden(msg) => Status::permission_denied(msg),
+            AppError::Validation(msg) => Status::invalid_argument(msg),
+            AppError::NotFound(msg) => Status::not_found(msg),
+            AppError::RateLimitExceeded(msg) | AppError::ConnectionLimitExceeded(msg) => {
+                Status::resource_exhausted(msg)
+            }
+            AppError::PayloadTooLarge(msg) => Status::out_of_range(msg),
+            AppError::Timeout(msg) => Status::deadline_exceeded(msg),
+            other => {
+                tracing::error!(error = %other, "gRPC request failed");
+                Status::internal("Internal server error")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_content_from_proto() {
+        let content = content_from_proto(Some(proto::NotificationContent {
+            content: Some(proto::notification_content::Content::Direct(
+                proto::DirectContent {
+                    event_type: "order.created".to_string(),
+                    payload_json: r#"{"order_id": 1}"#.to_string(),
+                },
+            )),
+        }))
+        .unwrap();
+        assert!(matches!(
+            content,
+            NotificationContent::Direct { ref event_type, ref payload }
+                if event_type == "order.created" && payload["order_id"] == 1
+        ));
+
+        let err = content_from_proto(Some(proto::NotificationContent {
+            content: Some(proto::notification_content::Content::Direct(
+                proto::DirectContent {
+                    event_type: "order.created".to_string(),
+                    payload_json: "{oops".to_string(),
+                },
+            )),
+        }))
+        .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+
+        assert!(content_from_proto(None).is_err());
+    }
+
+    #[test]
+    fn test_priority_and_target_from_proto() {
+        assert_eq!(priority_from_proto(0).unwrap(), None);
+        assert_eq!(priority_from_proto(3).unwrap(), Some(Priority::High));
+        assert!(priority_from_proto(42).is_err());
+
+        let target = target_from_proto(Some(proto::BatchTarget {
+            target: Some(proto::batch_target::Target::Channels(proto::StringList {
+                values: vec!["orders".to_string()],
+            })),
+        }))
+        .unwrap();
+        assert!(matches!(target, BatchTarget::Channels(ref c) if c == &["orders"]));
+        assert!(target_from_proto(None).is_err());
+    }
+
+    #[test]
+    fn test_notification_id_from_proto() {
+        let id = Uuid::new_v4();
+        assert_eq!(notification_id_from_proto(None).unwrap(), None);
+        assert_eq!(notification_id_from_proto(Some(id.to_string())).unwrap(), Some(id));
+
+        let err = notification_id_from_proto(Some("not-a-uuid".to_string())).unwrap_err();
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+    }
+}
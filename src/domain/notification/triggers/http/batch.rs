@@ -1,24 +1,49 @@
 //! Batch notification send API
 
 use std::collections::HashSet;
-
-use axum::{extract::State, Extension, Json};
+use std::convert::Infallible;
+use std::time::Duration;
+
+use axum::{
+    body::Body,
+    extract::State,
+    http::{header, HeaderMap},
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+use tokio_stream::wrappers::ReceiverStream;
 use uuid::Uuid;
 
 use crate::error::{AppError, Result};
-use crate::notification::{NotificationBuilder, NotificationTarget, Priority};
+use crate::metrics::{
+    BATCH_DRY_RUNS_TOTAL, DEDUP_CALLER_DUPLICATES_TOTAL, DEDUP_CALLER_PROVIDED_IDS_TOTAL,
+};
+use crate::notification::{DeliveryResult, NotificationBuilder, NotificationTarget, Priority};
 use crate::server::middleware::RequestTenantContext;
 use crate::server::AppState;
 
 use super::content::NotificationContent;
+use super::handlers::dispatch;
+use super::impersonation::Impersonation;
 
 /// Maximum number of notifications per batch
 const MAX_BATCH_SIZE: usize = 100;
 
 const SOURCE: &str = "http-api";
 
+/// Content type of the streaming batch response
+pub const NDJSON_CONTENT_TYPE: &str = "application/x-ndjson";
+
+/// Result lines buffered ahead of a slow streaming client
+const STREAM_BUFFER_LINES: usize = 16;
+
+const STOP_ON_ERROR_SKIP: &str = "Skipped due to stop_on_error";
+const STREAM_TIMEOUT_SKIP: &str = "Skipped due to stream timeout";
+
 /// Target specification for batch notifications
 #[derive(Debug, Clone, Deserialize)]
 #[serde(tag = "type", content = "value")]
@@ -38,6 +63,12 @@ pub enum BatchTarget {
     /// Send to users subscribed to multiple channels
     #[serde(rename = "channels")]
     Channels(Vec<String>),
+    /// Send to users subscribed to any channel matching a glob such as `orders.*`
+    #[serde(rename = "channel_pattern")]
+    ChannelPattern(String),
+    /// Send to users whose JWT `roles` claim includes the role
+    #[serde(rename = "role")]
+    Role(String),
 }
 
 impl BatchTarget {
@@ -63,6 +94,13 @@ impl BatchTarget {
                 };
                 NotificationTarget::Channels(namespaced)
             }
+            BatchTarget::ChannelPattern(pattern) => {
+                let namespaced = tenant_ctx
+                    .map(|t| t.namespace_channel(&pattern))
+                    .unwrap_or(pattern);
+                NotificationTarget::ChannelPattern(namespaced)
+            }
+            BatchTarget::Role(role) => NotificationTarget::Role(role),
         }
     }
 
@@ -82,6 +120,8 @@ impl BatchTarget {
                 sorted.sort();
                 format!("channels:{}", sorted.join(","))
             }
+            BatchTarget::ChannelPattern(pattern) => format!("channel_pattern:{}", pattern),
+            BatchTarget::Role(role) => format!("role:{}", role),
         }
     }
 }
@@ -102,8 +142,13 @@ pub struct BatchNotificationItem {
     pub priority: Option<Priority>,
     /// Optional TTL in seconds (overrides template default if provided)
     pub ttl: Option<u32>,
+    /// Optional caller-provided notification ID; repeated sends with the same ID are
+    /// delivered once
+    pub notification_id: Option<Uuid>,
     /// Optional correlation ID
     pub correlation_id: Option<String>,
+    /// User to send on behalf of; requires a bearer token with the `impersonate` scope
+    pub on_behalf_of: Option<String>,
 }
 
 /// Options for batch send
@@ -115,6 +160,10 @@ pub struct BatchOptions {
     /// Skip duplicate targets (based on target+event_type)
     #[serde(default)]
     pub deduplicate: bool,
+    /// Validate every item and report how many connections it would reach,
+    /// without sending anything
+    #[serde(default)]
+    pub dry_run: bool,
 }
 
 /// Batch send request
@@ -161,6 +210,9 @@ pub struct BatchSummary {
     pub skipped: usize,
     /// Total connections delivered to
     pub total_delivered: usize,
+    /// Whether this was a dry run; delivery counts are then the connections each
+    /// item would currently reach
+    pub dry_run: bool,
 }
 
 /// Response for batch send operation
@@ -176,93 +228,97 @@ pub struct BatchSendResponse {
     pub timestamp: DateTime<Utc>,
 }
 
-/// Send notifications in batch
-///
-/// Supports up to 100 notifications per batch with optional deduplication
-/// and stop-on-error behavior.
-#[tracing::instrument(
-    name = "http.batch_send",
-    skip(state, request),
-    fields(
-        batch_size = request.notifications.len(),
-        stop_on_error = request.options.stop_on_error,
-        deduplicate = request.options.deduplicate
-    )
-)]
-pub async fn batch_send(
-    State(state): State<AppState>,
-    tenant_ctx: Option<Extension<RequestTenantContext>>,
-    Json(request): Json<BatchSendRequest>,
-) -> Result<Json<BatchSendResponse>> {
-    let tenant_ref = tenant_ctx.as_ref().map(|t| &t.0);
-    let tenant_id = tenant_ref.map(|t| t.tenant_id());
+/// What to do after processing a batch item
+enum BatchFlow {
+    Continue,
+    /// Stop without reporting the remaining items
+    Stop,
+    /// Stop and report the remaining items as skipped
+    SkipRemaining,
+}
 
-    let batch_id = Uuid::new_v4();
-    let total = request.notifications.len();
+/// Processes batch items in order, tracking deduplication and the running summary.
+///
+/// Shared by the buffered (`/notifications/batch`) and streaming
+/// (`/notifications/batch-stream`) endpoints.
+struct BatchRun<'a> {
+    state: &'a AppState,
+    tenant: Option<&'a RequestTenantContext>,
+    options: BatchOptions,
+    /// Verified caller, when any item is sent on behalf of a user
+    caller_id: Option<String>,
+    seen_keys: HashSet<String>,
+    /// Caller-provided notification IDs already processed in this batch
+    seen_ids: HashSet<Uuid>,
+    summary: BatchSummary,
+}
 
-    // Validate batch size
-    if total > MAX_BATCH_SIZE {
-        return Err(AppError::Validation(format!(
-            "Batch size {} exceeds maximum of {}",
-            total, MAX_BATCH_SIZE
-        )));
+impl<'a> BatchRun<'a> {
+    fn new(
+        state: &'a AppState,
+        tenant: Option<&'a RequestTenantContext>,
+        options: BatchOptions,
+        caller_id: Option<String>,
+        total: usize,
+    ) -> Self {
+        if options.dry_run {
+            BATCH_DRY_RUNS_TOTAL.inc();
+        }
+        let dry_run = options.dry_run;
+        Self {
+            state,
+            tenant,
+            options,
+            caller_id,
+            seen_keys: HashSet::new(),
+            seen_ids: HashSet::new(),
+            summary: BatchSummary {
+                total,
+                succeeded: 0,
+                failed: 0,
+                skipped: 0,
+                total_delivered: 0,
+                dry_run,
+            },
+        }
     }
 
-    if total == 0 {
-        return Err(AppError::Validation("Batch cannot be empty".to_string()));
-    }
+    /// Resolve, deduplicate and dispatch a single item
+    async fn process(&mut self, index: usize, item: BatchNotificationItem) -> (BatchItemResult, BatchFlow) {
+        let tenant_id = self.tenant.map(|t| t.tenant_id());
 
-    let mut results = Vec::with_capacity(total);
-    let mut succeeded = 0;
-    let mut failed = 0;
-    let mut skipped = 0;
-    let mut total_delivered = 0;
-
-    // For deduplication
-    let mut seen_keys: HashSet<String> = HashSet::new();
+        // Items repeating a caller-provided ID are dropped before anything is resolved
+        if let Some(notification_id) = item.notification_id {
+            if !self.seen_ids.insert(notification_id) {
+                DEDUP_CALLER_PROVIDED_IDS_TOTAL.inc();
+                DEDUP_CALLER_DUPLICATES_TOTAL.inc();
+                return (self.duplicate_result(index, notification_id), BatchFlow::Continue);
+            }
+        }
 
-    for (index, item) in request.notifications.into_iter().enumerate() {
         // Resolve content (from template or direct)
         let resolved = match item
             .content
-            .resolve_for_tenant(&state.template_store, tenant_id, item.priority, item.ttl)
+            .resolve_for_tenant(&self.state.template_store, tenant_id, item.priority, item.ttl)
         {
             Ok(r) => r,
             Err(e) => {
-                results.push(BatchItemResult {
-                    index,
-                    notification_id: Uuid::nil(),
-                    delivered_to: 0,
-                    failed: 0,
-                    success: false,
-                    error: Some(e.to_string()),
-                    skipped: None,
-                });
-                failed += 1;
-                if request.options.stop_on_error {
-                    break;
-                }
-                continue;
+                self.summary.failed += 1;
+                let flow = if self.options.stop_on_error {
+                    BatchFlow::Stop
+                } else {
+                    BatchFlow::Continue
+                };
+                return (Self::error_result(index, e.to_string(), None), flow);
             }
         };
 
         // Check for deduplication
-        if request.options.deduplicate {
+        if self.options.deduplicate {
             let dedup_key = format!("{}:{}", item.target.dedup_key(), resolved.event_type);
-            if seen_keys.contains(&dedup_key) {
-                results.push(BatchItemResult {
-                    index,
-                    notification_id: Uuid::nil(),
-                    delivered_to: 0,
-                    failed: 0,
-                    success: true,
-                    error: None,
-                    skipped: Some(true),
-                });
-                skipped += 1;
-                continue;
+            if !self.seen_keys.insert(dedup_key) {
+                return (self.duplicate_result(index, Uuid::nil()), BatchFlow::Continue);
             }
-            seen_keys.insert(dedup_key);
         }
 
         // Build notification event
@@ -278,47 +334,162 @@ pub async fn batch_send(
             builder = builder.correlation_id(correlation_id);
         }
 
+        if let Some(notification_id) = item.notification_id {
+            builder = builder.id(notification_id);
+        }
+
+        if let (Some(caller_id), Some(on_behalf_of)) = (&self.caller_id, item.on_behalf_of) {
+            if !self.options.dry_run {
+                let impersonation = Impersonation {
+                    caller_id: caller_id.clone(),
+                    on_behalf_of,
+                };
+                builder = impersonation.apply(builder);
+            }
+        }
+
         let event = builder.build();
-        let target = item.target.into_notification_target(tenant_ref);
+        let target = item.target.into_notification_target(self.tenant);
+
+        // Dispatch notification with tenant scoping, or only count its connections
+        let result = if self.options.dry_run {
+            DeliveryResult {
+                notification_id: event.id,
+                delivered_to: self.state.dispatcher.count_target_connections(&target, tenant_id),
+                failed: 0,
+                success: true,
+                per_user_status: None,
+                failure_reason: None,
+            }
+        } else {
+            dispatch(self.state, target, event, tenant_id, item.notification_id).await
+        };
 
-        // Dispatch notification with tenant scoping
-        let result = state
-            .dispatcher
-            .dispatch_for_tenant(target, event, tenant_id)
-            .await;
+        self.summary.total_delivered += result.delivered_to;
 
-        let item_success = result.success;
-        total_delivered += result.delivered_to;
+        let flow = if result.success {
+            self.summary.succeeded += 1;
+            BatchFlow::Continue
+        } else {
+            self.summary.failed += 1;
+            if self.options.stop_on_error {
+                BatchFlow::SkipRemaining
+            } else {
+                BatchFlow::Continue
+            }
+        };
 
-        results.push(BatchItemResult {
+        let item_result = BatchItemResult {
             index,
             notification_id: result.notification_id,
             delivered_to: result.delivered_to,
             failed: result.failed,
-            success: item_success,
+            success: result.success,
             error: None,
             skipped: None,
-        });
+        };
+        (item_result, flow)
+    }
 
-        if item_success {
-            succeeded += 1;
-        } else {
-            failed += 1;
+    /// Record an item skipped as a duplicate of an earlier item
+    fn duplicate_result(&mut self, index: usize, notification_id: Uuid) -> BatchItemResult {
+        self.summary.skipped += 1;
+        BatchItemResult {
+            index,
+            notification_id,
+            delivered_to: 0,
+            failed: 0,
+            success: true,
+            error: None,
+            skipped: Some(true),
+        }
+    }
+
+    /// Record an item that was not processed
+    fn skip(&mut self, index: usize, reason: &str) -> BatchItemResult {
+        self.summary.skipped += 1;
+        Self::error_result(index, reason.to_string(), Some(true))
+    }
+
+    fn error_result(index: usize, error: String, skipped: Option<bool>) -> BatchItemResult {
+        BatchItemResult {
+            index,
+            notification_id: Uuid::nil(),
+            delivered_to: 0,
+            failed: 0,
+            success: false,
+            error: Some(error),
+            skipped,
+        }
+    }
+}
+
+fn validate_batch_size(total: usize) -> Result<()> {
+    if total > MAX_BATCH_SIZE {
+        return Err(AppError::Validation(format!(
+            "Batch size {} exceeds maximum of {}",
+            total, MAX_BATCH_SIZE
+        )));
+    }
+
+    if total == 0 {
+        return Err(AppError::Validation("Batch cannot be empty".to_string()));
+    }
+
+    Ok(())
+}
 
-            // Stop on first error if requested
-            if request.options.stop_on_error {
+/// Verify the caller once for the whole batch if any item is sent on behalf of a user
+fn authorize_batch_caller(
+    state: &AppState,
+    headers: &HeaderMap,
+    request: &BatchSendRequest,
+) -> Result<Option<String>> {
+    if request.notifications.iter().any(|item| item.on_behalf_of.is_some()) {
+        Impersonation::authorize_caller(state, headers).map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+/// Send notifications in batch
+///
+/// Supports up to 100 notifications per batch with optional deduplication
+/// and stop-on-error behavior.
+#[tracing::instrument(
+    name = "http.batch_send",
+    skip(state, headers, request),
+    fields(
+        batch_size = request.notifications.len(),
+        stop_on_error = request.options.stop_on_error,
+        deduplicate = request.options.deduplicate,
+        dry_run = request.options.dry_run
+    )
+)]
+pub async fn batch_send(
+    State(state): State<AppState>,
+    tenant_ctx: Option<Extension<RequestTenantContext>>,
+    headers: HeaderMap,
+    Json(request): Json<BatchSendRequest>,
+) -> Result<Json<BatchSendResponse>> {
+    let tenant_ref = tenant_ctx.as_ref().map(|t| &t.0);
+    let total = request.notifications.len();
+    validate_batch_size(total)?;
+    let caller_id = authorize_batch_caller(&state, &headers, &request)?;
+
+    let mut run = BatchRun::new(&state, tenant_ref, request.options, caller_id, total);
+    let mut results = Vec::with_capacity(total);
+
+    for (index, item) in request.notifications.into_iter().enumerate() {
+        let (result, flow) = run.process(index, item).await;
+        results.push(result);
+        match flow {
+            BatchFlow::Continue => {}
+            BatchFlow::Stop => break,
+            BatchFlow::SkipRemaining => {
                 // Mark remaining items as skipped
                 for remaining_index in (index + 1)..total {
-                    results.push(BatchItemResult {
-                        index: remaining_index,
-                        notification_id: Uuid::nil(),
-                        delivered_to: 0,
-                        failed: 0,
-                        success: false,
-                        error: Some("Skipped due to stop_on_error".to_string()),
-                        skipped: Some(true),
-                    });
-                    skipped += 1;
+                    results.push(run.skip(remaining_index, STOP_ON_ERROR_SKIP));
                 }
                 break;
             }
@@ -326,19 +497,90 @@ pub async fn batch_send(
     }
 
     Ok(Json(BatchSendResponse {
-        batch_id,
+        batch_id: Uuid::new_v4(),
         results,
-        summary: BatchSummary {
-            total,
-            succeeded,
-            failed,
-            skipped,
-            total_delivered,
-        },
+        summary: run.summary,
         timestamp: Utc::now(),
     }))
 }
 
+/// Send notifications in batch, streaming each item result as it completes
+///
+/// Accepts the same body as `batch_send` and responds with `application/x-ndjson`: one
+/// `BatchItemResult` JSON line per item, written as soon as the item is processed. Items
+/// not processed within `server.stream_request_timeout_seconds` are reported as skipped.
+/// Processing stops if the client disconnects.
+#[tracing::instrument(
+    name = "http.batch_send_stream",
+    skip(state, headers, request),
+    fields(
+        batch_size = request.notifications.len(),
+        stop_on_error = request.options.stop_on_error,
+        deduplicate = request.options.deduplicate,
+        dry_run = request.options.dry_run
+    )
+)]
+pub async fn batch_send_stream(
+    State(state): State<AppState>,
+    tenant_ctx: Option<Extension<RequestTenantContext>>,
+    headers: HeaderMap,
+    Json(request): Json<BatchSendRequest>,
+) -> Result<Response> {
+    let total = request.notifications.len();
+    validate_batch_size(total)?;
+    let caller_id = authorize_batch_caller(&state, &headers, &request)?;
+
+    let timeout = Duration::from_secs(state.settings.server.stream_request_timeout_seconds);
+    let (tx, rx) = mpsc::channel::<std::result::Result<String, Infallible>>(STREAM_BUFFER_LINES);
+
+    tokio::spawn(async move {
+        let deadline = Instant::now() + timeout;
+        let tenant_ref = tenant_ctx.as_ref().map(|t| &t.0);
+        let mut run = BatchRun::new(&state, tenant_ref, request.options, caller_id, total);
+        let mut items = request.notifications.into_iter().enumerate();
+
+        while let Some((index, item)) = items.next() {
+            let mut skip_reason = STOP_ON_ERROR_SKIP;
+            let (result, flow) = match tokio::time::timeout_at(deadline, run.process(index, item)).await {
+                Ok(processed) => processed,
+                Err(_) => {
+                    tracing::warn!(index, total, "Batch stream timed out");
+                    skip_reason = STREAM_TIMEOUT_SKIP;
+                    (run.skip(index, skip_reason), BatchFlow::SkipRemaining)
+                }
+            };
+            if tx.send(Ok(ndjson_line(&result))).await.is_err() {
+                tracing::debug!(index, "Batch stream client disconnected");
+                return;
+            }
+
+            match flow {
+                BatchFlow::Continue => continue,
+                BatchFlow::Stop => break,
+                BatchFlow::SkipRemaining => {}
+            }
+            for (remaining_index, _) in items.by_ref() {
+                let line = ndjson_line(&run.skip(remaining_index, skip_reason));
+                if tx.send(Ok(line)).await.is_err() {
+                    return;
+                }
+            }
+        }
+    });
+
+    Ok((
+        [(header::CONTENT_TYPE, NDJSON_CONTENT_TYPE)],
+        Body::from_stream(ReceiverStream::new(rx)),
+    )
+        .into_response())
+}
+
+fn ndjson_line(result: &BatchItemResult) -> String {
+    let mut line = serde_json::to_string(result).unwrap_or_default();
+    line.push('\n');
+    line
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -426,6 +668,17 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_batch_target_parse_channel_pattern() {
+        let json = r#"{"type": "channel_pattern", "value": "orders.*"}"#;
+        let target: BatchTarget = serde_json::from_str(json).unwrap();
+        assert_eq!(target.dedup_key(), "channel_pattern:orders.*");
+        match target.into_notification_target(None) {
+            NotificationTarget::ChannelPattern(pattern) => assert_eq!(pattern, "orders.*"),
+            _ => panic!("Expected ChannelPattern target"),
+        }
+    }
+
     #[test]
     fn test_batch_target_parse_users() {
         let json = r#"{"type": "users", "value": ["user-1", "user-2"]}"#;
@@ -509,6 +762,180 @@ mod tests {
         assert!(request.options.deduplicate);
     }
 
+    #[tokio::test]
+    async fn test_batch_stream_writes_one_line_per_item() {
+        use axum::http::StatusCode;
+        use futures::StreamExt;
+        use serde_json::json;
+        use tower::ServiceExt;
+
+        use crate::api::test_support::{json_request, test_state};
+        use crate::server::create_app;
+
+        let state = test_state().await;
+        let (tx, _rx) = tokio::sync::mpsc::channel(8);
+        state
+            .connection_manager
+            .register("user-1".to_string(), "default".to_string(), vec![], tx)
+            .unwrap();
+        let app = create_app(state);
+
+        let item = json!({
+            "target": { "type": "user", "value": "user-1" },
+            "event_type": "order.created",
+            "payload": {}
+        });
+        let mut request = json_request(
+            "POST",
+            "/api/v1/notifications/batch-stream",
+            json!({
+                "notifications": [item, item, { "target": { "type": "broadcast" }, "template_id": "missing" }],
+                "options": { "deduplicate": true }
+            }),
+        );
+        request
+            .headers_mut()
+            .insert(header::ACCEPT_ENCODING, "gzip".parse().unwrap());
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], NDJSON_CONTENT_TYPE);
+        assert!(response.headers().get(header::CONTENT_ENCODING).is_none());
+
+        // Each result arrives as its own chunk, one JSON line per item
+        let chunks: Vec<_> = response
+            .into_body()
+            .into_data_stream()
+            .map(|chunk| String::from_utf8(chunk.unwrap().to_vec()).unwrap())
+            .collect()
+            .await;
+        assert_eq!(chunks.len(), 3);
+        let lines: Vec<serde_json::Value> = chunks
+            .iter()
+            .map(|chunk| {
+                assert!(chunk.ends_with('\n'));
+                serde_json::from_str(chunk.trim_end()).unwrap()
+            })
+            .collect();
+
+        assert_eq!(lines[0]["index"], 0);
+        assert_eq!(lines[0]["delivered_to"], 1);
+        assert_eq!(lines[1]["skipped"], true);
+        assert_eq!(lines[2]["success"], false);
+        assert!(lines[2]["error"].as_str().unwrap().contains("missing"));
+    }
+
+    #[tokio::test]
+    async fn test_batch_skips_repeated_notification_ids() {
+        use axum::http::StatusCode;
+        use serde_json::json;
+        use tower::ServiceExt;
+
+        use crate::api::test_support::{json_request, test_state};
+        use crate::server::create_app;
+
+        let state = test_state().await;
+        let (tx, mut rx) = tokio::sync::mpsc::channel(8);
+        state
+            .connection_manager
+            .register("user-1".to_string(), "default".to_string(), vec![], tx)
+            .unwrap();
+        let app = create_app(state);
+
+        let notification_id = Uuid::new_v4();
+        let item = json!({
+            "target": { "type": "user", "value": "user-1" },
+            "event_type": "order.created",
+            "payload": {},
+            "notification_id": notification_id
+        });
+        let response = app
+            .clone()
+            .oneshot(json_request(
+                "POST",
+                "/api/v1/notifications/batch",
+                json!({ "notifications": [item, item] }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["summary"]["skipped"], 1);
+        assert_eq!(body["results"][0]["notification_id"], notification_id.to_string());
+        assert_eq!(body["results"][1]["notification_id"], notification_id.to_string());
+        assert_eq!(body["results"][1]["skipped"], true);
+
+        // Retrying the send in a later request returns the first result without redelivery
+        let response = app
+            .oneshot(json_request(
+                "POST",
+                "/api/v1/notifications/send",
+                json!({
+                    "target_user_id": "user-1",
+                    "event_type": "order.created",
+                    "payload": {},
+                    "notification_id": notification_id
+                }),
+            ))
+            .await
+            .unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["notification_id"], notification_id.to_string());
+        assert_eq!(body["delivered_to"], 1);
+
+        assert!(rx.try_recv().is_ok());
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_batch_dry_run_counts_connections_without_sending() {
+        use axum::http::StatusCode;
+        use serde_json::json;
+        use tower::ServiceExt;
+
+        use crate::api::test_support::{json_request, test_state};
+        use crate::server::create_app;
+
+        let state = test_state().await;
+        let (tx, mut rx) = tokio::sync::mpsc::channel(8);
+        state
+            .connection_manager
+            .register("user-1".to_string(), "default".to_string(), vec![], tx)
+            .unwrap();
+        let app = create_app(state);
+
+        let response = app
+            .oneshot(json_request(
+                "POST",
+                "/api/v1/notifications/batch",
+                json!({
+                    "notifications": [
+                        { "target": { "type": "user", "value": "user-1" }, "event_type": "order.created", "payload": {} },
+                        { "target": { "type": "user", "value": "offline" }, "event_type": "order.created", "payload": {} },
+                        { "target": { "type": "broadcast" }, "template_id": "missing" }
+                    ],
+                    "options": { "dry_run": true }
+                }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(body["results"][0]["delivered_to"], 1);
+        assert_eq!(body["results"][0]["success"], true);
+        assert_eq!(body["results"][1]["delivered_to"], 0);
+        assert_eq!(body["results"][1]["success"], true);
+        // Inputs are still validated
+        assert_eq!(body["results"][2]["success"], false);
+        assert_eq!(body["summary"]["dry_run"], true);
+        assert_eq!(body["summary"]["total_delivered"], 1);
+
+        assert!(rx.try_recv().is_err());
+    }
+
     #[test]
     fn test_batch_options_default() {
         let json = r#"{
@@ -518,5 +945,6 @@ mod tests {
         let request: BatchSendRequest = serde_json::from_str(json).unwrap();
         assert!(!request.options.stop_on_error);
         assert!(!request.options.deduplicate);
+        assert!(!request.options.dry_run);
     }
 }
@@ -4,7 +4,7 @@ use serde::Deserialize;
 
 use crate::error::{AppError, Result};
 use crate::notification::Priority;
-use crate::template::{substitute_variables, TemplateStore};
+use crate::template::TemplateStore;
 
 /// Content specification for notifications - either direct or template-based
 #[derive(Debug, Deserialize)]
@@ -70,20 +70,16 @@ impl NotificationContent {
                     &template_id,
                 );
 
-                // Get the template
-                let template = template_store
-                    .get(&scoped_id)
-                    .map_err(|e| AppError::Validation(e.to_string()))?;
-
-                // Substitute variables in the payload template
-                let payload = substitute_variables(&template.payload_template, &variables)
+                // Render the template (resolves inheritance and substitutes variables)
+                let rendered = template_store
+                    .render(&scoped_id, &variables)
                     .map_err(|e| AppError::Validation(e.to_string()))?;
 
                 Ok(ResolvedContent {
-                    event_type: template.event_type,
-                    payload,
-                    priority: priority_override.unwrap_or(template.default_priority),
-                    ttl: ttl_override.or(template.default_ttl),
+                    event_type: rendered.event_type,
+                    payload: rendered.payload,
+                    priority: priority_override.unwrap_or(rendered.priority),
+                    ttl: ttl_override.or(rendered.ttl),
                 })
             }
             NotificationContent::Direct { event_type, payload } => Ok(ResolvedContent {
@@ -1,15 +1,25 @@
 //! HTTP notification handlers
 
-use axum::{extract::State, Extension, Json};
+use std::collections::HashSet;
+
+use axum::{
+    extract::{Path, State},
+    http::HeaderMap,
+    Extension, Json,
+};
 use chrono::Utc;
 
+use uuid::Uuid;
+
 use crate::error::Result;
-use crate::notification::NotificationBuilder;
+use crate::notification::{DeliveryResult, NotificationBuilder, NotificationEvent, NotificationTarget};
 use crate::server::middleware::RequestTenantContext;
 use crate::server::AppState;
 
+use super::impersonation::Impersonation;
 use super::models::{
-    BroadcastNotificationRequest, ChannelNotificationRequest, MultiChannelNotificationRequest,
+    BroadcastNotificationRequest, ChannelNotificationRequest, ChannelPathNotificationRequest,
+    MultiChannelNotificationRequest,
     SendNotificationRequest, SendNotificationResponse, SendToUsersRequest,
 };
 
@@ -21,19 +31,54 @@ const MAX_TARGET_USERS: usize = 10_000;
 /// Maximum number of channels in a single multi-channel request
 const MAX_CHANNELS: usize = 100;
 
+/// Dispatch an event, deduplicating by its ID when the caller provided one
+pub(super) async fn dispatch(
+    state: &AppState,
+    target: NotificationTarget,
+    event: NotificationEvent,
+    tenant_id: Option<&str>,
+    caller_provided_id: Option<Uuid>,
+) -> DeliveryResult {
+    dispatch_excluding(state, target, event, tenant_id, caller_provided_id, &HashSet::new()).await
+}
+
+/// Dispatch an event to channel targets without reaching `exclude_user_ids`,
+/// deduplicating by its ID when the caller provided one
+async fn dispatch_excluding(
+    state: &AppState,
+    target: NotificationTarget,
+    event: NotificationEvent,
+    tenant_id: Option<&str>,
+    caller_provided_id: Option<Uuid>,
+    exclude_user_ids: &HashSet<String>,
+) -> DeliveryResult {
+    let notification_id = event.id;
+    let send = state
+        .dispatcher
+        .dispatch_for_tenant_excluding(target, event, tenant_id, exclude_user_ids);
+    if caller_provided_id.is_some() {
+        state.dispatcher.deduplicate(notification_id, tenant_id, send).await
+    } else {
+        send.await
+    }
+}
+
 /// Send notification to a specific user
 #[tracing::instrument(
     name = "http.send_notification",
-    skip(state, request, tenant_ctx),
+    skip(state, headers, request, tenant_ctx),
     fields(target_user_id = %request.target_user_id)
 )]
 pub async fn send_notification(
     State(state): State<AppState>,
     tenant_ctx: Option<Extension<RequestTenantContext>>,
+    headers: HeaderMap,
     Json(request): Json<SendNotificationRequest>,
 ) -> Result<Json<SendNotificationResponse>> {
     let tenant_id = tenant_ctx.as_ref().map(|t| t.0.tenant_id());
 
+    let impersonation = Impersonation::authorize(&state, &headers, request.on_behalf_of)?;
+
     // Resolve content (from template or direct)
     let resolved = request
         .content
@@ -51,15 +96,22 @@ pub async fn send_notification(
         builder = builder.correlation_id(correlation_id);
     }
 
-    let event = builder.build();
-    let result = state
-        .dispatcher
-        .dispatch_for_tenant(
-            crate::notification::NotificationTarget::User(request.target_user_id),
-            event,
-            tenant_id,
-        )
-        .await;
+    if let Some(notification_id) = request.notification_id {
+        builder = builder.id(notification_id);
+    }
+
+    if let Some(impersonation) = &impersonation {
+        builder = impersonation.apply(builder);
+    }
+
+    let result = dispatch(
+        &state,
+        NotificationTarget::User(request.target_user_id),
+        builder.build(),
+        tenant_id,
+        request.notification_id,
+    )
+    .await;
 
     Ok(Json(SendNotificationResponse {
         success: result.success,
@@ -67,18 +119,20 @@ pub async fn send_notification(
         delivered_to: result.delivered_to,
         failed: result.failed,
         timestamp: Utc::now(),
+        per_user_status: None,
     }))
 }
 
 /// Send notification to multiple users
 #[tracing::instrument(
     name = "http.send_to_users",
-    skip(state, request, tenant_ctx),
+    skip(state, headers, request, tenant_ctx),
     fields(user_count = request.target_user_ids.len())
 )]
 pub async fn send_to_users(
     State(state): State<AppState>,
     tenant_ctx: Option<Extension<RequestTenantContext>>,
+    headers: HeaderMap,
     Json(request): Json<SendToUsersRequest>,
 ) -> Result<Json<SendNotificationResponse>> {
     let tenant_id = tenant_ctx.as_ref().map(|t| t.0.tenant_id());
@@ -92,6 +146,8 @@ pub async fn send_to_users(
         )));
     }
 
+    let impersonation = Impersonation::authorize(&state, &headers, request.on_behalf_of)?;
+
     // Resolve content (from template or direct)
     let resolved = request
         .content
@@ -109,15 +165,22 @@ pub async fn send_to_users(
         builder = builder.correlation_id(correlation_id);
     }
 
-    let event = builder.build();
-    let result = state
-        .dispatcher
-        .dispatch_for_tenant(
-            crate::notification::NotificationTarget::Users(request.target_user_ids),
-            event,
-            tenant_id,
-        )
-        .await;
+    if let Some(notification_id) = request.notification_id {
+        builder = builder.id(notification_id);
+    }
+
+    if let Some(impersonation) = &impersonation {
+        builder = impersonation.apply(builder);
+    }
+
+    let result = dispatch(
+        &state,
+        NotificationTarget::Users(request.target_user_ids),
+        builder.build(),
+        tenant_id,
+        request.notification_id,
+    )
+    .await;
 
     Ok(Json(SendNotificationResponse {
         success: result.success,
@@ -125,22 +188,28 @@ pub async fn send_to_users(
         delivered_to: result.delivered_to,
         failed: result.failed,
         timestamp: Utc::now(),
+        per_user_status: result
+            .per_user_status
+            .filter(|_| request.include_per_user_status),
     }))
 }
 
 /// Broadcast notification to all connected users
 #[tracing::instrument(
     name = "http.broadcast",
-    skip(state, request, tenant_ctx),
+    skip(state, headers, request, tenant_ctx),
     fields(audience = ?request.audience)
 )]
 pub async fn broadcast_notification(
     State(state): State<AppState>,
     tenant_ctx: Option<Extension<RequestTenantContext>>,
+    headers: HeaderMap,
     Json(request): Json<BroadcastNotificationRequest>,
 ) -> Result<Json<SendNotificationResponse>> {
     let tenant_id = tenant_ctx.as_ref().map(|t| t.0.tenant_id());
 
+    let impersonation = Impersonation::authorize(&state, &headers, request.on_behalf_of)?;
+
     // Resolve content (from template or direct)
     let resolved = request
         .content
@@ -162,15 +231,22 @@ pub async fn broadcast_notification(
         builder = builder.correlation_id(correlation_id);
     }
 
-    let event = builder.build();
-    let result = state
-        .dispatcher
-        .dispatch_for_tenant(
-            crate::notification::NotificationTarget::Broadcast,
-            event,
-            tenant_id,
-        )
-        .await;
+    if let Some(notification_id) = request.notification_id {
+        builder = builder.id(notification_id);
+    }
+
+    if let Some(impersonation) = &impersonation {
+        builder = impersonation.apply(builder);
+    }
+
+    let result = dispatch(
+        &state,
+        NotificationTarget::Broadcast,
+        builder.build(),
+        tenant_id,
+        request.notification_id,
+    )
+    .await;
 
     Ok(Json(SendNotificationResponse {
         success: result.success,
@@ -178,18 +254,20 @@ pub async fn broadcast_notification(
         delivered_to: result.delivered_to,
         failed: result.failed,
         timestamp: Utc::now(),
+        per_user_status: None,
     }))
 }
 
 /// Send notification to a channel
 #[tracing::instrument(
     name = "http.channel_notification",
-    skip(state, request, tenant_ctx),
+    skip(state, headers, request, tenant_ctx),
     fields(channel = %request.channel)
 )]
 pub async fn channel_notification(
     State(state): State<AppState>,
     tenant_ctx: Option<Extension<RequestTenantContext>>,
+    headers: HeaderMap,
     Json(request): Json<ChannelNotificationRequest>,
 ) -> Result<Json<SendNotificationResponse>> {
     let tenant_id = tenant_ctx.as_ref().map(|t| t.0.tenant_id());
@@ -200,6 +278,8 @@ pub async fn channel_notification(
         .map(|t| t.0.namespace_channel(&request.channel))
         .unwrap_or_else(|| request.channel.clone());
 
+    let impersonation = Impersonation::authorize(&state, &headers, request.on_behalf_of)?;
+
     // Resolve content (from template or direct)
     let resolved = request
         .content
@@ -217,11 +297,23 @@ pub async fn channel_notification(
         builder = builder.correlation_id(correlation_id);
     }
 
-    let event = builder.build();
-    let result = state
-        .dispatcher
-        .send_to_channel(&channel, event)
-        .await;
+    if let Some(notification_id) = request.notification_id {
+        builder = builder.id(notification_id);
+    }
+
+    if let Some(impersonation) = &impersonation {
+        builder = impersonation.apply(builder);
+    }
+
+    let result = dispatch_excluding(
+        &state,
+        NotificationTarget::Channel(channel),
+        builder.build(),
+        tenant_id,
+        request.notification_id,
+        &request.exclude_user_ids.unwrap_or_default(),
+    )
+    .await;
 
     Ok(Json(SendNotificationResponse {
         success: result.success,
@@ -229,18 +321,33 @@ pub async fn channel_notification(
         delivered_to: result.delivered_to,
         failed: result.failed,
         timestamp: Utc::now(),
+        per_user_status: None,
     }))
 }
 
+/// Send notification to the channel named in the URL path
+/// (`POST /channels/{name}/notifications`), otherwise identical to `channel_notification`
+pub async fn channel_path_notification(
+    state: State<AppState>,
+    tenant_ctx: Option<Extension<RequestTenantContext>>,
+    Path(channel): Path<String>,
+    headers: HeaderMap,
+    Json(request): Json<ChannelPathNotificationRequest>,
+) -> Result<Json<SendNotificationResponse>> {
+    let request = request.into_channel_request(channel);
+    channel_notification(state, tenant_ctx, headers, Json(request)).await
+}
+
 /// Send notification to multiple channels
 #[tracing::instrument(
     name = "http.multi_channel_notification",
-    skip(state, request, tenant_ctx),
+    skip(state, headers, request, tenant_ctx),
     fields(channel_count = request.channels.len())
 )]
 pub async fn multi_channel_notification(
     State(state): State<AppState>,
     tenant_ctx: Option<Extension<RequestTenantContext>>,
+    headers: HeaderMap,
     Json(request): Json<MultiChannelNotificationRequest>,
 ) -> Result<Json<SendNotificationResponse>> {
     // Validate array size
@@ -264,6 +371,8 @@ pub async fn multi_channel_notification(
         None => request.channels,
     };
 
+    let impersonation = Impersonation::authorize(&state, &headers, request.on_behalf_of)?;
+
     // Resolve content (from template or direct)
     let resolved = request
         .content
@@ -281,11 +390,23 @@ pub async fn multi_channel_notification(
         builder = builder.correlation_id(correlation_id);
     }
 
-    let event = builder.build();
-    let result = state
-        .dispatcher
-        .send_to_channels(&channels, event)
-        .await;
+    if let Some(notification_id) = request.notification_id {
+        builder = builder.id(notification_id);
+    }
+
+    if let Some(impersonation) = &impersonation {
+        builder = impersonation.apply(builder);
+    }
+
+    let result = dispatch_excluding(
+        &state,
+        NotificationTarget::Channels(channels),
+        builder.build(),
+        tenant_id,
+        request.notification_id,
+        &request.exclude_user_ids.unwrap_or_default(),
+    )
+    .await;
 
     Ok(Json(SendNotificationResponse {
         success: result.success,
@@ -293,5 +414,6 @@ pub async fn multi_channel_notification(
         delivered_to: result.delivered_to,
         failed: result.failed,
         timestamp: Utc::now(),
+        per_user_status: None,
     }))
 }
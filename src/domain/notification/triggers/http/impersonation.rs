@@ -0,0 +1,183 @@
+//! Service-to-service impersonation for the notification API
+//!
+//! A send request with `on_behalf_of` is made by a service acting for a user. The
+//! calling service must present a JWT (`Authorization: Bearer ...`) carrying the
+//! `impersonate` scope; its `sub` is recorded as the caller in the audit trail.
+
+use axum::http::{header, HeaderMap};
+
+use crate::error::{AppError, Result};
+use crate::metrics::NOTIFICATIONS_IMPERSONATED_TOTAL;
+use crate::notification::{NotificationBuilder, CALLER_ID_HEADER};
+use crate::server::AppState;
+
+/// JWT scope required to send on behalf of another user
+pub(super) const IMPERSONATE_SCOPE: &str = "impersonate";
+
+/// A verified request to send on behalf of a user
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Impersonation {
+    /// `sub` of the calling service's JWT
+    pub caller_id: String,
+    /// User the notification is sent on behalf of
+    pub on_behalf_of: String,
+}
+
+impl Impersonation {
+    /// Verify that the caller may send on behalf of `on_behalf_of`.
+    ///
+    /// Returns `None` when the request is not impersonated. A missing or invalid token
+    /// is rejected as unauthenticated, a token without the `impersonate` scope as
+    /// forbidden.
+    pub fn authorize(
+        state: &AppState,
+        headers: &HeaderMap,
+        on_behalf_of: Option<String>,
+    ) -> Result<Option<Self>> {
+        match on_behalf_of {
+            Some(on_behalf_of) => Ok(Some(Self {
+                caller_id: Self::authorize_caller(state, headers)?,
+                on_behalf_of,
+            })),
+            None => Ok(None),
+        }
+    }
+
+    /// Verify that the caller may send on behalf of other users, returning its `sub`
+    pub fn authorize_caller(state: &AppState, headers: &HeaderMap) -> Result<String> {
+        let token = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .ok_or_else(|| {
+                AppError::Auth("on_behalf_of requires a bearer token".to_string())
+            })?;
+        let claims = state.jwt_validator.validate(token)?;
+
+        if !claims.has_scope(IMPERSONATE_SCOPE) {
+            tracing::warn!(caller_id = %claims.sub, "Impersonation rejected: missing scope");
+            return Err(AppError::Forbidden(format!(
+                "on_behalf_of requires the '{}' scope",
+                IMPERSONATE_SCOPE
+            )));
+        }
+
+        Ok(claims.sub)
+    }
+
+    /// Record the impersonation on the notification being built
+    pub fn apply(&self, builder: NotificationBuilder) -> NotificationBuilder {
+        NOTIFICATIONS_IMPERSONATED_TOTAL.inc();
+        builder
+            .impersonated_by(&self.on_behalf_of)
+            .header(CALLER_ID_HEADER, &self.caller_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use serde_json::json;
+    use tokio::sync::mpsc;
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::api::test_support::{
+        bearer_token, json_request, response_json, test_claims, test_state,
+    };
+    use crate::server::create_app;
+    use crate::websocket::{OutboundMessage, ServerMessage};
+
+    fn bearer(state: &AppState, scope: Option<&str>) -> String {
+        let mut claims = test_claims("billing-service");
+        claims.scope = scope.map(str::to_string);
+        bearer_token(state, &claims)
+    }
+
+    fn send_request(authorization: Option<String>) -> Request<Body> {
+        let mut request = json_request(
+            "POST",
+            "/api/v1/notifications/send",
+            json!({
+                "target_user_id": "user-1",
+                "event_type": "invoice.paid",
+                "payload": {},
+                "on_behalf_of": "alice"
+            }),
+        );
+        if let Some(authorization) = authorization {
+            request
+                .headers_mut()
+                .insert(header::AUTHORIZATION, authorization.parse().unwrap());
+        }
+        request
+    }
+
+    #[tokio::test]
+    async fn test_impersonation_rejected_without_scope() {
+        let state = test_state().await;
+        let (tx, mut rx) = mpsc::channel(8);
+        state
+            .connection_manager
+            .register("user-1".to_string(), "default".to_string(), vec![], tx)
+            .unwrap();
+        let app = create_app(state.clone());
+
+        let response = app.clone().oneshot(send_request(None)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = app
+            .clone()
+            .oneshot(send_request(Some(bearer(&state, Some("notify")))))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert_eq!(response_json(response).await["error"]["code"], "FORBIDDEN");
+
+        let mut batch = json_request(
+            "POST",
+            "/api/v1/notifications/batch",
+            json!({ "notifications": [{
+                "target": { "type": "user", "value": "user-1" },
+                "event_type": "invoice.paid",
+                "payload": {},
+                "on_behalf_of": "alice"
+            }] }),
+        );
+        batch
+            .headers_mut()
+            .insert(header::AUTHORIZATION, bearer(&state, None).parse().unwrap());
+        let response = app.oneshot(batch).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_impersonation_with_scope_marks_notification() {
+        let state = test_state().await;
+        let (tx, mut rx) = mpsc::channel(8);
+        state
+            .connection_manager
+            .register("user-1".to_string(), "default".to_string(), vec![], tx)
+            .unwrap();
+        let before = NOTIFICATIONS_IMPERSONATED_TOTAL.get();
+
+        let authorization = bearer(&state, Some("notify impersonate"));
+        let response = create_app(state)
+            .oneshot(send_request(Some(authorization)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response_json(response).await["delivered_to"], 1);
+        assert!(NOTIFICATIONS_IMPERSONATED_TOTAL.get() > before);
+
+        let Ok(OutboundMessage::Raw(ServerMessage::Notification { event })) = rx.try_recv()
+        else {
+            panic!("expected a notification");
+        };
+        assert_eq!(event.metadata.impersonated_by.as_deref(), Some("alice"));
+        assert_eq!(event.caller_id(), Some("billing-service"));
+    }
+}
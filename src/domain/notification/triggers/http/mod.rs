@@ -10,24 +10,25 @@
 mod batch;
 mod content;
 mod handlers;
+mod impersonation;
 mod models;
 
 // Re-export handlers
 pub use handlers::{
-    broadcast_notification, channel_notification, multi_channel_notification, send_notification,
-    send_to_users,
+    broadcast_notification, channel_notification, channel_path_notification,
+    multi_channel_notification, send_notification, send_to_users,
 };
 
 // Re-export batch
 pub use batch::{
-    batch_send, BatchItemResult, BatchNotificationItem, BatchOptions, BatchSendRequest,
-    BatchSendResponse, BatchSummary, BatchTarget,
+    batch_send, batch_send_stream, BatchItemResult, BatchNotificationItem, BatchOptions,
+    BatchSendRequest, BatchSendResponse, BatchSummary, BatchTarget, NDJSON_CONTENT_TYPE,
 };
 
 // Re-export models
 pub use models::{
-    BroadcastNotificationRequest, ChannelNotificationRequest, MultiChannelNotificationRequest,
-    SendNotificationRequest, SendNotificationResponse, SendToUsersRequest,
+    BroadcastNotificationRequest, ChannelNotificationRequest, ChannelPathNotificationRequest,
+    MultiChannelNotificationRequest, SendNotificationRequest, SendNotificationResponse, SendToUsersRequest,
 };
 
 // Re-export content types
@@ -1,10 +1,12 @@
 //! Request and response models for HTTP notification API
 
+use std::collections::{HashMap, HashSet};
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::notification::{Audience, Priority};
+use crate::notification::{Audience, Priority, UserDeliveryStatus};
 
 use super::content::NotificationContent;
 
@@ -24,8 +26,13 @@ pub struct SendNotificationRequest {
     pub priority: Option<Priority>,
     /// Optional TTL in seconds (overrides template default if provided)
     pub ttl: Option<u32>,
+    /// Optional caller-provided notification ID; repeated sends with the same ID are
+    /// delivered once
+    pub notification_id: Option<Uuid>,
     /// Optional correlation ID for tracing
     pub correlation_id: Option<String>,
+    /// User to send on behalf of; requires a bearer token with the `impersonate` scope
+    pub on_behalf_of: Option<String>,
 }
 
 /// Request to send notification to multiple users
@@ -37,6 +44,9 @@ pub struct SendNotificationRequest {
 pub struct SendToUsersRequest {
     /// Target user IDs
     pub target_user_ids: Vec<String>,
+    /// Report the outcome for each user in `per_user_status`
+    #[serde(default)]
+    pub include_per_user_status: bool,
     /// Notification content (direct or template-based)
     #[serde(flatten)]
     pub content: NotificationContent,
@@ -44,8 +54,13 @@ pub struct SendToUsersRequest {
     pub priority: Option<Priority>,
     /// Optional TTL in seconds (overrides template default if provided)
     pub ttl: Option<u32>,
+    /// Optional caller-provided notification ID; repeated sends with the same ID are
+    /// delivered once
+    pub notification_id: Option<Uuid>,
     /// Optional correlation ID
     pub correlation_id: Option<String>,
+    /// User to send on behalf of; requires a bearer token with the `impersonate` scope
+    pub on_behalf_of: Option<String>,
 }
 
 /// Request to broadcast notification to all users
@@ -62,10 +77,15 @@ pub struct BroadcastNotificationRequest {
     pub priority: Option<Priority>,
     /// Optional TTL in seconds (overrides template default if provided)
     pub ttl: Option<u32>,
+    /// Optional caller-provided notification ID; repeated sends with the same ID are
+    /// delivered once
+    pub notification_id: Option<Uuid>,
     /// Optional target audience filter
     pub audience: Option<Audience>,
     /// Optional correlation ID
     pub correlation_id: Option<String>,
+    /// User to send on behalf of; requires a bearer token with the `impersonate` scope
+    pub on_behalf_of: Option<String>,
 }
 
 /// Request to send notification to a channel
@@ -84,8 +104,53 @@ pub struct ChannelNotificationRequest {
     pub priority: Option<Priority>,
     /// Optional TTL in seconds (overrides template default if provided)
     pub ttl: Option<u32>,
+    /// Optional caller-provided notification ID; repeated sends with the same ID are
+    /// delivered once
+    pub notification_id: Option<Uuid>,
     /// Optional correlation ID
     pub correlation_id: Option<String>,
+    /// User to send on behalf of; requires a bearer token with the `impersonate` scope
+    pub on_behalf_of: Option<String>,
+    /// Users whose connections should not receive the notification (e.g. the sender)
+    pub exclude_user_ids: Option<HashSet<String>>,
+}
+
+/// Body of `POST /channels/{name}/notifications`: a [`ChannelNotificationRequest`]
+/// whose channel comes from the URL path
+#[derive(Debug, Deserialize)]
+pub struct ChannelPathNotificationRequest {
+    /// Notification content (direct or template-based)
+    #[serde(flatten)]
+    pub content: NotificationContent,
+    /// Priority level (overrides template default if provided)
+    pub priority: Option<Priority>,
+    /// Optional TTL in seconds (overrides template default if provided)
+    pub ttl: Option<u32>,
+    /// Optional caller-provided notification ID; repeated sends with the same ID are
+    /// delivered once
+    pub notification_id: Option<Uuid>,
+    /// Optional correlation ID
+    pub correlation_id: Option<String>,
+    /// User to send on behalf of; requires a bearer token with the `impersonate` scope
+    pub on_behalf_of: Option<String>,
+    /// Users whose connections should not receive the notification (e.g. the sender)
+    pub exclude_user_ids: Option<HashSet<String>>,
+}
+
+impl ChannelPathNotificationRequest {
+    /// The equivalent `/notifications/channel` request for `channel`
+    pub fn into_channel_request(self, channel: String) -> ChannelNotificationRequest {
+        ChannelNotificationRequest {
+            channel,
+            content: self.content,
+            priority: self.priority,
+            ttl: self.ttl,
+            notification_id: self.notification_id,
+            correlation_id: self.correlation_id,
+            on_behalf_of: self.on_behalf_of,
+            exclude_user_ids: self.exclude_user_ids,
+        }
+    }
 }
 
 /// Request to send notification to multiple channels
@@ -104,8 +169,15 @@ pub struct MultiChannelNotificationRequest {
     pub priority: Option<Priority>,
     /// Optional TTL in seconds (overrides template default if provided)
     pub ttl: Option<u32>,
+    /// Optional caller-provided notification ID; repeated sends with the same ID are
+    /// delivered once
+    pub notification_id: Option<Uuid>,
     /// Optional correlation ID
     pub correlation_id: Option<String>,
+    /// User to send on behalf of; requires a bearer token with the `impersonate` scope
+    pub on_behalf_of: Option<String>,
+    /// Users whose connections should not receive the notification (e.g. the sender)
+    pub exclude_user_ids: Option<HashSet<String>>,
 }
 
 /// Response for notification send operations
@@ -121,4 +193,7 @@ pub struct SendNotificationResponse {
     pub failed: usize,
     /// Timestamp of the operation
     pub timestamp: DateTime<Utc>,
+    /// Outcome for each target user (send-to-users with `include_per_user_status` only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub per_user_status: Option<HashMap<String, UserDeliveryStatus>>,
 }
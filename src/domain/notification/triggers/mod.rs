@@ -1,11 +1,20 @@
+mod grpc;
 mod http;
+mod quarantine;
 mod redis;
 
+pub use grpc::{proto as grpc_proto, GrpcNotificationService};
 pub use http::{
-    batch_send, broadcast_notification, channel_notification, multi_channel_notification,
-    send_notification, send_to_users, BatchItemResult, BatchNotificationItem, BatchOptions,
-    BatchSendRequest, BatchSendResponse, BatchSummary, BatchTarget, BroadcastNotificationRequest,
-    ChannelNotificationRequest, MultiChannelNotificationRequest, NotificationContent,
+    batch_send, batch_send_stream, broadcast_notification, channel_notification,
+    channel_path_notification, multi_channel_notification, send_notification, send_to_users,
+    BatchItemResult, BatchNotificationItem, BatchOptions, BatchSendRequest, BatchSendResponse,
+    BatchSummary, BatchTarget, BroadcastNotificationRequest, ChannelNotificationRequest,
+    ChannelPathNotificationRequest, MultiChannelNotificationRequest, NotificationContent,
     ResolvedContent, SendNotificationRequest, SendNotificationResponse, SendToUsersRequest,
+    NDJSON_CONTENT_TYPE,
+};
+pub use quarantine::{
+    create_quarantine_store, MemoryQuarantineStore, QuarantineError, QuarantineStore,
+    QuarantinedMessage, RedisQuarantineStore, QUARANTINE_KEY, QUARANTINE_MAX_ENTRIES,
 };
 pub use redis::RedisSubscriber;
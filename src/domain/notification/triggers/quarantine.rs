@@ -0,0 +1,227 @@
+//! Quarantine for Redis Pub/Sub messages that fail to deserialize.
+//!
+//! Instead of dropping malformed messages, the subscriber keeps them (raw payload, source
+//! channel, parse error and timestamp) so they can be inspected and reprocessed through
+//! the admin API once the publisher or schema is fixed. Entries are stored newest first
+//! and capped at [`QUARANTINE_MAX_ENTRIES`].
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::redis::pool::{PoolError, RedisPool, RedisPoolExt};
+
+/// Redis list holding quarantined messages
+pub const QUARANTINE_KEY: &str = "ara:quarantine:messages";
+
+/// Maximum number of quarantined messages kept (older entries are trimmed)
+pub const QUARANTINE_MAX_ENTRIES: usize = 1000;
+
+/// Errors that can occur during quarantine operations.
+#[derive(Debug, Error)]
+pub enum QuarantineError {
+    /// Redis operation failed
+    #[error("Redis error: {0}")]
+    Redis(#[from] PoolError),
+
+    /// Serialization error
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+}
+
+/// A Redis message that could not be deserialized
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuarantinedMessage {
+    /// Channel the message was received on
+    pub channel: String,
+    /// Raw message payload
+    pub payload: String,
+    /// Deserialization error
+    pub error: String,
+    /// When the message was quarantined
+    pub quarantined_at: DateTime<Utc>,
+}
+
+impl QuarantinedMessage {
+    /// Create a quarantine entry timestamped now
+    pub fn new(channel: &str, payload: &str, error: impl ToString) -> Self {
+        Self {
+            channel: channel.to_string(),
+            payload: payload.to_string(),
+            error: error.to_string(),
+            quarantined_at: Utc::now(),
+        }
+    }
+}
+
+/// Storage for quarantined messages.
+///
+/// Index 0 is the most recently quarantined message.
+#[async_trait]
+pub trait QuarantineStore: Send + Sync {
+    /// Add a message, trimming the oldest entries beyond `QUARANTINE_MAX_ENTRIES`
+    async fn push(&self, message: QuarantinedMessage) -> Result<(), QuarantineError>;
+
+    /// List all quarantined messages, newest first
+    async fn list(&self) -> Result<Vec<QuarantinedMessage>, QuarantineError>;
+
+    /// Get the message at `index`
+    async fn get(&self, index: usize) -> Result<Option<QuarantinedMessage>, QuarantineError>;
+
+    /// Remove a message (e.g. after it was reprocessed); returns whether it was present
+    async fn remove(&self, message: &QuarantinedMessage) -> Result<bool, QuarantineError>;
+}
+
+/// In-memory quarantine, used when no Redis pool is available
+#[derive(Default)]
+pub struct MemoryQuarantineStore {
+    messages: Mutex<VecDeque<QuarantinedMessage>>,
+}
+
+impl MemoryQuarantineStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl QuarantineStore for MemoryQuarantineStore {
+    async fn push(&self, message: QuarantinedMessage) -> Result<(), QuarantineError> {
+        let mut messages = self.messages.lock().unwrap();
+        messages.push_front(message);
+        messages.truncate(QUARANTINE_MAX_ENTRIES);
+        Ok(())
+    }
+
+    async fn list(&self) -> Result<Vec<QuarantinedMessage>, QuarantineError> {
+        Ok(self.messages.lock().unwrap().iter().cloned().collect())
+    }
+
+    async fn get(&self, index: usize) -> Result<Option<QuarantinedMessage>, QuarantineError> {
+        Ok(self.messages.lock().unwrap().get(index).cloned())
+    }
+
+    async fn remove(&self, message: &QuarantinedMessage) -> Result<bool, QuarantineError> {
+        let mut messages = self.messages.lock().unwrap();
+        match messages.iter().position(|m| m == message) {
+            Some(position) => {
+                messages.remove(position);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+}
+
+/// Redis-backed quarantine stored as JSON entries in the `QUARANTINE_KEY` list
+pub struct RedisQuarantineStore {
+    pool: Arc<dyn RedisPoolExt + Send + Sync>,
+}
+
+impl RedisQuarantineStore {
+    /// Create a store over any `RedisPoolExt` implementation
+    pub fn new(pool: Arc<dyn RedisPoolExt + Send + Sync>) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl QuarantineStore for RedisQuarantineStore {
+    async fn push(&self, message: QuarantinedMessage) -> Result<(), QuarantineError> {
+        let entry = serde_json::to_string(&message)?;
+        self.pool
+            .lpush_trim(QUARANTINE_KEY, &entry, QUARANTINE_MAX_ENTRIES)
+            .await?;
+        Ok(())
+    }
+
+    async fn list(&self) -> Result<Vec<QuarantinedMessage>, QuarantineError> {
+        let entries = self.pool.lrange(QUARANTINE_KEY, 0, -1).await?;
+        Ok(entries
+            .iter()
+            .filter_map(|entry| match serde_json::from_str(entry) {
+                Ok(message) => Some(message),
+                Err(e) => {
+                    tracing::warn!(error = %e, "Skipping unreadable quarantine entry");
+                    None
+                }
+            })
+            .collect())
+    }
+
+    async fn get(&self, index: usize) -> Result<Option<QuarantinedMessage>, QuarantineError> {
+        let Ok(index) = isize::try_from(index) else {
+            return Ok(None);
+        };
+        match self.pool.lindex(QUARANTINE_KEY, index).await? {
+            Some(entry) => Ok(Some(serde_json::from_str(&entry)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn remove(&self, message: &QuarantinedMessage) -> Result<bool, QuarantineError> {
+        let entry = serde_json::to_string(message)?;
+        let removed = self.pool.lrem(QUARANTINE_KEY, 1, &entry).await?;
+        Ok(removed > 0)
+    }
+}
+
+/// Create the quarantine store: Redis when a pool is available, otherwise in-memory
+pub fn create_quarantine_store(redis_pool: Option<Arc<RedisPool>>) -> Arc<dyn QuarantineStore> {
+    match redis_pool {
+        Some(pool) => Arc::new(RedisQuarantineStore::new(pool)),
+        None => Arc::new(MemoryQuarantineStore::new()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::redis::pool::MockRedisPoolExt;
+
+    #[tokio::test]
+    async fn test_memory_store_is_newest_first_and_capped() {
+        let store = MemoryQuarantineStore::new();
+        for i in 0..QUARANTINE_MAX_ENTRIES + 5 {
+            store
+                .push(QuarantinedMessage::new("notification:broadcast", &i.to_string(), "bad"))
+                .await
+                .unwrap();
+        }
+
+        let messages = store.list().await.unwrap();
+        assert_eq!(messages.len(), QUARANTINE_MAX_ENTRIES);
+        assert_eq!(messages[0].payload, (QUARANTINE_MAX_ENTRIES + 4).to_string());
+
+        let newest = store.get(0).await.unwrap().unwrap();
+        assert!(store.remove(&newest).await.unwrap());
+        assert!(!store.remove(&newest).await.unwrap());
+        assert_eq!(store.list().await.unwrap().len(), QUARANTINE_MAX_ENTRIES - 1);
+    }
+
+    #[tokio::test]
+    async fn test_redis_store_round_trip() {
+        let message = QuarantinedMessage::new("notification:user:u1", "{oops", "expected value");
+        let entry = serde_json::to_string(&message).unwrap();
+
+        let mut pool = MockRedisPoolExt::new();
+        let stored = entry.clone();
+        pool.expect_lindex()
+            .withf(|key, index| key == QUARANTINE_KEY && *index == 0)
+            .times(1)
+            .returning(move |_, _| Ok(Some(stored.clone())));
+        pool.expect_lrem()
+            .withf(move |key, count, value| key == QUARANTINE_KEY && *count == 1 && value == entry)
+            .times(1)
+            .returning(|_, _, _| Ok(1));
+
+        let store = RedisQuarantineStore::new(Arc::new(pool));
+        let fetched = store.get(0).await.unwrap().unwrap();
+        assert_eq!(fetched, message);
+        assert!(store.remove(&fetched).await.unwrap());
+    }
+}
@@ -1,20 +1,29 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
+use async_trait::async_trait;
+use futures::stream::BoxStream;
 use futures::StreamExt;
 use serde::Deserialize;
 use tokio::sync::broadcast;
 
 use crate::config::RedisConfig;
-use crate::notification::{NotificationBuilder, NotificationDispatcher, NotificationTarget, Priority};
+use crate::metrics::{REDIS_MESSAGES_QUARANTINED_TOTAL, REDIS_PATTERN_SUBSCRIPTIONS};
+use crate::notification::{
+    DeliveryResult, NotificationBuilder, NotificationDispatcher, NotificationTarget,
+    PayloadEncoding, Priority,
+};
 use crate::redis::{
     BackoffConfig, CircuitBreaker, CircuitBreakerConfig, CircuitState,
     ExponentialBackoff, RedisHealth,
 };
 
+use super::quarantine::{MemoryQuarantineStore, QuarantineStore, QuarantinedMessage};
+
 /// Message format received from Redis Pub/Sub
 #[derive(Debug, Deserialize)]
 pub struct RedisNotificationMessage {
-    /// Target type: "user", "users", "broadcast", "channel", "channels"
+    /// Target type: "user", "users", "broadcast", "channel", "channels", "channel_pattern", "role"
     #[serde(rename = "type")]
     pub target_type: String,
     /// Target value (user_id, channel name, or list)
@@ -23,6 +32,9 @@ pub struct RedisNotificationMessage {
     pub event: RedisEventData,
     /// Optional tenant ID for multi-tenant isolation
     pub tenant_id: Option<String>,
+    /// Transport-level headers propagated to the event (never sent to clients)
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
 }
 
 /// Target specification in Redis message
@@ -47,6 +59,75 @@ pub struct RedisEventData {
     pub ttl: Option<u32>,
     /// Correlation ID (optional)
     pub correlation_id: Option<String>,
+    /// WebSocket wire encoding (`json` or `msgpack`, optional)
+    #[serde(default)]
+    pub payload_encoding: PayloadEncoding,
+}
+
+/// A message received from a Pub/Sub subscription
+#[derive(Debug, Clone)]
+pub(crate) struct PubSubMessage {
+    /// Channel the message was published on
+    pub channel: String,
+    /// Pattern that matched the channel (for `PSUBSCRIBE` subscriptions)
+    pub pattern: Option<String>,
+    pub payload: String,
+}
+
+/// Subscription side of a Pub/Sub connection, implemented by `redis::aio::PubSub`
+#[async_trait]
+pub(crate) trait PubSubConnection: Send {
+    /// Subscribe to a named channel (`SUBSCRIBE`)
+    async fn subscribe(&mut self, channel: &str) -> redis::RedisResult<()>;
+
+    /// Subscribe to a glob pattern (`PSUBSCRIBE`)
+    async fn psubscribe(&mut self, pattern: &str) -> redis::RedisResult<()>;
+
+    /// Incoming messages; the stream ends when the connection is lost
+    fn into_messages(self) -> BoxStream<'static, PubSubMessage>;
+}
+
+#[async_trait]
+impl PubSubConnection for redis::aio::PubSub {
+    async fn subscribe(&mut self, channel: &str) -> redis::RedisResult<()> {
+        redis::aio::PubSub::subscribe(self, channel).await
+    }
+
+    async fn psubscribe(&mut self, pattern: &str) -> redis::RedisResult<()> {
+        redis::aio::PubSub::psubscribe(self, pattern).await
+    }
+
+    fn into_messages(self) -> BoxStream<'static, PubSubMessage> {
+        self.into_on_message()
+            .filter_map(|msg| async move {
+                let payload = match msg.get_payload() {
+                    Ok(p) => p,
+                    Err(e) => {
+                        tracing::warn!(error = %e, "Failed to get message payload");
+                        return None;
+                    }
+                };
+                Some(PubSubMessage {
+                    channel: msg.get_channel_name().to_string(),
+                    pattern: msg.from_pattern().then(|| msg.get_pattern().ok()).flatten(),
+                    payload,
+                })
+            })
+            .boxed()
+    }
+}
+
+/// Channels (`SUBSCRIBE`) and patterns (`PSUBSCRIBE`) the subscriber listens on
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Subscriptions {
+    channels: Vec<String>,
+    patterns: Vec<String>,
+}
+
+impl Subscriptions {
+    fn is_empty(&self) -> bool {
+        self.channels.is_empty() && self.patterns.is_empty()
+    }
 }
 
 /// Resilient Redis Pub/Sub subscriber with circuit breaker and exponential backoff
@@ -56,6 +137,7 @@ pub struct RedisSubscriber {
     shutdown: broadcast::Sender<()>,
     circuit_breaker: Arc<CircuitBreaker>,
     health: Arc<RedisHealth>,
+    quarantine: Arc<dyn QuarantineStore>,
 }
 
 impl RedisSubscriber {
@@ -73,9 +155,16 @@ impl RedisSubscriber {
             shutdown,
             circuit_breaker,
             health,
+            quarantine: Arc::new(MemoryQuarantineStore::new()),
         }
     }
 
+    /// Use the given store for messages that fail to deserialize (default: in-memory)
+    pub fn with_quarantine(mut self, quarantine: Arc<dyn QuarantineStore>) -> Self {
+        self.quarantine = quarantine;
+        self
+    }
+
     /// Create a new Redis subscriber with default circuit breaker and health
     pub fn with_defaults(config: RedisConfig, dispatcher: Arc<NotificationDispatcher>) -> Self {
         let cb_config = CircuitBreakerConfig {
@@ -106,13 +195,17 @@ impl RedisSubscriber {
 
     /// Start the Redis subscriber loop with resilience
     pub async fn start(&self) -> anyhow::Result<()> {
-        let channels = self.get_channels();
-        if channels.is_empty() {
+        let subscriptions = self.get_subscriptions();
+        if subscriptions.is_empty() {
             tracing::info!("No Redis channels configured, skipping Redis subscriber");
             return Ok(());
         }
 
-        tracing::info!(channels = ?channels, "Starting resilient Redis subscriber");
+        tracing::info!(
+            channels = ?subscriptions.channels,
+            patterns = ?subscriptions.patterns,
+            "Starting resilient Redis subscriber"
+        );
 
         // Create backoff configuration
         let backoff_config = BackoffConfig {
@@ -120,6 +213,7 @@ impl RedisSubscriber {
             max_delay_ms: self.config.backoff_max_delay_ms,
             multiplier: 2.0,
             jitter_factor: 0.1,
+            max_total_wait_ms: self.config.backoff_max_total_wait_ms,
         };
         let mut backoff = ExponentialBackoff::with_config(backoff_config);
 
@@ -147,12 +241,18 @@ impl RedisSubscriber {
 
             self.health.set_reconnecting();
 
-            match self.run_subscription_loop(&channels).await {
+            match self.run_subscription_loop(&subscriptions).await {
                 Ok(()) => {
                     tracing::info!("Redis subscriber stopped gracefully");
                     break;
                 }
                 Err(e) => {
+                    REDIS_PATTERN_SUBSCRIPTIONS.set(0);
+                    // A subscription that was established and then dropped starts a new
+                    // outage, so it gets a fresh wait budget
+                    if self.health.is_healthy() {
+                        backoff.reset();
+                    }
                     self.circuit_breaker.record_failure();
 
                     let delay = backoff.next_delay();
@@ -175,6 +275,22 @@ impl RedisSubscriber {
                             // Continue to retry
                         }
                     }
+
+                    if backoff.is_exhausted() {
+                        tracing::error!(
+                            alert = "redis_subscriber_retry_exhausted",
+                            attempts = backoff.attempt(),
+                            total_wait_ms = backoff.total_wait_ms(),
+                            last_error = %e,
+                            "Redis subscriber giving up, reconnect wait budget exhausted"
+                        );
+                        return Err(anyhow::anyhow!(
+                            "Redis subscriber gave up after {} attempts ({}ms waited): {}",
+                            backoff.attempt(),
+                            backoff.total_wait_ms(),
+                            e
+                        ));
+                    }
                 }
             }
         }
@@ -182,9 +298,12 @@ impl RedisSubscriber {
         Ok(())
     }
 
-    /// Get configured channels
-    fn get_channels(&self) -> Vec<String> {
-        if self.config.channels.is_empty() {
+    /// Get configured channels and patterns.
+    ///
+    /// Channels containing glob characters are subscribed as patterns, like
+    /// `subscribe_patterns`. Without any configuration the default channels are used.
+    fn get_subscriptions(&self) -> Subscriptions {
+        let channels = if self.config.channels.is_empty() && self.config.subscribe_patterns.is_empty() {
             // Default channels if none configured
             vec![
                 "notification:user:*".to_string(),
@@ -193,31 +312,52 @@ impl RedisSubscriber {
             ]
         } else {
             self.config.channels.clone()
+        };
+
+        let (patterns, channels): (Vec<String>, Vec<String>) = channels
+            .into_iter()
+            .partition(|channel| channel.contains('*') || channel.contains('?') || channel.contains('['));
+        Subscriptions {
+            channels,
+            patterns: patterns
+                .into_iter()
+                .chain(self.config.subscribe_patterns.iter().cloned())
+                .collect(),
         }
     }
 
-    /// Run the subscription loop
-    async fn run_subscription_loop(&self, channels: &[String]) -> anyhow::Result<()> {
+    /// Connect and run the subscription loop.
+    ///
+    /// Called again after every reconnect, so all channels and patterns are re-subscribed
+    /// on the new connection.
+    async fn run_subscription_loop(&self, subscriptions: &Subscriptions) -> anyhow::Result<()> {
         let client = redis::Client::open(self.config.url.as_str())?;
-        let mut pubsub = client.get_async_pubsub().await?;
-
-        // Subscribe to channels (with pattern support)
-        for channel in channels {
-            if channel.contains('*') || channel.contains('?') || channel.contains('[') {
-                pubsub.psubscribe(channel).await?;
-                tracing::debug!(pattern = %channel, "Subscribed to pattern");
-            } else {
-                pubsub.subscribe(channel).await?;
-                tracing::debug!(channel = %channel, "Subscribed to channel");
-            }
+        let pubsub = client.get_async_pubsub().await?;
+        self.run_on(pubsub, subscriptions).await
+    }
+
+    /// Subscribe on an established connection and handle messages until shutdown or disconnect
+    async fn run_on<P: PubSubConnection>(
+        &self,
+        mut pubsub: P,
+        subscriptions: &Subscriptions,
+    ) -> anyhow::Result<()> {
+        for channel in &subscriptions.channels {
+            pubsub.subscribe(channel).await?;
+            tracing::debug!(channel = %channel, "Subscribed to channel");
         }
+        for pattern in &subscriptions.patterns {
+            pubsub.psubscribe(pattern).await?;
+            tracing::debug!(pattern = %pattern, "Subscribed to pattern");
+        }
+        REDIS_PATTERN_SUBSCRIPTIONS.set(subscriptions.patterns.len() as i64);
 
         // Connection successful
         self.circuit_breaker.record_success();
         self.health.set_connected();
         tracing::info!("Redis subscription established");
 
-        let mut message_stream = pubsub.on_message();
+        let mut message_stream = pubsub.into_messages();
         let mut shutdown_rx = self.shutdown.subscribe();
 
         loop {
@@ -234,63 +374,77 @@ impl RedisSubscriber {
                             // Record successful message receipt
                             self.circuit_breaker.record_success();
 
-                            let channel: String = msg.get_channel_name().to_string();
-                            let payload: String = match msg.get_payload() {
-                                Ok(p) => p,
-                                Err(e) => {
-                                    tracing::warn!(error = %e, "Failed to get message payload");
-                                    continue;
-                                }
-                            };
-
-                            self.handle_message(&channel, &payload).await;
+                            if let Some(ref pattern) = msg.pattern {
+                                tracing::trace!(pattern = %pattern, channel = %msg.channel, "Pattern matched");
+                            }
+                            self.handle_message(&msg.channel, &msg.payload).await;
                         }
                         None => {
-                            tracing::warn!("Redis message stream ended");
-                            break;
+                            // Connection lost (e.g. Redis restart): reconnect and re-subscribe
+                            REDIS_PATTERN_SUBSCRIPTIONS.set(0);
+                            anyhow::bail!("Redis message stream ended");
                         }
                     }
                 }
             }
         }
 
+        REDIS_PATTERN_SUBSCRIPTIONS.set(0);
         Ok(())
     }
 
-    /// Handle a received message
+    /// Handle a received message, quarantining it if it cannot be deserialized
     async fn handle_message(&self, channel: &str, payload: &str) {
         tracing::debug!(channel = %channel, "Received Redis message");
 
-        // Parse the message
-        let message: RedisNotificationMessage = match serde_json::from_str(payload) {
-            Ok(m) => m,
-            Err(e) => {
-                tracing::warn!(
-                    error = %e,
-                    channel = %channel,
-                    payload = %payload,
-                    "Failed to parse Redis message"
-                );
-                return;
-            }
-        };
+        if let Err(e) = Self::process_payload(&self.dispatcher, channel, payload).await {
+            tracing::warn!(
+                error = %e,
+                channel = %channel,
+                payload = %payload,
+                "Failed to parse Redis message, moving it to quarantine"
+            );
+            self.quarantine_message(QuarantinedMessage::new(channel, payload, e))
+                .await;
+        }
+    }
+
+    /// Store a malformed message in the quarantine
+    async fn quarantine_message(&self, message: QuarantinedMessage) {
+        match self.quarantine.push(message).await {
+            Ok(()) => REDIS_MESSAGES_QUARANTINED_TOTAL.inc(),
+            Err(e) => tracing::error!(error = %e, "Failed to quarantine Redis message"),
+        }
+    }
+
+    /// Parse a raw Redis payload and dispatch it.
+    ///
+    /// Also used to reprocess quarantined messages. Returns the parse error if the payload
+    /// cannot be deserialized, and `Ok(None)` if its target type is unknown.
+    pub async fn process_payload(
+        dispatcher: &NotificationDispatcher,
+        channel: &str,
+        payload: &str,
+    ) -> Result<Option<DeliveryResult>, serde_json::Error> {
+        let message: RedisNotificationMessage = serde_json::from_str(payload)?;
 
         // Determine target first (before moving message fields), with tenant channel namespacing
-        let target = match self.parse_target(&message, message.tenant_id.as_deref()) {
+        let target = match Self::parse_target(&message, message.tenant_id.as_deref()) {
             Some(t) => t,
             None => {
                 tracing::warn!(
                     target_type = %message.target_type,
                     "Unknown target type in Redis message"
                 );
-                return;
+                return Ok(None);
             }
         };
 
         // Build notification event
         let mut builder = NotificationBuilder::new(&message.event.event_type, format!("redis:{}", channel))
             .payload(message.event.payload)
-            .priority(message.event.priority);
+            .priority(message.event.priority)
+            .payload_encoding(message.event.payload_encoding);
 
         if let Some(ttl) = message.event.ttl {
             builder = builder.ttl(ttl);
@@ -300,10 +454,13 @@ impl RedisSubscriber {
             builder = builder.correlation_id(correlation_id);
         }
 
+        for (key, value) in message.headers {
+            builder = builder.header(key, value);
+        }
+
         let event = builder.build();
 
-        let result = self
-            .dispatcher
+        let result = dispatcher
             .dispatch_for_tenant(target, event, message.tenant_id.as_deref())
             .await;
 
@@ -313,11 +470,12 @@ impl RedisSubscriber {
             failed = result.failed,
             "Dispatched notification from Redis"
         );
+
+        Ok(Some(result))
     }
 
     /// Parse target from Redis message, applying tenant channel namespacing if provided
     fn parse_target(
-        &self,
         message: &RedisNotificationMessage,
         tenant_id: Option<&str>,
     ) -> Option<NotificationTarget> {
@@ -360,6 +518,18 @@ impl RedisSubscriber {
                     .collect();
                 Some(NotificationTarget::Channels(channels))
             }
+            "channel_pattern" => {
+                let pattern = match &message.target {
+                    Some(RedisTarget::Single(pattern)) => pattern.clone(),
+                    _ => return None,
+                };
+                let pattern = Self::namespace_channel(pattern, tenant_id);
+                Some(NotificationTarget::ChannelPattern(pattern))
+            }
+            "role" => match &message.target {
+                Some(RedisTarget::Single(role)) => Some(NotificationTarget::Role(role.clone())),
+                _ => None,
+            },
             _ => None,
         }
     }
@@ -433,6 +603,22 @@ mod tests {
         assert_eq!(message.event.correlation_id, Some("req-abc".to_string()));
     }
 
+    #[test]
+    fn test_parse_message_with_headers() {
+        let json = r#"{
+            "type": "user",
+            "target": "user-123",
+            "headers": {"x-source-service": "billing"},
+            "event": {
+                "event_type": "invoice.paid",
+                "payload": {}
+            }
+        }"#;
+
+        let message: RedisNotificationMessage = serde_json::from_str(json).unwrap();
+        assert_eq!(message.headers.get("x-source-service"), Some(&"billing".to_string()));
+    }
+
     #[test]
     fn test_parse_multiple_users_message() {
         let json = r#"{
@@ -475,4 +661,202 @@ mod tests {
             _ => panic!("Expected multiple targets"),
         }
     }
+
+    fn subscriber_with_quarantine(quarantine: Arc<dyn QuarantineStore>) -> RedisSubscriber {
+        let dispatcher = Arc::new(NotificationDispatcher::new(Arc::new(
+            crate::connection_manager::ConnectionManager::new(),
+        )));
+        RedisSubscriber::with_defaults(RedisConfig::default(), dispatcher).with_quarantine(quarantine)
+    }
+
+    #[tokio::test]
+    async fn test_malformed_message_is_quarantined() {
+        use crate::redis::pool::MockRedisPoolExt;
+        use crate::triggers::{RedisQuarantineStore, QUARANTINE_KEY, QUARANTINE_MAX_ENTRIES};
+
+        let payload = r#"{"type": "user", "target": "user-1", "event": "#;
+        let mut pool = MockRedisPoolExt::new();
+        pool.expect_lpush_trim()
+            .withf(move |key, value, maxlen| {
+                let entry: QuarantinedMessage = serde_json::from_str(value).unwrap();
+                key == QUARANTINE_KEY
+                    && *maxlen == QUARANTINE_MAX_ENTRIES
+                    && entry.channel == "notification:user:user-1"
+                    && entry.payload == payload
+                    && !entry.error.is_empty()
+            })
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+
+        let subscriber = subscriber_with_quarantine(Arc::new(RedisQuarantineStore::new(Arc::new(pool))));
+        let before = REDIS_MESSAGES_QUARANTINED_TOTAL.get();
+        subscriber.handle_message("notification:user:user-1", payload).await;
+        assert!(REDIS_MESSAGES_QUARANTINED_TOTAL.get() > before);
+    }
+
+    #[tokio::test]
+    async fn test_only_unparseable_messages_are_quarantined() {
+        let quarantine = Arc::new(MemoryQuarantineStore::new());
+        let subscriber = subscriber_with_quarantine(quarantine.clone());
+
+        // Well-formed JSON that does not match the message schema is quarantined too
+        subscriber
+            .handle_message("notification:broadcast", r#"{"type": "broadcast"}"#)
+            .await;
+        subscriber
+            .handle_message(
+                "notification:broadcast",
+                r#"{"type": "broadcast", "event": {"event_type": "system.ping", "payload": {}}}"#,
+            )
+            .await;
+
+        let quarantined = quarantine.list().await.unwrap();
+        assert_eq!(quarantined.len(), 1);
+        assert_eq!(quarantined[0].payload, r#"{"type": "broadcast"}"#);
+    }
+
+    /// In-process stand-in for a Pub/Sub connection that applies `PSUBSCRIBE` glob matching
+    struct MockPubSub {
+        subscribed: Arc<std::sync::Mutex<Vec<String>>>,
+        channels: Vec<String>,
+        patterns: Vec<String>,
+        published: Vec<(String, String)>,
+    }
+
+    impl MockPubSub {
+        fn new(subscribed: Arc<std::sync::Mutex<Vec<String>>>, published: &[(&str, &str)]) -> Self {
+            Self {
+                subscribed,
+                channels: Vec::new(),
+                patterns: Vec::new(),
+                published: published
+                    .iter()
+                    .map(|(channel, payload)| (channel.to_string(), payload.to_string()))
+                    .collect(),
+            }
+        }
+    }
+
+    /// Redis glob matching, limited to `*`
+    fn glob_match(pattern: &str, channel: &str) -> bool {
+        match pattern.split_once('*') {
+            None => pattern == channel,
+            Some((prefix, rest)) => channel.strip_prefix(prefix).is_some_and(|tail| {
+                (0..=tail.len()).any(|i| tail.is_char_boundary(i) && glob_match(rest, &tail[i..]))
+            }),
+        }
+    }
+
+    #[async_trait]
+    impl PubSubConnection for MockPubSub {
+        async fn subscribe(&mut self, channel: &str) -> redis::RedisResult<()> {
+            self.subscribed.lock().unwrap().push(format!("SUBSCRIBE {}", channel));
+            self.channels.push(channel.to_string());
+            Ok(())
+        }
+
+        async fn psubscribe(&mut self, pattern: &str) -> redis::RedisResult<()> {
+            self.subscribed.lock().unwrap().push(format!("PSUBSCRIBE {}", pattern));
+            self.patterns.push(pattern.to_string());
+            Ok(())
+        }
+
+        fn into_messages(self) -> BoxStream<'static, PubSubMessage> {
+            let messages: Vec<PubSubMessage> = self
+                .published
+                .into_iter()
+                .filter_map(|(channel, payload)| {
+                    let pattern = if self.channels.contains(&channel) {
+                        None
+                    } else {
+                        Some(self.patterns.iter().find(|p| glob_match(p, &channel))?.clone())
+                    };
+                    Some(PubSubMessage { channel, pattern, payload })
+                })
+                .collect();
+            futures::stream::iter(messages).boxed()
+        }
+    }
+
+    #[test]
+    fn test_subscriptions_from_config() {
+        let dispatcher = Arc::new(NotificationDispatcher::new(Arc::new(
+            crate::connection_manager::ConnectionManager::new(),
+        )));
+
+        let subscriber = RedisSubscriber::with_defaults(RedisConfig::default(), dispatcher.clone());
+        let defaults = subscriber.get_subscriptions();
+        assert_eq!(defaults.channels, vec!["notification:broadcast"]);
+        assert_eq!(defaults.patterns, vec!["notification:user:*", "notification:channel:*"]);
+
+        let config = RedisConfig {
+            channels: vec!["orders".to_string(), "legacy:*".to_string()],
+            subscribe_patterns: vec!["ara:notifications:*".to_string()],
+            ..RedisConfig::default()
+        };
+        let subscriber = RedisSubscriber::with_defaults(config, dispatcher);
+        assert_eq!(
+            subscriber.get_subscriptions(),
+            Subscriptions {
+                channels: vec!["orders".to_string()],
+                patterns: vec!["legacy:*".to_string(), "ara:notifications:*".to_string()],
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_start_gives_up_when_wait_budget_exhausted() {
+        let connection_manager = Arc::new(crate::connection_manager::ConnectionManager::new());
+        let dispatcher = Arc::new(NotificationDispatcher::new(connection_manager));
+        let config = RedisConfig {
+            // Nothing listens on port 1, so every connection attempt fails
+            url: "redis://127.0.0.1:1".to_string(),
+            channels: vec!["orders".to_string()],
+            backoff_initial_delay_ms: 10,
+            backoff_max_delay_ms: 20,
+            backoff_max_total_wait_ms: Some(50),
+            ..RedisConfig::default()
+        };
+        let subscriber = RedisSubscriber::with_defaults(config, dispatcher);
+
+        let result = tokio::time::timeout(std::time::Duration::from_secs(5), subscriber.start())
+            .await
+            .expect("subscriber should give up once the wait budget is exhausted");
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_pattern_subscription_receives_matching_channels() {
+        let connection_manager = Arc::new(crate::connection_manager::ConnectionManager::new());
+        let (tx, mut rx) = tokio::sync::mpsc::channel(8);
+        connection_manager
+            .register("123".to_string(), "default".to_string(), vec![], tx)
+            .unwrap();
+        let dispatcher = Arc::new(NotificationDispatcher::new(connection_manager));
+        let config = RedisConfig {
+            subscribe_patterns: vec!["ara:notifications:*".to_string()],
+            ..RedisConfig::default()
+        };
+        let subscriber = RedisSubscriber::with_defaults(config, dispatcher);
+        let subscriptions = subscriber.get_subscriptions();
+
+        let payload = r#"{"type": "user", "target": "123", "event": {"event_type": "order.created", "payload": {}}}"#;
+        let subscribed = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let pubsub = MockPubSub::new(
+            subscribed.clone(),
+            &[("ara:notifications:user.123", payload), ("other:user.123", payload)],
+        );
+
+        // The stream ending (connection lost) is an error so `start` reconnects
+        assert!(subscriber.run_on(pubsub, &subscriptions).await.is_err());
+        assert_eq!(*subscribed.lock().unwrap(), vec!["PSUBSCRIBE ara:notifications:*"]);
+        assert!(rx.try_recv().is_ok());
+        assert!(rx.try_recv().is_err());
+
+        // A new connection after a Redis restart subscribes to the patterns again
+        let pubsub = MockPubSub::new(subscribed.clone(), &[("ara:notifications:user.123", payload)]);
+        assert!(subscriber.run_on(pubsub, &subscriptions).await.is_err());
+        assert_eq!(subscribed.lock().unwrap().len(), 2);
+        assert!(rx.try_recv().is_ok());
+    }
 }
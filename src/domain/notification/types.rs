@@ -1,7 +1,13 @@
+use std::collections::HashMap;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+/// Event header carrying the `sub` of the service that sent a notification on behalf of
+/// a user
+pub const CALLER_ID_HEADER: &str = "caller_id";
+
 /// Notification event that gets sent to clients
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NotificationEvent {
@@ -15,6 +21,16 @@ pub struct NotificationEvent {
     pub payload: serde_json::Value,
     /// Event metadata
     pub metadata: NotificationMetadata,
+    /// Client-side tags; a client replaces a displayed notification sharing a tag
+    /// with the newer one (e.g. an unread-count badge)
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Wire encoding preferred for this event's WebSocket frames
+    #[serde(default, skip_serializing_if = "PayloadEncoding::is_json")]
+    pub payload_encoding: PayloadEncoding,
+    /// Transport-level headers (server-side only, never sent to clients)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, String>,
 }
 
 /// Metadata associated with a notification
@@ -33,6 +49,29 @@ pub struct NotificationMetadata {
     /// Correlation ID for tracing (optional)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub correlation_id: Option<String>,
+    /// User a service sent this notification on behalf of (optional)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub impersonated_by: Option<String>,
+}
+
+/// Wire encoding of a notification sent over WebSocket
+///
+/// `Msgpack` notifications are sent as binary MessagePack frames to connections that
+/// opted in with `?encoding=msgpack`, and as JSON text frames to all others.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum PayloadEncoding {
+    /// JSON text frames (default)
+    #[default]
+    Json,
+    /// MessagePack binary frames
+    Msgpack,
+}
+
+impl PayloadEncoding {
+    pub fn is_json(&self) -> bool {
+        *self == Self::Json
+    }
 }
 
 /// Priority levels for notifications
@@ -78,11 +117,16 @@ pub enum NotificationTarget {
     Channel(String),
     /// Send to users subscribed to multiple channels
     Channels(Vec<String>),
+    /// Send to users subscribed to any channel matching a glob such as `orders.*`
+    ChannelPattern(String),
+    /// Send to users whose JWT `roles` claim includes the role
+    Role(String),
 }
 
 /// Builder for creating notification events
 #[derive(Debug, Clone)]
 pub struct NotificationBuilder {
+    id: Option<Uuid>,
     event_type: String,
     payload: serde_json::Value,
     source: String,
@@ -90,12 +134,17 @@ pub struct NotificationBuilder {
     ttl: Option<u32>,
     audience: Option<Audience>,
     correlation_id: Option<String>,
+    impersonated_by: Option<String>,
+    tags: Vec<String>,
+    payload_encoding: PayloadEncoding,
+    headers: HashMap<String, String>,
 }
 
 impl NotificationBuilder {
     /// Create a new notification builder
     pub fn new(event_type: impl Into<String>, source: impl Into<String>) -> Self {
         Self {
+            id: None,
             event_type: event_type.into(),
             payload: serde_json::Value::Null,
             source: source.into(),
@@ -103,9 +152,19 @@ impl NotificationBuilder {
             ttl: None,
             audience: None,
             correlation_id: None,
+            impersonated_by: None,
+            tags: Vec::new(),
+            payload_encoding: PayloadEncoding::default(),
+            headers: HashMap::new(),
         }
     }
 
+    /// Use a caller-provided notification ID instead of generating one
+    pub fn id(mut self, id: Uuid) -> Self {
+        self.id = Some(id);
+        self
+    }
+
     /// Set the payload
     pub fn payload(mut self, payload: serde_json::Value) -> Self {
         self.payload = payload;
@@ -142,10 +201,34 @@ impl NotificationBuilder {
         self
     }
 
+    /// Mark the notification as sent on behalf of `user_id`
+    pub fn impersonated_by(mut self, user_id: impl Into<String>) -> Self {
+        self.impersonated_by = Some(user_id.into());
+        self
+    }
+
+    /// Add a client-side tag
+    pub fn tag(mut self, tag: impl Into<String>) -> Self {
+        self.tags.push(tag.into());
+        self
+    }
+
+    /// Set the preferred WebSocket wire encoding
+    pub fn payload_encoding(mut self, encoding: PayloadEncoding) -> Self {
+        self.payload_encoding = encoding;
+        self
+    }
+
+    /// Add a transport-level header (not included in the client-visible payload)
+    pub fn header(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.insert(key.into(), value.into());
+        self
+    }
+
     /// Build the notification event
     pub fn build(self) -> NotificationEvent {
         NotificationEvent {
-            id: Uuid::new_v4(),
+            id: self.id.unwrap_or_else(Uuid::new_v4),
             occurred_at: Utc::now(),
             event_type: self.event_type,
             payload: self.payload,
@@ -155,7 +238,11 @@ impl NotificationBuilder {
                 ttl: self.ttl,
                 audience: self.audience,
                 correlation_id: self.correlation_id,
+                impersonated_by: self.impersonated_by,
             },
+            tags: self.tags,
+            payload_encoding: self.payload_encoding,
+            headers: self.headers,
         }
     }
 }
@@ -177,10 +264,26 @@ impl NotificationEvent {
         NotificationBuilder::new(event_type, source)
     }
 
+    /// Caller-provided ID tying this notification to the request that caused it
+    pub fn correlation_id(&self) -> Option<&str> {
+        self.metadata.correlation_id.as_deref()
+    }
+
+    /// Service that sent this notification on behalf of a user, if any
+    pub fn caller_id(&self) -> Option<&str> {
+        self.headers.get(CALLER_ID_HEADER).map(String::as_str)
+    }
+
+    /// When the notification expires, if it has a TTL
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        self.metadata
+            .ttl
+            .map(|ttl| self.occurred_at + chrono::Duration::seconds(ttl as i64))
+    }
+
     /// Check if the notification has expired based on TTL
     pub fn is_expired(&self) -> bool {
-        if let Some(ttl) = self.metadata.ttl {
-            let expiry = self.occurred_at + chrono::Duration::seconds(ttl as i64);
+        if let Some(expiry) = self.expires_at() {
             Utc::now() > expiry
         } else {
             false
@@ -210,6 +313,7 @@ impl NotificationMetadata {
             ttl: None,
             audience: None,
             correlation_id: None,
+            impersonated_by: None,
         }
     }
 }
@@ -258,6 +362,22 @@ mod tests {
         assert_eq!(event.metadata.correlation_id, Some("req-456".to_string()));
     }
 
+    #[test]
+    fn test_notification_builder_headers() {
+        let event = NotificationBuilder::new("order.created", "test-service")
+            .header("content-type", "application/json")
+            .header("x-source-service", "orders")
+            .build();
+
+        assert_eq!(event.headers.len(), 2);
+        assert_eq!(event.headers["x-source-service"], "orders");
+
+        // Headers survive server-side serialization (e.g. queue persistence)
+        let json = serde_json::to_string(&event).unwrap();
+        let restored: NotificationEvent = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.headers, event.headers);
+    }
+
     #[test]
     fn test_priority_ordering() {
         assert!(Priority::Critical > Priority::High);
@@ -8,9 +8,16 @@ use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
+use tokio::sync::mpsc;
 use uuid::Uuid;
 
+use crate::metrics::{
+    MessageMetrics, QUEUE_CHECKSUM_FAILURES_TOTAL, QUEUE_EXPIRED_TOTAL, QUEUE_REPLAYED_TOTAL,
+};
 use crate::notification::NotificationEvent;
+use crate::websocket::{OutboundMessage, ServerMessage};
+
+use super::ReplayResult;
 
 /// Errors that can occur during queue backend operations.
 #[derive(Debug, Error)]
@@ -58,6 +65,11 @@ pub struct StoredMessage {
     /// Number of delivery attempts
     pub attempts: u32,
 
+    /// CRC32 of the event, used to detect corruption in storage. Zero for messages
+    /// queued before checksums were recorded, which are not verified.
+    #[serde(default)]
+    pub checksum: u32,
+
     /// Redis stream ID (only set for Redis backend)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub stream_id: Option<String>,
@@ -68,6 +80,7 @@ impl StoredMessage {
     pub fn new(event: NotificationEvent) -> Self {
         Self {
             id: Uuid::new_v4(),
+            checksum: Self::event_checksum(&event),
             event,
             queued_at: Utc::now(),
             attempts: 0,
@@ -75,11 +88,108 @@ impl StoredMessage {
         }
     }
 
-    /// Check if the message has expired based on the given TTL.
+    /// CRC32 of an event's JSON form. The event goes through `serde_json::Value`
+    /// first so that maps (headers, payload objects) are hashed in sorted key
+    /// order, whatever order they were stored or deserialized in.
+    pub fn event_checksum(event: &NotificationEvent) -> u32 {
+        serde_json::to_value(event)
+            .and_then(|value| serde_json::to_vec(&value))
+            .map(|bytes| crc32fast::hash(&bytes))
+            .unwrap_or(0)
+    }
+
+    /// Whether the event still matches the checksum recorded when it was queued
+    pub fn verify_checksum(&self) -> bool {
+        self.checksum == 0 || self.checksum == Self::event_checksum(&self.event)
+    }
+
+    /// Check if the message has expired based on the given queue TTL or the
+    /// notification's own TTL, whichever is reached first.
     pub fn is_expired(&self, ttl_seconds: u64) -> bool {
         let now = Utc::now();
         let age = now.signed_duration_since(self.queued_at);
-        age.num_seconds() >= ttl_seconds as i64
+        age.num_seconds() >= ttl_seconds as i64 || self.event.is_expired()
+    }
+}
+
+/// Check a message read back from storage, counting and logging a checksum
+/// mismatch. Corrupted messages are skipped rather than delivered.
+pub(super) fn checksum_matches(message: &StoredMessage, user_id: &str) -> bool {
+    if message.verify_checksum() {
+        return true;
+    }
+    QUEUE_CHECKSUM_FAILURES_TOTAL.inc();
+    tracing::warn!(
+        user_id = %user_id,
+        message_id = %message.id,
+        "Queued message failed checksum verification, skipping"
+    );
+    false
+}
+
+/// Send a user's queued messages to a connection in order, removing each one from
+/// the queue only after it has been sent.
+///
+/// Messages stay queued while they are replayed, so a failed send, a crash or a
+/// cancelled replay leaves every undelivered message in place for the next replay.
+/// Expired messages are removed as they are reached.
+pub(super) async fn replay_in_place<B: MessageQueueBackend + ?Sized>(
+    backend: &B,
+    user_id: &str,
+    messages: Vec<StoredMessage>,
+    sender: &mpsc::Sender<OutboundMessage>,
+) -> ReplayResult {
+    let mut result = ReplayResult::empty();
+    let ttl = backend.message_ttl_seconds();
+
+    let total = messages.len();
+    for (index, message) in messages.into_iter().enumerate() {
+        if message.is_expired(ttl) {
+            result.expired += 1;
+            QUEUE_EXPIRED_TOTAL.inc();
+            remove_replayed(backend, user_id, &message).await;
+            continue;
+        }
+
+        let queued_at = message.queued_at;
+        let msg = OutboundMessage::Raw(ServerMessage::Notification {
+            event: message.event.clone(),
+        });
+        if sender.send(msg).await.is_err() {
+            result.failed += 1;
+            // The message and everything after it are still queued
+            result.remaining = total - index;
+            tracing::warn!(
+                user_id = %user_id,
+                message_id = %message.id,
+                "Failed to replay message, connection may be closed"
+            );
+            // If sending fails, stop replaying (connection is dead)
+            break;
+        }
+        MessageMetrics::record_e2e_latency("replay", queued_at);
+        QUEUE_REPLAYED_TOTAL.inc();
+        result.replayed += 1;
+        remove_replayed(backend, user_id, &message).await;
+    }
+
+    result
+}
+
+/// Remove a replayed or expired message, logging a failure. A sent message that
+/// cannot be removed is delivered again by the next replay.
+async fn remove_replayed<B: MessageQueueBackend + ?Sized>(
+    backend: &B,
+    user_id: &str,
+    message: &StoredMessage,
+) {
+    if let Err(e) = backend.remove_message(user_id, message).await {
+        tracing::warn!(
+            user_id = %user_id,
+            message_id = %message.id,
+            error = %e,
+            "Failed to remove replayed message from the queue"
+        );
     }
 }
 
@@ -170,6 +280,25 @@ pub trait MessageQueueBackend: Send + Sync {
     /// A `DrainResult` containing the messages and count of expired messages.
     async fn drain(&self, user_id: &str) -> Result<DrainResult, QueueBackendError>;
 
+    /// Replay a user's queued messages to a newly connected client.
+    ///
+    /// Messages are sent in order and each one is removed from the queue only once
+    /// it has been sent; expired messages are discarded. If the connection drops or
+    /// the replay is cancelled, the undelivered messages are still queued for the
+    /// next replay. Backends override this when concurrent replays for the same user
+    /// need extra coordination.
+    async fn replay(
+        &self,
+        user_id: &str,
+        sender: &mpsc::Sender<OutboundMessage>,
+    ) -> Result<ReplayResult, QueueBackendError> {
+        if !self.is_enabled() {
+            return Ok(ReplayResult::empty());
+        }
+        let messages = self.peek(user_id, usize::MAX).await?;
+        Ok(replay_in_place(self, user_id, messages, sender).await)
+    }
+
     /// Peek at messages without removing them.
     ///
     /// Useful for debugging and monitoring.
@@ -183,6 +312,12 @@ pub trait MessageQueueBackend: Send + Sync {
     /// Get the queue size for a specific user.
     async fn queue_size(&self, user_id: &str) -> Result<usize, QueueBackendError>;
 
+    /// Check whether a notification is waiting in a user's queue.
+    ///
+    /// Expired messages do not count. Backend errors are logged and reported as
+    /// not queued.
+    async fn is_queued(&self, user_id: &str, notification_id: Uuid) -> bool;
+
     /// Clean up expired messages from all queues.
     ///
     /// # Returns
@@ -190,6 +325,30 @@ pub trait MessageQueueBackend: Send + Sync {
     /// The number of messages removed.
     async fn cleanup_expired(&self) -> Result<usize, QueueBackendError>;
 
+    /// Remove specific messages (by `StoredMessage::id`) from a user's queue, leaving
+    /// any others in place.
+    ///
+    /// # Returns
+    ///
+    /// The number of messages removed.
+    async fn remove_messages(
+        &self,
+        user_id: &str,
+        message_ids: &[Uuid],
+    ) -> Result<usize, QueueBackendError>;
+
+    /// Remove one message read from a user's queue, leaving any others in place.
+    ///
+    /// Used by replays to remove each message once it has been sent. Backends
+    /// override this when they can remove a message more cheaply than by ID.
+    async fn remove_message(
+        &self,
+        user_id: &str,
+        message: &StoredMessage,
+    ) -> Result<(), QueueBackendError> {
+        self.remove_messages(user_id, &[message.id]).await.map(|_| ())
+    }
+
     /// Clear the queue for a specific user.
     ///
     /// # Returns
@@ -197,6 +356,11 @@ pub trait MessageQueueBackend: Send + Sync {
     /// The number of messages removed.
     async fn clear_user_queue(&self, user_id: &str) -> Result<usize, QueueBackendError>;
 
+    /// List all users that currently have queued messages.
+    ///
+    /// Used for backend migration; may be expensive on persistent backends.
+    async fn list_users(&self) -> Result<Vec<String>, QueueBackendError>;
+
     /// Get queue statistics.
     async fn stats(&self) -> QueueBackendStats;
 }
@@ -206,6 +370,65 @@ mod tests {
     use super::*;
     use serde_json::json;
 
+    #[test]
+    fn test_stored_message_preserves_payload_encoding() {
+        use crate::notification::PayloadEncoding;
+
+        let json_event = NotificationEvent::builder("test.event", "test-source").build();
+        let stored = serde_json::to_string(&StoredMessage::new(json_event)).unwrap();
+        // JSON is the default and is not written, keeping existing entries unchanged
+        assert!(!stored.contains("payload_encoding"));
+        let restored: StoredMessage = serde_json::from_str(&stored).unwrap();
+        assert_eq!(restored.event.payload_encoding, PayloadEncoding::Json);
+
+        let msgpack_event = NotificationEvent::builder("test.event", "test-source")
+            .payload(json!({"key": "value"}))
+            .payload_encoding(PayloadEncoding::Msgpack)
+            .build();
+        let stored = serde_json::to_string(&StoredMessage::new(msgpack_event)).unwrap();
+        let restored: StoredMessage = serde_json::from_str(&stored).unwrap();
+        assert_eq!(restored.event.payload_encoding, PayloadEncoding::Msgpack);
+        assert_eq!(restored.event.payload["key"], "value");
+    }
+
+    #[test]
+    fn test_corrupted_stored_message_fails_checksum() {
+        let event = NotificationEvent::builder("test.event", "test-source")
+            .payload(json!({"amount": 100, "currency": "USD"}))
+            .header("trace-id", "abc")
+            .header("tenant", "acme")
+            .build();
+        let stored = serde_json::to_vec(&StoredMessage::new(event)).unwrap();
+
+        // An intact message round-trips, including its unordered headers
+        let restored: StoredMessage = serde_json::from_slice(&stored).unwrap();
+        assert_ne!(restored.checksum, 0);
+        assert!(restored.verify_checksum());
+        assert!(checksum_matches(&restored, "user-1"));
+
+        // Flip a byte inside the payload: still valid JSON, but not the queued event
+        let marker = br#""amount":100"#;
+        let position = stored
+            .windows(marker.len())
+            .position(|window| window == marker)
+            .unwrap();
+        let mut corrupted = stored.clone();
+        corrupted[position + marker.len() - 3] = b'9';
+        let restored: StoredMessage = serde_json::from_slice(&corrupted).unwrap();
+        assert_eq!(restored.event.payload["amount"], 900);
+        assert!(!restored.verify_checksum());
+
+        let failures = QUEUE_CHECKSUM_FAILURES_TOTAL.get();
+        assert!(!checksum_matches(&restored, "user-1"));
+        assert!(QUEUE_CHECKSUM_FAILURES_TOTAL.get() > failures);
+
+        // Messages queued before checksums were recorded are not verified
+        let mut legacy: serde_json::Value = serde_json::from_slice(&corrupted).unwrap();
+        legacy.as_object_mut().unwrap().remove("checksum");
+        let legacy: StoredMessage = serde_json::from_value(legacy).unwrap();
+        assert!(legacy.verify_checksum());
+    }
+
     #[test]
     fn test_stored_message_new() {
         let event = NotificationEvent::builder("test.event", "test-source")
@@ -243,6 +466,19 @@ mod tests {
         assert!(msg.is_expired(0));
     }
 
+    #[test]
+    fn test_stored_message_expires_with_notification_ttl() {
+        let mut event = NotificationEvent::builder("test.event", "test-source")
+            .ttl(0)
+            .build();
+        event.occurred_at = Utc::now() - chrono::Duration::seconds(1);
+
+        let msg = StoredMessage::new(event);
+
+        // The notification's own TTL is shorter than the queue TTL
+        assert!(msg.is_expired(3600));
+    }
+
     #[test]
     fn test_stored_message_serialization() {
         let event = NotificationEvent::builder("test.event", "test-source")
@@ -1,13 +1,16 @@
-//! Queue backend factory
+//! Queue backend factory and backend-to-backend migration
 
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
 
 use crate::config::QueueConfig as SettingsQueueConfig;
 use crate::postgres::PostgresPool;
 use crate::redis::pool::RedisPool;
 
-use super::backend::MessageQueueBackend;
-use super::memory_backend::MemoryQueueBackend;
+use super::backend::{MessageQueueBackend, QueueBackendError};
+use super::memory_backend::{MemoryQueueBackend, MemoryQueueBackendConfig};
 use super::models::QueueConfig;
 use super::postgres_backend::PostgresQueueBackend;
 use super::redis_backend::RedisQueueBackend;
@@ -43,6 +46,9 @@ pub fn create_queue_backend(
         message_ttl_seconds: settings.message_ttl_seconds,
         cleanup_interval_seconds: settings.cleanup_interval_seconds,
     };
+    let memory_config = MemoryQueueBackendConfig {
+        shard_count: settings.shard_count,
+    };
 
     match settings.backend.as_str() {
         "postgres" => {
@@ -62,7 +68,10 @@ pub fn create_queue_backend(
                 tracing::warn!(
                     "PostgreSQL backend requested but no pool provided, falling back to memory"
                 );
-                Arc::new(MemoryQueueBackend::new(config))
+                Arc::new(MemoryQueueBackend::with_backend_config(
+                    config,
+                    memory_config,
+                ))
             }
         }
         "redis" => {
@@ -84,12 +93,296 @@ pub fn create_queue_backend(
                 tracing::warn!(
                     "Redis backend requested but no pool provided, falling back to memory"
                 );
-                Arc::new(MemoryQueueBackend::new(config))
+                Arc::new(MemoryQueueBackend::with_backend_config(
+                    config,
+                    memory_config,
+                ))
             }
         }
         _ => {
-            tracing::info!(backend = "memory", "Creating memory queue backend");
-            Arc::new(MemoryQueueBackend::new(config))
+            tracing::info!(
+                backend = "memory",
+                shard_count = settings.shard_count,
+                "Creating memory queue backend"
+            );
+            Arc::new(MemoryQueueBackend::with_backend_config(
+                config,
+                memory_config,
+            ))
+        }
+    }
+}
+
+// ============================================================================
+// Backend Migration
+// ============================================================================
+
+/// Outcome of migrating queued messages between backends
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MigrationResult {
+    /// Users whose queues were fully moved to the target backend
+    pub users_migrated: usize,
+    /// Total messages written to the target backend
+    pub messages_migrated: usize,
+    /// Per-user failures (the source queue is left untouched for these users)
+    pub errors: Vec<String>,
+}
+
+/// Lifecycle state of a queue migration
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MigrationState {
+    #[default]
+    Idle,
+    Running,
+    Completed,
+    Failed,
+}
+
+/// Point-in-time view of migration progress, for status polling
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MigrationStatus {
+    pub state: MigrationState,
+    /// Users discovered in the source backend
+    pub users_total: usize,
+    #[serde(flatten)]
+    pub result: MigrationResult,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+/// Shared progress tracker updated while a migration runs
+#[derive(Debug, Default)]
+pub struct MigrationProgress {
+    status: Mutex<MigrationStatus>,
+}
+
+impl MigrationProgress {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Mark a migration as started. Returns false if one is already running.
+    pub fn try_start(&self) -> bool {
+        let mut status = self.lock();
+        if status.state == MigrationState::Running {
+            return false;
+        }
+        *status = MigrationStatus {
+            state: MigrationState::Running,
+            started_at: Some(Utc::now()),
+            ..Default::default()
+        };
+        true
+    }
+
+    /// Get a snapshot of the current status
+    pub fn snapshot(&self) -> MigrationStatus {
+        self.lock().clone()
+    }
+
+    fn update(&self, f: impl FnOnce(&mut MigrationStatus)) {
+        f(&mut self.lock());
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, MigrationStatus> {
+        self.status.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Move all queued messages from one backend to another.
+///
+/// Users are processed in batches of `batch_size`, yielding between batches so
+/// live traffic is not starved. For each user the whole source queue is peeked,
+/// re-enqueued on the target, then the copied messages are removed from the source.
+/// Messages that arrive on the source while a user is being copied are picked up by
+/// a catch-up pass; anything arriving later is left on the source. Re-enqueued
+/// messages get a fresh `queued_at` on the target.
+pub async fn migrate_backend(
+    from: &dyn MessageQueueBackend,
+    to: &dyn MessageQueueBackend,
+    batch_size: usize,
+) -> MigrationResult {
+    let progress = MigrationProgress::new();
+    progress.try_start();
+    migrate_backend_with_progress(from, to, batch_size, &progress).await
+}
+
+/// Same as [`migrate_backend`], reporting progress into `progress` as it goes.
+///
+/// The caller is expected to have called [`MigrationProgress::try_start`].
+pub async fn migrate_backend_with_progress(
+    from: &dyn MessageQueueBackend,
+    to: &dyn MessageQueueBackend,
+    batch_size: usize,
+    progress: &MigrationProgress,
+) -> MigrationResult {
+    let mut result = MigrationResult::default();
+
+    let users = match from.list_users().await {
+        Ok(users) => users,
+        Err(e) => {
+            result.errors.push(format!("Failed to list users: {}", e));
+            progress.update(|status| {
+                status.state = MigrationState::Failed;
+                status.result = result.clone();
+                status.finished_at = Some(Utc::now());
+            });
+            return result;
         }
+    };
+    progress.update(|status| status.users_total = users.len());
+
+    tracing::info!(users = users.len(), batch_size, "Starting queue backend migration");
+
+    for batch in users.chunks(batch_size.max(1)) {
+        for user_id in batch {
+            match migrate_user(from, to, user_id).await {
+                Ok(count) => {
+                    result.users_migrated += 1;
+                    result.messages_migrated += count;
+                }
+                Err(e) => {
+                    tracing::warn!(user_id = %user_id, error = %e, "Failed to migrate user queue");
+                    result.errors.push(format!("{}: {}", user_id, e));
+                }
+            }
+        }
+
+        progress.update(|status| status.result = result.clone());
+        tokio::task::yield_now().await;
+    }
+
+    tracing::info!(
+        users_migrated = result.users_migrated,
+        messages_migrated = result.messages_migrated,
+        errors = result.errors.len(),
+        "Queue backend migration finished"
+    );
+
+    progress.update(|status| {
+        status.state = MigrationState::Completed;
+        status.result = result.clone();
+        status.finished_at = Some(Utc::now());
+    });
+
+    result
+}
+
+/// Copy one user's queue to the target backend, then remove the copied messages
+/// from the source. Messages enqueued on the source after the catch-up pass stay there.
+async fn migrate_user(
+    from: &dyn MessageQueueBackend,
+    to: &dyn MessageQueueBackend,
+    user_id: &str,
+) -> Result<usize, QueueBackendError> {
+    let size = from.queue_size(user_id).await?;
+    let messages = from.peek(user_id, size).await?;
+    for message in &messages {
+        to.enqueue(user_id, message.event.clone()).await?;
+    }
+    let mut migrated = messages.len();
+
+    // Catch-up pass for messages enqueued on the source while copying
+    let current_size = from.queue_size(user_id).await?;
+    let current = from.peek(user_id, current_size).await?;
+    let new_messages: Vec<_> = current
+        .into_iter()
+        .filter(|m| !messages.iter().any(|old| old.id == m.id))
+        .collect();
+    for message in &new_messages {
+        to.enqueue(user_id, message.event.clone()).await?;
+    }
+    migrated += new_messages.len();
+
+    let message_ids: Vec<_> = messages
+        .iter()
+        .chain(&new_messages)
+        .map(|message| message.id)
+        .collect();
+    from.remove_messages(user_id, &message_ids).await?;
+    Ok(migrated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notification::NotificationBuilder;
+
+    fn memory_backend() -> MemoryQueueBackend {
+        MemoryQueueBackend::new(QueueConfig {
+            enabled: true,
+            max_queue_size_per_user: 100,
+            message_ttl_seconds: 3600,
+            cleanup_interval_seconds: 300,
+        })
+    }
+
+    fn event(event_type: &str) -> crate::notification::NotificationEvent {
+        NotificationBuilder::new(event_type, "test")
+            .payload(serde_json::json!({}))
+            .build()
+    }
+
+    #[tokio::test]
+    async fn test_migrate_backend_moves_all_messages() {
+        let from = memory_backend();
+        let to = memory_backend();
+
+        for i in 0..5 {
+            from.enqueue("user-1", event(&format!("a.{}", i))).await.unwrap();
+        }
+        from.enqueue("user-2", event("b")).await.unwrap();
+        from.enqueue("user-3", event("c")).await.unwrap();
+
+        let result = migrate_backend(&from, &to, 2).await;
+        assert_eq!(result.users_migrated, 3);
+        assert_eq!(result.messages_migrated, 7);
+        assert!(result.errors.is_empty());
+
+        assert!(from.list_users().await.unwrap().is_empty());
+        assert_eq!(to.queue_size("user-1").await.unwrap(), 5);
+
+        // Order is preserved
+        let moved = to.peek("user-1", 5).await.unwrap();
+        assert_eq!(moved[0].event.event_type, "a.0");
+        assert_eq!(moved[4].event.event_type, "a.4");
+    }
+
+    #[tokio::test]
+    async fn test_migrate_backend_keeps_source_on_error() {
+        let from = memory_backend();
+        let to = MemoryQueueBackend::new(QueueConfig {
+            enabled: false,
+            ..Default::default()
+        });
+        from.enqueue("user-1", event("a")).await.unwrap();
+
+        let result = migrate_backend(&from, &to, 10).await;
+        assert_eq!(result.users_migrated, 0);
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(from.queue_size("user-1").await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_migration_progress() {
+        let from = memory_backend();
+        let to = memory_backend();
+        from.enqueue("user-1", event("a")).await.unwrap();
+
+        let progress = MigrationProgress::new();
+        assert_eq!(progress.snapshot().state, MigrationState::Idle);
+        assert!(progress.try_start());
+        assert!(!progress.try_start());
+
+        migrate_backend_with_progress(&from, &to, 10, &progress).await;
+        let status = progress.snapshot();
+        assert_eq!(status.state, MigrationState::Completed);
+        assert_eq!(status.users_total, 1);
+        assert_eq!(status.result.messages_migrated, 1);
+        assert!(status.finished_at.is_some());
+        assert!(progress.try_start());
     }
 }
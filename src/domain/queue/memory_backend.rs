@@ -7,8 +7,11 @@ use std::collections::VecDeque;
 
 use async_trait::async_trait;
 use dashmap::DashMap;
+use uuid::Uuid;
 
-use crate::metrics::{QUEUE_DROPPED_TOTAL, QUEUE_ENQUEUED_TOTAL, QUEUE_EXPIRED_TOTAL};
+use crate::metrics::{
+    QUEUE_DEDUP_SKIPPED_TOTAL, QUEUE_DROPPED_TOTAL, QUEUE_ENQUEUED_TOTAL, QUEUE_EXPIRED_TOTAL,
+};
 use crate::notification::NotificationEvent;
 
 use super::backend::{
@@ -16,14 +19,33 @@ use super::backend::{
 };
 use super::QueueConfig;
 
+/// Default number of shards for the in-memory queue
+pub const DEFAULT_QUEUE_SHARD_COUNT: usize = 64;
+
+/// Memory-backend specific tuning
+#[derive(Debug, Clone)]
+pub struct MemoryQueueBackendConfig {
+    /// Number of independent `DashMap` shards, selected by FNV-1a hash of the user ID
+    pub shard_count: usize,
+}
+
+impl Default for MemoryQueueBackendConfig {
+    fn default() -> Self {
+        Self {
+            shard_count: DEFAULT_QUEUE_SHARD_COUNT,
+        }
+    }
+}
+
 /// In-memory message queue backend.
 ///
-/// Uses `DashMap` for concurrent access to per-user queues.
+/// Uses a fixed array of `DashMap` shards for concurrent access to per-user queues,
+/// so user IDs sharing a common prefix don't contend on the same map.
 /// Each user has a `VecDeque` acting as a circular buffer.
 /// When queue is full, oldest messages are dropped (FIFO).
 pub struct MemoryQueueBackend {
-    /// Per-user message queues
-    queues: DashMap<String, VecDeque<StoredMessage>>,
+    /// Per-user message queues, sharded by user ID hash
+    shards: Box<[DashMap<String, VecDeque<StoredMessage>>]>,
     /// Configuration
     config: QueueConfig,
 }
@@ -31,11 +53,45 @@ pub struct MemoryQueueBackend {
 impl MemoryQueueBackend {
     /// Create a new memory queue backend with the given configuration.
     pub fn new(config: QueueConfig) -> Self {
-        Self {
-            queues: DashMap::new(),
-            config,
-        }
+        Self::with_backend_config(config, MemoryQueueBackendConfig::default())
+    }
+
+    /// Create a new memory queue backend with explicit sharding configuration.
+    pub fn with_backend_config(
+        config: QueueConfig,
+        backend_config: MemoryQueueBackendConfig,
+    ) -> Self {
+        let shards = (0..backend_config.shard_count.max(1))
+            .map(|_| DashMap::new())
+            .collect();
+        Self { shards, config }
+    }
+
+    /// Number of shards in use
+    pub fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// Select the shard holding a user's queue
+    fn shard(&self, user_id: &str) -> &DashMap<String, VecDeque<StoredMessage>> {
+        let index = fnv1a_hash(user_id.as_bytes()) % self.shards.len() as u64;
+        &self.shards[index as usize]
     }
+
+    /// Total number of user queues across all shards
+    fn user_count(&self) -> usize {
+        self.shards.iter().map(DashMap::len).sum()
+    }
+}
+
+/// 64-bit FNV-1a hash
+pub(crate) fn fnv1a_hash(bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    bytes.iter().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(PRIME)
+    })
 }
 
 #[async_trait]
@@ -53,9 +109,24 @@ impl MessageQueueBackend for MemoryQueueBackend {
             return Err(QueueBackendError::Disabled);
         }
 
-        let message = StoredMessage::new(event);
+        let ttl = self.config.message_ttl_seconds;
+        let mut queue = self.shard(user_id).entry(user_id.to_string()).or_default();
+
+        // Checked under the entry lock, so concurrent duplicates are queued once
+        if queue
+            .iter()
+            .any(|msg| msg.event.id == event.id && !msg.is_expired(ttl))
+        {
+            QUEUE_DEDUP_SKIPPED_TOTAL.inc();
+            tracing::debug!(
+                user_id = %user_id,
+                notification_id = %event.id,
+                "Notification already queued, skipping duplicate"
+            );
+            return Ok(());
+        }
 
-        let mut queue = self.queues.entry(user_id.to_string()).or_default();
+        let message = StoredMessage::new(event);
 
         // If queue is full, remove oldest message
         if queue.len() >= self.config.max_queue_size_per_user {
@@ -88,7 +159,7 @@ impl MessageQueueBackend for MemoryQueueBackend {
         }
 
         // Take ownership of the queue for this user
-        let messages = match self.queues.remove(user_id) {
+        let messages = match self.shard(user_id).remove(user_id) {
             Some((_, queue)) => queue,
             None => return Ok(DrainResult::default()),
         };
@@ -135,7 +206,7 @@ impl MessageQueueBackend for MemoryQueueBackend {
         }
 
         let messages = self
-            .queues
+            .shard(user_id)
             .get(user_id)
             .map(|q| q.iter().take(limit).cloned().collect())
             .unwrap_or_default();
@@ -144,33 +215,44 @@ impl MessageQueueBackend for MemoryQueueBackend {
     }
 
     async fn queue_size(&self, user_id: &str) -> Result<usize, QueueBackendError> {
-        Ok(self.queues.get(user_id).map(|q| q.len()).unwrap_or(0))
+        Ok(self.shard(user_id).get(user_id).map(|q| q.len()).unwrap_or(0))
+    }
+
+    async fn is_queued(&self, user_id: &str, notification_id: Uuid) -> bool {
+        let ttl = self.config.message_ttl_seconds;
+        self.shard(user_id).get(user_id).is_some_and(|queue| {
+            queue
+                .iter()
+                .any(|msg| msg.event.id == notification_id && !msg.is_expired(ttl))
+        })
     }
 
     async fn cleanup_expired(&self) -> Result<usize, QueueBackendError> {
         let ttl = self.config.message_ttl_seconds;
         let mut removed = 0;
 
-        // Collect user IDs first to avoid holding locks
-        let user_ids: Vec<String> = self.queues.iter().map(|r| r.key().clone()).collect();
-
-        for user_id in user_ids {
-            if let Some(mut queue) = self.queues.get_mut(&user_id) {
-                let before = queue.len();
-                queue.retain(|msg| !msg.is_expired(ttl));
-                let after = queue.len();
-                let expired = before - after;
-                removed += expired;
-
-                // Update Prometheus metrics for expired messages
-                if expired > 0 {
-                    QUEUE_EXPIRED_TOTAL.inc_by(expired as u64);
-                }
-
-                // Remove empty queues
-                if queue.is_empty() {
-                    drop(queue);
-                    self.queues.remove(&user_id);
+        for shard in self.shards.iter() {
+            // Collect user IDs first to avoid holding locks
+            let user_ids: Vec<String> = shard.iter().map(|r| r.key().clone()).collect();
+
+            for user_id in user_ids {
+                if let Some(mut queue) = shard.get_mut(&user_id) {
+                    let before = queue.len();
+                    queue.retain(|msg| !msg.is_expired(ttl));
+                    let after = queue.len();
+                    let expired = before - after;
+                    removed += expired;
+
+                    // Update Prometheus metrics for expired messages
+                    if expired > 0 {
+                        QUEUE_EXPIRED_TOTAL.inc_by(expired as u64);
+                    }
+
+                    // Remove empty queues
+                    if queue.is_empty() {
+                        drop(queue);
+                        shard.remove(&user_id);
+                    }
                 }
             }
         }
@@ -178,7 +260,7 @@ impl MessageQueueBackend for MemoryQueueBackend {
         if removed > 0 {
             tracing::info!(
                 removed = removed,
-                remaining_users = self.queues.len(),
+                remaining_users = self.user_count(),
                 "Cleaned up expired messages"
             );
         }
@@ -186,8 +268,36 @@ impl MessageQueueBackend for MemoryQueueBackend {
         Ok(removed)
     }
 
+    async fn remove_messages(
+        &self,
+        user_id: &str,
+        message_ids: &[Uuid],
+    ) -> Result<usize, QueueBackendError> {
+        let shard = self.shard(user_id);
+        let removed = match shard.get_mut(user_id) {
+            Some(mut queue) => {
+                let before = queue.len();
+                queue.retain(|msg| !message_ids.contains(&msg.id));
+                before - queue.len()
+            }
+            None => return Ok(0),
+        };
+        shard.remove_if(user_id, |_, queue| queue.is_empty());
+        Ok(removed)
+    }
+
     async fn clear_user_queue(&self, user_id: &str) -> Result<usize, QueueBackendError> {
-        Ok(self.queues.remove(user_id).map(|(_, q)| q.len()).unwrap_or(0))
+        Ok(self.shard(user_id).remove(user_id).map(|(_, q)| q.len()).unwrap_or(0))
+    }
+
+    async fn list_users(&self) -> Result<Vec<String>, QueueBackendError> {
+        Ok(self
+            .shards
+            .iter()
+            .flat_map(|shard| shard.iter())
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| entry.key().clone())
+            .collect())
     }
 
     async fn stats(&self) -> QueueBackendStats {
@@ -195,7 +305,7 @@ impl MessageQueueBackend for MemoryQueueBackend {
         let mut users_with_queue = 0;
         let mut max_queue_size = 0;
 
-        for entry in self.queues.iter() {
+        for entry in self.shards.iter().flat_map(|shard| shard.iter()) {
             let size = entry.len();
             total_messages += size;
             users_with_queue += 1;
@@ -216,7 +326,10 @@ impl MessageQueueBackend for MemoryQueueBackend {
 
 #[cfg(test)]
 mod tests {
+    use std::sync::Arc;
+
     use super::*;
+    use crate::websocket::{OutboundMessage, ServerMessage};
     use serde_json::json;
 
     fn create_test_event() -> NotificationEvent {
@@ -247,6 +360,27 @@ mod tests {
         assert!(matches!(result, Err(QueueBackendError::Disabled)));
     }
 
+    #[tokio::test]
+    async fn test_remove_messages_keeps_others() {
+        let backend = MemoryQueueBackend::new(create_enabled_config());
+        for _ in 0..3 {
+            backend.enqueue("user-1", create_test_event()).await.unwrap();
+        }
+        let queued = backend.peek("user-1", 3).await.unwrap();
+
+        let removed = backend
+            .remove_messages("user-1", &[queued[0].id, queued[2].id, Uuid::new_v4()])
+            .await
+            .unwrap();
+        assert_eq!(removed, 2);
+        let remaining = backend.peek("user-1", 3).await.unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].id, queued[1].id);
+
+        backend.remove_messages("user-1", &[queued[1].id]).await.unwrap();
+        assert!(backend.list_users().await.unwrap().is_empty());
+    }
+
     #[tokio::test]
     async fn test_enqueue_success() {
         let backend = MemoryQueueBackend::new(create_enabled_config());
@@ -259,6 +393,46 @@ mod tests {
         assert_eq!(backend.queue_size("user-1").await.unwrap(), 5);
     }
 
+    #[tokio::test]
+    async fn test_is_queued() {
+        let backend = MemoryQueueBackend::new(create_enabled_config());
+        let event = create_test_event();
+        let notification_id = event.id;
+
+        assert!(!backend.is_queued("user-1", notification_id).await);
+        backend.enqueue("user-1", event).await.unwrap();
+        assert!(backend.is_queued("user-1", notification_id).await);
+        assert!(!backend.is_queued("user-2", notification_id).await);
+
+        backend.drain("user-1").await.unwrap();
+        assert!(!backend.is_queued("user-1", notification_id).await);
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_enqueue_delivers_once() {
+        let backend = MemoryQueueBackend::new(create_enabled_config());
+        let event = create_test_event();
+        let skipped = QUEUE_DEDUP_SKIPPED_TOTAL.get();
+
+        backend.enqueue("user-1", event.clone()).await.unwrap();
+        backend.enqueue("user-1", event.clone()).await.unwrap();
+        assert_eq!(backend.queue_size("user-1").await.unwrap(), 1);
+        assert!(QUEUE_DEDUP_SKIPPED_TOTAL.get() > skipped);
+        // Other users' queues are independent
+        backend.enqueue("user-2", event.clone()).await.unwrap();
+        assert_eq!(backend.queue_size("user-2").await.unwrap(), 1);
+
+        let (tx, mut rx) = tokio::sync::mpsc::channel(8);
+        let result = backend.replay("user-1", &tx).await.unwrap();
+        assert_eq!(result.replayed, 1);
+        assert!(rx.try_recv().is_ok());
+        assert!(rx.try_recv().is_err());
+
+        // Once replayed, the notification can be queued again
+        backend.enqueue("user-1", event).await.unwrap();
+        assert_eq!(backend.queue_size("user-1").await.unwrap(), 1);
+    }
+
     #[tokio::test]
     async fn test_enqueue_drops_oldest_when_full() {
         let config = QueueConfig {
@@ -328,6 +502,109 @@ mod tests {
         assert_eq!(result.expired, 3);
     }
 
+    #[tokio::test]
+    async fn test_replay_sends_in_order() {
+        let backend = MemoryQueueBackend::new(create_enabled_config());
+        for _ in 0..3 {
+            backend.enqueue("user-1", create_test_event()).await.unwrap();
+        }
+        let queued = backend.peek("user-1", 3).await.unwrap();
+
+        let (tx, mut rx) = tokio::sync::mpsc::channel(8);
+        let result = backend.replay("user-1", &tx).await.unwrap();
+        assert_eq!(result.replayed, 3);
+        assert_eq!(result.failed, 0);
+        assert_eq!(backend.queue_size("user-1").await.unwrap(), 0);
+
+        for stored in queued {
+            match rx.recv().await {
+                Some(crate::websocket::OutboundMessage::Raw(
+                    crate::websocket::ServerMessage::Notification { event },
+                )) => assert_eq!(event.id, stored.event.id),
+                other => panic!("unexpected message: {:?}", other.is_some()),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_interrupted_replay_keeps_undelivered_messages() {
+        let backend = Arc::new(MemoryQueueBackend::new(create_enabled_config()));
+        let mut event_ids = Vec::new();
+        for _ in 0..5 {
+            let event = create_test_event();
+            event_ids.push(event.id);
+            backend.enqueue("user-1", event).await.unwrap();
+        }
+        let received_ids = |rx: &mut tokio::sync::mpsc::Receiver<OutboundMessage>| {
+            let mut ids = Vec::new();
+            while let Ok(OutboundMessage::Raw(ServerMessage::Notification { event })) = rx.try_recv() {
+                ids.push(event.id);
+            }
+            ids
+        };
+
+        // The connection accepts two messages, then drops while the third is sent
+        let (tx, mut rx) = tokio::sync::mpsc::channel(2);
+        let replay = tokio::spawn({
+            let backend = backend.clone();
+            async move { backend.replay("user-1", &tx).await }
+        });
+        while rx.len() < 2 {
+            tokio::task::yield_now().await;
+        }
+        // A message queued meanwhile stays behind the undelivered ones
+        let late = create_test_event();
+        event_ids.push(late.id);
+        backend.enqueue("user-1", late).await.unwrap();
+        rx.close();
+
+        let result = replay.await.unwrap().unwrap();
+        assert_eq!(result.replayed, 2);
+        assert_eq!(result.failed, 1);
+        assert_eq!(result.remaining, 3);
+        assert_eq!(received_ids(&mut rx), event_ids[..2]);
+        assert_eq!(backend.queue_size("user-1").await.unwrap(), 4);
+
+        // The next replay resumes with the first undelivered message
+        let (tx, mut rx) = tokio::sync::mpsc::channel(8);
+        let result = backend.replay("user-1", &tx).await.unwrap();
+        assert_eq!(result.replayed, 4);
+        assert_eq!(received_ids(&mut rx), event_ids[2..]);
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_replay_keeps_unsent_messages() {
+        let backend = Arc::new(MemoryQueueBackend::new(create_enabled_config()));
+        let mut event_ids = Vec::new();
+        for _ in 0..5 {
+            let event = create_test_event();
+            event_ids.push(event.id);
+            backend.enqueue("user-1", event).await.unwrap();
+        }
+
+        // The replay task is dropped while blocked sending the third message
+        let (tx, mut rx) = tokio::sync::mpsc::channel(2);
+        let replay = tokio::spawn({
+            let backend = backend.clone();
+            async move { backend.replay("user-1", &tx).await }
+        });
+        while rx.len() < 2 {
+            tokio::task::yield_now().await;
+        }
+        replay.abort();
+        assert!(replay.await.unwrap_err().is_cancelled());
+        assert_eq!(backend.queue_size("user-1").await.unwrap(), 3);
+
+        let (tx, mut rx) = tokio::sync::mpsc::channel(8);
+        let result = backend.replay("user-1", &tx).await.unwrap();
+        assert_eq!(result.replayed, 3);
+        let mut received = Vec::new();
+        while let Ok(OutboundMessage::Raw(ServerMessage::Notification { event })) = rx.try_recv() {
+            received.push(event.id);
+        }
+        assert_eq!(received, event_ids[2..]);
+    }
+
     #[tokio::test]
     async fn test_peek() {
         let backend = MemoryQueueBackend::new(create_enabled_config());
@@ -417,4 +694,45 @@ mod tests {
         assert_eq!(stats.max_queue_size_config, 10);
         assert_eq!(stats.message_ttl_seconds, 3600);
     }
+
+    #[test]
+    fn test_fnv1a_hash() {
+        assert_eq!(fnv1a_hash(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a_hash(b"a"), 0xaf63_dc4c_8601_ec8c);
+    }
+
+    #[tokio::test]
+    async fn test_sharded_users_spread_across_shards() {
+        let backend = MemoryQueueBackend::new(create_enabled_config());
+        assert_eq!(backend.shard_count(), DEFAULT_QUEUE_SHARD_COUNT);
+
+        for i in 0..500 {
+            backend
+                .enqueue(&format!("user-{}", i), create_test_event())
+                .await
+                .unwrap();
+        }
+
+        let used_shards = backend.shards.iter().filter(|s| !s.is_empty()).count();
+        assert!(used_shards > DEFAULT_QUEUE_SHARD_COUNT / 2);
+        assert_eq!(backend.user_count(), 500);
+        assert_eq!(backend.list_users().await.unwrap().len(), 500);
+        assert_eq!(backend.stats().await.total_messages, 500);
+    }
+
+    #[tokio::test]
+    async fn test_single_shard_backend() {
+        let backend = MemoryQueueBackend::with_backend_config(
+            create_enabled_config(),
+            MemoryQueueBackendConfig { shard_count: 0 },
+        );
+        assert_eq!(backend.shard_count(), 1);
+
+        backend.enqueue("user-1", create_test_event()).await.unwrap();
+        backend.enqueue("user-2", create_test_event()).await.unwrap();
+        assert_eq!(backend.queue_size("user-1").await.unwrap(), 1);
+        assert_eq!(backend.clear_user_queue("user-2").await.unwrap(), 1);
+        assert_eq!(backend.drain("user-1").await.unwrap().messages.len(), 1);
+        assert_eq!(backend.user_count(), 0);
+    }
 }
@@ -26,8 +26,11 @@ mod user_queue;
 pub use backend::{
     DrainResult, MessageQueueBackend, QueueBackendError, QueueBackendStats, StoredMessage,
 };
-pub use factory::create_queue_backend;
-pub use memory_backend::MemoryQueueBackend;
+pub use factory::{
+    create_queue_backend, migrate_backend, migrate_backend_with_progress, MigrationProgress,
+    MigrationResult, MigrationState, MigrationStatus,
+};
+pub use memory_backend::{MemoryQueueBackend, MemoryQueueBackendConfig};
 pub use models::{QueueConfig, QueueError, QueueStats, QueuedMessage, ReplayResult};
 pub use postgres_backend::PostgresQueueBackend;
 pub use redis_backend::RedisQueueBackend;
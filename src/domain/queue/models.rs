@@ -41,6 +41,12 @@ pub struct QueuedMessage {
     pub queued_at: DateTime<Utc>,
     /// Number of delivery attempts
     pub attempts: u32,
+    /// Whether the message has been replayed to the user; delivered messages are
+    /// removed once the replay pass that sent them finishes
+    pub delivered: bool,
+    /// Whether a replay has claimed the message and is sending it, so concurrent
+    /// replays skip it
+    pub in_flight: bool,
 }
 
 impl QueuedMessage {
@@ -51,14 +57,17 @@ impl QueuedMessage {
             event,
             queued_at: Utc::now(),
             attempts: 0,
+            delivered: false,
+            in_flight: false,
         }
     }
 
-    /// Check if the message has expired
+    /// Check if the message has expired, either by the queue TTL or by the
+    /// notification's own TTL, whichever is reached first
     pub fn is_expired(&self, ttl_seconds: u64) -> bool {
         let now = Utc::now();
         let age = now.signed_duration_since(self.queued_at);
-        age.num_seconds() >= ttl_seconds as i64
+        age.num_seconds() >= ttl_seconds as i64 || self.event.is_expired()
     }
 }
 
@@ -71,6 +80,8 @@ pub struct ReplayResult {
     pub failed: usize,
     /// Number of messages that were expired and discarded
     pub expired: usize,
+    /// Number of undelivered messages left in the queue for a later replay
+    pub remaining: usize,
 }
 
 impl ReplayResult {
@@ -79,6 +90,7 @@ impl ReplayResult {
             replayed: 0,
             failed: 0,
             expired: 0,
+            remaining: 0,
         }
     }
 }
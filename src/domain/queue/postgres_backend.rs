@@ -4,16 +4,52 @@
 //! using PostgreSQL for storage. Messages are stored in a table with JSONB event data
 //! and automatic expiration.
 
+use std::time::Duration as StdDuration;
+
 use async_trait::async_trait;
 use chrono::{Duration, Utc};
-use sqlx::PgPool;
+use sqlx::pool::PoolConnection;
+use sqlx::{PgConnection, PgPool, Postgres};
+use tokio::sync::mpsc;
 use uuid::Uuid;
 
-use crate::metrics::{QUEUE_DROPPED_TOTAL, QUEUE_ENQUEUED_TOTAL, QUEUE_EXPIRED_TOTAL};
+use crate::metrics::{
+    QUEUE_DEDUP_SKIPPED_TOTAL, QUEUE_DROPPED_TOTAL, QUEUE_ENQUEUED_TOTAL, QUEUE_EXPIRED_TOTAL,
+    QUEUE_REPLAY_LOCK_CONTENTION_TOTAL,
+};
 use crate::notification::NotificationEvent;
+use crate::websocket::OutboundMessage;
+
+use super::backend::{
+    checksum_matches, replay_in_place, DrainResult, MessageQueueBackend, QueueBackendError,
+    QueueBackendStats, StoredMessage,
+};
+use super::{QueueConfig, ReplayResult};
+
+/// How long a replay waits for another connection's replay lock
+const REPLAY_LOCK_TIMEOUT: StdDuration = StdDuration::from_secs(2);
+
+/// Interval between advisory lock attempts while waiting
+const REPLAY_LOCK_RETRY_INTERVAL: StdDuration = StdDuration::from_millis(100);
 
-use super::backend::{DrainResult, MessageQueueBackend, QueueBackendError, QueueBackendStats, StoredMessage};
-use super::QueueConfig;
+/// Columns read back for a queued message: id, event_data, queued_at, attempts, checksum
+type QueueRow = (Uuid, serde_json::Value, chrono::DateTime<Utc>, i32, Option<i64>);
+
+/// Connection holding a user's replay advisory lock. Dropped while still `held`
+/// (e.g. when the replay is cancelled), it closes the connection rather than returning
+/// it to the pool, and closing the session releases the lock.
+struct ReplayLock {
+    conn: PoolConnection<Postgres>,
+    held: bool,
+}
+
+impl Drop for ReplayLock {
+    fn drop(&mut self) {
+        if self.held {
+            self.conn.close_on_drop();
+        }
+    }
+}
 
 /// PostgreSQL-based message queue backend.
 ///
@@ -50,107 +86,124 @@ impl PostgresQueueBackend {
             tenant_id,
         }
     }
-}
 
-#[async_trait]
-impl MessageQueueBackend for PostgresQueueBackend {
-    fn is_enabled(&self) -> bool {
-        self.config.enabled
-    }
-
-    fn message_ttl_seconds(&self) -> u64 {
-        self.config.message_ttl_seconds
-    }
-
-    async fn enqueue(&self, user_id: &str, event: NotificationEvent) -> Result<(), QueueBackendError> {
+    /// Replay a user's queued messages while holding a PostgreSQL advisory lock.
+    ///
+    /// Used as this backend's [`MessageQueueBackend::replay`]. Guards against
+    /// duplicate delivery when the same user reconnects on several connections at
+    /// once: only the lock holder sends the queue, deleting each message once it has
+    /// been sent. Callers that cannot acquire the lock retry every 100 ms for up to 2
+    /// seconds and then return an empty `ReplayResult`; by the time the lock frees up
+    /// the sent messages are gone, so late acquirers replay only what is left. The
+    /// lock is keyed by tenant and user, so the same user ID in different tenants
+    /// does not contend. If the replay is dropped while holding the lock, its
+    /// connection is closed instead of returned to the pool, which releases the lock.
+    pub async fn replay_with_advisory_lock(
+        &self,
+        user_id: &str,
+        sender: &mpsc::Sender<OutboundMessage>,
+    ) -> Result<ReplayResult, QueueBackendError> {
         if !self.config.enabled {
-            return Err(QueueBackendError::Disabled);
+            return Ok(ReplayResult::empty());
         }
 
-        let expires_at = Utc::now() + Duration::seconds(self.config.message_ttl_seconds as i64);
-        let event_data = serde_json::to_value(&event)?;
-        let id = Uuid::new_v4();
-
-        // Atomic enqueue with queue size enforcement using CTE
-        // This prevents race conditions by combining delete + insert in a single query
-        let result: (i64,) = sqlx::query_as(
-            r#"
-            WITH deleted AS (
-                DELETE FROM message_queue
-                WHERE id IN (
-                    SELECT id FROM message_queue
-                    WHERE tenant_id = $1 AND user_id = $2
-                    AND (SELECT COUNT(*) FROM message_queue WHERE tenant_id = $1 AND user_id = $2) >= $3
-                    ORDER BY queued_at ASC
-                    LIMIT 1
-                )
-                RETURNING 1
-            ),
-            inserted AS (
-                INSERT INTO message_queue (id, tenant_id, user_id, event_data, queued_at, expires_at)
-                VALUES ($4, $1, $2, $5, NOW(), $6)
-                RETURNING 1
-            )
-            SELECT COALESCE((SELECT COUNT(*) FROM deleted), 0) as dropped
-            "#
-        )
-        .bind(&self.tenant_id)
-        .bind(user_id)
-        .bind(self.config.max_queue_size_per_user as i64)
-        .bind(id)
-        .bind(&event_data)
-        .bind(expires_at)
-        .fetch_one(&self.pool)
-        .await
-        .map_err(QueueBackendError::Postgres)?;
+        // Advisory locks are session-scoped, so locking and unlocking run on one connection
+        let mut conn = self.pool.acquire().await?;
 
-        let dropped = result.0;
-        if dropped > 0 {
-            QUEUE_DROPPED_TOTAL.inc();
+        if !self.try_advisory_lock(&mut conn, user_id).await? {
+            QUEUE_REPLAY_LOCK_CONTENTION_TOTAL.inc();
             tracing::debug!(
                 user_id = %user_id,
                 tenant_id = %self.tenant_id,
-                "Dropped oldest message from full queue"
+                "Replay lock held by another connection, waiting"
             );
+
+            let deadline = tokio::time::Instant::now() + REPLAY_LOCK_TIMEOUT;
+            loop {
+                if tokio::time::Instant::now() >= deadline {
+                    return Ok(ReplayResult::empty());
+                }
+                tokio::time::sleep(REPLAY_LOCK_RETRY_INTERVAL).await;
+                if self.try_advisory_lock(&mut conn, user_id).await? {
+                    break;
+                }
+            }
         }
 
-        // Update queue stats
-        if let Err(e) = sqlx::query("SELECT upsert_queue_stats($1, 1, 0, 0)")
+        let mut lock = ReplayLock { conn, held: true };
+        let result = self.replay_locked(user_id, sender).await;
+
+        // Release before propagating any error so the lock never leaks
+        match sqlx::query("SELECT pg_advisory_unlock(hashtext($1 || ':' || $2))")
             .bind(&self.tenant_id)
-            .execute(&self.pool)
+            .bind(user_id)
+            .execute(&mut *lock.conn)
             .await
         {
-            tracing::warn!(error = %e, "Failed to update queue stats after enqueue");
+            Ok(_) => lock.held = false,
+            Err(e) => {
+                tracing::warn!(error = %e, user_id = %user_id, "Failed to release replay lock");
+            }
         }
 
-        QUEUE_ENQUEUED_TOTAL.inc();
-
-        tracing::trace!(
-            user_id = %user_id,
-            tenant_id = %self.tenant_id,
-            message_id = %id,
-            "Message enqueued to PostgreSQL"
-        );
+        result
+    }
 
-        Ok(())
+    /// Send a user's queue while holding the replay lock, oldest first
+    async fn replay_locked(
+        &self,
+        user_id: &str,
+        sender: &mpsc::Sender<OutboundMessage>,
+    ) -> Result<ReplayResult, QueueBackendError> {
+        let expired = sqlx::query(
+            "DELETE FROM message_queue WHERE tenant_id = $1 AND user_id = $2 AND expires_at <= NOW()"
+        )
+        .bind(&self.tenant_id)
+        .bind(user_id)
+        .execute(&self.pool)
+        .await
+        .map_err(QueueBackendError::Postgres)?
+        .rows_affected() as usize;
+        QUEUE_EXPIRED_TOTAL.inc_by(expired as u64);
+
+        let messages = self.peek(user_id, self.config.max_queue_size_per_user).await?;
+        let mut result = replay_in_place(self, user_id, messages, sender).await;
+        result.expired += expired;
+        Ok(result)
     }
 
-    async fn drain(&self, user_id: &str) -> Result<DrainResult, QueueBackendError> {
-        if !self.config.enabled {
-            return Ok(DrainResult::default());
-        }
+    /// Attempt to take the per-user replay advisory lock without blocking
+    async fn try_advisory_lock(
+        &self,
+        conn: &mut PgConnection,
+        user_id: &str,
+    ) -> Result<bool, QueueBackendError> {
+        let acquired: bool =
+            sqlx::query_scalar("SELECT pg_try_advisory_lock(hashtext($1 || ':' || $2))")
+                .bind(&self.tenant_id)
+                .bind(user_id)
+                .fetch_one(conn)
+                .await?;
+        Ok(acquired)
+    }
 
+    /// Drain a user's queue using the given connection
+    async fn drain_on(
+        &self,
+        conn: &mut PgConnection,
+        user_id: &str,
+    ) -> Result<DrainResult, QueueBackendError> {
         // Fetch and delete all non-expired messages for this user in one query
-        let rows: Vec<(Uuid, serde_json::Value, chrono::DateTime<Utc>, i32)> = sqlx::query_as(
+        let rows: Vec<QueueRow> = sqlx::query_as(
             r#"
             DELETE FROM message_queue
             WHERE tenant_id = $1 AND user_id = $2 AND expires_at > NOW()
-            RETURNING id, event_data, queued_at, attempts
+            RETURNING id, event_data, queued_at, attempts, checksum
             "#
         )
         .bind(&self.tenant_id)
         .bind(user_id)
-        .fetch_all(&self.pool)
+        .fetch_all(&mut *conn)
         .await
         .map_err(QueueBackendError::Postgres)?;
 
@@ -163,22 +216,23 @@ impl MessageQueueBackend for PostgresQueueBackend {
         )
         .bind(&self.tenant_id)
         .bind(user_id)
-        .execute(&self.pool)
+        .execute(&mut *conn)
         .await
         .map_err(QueueBackendError::Postgres)?;
 
         let expired = expired_result.rows_affected() as usize;
 
-        // Convert rows to StoredMessage
-        let messages: Vec<StoredMessage> = rows
+        // Convert rows to StoredMessage, oldest first (RETURNING rows are unordered)
+        let mut messages: Vec<StoredMessage> = rows
             .into_iter()
-            .filter_map(|(id, event_data, queued_at, attempts)| {
+            .filter_map(|(id, event_data, queued_at, attempts, checksum)| {
                 match serde_json::from_value(event_data) {
                     Ok(event) => Some(StoredMessage {
                         id,
                         event,
                         queued_at,
                         attempts: attempts as u32,
+                        checksum: checksum.unwrap_or(0) as u32,
                         stream_id: None,
                     }),
                     Err(e) => {
@@ -191,7 +245,9 @@ impl MessageQueueBackend for PostgresQueueBackend {
                     }
                 }
             })
+            .filter(|message| checksum_matches(message, user_id))
             .collect();
+        messages.sort_by_key(|message| message.queued_at);
 
         let drained_count = messages.len();
 
@@ -201,7 +257,7 @@ impl MessageQueueBackend for PostgresQueueBackend {
                 .bind(&self.tenant_id)
                 .bind(drained_count as i64)
                 .bind(expired as i64)
-                .execute(&self.pool)
+                .execute(&mut *conn)
                 .await
             {
                 tracing::warn!(error = %e, "Failed to update queue stats after drain");
@@ -222,15 +278,132 @@ impl MessageQueueBackend for PostgresQueueBackend {
 
         Ok(DrainResult { messages, expired })
     }
+}
+
+#[async_trait]
+impl MessageQueueBackend for PostgresQueueBackend {
+    fn is_enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    fn message_ttl_seconds(&self) -> u64 {
+        self.config.message_ttl_seconds
+    }
+
+    async fn enqueue(&self, user_id: &str, event: NotificationEvent) -> Result<(), QueueBackendError> {
+        if !self.config.enabled {
+            return Err(QueueBackendError::Disabled);
+        }
+
+        let expires_at = Utc::now() + Duration::seconds(self.config.message_ttl_seconds as i64);
+        let event_data = serde_json::to_value(&event)?;
+        let checksum = StoredMessage::event_checksum(&event);
+        let id = Uuid::new_v4();
+
+        // Atomic enqueue with deduplication and queue size enforcement using CTE.
+        // This prevents race conditions by combining insert + delete in a single query;
+        // the oldest message is only dropped when the insert went through.
+        let result: (i64, i64) = sqlx::query_as(
+            r#"
+            WITH inserted AS (
+                INSERT INTO message_queue (id, tenant_id, user_id, event_data, queued_at, expires_at, checksum)
+                VALUES ($4, $1, $2, $5, NOW(), $6, $7)
+                ON CONFLICT (tenant_id, user_id, (event_data->>'id')) DO NOTHING
+                RETURNING 1
+            ),
+            deleted AS (
+                DELETE FROM message_queue
+                WHERE id IN (
+                    SELECT id FROM message_queue
+                    WHERE tenant_id = $1 AND user_id = $2
+                    AND EXISTS (SELECT 1 FROM inserted)
+                    AND (SELECT COUNT(*) FROM message_queue WHERE tenant_id = $1 AND user_id = $2) >= $3
+                    ORDER BY queued_at ASC
+                    LIMIT 1
+                )
+                RETURNING 1
+            )
+            SELECT (SELECT COUNT(*) FROM inserted) as inserted,
+                   (SELECT COUNT(*) FROM deleted) as dropped
+            "#
+        )
+        .bind(&self.tenant_id)
+        .bind(user_id)
+        .bind(self.config.max_queue_size_per_user as i64)
+        .bind(id)
+        .bind(&event_data)
+        .bind(expires_at)
+        .bind(checksum as i64)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(QueueBackendError::Postgres)?;
+
+        let (inserted, dropped) = result;
+        if inserted == 0 {
+            QUEUE_DEDUP_SKIPPED_TOTAL.inc();
+            tracing::debug!(
+                user_id = %user_id,
+                tenant_id = %self.tenant_id,
+                notification_id = %event.id,
+                "Notification already queued, skipping duplicate"
+            );
+            return Ok(());
+        }
+        if dropped > 0 {
+            QUEUE_DROPPED_TOTAL.inc();
+            tracing::debug!(
+                user_id = %user_id,
+                tenant_id = %self.tenant_id,
+                "Dropped oldest message from full queue"
+            );
+        }
+
+        // Update queue stats
+        if let Err(e) = sqlx::query("SELECT upsert_queue_stats($1, 1, 0, 0)")
+            .bind(&self.tenant_id)
+            .execute(&self.pool)
+            .await
+        {
+            tracing::warn!(error = %e, "Failed to update queue stats after enqueue");
+        }
+
+        QUEUE_ENQUEUED_TOTAL.inc();
+
+        tracing::trace!(
+            user_id = %user_id,
+            tenant_id = %self.tenant_id,
+            message_id = %id,
+            "Message enqueued to PostgreSQL"
+        );
+
+        Ok(())
+    }
+
+    async fn drain(&self, user_id: &str) -> Result<DrainResult, QueueBackendError> {
+        if !self.config.enabled {
+            return Ok(DrainResult::default());
+        }
+
+        let mut conn = self.pool.acquire().await?;
+        self.drain_on(&mut conn, user_id).await
+    }
+
+    async fn replay(
+        &self,
+        user_id: &str,
+        sender: &mpsc::Sender<OutboundMessage>,
+    ) -> Result<ReplayResult, QueueBackendError> {
+        self.replay_with_advisory_lock(user_id, sender).await
+    }
 
     async fn peek(&self, user_id: &str, limit: usize) -> Result<Vec<StoredMessage>, QueueBackendError> {
         if !self.config.enabled {
             return Ok(Vec::new());
         }
 
-        let rows: Vec<(Uuid, serde_json::Value, chrono::DateTime<Utc>, i32)> = sqlx::query_as(
+        let rows: Vec<QueueRow> = sqlx::query_as(
             r#"
-            SELECT id, event_data, queued_at, attempts
+            SELECT id, event_data, queued_at, attempts, checksum
             FROM message_queue
             WHERE tenant_id = $1 AND user_id = $2 AND expires_at > NOW()
             ORDER BY queued_at ASC
@@ -246,13 +419,14 @@ impl MessageQueueBackend for PostgresQueueBackend {
 
         let messages = rows
             .into_iter()
-            .filter_map(|(id, event_data, queued_at, attempts)| {
+            .filter_map(|(id, event_data, queued_at, attempts, checksum)| {
                 match serde_json::from_value(event_data) {
                     Ok(event) => Some(StoredMessage {
                         id,
                         event,
                         queued_at,
                         attempts: attempts as u32,
+                        checksum: checksum.unwrap_or(0) as u32,
                         stream_id: None,
                     }),
                     Err(e) => {
@@ -265,6 +439,7 @@ impl MessageQueueBackend for PostgresQueueBackend {
                     }
                 }
             })
+            .filter(|message| checksum_matches(message, user_id))
             .collect();
 
         Ok(messages)
@@ -287,6 +462,35 @@ impl MessageQueueBackend for PostgresQueueBackend {
         Ok(count as usize)
     }
 
+    async fn is_queued(&self, user_id: &str, notification_id: Uuid) -> bool {
+        if !self.config.enabled {
+            return false;
+        }
+
+        let result = sqlx::query_scalar::<_, bool>(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM message_queue
+                WHERE tenant_id = $1 AND user_id = $2 AND expires_at > NOW()
+                AND event_data->>'id' = $3
+            )
+            "#
+        )
+        .bind(&self.tenant_id)
+        .bind(user_id)
+        .bind(notification_id.to_string())
+        .fetch_one(&self.pool)
+        .await;
+
+        match result {
+            Ok(queued) => queued,
+            Err(e) => {
+                tracing::warn!(user_id = %user_id, error = %e, "Failed to check PostgreSQL queue");
+                false
+            }
+        }
+    }
+
     async fn cleanup_expired(&self) -> Result<usize, QueueBackendError> {
         if !self.config.enabled {
             return Ok(0);
@@ -315,6 +519,28 @@ impl MessageQueueBackend for PostgresQueueBackend {
         Ok(count)
     }
 
+    async fn remove_messages(
+        &self,
+        user_id: &str,
+        message_ids: &[Uuid],
+    ) -> Result<usize, QueueBackendError> {
+        if !self.config.enabled || message_ids.is_empty() {
+            return Ok(0);
+        }
+
+        let result = sqlx::query(
+            "DELETE FROM message_queue WHERE tenant_id = $1 AND user_id = $2 AND id = ANY($3)"
+        )
+        .bind(&self.tenant_id)
+        .bind(user_id)
+        .bind(message_ids)
+        .execute(&self.pool)
+        .await
+        .map_err(QueueBackendError::Postgres)?;
+
+        Ok(result.rows_affected() as usize)
+    }
+
     async fn clear_user_queue(&self, user_id: &str) -> Result<usize, QueueBackendError> {
         if !self.config.enabled {
             return Ok(0);
@@ -332,6 +558,18 @@ impl MessageQueueBackend for PostgresQueueBackend {
         Ok(result.rows_affected() as usize)
     }
 
+    async fn list_users(&self) -> Result<Vec<String>, QueueBackendError> {
+        let users: Vec<String> = sqlx::query_scalar(
+            "SELECT DISTINCT user_id FROM message_queue WHERE tenant_id = $1 AND expires_at > NOW()"
+        )
+        .bind(&self.tenant_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(QueueBackendError::Postgres)?;
+
+        Ok(users)
+    }
+
     async fn stats(&self) -> QueueBackendStats {
         // Get total messages and unique users
         let (total_messages, users_with_queue, max_queue_size): (i64, i64, i64) = sqlx::query_as(
@@ -396,4 +634,181 @@ mod tests {
         let _tenant_id = "test-tenant".to_string();
         assert!(config.enabled);
     }
+
+    /// Requires a PostgreSQL instance with the `migrations/` applied:
+    /// `DATABASE_URL=postgres://... cargo test -- --ignored`
+    #[tokio::test]
+    #[ignore]
+    async fn test_concurrent_replays_deliver_once() {
+        let url = std::env::var("DATABASE_URL")
+            .unwrap_or_else(|_| "postgres://localhost:5432/ara_notification".to_string());
+        let pool = PgPool::connect(&url).await.unwrap();
+        let backend = std::sync::Arc::new(PostgresQueueBackend::with_tenant(
+            create_test_config(),
+            pool,
+            format!("replay-lock-{}", Uuid::new_v4()),
+        ));
+        let user_id = format!("user-{}", Uuid::new_v4());
+
+        const MESSAGES: usize = 50;
+        for i in 0..MESSAGES {
+            let event = NotificationEvent::new(
+                "test.event".to_string(),
+                serde_json::json!({"index": i}),
+                "test".to_string(),
+            );
+            backend.enqueue(&user_id, event).await.unwrap();
+        }
+
+        let barrier = std::sync::Arc::new(tokio::sync::Barrier::new(10));
+        let handles: Vec<_> = (0..10)
+            .map(|_| {
+                let backend = backend.clone();
+                let barrier = barrier.clone();
+                let user_id = user_id.clone();
+                tokio::spawn(async move {
+                    let (tx, mut rx) = mpsc::channel(MESSAGES);
+                    barrier.wait().await;
+                    let result = backend.replay(&user_id, &tx).await.unwrap();
+                    drop(tx);
+                    let mut received = 0;
+                    while rx.recv().await.is_some() {
+                        received += 1;
+                    }
+                    assert_eq!(result.replayed, received);
+                    result
+                })
+            })
+            .collect();
+
+        let mut replayed: Vec<usize> = Vec::new();
+        for handle in handles {
+            replayed.push(handle.await.unwrap().replayed);
+        }
+        replayed.sort_unstable();
+
+        assert_eq!(replayed[9], MESSAGES);
+        assert!(replayed[..9].iter().all(|&n| n == 0));
+        assert_eq!(backend.queue_size(&user_id).await.unwrap(), 0);
+    }
+
+    /// Requires a PostgreSQL instance with the `migrations/` applied:
+    /// `DATABASE_URL=postgres://... cargo test -- --ignored`
+    #[tokio::test]
+    #[ignore]
+    async fn test_duplicate_enqueue_is_skipped() {
+        let url = std::env::var("DATABASE_URL")
+            .unwrap_or_else(|_| "postgres://localhost:5432/ara_notification".to_string());
+        let pool = PgPool::connect(&url).await.unwrap();
+        let backend = PostgresQueueBackend::with_tenant(
+            create_test_config(),
+            pool,
+            format!("dedup-{}", Uuid::new_v4()),
+        );
+        let user_id = format!("user-{}", Uuid::new_v4());
+        let event = NotificationEvent::new(
+            "test.event".to_string(),
+            serde_json::json!({}),
+            "test".to_string(),
+        );
+
+        backend.enqueue(&user_id, event.clone()).await.unwrap();
+        backend.enqueue(&user_id, event.clone()).await.unwrap();
+        assert_eq!(backend.queue_size(&user_id).await.unwrap(), 1);
+        assert!(backend.is_queued(&user_id, event.id).await);
+
+        let drained = backend.drain(&user_id).await.unwrap();
+        assert_eq!(drained.messages.len(), 1);
+        assert!(!backend.is_queued(&user_id, event.id).await);
+    }
+
+    /// Requires a PostgreSQL instance with the `migrations/` applied:
+    /// `DATABASE_URL=postgres://... cargo test -- --ignored`
+    #[tokio::test]
+    #[ignore]
+    async fn test_interrupted_replay_keeps_messages() {
+        let url = std::env::var("DATABASE_URL")
+            .unwrap_or_else(|_| "postgres://localhost:5432/ara_notification".to_string());
+        let pool = PgPool::connect(&url).await.unwrap();
+        let backend = PostgresQueueBackend::with_tenant(
+            create_test_config(),
+            pool,
+            format!("interrupted-{}", Uuid::new_v4()),
+        );
+        let user_id = format!("user-{}", Uuid::new_v4());
+
+        let mut event_ids = Vec::new();
+        for i in 0..3 {
+            let event = NotificationEvent::new(
+                "test.event".to_string(),
+                serde_json::json!({"index": i}),
+                "test".to_string(),
+            );
+            event_ids.push(event.id);
+            backend.enqueue(&user_id, event).await.unwrap();
+        }
+
+        // The connection is gone before the first send
+        let (tx, rx) = mpsc::channel(8);
+        drop(rx);
+        let result = backend.replay(&user_id, &tx).await.unwrap();
+        assert_eq!(result.replayed, 0);
+        assert_eq!(result.remaining, 3);
+        assert_eq!(backend.queue_size(&user_id).await.unwrap(), 3);
+
+        let (tx, mut rx) = mpsc::channel(8);
+        let result = backend.replay(&user_id, &tx).await.unwrap();
+        assert_eq!(result.replayed, 3);
+        let mut received = Vec::new();
+        while let Ok(OutboundMessage::Raw(crate::websocket::ServerMessage::Notification {
+            event,
+        })) = rx.try_recv()
+        {
+            received.push(event.id);
+        }
+        assert_eq!(received, event_ids);
+    }
+
+    /// Requires a PostgreSQL instance with the `migrations/` applied:
+    /// `DATABASE_URL=postgres://... cargo test -- --ignored`
+    #[tokio::test]
+    #[ignore]
+    async fn test_cancelled_replay_releases_lock() {
+        let url = std::env::var("DATABASE_URL")
+            .unwrap_or_else(|_| "postgres://localhost:5432/ara_notification".to_string());
+        let pool = PgPool::connect(&url).await.unwrap();
+        let backend = std::sync::Arc::new(PostgresQueueBackend::with_tenant(
+            create_test_config(),
+            pool,
+            format!("cancelled-{}", Uuid::new_v4()),
+        ));
+        let user_id = format!("user-{}", Uuid::new_v4());
+        for i in 0..3 {
+            let event = NotificationEvent::new(
+                "test.event".to_string(),
+                serde_json::json!({"index": i}),
+                "test".to_string(),
+            );
+            backend.enqueue(&user_id, event).await.unwrap();
+        }
+
+        // The replay is dropped while blocked sending the second message
+        let (tx, mut rx) = mpsc::channel(1);
+        let replay = tokio::spawn({
+            let backend = backend.clone();
+            let user_id = user_id.clone();
+            async move { backend.replay(&user_id, &tx).await }
+        });
+        while rx.len() < 1 {
+            tokio::time::sleep(StdDuration::from_millis(10)).await;
+        }
+        tokio::time::sleep(StdDuration::from_millis(100)).await;
+        replay.abort();
+        assert!(replay.await.unwrap_err().is_cancelled());
+
+        // The lock is free again and only the sent message is gone
+        let (tx, _rx) = mpsc::channel(8);
+        let result = backend.replay(&user_id, &tx).await.unwrap();
+        assert_eq!(result.replayed, 2);
+    }
 }
@@ -6,19 +6,34 @@
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use chrono::Utc;
+use lazy_static::lazy_static;
+use tokio::sync::mpsc;
+use uuid::Uuid;
 
+use crate::metrics::QUEUE_DEDUP_SKIPPED_TOTAL;
 use crate::notification::NotificationEvent;
+use crate::websocket::OutboundMessage;
 use crate::redis::pool::{PoolError, RedisPool, RedisPoolExt};
 
 use super::backend::{
-    DrainResult, MessageQueueBackend, QueueBackendError, QueueBackendStats, StoredMessage,
+    checksum_matches, replay_in_place, DrainResult, MessageQueueBackend, QueueBackendError,
+    QueueBackendStats, StoredMessage,
 };
-use super::QueueConfig;
+use super::{QueueConfig, ReplayResult};
+
+lazy_static! {
+    /// Atomic dedup check + XADD for a new queued message (see `scripts/queue_enqueue.lua`)
+    static ref ENQUEUE_SCRIPT: redis::Script =
+        redis::Script::new(include_str!("../../../scripts/queue_enqueue.lua"));
+}
 
 /// Redis-based message queue backend.
 ///
 /// Uses Redis Streams for persistent message storage.
-/// Each user has a dedicated stream: `{prefix}:{tenant_id}:{user_id}`.
+/// Each user has a dedicated stream: `{prefix}:{tenant_id}:{user_id}`, and a sorted
+/// set of the notification IDs in it: `{prefix}-ids:{tenant_id}:{user_id}`
+/// (score = enqueue time), used to skip duplicates.
 pub struct RedisQueueBackend {
     /// Redis connection pool
     pool: Arc<RedisPool>,
@@ -68,6 +83,42 @@ impl RedisQueueBackend {
         format!("{}:{}:{}", self.prefix, self.tenant_id, user_id)
     }
 
+    /// Generate the Redis key for the notification IDs in a user's queue. Kept
+    /// outside `{prefix}:{tenant_id}:` so `list_users` doesn't pick it up.
+    fn queued_ids_key(&self, user_id: &str) -> String {
+        format!("{}-ids:{}:{}", self.prefix, self.tenant_id, user_id)
+    }
+
+    /// Read a user's queued messages in order, along with the stream IDs of entries
+    /// that cannot be delivered because they fail to parse or to verify.
+    async fn read_stream(
+        &self,
+        user_id: &str,
+    ) -> Result<(Vec<StoredMessage>, Vec<String>), QueueBackendError> {
+        let entries = self
+            .pool
+            .xrange_all(&self.queue_key(user_id))
+            .await
+            .map_err(Self::map_error)?;
+
+        let mut messages = Vec::new();
+        let mut unreadable = Vec::new();
+        for (stream_id, fields) in entries {
+            let message = fields
+                .iter()
+                .find(|(k, _)| k == "data")
+                .and_then(|(_, json)| serde_json::from_str::<StoredMessage>(json).ok());
+            match message {
+                Some(mut msg) if checksum_matches(&msg, user_id) => {
+                    msg.stream_id = Some(stream_id);
+                    messages.push(msg);
+                }
+                _ => unreadable.push(stream_id),
+            }
+        }
+        Ok((messages, unreadable))
+    }
+
     /// Convert pool error to queue backend error.
     fn map_error(err: PoolError) -> QueueBackendError {
         match err {
@@ -76,6 +127,7 @@ impl RedisQueueBackend {
                 QueueBackendError::Unavailable("Circuit breaker is open".to_string())
             }
             PoolError::ConnectionUnavailable(msg) => QueueBackendError::Unavailable(msg),
+            e @ PoolError::Timeout(_) => QueueBackendError::Unavailable(e.to_string()),
         }
     }
 }
@@ -95,22 +147,38 @@ impl MessageQueueBackend for RedisQueueBackend {
             return Err(QueueBackendError::Disabled);
         }
 
+        let notification_id = event.id.to_string();
         let message = StoredMessage::new(event);
         let key = self.queue_key(user_id);
+        let ids_key = self.queued_ids_key(user_id);
 
         // Serialize the message
         let msg_json = serde_json::to_string(&message)?;
 
-        // Add to stream with MAXLEN trimming
-        let fields = [
-            ("data", msg_json.as_str()),
-        ];
-
-        self.pool
-            .xadd_maxlen(&key, self.config.max_queue_size_per_user, &fields)
+        // Add to stream with MAXLEN trimming, unless the notification is already queued
+        let max_size = self.config.max_queue_size_per_user.to_string();
+        let ttl = self.config.message_ttl_seconds.to_string();
+        let now = Utc::now().timestamp().to_string();
+        let queued = self
+            .pool
+            .eval_script(
+                &ENQUEUE_SCRIPT,
+                &[&key, &ids_key],
+                &[&notification_id, &msg_json, &max_size, &ttl, &now],
+            )
             .await
             .map_err(Self::map_error)?;
 
+        if queued == redis::Value::Int(0) {
+            QUEUE_DEDUP_SKIPPED_TOTAL.inc();
+            tracing::debug!(
+                user_id = %user_id,
+                notification_id = %notification_id,
+                "Notification already queued, skipping duplicate"
+            );
+            return Ok(());
+        }
+
         tracing::debug!(
             user_id = %user_id,
             message_id = %message.id,
@@ -148,6 +216,9 @@ impl MessageQueueBackend for RedisQueueBackend {
                 match serde_json::from_str::<StoredMessage>(json) {
                     Ok(mut msg) => {
                         msg.stream_id = Some(stream_id);
+                        if !checksum_matches(&msg, user_id) {
+                            continue;
+                        }
                         if msg.is_expired(ttl) {
                             expired += 1;
                         } else {
@@ -165,9 +236,13 @@ impl MessageQueueBackend for RedisQueueBackend {
             }
         }
 
-        // Delete the stream after draining
+        // Delete the stream and its queued IDs after draining
         if !messages.is_empty() || expired > 0 {
             self.pool.del(&key).await.map_err(Self::map_error)?;
+            self.pool
+                .del(&self.queued_ids_key(user_id))
+                .await
+                .map_err(Self::map_error)?;
         }
 
         tracing::info!(
@@ -180,29 +255,53 @@ impl MessageQueueBackend for RedisQueueBackend {
         Ok(DrainResult { messages, expired })
     }
 
-    async fn peek(&self, user_id: &str, limit: usize) -> Result<Vec<StoredMessage>, QueueBackendError> {
+    async fn replay(
+        &self,
+        user_id: &str,
+        sender: &mpsc::Sender<OutboundMessage>,
+    ) -> Result<ReplayResult, QueueBackendError> {
         if !self.config.enabled {
-            return Ok(Vec::new());
+            return Ok(ReplayResult::empty());
         }
 
-        let key = self.queue_key(user_id);
-
-        // Read entries from the stream
-        let entries = self.pool.xrange_all(&key).await.map_err(Self::map_error)?;
+        let (messages, unreadable) = self.read_stream(user_id).await?;
 
-        let mut messages = Vec::new();
+        // Entries that can never be delivered would otherwise stay queued for good
+        if !unreadable.is_empty() {
+            if let Err(e) = self.pool.xdel(&self.queue_key(user_id), &unreadable).await {
+                tracing::warn!(
+                    user_id = %user_id,
+                    error = %Self::map_error(e),
+                    "Failed to remove unreadable queued messages"
+                );
+            }
+        }
 
-        for (stream_id, fields) in entries.into_iter().take(limit) {
-            let data = fields.iter().find(|(k, _)| k == "data").map(|(_, v)| v);
+        Ok(replay_in_place(self, user_id, messages, sender).await)
+    }
 
-            if let Some(json) = data {
-                if let Ok(mut msg) = serde_json::from_str::<StoredMessage>(json) {
-                    msg.stream_id = Some(stream_id);
-                    messages.push(msg);
-                }
+    async fn is_queued(&self, user_id: &str, notification_id: Uuid) -> bool {
+        let cutoff = Utc::now().timestamp() - self.config.message_ttl_seconds as i64;
+        let queued_at = self
+            .pool
+            .zscore(&self.queued_ids_key(user_id), &notification_id.to_string())
+            .await;
+        match queued_at {
+            Ok(queued_at) => queued_at.is_some_and(|at| at > cutoff as f64),
+            Err(e) => {
+                tracing::warn!(user_id = %user_id, error = %e, "Failed to check Redis queue");
+                false
             }
         }
+    }
 
+    async fn peek(&self, user_id: &str, limit: usize) -> Result<Vec<StoredMessage>, QueueBackendError> {
+        if !self.config.enabled {
+            return Ok(Vec::new());
+        }
+
+        let (mut messages, _) = self.read_stream(user_id).await?;
+        messages.truncate(limit);
         Ok(messages)
     }
 
@@ -225,18 +324,87 @@ impl MessageQueueBackend for RedisQueueBackend {
         Ok(0)
     }
 
+    async fn remove_message(
+        &self,
+        user_id: &str,
+        message: &StoredMessage,
+    ) -> Result<(), QueueBackendError> {
+        let Some(stream_id) = &message.stream_id else {
+            return self.remove_messages(user_id, &[message.id]).await.map(|_| ());
+        };
+
+        // Messages read from the stream carry their entry ID, so no scan is needed
+        self.pool
+            .xdel(&self.queue_key(user_id), std::slice::from_ref(stream_id))
+            .await
+            .map_err(Self::map_error)?;
+        self.pool
+            .zrem(&self.queued_ids_key(user_id), &message.event.id.to_string())
+            .await
+            .map_err(Self::map_error)?;
+        Ok(())
+    }
+
+    async fn remove_messages(
+        &self,
+        user_id: &str,
+        message_ids: &[Uuid],
+    ) -> Result<usize, QueueBackendError> {
+        let key = self.queue_key(user_id);
+        let entries = self.pool.xrange_all(&key).await.map_err(Self::map_error)?;
+
+        // Entries are deleted by stream ID, so messages added meanwhile are untouched
+        let (stream_ids, notification_ids): (Vec<String>, Vec<String>) = entries
+            .into_iter()
+            .filter_map(|(stream_id, fields)| {
+                fields
+                    .iter()
+                    .find(|(k, _)| k == "data")
+                    .and_then(|(_, json)| serde_json::from_str::<StoredMessage>(json).ok())
+                    .filter(|msg| message_ids.contains(&msg.id))
+                    .map(|msg| (stream_id, msg.event.id.to_string()))
+            })
+            .unzip();
+
+        let removed = self.pool.xdel(&key, &stream_ids).await.map_err(Self::map_error)?;
+        let ids_key = self.queued_ids_key(user_id);
+        for notification_id in &notification_ids {
+            self.pool.zrem(&ids_key, notification_id).await.map_err(Self::map_error)?;
+        }
+        Ok(removed)
+    }
+
     async fn clear_user_queue(&self, user_id: &str) -> Result<usize, QueueBackendError> {
         let key = self.queue_key(user_id);
 
         // Get count before deleting
         let count = self.queue_size(user_id).await?;
 
-        // Delete the stream
+        // Delete the stream and its queued IDs
         self.pool.del(&key).await.map_err(Self::map_error)?;
+        self.pool
+            .del(&self.queued_ids_key(user_id))
+            .await
+            .map_err(Self::map_error)?;
 
         Ok(count)
     }
 
+    async fn list_users(&self) -> Result<Vec<String>, QueueBackendError> {
+        let key_prefix = self.queue_key("");
+        let keys = self
+            .pool
+            .scan_keys(&format!("{}*", key_prefix))
+            .await
+            .map_err(Self::map_error)?;
+
+        Ok(keys
+            .into_iter()
+            .filter_map(|key| key.strip_prefix(&key_prefix).map(str::to_string))
+            .filter(|user_id| !user_id.is_empty())
+            .collect())
+    }
+
     async fn stats(&self) -> QueueBackendStats {
         // For Redis backend, getting accurate stats would require
         // scanning all keys, which is expensive. Return basic info.
@@ -263,6 +431,11 @@ mod tests {
         let backend = RedisQueueBackend::new(config, pool, "ara:queue".to_string());
 
         assert_eq!(backend.queue_key("user-123"), "ara:queue:default:user-123");
+        // Not matched by the `list_users` scan of `ara:queue:default:*`
+        assert_eq!(
+            backend.queued_ids_key("user-123"),
+            "ara:queue-ids:default:user-123"
+        );
     }
 
     #[test]
@@ -283,19 +456,21 @@ mod tests {
         // Create a mock pool for testing key generation
         // Actual Redis tests would use #[ignore] and require a real Redis
         use crate::config::RedisConfig;
-        use crate::redis::{CircuitBreaker, RedisHealth};
+        use crate::redis::{BulkheadCircuitBreaker, RedisHealth};
 
         let config = RedisConfig {
             url: "redis://localhost:6379".to_string(),
             channels: vec![],
+            subscribe_patterns: vec![],
             circuit_breaker_failure_threshold: 5,
             circuit_breaker_success_threshold: 2,
             circuit_breaker_reset_timeout_seconds: 30,
             backoff_initial_delay_ms: 100,
             backoff_max_delay_ms: 30000,
+            backoff_max_total_wait_ms: None,
         };
 
-        let cb = Arc::new(CircuitBreaker::new());
+        let cb = Arc::new(BulkheadCircuitBreaker::new());
         let health = Arc::new(RedisHealth::new());
 
         Arc::new(RedisPool::new(config, cb, health).unwrap())
@@ -2,15 +2,41 @@
 
 use std::collections::VecDeque;
 
-use dashmap::DashMap;
+use dashmap::{DashMap, DashSet};
 use tokio::sync::mpsc;
+use uuid::Uuid;
 
-use crate::metrics::{QUEUE_DROPPED_TOTAL, QUEUE_ENQUEUED_TOTAL, QUEUE_EXPIRED_TOTAL, QUEUE_REPLAYED_TOTAL};
+use crate::metrics::{
+    QUEUE_DEDUP_SKIPPED_TOTAL, QUEUE_DROPPED_TOTAL, QUEUE_ENQUEUED_TOTAL, QUEUE_EXPIRED_TOTAL,
+    QUEUE_REPLAYED_TOTAL,
+};
 use crate::websocket::{OutboundMessage, ServerMessage};
 
 use super::models::{QueueConfig, QueueError, QueueStats, QueuedMessage, ReplayResult};
 use crate::notification::NotificationEvent;
 
+/// A replay's claim on one queued message, released on drop unless the message was
+/// marked delivered.
+struct Claim<'a> {
+    queue: &'a UserMessageQueue,
+    user_id: &'a str,
+    message_id: Uuid,
+}
+
+impl Claim<'_> {
+    /// Mark the claimed message delivered, settling the claim
+    fn delivered(self) {
+        self.queue.mark_delivered(self.user_id, self.message_id);
+        std::mem::forget(self);
+    }
+}
+
+impl Drop for Claim<'_> {
+    fn drop(&mut self) {
+        self.queue.release(self.user_id, self.message_id);
+    }
+}
+
 /// Per-user message queue for offline delivery.
 ///
 /// When a user disconnects, messages are stored in their queue.
@@ -22,6 +48,7 @@ use crate::notification::NotificationEvent;
 /// - Each user has a `VecDeque` acting as a circular buffer
 /// - When queue is full, oldest messages are dropped (FIFO)
 /// - Expired messages are automatically cleaned up
+/// - A notification already waiting in a user's queue is not queued again
 ///
 /// # Example
 ///
@@ -37,6 +64,8 @@ use crate::notification::NotificationEvent;
 pub struct UserMessageQueue {
     /// Per-user message queues
     queues: DashMap<String, VecDeque<QueuedMessage>>,
+    /// Notification IDs waiting in each user's queue, for deduplication
+    queued_ids: DashMap<String, DashSet<Uuid>>,
     /// Configuration
     config: QueueConfig,
 }
@@ -46,6 +75,7 @@ impl UserMessageQueue {
     pub fn new(config: QueueConfig) -> Self {
         Self {
             queues: DashMap::new(),
+            queued_ids: DashMap::new(),
             config,
         }
     }
@@ -62,20 +92,38 @@ impl UserMessageQueue {
 
     /// Enqueue a message for a user.
     ///
-    /// If the queue is full, the oldest message is dropped to make room.
+    /// If the queue is full, the oldest message is dropped to make room. Enqueuing a
+    /// notification that is already queued for the user is a no-op.
     /// Returns an error if the queue is disabled.
     pub fn enqueue(&self, user_id: &str, event: NotificationEvent) -> Result<(), QueueError> {
         if !self.config.enabled {
             return Err(QueueError::Disabled);
         }
 
-        let message = QueuedMessage::new(event);
-
+        let notification_id = event.id;
         let mut queue = self.queues.entry(user_id.to_string()).or_default();
 
+        if !self
+            .queued_ids
+            .entry(user_id.to_string())
+            .or_default()
+            .insert(notification_id)
+        {
+            QUEUE_DEDUP_SKIPPED_TOTAL.inc();
+            tracing::debug!(
+                user_id = %user_id,
+                notification_id = %notification_id,
+                "Notification already queued, skipping"
+            );
+            return Ok(());
+        }
+
+        let message = QueuedMessage::new(event);
+
         // If queue is full, remove oldest message
         if queue.len() >= self.config.max_queue_size_per_user {
             if let Some(dropped) = queue.pop_front() {
+                self.forget_queued(user_id, dropped.event.id);
                 QUEUE_DROPPED_TOTAL.inc();
                 tracing::debug!(
                     user_id = %user_id,
@@ -101,41 +149,111 @@ impl UserMessageQueue {
     /// Replay all queued messages to a user's connection.
     ///
     /// Messages are sent in order (oldest first) and removed from the queue
-    /// upon successful delivery. Expired messages are discarded.
+    /// once delivered. Expired messages are discarded. If a send fails, the
+    /// undelivered messages stay queued for the next replay.
     pub async fn replay(
         &self,
         user_id: &str,
         sender: &mpsc::Sender<OutboundMessage>,
     ) -> ReplayResult {
-        if !self.config.enabled {
-            return ReplayResult::empty();
+        self.replay_partial(user_id, sender, usize::MAX).await
+    }
+
+    /// Replay up to `batch_size` queued messages to a user's connection.
+    ///
+    /// Messages stay in the queue while they are replayed and are marked delivered
+    /// one at a time as they are sent. Each message is claimed before its send is
+    /// awaited, so a concurrent replay for the same user skips it. A send error
+    /// stops the replay and leaves the remaining messages undelivered, so the next
+    /// call resumes from the first undelivered message. Delivered and expired
+    /// messages are removed before returning.
+    pub async fn replay_partial(
+        &self,
+        user_id: &str,
+        sender: &mpsc::Sender<OutboundMessage>,
+        batch_size: usize,
+    ) -> ReplayResult {
+        let mut result = ReplayResult::empty();
+        if !self.config.enabled || !self.queues.contains_key(user_id) {
+            return result;
         }
 
-        // Take ownership of the queue for this user
-        let messages = match self.queues.remove(user_id) {
-            Some((_, queue)) => queue,
-            None => return ReplayResult::empty(),
-        };
+        tracing::info!(
+            user_id = %user_id,
+            message_count = self.queue_size(user_id),
+            batch_size = batch_size,
+            "Starting message replay for reconnected user"
+        );
+
+        while result.replayed < batch_size {
+            let Some(message) = self.claim_next_undelivered(user_id, &mut result.expired) else {
+                break;
+            };
+            // Releases the claim if the send fails or this replay is dropped mid-send
+            let claim = Claim {
+                queue: self,
+                user_id,
+                message_id: message.id,
+            };
+
+            let notification_id = message.event.id;
+            let server_msg = ServerMessage::Notification {
+                event: message.event,
+            };
+
+            if sender.send(OutboundMessage::Raw(server_msg)).await.is_err() {
+                drop(claim);
+                result.failed += 1;
+                tracing::warn!(
+                    user_id = %user_id,
+                    message_id = %message.id,
+                    "Failed to replay message, connection may be closed"
+                );
+                // If sending fails, stop replaying (connection is dead)
+                break;
+            }
 
-        if messages.is_empty() {
-            return ReplayResult::empty();
+            claim.delivered();
+            self.forget_queued(user_id, notification_id);
+            result.replayed += 1;
+            QUEUE_REPLAYED_TOTAL.inc();
         }
 
-        let total = messages.len();
-        let mut replayed = 0;
-        let mut failed = 0;
-        let mut expired = 0;
+        result.remaining = self.remove_delivered(user_id);
 
         tracing::info!(
             user_id = %user_id,
-            message_count = total,
-            "Starting message replay for reconnected user"
+            replayed = result.replayed,
+            failed = result.failed,
+            expired = result.expired,
+            remaining = result.remaining,
+            "Message replay completed"
         );
 
-        for message in messages {
-            // Skip expired messages
-            if message.is_expired(self.config.message_ttl_seconds) {
-                expired += 1;
+        result
+    }
+
+    /// Claim the oldest undelivered message that no other replay is sending and
+    /// return a copy of it, discarding expired ones on the way. The claim is taken
+    /// under the queue lock, before the send is awaited.
+    fn claim_next_undelivered(
+        &self,
+        user_id: &str,
+        expired: &mut usize,
+    ) -> Option<QueuedMessage> {
+        let mut queue = self.queues.get_mut(user_id)?;
+        loop {
+            let index = queue
+                .iter()
+                .position(|message| !message.delivered && !message.in_flight)?;
+            if !queue[index].is_expired(self.config.message_ttl_seconds) {
+                queue[index].in_flight = true;
+                return Some(queue[index].clone());
+            }
+
+            if let Some(message) = queue.remove(index) {
+                self.forget_queued(user_id, message.event.id);
+                *expired += 1;
                 QUEUE_EXPIRED_TOTAL.inc();
                 tracing::debug!(
                     user_id = %user_id,
@@ -143,45 +261,59 @@ impl UserMessageQueue {
                     queued_at = %message.queued_at,
                     "Discarding expired message"
                 );
-                continue;
             }
+        }
+    }
 
-            // Send the message
-            let server_msg = ServerMessage::Notification {
-                event: message.event,
-            };
+    /// Mark a message delivered. Looked up by ID since messages may have been
+    /// enqueued or dropped while it was being sent.
+    fn mark_delivered(&self, user_id: &str, message_id: uuid::Uuid) {
+        if let Some(mut queue) = self.queues.get_mut(user_id) {
+            if let Some(message) = queue.iter_mut().find(|message| message.id == message_id) {
+                message.delivered = true;
+                message.in_flight = false;
+            }
+        }
+    }
 
-            match sender.send(OutboundMessage::Raw(server_msg)).await {
-                Ok(_) => {
-                    replayed += 1;
-                    QUEUE_REPLAYED_TOTAL.inc();
-                }
-                Err(_) => {
-                    failed += 1;
-                    tracing::warn!(
-                        user_id = %user_id,
-                        message_id = %message.id,
-                        "Failed to replay message, connection may be closed"
-                    );
-                    // If sending fails, stop replaying (connection is dead)
-                    break;
-                }
+    /// Release the claim on a message that was not sent, so a later replay sends it
+    fn release(&self, user_id: &str, message_id: uuid::Uuid) {
+        if let Some(mut queue) = self.queues.get_mut(user_id) {
+            if let Some(message) = queue.iter_mut().find(|message| message.id == message_id) {
+                message.in_flight = false;
             }
         }
+    }
 
-        tracing::info!(
-            user_id = %user_id,
-            replayed = replayed,
-            failed = failed,
-            expired = expired,
-            "Message replay completed"
-        );
+    /// Remove delivered messages, dropping the queue once empty.
+    /// Returns the number of messages left.
+    fn remove_delivered(&self, user_id: &str) -> usize {
+        let remaining = match self.queues.get_mut(user_id) {
+            Some(mut queue) => {
+                queue.retain(|message| !message.delivered);
+                queue.len()
+            }
+            None => return 0,
+        };
+        if remaining == 0 {
+            self.queues.remove_if(user_id, |_, queue| queue.is_empty());
+        }
+        remaining
+    }
 
-        ReplayResult {
-            replayed,
-            failed,
-            expired,
+    /// Stop treating `notification_id` as queued for the user
+    fn forget_queued(&self, user_id: &str, notification_id: Uuid) {
+        if let Some(ids) = self.queued_ids.get(user_id) {
+            ids.remove(&notification_id);
         }
+        self.queued_ids.remove_if(user_id, |_, ids| ids.is_empty());
+    }
+
+    /// Whether `notification_id` is waiting in the user's queue
+    pub fn is_queued(&self, user_id: &str, notification_id: Uuid) -> bool {
+        self.queued_ids
+            .get(user_id)
+            .is_some_and(|ids| ids.contains(&notification_id))
     }
 
     /// Get the number of queued messages for a user
@@ -212,7 +344,13 @@ impl UserMessageQueue {
         for user_id in user_ids {
             if let Some(mut queue) = self.queues.get_mut(&user_id) {
                 let before = queue.len();
-                queue.retain(|msg| !msg.is_expired(ttl));
+                queue.retain(|msg| {
+                    let expired = msg.is_expired(ttl);
+                    if expired {
+                        self.forget_queued(&user_id, msg.event.id);
+                    }
+                    !expired
+                });
                 let after = queue.len();
                 let expired = before - after;
                 removed += expired;
@@ -246,10 +384,12 @@ impl UserMessageQueue {
     /// This is called when all of a user's connections successfully receive
     /// the replayed messages.
     pub fn clear_user_queue(&self, user_id: &str) -> usize {
-        match self.queues.remove(user_id) {
+        let cleared = match self.queues.remove(user_id) {
             Some((_, queue)) => queue.len(),
             None => 0,
-        }
+        };
+        self.queued_ids.remove(user_id);
+        cleared
     }
 
     /// Get queue statistics
@@ -278,6 +418,8 @@ impl UserMessageQueue {
 
 #[cfg(test)]
 mod tests {
+    use std::sync::Arc;
+
     use super::*;
     use crate::notification::NotificationEvent;
     use serde_json::json;
@@ -339,6 +481,53 @@ mod tests {
         assert_eq!(queue.queue_size("user-1"), 3);
     }
 
+    #[test]
+    fn test_enqueue_skips_duplicate_notification() {
+        let config = QueueConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        let queue = UserMessageQueue::new(config);
+        let event = create_test_event();
+        let skipped = QUEUE_DEDUP_SKIPPED_TOTAL.get();
+
+        queue.enqueue("user-1", event.clone()).unwrap();
+        queue.enqueue("user-1", event.clone()).unwrap();
+        assert_eq!(queue.queue_size("user-1"), 1);
+        assert!(queue.is_queued("user-1", event.id));
+        assert!(QUEUE_DEDUP_SKIPPED_TOTAL.get() > skipped);
+
+        // Deduplication is per user
+        queue.enqueue("user-2", event).unwrap();
+        assert_eq!(queue.queue_size("user-2"), 1);
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_notification_delivered_once() {
+        let config = QueueConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        let queue = UserMessageQueue::new(config);
+        let event = create_test_event();
+        let (tx, mut rx) = mpsc::channel(10);
+
+        queue.enqueue("user-1", event.clone()).unwrap();
+        queue.enqueue("user-1", event.clone()).unwrap();
+        let result = queue.replay("user-1", &tx).await;
+        assert_eq!(result.replayed, 1);
+        assert!(matches!(
+            rx.try_recv(),
+            Ok(OutboundMessage::Raw(ServerMessage::Notification { event: ref e })) if e.id == event.id
+        ));
+        assert!(rx.try_recv().is_err());
+
+        // Once replayed, the notification is no longer queued and may be queued again
+        assert!(!queue.is_queued("user-1", event.id));
+        queue.enqueue("user-1", event).unwrap();
+        assert_eq!(queue.queue_size("user-1"), 1);
+    }
+
     #[test]
     fn test_cleanup_expired() {
         let config = QueueConfig {
@@ -360,6 +549,7 @@ mod tests {
         assert_eq!(removed, 5);
         assert_eq!(queue.queue_size("user-1"), 0);
         assert_eq!(queue.users_with_queue(), 0);
+        assert!(queue.queued_ids.is_empty());
     }
 
     #[test]
@@ -436,6 +626,141 @@ mod tests {
         assert_eq!(received, 3);
     }
 
+    #[tokio::test]
+    async fn test_replay_partial_resumes_after_interruption() {
+        let config = QueueConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        let queue = UserMessageQueue::new(config);
+
+        let mut event_ids = Vec::new();
+        for _ in 0..10 {
+            let event = create_test_event();
+            event_ids.push(event.id);
+            queue.enqueue("user-1", event).unwrap();
+        }
+        let received_ids = |rx: &mut mpsc::Receiver<OutboundMessage>| {
+            let mut ids = Vec::new();
+            while let Ok(OutboundMessage::Raw(ServerMessage::Notification { event })) = rx.try_recv() {
+                ids.push(event.id);
+            }
+            ids
+        };
+
+        // First batch is delivered and removed
+        let (tx, mut rx) = mpsc::channel(20);
+        let result = queue.replay_partial("user-1", &tx, 4).await;
+        assert_eq!(result.replayed, 4);
+        assert_eq!(result.remaining, 6);
+        assert_eq!(received_ids(&mut rx), event_ids[..4]);
+
+        // The connection drops: nothing is lost
+        drop(rx);
+        let result = queue.replay_partial("user-1", &tx, 4).await;
+        assert_eq!(result.replayed, 0);
+        assert_eq!(result.failed, 1);
+        assert_eq!(result.remaining, 6);
+        assert_eq!(queue.queue_size("user-1"), 6);
+
+        // A reconnect resumes from the first undelivered message
+        let (tx, mut rx) = mpsc::channel(20);
+        let result = queue.replay("user-1", &tx).await;
+        assert_eq!(result.replayed, 6);
+        assert_eq!(result.remaining, 0);
+        assert_eq!(received_ids(&mut rx), event_ids[4..]);
+        assert_eq!(queue.users_with_queue(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_replays_skip_in_flight_messages() {
+        let config = QueueConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        let queue = Arc::new(UserMessageQueue::new(config));
+
+        let mut event_ids = Vec::new();
+        for _ in 0..4 {
+            let event = create_test_event();
+            event_ids.push(event.id);
+            queue.enqueue("user-1", event).unwrap();
+        }
+        let received_ids = |rx: &mut mpsc::Receiver<OutboundMessage>| {
+            let mut ids = Vec::new();
+            while let Ok(OutboundMessage::Raw(ServerMessage::Notification { event })) = rx.try_recv() {
+                ids.push(event.id);
+            }
+            ids
+        };
+
+        // A slow connection takes the first message and blocks sending the second
+        let (slow_tx, mut slow_rx) = mpsc::channel(1);
+        let slow = tokio::spawn({
+            let queue = queue.clone();
+            async move { queue.replay("user-1", &slow_tx).await }
+        });
+        while slow_rx.is_empty() {
+            tokio::task::yield_now().await;
+        }
+
+        // A second connection skips the message in flight
+        let (tx, mut rx) = mpsc::channel(20);
+        let result = queue.replay("user-1", &tx).await;
+        assert_eq!(result.replayed, 2);
+        assert_eq!(received_ids(&mut rx), event_ids[2..]);
+
+        // The slow connection drops: its in-flight message stays queued
+        slow_rx.close();
+        let result = slow.await.unwrap();
+        assert_eq!(result.replayed, 1);
+        assert_eq!(result.failed, 1);
+        assert_eq!(received_ids(&mut slow_rx), event_ids[..1]);
+
+        let result = queue.replay("user-1", &tx).await;
+        assert_eq!(result.replayed, 1);
+        assert_eq!(received_ids(&mut rx), event_ids[1..2]);
+        assert_eq!(queue.users_with_queue(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_replay_releases_claim() {
+        let config = QueueConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        let queue = Arc::new(UserMessageQueue::new(config));
+        let mut event_ids = Vec::new();
+        for _ in 0..2 {
+            let event = create_test_event();
+            event_ids.push(event.id);
+            queue.enqueue("user-1", event).unwrap();
+        }
+
+        // The replay is dropped while blocked sending the second message
+        let (slow_tx, slow_rx) = mpsc::channel(1);
+        let slow = tokio::spawn({
+            let queue = queue.clone();
+            async move { queue.replay("user-1", &slow_tx).await }
+        });
+        while slow_rx.is_empty() {
+            tokio::task::yield_now().await;
+        }
+        tokio::task::yield_now().await;
+        slow.abort();
+        assert!(slow.await.unwrap_err().is_cancelled());
+
+        // The claim is released, so the next replay sends the second message
+        let (tx, mut rx) = mpsc::channel(8);
+        let result = queue.replay("user-1", &tx).await;
+        assert_eq!(result.replayed, 1);
+        let Ok(OutboundMessage::Raw(ServerMessage::Notification { event })) = rx.try_recv() else {
+            panic!("expected a replayed notification");
+        };
+        assert_eq!(event.id, event_ids[1]);
+        assert_eq!(queue.users_with_queue(), 0);
+    }
+
     #[test]
     fn test_clear_user_queue() {
         let config = QueueConfig {
@@ -1,6 +1,7 @@
 //! Rate limiting configuration
 
-use serde::Deserialize;
+use ipnetwork::IpNetwork;
+use serde::{Deserialize, Deserializer};
 
 /// Configuration for rate limiting
 #[derive(Debug, Clone, Deserialize)]
@@ -32,6 +33,111 @@ pub struct RateLimitConfig {
     /// Redis key prefix for rate limit data
     #[serde(default = "default_redis_prefix")]
     pub redis_prefix: String,
+    /// CIDR ranges that bypass rate limiting
+    #[serde(default)]
+    pub ip_allowlist: Vec<IpNetwork>,
+    /// CIDR ranges that are always denied (checked before the allowlist)
+    #[serde(default)]
+    pub ip_blocklist: Vec<IpNetwork>,
+    /// Share of denied requests and of denied clients above which enforcement is bypassed
+    /// as misconfigured (0, the default, disables the bypass)
+    #[serde(default = "default_emergency_bypass_deny_ratio")]
+    pub emergency_bypass_deny_ratio: f64,
+    /// Window over which the deny ratio is measured, and the longest a bypass lasts (seconds)
+    #[serde(default = "default_emergency_bypass_window")]
+    pub emergency_bypass_window_seconds: u64,
+    /// Minimum decisions in the window before the deny ratio is trusted
+    #[serde(default = "default_emergency_bypass_min_requests")]
+    pub emergency_bypass_min_requests: u64,
+    /// Path-specific HTTP limits; the first rule matching the request path wins
+    #[serde(default, deserialize_with = "deserialize_rules")]
+    pub rules: Vec<RateLimitRule>,
+}
+
+/// HTTP rate limit for requests whose path matches a glob pattern
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct RateLimitRule {
+    /// Glob matched against the full request path (`*` matches any run of characters,
+    /// including `/`; `?` matches one character)
+    pub path_pattern: String,
+    /// Sustained requests per second allowed on matching paths
+    pub requests_per_second: u32,
+    /// Burst capacity on matching paths
+    pub burst_size: u32,
+}
+
+impl RateLimitRule {
+    /// Whether `path` matches this rule's pattern
+    pub fn matches(&self, path: &str) -> bool {
+        glob_match(self.path_pattern.as_bytes(), path.as_bytes())
+    }
+}
+
+/// Match `text` against a glob supporting `*` and `?`
+pub(crate) fn glob_match(pattern: &[u8], text: &[u8]) -> bool {
+    // Backtrack to the most recent `*` on mismatch
+    let (mut p, mut t) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some(b'*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == b'?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                Some((star_p, star_t)) => {
+                    p = star_p + 1;
+                    t = star_t + 1;
+                    star = Some((star_p, star_t + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == b'*')
+}
+
+/// Deserialize rules from a list or a `pattern=rps:burst,...` string (env vars)
+pub fn deserialize_rules<'de, D>(deserializer: D) -> Result<Vec<RateLimitRule>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Rules {
+        List(Vec<RateLimitRule>),
+        Text(String),
+    }
+
+    match Option::<Rules>::deserialize(deserializer)? {
+        None => Ok(Vec::new()),
+        Some(Rules::List(rules)) => Ok(rules),
+        Some(Rules::Text(text)) => text
+            .split(',')
+            .map(str::trim)
+            .filter(|rule| !rule.is_empty())
+            .map(|rule| {
+                let parsed = rule.rsplit_once('=').and_then(|(pattern, limits)| {
+                    let (rps, burst) = limits.split_once(':')?;
+                    Some(RateLimitRule {
+                        path_pattern: pattern.trim().to_string(),
+                        requests_per_second: rps.trim().parse().ok()?,
+                        burst_size: burst.trim().parse().ok()?,
+                    })
+                });
+                parsed.ok_or_else(|| {
+                    serde::de::Error::custom(format!(
+                        "invalid rate limit rule '{}', expected pattern=rps:burst",
+                        rule
+                    ))
+                })
+            })
+            .collect(),
+    }
 }
 
 fn default_backend() -> String {
@@ -66,6 +172,18 @@ fn default_bucket_ttl() -> u64 {
     300 // Remove buckets unused for 5 minutes
 }
 
+fn default_emergency_bypass_deny_ratio() -> f64 {
+    0.0 // Opt-in: never bypass unless configured
+}
+
+fn default_emergency_bypass_window() -> u64 {
+    300 // Measure over 5 minutes
+}
+
+fn default_emergency_bypass_min_requests() -> u64 {
+    100
+}
+
 impl Default for RateLimitConfig {
     fn default() -> Self {
         Self {
@@ -78,6 +196,53 @@ impl Default for RateLimitConfig {
             bucket_ttl_seconds: default_bucket_ttl(),
             backend: default_backend(),
             redis_prefix: default_redis_prefix(),
+            ip_allowlist: Vec::new(),
+            ip_blocklist: Vec::new(),
+            emergency_bypass_deny_ratio: default_emergency_bypass_deny_ratio(),
+            emergency_bypass_window_seconds: default_emergency_bypass_window(),
+            emergency_bypass_min_requests: default_emergency_bypass_min_requests(),
+            rules: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(pattern: &str) -> RateLimitRule {
+        RateLimitRule {
+            path_pattern: pattern.to_string(),
+            requests_per_second: 10,
+            burst_size: 10,
         }
     }
+
+    #[test]
+    fn test_rule_glob_matching() {
+        assert!(rule("/api/v1/notifications/batch").matches("/api/v1/notifications/batch"));
+        assert!(!rule("/api/v1/notifications/batch").matches("/api/v1/notifications/batch-stream"));
+        assert!(rule("/api/v1/notifications/batch*").matches("/api/v1/notifications/batch-stream"));
+        assert!(rule("*/templates/*").matches("/api/v1/templates/welcome/preview"));
+        assert!(rule("/api/v?/stats").matches("/api/v1/stats"));
+        assert!(!rule("/api/v?/stats").matches("/api/v10/stats"));
+        assert!(!rule("/admin/*").matches("/api/v1/admin/x"));
+    }
+
+    #[test]
+    fn test_rules_from_string() {
+        let config: RateLimitConfig = serde_json::from_value(serde_json::json!({
+            "rules": "/api/v1/notifications/batch=10:20, /api/v1/templates*=50:100"
+        }))
+        .unwrap();
+        assert_eq!(config.rules.len(), 2);
+        assert_eq!(config.rules[0].path_pattern, "/api/v1/notifications/batch");
+        assert_eq!(config.rules[0].requests_per_second, 10);
+        assert_eq!(config.rules[1].burst_size, 100);
+
+        let invalid = serde_json::from_value::<RateLimitConfig>(serde_json::json!({
+            "rules": "/api/v1/notifications/batch=10"
+        }));
+        assert!(invalid.is_err());
+    }
 }
@@ -6,6 +6,7 @@ use async_trait::async_trait;
 use redis::AsyncCommands;
 
 use crate::redis::pool::RedisPool;
+use crate::redis::OperationType;
 
 use super::config::RateLimitConfig;
 use super::limiter::{RateLimitResult, RateLimiter};
@@ -142,10 +143,6 @@ impl DistributedRateLimiter for RedisRateLimiterBackend {
             return Ok((true, limit, 0));
         }
 
-        let mut conn = self.pool.get_connection().await.map_err(|e| {
-            RateLimitError::BackendError(format!("Failed to get connection: {}", e))
-        })?;
-
         let key = self.rate_limit_key(identifier, window_seconds);
 
         // Use a Lua script for atomic increment + check
@@ -160,10 +157,12 @@ impl DistributedRateLimiter for RedisRateLimiterBackend {
             "#,
         );
 
-        let count: u32 = script
-            .key(&key)
-            .arg(window_seconds)
-            .invoke_async(&mut conn)
+        let mut invocation = script.prepare_invoke();
+        invocation.key(&key).arg(window_seconds);
+
+        let count: u32 = self
+            .pool
+            .execute(OperationType::Write, |mut conn| async move { invocation.invoke_async(&mut conn).await })
             .await
             .map_err(|e| RateLimitError::BackendError(e.to_string()))?;
 
@@ -191,13 +190,10 @@ impl DistributedRateLimiter for RedisRateLimiterBackend {
             return Ok(0);
         }
 
-        let mut conn = self.pool.get_connection().await.map_err(|e| {
-            RateLimitError::BackendError(format!("Failed to get connection: {}", e))
-        })?;
-
         let key = self.rate_limit_key(identifier, window_seconds);
-        let count: Option<u32> = conn
-            .get(&key)
+        let count: Option<u32> = self
+            .pool
+            .execute(OperationType::Read, |mut conn| async move { conn.get(&key).await })
             .await
             .map_err(|e| RateLimitError::BackendError(e.to_string()))?;
 
@@ -1,13 +1,84 @@
 //! Local rate limiter implementation
 
+use std::collections::{HashMap, VecDeque};
 use std::net::IpAddr;
-use std::time::Instant;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
 use dashmap::DashMap;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
 use super::config::RateLimitConfig;
-use super::token_bucket::TokenBucket;
+use super::token_bucket::{TokenBucket, TokenBucketSnapshot};
+use crate::metrics::{
+    RateLimitMetrics, RATELIMIT_ALLOWED_TOTAL, RATELIMIT_BLOCKLISTED_TOTAL,
+    RATELIMIT_DENIED_TOTAL,
+};
+
+/// File name used to carry rate limiter state across restarts
+const STATE_FILE_NAME: &str = "ara-ratelimit-state.json";
+
+/// Directory under the system temp directory holding the state file by default
+const DEFAULT_STATE_DIR_NAME: &str = "ara-ratelimit";
+
+/// Self-calibration runs at most this often
+const CALIBRATION_MIN_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Distinct clients tracked per calibration interval; further clients are not counted
+const MAX_CALIBRATION_CLIENTS: usize = 100_000;
+
+/// Default location of the persisted rate limiter state (in a private directory under
+/// the system temp directory)
+pub fn default_state_path() -> PathBuf {
+    state_path_in(&std::env::temp_dir().join(DEFAULT_STATE_DIR_NAME))
+}
+
+/// Location of the persisted rate limiter state in `dir`
+pub fn state_path_in(dir: &Path) -> PathBuf {
+    dir.join(STATE_FILE_NAME)
+}
+
+/// Key bucket identifiers are API keys or IPs; buckets are keyed by their SHA-256 so
+/// credentials never end up in the persisted state
+fn hashed_bucket_key(key: &str) -> String {
+    Sha256::digest(key.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Directory the state file lives in
+fn state_dir(path: &Path) -> &Path {
+    path.parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(Path::new("."))
+}
+
+/// Check that `dir` is a real directory only its owner can access, so nobody else
+/// can read the state or plant a symlink in place of the state file
+fn ensure_private_dir(dir: &Path) -> std::io::Result<()> {
+    let metadata = std::fs::symlink_metadata(dir)?;
+    if !metadata.is_dir() {
+        return Err(std::io::Error::other(format!(
+            "rate limiter state directory {} is not a directory",
+            dir.display()
+        )));
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if metadata.permissions().mode() & 0o077 != 0 {
+            return Err(std::io::Error::other(format!(
+                "rate limiter state directory {} must only be accessible to its owner",
+                dir.display()
+            )));
+        }
+    }
+    Ok(())
+}
 
 /// Result of a rate limit check
 #[derive(Debug, Clone)]
@@ -46,6 +117,79 @@ impl BucketEntry {
             created_at: Instant::now(),
         }
     }
+
+    fn from_snapshot(snapshot: &TokenBucketSnapshot) -> Self {
+        Self {
+            bucket: TokenBucket::from_snapshot(snapshot),
+            created_at: Instant::now(),
+        }
+    }
+}
+
+/// Cumulative allow/deny decisions observed at one point in time
+#[derive(Debug, Clone, Copy)]
+struct DecisionSample {
+    at: Instant,
+    allowed: u64,
+    denied: u64,
+}
+
+impl DecisionSample {
+    /// Read the process-wide rate limit counters. Blocklist rejections are
+    /// deliberate, so they do not count towards the deny rate.
+    fn from_metrics(at: Instant) -> Self {
+        let sum = |counter: &prometheus::IntCounterVec| {
+            ["http", "ws"]
+                .iter()
+                .map(|target| counter.with_label_values(&[target]).get())
+                .sum::<u64>()
+        };
+        Self {
+            at,
+            allowed: sum(&RATELIMIT_ALLOWED_TOTAL),
+            denied: sum(&RATELIMIT_DENIED_TOTAL)
+                .saturating_sub(RATELIMIT_BLOCKLISTED_TOTAL.get()),
+        }
+    }
+}
+
+/// Distinct clients (API keys or IPs) seen during one calibration interval
+#[derive(Debug, Clone, Copy, Default)]
+struct ClientCounts {
+    clients: usize,
+    denied: usize,
+}
+
+/// Samples kept by [`RateLimiter::self_calibrate`]
+#[derive(Default)]
+struct Calibration {
+    last_run: Option<Instant>,
+    samples: VecDeque<DecisionSample>,
+    /// When the current emergency bypass was activated
+    bypass_since: Option<Instant>,
+}
+
+/// Serializable snapshot of all local rate limiter buckets
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RateLimiterState {
+    /// When the snapshot was taken (Unix milliseconds)
+    pub exported_at: i64,
+    /// IP-based buckets
+    pub ip_buckets: HashMap<IpAddr, TokenBucketSnapshot>,
+    /// API key / identifier buckets, keyed by the SHA-256 of the identifier
+    pub key_buckets: HashMap<String, TokenBucketSnapshot>,
+}
+
+impl RateLimiterState {
+    /// Total number of buckets in the snapshot
+    pub fn len(&self) -> usize {
+        self.ip_buckets.len() + self.key_buckets.len()
+    }
+
+    /// Whether the snapshot contains no buckets
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
 }
 
 /// Main rate limiter that manages multiple token buckets.
@@ -57,10 +201,16 @@ impl BucketEntry {
 pub struct RateLimiter {
     /// IP-based buckets for connection limiting
     ip_buckets: DashMap<IpAddr, BucketEntry>,
-    /// API key / user based buckets for HTTP requests
+    /// API key / user based buckets for HTTP requests, keyed by the hashed identifier
     key_buckets: DashMap<String, BucketEntry>,
     /// Configuration
     config: RateLimitConfig,
+    /// Set when the deny rate suggests a misconfiguration; every check is allowed
+    emergency_bypass: AtomicBool,
+    /// Deny rate history used by self-calibration
+    calibration: Mutex<Calibration>,
+    /// Clients seen since the last calibration run, and whether each was denied
+    calibration_clients: DashMap<String, bool>,
 }
 
 impl RateLimiter {
@@ -70,6 +220,9 @@ impl RateLimiter {
             ip_buckets: DashMap::new(),
             key_buckets: DashMap::new(),
             config,
+            emergency_bypass: AtomicBool::new(false),
+            calibration: Mutex::new(Calibration::default()),
+            calibration_clients: DashMap::new(),
         }
     }
 
@@ -95,6 +248,13 @@ impl RateLimiter {
         }
 
         let limit = self.config.ws_connections_per_minute;
+        if let Some(result) = self.check_ip_lists(ip, limit) {
+            return result;
+        }
+        if self.is_emergency_bypass_active() {
+            return Self::bypassed(limit);
+        }
+
         // Refill rate: connections per minute -> tokens per second
         let refill_rate = (limit as f64 / 60.0).ceil() as u32;
 
@@ -106,7 +266,9 @@ impl RateLimiter {
         let bucket = &entry.bucket;
         let reset_at = bucket.last_activity() + 60_000; // Reset after 1 minute
 
-        if bucket.try_consume() {
+        let allowed = bucket.try_consume();
+        self.note_client(|| ip.to_string(), !allowed);
+        if allowed {
             RateLimitResult::Allowed {
                 remaining: bucket.available(),
                 limit,
@@ -132,28 +294,46 @@ impl RateLimiter {
             };
         }
 
-        let limit = self.config.http_burst_size;
-        let refill_rate = self.config.http_requests_per_second;
+        self.check_key_bucket(
+            key.to_string(),
+            self.config.http_burst_size,
+            self.config.http_requests_per_second,
+        )
+    }
+
+    /// Take a token from the key bucket `bucket_key`, creating it with the given limits
+    fn check_key_bucket(
+        &self,
+        bucket_key: String,
+        burst_size: u32,
+        requests_per_second: u32,
+    ) -> RateLimitResult {
+        if self.is_emergency_bypass_active() {
+            return Self::bypassed(requests_per_second);
+        }
 
+        let bucket_key = hashed_bucket_key(&bucket_key);
         let entry = self
             .key_buckets
-            .entry(key.to_string())
-            .or_insert_with(|| BucketEntry::new(limit, refill_rate));
+            .entry(bucket_key.clone())
+            .or_insert_with(|| BucketEntry::new(burst_size, requests_per_second));
 
         let bucket = &entry.bucket;
         let reset_at = bucket.last_activity() + 1_000; // Reset after 1 second
 
-        if bucket.try_consume() {
+        let allowed = bucket.try_consume();
+        self.note_client(|| bucket_key, !allowed);
+        if allowed {
             RateLimitResult::Allowed {
                 remaining: bucket.available(),
-                limit: self.config.http_requests_per_second,
+                limit: requests_per_second,
                 reset_at,
             }
         } else {
             let retry_after = bucket.retry_after();
             RateLimitResult::Denied {
                 retry_after,
-                limit: self.config.http_requests_per_second,
+                limit: requests_per_second,
                 reset_at,
             }
         }
@@ -161,12 +341,225 @@ impl RateLimiter {
 
     /// Check rate limit for HTTP request using IP if no API key provided
     pub fn check_http(&self, key: Option<&str>, ip: IpAddr) -> RateLimitResult {
+        if self.config.enabled {
+            if let Some(result) = self.check_ip_lists(ip, self.config.http_requests_per_second) {
+                return result;
+            }
+        }
+
         match key {
             Some(k) => self.check_key(k),
             None => self.check_key(&ip.to_string()),
         }
     }
 
+    /// Check rate limit for an HTTP request to `path`. The first rule matching the path
+    /// applies its own limits in a bucket separate from the global one; without a
+    /// matching rule this is [`check_http`](Self::check_http).
+    pub fn check_http_path(&self, key: Option<&str>, ip: IpAddr, path: &str) -> RateLimitResult {
+        let rule = match self.config.rules.iter().find(|rule| rule.matches(path)) {
+            Some(rule) if self.config.enabled => rule,
+            _ => return self.check_http(key, ip),
+        };
+        if let Some(result) = self.check_ip_lists(ip, rule.requests_per_second) {
+            return result;
+        }
+
+        let identifier = match key {
+            Some(k) => k.to_string(),
+            None => ip.to_string(),
+        };
+        self.check_key_bucket(
+            format!("rule:{}:{}", rule.path_pattern, identifier),
+            rule.burst_size,
+            rule.requests_per_second,
+        )
+    }
+
+    /// Apply the IP blocklist and allowlist (blocklist wins).
+    /// Returns `None` if the IP is on neither list.
+    fn check_ip_lists(&self, ip: IpAddr, limit: u32) -> Option<RateLimitResult> {
+        if self.config.ip_blocklist.iter().any(|net| net.contains(ip)) {
+            RateLimitMetrics::record_blocklisted();
+            return Some(RateLimitResult::Denied {
+                retry_after: u64::MAX,
+                limit,
+                reset_at: 0,
+            });
+        }
+
+        if self.config.ip_allowlist.iter().any(|net| net.contains(ip)) {
+            RateLimitMetrics::record_allowlisted();
+            return Some(RateLimitResult::Allowed {
+                remaining: u32::MAX,
+                limit,
+                reset_at: 0,
+            });
+        }
+
+        None
+    }
+
+    /// Result returned while the emergency bypass is active
+    fn bypassed(limit: u32) -> RateLimitResult {
+        RateLimitResult::Allowed {
+            remaining: u32::MAX,
+            limit,
+            reset_at: 0,
+        }
+    }
+
+    /// Whether enforcement is currently bypassed
+    pub fn is_emergency_bypass_active(&self) -> bool {
+        self.emergency_bypass.load(Ordering::Relaxed)
+    }
+
+    /// Re-enable enforcement after an emergency bypass.
+    /// The deny rate history is reset so the bypass is not re-triggered by old data.
+    pub fn clear_emergency_bypass(&self) {
+        if let Ok(mut calibration) = self.calibration.lock() {
+            self.end_bypass(
+                &mut calibration,
+                "Rate limit emergency bypass cleared, enforcement re-enabled",
+            );
+        }
+    }
+
+    /// Turn enforcement back on and forget the deny rate history
+    fn end_bypass(&self, calibration: &mut Calibration, message: &str) {
+        if self.emergency_bypass.swap(false, Ordering::Relaxed) {
+            tracing::warn!("{}", message);
+        }
+        RateLimitMetrics::set_emergency_bypass(false);
+        calibration.samples.clear();
+        calibration.bypass_since = None;
+        self.calibration_clients.clear();
+    }
+
+    /// Remember whether `client` was denied during the current calibration interval.
+    /// Only tracked when self-calibration is enabled.
+    fn note_client(&self, client: impl FnOnce() -> String, denied: bool) {
+        if self.config.emergency_bypass_deny_ratio <= 0.0 {
+            return;
+        }
+        let client = client();
+        if let Some(mut was_denied) = self.calibration_clients.get_mut(&client) {
+            *was_denied |= denied;
+        } else if self.calibration_clients.len() < MAX_CALIBRATION_CLIENTS {
+            self.calibration_clients.insert(client, denied);
+        }
+    }
+
+    /// Count and forget the clients seen since the last calibration run
+    fn take_client_counts(&self) -> ClientCounts {
+        let mut counts = ClientCounts::default();
+        self.calibration_clients.retain(|_, denied| {
+            counts.clients += 1;
+            counts.denied += usize::from(*denied);
+            false
+        });
+        counts
+    }
+
+    /// Check the deny rate over the last `window_secs` and activate the emergency
+    /// bypass if it exceeds `emergency_bypass_deny_ratio`, which usually means the
+    /// limits are misconfigured and legitimate users are being locked out.
+    ///
+    /// Both the share of denied requests and the share of distinct clients (API keys
+    /// or IPs) denied since the previous check must exceed the ratio, so one client
+    /// hammering a limited endpoint cannot switch enforcement off for everyone. A
+    /// bypass lasts at most `window_secs`; enforcement is then re-enabled.
+    ///
+    /// Cheap to call on every request: the check runs at most once per minute.
+    /// Returns whether the bypass is active.
+    pub fn self_calibrate(&self, window_secs: u64) -> bool {
+        let now = Instant::now();
+        if self.config.enabled && self.config.emergency_bypass_deny_ratio > 0.0 {
+            self.calibrate(
+                DecisionSample::from_metrics(now),
+                || self.take_client_counts(),
+                Duration::from_secs(window_secs),
+            );
+        }
+        self.is_emergency_bypass_active()
+    }
+
+    fn calibrate(
+        &self,
+        sample: DecisionSample,
+        clients: impl FnOnce() -> ClientCounts,
+        window: Duration,
+    ) {
+        let Ok(mut calibration) = self.calibration.try_lock() else {
+            // Another request is already calibrating
+            return;
+        };
+        if calibration
+            .last_run
+            .is_some_and(|last| sample.at.duration_since(last) < CALIBRATION_MIN_INTERVAL)
+        {
+            return;
+        }
+        calibration.last_run = Some(sample.at);
+        let clients = clients();
+
+        if let Some(since) = calibration.bypass_since {
+            if sample.at.duration_since(since) >= window {
+                self.end_bypass(
+                    &mut calibration,
+                    "Rate limit emergency bypass expired, enforcement re-enabled",
+                );
+                calibration.samples.push_back(sample);
+            }
+            return;
+        }
+        calibration.samples.push_back(sample);
+
+        // The baseline is the newest sample taken at or before the start of the window
+        while calibration.samples.len() > 1
+            && sample.at.duration_since(calibration.samples[1].at) >= window
+        {
+            calibration.samples.pop_front();
+        }
+        let Some(baseline) = calibration.samples.front().copied() else {
+            return;
+        };
+
+        let allowed = sample.allowed.saturating_sub(baseline.allowed);
+        let denied = sample.denied.saturating_sub(baseline.denied);
+        let total = allowed + denied;
+        if total == 0 || total < self.config.emergency_bypass_min_requests {
+            return;
+        }
+
+        let deny_ratio = denied as f64 / total as f64;
+        let client_deny_ratio = if clients.clients == 0 {
+            0.0
+        } else {
+            clients.denied as f64 / clients.clients as f64
+        };
+        let threshold = self.config.emergency_bypass_deny_ratio;
+        if deny_ratio > threshold
+            && client_deny_ratio > threshold
+            && !self.emergency_bypass.swap(true, Ordering::Relaxed)
+        {
+            calibration.bypass_since = Some(sample.at);
+            RateLimitMetrics::set_emergency_bypass(true);
+            tracing::error!(
+                deny_ratio = deny_ratio,
+                client_deny_ratio = client_deny_ratio,
+                threshold = threshold,
+                allowed = allowed,
+                denied = denied,
+                clients = clients.clients,
+                denied_clients = clients.denied,
+                window_secs = window.as_secs(),
+                "CRITICAL: rate limiter denied too many clients, likely misconfigured; \
+                 enforcement bypassed for one window"
+            );
+        }
+    }
+
     /// Clean up stale buckets that haven't been used recently
     pub fn cleanup_stale(&self) -> usize {
         let ttl_ms = (self.config.bucket_ttl_seconds * 1000) as i64;
@@ -207,6 +600,102 @@ impl RateLimiter {
         removed
     }
 
+    /// Export every bucket's state so it can survive a restart
+    pub fn export_state(&self) -> RateLimiterState {
+        RateLimiterState {
+            exported_at: TokenBucket::now_millis(),
+            ip_buckets: self
+                .ip_buckets
+                .iter()
+                .map(|entry| (*entry.key(), entry.bucket.snapshot()))
+                .collect(),
+            key_buckets: self
+                .key_buckets
+                .iter()
+                .map(|entry| (entry.key().clone(), entry.bucket.snapshot()))
+                .collect(),
+        }
+    }
+
+    /// Restore buckets from a snapshot, replacing any existing bucket with the same key
+    pub fn import_state(&self, state: RateLimiterState) {
+        for (ip, snapshot) in state.ip_buckets {
+            self.ip_buckets.insert(ip, BucketEntry::from_snapshot(&snapshot));
+        }
+        for (key, snapshot) in state.key_buckets {
+            self.key_buckets.insert(key, BucketEntry::from_snapshot(&snapshot));
+        }
+    }
+
+    /// Write the exported state to `path` as JSON.
+    ///
+    /// The directory is created owner-only if missing and must not be accessible to
+    /// other users. The file is written with mode 0600 to a temporary file that is
+    /// then renamed over `path`, so a symlink at `path` is replaced, not followed.
+    pub fn save_state(&self, path: &Path) -> std::io::Result<usize> {
+        use std::io::Write;
+
+        let state = self.export_state();
+        let json = serde_json::to_vec(&state)?;
+
+        let dir = state_dir(path);
+        let mut builder = std::fs::DirBuilder::new();
+        builder.recursive(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::DirBuilderExt;
+            builder.mode(0o700);
+        }
+        builder.create(dir)?;
+        ensure_private_dir(dir)?;
+
+        let tmp_path = dir.join(format!(".{}.{}.tmp", STATE_FILE_NAME, uuid::Uuid::new_v4()));
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let written = options.open(&tmp_path).and_then(|mut file| {
+            file.write_all(&json)?;
+            file.sync_all()
+        });
+        if let Err(e) = written.and_then(|()| std::fs::rename(&tmp_path, path)) {
+            let _ = std::fs::remove_file(&tmp_path);
+            return Err(e);
+        }
+        Ok(state.len())
+    }
+
+    /// Import state from `path` if the file exists and is younger than `max_age`.
+    ///
+    /// The file is removed after reading so a stale snapshot is never applied twice.
+    /// Returns the number of buckets restored.
+    pub fn restore_state(&self, path: &Path, max_age: Duration) -> std::io::Result<usize> {
+        match ensure_private_dir(state_dir(path)) {
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            result => result?,
+        }
+        let json = match std::fs::read(path) {
+            Ok(json) => json,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(e),
+        };
+        let _ = std::fs::remove_file(path);
+
+        let state: RateLimiterState = serde_json::from_slice(&json)?;
+        let age_ms = TokenBucket::now_millis() - state.exported_at;
+        if age_ms < 0 || age_ms as u128 >= max_age.as_millis() {
+            tracing::info!(age_ms = age_ms, "Ignoring stale rate limiter state");
+            return Ok(0);
+        }
+
+        let restored = state.len();
+        self.import_state(state);
+        Ok(restored)
+    }
+
     /// Get statistics about the rate limiter
     pub fn stats(&self) -> RateLimiterStats {
         RateLimiterStats {
@@ -232,6 +721,7 @@ pub struct RateLimiterStats {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ratelimit::RateLimitRule;
     use std::net::Ipv4Addr;
 
     #[test]
@@ -311,6 +801,65 @@ mod tests {
         assert!(!limiter.check_key("key-2").is_allowed());
     }
 
+    #[test]
+    fn test_path_rule_limits_independently_of_global() {
+        let config = RateLimitConfig {
+            enabled: true,
+            http_requests_per_second: 100,
+            http_burst_size: 100,
+            rules: vec![RateLimitRule {
+                path_pattern: "/api/v1/notifications/batch".to_string(),
+                requests_per_second: 10,
+                burst_size: 10,
+            }],
+            ..Default::default()
+        };
+        let limiter = RateLimiter::new(config);
+        let ip = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+        let batch = "/api/v1/notifications/batch";
+        let send = "/api/v1/notifications/send";
+
+        for _ in 0..10 {
+            assert!(limiter.check_http_path(Some("key"), ip, batch).is_allowed());
+        }
+        match limiter.check_http_path(Some("key"), ip, batch) {
+            RateLimitResult::Denied { limit, .. } => assert_eq!(limit, 10),
+            other => panic!("expected rule limit to deny, got {:?}", other),
+        }
+
+        // The global bucket for the same key is untouched
+        for _ in 0..100 {
+            assert!(limiter.check_http_path(Some("key"), ip, send).is_allowed());
+        }
+        assert!(!limiter.check_http_path(Some("key"), ip, send).is_allowed());
+    }
+
+    #[test]
+    fn test_path_rule_unaffected_by_exhausted_global() {
+        let config = RateLimitConfig {
+            enabled: true,
+            http_requests_per_second: 100,
+            http_burst_size: 100,
+            rules: vec![RateLimitRule {
+                path_pattern: "/api/v1/notifications/batch".to_string(),
+                requests_per_second: 10,
+                burst_size: 10,
+            }],
+            ..Default::default()
+        };
+        let limiter = RateLimiter::new(config);
+        let ip = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+
+        for _ in 0..100 {
+            assert!(limiter.check_http_path(None, ip, "/api/v1/channels").is_allowed());
+        }
+        assert!(!limiter.check_http_path(None, ip, "/api/v1/channels").is_allowed());
+
+        assert!(limiter
+            .check_http_path(None, ip, "/api/v1/notifications/batch")
+            .is_allowed());
+    }
+
     #[test]
     fn test_cleanup_stale_buckets() {
         let config = RateLimitConfig {
@@ -335,6 +884,165 @@ mod tests {
         assert_eq!(limiter.key_buckets.len(), 0);
     }
 
+    #[test]
+    fn test_export_import_state_keeps_buckets_exhausted() {
+        let config = RateLimitConfig {
+            enabled: true,
+            http_requests_per_second: 1,
+            http_burst_size: 3,
+            ws_connections_per_minute: 2,
+            ..Default::default()
+        };
+        let limiter = RateLimiter::new(config.clone());
+
+        let ip = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 7));
+        for _ in 0..3 {
+            assert!(limiter.check_key("api-key").is_allowed());
+        }
+        for _ in 0..2 {
+            assert!(limiter.check_ip(ip).is_allowed());
+        }
+        assert!(!limiter.check_key("api-key").is_allowed());
+        assert!(!limiter.check_ip(ip).is_allowed());
+
+        // Round-trip through JSON as a restart would
+        let json = serde_json::to_string(&limiter.export_state()).unwrap();
+        let state: RateLimiterState = serde_json::from_str(&json).unwrap();
+        assert_eq!(state.len(), 2);
+
+        let restarted = RateLimiter::new(config);
+        restarted.import_state(state);
+
+        assert!(!restarted.check_key("api-key").is_allowed());
+        assert!(!restarted.check_ip(ip).is_allowed());
+        // Untouched keys still get a fresh bucket
+        assert!(restarted.check_key("other-key").is_allowed());
+    }
+
+    #[test]
+    fn test_save_and_restore_state_file() {
+        let config = RateLimitConfig {
+            enabled: true,
+            http_requests_per_second: 1,
+            http_burst_size: 1,
+            ..Default::default()
+        };
+        let dir = std::env::temp_dir().join(format!("ara-ratelimit-test-{}", uuid::Uuid::new_v4()));
+        let path = state_path_in(&dir);
+
+        let limiter = RateLimiter::new(config.clone());
+        assert!(limiter.check_key("api-key").is_allowed());
+        assert_eq!(limiter.save_state(&path).unwrap(), 1);
+
+        let restarted = RateLimiter::new(config.clone());
+        assert_eq!(restarted.restore_state(&path, Duration::from_secs(60)).unwrap(), 1);
+        assert!(!restarted.check_key("api-key").is_allowed());
+
+        // File is consumed on restore
+        assert!(!path.exists());
+        assert_eq!(restarted.restore_state(&path, Duration::from_secs(60)).unwrap(), 0);
+
+        // Stale snapshots are ignored
+        limiter.save_state(&path).unwrap();
+        let fresh = RateLimiter::new(config);
+        assert_eq!(fresh.restore_state(&path, Duration::ZERO).unwrap(), 0);
+        assert!(fresh.check_key("api-key").is_allowed());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_saved_state_is_private_and_holds_no_api_keys() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let config = RateLimitConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        let dir = std::env::temp_dir().join(format!("ara-ratelimit-test-{}", uuid::Uuid::new_v4()));
+        let path = state_path_in(&dir);
+        let limiter = RateLimiter::new(config);
+        assert!(limiter.check_key("secret-api-key").is_allowed());
+
+        // A symlink planted at the state path is replaced, not written through
+        std::fs::create_dir(&dir).unwrap();
+        std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o700)).unwrap();
+        let victim = dir.join("victim");
+        std::fs::write(&victim, "untouched").unwrap();
+        std::os::unix::fs::symlink(&victim, &path).unwrap();
+
+        limiter.save_state(&path).unwrap();
+        assert_eq!(std::fs::read_to_string(&victim).unwrap(), "untouched");
+        let metadata = std::fs::symlink_metadata(&path).unwrap();
+        assert!(metadata.is_file());
+        assert_eq!(metadata.permissions().mode() & 0o777, 0o600);
+        assert!(!std::fs::read_to_string(&path).unwrap().contains("secret-api-key"));
+
+        // A directory other users can access is refused
+        std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o777)).unwrap();
+        assert!(limiter.save_state(&path).is_err());
+        assert!(limiter.restore_state(&path, Duration::from_secs(60)).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_ip_blocklist() {
+        let config = RateLimitConfig {
+            enabled: true,
+            ip_blocklist: vec!["10.0.0.0/8".parse().unwrap(), "2001:db8::/32".parse().unwrap()],
+            ..Default::default()
+        };
+        let limiter = RateLimiter::new(config);
+
+        let blocked = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+        match limiter.check_ip(blocked) {
+            RateLimitResult::Denied { retry_after, .. } => assert_eq!(retry_after, u64::MAX),
+            other => panic!("expected blocklisted IP to be denied, got {:?}", other),
+        }
+        assert!(!limiter.check_http(Some("api-key"), blocked).is_allowed());
+
+        let ipv6: IpAddr = "2001:db8::1".parse().unwrap();
+        assert!(!limiter.check_ip(ipv6).is_allowed());
+
+        let other = IpAddr::V4(Ipv4Addr::new(172, 16, 0, 1));
+        assert!(limiter.check_ip(other).is_allowed());
+    }
+
+    #[test]
+    fn test_ip_allowlist_bypasses_limits() {
+        let config = RateLimitConfig {
+            enabled: true,
+            ws_connections_per_minute: 1,
+            ip_allowlist: vec!["192.168.0.0/16".parse().unwrap(), "fd00::/8".parse().unwrap()],
+            ip_blocklist: vec!["192.168.99.0/24".parse().unwrap()],
+            ..Default::default()
+        };
+        let limiter = RateLimiter::new(config);
+
+        let trusted = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 10));
+        for _ in 0..10 {
+            match limiter.check_ip(trusted) {
+                RateLimitResult::Allowed { remaining, .. } => assert_eq!(remaining, u32::MAX),
+                other => panic!("expected allowlisted IP to be allowed, got {:?}", other),
+            }
+        }
+        assert_eq!(limiter.stats().ip_buckets, 0);
+
+        let trusted_v6: IpAddr = "fd00::42".parse().unwrap();
+        for _ in 0..10 {
+            assert!(limiter.check_ip(trusted_v6).is_allowed());
+        }
+
+        // Blocklist takes precedence over an overlapping allowlist entry
+        let blocked = IpAddr::V4(Ipv4Addr::new(192, 168, 99, 1));
+        assert!(!limiter.check_ip(blocked).is_allowed());
+
+        // Unlisted IPs are still limited
+        let other = IpAddr::V4(Ipv4Addr::new(8, 8, 8, 8));
+        assert!(limiter.check_ip(other).is_allowed());
+        assert!(!limiter.check_ip(other).is_allowed());
+    }
+
     #[test]
     fn test_stats() {
         let config = RateLimitConfig {
@@ -357,4 +1065,154 @@ mod tests {
         assert_eq!(stats.http_limit, 100);
         assert_eq!(stats.ws_limit, 20);
     }
+
+    fn calibrating_limiter() -> RateLimiter {
+        RateLimiter::new(RateLimitConfig {
+            enabled: true,
+            ws_connections_per_minute: 1,
+            emergency_bypass_deny_ratio: 0.5,
+            emergency_bypass_min_requests: 10,
+            ..Default::default()
+        })
+    }
+
+    fn sample(at: Instant, allowed: u64, denied: u64) -> DecisionSample {
+        DecisionSample {
+            at,
+            allowed,
+            denied,
+        }
+    }
+
+    /// Every client seen in the interval was denied
+    fn all_denied() -> ClientCounts {
+        ClientCounts {
+            clients: 5,
+            denied: 5,
+        }
+    }
+
+    #[test]
+    fn test_emergency_bypass_activates_and_clears() {
+        let limiter = calibrating_limiter();
+        let window = Duration::from_secs(300);
+        let ip = IpAddr::V4(Ipv4Addr::new(172, 16, 0, 1));
+        assert!(limiter.check_ip(ip).is_allowed());
+        assert!(!limiter.check_ip(ip).is_allowed());
+
+        let start = Instant::now();
+        limiter.calibrate(sample(start, 100, 10), all_denied, window);
+        assert!(!limiter.is_emergency_bypass_active());
+
+        // 2 allowed vs 40 denied since the baseline
+        limiter.calibrate(sample(start + Duration::from_secs(61), 102, 50), all_denied, window);
+        assert!(limiter.is_emergency_bypass_active());
+        assert!(limiter.check_ip(ip).is_allowed());
+        assert!(limiter.check_key("exhausted").is_allowed());
+
+        limiter.clear_emergency_bypass();
+        assert!(!limiter.is_emergency_bypass_active());
+        assert!(!limiter.check_ip(ip).is_allowed());
+    }
+
+    #[test]
+    fn test_emergency_bypass_ignores_healthy_or_small_samples() {
+        let limiter = calibrating_limiter();
+        let window = Duration::from_secs(300);
+        let start = Instant::now();
+        limiter.calibrate(sample(start, 0, 0), all_denied, window);
+
+        // Too few decisions to judge
+        limiter.calibrate(sample(start + Duration::from_secs(61), 1, 5), all_denied, window);
+        assert!(!limiter.is_emergency_bypass_active());
+
+        // Mostly allowed
+        limiter.calibrate(sample(start + Duration::from_secs(122), 100, 20), all_denied, window);
+        assert!(!limiter.is_emergency_bypass_active());
+    }
+
+    #[test]
+    fn test_self_calibration_runs_at_most_once_per_minute() {
+        let limiter = calibrating_limiter();
+        let window = Duration::from_secs(300);
+        let start = Instant::now();
+        limiter.calibrate(sample(start, 0, 0), all_denied, window);
+
+        // Would trip the bypass, but arrives within a minute of the last run
+        limiter.calibrate(sample(start + Duration::from_secs(30), 0, 100), all_denied, window);
+        assert!(!limiter.is_emergency_bypass_active());
+
+        limiter.calibrate(sample(start + Duration::from_secs(60), 0, 100), all_denied, window);
+        assert!(limiter.is_emergency_bypass_active());
+    }
+
+    #[test]
+    fn test_deny_rate_measured_within_window() {
+        let start = Instant::now();
+        let history = [
+            sample(start, 0, 0),
+            sample(start + Duration::from_secs(60), 1000, 0),
+            sample(start + Duration::from_secs(240), 1000, 100),
+        ];
+
+        // Over the whole history the denials are diluted by earlier traffic
+        let limiter = calibrating_limiter();
+        for s in history {
+            limiter.calibrate(s, all_denied, Duration::from_secs(600));
+        }
+        assert!(!limiter.is_emergency_bypass_active());
+
+        // Within the last two minutes nothing was allowed
+        let limiter = calibrating_limiter();
+        for s in history {
+            limiter.calibrate(s, all_denied, Duration::from_secs(120));
+        }
+        assert!(limiter.is_emergency_bypass_active());
+    }
+
+    #[test]
+    fn test_single_hammering_client_does_not_trip_bypass() {
+        let limiter = RateLimiter::new(RateLimitConfig {
+            enabled: true,
+            http_burst_size: 1,
+            http_requests_per_second: 1,
+            emergency_bypass_deny_ratio: 0.5,
+            emergency_bypass_min_requests: 10,
+            ..Default::default()
+        });
+        let window = Duration::from_secs(300);
+        for i in 0..10 {
+            assert!(limiter.check_key(&format!("client-{}", i)).is_allowed());
+        }
+        for _ in 0..100 {
+            limiter.check_key("hammer");
+        }
+
+        // Almost every request was denied, but only one of eleven clients
+        let start = Instant::now();
+        limiter.calibrate(sample(start, 0, 0), all_denied, window);
+        limiter.calibrate(
+            sample(start + Duration::from_secs(60), 11, 99),
+            || limiter.take_client_counts(),
+            window,
+        );
+        assert!(!limiter.is_emergency_bypass_active());
+        assert!(!limiter.check_key("hammer").is_allowed());
+    }
+
+    #[test]
+    fn test_emergency_bypass_expires_after_window() {
+        let limiter = calibrating_limiter();
+        let window = Duration::from_secs(300);
+        let start = Instant::now();
+        limiter.calibrate(sample(start, 0, 0), all_denied, window);
+        limiter.calibrate(sample(start + Duration::from_secs(60), 0, 100), all_denied, window);
+        assert!(limiter.is_emergency_bypass_active());
+
+        limiter.calibrate(sample(start + Duration::from_secs(300), 500, 100), all_denied, window);
+        assert!(limiter.is_emergency_bypass_active());
+
+        limiter.calibrate(sample(start + Duration::from_secs(360), 600, 100), all_denied, window);
+        assert!(!limiter.is_emergency_bypass_active());
+    }
 }
@@ -10,10 +10,14 @@ mod distributed;
 mod limiter;
 mod token_bucket;
 
-pub use config::RateLimitConfig;
+pub use config::{deserialize_rules, RateLimitConfig, RateLimitRule};
+pub(crate) use config::glob_match;
 pub use distributed::{
     create_distributed_rate_limiter, DistributedRateLimiter, LocalRateLimiterBackend,
     RateLimitBackendType, RateLimitError, RedisRateLimiterBackend,
 };
-pub use limiter::{RateLimitResult, RateLimiter, RateLimiterStats};
-pub use token_bucket::TokenBucket;
+pub use limiter::{
+    default_state_path, state_path_in, RateLimitResult, RateLimiter, RateLimiterState,
+    RateLimiterStats,
+};
+pub use token_bucket::{TokenBucket, TokenBucketSnapshot};
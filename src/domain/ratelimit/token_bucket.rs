@@ -3,6 +3,8 @@
 use std::sync::atomic::{AtomicI64, AtomicU32, Ordering};
 use std::time::SystemTime;
 
+use serde::{Deserialize, Serialize};
+
 /// Token Bucket for rate limiting.
 ///
 /// Uses atomic operations for lock-free concurrent access.
@@ -19,6 +21,19 @@ pub struct TokenBucket {
     refill_rate: u32,
 }
 
+/// Point-in-time copy of a token bucket, used to persist rate limiter state
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenBucketSnapshot {
+    /// Tokens left at `last_refill`
+    pub tokens: u32,
+    /// Last refill timestamp (Unix milliseconds)
+    pub last_refill: i64,
+    /// Maximum bucket capacity
+    pub capacity: u32,
+    /// Tokens added per second
+    pub refill_rate: u32,
+}
+
 impl TokenBucket {
     /// Create a new token bucket
     pub fn new(capacity: u32, refill_rate: u32) -> Self {
@@ -30,6 +45,27 @@ impl TokenBucket {
         }
     }
 
+    /// Restore a token bucket from a snapshot.
+    /// Tokens keep refilling from the snapshot's `last_refill` time.
+    pub fn from_snapshot(snapshot: &TokenBucketSnapshot) -> Self {
+        Self {
+            tokens: AtomicU32::new(snapshot.tokens.min(snapshot.capacity)),
+            last_refill: AtomicI64::new(snapshot.last_refill),
+            capacity: snapshot.capacity,
+            refill_rate: snapshot.refill_rate,
+        }
+    }
+
+    /// Capture the current bucket state
+    pub fn snapshot(&self) -> TokenBucketSnapshot {
+        TokenBucketSnapshot {
+            tokens: self.tokens.load(Ordering::Relaxed),
+            last_refill: self.last_refill.load(Ordering::Relaxed),
+            capacity: self.capacity,
+            refill_rate: self.refill_rate,
+        }
+    }
+
     /// Get current time in milliseconds
     pub fn now_millis() -> i64 {
         SystemTime::now()
@@ -138,4 +174,19 @@ mod tests {
         // Should have refilled some tokens
         assert!(bucket.try_consume());
     }
+
+    #[test]
+    fn test_token_bucket_snapshot_round_trip() {
+        let bucket = TokenBucket::new(3, 1);
+        assert!(bucket.try_consume_n(3));
+
+        let snapshot = bucket.snapshot();
+        assert_eq!(snapshot.tokens, 0);
+        assert_eq!(snapshot.capacity, 3);
+        assert_eq!(snapshot.refill_rate, 1);
+
+        let restored = TokenBucket::from_snapshot(&snapshot);
+        assert_eq!(restored.snapshot(), snapshot);
+        assert!(!restored.try_consume());
+    }
 }
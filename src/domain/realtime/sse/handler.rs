@@ -17,10 +17,17 @@ use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc;
 use tokio_stream::wrappers::ReceiverStream;
 
+use crate::connection_manager::{ConnectionMetadata, Transport};
 use crate::metrics::{WS_CONNECTIONS_CLOSED, WS_CONNECTIONS_OPENED, WS_CONNECTION_DURATION};
 use crate::server::AppState;
 use crate::websocket::{OutboundMessage, ServerMessage};
 
+/// Error code sent when the connection's token has expired
+const AUTH_EXPIRED_CODE: &str = "AUTH_EXPIRED";
+
+/// Reconnect delay sent with `AUTH_EXPIRED`, giving the client time to refresh its token
+const AUTH_EXPIRED_RETRY: Duration = Duration::from_secs(60);
+
 /// SSE event types
 #[derive(Debug, Clone, Serialize)]
 #[serde(tag = "type")]
@@ -80,7 +87,14 @@ pub async fn sse_handler(
     let (tx, rx) = mpsc::channel::<OutboundMessage>(32);
 
     // Register connection with limit checking
-    let handle = match state.connection_manager.register(user_id.clone(), tenant_id.clone(), roles, tx) {
+    let metadata = ConnectionMetadata {
+        transport: Transport::Sse,
+        ..Default::default()
+    };
+    let handle = match state
+        .connection_manager
+        .register_with_metadata(user_id.clone(), tenant_id.clone(), roles, tx, metadata)
+    {
         Ok(h) => h,
         Err(e) => {
             tracing::warn!(user_id = %user_id, error = %e, "SSE connection rejected");
@@ -97,6 +111,7 @@ pub async fn sse_handler(
 
     // Record connection opened metric
     WS_CONNECTIONS_OPENED.inc();
+    state.tenant_manager.record_connection(&tenant_id);
 
     tracing::info!(
         connection_id = %connection_id,
@@ -105,50 +120,36 @@ pub async fn sse_handler(
         "SSE connection established"
     );
 
-    // Register session in cluster store for cross-server routing
-    if state.session_store.is_enabled() {
-        let session_info = crate::cluster::SessionInfo {
-            connection_id,
-            user_id: user_id.clone(),
-            tenant_id: tenant_id.clone(),
-            server_id: state.session_store.server_id().to_string(),
-            connected_at: chrono::Utc::now().timestamp(),
-            channels: vec![],
-        };
-        if let Err(e) = state.session_store.register_session(&session_info).await {
-            tracing::warn!(
-                connection_id = %connection_id,
-                error = %e,
-                "Failed to register SSE session in cluster store"
-            );
-        }
+    // Register session in the session store (local or cluster-wide, for cross-server routing)
+    let session_info = crate::cluster::SessionInfo {
+        connection_id,
+        user_id: user_id.clone(),
+        tenant_id: tenant_id.clone(),
+        server_id: state.session_store.server_id().to_string(),
+        connected_at: chrono::Utc::now().timestamp(),
+        channels: vec![],
+        server_tags: state.settings.cluster.server_tags.clone(),
+    };
+    if let Err(e) = state.session_store.register_session(&session_info).await {
+        tracing::warn!(
+            connection_id = %connection_id,
+            error = %e,
+            "Failed to register SSE session in cluster store"
+        );
     }
 
     // Replay any queued messages for this user (tenant-scoped key)
     let queue_key = crate::auth::tenant_scoped_key(&tenant_id, &user_id);
     if state.queue_backend.is_enabled() {
-        match state.queue_backend.drain(&queue_key).await {
-            Ok(drain_result) => {
-                let mut replayed = 0;
-                let mut failed = 0;
-
-                for stored_msg in drain_result.messages {
-                    let msg = OutboundMessage::Raw(ServerMessage::Notification {
-                        event: stored_msg.event,
-                    });
-                    match handle.sender.send(msg).await {
-                        Ok(_) => replayed += 1,
-                        Err(_) => failed += 1,
-                    }
-                }
-
-                if replayed > 0 || drain_result.expired > 0 {
+        match state.queue_backend.replay(&queue_key, &handle.sender).await {
+            Ok(result) => {
+                if result.replayed > 0 || result.expired > 0 {
                     tracing::info!(
                         connection_id = %connection_id,
                         user_id = %user_id,
-                        replayed = replayed,
-                        expired = drain_result.expired,
-                        failed = failed,
+                        replayed = result.replayed,
+                        expired = result.expired,
+                        failed = result.failed,
                         "Replayed queued messages on SSE connect"
                     );
                 }
@@ -169,8 +170,10 @@ pub async fn sse_handler(
         rx,
         connection_id,
         user_id.clone(),
+        tenant_id.clone(),
         state.clone(),
         connection_start,
+        Duration::from_millis(state.settings.websocket.sse_reconnect_retry_ms),
     );
 
     // Return SSE response with keep-alive
@@ -202,18 +205,37 @@ fn extract_token(query: &SseQuery, headers: &HeaderMap) -> Option<String> {
     None
 }
 
-/// Create the SSE event stream
+/// Reconnect delay to send with `msg` when it overrides the default: the shutdown's
+/// `reconnect_after_seconds`, or a longer back-off when the token has expired
+fn reconnect_retry(msg: &OutboundMessage) -> Option<Duration> {
+    match msg {
+        OutboundMessage::Raw(ServerMessage::Shutdown {
+            reconnect_after_seconds: Some(seconds),
+            ..
+        }) => Some(Duration::from_secs(*seconds)),
+        OutboundMessage::Raw(ServerMessage::Error { code, .. }) if code == AUTH_EXPIRED_CODE => {
+            Some(AUTH_EXPIRED_RETRY)
+        }
+        _ => None,
+    }
+}
+
+/// Create the SSE event stream. The connected event carries `retry` so browsers back
+/// off instead of reconnecting immediately when the stream ends.
 fn create_sse_stream(
     rx: mpsc::Receiver<OutboundMessage>,
     connection_id: uuid::Uuid,
     user_id: String,
+    tenant_id: String,
     state: AppState,
     connection_start: std::time::Instant,
+    retry: Duration,
 ) -> impl Stream<Item = Result<Event, Infallible>> {
     // Create a cleanup guard that will be dropped when the stream ends
     let cleanup_guard = CleanupGuard::new(
         connection_id,
         user_id.clone(),
+        tenant_id,
         state,
         connection_start,
     );
@@ -230,7 +252,7 @@ fn create_sse_stream(
     // Use async_stream to create a simple stream with cleanup
     async_stream::stream! {
         // Emit initial connected event
-        yield Ok(Event::default().event("connected").data(connected_json));
+        yield Ok(Event::default().event("connected").data(connected_json).retry(retry));
 
         // Hold the cleanup guard - it will be dropped when the stream ends
         let _guard = cleanup_guard;
@@ -238,7 +260,7 @@ fn create_sse_stream(
         // Stream messages
         let mut message_stream = message_stream;
         while let Some(msg) = message_stream.next().await {
-            let event = match msg.to_json() {
+            let mut event = match msg.to_json() {
                 Ok(json) => {
                     // Determine event type from message
                     let event_type = match &msg {
@@ -246,7 +268,7 @@ fn create_sse_stream(
                         OutboundMessage::Raw(ServerMessage::Heartbeat) => "heartbeat",
                         OutboundMessage::Raw(ServerMessage::Error { .. }) => "error",
                         OutboundMessage::Raw(_) => "message",
-                        OutboundMessage::Serialized(_) => "notification",
+                        OutboundMessage::Serialized(_) | OutboundMessage::Binary(_) => "notification",
                     };
                     Event::default().event(event_type).data(json)
                 }
@@ -257,7 +279,19 @@ fn create_sse_stream(
                         .data(format!(r#"{{"code":"SERIALIZATION_ERROR","message":"{}"}}"#, e))
                 }
             };
+            let auth_expired = matches!(
+                &msg,
+                OutboundMessage::Raw(ServerMessage::Error { code, .. }) if code == AUTH_EXPIRED_CODE
+            );
+            if let Some(retry) = reconnect_retry(&msg) {
+                event = event.retry(retry);
+            }
             yield Ok(event);
+
+            // The token cannot be used any more; close so the client reconnects with a new one
+            if auth_expired {
+                break;
+            }
         }
     }
 }
@@ -266,6 +300,7 @@ fn create_sse_stream(
 struct CleanupGuard {
     connection_id: uuid::Uuid,
     user_id: String,
+    tenant_id: String,
     state: AppState,
     connection_start: std::time::Instant,
 }
@@ -274,12 +309,14 @@ impl CleanupGuard {
     fn new(
         connection_id: uuid::Uuid,
         user_id: String,
+        tenant_id: String,
         state: AppState,
         connection_start: std::time::Instant,
     ) -> Self {
         Self {
             connection_id,
             user_id,
+            tenant_id,
             state,
             connection_start,
         }
@@ -292,6 +329,7 @@ impl Drop for CleanupGuard {
         WS_CONNECTIONS_CLOSED.inc();
         let duration = self.connection_start.elapsed().as_secs_f64();
         WS_CONNECTION_DURATION.observe(duration);
+        self.state.tenant_manager.record_disconnection(&self.tenant_id);
 
         tracing::info!(
             connection_id = %self.connection_id,
@@ -307,14 +345,12 @@ impl Drop for CleanupGuard {
         tokio::spawn(async move {
             connection_manager.unregister(connection_id).await;
             // Unregister from cluster store
-            if session_store.is_enabled() {
-                if let Err(e) = session_store.unregister_session(connection_id).await {
-                    tracing::warn!(
-                        connection_id = %connection_id,
-                        error = %e,
-                        "Failed to unregister SSE session from cluster store"
-                    );
-                }
+            if let Err(e) = session_store.unregister_session(connection_id).await {
+                tracing::warn!(
+                    connection_id = %connection_id,
+                    error = %e,
+                    "Failed to unregister SSE session from cluster store"
+                );
             }
         });
     }
@@ -373,4 +409,46 @@ mod tests {
         let headers = HeaderMap::new();
         assert_eq!(extract_token(&query, &headers), None);
     }
+
+    #[tokio::test]
+    async fn test_stream_sends_retry_hints() {
+        let state = crate::api::test_support::test_state().await;
+        let (tx, rx) = mpsc::channel(8);
+        let stream = create_sse_stream(
+            rx,
+            uuid::Uuid::new_v4(),
+            "user-1".to_string(),
+            "default".to_string(),
+            state,
+            std::time::Instant::now(),
+            Duration::from_millis(5000),
+        );
+
+        tx.send(OutboundMessage::Raw(ServerMessage::shutdown("maintenance", Some(7))))
+            .await
+            .unwrap();
+        tx.send(OutboundMessage::Raw(ServerMessage::error(AUTH_EXPIRED_CODE, "Token expired")))
+            .await
+            .unwrap();
+        tx.send(OutboundMessage::Raw(ServerMessage::Heartbeat))
+            .await
+            .unwrap();
+        drop(tx);
+
+        let chunks: Vec<String> = Sse::new(stream)
+            .into_response()
+            .into_body()
+            .into_data_stream()
+            .map(|chunk| String::from_utf8(chunk.unwrap().to_vec()).unwrap())
+            .collect()
+            .await;
+
+        // The stream ends after AUTH_EXPIRED, so the heartbeat is never sent
+        assert_eq!(chunks.len(), 3);
+        assert!(chunks[0].starts_with("event: connected\n"));
+        assert!(chunks[0].contains("\nretry: 5000\n"));
+        assert!(chunks[1].contains("\nretry: 7000\n"));
+        assert!(chunks[2].contains(AUTH_EXPIRED_CODE));
+        assert!(chunks[2].contains("\nretry: 60000\n"));
+    }
 }
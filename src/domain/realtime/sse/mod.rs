@@ -24,6 +24,12 @@
 //! - `heartbeat` - Keep-alive event with timestamp
 //! - `connected` - Initial connection confirmation
 //! - `error` - Error event (connection will close after)
+//!
+//! # Reconnection
+//!
+//! Events carry the SSE `retry` field so browsers wait before reconnecting:
+//! `WEBSOCKET_SSE_RECONNECT_RETRY_MS` on connect, the shutdown's
+//! `reconnect_after_seconds` during shutdown, and 60 seconds with `AUTH_EXPIRED`.
 
 mod handler;
 
@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use axum::{
     extract::{
-        ws::{Message, WebSocket},
+        ws::{CloseFrame, Message, WebSocket},
         Query, State, WebSocketUpgrade,
     },
     http::{header, HeaderMap, StatusCode},
@@ -14,17 +14,39 @@ use tokio::sync::mpsc;
 
 use crate::auth::Claims;
 use crate::cluster::SessionInfo;
-use crate::connection_manager::ConnectionHandle;
-use crate::metrics::{WsMessageMetrics, WS_CONNECTIONS_CLOSED, WS_CONNECTIONS_OPENED, WS_CONNECTION_DURATION};
+use crate::config::WebSocketConfig;
+use crate::connection_manager::{ConnectionHandle, ConnectionMetadata, Transport};
+use crate::metrics::{
+    WsMessageMetrics, WS_BINARY_MESSAGES_SENT_TOTAL, WS_CONNECTIONS_CLOSED, WS_CONNECTIONS_OPENED,
+    WS_CONNECTION_DURATION, WS_FRAME_TOO_LARGE_TOTAL,
+};
+use crate::notification::PayloadEncoding;
 use crate::server::AppState;
 
-use super::message::{ClientMessage, OutboundMessage, ServerMessage};
+use super::message::{
+    encode_msgpack, ClientMessage, OutboundMessage, ServerMessage, PROTOCOL_VERSION,
+};
 
 const CHANNEL_BUFFER_SIZE: usize = 32;
 
+/// Close code sent to clients whose protocol version is below the configured minimum
+const PROTOCOL_TOO_OLD_CLOSE_CODE: u16 = 4000;
+
+/// Close code sent to clients whose frame exceeds `websocket.max_frame_size_bytes`
+/// (1009 Message Too Big)
+const FRAME_TOO_LARGE_CLOSE_CODE: u16 = 1009;
+
+/// Version assumed for clients that do not send `?protocol_version=`
+const DEFAULT_CLIENT_PROTOCOL_VERSION: u8 = 1;
+
 #[derive(Debug, Deserialize)]
 pub struct WsQuery {
     pub token: Option<String>,
+    /// Wire encoding for notifications (`json` or `msgpack`)
+    #[serde(default)]
+    pub encoding: PayloadEncoding,
+    /// Server message protocol version the client understands
+    pub protocol_version: Option<u8>,
 }
 
 /// WebSocket upgrade handler
@@ -62,11 +84,35 @@ pub async fn ws_handler(
         }
     };
 
-    tracing::info!(user_id = %claims.sub, "WebSocket upgrade requested");
+    tracing::info!(user_id = %claims.sub, encoding = ?query.encoding, "WebSocket upgrade requested");
 
-    // Upgrade to WebSocket with message size limits
-    ws.max_message_size(64 * 1024) // 64 KB max message size
-        .on_upgrade(move |socket| handle_socket(socket, state, claims))
+    let min_version = state.settings.websocket.min_client_protocol_version;
+    let protocol_version =
+        match negotiate_protocol_version(query.protocol_version, &state.settings.websocket) {
+            Ok(version) => version,
+            Err(requested) => {
+                tracing::warn!(
+                    user_id = %claims.sub,
+                    requested = requested,
+                    min = min_version,
+                    "WebSocket client protocol version too old"
+                );
+                return ws
+                    .on_upgrade(move |socket| reject_protocol_version(socket, requested, min_version));
+            }
+        };
+
+    let metadata = ConnectionMetadata {
+        transport: Transport::WebSocket,
+        payload_encoding: query.encoding,
+        protocol_version,
+    };
+
+    // Upgrade to WebSocket with frame and message size limits
+    let max_frame_size = state.settings.websocket.max_frame_size_bytes;
+    ws.max_frame_size(max_frame_size)
+        .max_message_size(max_frame_size)
+        .on_upgrade(move |socket| handle_socket(socket, state, claims, metadata))
 }
 
 /// Extract token from query parameter or Authorization header
@@ -88,16 +134,50 @@ fn extract_token(query: &WsQuery, headers: &HeaderMap) -> Option<String> {
     None
 }
 
+/// Resolve the client's requested protocol version against the configured range.
+///
+/// Clients that omit the parameter are assumed to speak version 1, and clients
+/// newer than the server are served the highest configured version. A version
+/// below the minimum is returned as the error.
+fn negotiate_protocol_version(requested: Option<u8>, config: &WebSocketConfig) -> Result<u8, u8> {
+    let requested = requested.unwrap_or(DEFAULT_CLIENT_PROTOCOL_VERSION);
+    if requested < config.min_client_protocol_version {
+        return Err(requested);
+    }
+    Ok(requested.min(config.max_client_protocol_version))
+}
+
+/// Tell a client its protocol version is no longer supported and close the socket
+async fn reject_protocol_version(socket: WebSocket, requested: u8, min_version: u8) {
+    let (mut ws_sender, _) = socket.split();
+    let error_msg = ServerMessage::error(
+        "PROTOCOL_TOO_OLD",
+        format!(
+            "Protocol version {} is no longer supported (minimum {}, current {})",
+            requested, min_version, PROTOCOL_VERSION
+        ),
+    );
+    if let Ok(json) = error_msg.to_json() {
+        let _ = ws_sender.send(Message::Text(json.into())).await;
+    }
+    let _ = ws_sender
+        .send(Message::Close(Some(CloseFrame {
+            code: PROTOCOL_TOO_OLD_CLOSE_CODE,
+            reason: "protocol version too old".into(),
+        })))
+        .await;
+}
+
 /// Handle an established WebSocket connection
 #[tracing::instrument(
     name = "ws.connection",
-    skip(socket, state, claims),
+    skip(socket, state, claims, metadata),
     fields(
         user_id = %claims.sub,
         otel.kind = "server"
     )
 )]
-async fn handle_socket(socket: WebSocket, state: AppState, claims: Claims) {
+async fn handle_socket(socket: WebSocket, state: AppState, claims: Claims, metadata: ConnectionMetadata) {
     let user_id = claims.sub.clone();
     let tenant_id = claims.tenant_id().to_string();
     let roles = claims.roles.clone();
@@ -107,14 +187,17 @@ async fn handle_socket(socket: WebSocket, state: AppState, claims: Claims) {
     let (tx, mut rx) = mpsc::channel::<OutboundMessage>(CHANNEL_BUFFER_SIZE);
 
     // Register connection with limit checking
-    let handle = match state.connection_manager.register(user_id.clone(), tenant_id.clone(), roles, tx) {
+    let handle = match state
+        .connection_manager
+        .register_with_metadata(user_id.clone(), tenant_id.clone(), roles, tx, metadata)
+    {
         Ok(h) => h,
         Err(e) => {
             tracing::warn!(user_id = %user_id, error = %e, "Connection rejected");
             // Send error and close
             let (mut ws_sender, _) = socket.split();
             let error_msg = ServerMessage::error("CONNECTION_LIMIT", e.to_string());
-            if let Ok(json) = serde_json::to_string(&error_msg) {
+            if let Ok(json) = error_msg.to_json() {
                 let _ = ws_sender.send(Message::Text(json.into())).await;
             }
             let _ = ws_sender.close().await;
@@ -125,24 +208,24 @@ async fn handle_socket(socket: WebSocket, state: AppState, claims: Claims) {
 
     // Record connection opened metric
     WS_CONNECTIONS_OPENED.inc();
-
-    // Register session in cluster store (for distributed deployments)
-    if state.session_store.is_enabled() {
-        let session_info = SessionInfo {
-            connection_id,
-            user_id: user_id.clone(),
-            tenant_id: tenant_id.clone(),
-            server_id: state.session_store.server_id().to_string(),
-            connected_at: chrono::Utc::now().timestamp(),
-            channels: vec![],
-        };
-        if let Err(e) = state.session_store.register_session(&session_info).await {
-            tracing::warn!(
-                connection_id = %connection_id,
-                error = %e,
-                "Failed to register session in cluster store"
-            );
-        }
+    state.tenant_manager.record_connection(&tenant_id);
+
+    // Register session in the session store (local or cluster-wide)
+    let session_info = SessionInfo {
+        connection_id,
+        user_id: user_id.clone(),
+        tenant_id: tenant_id.clone(),
+        server_id: state.session_store.server_id().to_string(),
+        connected_at: chrono::Utc::now().timestamp(),
+        channels: vec![],
+        server_tags: state.settings.cluster.server_tags.clone(),
+    };
+    if let Err(e) = state.session_store.register_session(&session_info).await {
+        tracing::warn!(
+            connection_id = %connection_id,
+            error = %e,
+            "Failed to register session in cluster store"
+        );
     }
 
     tracing::info!(
@@ -155,28 +238,15 @@ async fn handle_socket(socket: WebSocket, state: AppState, claims: Claims) {
     // Replay any queued messages for this user (tenant-scoped key)
     let queue_key = crate::auth::tenant_scoped_key(&tenant_id, &user_id);
     if state.queue_backend.is_enabled() {
-        match state.queue_backend.drain(&queue_key).await {
-            Ok(drain_result) => {
-                let mut replayed = 0;
-                let mut failed = 0;
-
-                for stored_msg in drain_result.messages {
-                    let msg = OutboundMessage::Raw(ServerMessage::Notification {
-                        event: stored_msg.event,
-                    });
-                    match handle.sender.send(msg).await {
-                        Ok(_) => replayed += 1,
-                        Err(_) => failed += 1,
-                    }
-                }
-
-                if replayed > 0 || drain_result.expired > 0 {
+        match state.queue_backend.replay(&queue_key, &handle.sender).await {
+            Ok(result) => {
+                if result.replayed > 0 || result.expired > 0 {
                     tracing::info!(
                         connection_id = %connection_id,
                         user_id = %user_id,
-                        replayed = replayed,
-                        expired = drain_result.expired,
-                        failed = failed,
+                        replayed = result.replayed,
+                        expired = result.expired,
+                        failed = result.failed,
                         "Replayed queued messages on reconnect"
                     );
                 }
@@ -196,19 +266,43 @@ async fn handle_socket(socket: WebSocket, state: AppState, claims: Claims) {
     let (mut ws_sender, mut ws_receiver) = socket.split();
 
     // Task for sending messages from channel to WebSocket
+    let accepts_msgpack = handle.accepts_msgpack();
+    let protocol_version = handle.metadata.protocol_version;
+    // Close frame requested by the receive task, sent after pending messages are flushed
+    let (close_tx, mut close_rx) = tokio::sync::oneshot::channel::<CloseFrame>();
     let send_task = tokio::spawn(async move {
-        while let Some(msg) = rx.recv().await {
-            // Convert OutboundMessage to JSON string
-            // Pre-serialized messages avoid the serialization cost here
-            let text = match msg.to_json() {
-                Ok(t) => t,
+        loop {
+            let msg = tokio::select! {
+                msg = rx.recv() => match msg {
+                    Some(msg) => msg,
+                    None => break,
+                },
+                close = &mut close_rx => {
+                    if let Ok(frame) = close {
+                        let _ = ws_sender.send(Message::Close(Some(frame))).await;
+                    }
+                    break;
+                }
+            };
+            let is_heartbeat = msg.is_heartbeat();
+            let frame = match encode_frame(msg, accepts_msgpack, protocol_version) {
+                Ok(frame) => frame,
                 Err(e) => {
                     tracing::error!(error = %e, "Failed to serialize message");
                     continue;
                 }
             };
+            let is_binary = matches!(frame, Message::Binary(_));
 
-            if ws_sender.send(Message::Text(text.into())).await.is_err() {
+            if ws_sender.send(frame).await.is_err() {
+                break;
+            }
+            if is_binary {
+                WS_BINARY_MESSAGES_SENT_TOTAL.inc();
+            }
+            // Follow heartbeats with a ping frame; the client's automatic pong is what
+            // clears the connection's missed-heartbeat count
+            if is_heartbeat && ws_sender.send(Message::Ping(Default::default())).await.is_err() {
                 break;
             }
         }
@@ -225,6 +319,21 @@ async fn handle_socket(socket: WebSocket, state: AppState, claims: Claims) {
                         break;
                     }
                 }
+                Err(e) if is_frame_too_large(&e) => {
+                    WS_FRAME_TOO_LARGE_TOTAL.inc();
+                    tracing::warn!(
+                        connection_id = %handle_clone.id,
+                        user_id = %handle_clone.user_id,
+                        max_frame_size_bytes = state_clone.settings.websocket.max_frame_size_bytes,
+                        error = %e,
+                        "WebSocket frame too large, closing connection"
+                    );
+                    let _ = close_tx.send(CloseFrame {
+                        code: FRAME_TOO_LARGE_CLOSE_CODE,
+                        reason: "frame too large".into(),
+                    });
+                    break;
+                }
                 Err(e) => {
                     tracing::warn!(error = %e, "WebSocket receive error");
                     break;
@@ -245,16 +354,15 @@ async fn handle_socket(socket: WebSocket, state: AppState, claims: Claims) {
 
     // Unregister connection
     state.connection_manager.unregister(connection_id).await;
+    state.tenant_manager.record_disconnection(&tenant_id);
 
     // Unregister session from cluster store
-    if state.session_store.is_enabled() {
-        if let Err(e) = state.session_store.unregister_session(connection_id).await {
-            tracing::warn!(
-                connection_id = %connection_id,
-                error = %e,
-                "Failed to unregister session from cluster store"
-            );
-        }
+    if let Err(e) = state.session_store.unregister_session(connection_id).await {
+        tracing::warn!(
+            connection_id = %connection_id,
+            error = %e,
+            "Failed to unregister session from cluster store"
+        );
     }
 
     // Record connection closed and duration metrics
@@ -270,6 +378,42 @@ async fn handle_socket(socket: WebSocket, state: AppState, claims: Claims) {
     );
 }
 
+/// Whether a receive error means the client sent a frame or message above the size limit
+fn is_frame_too_large(error: &axum::Error) -> bool {
+    std::error::Error::source(error)
+        .and_then(|source| source.downcast_ref::<tungstenite::Error>())
+        .is_some_and(|e| {
+            matches!(
+                e,
+                tungstenite::Error::Capacity(tungstenite::error::CapacityError::MessageTooLong { .. })
+            )
+        })
+}
+
+/// Encode an outbound message as a WebSocket frame.
+///
+/// Messages are first shaped for the connection's protocol version. MessagePack
+/// notifications become binary frames when the connection accepts them; everything
+/// else is sent as JSON text (pre-serialized messages skip serialization).
+fn encode_frame(
+    msg: OutboundMessage,
+    accepts_msgpack: bool,
+    protocol_version: u8,
+) -> Result<Message, String> {
+    match msg.for_protocol(protocol_version, accepts_msgpack)? {
+        OutboundMessage::Binary(bytes) => Ok(Message::Binary(bytes.to_vec().into())),
+        OutboundMessage::Raw(message) if accepts_msgpack && message.prefers_msgpack() => {
+            encode_msgpack(&message)
+                .map(|bytes| Message::Binary(bytes.into()))
+                .map_err(|e| e.to_string())
+        }
+        msg => msg
+            .to_json()
+            .map(|text| Message::Text(text.into()))
+            .map_err(|e| e.to_string()),
+    }
+}
+
 /// Process a received WebSocket message
 /// Returns false if the connection should be closed
 async fn process_message(
@@ -314,6 +458,7 @@ async fn process_message(
         }
         Message::Pong(_) => {
             handle.update_activity();
+            WsMessageMetrics::record_pong();
             true
         }
         Message::Close(_) => {
@@ -377,7 +522,12 @@ async fn handle_ack(
         return;
     }
 
-    let acknowledged = state.ack_backend.acknowledge(notification_id, &handle.user_id).await;
+    // Pending ACKs are keyed by tenant-namespaced user IDs, so a user of one tenant
+    // cannot acknowledge a notification sent to the same user ID in another tenant
+    let acknowledged = state
+        .ack_backend
+        .acknowledge(notification_id, &handle.namespaced_user_id())
+        .await;
 
     if acknowledged {
         // Send confirmation back to client
@@ -421,10 +571,26 @@ async fn handle_subscribe(
                 channel = %channel,
                 "Invalid channel name"
             );
+            WsMessageMetrics::record_subscription_rejected("invalid_name");
             errors.push(format!("Invalid channel name: {}", channel));
             continue;
         }
 
+        // Enforce tenant channel policy (rejects this channel only, connection stays open)
+        if let Err(e) = tenant_ctx.validate_channel_name(&channel) {
+            tracing::warn!(
+                connection_id = %handle.id,
+                channel = %channel,
+                reason = e.reason(),
+                "Channel rejected by tenant policy"
+            );
+            WsMessageMetrics::record_subscription_rejected(e.reason());
+            let _ = handle
+                .send(ServerMessage::error("CHANNEL_NOT_ALLOWED", e.to_string()))
+                .await;
+            continue;
+        }
+
         // Namespace channel for tenant isolation
         let namespaced = tenant_ctx.namespace_channel(&channel);
 
@@ -455,15 +621,13 @@ async fn handle_subscribe(
         let _ = handle.send(ServerMessage::subscribed(subscribed.clone())).await;
 
         // Update session channels in cluster store
-        if state.session_store.is_enabled() {
-            let current_channels: Vec<String> = handle.subscriptions.read().await.iter().cloned().collect();
-            if let Err(e) = state.session_store.update_session_channels(handle.id, current_channels).await {
-                tracing::warn!(
-                    connection_id = %handle.id,
-                    error = %e,
-                    "Failed to update session channels in cluster store"
-                );
-            }
+        let current_channels: Vec<String> = handle.subscriptions.read().await.iter().cloned().collect();
+        if let Err(e) = state.session_store.update_session_channels(handle.id, current_channels).await {
+            tracing::warn!(
+                connection_id = %handle.id,
+                error = %e,
+                "Failed to update session channels in cluster store"
+            );
         }
     }
 
@@ -513,15 +677,13 @@ async fn handle_unsubscribe(
         let _ = handle.send(ServerMessage::unsubscribed(unsubscribed)).await;
 
         // Update session channels in cluster store
-        if state.session_store.is_enabled() {
-            let current_channels: Vec<String> = handle.subscriptions.read().await.iter().cloned().collect();
-            if let Err(e) = state.session_store.update_session_channels(handle.id, current_channels).await {
-                tracing::warn!(
-                    connection_id = %handle.id,
-                    error = %e,
-                    "Failed to update session channels in cluster store"
-                );
-            }
+        let current_channels: Vec<String> = handle.subscriptions.read().await.iter().cloned().collect();
+        if let Err(e) = state.session_store.update_session_channels(handle.id, current_channels).await {
+            tracing::warn!(
+                connection_id = %handle.id,
+                error = %e,
+                "Failed to update session channels in cluster store"
+            );
         }
     }
 }
@@ -542,6 +704,68 @@ fn is_valid_channel_name(name: &str) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::notification::NotificationBuilder;
+
+    #[test]
+    fn test_encode_frame_uses_binary_only_for_opted_in_msgpack() {
+        let msgpack = ServerMessage::Notification {
+            event: NotificationBuilder::new("order.created", "test")
+                .payload_encoding(PayloadEncoding::Msgpack)
+                .build(),
+        };
+        let json = ServerMessage::Notification {
+            event: NotificationBuilder::new("order.created", "test").build(),
+        };
+
+        let frame = encode_frame(OutboundMessage::Raw(msgpack.clone()), true, PROTOCOL_VERSION).unwrap();
+        assert!(matches!(frame, Message::Binary(_)));
+        // Clients that did not opt in get JSON text
+        let frame = encode_frame(OutboundMessage::Raw(msgpack), false, PROTOCOL_VERSION).unwrap();
+        assert!(matches!(frame, Message::Text(_)));
+        let frame = encode_frame(OutboundMessage::Raw(json), true, PROTOCOL_VERSION).unwrap();
+        assert!(matches!(frame, Message::Text(_)));
+        let frame = encode_frame(OutboundMessage::Raw(ServerMessage::Pong), true, PROTOCOL_VERSION).unwrap();
+        assert!(matches!(frame, Message::Text(_)));
+    }
+
+    #[test]
+    fn test_negotiate_protocol_version() {
+        let mut config = WebSocketConfig::default();
+        assert_eq!(negotiate_protocol_version(None, &config), Ok(1));
+        assert_eq!(negotiate_protocol_version(Some(1), &config), Ok(1));
+        // Clients newer than the server are served the newest configured version
+        assert_eq!(negotiate_protocol_version(Some(9), &config), Ok(PROTOCOL_VERSION));
+        assert_eq!(negotiate_protocol_version(Some(0), &config), Err(0));
+
+        config.min_client_protocol_version = 2;
+        config.max_client_protocol_version = 3;
+        assert_eq!(negotiate_protocol_version(None, &config), Err(1));
+        assert_eq!(negotiate_protocol_version(Some(2), &config), Ok(2));
+        assert_eq!(negotiate_protocol_version(Some(5), &config), Ok(3));
+    }
+
+    #[test]
+    fn test_encode_frame_for_older_protocol_keeps_frame_type() {
+        let msgpack = ServerMessage::Notification {
+            event: NotificationBuilder::new("order.created", "test")
+                .payload_encoding(PayloadEncoding::Msgpack)
+                .build(),
+        };
+
+        let Message::Binary(bytes) = encode_frame(OutboundMessage::Raw(msgpack), true, 0).unwrap()
+        else {
+            panic!("Expected binary frame");
+        };
+        let value: serde_json::Value = rmp_serde::from_slice(&bytes).unwrap();
+        assert_eq!(value["v"], 0);
+        assert_eq!(value["event_type"], "order.created");
+
+        let heartbeat = OutboundMessage::preserialized(&ServerMessage::Heartbeat).unwrap();
+        let Message::Text(text) = encode_frame(heartbeat, false, 0).unwrap() else {
+            panic!("Expected text frame");
+        };
+        assert_eq!(text.as_str(), r#"{"type":"heartbeat","v":0}"#);
+    }
 
     #[test]
     fn test_valid_channel_names() {
@@ -564,4 +788,54 @@ mod tests {
         // Too long
         assert!(!is_valid_channel_name(&"a".repeat(65)));
     }
+
+    #[tokio::test]
+    async fn test_oversized_frame_closes_connection_with_1009() {
+        use std::net::SocketAddr;
+
+        use serde_json::json;
+        use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+        use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+        use tokio_tungstenite::tungstenite::Message as ClientMessage;
+
+        use crate::api::test_support::{bearer_token, test_claims, test_state_with};
+        use crate::server::create_app;
+
+        let state = test_state_with(json!({
+            "websocket": { "max_frame_size_bytes": 64 * 1024 }
+        }))
+        .await;
+        let authorization = bearer_token(&state, &test_claims("user-1"));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = create_app(state).into_make_service_with_connect_info::<SocketAddr>();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let mut request = format!("ws://{}/ws", addr).into_client_request().unwrap();
+        request
+            .headers_mut()
+            .insert(header::AUTHORIZATION, authorization.parse().unwrap());
+        let (mut socket, _) = tokio_tungstenite::connect_async(request).await.unwrap();
+        let before = WS_FRAME_TOO_LARGE_TOTAL.get();
+
+        socket
+            .send(ClientMessage::Text("x".repeat(128 * 1024).into()))
+            .await
+            .unwrap();
+
+        let close = tokio::time::timeout(std::time::Duration::from_secs(5), async {
+            while let Some(Ok(message)) = socket.next().await {
+                if let ClientMessage::Close(frame) = message {
+                    return frame;
+                }
+            }
+            None
+        })
+        .await
+        .expect("connection was not closed")
+        .expect("close frame");
+        assert_eq!(close.code, CloseCode::Size);
+        assert_eq!(u16::from(close.code), FRAME_TOO_LARGE_CLOSE_CODE);
+        assert!(WS_FRAME_TOO_LARGE_TOTAL.get() > before);
+    }
 }
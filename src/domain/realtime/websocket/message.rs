@@ -1,9 +1,73 @@
 use std::sync::Arc;
 
-use serde::{Deserialize, Serialize};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize, Serializer};
 use uuid::Uuid;
 
-use crate::notification::NotificationEvent;
+use crate::notification::{NotificationEvent, NotificationMetadata, PayloadEncoding};
+
+/// Current server protocol version, written as `v` on every server message.
+///
+/// Bump this when a server message gains a field, and record the field in
+/// [`FIELD_HISTORY`] so connections negotiated at an older version keep
+/// receiving the shape they were written against. See `PROTOCOL.md`.
+pub const PROTOCOL_VERSION: u8 = 2;
+
+/// A server message field added after the first protocol version
+#[derive(Debug, Clone, Copy)]
+pub struct FieldIntroduction {
+    /// Message `type` the field belongs to
+    pub message_type: &'static str,
+    /// Top-level field name
+    pub field: &'static str,
+    /// Protocol version that introduced the field
+    pub since: u8,
+}
+
+/// Fields introduced after protocol version 1, oldest first
+pub const FIELD_HISTORY: &[FieldIntroduction] = &[FieldIntroduction {
+    message_type: "notification",
+    field: "tags",
+    since: 2,
+}];
+
+/// Versioned wire envelope: the `v` field followed by the flattened message
+#[derive(Serialize)]
+struct Envelope<'a> {
+    v: u8,
+    #[serde(flatten)]
+    message: &'a ServerMessage,
+}
+
+impl<'a> Envelope<'a> {
+    fn current(message: &'a ServerMessage) -> Self {
+        Self {
+            v: PROTOCOL_VERSION,
+            message,
+        }
+    }
+}
+
+/// Rewrite a serialized server message for an older protocol version.
+///
+/// Sets `v` to the target version and removes every field that `history`
+/// records as introduced after it.
+pub fn downgrade_message(value: &mut serde_json::Value, version: u8, history: &[FieldIntroduction]) {
+    let Some(object) = value.as_object_mut() else {
+        return;
+    };
+    let message_type = object
+        .get("type")
+        .and_then(|t| t.as_str())
+        .unwrap_or_default()
+        .to_string();
+    for introduction in history {
+        if introduction.since > version && introduction.message_type == message_type {
+            object.remove(introduction.field);
+        }
+    }
+    object.insert("v".to_string(), version.into());
+}
 
 /// Messages sent from client to server
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -18,28 +82,105 @@ pub enum ClientMessage {
 /// Outbound message wrapper for efficient multi-send scenarios
 /// When sending the same message to many connections, pre-serializing once
 /// and sharing the Arc<str> avoids repeated serialization overhead
+// Raw notifications dominate traffic, so boxing the large variant would only add an allocation
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug, Clone)]
 pub enum OutboundMessage {
     /// Message that will be serialized when sent
     Raw(ServerMessage),
     /// Pre-serialized message (shared across multiple sends via Arc)
     Serialized(Arc<str>),
+    /// Pre-encoded MessagePack message, sent as a binary WebSocket frame
+    Binary(Arc<[u8]>),
+}
+
+/// Encode a server message as MessagePack.
+///
+/// Uses named fields and human-readable forms so ids and timestamps match
+/// the JSON representation instead of being packed as raw bytes.
+pub fn encode_msgpack(message: &ServerMessage) -> Result<Vec<u8>, rmp_serde::encode::Error> {
+    let mut bytes = Vec::new();
+    let mut serializer = rmp_serde::Serializer::new(&mut bytes)
+        .with_struct_map()
+        .with_human_readable();
+    Envelope::current(message).serialize(&mut serializer)?;
+    Ok(bytes)
+}
+
+lazy_static! {
+    /// JSON form of a heartbeat, as produced by `OutboundMessage::preserialized`
+    static ref HEARTBEAT_JSON: String = ServerMessage::Heartbeat.to_json().unwrap_or_default();
 }
 
 impl OutboundMessage {
     /// Create a pre-serialized message from a ServerMessage
     pub fn preserialized(message: &ServerMessage) -> Result<Self, serde_json::Error> {
-        let json = serde_json::to_string(message)?;
+        let json = message.to_json()?;
         Ok(Self::Serialized(Arc::from(json)))
     }
 
+    /// Create a pre-encoded MessagePack message from a ServerMessage
+    pub fn msgpack(message: &ServerMessage) -> Result<Self, rmp_serde::encode::Error> {
+        Ok(Self::Binary(Arc::from(encode_msgpack(message)?)))
+    }
+
     /// Convert to JSON string, either by returning the pre-serialized string
     /// or by serializing the raw message.
     /// For Serialized variant, creates a String from the Arc<str> without re-serialization.
+    /// Binary messages have no JSON form and return an error.
     pub fn to_json(&self) -> Result<String, serde_json::Error> {
         match self {
-            Self::Raw(msg) => serde_json::to_string(msg),
+            Self::Raw(msg) => msg.to_json(),
             Self::Serialized(json) => Ok(json.to_string()),
+            Self::Binary(_) => Err(serde::ser::Error::custom(
+                "MessagePack message has no JSON representation",
+            )),
+        }
+    }
+
+    /// Whether this is a heartbeat, raw or pre-serialized
+    pub fn is_heartbeat(&self) -> bool {
+        match self {
+            Self::Raw(ServerMessage::Heartbeat) => true,
+            Self::Serialized(json) => **json == **HEARTBEAT_JSON,
+            _ => false,
+        }
+    }
+
+    /// Re-encode the message for a connection negotiated at `version`.
+    ///
+    /// Messages for the current version are returned unchanged. Older versions
+    /// are downgraded through [`FIELD_HISTORY`]; the result is binary if the
+    /// message would have been sent as MessagePack, JSON text otherwise.
+    pub fn for_protocol(self, version: u8, accepts_msgpack: bool) -> Result<Self, String> {
+        if version >= PROTOCOL_VERSION {
+            return Ok(self);
+        }
+
+        let (mut value, binary) = match &self {
+            Self::Raw(message) => (
+                serde_json::to_value(Envelope::current(message)).map_err(|e| e.to_string())?,
+                accepts_msgpack && message.prefers_msgpack(),
+            ),
+            Self::Serialized(json) => (
+                serde_json::from_str(json).map_err(|e| e.to_string())?,
+                false,
+            ),
+            Self::Binary(bytes) => (
+                rmp_serde::from_slice(bytes).map_err(|e| e.to_string())?,
+                true,
+            ),
+        };
+        downgrade_message(&mut value, version, FIELD_HISTORY);
+
+        if binary {
+            rmp_serde::to_vec_named(&value)
+                .map(|bytes| Self::Binary(Arc::from(bytes)))
+                .map_err(|e| e.to_string())
+        } else {
+            serde_json::to_string(&value)
+                .map(|json| Self::Serialized(Arc::from(json)))
+                .map_err(|e| e.to_string())
         }
     }
 }
@@ -51,12 +192,14 @@ impl From<ServerMessage> for OutboundMessage {
 }
 
 /// Messages sent from server to client
+// Notifications dominate traffic, so boxing the event would only add an allocation
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum ServerMessage {
     #[serde(rename = "notification")]
     Notification {
-        #[serde(flatten)]
+        #[serde(flatten, serialize_with = "serialize_client_event")]
         event: NotificationEvent,
     },
     #[serde(rename = "subscribed")]
@@ -91,9 +234,43 @@ pub enum ServerMessage {
         #[serde(skip_serializing_if = "Option::is_none")]
         reconnect_after_seconds: Option<u64>,
     },
+    /// A channel the connection was subscribed to was deleted; the subscription has ended
+    #[serde(rename = "channel_deleted")]
+    ChannelDeleted { channel: String },
+}
+
+/// Client-visible view of a notification event (omits server-side headers)
+#[derive(Serialize)]
+struct ClientNotificationEvent<'a> {
+    id: &'a Uuid,
+    occurred_at: &'a chrono::DateTime<chrono::Utc>,
+    event_type: &'a str,
+    payload: &'a serde_json::Value,
+    metadata: &'a NotificationMetadata,
+    tags: &'a [String],
+}
+
+fn serialize_client_event<S: Serializer>(
+    event: &NotificationEvent,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    ClientNotificationEvent {
+        id: &event.id,
+        occurred_at: &event.occurred_at,
+        event_type: &event.event_type,
+        payload: &event.payload,
+        metadata: &event.metadata,
+        tags: &event.tags,
+    }
+    .serialize(serializer)
 }
 
 impl ServerMessage {
+    /// Serialize with the current protocol version envelope
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(&Envelope::current(self))
+    }
+
     pub fn error(code: impl Into<String>, message: impl Into<String>) -> Self {
         Self::Error {
             code: code.into(),
@@ -119,4 +296,191 @@ impl ServerMessage {
             reconnect_after_seconds,
         }
     }
+
+    pub fn channel_deleted(channel: impl Into<String>) -> Self {
+        Self::ChannelDeleted {
+            channel: channel.into(),
+        }
+    }
+
+    /// Whether this is a notification to send as MessagePack to connections that accept it
+    pub fn prefers_msgpack(&self) -> bool {
+        matches!(
+            self,
+            Self::Notification { event } if event.payload_encoding == PayloadEncoding::Msgpack
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notification::NotificationBuilder;
+
+    #[test]
+    fn test_notification_serialization_omits_headers() {
+        let event = NotificationBuilder::new("order.created", "test-service")
+            .payload(serde_json::json!({"order_id": "123"}))
+            .header("x-routing-hint", "eu-west")
+            .build();
+        let message = ServerMessage::Notification { event };
+
+        let json = OutboundMessage::preserialized(&message).unwrap().to_json().unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(value["type"], "notification");
+        assert_eq!(value["event_type"], "order.created");
+        assert_eq!(value["payload"]["order_id"], "123");
+        assert!(value.get("headers").is_none());
+        assert!(!json.contains("x-routing-hint"));
+    }
+
+    #[test]
+    fn test_is_heartbeat() {
+        assert!(OutboundMessage::Raw(ServerMessage::Heartbeat).is_heartbeat());
+        assert!(OutboundMessage::preserialized(&ServerMessage::Heartbeat)
+            .unwrap()
+            .is_heartbeat());
+        let pong = OutboundMessage::preserialized(&ServerMessage::Pong).unwrap();
+        assert!(!pong.is_heartbeat());
+    }
+
+    #[test]
+    fn test_msgpack_notification_round_trip() {
+        let event = NotificationBuilder::new("order.created", "test-service")
+            .payload(serde_json::json!({"order_id": "123", "items": [1, 2]}))
+            .payload_encoding(PayloadEncoding::Msgpack)
+            .build();
+        let message = ServerMessage::Notification { event };
+        assert!(message.prefers_msgpack());
+
+        let OutboundMessage::Binary(bytes) = OutboundMessage::msgpack(&message).unwrap() else {
+            panic!("Expected binary message");
+        };
+        let value: serde_json::Value = rmp_serde::from_slice(&bytes).unwrap();
+        assert_eq!(value["type"], "notification");
+        assert_eq!(value["event_type"], "order.created");
+        assert_eq!(value["payload"]["items"][1], 2);
+        assert_eq!(value["v"], PROTOCOL_VERSION);
+        assert!(OutboundMessage::Binary(bytes).to_json().is_err());
+    }
+
+    #[test]
+    fn test_every_message_carries_protocol_version() {
+        let messages = vec![
+            ServerMessage::Notification {
+                event: NotificationBuilder::new("order.created", "test").build(),
+            },
+            ServerMessage::subscribed(vec!["orders".to_string()]),
+            ServerMessage::unsubscribed(vec!["orders".to_string()]),
+            ServerMessage::Pong,
+            ServerMessage::Heartbeat,
+            ServerMessage::acked(Uuid::new_v4()),
+            ServerMessage::error("CODE", "message"),
+            ServerMessage::shutdown("maintenance", Some(5)),
+        ];
+
+        for message in messages {
+            let value: serde_json::Value =
+                serde_json::from_str(&message.to_json().unwrap()).unwrap();
+            assert_eq!(value["v"], PROTOCOL_VERSION, "missing version on {:?}", message);
+            assert!(value["type"].is_string());
+
+            // The envelope stays readable by clients deserializing ServerMessage
+            let parsed: ServerMessage = serde_json::from_value(value).unwrap();
+            assert_eq!(parsed.to_json().unwrap(), message.to_json().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_current_wire_format() {
+        let json = ServerMessage::shutdown("maintenance", None).to_json().unwrap();
+        assert_eq!(json, r#"{"v":2,"type":"shutdown","reason":"maintenance"}"#);
+
+        let json = ServerMessage::subscribed(vec!["a".to_string()]).to_json().unwrap();
+        assert_eq!(json, r#"{"v":2,"type":"subscribed","payload":["a"]}"#);
+    }
+
+    #[test]
+    fn test_notification_tags_serialize_as_array() {
+        let tagged = ServerMessage::Notification {
+            event: NotificationBuilder::new("badge.updated", "test")
+                .tag("badge_count")
+                .tag("user_123")
+                .build(),
+        };
+        let value: serde_json::Value = serde_json::from_str(&tagged.to_json().unwrap()).unwrap();
+        assert_eq!(value["tags"], serde_json::json!(["badge_count", "user_123"]));
+
+        let untagged = ServerMessage::Notification {
+            event: NotificationBuilder::new("order.created", "test").build(),
+        };
+        let value: serde_json::Value = serde_json::from_str(&untagged.to_json().unwrap()).unwrap();
+        assert_eq!(value["tags"], serde_json::json!([]));
+
+        // Version 1 clients do not know the field
+        let OutboundMessage::Serialized(json) = OutboundMessage::preserialized(&tagged)
+            .unwrap()
+            .for_protocol(1, false)
+            .unwrap()
+        else {
+            panic!("Expected serialized message");
+        };
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["v"], 1);
+        assert!(value.get("tags").is_none());
+    }
+
+    #[test]
+    fn test_downgrade_strips_fields_introduced_later() {
+        let history = [
+            FieldIntroduction {
+                message_type: "shutdown",
+                field: "reconnect_after_seconds",
+                since: 2,
+            },
+            FieldIntroduction {
+                message_type: "error",
+                field: "retryable",
+                since: 3,
+            },
+        ];
+        let mut shutdown = serde_json::json!({
+            "v": 3, "type": "shutdown", "reason": "maintenance", "reconnect_after_seconds": 5
+        });
+        let mut error = serde_json::json!({
+            "v": 3, "type": "error", "code": "X", "message": "m", "retryable": true
+        });
+
+        let mut v2_error = error.clone();
+        downgrade_message(&mut v2_error, 2, &history);
+        assert_eq!(v2_error["v"], 2);
+        assert!(v2_error.get("retryable").is_none());
+        assert_eq!(v2_error["code"], "X");
+
+        downgrade_message(&mut shutdown, 1, &history);
+        downgrade_message(&mut error, 1, &history);
+        assert_eq!(
+            shutdown,
+            serde_json::json!({"v": 1, "type": "shutdown", "reason": "maintenance"})
+        );
+        assert_eq!(
+            error,
+            serde_json::json!({"v": 1, "type": "error", "code": "X", "message": "m"})
+        );
+    }
+
+    #[test]
+    fn test_for_protocol_keeps_current_version_untouched() {
+        let outbound = OutboundMessage::preserialized(&ServerMessage::Pong).unwrap();
+        let OutboundMessage::Serialized(before) = outbound.clone() else {
+            panic!("Expected serialized message");
+        };
+        let OutboundMessage::Serialized(after) =
+            outbound.for_protocol(PROTOCOL_VERSION, false).unwrap()
+        else {
+            panic!("Expected serialized message");
+        };
+        assert!(Arc::ptr_eq(&before, &after));
+    }
 }
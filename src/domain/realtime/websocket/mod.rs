@@ -2,4 +2,7 @@ mod handler;
 mod message;
 
 pub use handler::ws_handler;
-pub use message::{ClientMessage, OutboundMessage, ServerMessage};
+pub use message::{
+    downgrade_message, ClientMessage, FieldIntroduction, OutboundMessage, ServerMessage,
+    FIELD_HISTORY, PROTOCOL_VERSION,
+};
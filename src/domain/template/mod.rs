@@ -4,6 +4,7 @@
 //! - Template definition with variable placeholders ({{variable}})
 //! - In-memory template storage with CRUD operations
 //! - Variable substitution engine for rendering templates
+//! - Template inheritance via `extends` (child payload deep-merged over the parent)
 //!
 //! # Example
 //!
@@ -21,6 +22,9 @@
 //!     }),
 //!     default_priority: Priority::High,
 //!     default_ttl: Some(86400),
+//!     strict_mode: false,
+//!     extends: None,
+//!     variable_schema: None,
 //! };
 //!
 //! store.create(template)?;
@@ -39,8 +43,10 @@ mod substitution;
 mod types;
 
 pub use store::{create_template_store, TemplateStore};
-pub use substitution::substitute_variables;
+pub use substitution::{
+    substitute_variables, substitute_variables_with_mode, CompiledTemplate, SubstitutionMode,
+};
 pub use types::{
-    CreateTemplateRequest, RenderedTemplate, Template, TemplateError, TemplateListResponse,
-    TemplateResult, UpdateTemplateRequest,
+    CreateTemplateRequest, ImportConflictStrategy, ImportResult, RenderedTemplate, Template,
+    TemplateError, TemplateListResponse, TemplateResult, UpdateTemplateRequest,
 };
@@ -1,18 +1,30 @@
 //! Template storage with CRUD operations
 
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 
 use chrono::Utc;
+use dashmap::mapref::entry::Entry;
 use dashmap::DashMap;
 
-use super::substitution::substitute_variables;
+use super::substitution::CompiledTemplate;
 use super::types::{
-    RenderedTemplate, Template, TemplateError, TemplateResult, UpdateTemplateRequest,
+    ImportConflictStrategy, ImportResult, RenderedTemplate, Template, TemplateError,
+    TemplateResult, UpdateTemplateRequest,
 };
 
+/// Maximum number of `extends` hops followed when resolving a template
+const MAX_INHERITANCE_DEPTH: usize = 5;
+
 /// In-memory template storage
 pub struct TemplateStore {
     templates: DashMap<String, Template>,
+    /// Compiled templates by ID, tagged with the `generation` they were built from
+    compiled: DashMap<String, (u64, Arc<CompiledTemplate>)>,
+    /// Bumped after every change, so compiled templates that may depend on a changed
+    /// template (directly or through `extends`) are rebuilt
+    generation: AtomicU64,
 }
 
 impl Default for TemplateStore {
@@ -26,12 +38,15 @@ impl TemplateStore {
     pub fn new() -> Self {
         Self {
             templates: DashMap::new(),
+            compiled: DashMap::new(),
+            generation: AtomicU64::new(0),
         }
     }
 
     /// Create a new template
     pub fn create(&self, template: Template) -> TemplateResult<Template> {
         template.validate()?;
+        template.validate_extends(|parent| self.exists(parent))?;
 
         if self.templates.contains_key(&template.id) {
             return Err(TemplateError::AlreadyExists(template.id));
@@ -39,6 +54,7 @@ impl TemplateStore {
 
         let id = template.id.clone();
         self.templates.insert(id.clone(), template);
+        self.invalidate_compiled();
 
         Ok(self.templates.get(&id).unwrap().clone())
     }
@@ -59,6 +75,29 @@ impl TemplateStore {
             .collect()
     }
 
+    /// Templates whose ID, name, event type or description contains `query`
+    /// (case-insensitive). Exact ID matches come first, then templates with a field
+    /// starting with `query`, then the remaining matches; ties are ordered by ID.
+    pub fn search(&self, query: &str) -> Vec<Template> {
+        self.search_with_prefix(query, "")
+    }
+
+    /// Like [`search`](Self::search), limited to IDs starting with `id_prefix` (such as a
+    /// tenant's `{tenant}:`), which is ignored when ranking ID matches
+    pub fn search_with_prefix(&self, query: &str, id_prefix: &str) -> Vec<Template> {
+        let query = query.to_lowercase();
+        let mut matches: Vec<(u8, Template)> = self
+            .templates
+            .iter()
+            .filter_map(|entry| {
+                let id = entry.key().strip_prefix(id_prefix)?;
+                search_rank(entry.value(), id, &query).map(|rank| (rank, entry.value().clone()))
+            })
+            .collect();
+        matches.sort_by(|(a_rank, a), (b_rank, b)| a_rank.cmp(b_rank).then_with(|| a.id.cmp(&b.id)));
+        matches.into_iter().map(|(_, template)| template).collect()
+    }
+
     /// Update an existing template
     pub fn update(&self, id: &str, updates: UpdateTemplateRequest) -> TemplateResult<Template> {
         let mut template = self.get(id)?;
@@ -87,10 +126,24 @@ impl TemplateStore {
             template.description = description;
         }
 
+        if let Some(strict_mode) = updates.strict_mode {
+            template.strict_mode = strict_mode;
+        }
+
+        if let Some(extends) = updates.extends {
+            template.extends = extends;
+        }
+
+        if let Some(variable_schema) = updates.variable_schema {
+            template.variable_schema = variable_schema;
+        }
+
         template.updated_at = Utc::now();
         template.validate()?;
+        template.validate_extends(|parent| self.exists(parent))?;
 
         self.templates.insert(id.to_string(), template.clone());
+        self.invalidate_compiled();
 
         Ok(template)
     }
@@ -99,8 +152,9 @@ impl TemplateStore {
     pub fn delete(&self, id: &str) -> TemplateResult<()> {
         self.templates
             .remove(id)
-            .map(|_| ())
-            .ok_or_else(|| TemplateError::NotFound(id.to_string()))
+            .ok_or_else(|| TemplateError::NotFound(id.to_string()))?;
+        self.invalidate_compiled();
+        Ok(())
     }
 
     /// Check if a template exists
@@ -113,23 +167,223 @@ impl TemplateStore {
         self.templates.len()
     }
 
+    /// Export all templates as a JSON array, ordered by ID
+    pub fn export_all(&self) -> serde_json::Value {
+        let mut templates = self.list();
+        templates.sort_by(|a, b| a.id.cmp(&b.id));
+        serde_json::to_value(templates).unwrap_or_else(|_| serde_json::Value::Array(Vec::new()))
+    }
+
+    /// Import templates from a JSON array (as produced by `export_all`).
+    ///
+    /// Invalid entries are reported in `failed` and do not stop the import.
+    /// With `ImportConflictStrategy::Fail`, nothing is imported if any ID already exists.
+    pub fn import_from_json(
+        &self,
+        json: &serde_json::Value,
+        on_conflict: ImportConflictStrategy,
+    ) -> ImportResult {
+        let mut result = ImportResult::default();
+
+        let Some(entries) = json.as_array() else {
+            result
+                .failed
+                .push((String::new(), "expected a JSON array of templates".to_string()));
+            return result;
+        };
+
+        let mut templates = Vec::with_capacity(entries.len());
+        for (index, entry) in entries.iter().enumerate() {
+            let id = entry
+                .get("id")
+                .and_then(|v| v.as_str())
+                .map(str::to_string)
+                .unwrap_or_else(|| format!("#{}", index));
+
+            match serde_json::from_value::<Template>(entry.clone()) {
+                Ok(template) => match template.validate() {
+                    Ok(()) => templates.push(template),
+                    Err(e) => result.failed.push((id, e.to_string())),
+                },
+                Err(e) => result.failed.push((id, e.to_string())),
+            }
+        }
+
+        // Parents may be defined later in the same batch
+        let batch_ids: HashSet<String> = templates.iter().map(|t| t.id.clone()).collect();
+        let mut resolvable = Vec::with_capacity(templates.len());
+        for template in templates {
+            match template.validate_extends(|parent| batch_ids.contains(parent) || self.exists(parent))
+            {
+                Ok(()) => resolvable.push(template),
+                Err(e) => result.failed.push((template.id, e.to_string())),
+            }
+        }
+        let templates = resolvable;
+
+        if on_conflict == ImportConflictStrategy::Fail {
+            let conflicts: Vec<(String, String)> = templates
+                .iter()
+                .filter(|t| self.exists(&t.id))
+                .map(|t| (t.id.clone(), TemplateError::AlreadyExists(t.id.clone()).to_string()))
+                .collect();
+            if !conflicts.is_empty() {
+                result.failed.extend(conflicts);
+                return result;
+            }
+        }
+
+        for template in templates {
+            if on_conflict == ImportConflictStrategy::Skip {
+                match self.templates.entry(template.id.clone()) {
+                    Entry::Occupied(_) => {
+                        result.skipped += 1;
+                        continue;
+                    }
+                    Entry::Vacant(slot) => {
+                        slot.insert(template);
+                    }
+                }
+            } else {
+                self.templates.insert(template.id.clone(), template);
+            }
+            result.imported += 1;
+        }
+        if result.imported > 0 {
+            self.invalidate_compiled();
+        }
+
+        result
+    }
+
     /// Render a template with variables
     pub fn render(
         &self,
         id: &str,
         variables: &serde_json::Value,
     ) -> TemplateResult<RenderedTemplate> {
+        let compiled = self.compile(id)?;
+        self.render_compiled(&compiled, variables)
+    }
+
+    /// Compiled form of a template, built on first use and cached until any
+    /// template changes
+    pub fn compile(&self, id: &str) -> TemplateResult<Arc<CompiledTemplate>> {
+        let generation = self.generation.load(Ordering::Acquire);
+        if let Some(entry) = self.compiled.get(id) {
+            if entry.0 == generation {
+                return Ok(entry.1.clone());
+            }
+        }
+
         let template = self.get(id)?;
+        let payload = self.resolve_payload(&template)?;
+        let compiled = Arc::new(CompiledTemplate::new(template, &payload));
+        self.compiled
+            .insert(id.to_string(), (generation, compiled.clone()));
+        Ok(compiled)
+    }
 
-        let rendered_payload = substitute_variables(&template.payload_template, variables)?;
+    /// Render a compiled template with variables
+    pub fn render_compiled(
+        &self,
+        compiled: &CompiledTemplate,
+        variables: &serde_json::Value,
+    ) -> TemplateResult<RenderedTemplate> {
+        let template = compiled.template();
+        template.validate_variables(variables)?;
+        let rendered_payload = compiled.substitute(variables)?;
 
         Ok(RenderedTemplate {
-            event_type: template.event_type,
+            event_type: template.event_type.clone(),
             payload: rendered_payload,
             priority: template.default_priority,
             ttl: template.default_ttl,
         })
     }
+
+    /// Drop compiled templates after a change
+    fn invalidate_compiled(&self) {
+        self.generation.fetch_add(1, Ordering::AcqRel);
+        self.compiled.clear();
+    }
+
+    /// Build the effective payload template by merging the `extends` chain,
+    /// from the root ancestor down to `template` (child keys win)
+    fn resolve_payload(&self, template: &Template) -> TemplateResult<serde_json::Value> {
+        let mut chain = vec![template.payload_template.clone()];
+        let mut visited = HashSet::from([template.id.clone()]);
+        let mut next = template.extends.clone();
+
+        while let Some(parent_id) = next {
+            if !visited.insert(parent_id.clone()) {
+                return Err(TemplateError::InvalidTemplate(
+                    "circular inheritance".to_string(),
+                ));
+            }
+            if chain.len() > MAX_INHERITANCE_DEPTH {
+                return Err(TemplateError::InvalidTemplate(format!(
+                    "inheritance depth exceeds {} levels",
+                    MAX_INHERITANCE_DEPTH
+                )));
+            }
+
+            let parent = self.get(&parent_id).map_err(|_| {
+                TemplateError::InvalidTemplate(format!("extends unknown template: {}", parent_id))
+            })?;
+            chain.push(parent.payload_template);
+            next = parent.extends;
+        }
+
+        let mut payload = chain.pop().unwrap_or(serde_json::Value::Null);
+        while let Some(child) = chain.pop() {
+            merge_payload(&mut payload, child);
+        }
+
+        Ok(payload)
+    }
+}
+
+/// Deep-merge `overlay` into `base`: objects merge recursively, anything else is replaced
+/// Rank of a template for a lowercase search query: 0 for an exact ID match, 1 when
+/// a field starts with the query, 2 when a field contains it, `None` otherwise
+fn search_rank(template: &Template, id: &str, query: &str) -> Option<u8> {
+    let id = id.to_lowercase();
+    if id == query {
+        return Some(0);
+    }
+    let fields: Vec<String> = [
+        Some(id),
+        Some(template.name.to_lowercase()),
+        Some(template.event_type.to_lowercase()),
+        template.description.as_ref().map(|d| d.to_lowercase()),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    if fields.iter().any(|field| field.starts_with(query)) {
+        Some(1)
+    } else if fields.iter().any(|field| field.contains(query)) {
+        Some(2)
+    } else {
+        None
+    }
+}
+
+fn merge_payload(base: &mut serde_json::Value, overlay: serde_json::Value) {
+    match (base, overlay) {
+        (serde_json::Value::Object(base), serde_json::Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_payload(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
 }
 
 /// Create an Arc-wrapped template store
@@ -155,6 +409,9 @@ mod tests {
             default_priority: Priority::High,
             default_ttl: Some(3600),
             description: Some("A test template".to_string()),
+            strict_mode: false,
+            extends: None,
+            variable_schema: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
@@ -178,6 +435,9 @@ mod tests {
             default_priority: Priority::Normal,
             default_ttl: None,
             description: None,
+            strict_mode: false,
+            extends: None,
+            variable_schema: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
@@ -201,6 +461,9 @@ mod tests {
             default_priority: Priority::Normal,
             default_ttl: None,
             description: None,
+            strict_mode: false,
+            extends: None,
+            variable_schema: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
@@ -214,6 +477,9 @@ mod tests {
             default_priority: Some(Priority::High),
             default_ttl: None,
             description: None,
+            strict_mode: None,
+            extends: None,
+            variable_schema: None,
         };
 
         let updated = store.update("update-test", updates).unwrap();
@@ -233,6 +499,9 @@ mod tests {
             default_priority: Priority::Normal,
             default_ttl: None,
             description: None,
+            strict_mode: false,
+            extends: None,
+            variable_schema: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
@@ -257,6 +526,9 @@ mod tests {
                 default_priority: Priority::Normal,
                 default_ttl: None,
                 description: None,
+                strict_mode: false,
+                extends: None,
+                variable_schema: None,
                 created_at: Utc::now(),
                 updated_at: Utc::now(),
             };
@@ -267,6 +539,38 @@ mod tests {
         assert_eq!(list.len(), 3);
     }
 
+    #[test]
+    fn test_store_search_ranking() {
+        let store = TemplateStore::new();
+        let template = |id: &str, name: &str, event_type: &str| Template {
+            id: id.to_string(),
+            name: name.to_string(),
+            event_type: event_type.to_string(),
+            payload_template: json!({}),
+            default_priority: Priority::Normal,
+            default_ttl: None,
+            description: None,
+            strict_mode: false,
+            extends: None,
+            variable_schema: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        store.create(template("invoice-with-order-ref", "Invoice", "invoice.created")).unwrap();
+        store.create(template("order-shipped", "Order Shipped", "order.shipped")).unwrap();
+        store.create(template("order", "Generic", "misc")).unwrap();
+        store.create(template("welcome", "Welcome", "user.signup")).unwrap();
+        let mut described = template("digest", "Weekly Digest", "digest.weekly");
+        described.description = Some("Summary of ORDER activity".to_string());
+        store.create(described).unwrap();
+
+        let ids: Vec<String> = store.search("Order").into_iter().map(|t| t.id).collect();
+        assert_eq!(ids, vec!["order", "order-shipped", "digest", "invoice-with-order-ref"]);
+
+        assert!(store.search("missing").is_empty());
+        assert_eq!(store.search("signup")[0].id, "welcome");
+    }
+
     #[test]
     fn test_render_template() {
         let store = TemplateStore::new();
@@ -282,6 +586,9 @@ mod tests {
             default_priority: Priority::High,
             default_ttl: Some(86400),
             description: None,
+            strict_mode: false,
+            extends: None,
+            variable_schema: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
@@ -300,4 +607,365 @@ mod tests {
         assert_eq!(rendered.priority, Priority::High);
         assert_eq!(rendered.ttl, Some(86400));
     }
+
+    #[test]
+    fn test_render_strict_mode() {
+        let store = TemplateStore::new();
+
+        let mut template = import_template("strict-test", "Strict");
+        template.payload_template = json!({"message": "{{text}} via {{channel}}"});
+        template.strict_mode = true;
+        store.create(template).unwrap();
+
+        let rendered = store
+            .render("strict-test", &json!({"text": "hi", "channel": "sms"}))
+            .unwrap();
+        assert_eq!(rendered.payload["message"], "hi via sms");
+
+        let result = store.render("strict-test", &json!({"text": "hi"}));
+        assert!(matches!(
+            result,
+            Err(TemplateError::SubstitutionFailed(msg)) if msg == "unresolved placeholder: {{channel}}"
+        ));
+
+        // Lenient templates leave missing placeholders untouched
+        let lenient = UpdateTemplateRequest {
+            name: None,
+            event_type: None,
+            payload_template: None,
+            default_priority: None,
+            default_ttl: None,
+            description: None,
+            strict_mode: Some(false),
+            extends: None,
+            variable_schema: None,
+        };
+        store.update("strict-test", lenient).unwrap();
+        let rendered = store.render("strict-test", &json!({"text": "hi"})).unwrap();
+        assert_eq!(rendered.payload["message"], "hi via {{channel}}");
+    }
+
+    #[test]
+    fn test_render_validates_variable_schema() {
+        let store = TemplateStore::new();
+
+        let mut template = import_template("order-update", "Order Update");
+        template.payload_template = json!({"message": "Order {{order_id}} updated"});
+        template.variable_schema = Some(json!({
+            "type": "object",
+            "properties": {"order_id": {"type": "string"}},
+            "required": ["order_id"]
+        }));
+        store.create(template).unwrap();
+
+        let result = store.render("order-update", &json!({"other": "value"}));
+        assert!(matches!(
+            result,
+            Err(TemplateError::SubstitutionFailed(msg)) if msg.starts_with("variables do not match schema:")
+        ));
+
+        let result = store.render("order-update", &json!({"order_id": 42}));
+        assert!(matches!(result, Err(TemplateError::SubstitutionFailed(_))));
+
+        let rendered = store
+            .render("order-update", &json!({"order_id": "ORD-7"}))
+            .unwrap();
+        assert_eq!(rendered.payload["message"], "Order ORD-7 updated");
+    }
+
+    #[test]
+    fn test_invalid_variable_schema_rejected() {
+        let store = TemplateStore::new();
+
+        let mut template = import_template("bad-schema", "Bad Schema");
+        template.variable_schema = Some(json!({"type": "not-a-type"}));
+
+        let result = store.create(template);
+        assert!(matches!(result, Err(TemplateError::InvalidTemplate(_))));
+    }
+
+    fn child_template(id: &str, extends: Option<&str>, payload: serde_json::Value) -> Template {
+        let mut template = import_template(id, id);
+        template.payload_template = payload;
+        template.extends = extends.map(str::to_string);
+        template
+    }
+
+    #[test]
+    fn test_render_single_level_inheritance() {
+        let store = TemplateStore::new();
+        store
+            .create(child_template(
+                "base-alert",
+                None,
+                json!({
+                    "title": "Alert",
+                    "severity": "info",
+                    "meta": {"source": "{{source}}", "team": "ops"}
+                }),
+            ))
+            .unwrap();
+        store
+            .create(child_template(
+                "disk-alert",
+                Some("base-alert"),
+                json!({
+                    "severity": "critical",
+                    "meta": {"team": "storage"}
+                }),
+            ))
+            .unwrap();
+
+        let rendered = store
+            .render("disk-alert", &json!({"source": "node-1"}))
+            .unwrap();
+        assert_eq!(
+            rendered.payload,
+            json!({
+                "title": "Alert",
+                "severity": "critical",
+                "meta": {"source": "node-1", "team": "storage"}
+            })
+        );
+    }
+
+    #[test]
+    fn test_update_null_extends_removes_parent() {
+        let store = TemplateStore::new();
+        store
+            .create(child_template("parent", None, json!({"title": "Hi"})))
+            .unwrap();
+        store
+            .create(child_template("child", Some("parent"), json!({"body": "{{body}}"})))
+            .unwrap();
+
+        // An absent field leaves the parent in place
+        let untouched: UpdateTemplateRequest = serde_json::from_value(json!({})).unwrap();
+        assert!(untouched.extends.is_none());
+        store.update("child", untouched).unwrap();
+        assert_eq!(store.get("child").unwrap().extends.as_deref(), Some("parent"));
+
+        let cleared: UpdateTemplateRequest =
+            serde_json::from_value(json!({"extends": null})).unwrap();
+        assert_eq!(cleared.extends, Some(None));
+        store.update("child", cleared).unwrap();
+        assert!(store.get("child").unwrap().extends.is_none());
+
+        let rendered = store.render("child", &json!({"body": "x"})).unwrap();
+        assert_eq!(rendered.payload, json!({"body": "x"}));
+    }
+
+    #[test]
+    fn test_render_two_level_inheritance() {
+        let store = TemplateStore::new();
+        store
+            .create(child_template("root", None, json!({"a": 1, "b": 1, "c": 1})))
+            .unwrap();
+        store
+            .create(child_template("middle", Some("root"), json!({"b": 2, "c": 2})))
+            .unwrap();
+        store
+            .create(child_template("leaf", Some("middle"), json!({"c": "{{c}}"})))
+            .unwrap();
+
+        let rendered = store.render("leaf", &json!({"c": 3})).unwrap();
+        assert_eq!(rendered.payload, json!({"a": 1, "b": 2, "c": "3"}));
+    }
+
+    #[test]
+    fn test_compiled_template_cached_until_change() {
+        let store = TemplateStore::new();
+        store
+            .create(child_template("parent", None, json!({"title": "Hi {{name}}"})))
+            .unwrap();
+        store
+            .create(child_template("child", Some("parent"), json!({"body": "{{body}}"})))
+            .unwrap();
+
+        let compiled = store.compile("child").unwrap();
+        assert!(Arc::ptr_eq(&compiled, &store.compile("child").unwrap()));
+        let rendered = store
+            .render_compiled(&compiled, &json!({"name": "Ann", "body": "text"}))
+            .unwrap();
+        assert_eq!(rendered.payload, json!({"title": "Hi Ann", "body": "text"}));
+
+        // Changing the parent rebuilds the child's compiled form
+        let updates = UpdateTemplateRequest {
+            name: None,
+            event_type: None,
+            payload_template: Some(json!({"title": "Hello {{name}}"})),
+            default_priority: None,
+            default_ttl: None,
+            description: None,
+            strict_mode: None,
+            extends: None,
+            variable_schema: None,
+        };
+        store.update("parent", updates).unwrap();
+        assert!(!Arc::ptr_eq(&compiled, &store.compile("child").unwrap()));
+        let rendered = store.render("child", &json!({"name": "Ann"})).unwrap();
+        assert_eq!(rendered.payload["title"], "Hello Ann");
+
+        store.delete("child").unwrap();
+        assert!(matches!(store.compile("child"), Err(TemplateError::NotFound(_))));
+    }
+
+    #[test]
+    fn test_render_circular_inheritance() {
+        let store = TemplateStore::new();
+        store
+            .create(child_template("a", None, json!({"x": 1})))
+            .unwrap();
+        store
+            .create(child_template("b", Some("a"), json!({"y": 2})))
+            .unwrap();
+
+        // Close the loop: a extends b extends a
+        let updates = UpdateTemplateRequest {
+            name: None,
+            event_type: None,
+            payload_template: None,
+            default_priority: None,
+            default_ttl: None,
+            description: None,
+            strict_mode: None,
+            extends: Some(Some("b".to_string())),
+            variable_schema: None,
+        };
+        store.update("a", updates).unwrap();
+
+        let result = store.render("b", &json!({}));
+        assert!(matches!(
+            result,
+            Err(TemplateError::InvalidTemplate(msg)) if msg == "circular inheritance"
+        ));
+    }
+
+    #[test]
+    fn test_render_inheritance_depth_limit() {
+        let store = TemplateStore::new();
+        store
+            .create(child_template("level-0", None, json!({})))
+            .unwrap();
+        for i in 1..=6 {
+            store
+                .create(child_template(
+                    &format!("level-{}", i),
+                    Some(&format!("level-{}", i - 1)),
+                    json!({}),
+                ))
+                .unwrap();
+        }
+
+        assert!(store.render("level-5", &json!({})).is_ok());
+        assert!(matches!(
+            store.render("level-6", &json!({})),
+            Err(TemplateError::InvalidTemplate(_))
+        ));
+    }
+
+    #[test]
+    fn test_extends_must_reference_known_template() {
+        let store = TemplateStore::new();
+
+        let result = store.create(child_template("orphan", Some("missing"), json!({})));
+        assert!(matches!(result, Err(TemplateError::InvalidTemplate(_))));
+
+        let result = store.create(child_template("self-ref", Some("self-ref"), json!({})));
+        assert!(matches!(result, Err(TemplateError::InvalidTemplate(_))));
+    }
+
+    fn import_template(id: &str, name: &str) -> Template {
+        Template {
+            id: id.to_string(),
+            name: name.to_string(),
+            event_type: "test".to_string(),
+            payload_template: json!({"message": "{{text}}"}),
+            default_priority: Priority::Normal,
+            default_ttl: None,
+            description: None,
+            strict_mode: false,
+            extends: None,
+            variable_schema: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_export_import_round_trip() {
+        let store = TemplateStore::new();
+        for i in 0..5 {
+            store
+                .create(import_template(&format!("template-{}", i), &format!("Template {}", i)))
+                .unwrap();
+        }
+
+        let exported = store.export_all();
+        assert_eq!(exported.as_array().unwrap().len(), 5);
+
+        for template in store.list() {
+            store.delete(&template.id).unwrap();
+        }
+        assert_eq!(store.count(), 0);
+
+        let result = store.import_from_json(&exported, ImportConflictStrategy::Fail);
+        assert_eq!(result.imported, 5);
+        assert_eq!(result.skipped, 0);
+        assert!(result.failed.is_empty());
+
+        for i in 0..5 {
+            let template = store.get(&format!("template-{}", i)).unwrap();
+            assert_eq!(template.name, format!("Template {}", i));
+            assert_eq!(template.payload_template, json!({"message": "{{text}}"}));
+        }
+    }
+
+    #[test]
+    fn test_import_conflict_strategies() {
+        let store = TemplateStore::new();
+        store.create(import_template("existing", "Original")).unwrap();
+
+        let json = serde_json::to_value(vec![
+            import_template("existing", "Replacement"),
+            import_template("new", "New"),
+        ])
+        .unwrap();
+
+        let result = store.import_from_json(&json, ImportConflictStrategy::Fail);
+        assert_eq!(result.imported, 0);
+        assert_eq!(result.failed.len(), 1);
+        assert_eq!(result.failed[0].0, "existing");
+        assert!(!store.exists("new"));
+
+        let result = store.import_from_json(&json, ImportConflictStrategy::Skip);
+        assert_eq!(result.imported, 1);
+        assert_eq!(result.skipped, 1);
+        assert_eq!(store.get("existing").unwrap().name, "Original");
+
+        let result = store.import_from_json(&json, ImportConflictStrategy::Overwrite);
+        assert_eq!(result.imported, 2);
+        assert_eq!(store.get("existing").unwrap().name, "Replacement");
+    }
+
+    #[test]
+    fn test_import_invalid_entries() {
+        let store = TemplateStore::new();
+
+        let result = store.import_from_json(&json!({"id": "not-an-array"}), ImportConflictStrategy::Skip);
+        assert_eq!(result.imported, 0);
+        assert_eq!(result.failed.len(), 1);
+
+        let json = json!([
+            {"id": "missing-fields"},
+            {"id": "bad id!", "name": "Bad", "event_type": "test", "payload_template": {}},
+            {"id": "good", "name": "Good", "event_type": "test", "payload_template": {}}
+        ]);
+        let result = store.import_from_json(&json, ImportConflictStrategy::Skip);
+        assert_eq!(result.imported, 1);
+        assert_eq!(result.failed.len(), 2);
+        assert_eq!(result.failed[0].0, "missing-fields");
+        assert_eq!(result.failed[1].0, "bad id!");
+        assert!(store.exists("good"));
+    }
 }
@@ -1,11 +1,32 @@
 //! Variable substitution engine for templates
 
-use super::types::{TemplateError, TemplateResult};
+use std::borrow::Cow;
 
-/// Substitute {{variable}} placeholders in a JSON value
+use super::types::{Template, TemplateError, TemplateResult};
+
+/// How placeholders without a matching variable are handled
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SubstitutionMode {
+    /// Leave unresolved placeholders in the output as-is
+    #[default]
+    Lenient,
+    /// Fail if any placeholder remains after substitution
+    Strict,
+}
+
+/// Substitute {{variable}} placeholders in a JSON value (lenient mode)
 pub fn substitute_variables(
     template: &serde_json::Value,
     variables: &serde_json::Value,
+) -> TemplateResult<serde_json::Value> {
+    substitute_variables_with_mode(template, variables, SubstitutionMode::Lenient)
+}
+
+/// Substitute {{variable}} placeholders in a JSON value using the given mode
+pub fn substitute_variables_with_mode(
+    template: &serde_json::Value,
+    variables: &serde_json::Value,
+    mode: SubstitutionMode,
 ) -> TemplateResult<serde_json::Value> {
     let vars = match variables {
         serde_json::Value::Object(map) => map,
@@ -16,7 +37,18 @@ pub fn substitute_variables(
         }
     };
 
-    substitute_value(template, vars)
+    let rendered = substitute_value(template, vars)?;
+
+    if mode == SubstitutionMode::Strict {
+        if let Some(placeholder) = find_unresolved(&rendered) {
+            return Err(TemplateError::SubstitutionFailed(format!(
+                "unresolved placeholder: {}",
+                placeholder
+            )));
+        }
+    }
+
+    Ok(rendered)
 }
 
 fn substitute_value(
@@ -55,20 +87,201 @@ fn substitute_string(
     // Find all {{variable}} patterns and replace them
     for (key, value) in variables {
         let pattern = format!("{{{{{}}}}}", key);
-        let replacement = match value {
-            serde_json::Value::String(s) => s.clone(),
-            serde_json::Value::Number(n) => n.to_string(),
-            serde_json::Value::Bool(b) => b.to_string(),
-            serde_json::Value::Null => "".to_string(),
-            // For arrays and objects, use JSON representation
-            _ => value.to_string(),
-        };
-        result = result.replace(&pattern, &replacement);
+        result = result.replace(&pattern, &variable_text(value));
     }
 
     result
 }
 
+/// Text a variable value is substituted as
+fn variable_text(value: &serde_json::Value) -> Cow<'_, str> {
+    match value {
+        serde_json::Value::String(s) => Cow::Borrowed(s),
+        serde_json::Value::Number(n) => Cow::Owned(n.to_string()),
+        serde_json::Value::Bool(b) => Cow::Owned(b.to_string()),
+        serde_json::Value::Null => Cow::Borrowed(""),
+        // For arrays and objects, use JSON representation
+        _ => Cow::Owned(value.to_string()),
+    }
+}
+
+/// A template whose payload has been split into literal text and `{{variable}}`
+/// placeholders ahead of time.
+///
+/// Rendering is a single pass over the payload, independent of how many variables
+/// are supplied, whereas `substitute_variables` scans every string once per variable.
+/// Substituted values are inserted verbatim: placeholders inside a variable's value
+/// are never expanded.
+#[derive(Debug, Clone)]
+pub struct CompiledTemplate {
+    template: Template,
+    payload: CompiledValue,
+}
+
+impl CompiledTemplate {
+    /// Compile `payload` (the template's payload with any `extends` chain merged in)
+    pub fn new(template: Template, payload: &serde_json::Value) -> Self {
+        Self {
+            template,
+            payload: CompiledValue::compile(payload),
+        }
+    }
+
+    /// The template this was compiled from
+    pub fn template(&self) -> &Template {
+        &self.template
+    }
+
+    /// Substitute `variables` into the payload, honouring the template's
+    /// substitution mode
+    pub fn substitute(&self, variables: &serde_json::Value) -> TemplateResult<serde_json::Value> {
+        let serde_json::Value::Object(vars) = variables else {
+            return Err(TemplateError::SubstitutionFailed(
+                "Variables must be an object".to_string(),
+            ));
+        };
+
+        let rendered = self.payload.render(vars);
+
+        if self.template.substitution_mode() == SubstitutionMode::Strict {
+            if let Some(placeholder) = find_unresolved(&rendered) {
+                return Err(TemplateError::SubstitutionFailed(format!(
+                    "unresolved placeholder: {}",
+                    placeholder
+                )));
+            }
+        }
+
+        Ok(rendered)
+    }
+}
+
+#[derive(Debug, Clone)]
+enum CompiledValue {
+    Text(CompiledString),
+    Array(Vec<CompiledValue>),
+    Object(Vec<(CompiledString, CompiledValue)>),
+    /// Numbers, booleans and null are passed through as-is
+    Other(serde_json::Value),
+}
+
+impl CompiledValue {
+    fn compile(value: &serde_json::Value) -> Self {
+        match value {
+            serde_json::Value::String(s) => CompiledValue::Text(CompiledString::compile(s)),
+            serde_json::Value::Array(arr) => {
+                CompiledValue::Array(arr.iter().map(CompiledValue::compile).collect())
+            }
+            serde_json::Value::Object(obj) => CompiledValue::Object(
+                obj.iter()
+                    .map(|(key, val)| (CompiledString::compile(key), CompiledValue::compile(val)))
+                    .collect(),
+            ),
+            _ => CompiledValue::Other(value.clone()),
+        }
+    }
+
+    fn render(&self, variables: &serde_json::Map<String, serde_json::Value>) -> serde_json::Value {
+        match self {
+            CompiledValue::Text(s) => serde_json::Value::String(s.render(variables)),
+            CompiledValue::Array(arr) => {
+                serde_json::Value::Array(arr.iter().map(|v| v.render(variables)).collect())
+            }
+            CompiledValue::Object(obj) => serde_json::Value::Object(
+                obj.iter()
+                    .map(|(key, val)| (key.render(variables), val.render(variables)))
+                    .collect(),
+            ),
+            CompiledValue::Other(value) => value.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Segment {
+    Literal(String),
+    /// Variable name between `{{` and `}}`
+    Placeholder(String),
+}
+
+/// A string split into literal and placeholder segments
+#[derive(Debug, Clone)]
+struct CompiledString {
+    segments: Vec<Segment>,
+    /// Total length of the literal segments, used to size the output
+    literal_len: usize,
+}
+
+impl CompiledString {
+    fn compile(s: &str) -> Self {
+        let mut segments = Vec::new();
+        let mut rest = s;
+        // Each placeholder is the innermost `{{...}}` before the next `}}`, which
+        // is the text `String::replace` would match for that variable
+        while let Some(end) = rest.find("}}") {
+            let Some(start) = rest[..end].rfind("{{") else {
+                segments.push(Segment::Literal(rest[..end + 2].to_string()));
+                rest = &rest[end + 2..];
+                continue;
+            };
+            if start > 0 {
+                segments.push(Segment::Literal(rest[..start].to_string()));
+            }
+            segments.push(Segment::Placeholder(rest[start + 2..end].to_string()));
+            rest = &rest[end + 2..];
+        }
+        if !rest.is_empty() {
+            segments.push(Segment::Literal(rest.to_string()));
+        }
+
+        let literal_len = segments
+            .iter()
+            .map(|segment| match segment {
+                Segment::Literal(text) => text.len(),
+                Segment::Placeholder(_) => 0,
+            })
+            .sum();
+        Self { segments, literal_len }
+    }
+
+    fn render(&self, variables: &serde_json::Map<String, serde_json::Value>) -> String {
+        let mut out = String::with_capacity(self.literal_len);
+        for segment in &self.segments {
+            match segment {
+                Segment::Literal(text) => out.push_str(text),
+                Segment::Placeholder(name) => match variables.get(name) {
+                    Some(value) => out.push_str(&variable_text(value)),
+                    // Unresolved placeholders are kept as written
+                    None => {
+                        out.push_str("{{");
+                        out.push_str(name);
+                        out.push_str("}}");
+                    }
+                },
+            }
+        }
+        out
+    }
+}
+
+/// Find the first remaining {{...}} placeholder in a rendered value
+fn find_unresolved(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::String(s) => find_placeholder(s),
+        serde_json::Value::Array(arr) => arr.iter().find_map(find_unresolved),
+        serde_json::Value::Object(obj) => obj
+            .iter()
+            .find_map(|(key, val)| find_placeholder(key).or_else(|| find_unresolved(val))),
+        _ => None,
+    }
+}
+
+fn find_placeholder(s: &str) -> Option<String> {
+    let start = s.find("{{")?;
+    let end = s[start + 2..].find("}}")?;
+    Some(s[start..start + 2 + end + 2].to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -158,4 +371,133 @@ mod tests {
         let result = substitute_variables(&template, &variables).unwrap();
         assert_eq!(result["count"], "You have 42 items");
     }
+
+    #[test]
+    fn test_lenient_keeps_unresolved_placeholder() {
+        let template = json!({
+            "message": "Hello, {{name}}! Code: {{code}}"
+        });
+
+        let variables = json!({
+            "name": "World"
+        });
+
+        let result =
+            substitute_variables_with_mode(&template, &variables, SubstitutionMode::Lenient)
+                .unwrap();
+        assert_eq!(result["message"], "Hello, World! Code: {{code}}");
+    }
+
+    #[test]
+    fn test_strict_resolved_succeeds() {
+        let template = json!({
+            "title": "Order {{order_id}}",
+            "items": ["{{item}}"]
+        });
+
+        let variables = json!({
+            "order_id": "ORD-1",
+            "item": "Book"
+        });
+
+        let result =
+            substitute_variables_with_mode(&template, &variables, SubstitutionMode::Strict)
+                .unwrap();
+        assert_eq!(result["title"], "Order ORD-1");
+        assert_eq!(result["items"][0], "Book");
+    }
+
+    #[test]
+    fn test_strict_unresolved_fails() {
+        let template = json!({
+            "data": {
+                "body": "Hi {{name}}, see {{foo}}"
+            }
+        });
+
+        let variables = json!({
+            "name": "Alice"
+        });
+
+        let err = substitute_variables_with_mode(&template, &variables, SubstitutionMode::Strict)
+            .unwrap_err();
+        match err {
+            TemplateError::SubstitutionFailed(msg) => {
+                assert_eq!(msg, "unresolved placeholder: {{foo}}")
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_strict_unresolved_in_key_fails() {
+        let template = json!({
+            "{{field}}": "value"
+        });
+
+        let result =
+            substitute_variables_with_mode(&template, &json!({}), SubstitutionMode::Strict);
+        assert!(matches!(result, Err(TemplateError::SubstitutionFailed(_))));
+    }
+
+    fn compiled(payload: serde_json::Value, strict_mode: bool) -> CompiledTemplate {
+        let template: Template = serde_json::from_value(json!({
+            "id": "compiled",
+            "name": "Compiled",
+            "event_type": "test.compiled",
+            "payload_template": payload,
+            "strict_mode": strict_mode
+        }))
+        .unwrap();
+        let payload = template.payload_template.clone();
+        CompiledTemplate::new(template, &payload)
+    }
+
+    #[test]
+    fn test_compiled_matches_naive_substitution() {
+        let payload = json!({
+            "title": "Order {{order_id}} for {{name}}",
+            "{{key}}": ["{{count}} items", "{{missing}}", 7, null],
+            "braces": "{{{name}}} }} {{ {{name}}",
+            "nested": { "flag": "{{flag}}", "data": "{{data}}", "empty": "{{nothing}}" }
+        });
+        let variables = json!({
+            "order_id": "ORD-1",
+            "name": "Alice",
+            "key": "dynamic",
+            "count": 3,
+            "flag": true,
+            "data": { "a": 1 },
+            "nothing": null
+        });
+
+        let naive = substitute_variables(&payload, &variables).unwrap();
+        let rendered = compiled(payload, false).substitute(&variables).unwrap();
+        assert_eq!(rendered, naive);
+        assert_eq!(rendered["braces"], "{Alice} }} {{ Alice");
+        assert_eq!(rendered["dynamic"][1], "{{missing}}");
+    }
+
+    #[test]
+    fn test_compiled_does_not_expand_placeholders_in_values() {
+        let template = compiled(json!({ "text": "{{a}} {{b}}" }), false);
+        let rendered = template
+            .substitute(&json!({ "a": "{{b}}", "b": "x" }))
+            .unwrap();
+        assert_eq!(rendered["text"], "{{b}} x");
+    }
+
+    #[test]
+    fn test_compiled_strict_unresolved_fails() {
+        let template = compiled(json!({ "text": "Hi {{name}}" }), true);
+        assert!(template.substitute(&json!({ "name": "Bob" })).is_ok());
+        assert!(matches!(
+            template.substitute(&json!({})),
+            Err(TemplateError::SubstitutionFailed(_))
+        ));
+        assert!(matches!(
+            template.substitute(&json!("not an object")),
+            Err(TemplateError::SubstitutionFailed(_))
+        ));
+    }
 }
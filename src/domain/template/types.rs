@@ -1,9 +1,10 @@
 //! Template types and error definitions
 
 use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use thiserror::Error;
 
+use super::substitution::SubstitutionMode;
 use crate::notification::Priority;
 
 /// Template-specific error type
@@ -55,6 +56,18 @@ pub struct Template {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
 
+    /// Fail rendering when a {{variable}} placeholder is left unresolved
+    #[serde(default)]
+    pub strict_mode: bool,
+
+    /// Parent template ID whose payload this template overrides (optional)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extends: Option<String>,
+
+    /// JSON Schema the render variables must satisfy (optional)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variable_schema: Option<serde_json::Value>,
+
     /// Creation timestamp
     #[serde(default = "Utc::now")]
     pub created_at: DateTime<Utc>,
@@ -65,6 +78,15 @@ pub struct Template {
 }
 
 impl Template {
+    /// Substitution mode used when rendering this template
+    pub fn substitution_mode(&self) -> SubstitutionMode {
+        if self.strict_mode {
+            SubstitutionMode::Strict
+        } else {
+            SubstitutionMode::Lenient
+        }
+    }
+
     /// Validate the template
     pub fn validate(&self) -> TemplateResult<()> {
         // Validate ID (up to 130 chars to accommodate tenant-prefixed IDs: "tenant_id:template_id")
@@ -100,8 +122,49 @@ impl Template {
             ));
         }
 
+        // Validate variable schema compiles
+        if let Some(schema) = &self.variable_schema {
+            jsonschema::validator_for(schema).map_err(|e| {
+                TemplateError::InvalidTemplate(format!("invalid variable schema: {}", e))
+            })?;
+        }
+
+        // Validate parent reference (existence is checked by `validate_extends`)
+        if let Some(parent) = &self.extends {
+            if parent.is_empty() || *parent == self.id {
+                return Err(TemplateError::InvalidTemplate(
+                    "extends must reference another template".to_string(),
+                ));
+            }
+        }
+
         Ok(())
     }
+
+    /// Check render variables against `variable_schema` (if set)
+    pub fn validate_variables(&self, variables: &serde_json::Value) -> TemplateResult<()> {
+        let Some(schema) = &self.variable_schema else {
+            return Ok(());
+        };
+
+        let validator = jsonschema::validator_for(schema).map_err(|e| {
+            TemplateError::InvalidTemplate(format!("invalid variable schema: {}", e))
+        })?;
+        validator.validate(variables).map_err(|e| {
+            TemplateError::SubstitutionFailed(format!("variables do not match schema: {}", e))
+        })
+    }
+
+    /// Check that `extends` (if set) references a known template ID
+    pub fn validate_extends(&self, is_known: impl Fn(&str) -> bool) -> TemplateResult<()> {
+        match &self.extends {
+            Some(parent) if !is_known(parent) => Err(TemplateError::InvalidTemplate(format!(
+                "extends unknown template: {}",
+                parent
+            ))),
+            _ => Ok(()),
+        }
+    }
 }
 
 /// Request to create a new template
@@ -128,6 +191,16 @@ pub struct CreateTemplateRequest {
 
     /// Template description (optional)
     pub description: Option<String>,
+
+    /// Reject renders with unresolved placeholders (optional, defaults to false)
+    #[serde(default)]
+    pub strict_mode: bool,
+
+    /// Parent template ID to inherit the payload from (optional)
+    pub extends: Option<String>,
+
+    /// JSON Schema for render variables (optional)
+    pub variable_schema: Option<serde_json::Value>,
 }
 
 impl From<CreateTemplateRequest> for Template {
@@ -141,12 +214,25 @@ impl From<CreateTemplateRequest> for Template {
             default_priority: req.default_priority,
             default_ttl: req.default_ttl,
             description: req.description,
+            strict_mode: req.strict_mode,
+            extends: req.extends,
+            variable_schema: req.variable_schema,
             created_at: now,
             updated_at: now,
         }
     }
 }
 
+/// Deserialize a field that distinguishes "absent" (`None`) from an explicit
+/// `null` (`Some(None)`), so updates can clear optional values
+fn deserialize_double_option<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::deserialize(deserializer).map(Some)
+}
+
 /// Request to update an existing template
 #[derive(Debug, Deserialize)]
 pub struct UpdateTemplateRequest {
@@ -163,10 +249,23 @@ pub struct UpdateTemplateRequest {
     pub default_priority: Option<Priority>,
 
     /// Default TTL in seconds (optional, use null to clear)
+    #[serde(default, deserialize_with = "deserialize_double_option")]
     pub default_ttl: Option<Option<u32>>,
 
     /// Template description (optional, use null to clear)
+    #[serde(default, deserialize_with = "deserialize_double_option")]
     pub description: Option<Option<String>>,
+
+    /// Strict substitution mode (optional)
+    pub strict_mode: Option<bool>,
+
+    /// Parent template ID (optional, use null to clear)
+    #[serde(default, deserialize_with = "deserialize_double_option")]
+    pub extends: Option<Option<String>>,
+
+    /// JSON Schema for render variables (optional, use null to clear)
+    #[serde(default, deserialize_with = "deserialize_double_option")]
+    pub variable_schema: Option<Option<serde_json::Value>>,
 }
 
 /// Response for listing templates
@@ -179,8 +278,34 @@ pub struct TemplateListResponse {
     pub total: usize,
 }
 
+/// How `TemplateStore::import_from_json` handles IDs that already exist
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportConflictStrategy {
+    /// Keep the existing template and count the entry as skipped
+    #[default]
+    Skip,
+    /// Replace the existing template
+    Overwrite,
+    /// Abort the whole import if any ID already exists
+    Fail,
+}
+
+/// Outcome of a bulk template import
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ImportResult {
+    /// Templates created or overwritten
+    pub imported: usize,
+
+    /// Entries skipped because the ID already existed
+    pub skipped: usize,
+
+    /// Entries that could not be imported, as (template ID, reason)
+    pub failed: Vec<(String, String)>,
+}
+
 /// A rendered template ready for notification creation
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct RenderedTemplate {
     /// Event type
     pub event_type: String,
@@ -192,5 +317,6 @@ pub struct RenderedTemplate {
     pub priority: Priority,
 
     /// TTL from template
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub ttl: Option<u32>,
 }
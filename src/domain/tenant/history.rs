@@ -0,0 +1,161 @@
+//! Per-tenant metrics history for dashboard sparklines.
+//!
+//! Each tenant keeps a fixed-size ring buffer of per-minute samples
+//! (24 hours at one sample per minute). Samples are pushed by the
+//! `MetricsSamplerTask` and served by the admin tenant endpoints.
+
+use std::collections::VecDeque;
+
+use serde::Serialize;
+
+use super::TenantStatsSnapshot;
+
+/// Maximum samples retained per tenant (24h at 1 sample/minute)
+pub const MAX_TENANT_SAMPLES: usize = 1440;
+
+/// A single point-in-time tenant metrics sample
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct TimestampedSample {
+    /// Sample time (Unix epoch seconds)
+    pub timestamp: i64,
+    /// Active connections at sample time
+    pub active_connections: u64,
+    /// Messages sent since the previous sample
+    pub messages_per_minute: u64,
+}
+
+/// Bounded history of tenant metrics samples, oldest first
+#[derive(Debug)]
+pub struct TenantMetricsRingBuffer {
+    samples: VecDeque<TimestampedSample>,
+    capacity: usize,
+    /// Cumulative `messages_sent` at the previous sample, used to compute deltas
+    last_messages_sent: Option<u64>,
+}
+
+impl TenantMetricsRingBuffer {
+    /// Create a ring buffer holding up to `capacity` samples
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(capacity.min(MAX_TENANT_SAMPLES)),
+            capacity: capacity.max(1),
+            last_messages_sent: None,
+        }
+    }
+
+    /// Record a sample from a stats snapshot, evicting the oldest sample when full
+    pub fn record(&mut self, timestamp: i64, stats: &TenantStatsSnapshot) {
+        let messages_per_minute = match self.last_messages_sent {
+            Some(previous) => stats.messages_sent.saturating_sub(previous),
+            None => 0,
+        };
+        self.last_messages_sent = Some(stats.messages_sent);
+
+        self.push(TimestampedSample {
+            timestamp,
+            active_connections: stats.active_connections,
+            messages_per_minute,
+        });
+    }
+
+    /// Push a raw sample, evicting the oldest sample when full
+    pub fn push(&mut self, sample: TimestampedSample) {
+        while self.samples.len() >= self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    /// Samples with `start <= timestamp <= end`, oldest first
+    pub fn range(&self, start: i64, end: i64) -> Vec<TimestampedSample> {
+        self.samples
+            .iter()
+            .filter(|s| s.timestamp >= start && s.timestamp <= end)
+            .copied()
+            .collect()
+    }
+
+    /// All retained samples, oldest first
+    pub fn samples(&self) -> Vec<TimestampedSample> {
+        self.samples.iter().copied().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+}
+
+impl Default for TenantMetricsRingBuffer {
+    fn default() -> Self {
+        Self::new(MAX_TENANT_SAMPLES)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(active: u64, sent: u64) -> TenantStatsSnapshot {
+        TenantStatsSnapshot {
+            active_connections: active,
+            messages_sent: sent,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_record_computes_message_rate() {
+        let mut buffer = TenantMetricsRingBuffer::default();
+        buffer.record(60, &snapshot(3, 100));
+        buffer.record(120, &snapshot(4, 130));
+        buffer.record(180, &snapshot(2, 130));
+
+        let samples = buffer.samples();
+        assert_eq!(samples.len(), 3);
+        assert_eq!(samples[0].messages_per_minute, 0);
+        assert_eq!(samples[1].messages_per_minute, 30);
+        assert_eq!(samples[1].active_connections, 4);
+        assert_eq!(samples[2].messages_per_minute, 0);
+    }
+
+    #[test]
+    fn test_capacity_evicts_oldest() {
+        let mut buffer = TenantMetricsRingBuffer::new(3);
+        for i in 0..5 {
+            buffer.record(i * 60, &snapshot(i as u64, 0));
+        }
+
+        let samples = buffer.samples();
+        assert_eq!(samples.len(), 3);
+        assert_eq!(samples[0].timestamp, 120);
+        assert_eq!(samples[2].timestamp, 240);
+    }
+
+    #[test]
+    fn test_default_capacity_is_24h() {
+        let mut buffer = TenantMetricsRingBuffer::default();
+        for i in 0..(MAX_TENANT_SAMPLES as i64 + 10) {
+            buffer.record(i * 60, &snapshot(1, 0));
+        }
+        assert_eq!(buffer.len(), MAX_TENANT_SAMPLES);
+        assert_eq!(buffer.samples()[0].timestamp, 600);
+    }
+
+    #[test]
+    fn test_range_is_inclusive() {
+        let mut buffer = TenantMetricsRingBuffer::default();
+        for i in 0..10 {
+            buffer.record(i * 60, &snapshot(1, 0));
+        }
+
+        let range = buffer.range(120, 300);
+        assert_eq!(range.len(), 4);
+        assert_eq!(range[0].timestamp, 120);
+        assert_eq!(range[3].timestamp, 300);
+        assert!(buffer.range(1000, 2000).is_empty());
+    }
+}
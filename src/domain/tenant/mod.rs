@@ -4,7 +4,8 @@
 //! - Tenant identification from JWT claims
 //! - Channel namespacing for tenant isolation
 //! - Per-tenant connection limits
-//! - Per-tenant statistics
+//! - Per-tenant channel name policies
+//! - Per-tenant statistics and 24h metrics history
 //!
 //! # Channel Namespacing
 //!
@@ -12,6 +13,10 @@
 //! - User subscribes to "orders" → Internal channel becomes "tenant-acme:orders"
 //! - This ensures complete isolation between tenants
 //!
+//! User IDs are namespaced the same way wherever they key shared state (connection
+//! index, offline queue, pending ACKs), so user "1" of one tenant can never see or
+//! acknowledge notifications of user "1" of another.
+//!
 //! # Configuration
 //!
 //! Multi-tenancy can be enabled via environment variables:
@@ -20,13 +25,20 @@
 //! - `TENANT_DEFAULT_MAX_CONNECTIONS_PER_USER=5` - Default per-tenant per-user limit
 
 use dashmap::DashMap;
-use serde::{Deserialize, Serialize};
+use regex::Regex;
+use serde::{Deserialize, Deserializer, Serialize};
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use thiserror::Error;
 
-use crate::auth::DEFAULT_TENANT_ID;
+use crate::auth::{tenant_scoped_key, DEFAULT_TENANT_ID};
 use crate::connection_manager::ConnectionLimits;
 
+mod history;
+
+pub use history::{TenantMetricsRingBuffer, TimestampedSample, MAX_TENANT_SAMPLES};
+
 // ============================================================================
 // Configuration
 // ============================================================================
@@ -43,6 +55,12 @@ pub struct TenantConfig {
     /// Per-tenant limit overrides (tenant_id -> limits)
     #[serde(default)]
     pub tenant_overrides: HashMap<String, TenantLimitsConfig>,
+    /// Default channel name policy for tenants without a specific policy
+    #[serde(default)]
+    pub default_policy: TenantPolicy,
+    /// Per-tenant channel name policies (tenant_id -> policy)
+    #[serde(default)]
+    pub policy_overrides: HashMap<String, TenantPolicy>,
 }
 
 impl Default for TenantConfig {
@@ -51,12 +69,14 @@ impl Default for TenantConfig {
             enabled: false,
             default_limits: default_tenant_limits(),
             tenant_overrides: HashMap::new(),
+            default_policy: TenantPolicy::default(),
+            policy_overrides: HashMap::new(),
         }
     }
 }
 
 /// Per-tenant connection limits configuration
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct TenantLimitsConfig {
     /// Maximum total connections for this tenant
     #[serde(default = "default_max_connections")]
@@ -69,7 +89,7 @@ pub struct TenantLimitsConfig {
     pub max_subscriptions_per_connection: usize,
 }
 
-fn default_tenant_limits() -> TenantLimitsConfig {
+pub(crate) fn default_tenant_limits() -> TenantLimitsConfig {
     TenantLimitsConfig {
         max_connections: 1000,
         max_connections_per_user: 5,
@@ -89,6 +109,64 @@ fn default_max_subscriptions() -> usize {
     50
 }
 
+/// Channel name policy applied to a tenant's subscriptions
+#[derive(Debug, Clone, Deserialize)]
+pub struct TenantPolicy {
+    /// If set, channel names must fully match this pattern
+    #[serde(default, deserialize_with = "deserialize_channel_pattern")]
+    pub allowed_channel_pattern: Option<Regex>,
+    /// Maximum channel name length (before namespacing)
+    #[serde(default = "default_max_channel_name_length")]
+    pub max_channel_name_length: usize,
+}
+
+impl Default for TenantPolicy {
+    fn default() -> Self {
+        Self {
+            allowed_channel_pattern: None,
+            max_channel_name_length: default_max_channel_name_length(),
+        }
+    }
+}
+
+fn default_max_channel_name_length() -> usize {
+    64
+}
+
+/// Compile the configured pattern anchored to the whole channel name
+fn deserialize_channel_pattern<'de, D>(deserializer: D) -> Result<Option<Regex>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let pattern: Option<String> = Option::deserialize(deserializer)?;
+    match pattern.filter(|p| !p.is_empty()) {
+        Some(p) => Regex::new(&format!("^(?:{})$", p))
+            .map(Some)
+            .map_err(serde::de::Error::custom),
+        None => Ok(None),
+    }
+}
+
+/// Reasons a channel name is rejected by a tenant policy
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+pub enum TenantPolicyError {
+    #[error("Channel name exceeds {max} characters")]
+    ChannelNameTooLong { max: usize },
+
+    #[error("Channel '{0}' is not allowed for this tenant")]
+    ChannelNotAllowed(String),
+}
+
+impl TenantPolicyError {
+    /// Short reason label for metrics
+    pub fn reason(&self) -> &'static str {
+        match self {
+            TenantPolicyError::ChannelNameTooLong { .. } => "too_long",
+            TenantPolicyError::ChannelNotAllowed(_) => "pattern_mismatch",
+        }
+    }
+}
+
 impl From<&TenantLimitsConfig> for ConnectionLimits {
     fn from(config: &TenantLimitsConfig) -> Self {
         ConnectionLimits {
@@ -110,6 +188,8 @@ pub struct TenantContext {
     pub tenant_id: String,
     /// Whether this is the default tenant
     pub is_default: bool,
+    /// Channel name policy for this tenant
+    pub policy: Arc<TenantPolicy>,
 }
 
 impl TenantContext {
@@ -120,6 +200,7 @@ impl TenantContext {
         Self {
             tenant_id,
             is_default,
+            policy: Arc::new(TenantPolicy::default()),
         }
     }
 
@@ -128,6 +209,29 @@ impl TenantContext {
         Self::new(DEFAULT_TENANT_ID)
     }
 
+    /// Attach a channel name policy to this context
+    pub fn with_policy(mut self, policy: TenantPolicy) -> Self {
+        self.policy = Arc::new(policy);
+        self
+    }
+
+    /// Check a (non-namespaced) channel name against this tenant's policy
+    pub fn validate_channel_name(&self, channel: &str) -> Result<(), TenantPolicyError> {
+        if channel.chars().count() > self.policy.max_channel_name_length {
+            return Err(TenantPolicyError::ChannelNameTooLong {
+                max: self.policy.max_channel_name_length,
+            });
+        }
+
+        if let Some(pattern) = &self.policy.allowed_channel_pattern {
+            if !pattern.is_match(channel) {
+                return Err(TenantPolicyError::ChannelNotAllowed(channel.to_string()));
+            }
+        }
+
+        Ok(())
+    }
+
     /// Namespace a channel name for this tenant
     ///
     /// When multi-tenancy is enabled, channels are prefixed with the tenant ID
@@ -163,18 +267,60 @@ impl TenantContext {
             }
         }
     }
+
+    /// Namespace a user ID for this tenant
+    ///
+    /// Used as the key for per-user state (connection index, offline queue, pending
+    /// ACKs) so equal user IDs in different tenants never share entries.
+    pub fn namespace_user_id(&self, user_id: &str) -> String {
+        tenant_scoped_key(&self.tenant_id, user_id)
+    }
+
+    /// Extract the original user ID from a namespaced user ID
+    ///
+    /// Returns None if the key belongs to a different tenant. Default tenant keys
+    /// are unprefixed unless the user ID itself contains `:` (see `tenant_scoped_key`).
+    pub fn extract_user_id(&self, namespaced: &str) -> Option<String> {
+        if self.is_default {
+            match namespaced.strip_prefix(&format!("{}:", DEFAULT_TENANT_ID)) {
+                Some(user_id) => Some(user_id.to_string()),
+                None => (!namespaced.contains(':')).then(|| namespaced.to_string()),
+            }
+        } else {
+            namespaced
+                .strip_prefix(&format!("{}:", self.tenant_id))
+                .map(str::to_string)
+        }
+    }
 }
 
 // ============================================================================
 // Tenant Manager
 // ============================================================================
 
+/// Errors returned by dynamic tenant registration
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+pub enum TenantRegistrationError {
+    #[error("Tenant '{0}' is already registered")]
+    AlreadyRegistered(String),
+
+    #[error("Tenant '{0}' is not registered")]
+    NotFound(String),
+
+    #[error("Tenant still has {0} active connections")]
+    HasActiveConnections(u64),
+}
+
 /// Manages tenant-specific state and limits
 pub struct TenantManager {
     /// Configuration
     config: TenantConfig,
+    /// Dynamically registered tenants (tenant_id -> limits), checked before static overrides
+    registry: Arc<RwLock<HashMap<String, TenantLimitsConfig>>>,
     /// Per-tenant statistics
     stats: DashMap<String, TenantStats>,
+    /// Per-tenant metrics history (sampled once per minute)
+    history: DashMap<String, TenantMetricsRingBuffer>,
 }
 
 impl TenantManager {
@@ -182,7 +328,9 @@ impl TenantManager {
     pub fn new(config: TenantConfig) -> Self {
         Self {
             config,
+            registry: Arc::new(RwLock::new(HashMap::new())),
             stats: DashMap::new(),
+            history: DashMap::new(),
         }
     }
 
@@ -198,6 +346,16 @@ impl TenantManager {
             return ConnectionLimits::default();
         }
 
+        // Dynamically registered tenants take precedence over static config
+        if let Some(limits) = self
+            .registry
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(tenant_id)
+        {
+            return limits.into();
+        }
+
         // Check for tenant-specific overrides
         if let Some(override_config) = self.config.tenant_overrides.get(tenant_id) {
             return override_config.into();
@@ -207,10 +365,60 @@ impl TenantManager {
         (&self.config.default_limits).into()
     }
 
+    /// Register a tenant at runtime with its own connection limits
+    pub fn register_tenant(
+        &self,
+        tenant_id: &str,
+        limits: TenantLimitsConfig,
+    ) -> Result<(), TenantRegistrationError> {
+        let mut registry = self.registry.write().unwrap_or_else(|e| e.into_inner());
+        if registry.contains_key(tenant_id) {
+            return Err(TenantRegistrationError::AlreadyRegistered(
+                tenant_id.to_string(),
+            ));
+        }
+        registry.insert(tenant_id.to_string(), limits);
+
+        tracing::info!(tenant_id = %tenant_id, "Tenant registered");
+        Ok(())
+    }
+
+    /// Unregister a dynamically registered tenant.
+    /// Fails if the tenant still has active connections.
+    pub fn unregister_tenant(&self, tenant_id: &str) -> Result<(), TenantRegistrationError> {
+        let mut registry = self.registry.write().unwrap_or_else(|e| e.into_inner());
+        if !registry.contains_key(tenant_id) {
+            return Err(TenantRegistrationError::NotFound(tenant_id.to_string()));
+        }
+
+        let active = self.get_stats(tenant_id).active_connections;
+        if active > 0 {
+            return Err(TenantRegistrationError::HasActiveConnections(active));
+        }
+        registry.remove(tenant_id);
+
+        tracing::info!(tenant_id = %tenant_id, "Tenant unregistered");
+        Ok(())
+    }
+
+    /// Get the limits of a dynamically registered tenant
+    pub fn registered_limits(&self, tenant_id: &str) -> Option<TenantLimitsConfig> {
+        self.registry
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(tenant_id)
+            .cloned()
+    }
+
     /// Create a tenant context from a tenant ID
     pub fn create_context(&self, tenant_id: &str) -> TenantContext {
         if self.config.enabled {
-            TenantContext::new(tenant_id)
+            let policy = self
+                .config
+                .policy_overrides
+                .get(tenant_id)
+                .unwrap_or(&self.config.default_policy);
+            TenantContext::new(tenant_id).with_policy(policy.clone())
         } else {
             TenantContext::default_tenant()
         }
@@ -275,6 +483,46 @@ impl TenantManager {
             .map(|entry| (entry.key().clone(), entry.snapshot()))
             .collect()
     }
+
+    /// Stats summed across all tracked tenants, for cluster-level reporting
+    pub fn get_aggregate_stats(&self) -> TenantStatsSnapshot {
+        self.stats
+            .iter()
+            .fold(TenantStatsSnapshot::default(), |total, entry| TenantStatsSnapshot {
+                active_connections: total
+                    .active_connections
+                    .saturating_add(entry.active_connections.load(Ordering::Relaxed)),
+                total_connections: total
+                    .total_connections
+                    .saturating_add(entry.total_connections.load(Ordering::Relaxed)),
+                messages_sent: total
+                    .messages_sent
+                    .saturating_add(entry.messages_sent.load(Ordering::Relaxed)),
+                messages_delivered: total
+                    .messages_delivered
+                    .saturating_add(entry.messages_delivered.load(Ordering::Relaxed)),
+            })
+    }
+
+    /// Push a metrics sample for every known tenant. Returns the number of tenants sampled.
+    pub fn sample_metrics(&self, timestamp: i64) -> usize {
+        let snapshots = self.all_stats();
+        for (tenant_id, snapshot) in &snapshots {
+            self.history
+                .entry(tenant_id.clone())
+                .or_default()
+                .record(timestamp, snapshot);
+        }
+        snapshots.len()
+    }
+
+    /// Get a tenant's metrics samples with `start <= timestamp <= end`
+    pub fn metrics_history(&self, tenant_id: &str, start: i64, end: i64) -> Vec<TimestampedSample> {
+        self.history
+            .get(tenant_id)
+            .map(|buffer| buffer.range(start, end))
+            .unwrap_or_default()
+    }
 }
 
 impl Default for TenantManager {
@@ -414,6 +662,31 @@ mod tests {
         assert_eq!(ctx.extract_channel_name("orders"), None);
     }
 
+    #[test]
+    fn test_namespace_user_id() {
+        let default = TenantContext::default_tenant();
+        let acme = TenantContext::new("acme");
+        let globex = TenantContext::new("globex");
+
+        assert_eq!(default.namespace_user_id("1"), "1");
+        assert_eq!(acme.namespace_user_id("1"), "acme:1");
+        assert_ne!(acme.namespace_user_id("1"), globex.namespace_user_id("1"));
+    }
+
+    #[test]
+    fn test_extract_user_id() {
+        let default = TenantContext::default_tenant();
+        let acme = TenantContext::new("acme");
+
+        assert_eq!(acme.extract_user_id("acme:1"), Some("1".to_string()));
+        assert_eq!(acme.extract_user_id("globex:1"), None);
+        assert_eq!(acme.extract_user_id("1"), None);
+        assert_eq!(default.extract_user_id("1"), Some("1".to_string()));
+        assert_eq!(default.extract_user_id("acme:1"), None);
+        assert_eq!(default.namespace_user_id("acme:1"), "default:acme:1");
+        assert_eq!(default.extract_user_id("default:acme:1"), Some("acme:1".to_string()));
+    }
+
     #[test]
     fn test_tenant_manager_disabled() {
         let manager = TenantManager::new(TenantConfig::default());
@@ -455,6 +728,7 @@ mod tests {
             enabled: true,
             default_limits: default_tenant_limits(),
             tenant_overrides: overrides,
+            ..Default::default()
         };
         let manager = TenantManager::new(config);
 
@@ -468,6 +742,197 @@ mod tests {
         assert_eq!(premium_limits.max_connections_per_user, 10);
     }
 
+    #[test]
+    fn test_register_tenant_lifecycle() {
+        let manager = TenantManager::new(TenantConfig {
+            enabled: true,
+            ..Default::default()
+        });
+        let limits = TenantLimitsConfig {
+            max_connections: 250,
+            max_connections_per_user: 2,
+            max_subscriptions_per_connection: 20,
+        };
+
+        assert!(manager.register_tenant("acme", limits.clone()).is_ok());
+        assert_eq!(manager.get_limits("acme").max_connections, 250);
+        assert_eq!(
+            manager.register_tenant("acme", limits),
+            Err(TenantRegistrationError::AlreadyRegistered("acme".to_string()))
+        );
+
+        assert!(manager.unregister_tenant("acme").is_ok());
+        assert!(manager.registered_limits("acme").is_none());
+        assert_eq!(manager.get_limits("acme").max_connections, 1000);
+        assert_eq!(
+            manager.unregister_tenant("acme"),
+            Err(TenantRegistrationError::NotFound("acme".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_registered_tenant_overrides_static_config() {
+        let mut overrides = HashMap::new();
+        overrides.insert("premium".to_string(), default_tenant_limits());
+        let manager = TenantManager::new(TenantConfig {
+            enabled: true,
+            default_limits: default_tenant_limits(),
+            tenant_overrides: overrides,
+            ..Default::default()
+        });
+
+        let limits = TenantLimitsConfig {
+            max_connections: 9000,
+            max_connections_per_user: 20,
+            max_subscriptions_per_connection: 200,
+        };
+        manager.register_tenant("premium", limits).unwrap();
+        assert_eq!(manager.get_limits("premium").max_connections, 9000);
+    }
+
+    #[test]
+    fn test_unregister_tenant_with_active_connections() {
+        let manager = TenantManager::new(TenantConfig {
+            enabled: true,
+            ..Default::default()
+        });
+        manager
+            .register_tenant("acme", default_tenant_limits())
+            .unwrap();
+
+        manager.record_connection("acme");
+        manager.record_connection("acme");
+        assert_eq!(
+            manager.unregister_tenant("acme"),
+            Err(TenantRegistrationError::HasActiveConnections(2))
+        );
+        assert!(manager.registered_limits("acme").is_some());
+
+        manager.record_disconnection("acme");
+        manager.record_disconnection("acme");
+        assert!(manager.unregister_tenant("acme").is_ok());
+    }
+
+    #[test]
+    fn test_aggregate_stats_sums_all_tenants() {
+        let manager = TenantManager::default();
+        assert_eq!(manager.get_aggregate_stats().total_connections, 0);
+
+        // (tenant, connections, disconnections, messages sent, messages delivered)
+        for (tenant, connections, disconnections, sent, delivered) in [
+            ("acme", 3, 1, 5, 10),
+            ("globex", 2, 0, 1, 4),
+            ("initech", 4, 4, 0, 0),
+        ] {
+            for _ in 0..connections {
+                manager.record_connection(tenant);
+            }
+            for _ in 0..disconnections {
+                manager.record_disconnection(tenant);
+            }
+            for _ in 0..sent {
+                manager.record_message_sent(tenant);
+            }
+            manager.record_message_delivered(tenant, delivered);
+        }
+
+        let aggregate = manager.get_aggregate_stats();
+        assert_eq!(aggregate.active_connections, 4);
+        assert_eq!(aggregate.total_connections, 9);
+        assert_eq!(aggregate.messages_sent, 6);
+        assert_eq!(aggregate.messages_delivered, 14);
+    }
+
+    #[test]
+    fn test_validate_channel_name_default_policy() {
+        let ctx = TenantContext::new("acme");
+        assert!(ctx.validate_channel_name("orders").is_ok());
+        assert_eq!(
+            ctx.validate_channel_name(&"a".repeat(65)),
+            Err(TenantPolicyError::ChannelNameTooLong { max: 64 })
+        );
+    }
+
+    #[test]
+    fn test_validate_channel_name_with_pattern() {
+        let policy = TenantPolicy {
+            allowed_channel_pattern: Some(Regex::new("^(?:orders|alerts)(\\..+)?$").unwrap()),
+            max_channel_name_length: 20,
+        };
+        let ctx = TenantContext::new("acme").with_policy(policy);
+
+        assert!(ctx.validate_channel_name("orders").is_ok());
+        assert!(ctx.validate_channel_name("alerts.eu").is_ok());
+
+        let err = ctx.validate_channel_name("billing").unwrap_err();
+        assert_eq!(err.reason(), "pattern_mismatch");
+
+        let err = ctx.validate_channel_name("orders.very-long-suffix").unwrap_err();
+        assert_eq!(err.reason(), "too_long");
+    }
+
+    #[test]
+    fn test_policy_deserialize_anchors_pattern() {
+        let policy: TenantPolicy =
+            serde_json::from_str(r#"{"allowed_channel_pattern": "orders-[0-9]+"}"#).unwrap();
+        assert_eq!(policy.max_channel_name_length, 64);
+
+        let ctx = TenantContext::new("acme").with_policy(policy);
+        assert!(ctx.validate_channel_name("orders-42").is_ok());
+        assert!(ctx.validate_channel_name("xorders-42").is_err());
+        assert!(ctx.validate_channel_name("orders-42x").is_err());
+
+        let invalid = serde_json::from_str::<TenantPolicy>(r#"{"allowed_channel_pattern": "("}"#);
+        assert!(invalid.is_err());
+    }
+
+    #[test]
+    fn test_create_context_applies_policy_override() {
+        let mut policy_overrides = HashMap::new();
+        policy_overrides.insert(
+            "acme".to_string(),
+            TenantPolicy {
+                allowed_channel_pattern: Some(Regex::new("^orders$").unwrap()),
+                max_channel_name_length: 64,
+            },
+        );
+        let manager = TenantManager::new(TenantConfig {
+            enabled: true,
+            policy_overrides,
+            ..Default::default()
+        });
+
+        let acme = manager.create_context("acme");
+        assert!(acme.validate_channel_name("orders").is_ok());
+        assert!(acme.validate_channel_name("alerts").is_err());
+
+        let other = manager.create_context("other");
+        assert!(other.validate_channel_name("alerts").is_ok());
+    }
+
+    #[test]
+    fn test_sample_metrics_history() {
+        let manager = TenantManager::new(TenantConfig {
+            enabled: true,
+            ..Default::default()
+        });
+        manager.record_connection("acme");
+        manager.record_message_sent("acme");
+
+        assert_eq!(manager.sample_metrics(60), 1);
+        manager.record_message_sent("acme");
+        manager.record_message_sent("acme");
+        manager.sample_metrics(120);
+
+        let samples = manager.metrics_history("acme", 0, i64::MAX);
+        assert_eq!(samples.len(), 2);
+        assert_eq!(samples[1].active_connections, 1);
+        assert_eq!(samples[1].messages_per_minute, 2);
+
+        assert_eq!(manager.metrics_history("acme", 100, 200).len(), 1);
+        assert!(manager.metrics_history("unknown", 0, i64::MAX).is_empty());
+    }
+
     #[test]
     fn test_tenant_stats_recording() {
         let manager = TenantManager::new(TenantConfig {
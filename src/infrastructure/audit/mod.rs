@@ -0,0 +1,146 @@
+//! Compliance audit trail for notification dispatches.
+//!
+//! Every dispatch is emitted as a `tracing` event under [`AUDIT_TARGET`]. When
+//! `audit.enabled` is set, [`AuditLogger`] is added to the subscriber as its own layer and
+//! writes those events as JSON lines to a daily-rotated file. The layer carries its own
+//! filter ([`AuditLogger::filter`]), so `RUST_LOG` never suppresses audit entries, and the
+//! console filter excludes them from the application log.
+
+use std::fmt;
+use std::io::Write;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_appender::non_blocking::{NonBlocking, NonBlockingBuilder, WorkerGuard};
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::filter::Targets;
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::layer::{Context, Layer};
+use uuid::Uuid;
+
+use crate::config::AuditConfig;
+
+/// `tracing` target used for audit events
+pub const AUDIT_TARGET: &str = "audit";
+
+/// Name of the event field carrying the serialized `AuditEvent`
+const AUDIT_FIELD: &str = "audit";
+
+/// Whether any subscriber layer is listening for audit events, so callers can skip
+/// building and serializing entries nobody will write
+pub fn audit_enabled() -> bool {
+    tracing::enabled!(target: AUDIT_TARGET, Level::INFO)
+}
+
+/// One audit entry per notification dispatch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEvent {
+    pub notification_id: Uuid,
+    /// `user`, `users`, `broadcast`, `channel`, `channels`, `channel_pattern` or `role`
+    pub target_type: String,
+    /// Target user or channel; lists are comma-separated, `*` for broadcasts
+    pub user_id_or_channel: String,
+    pub source: String,
+    pub event_type: String,
+    pub priority: String,
+    /// Number of connections the notification was delivered to
+    pub delivered_to: usize,
+    /// Tenant scope of the dispatch (`None` when not tenant-scoped)
+    pub tenant_id: Option<String>,
+    pub timestamp: DateTime<Utc>,
+    pub correlation_id: Option<String>,
+    /// Service that sent the notification on behalf of `on_behalf_of` (JWT `sub`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub caller_id: Option<String>,
+    /// User the notification was sent on behalf of
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_behalf_of: Option<String>,
+}
+
+impl AuditEvent {
+    /// Emit this entry to the audit trail (a no-op unless an `AuditLogger` is installed)
+    pub fn record(&self) {
+        if !audit_enabled() {
+            return;
+        }
+        match serde_json::to_string(self) {
+            Ok(line) => tracing::event!(target: AUDIT_TARGET, Level::INFO, audit = line.as_str()),
+            Err(e) => tracing::error!(
+                notification_id = %self.notification_id,
+                error = %e,
+                "Failed to serialize audit event"
+            ),
+        }
+    }
+}
+
+/// Subscriber layer writing audit events as JSON lines
+pub struct AuditLogger<W = NonBlocking> {
+    writer: W,
+}
+
+impl AuditLogger {
+    /// Write to `<directory>/<prefix>.YYYY-MM-DD`, rotated daily.
+    ///
+    /// Writes go through a background worker that blocks rather than drops entries when
+    /// it falls behind; keep the returned guard alive to flush on shutdown.
+    pub fn daily(config: &AuditConfig) -> Result<(Self, WorkerGuard), String> {
+        let appender = RollingFileAppender::builder()
+            .rotation(Rotation::DAILY)
+            .filename_prefix(&config.prefix)
+            .build(&config.directory)
+            .map_err(|e| e.to_string())?;
+        let (writer, guard) = NonBlockingBuilder::default().lossy(false).finish(appender);
+        Ok((Self { writer }, guard))
+    }
+
+    /// Per-layer filter selecting only audit events, independent of `RUST_LOG`
+    pub fn filter() -> Targets {
+        Targets::new().with_target(AUDIT_TARGET, Level::INFO)
+    }
+}
+
+impl<W> AuditLogger<W>
+where
+    W: for<'a> MakeWriter<'a> + 'static,
+{
+    /// Write audit lines to an arbitrary writer
+    pub fn with_writer(writer: W) -> Self {
+        Self { writer }
+    }
+}
+
+impl<S, W> Layer<S> for AuditLogger<W>
+where
+    S: Subscriber,
+    W: for<'a> MakeWriter<'a> + 'static,
+{
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        if event.metadata().target() != AUDIT_TARGET {
+            return;
+        }
+        let mut visitor = AuditFieldVisitor(None);
+        event.record(&mut visitor);
+        if let Some(line) = visitor.0 {
+            let mut writer = self.writer.make_writer();
+            if let Err(e) = writeln!(writer, "{}", line) {
+                eprintln!("Failed to write audit event: {}", e);
+            }
+        }
+    }
+}
+
+/// Extracts the pre-serialized JSON line from an audit event
+struct AuditFieldVisitor(Option<String>);
+
+impl Visit for AuditFieldVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == AUDIT_FIELD {
+            self.0 = Some(value.to_string());
+        }
+    }
+
+    fn record_debug(&mut self, _field: &Field, _value: &dyn fmt::Debug) {}
+}
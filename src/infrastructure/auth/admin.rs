@@ -0,0 +1,151 @@
+//! Admin API key authentication.
+
+use std::sync::Arc;
+
+use axum::extract::{FromRef, FromRequestParts};
+use axum::http::{header, request::Parts, HeaderMap};
+use subtle::ConstantTimeEq;
+
+use crate::config::Settings;
+use crate::error::AppError;
+use crate::metrics::ADMIN_AUTH_FAILURES_TOTAL;
+
+/// Proof that a request carried `Authorization: Bearer <api.admin_key>`.
+///
+/// Guard a router with `axum::middleware::from_extractor_with_state::<AdminAuth, _>(state)`.
+/// Without a configured admin key the regular `api.key` is required instead; with neither
+/// configured, every admin request is rejected.
+#[derive(Debug, Clone, Copy)]
+pub struct AdminAuth;
+
+impl<S> FromRequestParts<S> for AdminAuth
+where
+    Arc<Settings>: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let settings = Arc::<Settings>::from_ref(state);
+        AdminAuth::verify(&settings, &parts.headers)
+    }
+}
+
+impl AdminAuth {
+    /// Check `headers` for the admin API key, for handlers that also accept other credentials
+    pub fn verify(settings: &Settings, headers: &HeaderMap) -> Result<Self, AppError> {
+        let Some(expected_key) = settings.api.admin_key.as_deref().or(settings.api.key.as_deref())
+        else {
+            tracing::error!(
+                "Neither ADMIN_API_KEY nor API_KEY is configured, rejecting admin request"
+            );
+            return Err(reject("not_configured", "Admin API key is not configured"));
+        };
+
+        let provided = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "));
+
+        match provided {
+            Some(key) if bool::from(key.as_bytes().ct_eq(expected_key.as_bytes())) => Ok(AdminAuth),
+            Some(_) => {
+                tracing::warn!("Invalid admin API key provided");
+                Err(reject("invalid", "Invalid admin API key"))
+            }
+            None => {
+                tracing::warn!("Missing admin API key");
+                Err(reject("missing", "Missing admin API key"))
+            }
+        }
+    }
+}
+
+fn reject(reason: &str, message: &str) -> AppError {
+    ADMIN_AUTH_FAILURES_TOTAL.with_label_values(&[reason]).inc();
+    AppError::Auth(message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use axum::http::{header, Request, StatusCode};
+    use axum::{middleware, routing::get, Router};
+    use serde_json::json;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    const ADMIN_KEY: &str = "admin-key-for-tests-0123456789";
+
+    fn settings(admin_key: Option<&str>, is_production: bool) -> Arc<Settings> {
+        settings_with_api_key(admin_key, None, is_production)
+    }
+
+    fn settings_with_api_key(
+        admin_key: Option<&str>,
+        api_key: Option<&str>,
+        is_production: bool,
+    ) -> Arc<Settings> {
+        let mut settings: Settings = serde_json::from_value(json!({
+            "jwt": { "secret": "test-secret-key-for-admin-auth-tests" },
+            "api": { "admin_key": admin_key, "key": api_key }
+        }))
+        .unwrap();
+        settings.is_production = is_production;
+        Arc::new(settings)
+    }
+
+    fn app(settings: Arc<Settings>) -> Router {
+        Router::new()
+            .route("/admin/ping", get(|| async { "pong" }))
+            .layer(middleware::from_extractor_with_state::<AdminAuth, _>(settings))
+    }
+
+    async fn get_status(app: Router, authorization: Option<&str>) -> StatusCode {
+        let mut request = Request::builder().uri("/admin/ping");
+        if let Some(value) = authorization {
+            request = request.header(header::AUTHORIZATION, value);
+        }
+        app.oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn test_admin_auth_correct_key() {
+        let app = app(settings(Some(ADMIN_KEY), true));
+        let status = get_status(app, Some(&format!("Bearer {}", ADMIN_KEY))).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_admin_auth_wrong_key() {
+        let app = app(settings(Some(ADMIN_KEY), true));
+        assert_eq!(get_status(app.clone(), Some("Bearer wrong-key")).await, StatusCode::UNAUTHORIZED);
+        // The key must be sent as a bearer token
+        assert_eq!(get_status(app, Some(ADMIN_KEY)).await, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_admin_auth_missing_key() {
+        let app = app(settings(Some(ADMIN_KEY), true));
+        assert_eq!(get_status(app, None).await, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_admin_auth_unconfigured_key() {
+        // Never fails open, not even in development
+        assert_eq!(get_status(app(settings(None, false)), None).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(get_status(app(settings(None, true)), None).await, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_admin_auth_falls_back_to_api_key() {
+        let app = app(settings_with_api_key(None, Some("api-key"), false));
+        assert_eq!(get_status(app.clone(), None).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(get_status(app.clone(), Some("Bearer wrong-key")).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(get_status(app, Some("Bearer api-key")).await, StatusCode::OK);
+    }
+}
@@ -6,8 +6,10 @@ pub const DEFAULT_TENANT_ID: &str = "default";
 
 /// Build a queue key that includes tenant scope.
 /// Non-default tenants get `{tenant_id}:{user_id}`, default tenant gets `{user_id}`.
+/// A default-tenant user ID containing `:` gets `default:{user_id}`, so a subject
+/// like `acme:1` can't share a key with tenant `acme`'s user `1`.
 pub fn tenant_scoped_key(tenant_id: &str, user_id: &str) -> String {
-    if tenant_id == DEFAULT_TENANT_ID {
+    if tenant_id == DEFAULT_TENANT_ID && !user_id.contains(':') {
         user_id.to_string()
     } else {
         format!("{}:{}", tenant_id, user_id)
@@ -28,6 +30,9 @@ pub struct Claims {
     /// Tenant ID for multi-tenancy support
     #[serde(default)]
     pub tenant_id: Option<String>,
+    /// Space-separated OAuth-style scopes granted to the caller (e.g. `"impersonate"`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
     /// Additional custom claims
     #[serde(flatten)]
     pub extra: HashMap<String, serde_json::Value>,
@@ -47,6 +52,12 @@ impl Claims {
         self.roles.iter().any(|r| r == role)
     }
 
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scope
+            .as_deref()
+            .is_some_and(|scopes| scopes.split_whitespace().any(|s| s == scope))
+    }
+
     pub fn is_expired(&self) -> bool {
         let now = chrono::Utc::now().timestamp();
         self.exp < now
@@ -60,6 +71,11 @@ mod tests {
     #[test]
     fn test_tenant_scoped_key_default_tenant() {
         assert_eq!(tenant_scoped_key("default", "user-123"), "user-123");
+        assert_eq!(tenant_scoped_key("default", "acme:1"), "default:acme:1");
+        assert_ne!(
+            tenant_scoped_key("default", "acme:1"),
+            tenant_scoped_key("acme", "1")
+        );
     }
 
     #[test]
@@ -78,6 +94,7 @@ mod tests {
             iat: 0,
             roles: vec![],
             tenant_id: None,
+            scope: None,
             extra: std::collections::HashMap::new(),
         };
         assert_eq!(claims.tenant_id(), "default");
@@ -91,8 +108,24 @@ mod tests {
             iat: 0,
             roles: vec![],
             tenant_id: Some("acme".to_string()),
+            scope: None,
             extra: std::collections::HashMap::new(),
         };
         assert_eq!(claims.tenant_id(), "acme");
     }
+
+    #[test]
+    fn test_claims_has_scope() {
+        let claims: Claims = serde_json::from_value(serde_json::json!({
+            "sub": "billing-service",
+            "exp": i64::MAX,
+            "iat": 0,
+            "scope": "notify impersonate"
+        }))
+        .unwrap();
+        assert!(claims.has_scope("impersonate"));
+        assert!(claims.has_scope("notify"));
+        assert!(!claims.has_scope("imperson"));
+        assert!(!claims.extra.contains_key("scope"));
+    }
 }
@@ -97,6 +97,7 @@ mod tests {
             iat: chrono::Utc::now().timestamp(),
             roles: vec!["user".to_string()],
             tenant_id: None,
+            scope: None,
             extra: Default::default(),
         };
 
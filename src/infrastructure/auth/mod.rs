@@ -1,5 +1,7 @@
+mod admin;
 mod claims;
 mod jwt;
 
+pub use admin::AdminAuth;
 pub use claims::{tenant_scoped_key, Claims, DEFAULT_TENANT_ID};
 pub use jwt::JwtValidator;
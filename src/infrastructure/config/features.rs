@@ -0,0 +1,139 @@
+//! Runtime feature flags.
+//!
+//! Operators can switch individual modules off without a redeploy by setting
+//! `ARA_FEATURE_<NAME>=false` (also `0`, `off` or `no`). Flags are read once at
+//! startup and take precedence over the module's own configuration. Unset or
+//! unrecognized values leave the feature enabled.
+
+use std::env;
+
+use crate::metrics::FEATURE_FLAG_DISABLED;
+
+/// Modules that can be disabled with `ARA_FEATURE_*` environment variables
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeatureFlags {
+    /// `ARA_FEATURE_ACK_TRACKING`: delivery ACK tracking (a no-op backend is used when off)
+    pub ack_tracking: bool,
+    /// `ARA_FEATURE_CLUSTER`: cluster session store and routed message subscriber
+    pub cluster: bool,
+    /// `ARA_FEATURE_RATE_LIMITING`: HTTP and connection rate limiting
+    pub rate_limiting: bool,
+    /// `ARA_FEATURE_MESSAGE_QUEUE`: offline message queue
+    pub message_queue: bool,
+    /// `ARA_FEATURE_SSE`: the `/sse` endpoint
+    pub sse: bool,
+}
+
+impl Default for FeatureFlags {
+    fn default() -> Self {
+        Self {
+            ack_tracking: true,
+            cluster: true,
+            rate_limiting: true,
+            message_queue: true,
+            sse: true,
+        }
+    }
+}
+
+impl FeatureFlags {
+    /// Read the flags from the process environment
+    pub fn from_env() -> Self {
+        Self::from_lookup(|key| env::var(key).ok())
+    }
+
+    /// Read the flags through `lookup`, which maps a variable name to its value
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let enabled = |name: &str| {
+            lookup(&format!("ARA_FEATURE_{}", name))
+                .map(|value| !is_off(&value))
+                .unwrap_or(true)
+        };
+        Self {
+            ack_tracking: enabled("ACK_TRACKING"),
+            cluster: enabled("CLUSTER"),
+            rate_limiting: enabled("RATE_LIMITING"),
+            message_queue: enabled("MESSAGE_QUEUE"),
+            sse: enabled("SSE"),
+        }
+    }
+
+    /// Feature names paired with whether they are enabled
+    pub fn entries(&self) -> [(&'static str, bool); 5] {
+        [
+            ("ack_tracking", self.ack_tracking),
+            ("cluster", self.cluster),
+            ("rate_limiting", self.rate_limiting),
+            ("message_queue", self.message_queue),
+            ("sse", self.sse),
+        ]
+    }
+
+    /// Names of the disabled features
+    pub fn disabled(&self) -> Vec<&'static str> {
+        self.entries()
+            .into_iter()
+            .filter(|(_, enabled)| !enabled)
+            .map(|(name, _)| name)
+            .collect()
+    }
+
+    /// Publish the `feature_flag_disabled` gauge for every feature
+    pub fn record_metrics(&self) {
+        for (name, enabled) in self.entries() {
+            FEATURE_FLAG_DISABLED
+                .with_label_values(&[name])
+                .set(i64::from(!enabled));
+        }
+    }
+}
+
+fn is_off(value: &str) -> bool {
+    matches!(
+        value.trim().to_ascii_lowercase().as_str(),
+        "false" | "0" | "off" | "no"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_feature_flags_from_lookup() {
+        let vars: HashMap<&str, &str> = [
+            ("ARA_FEATURE_ACK_TRACKING", "false"),
+            ("ARA_FEATURE_SSE", "Off"),
+            ("ARA_FEATURE_CLUSTER", "true"),
+            ("ARA_FEATURE_RATE_LIMITING", "maybe"),
+        ]
+        .into_iter()
+        .collect();
+
+        let flags = FeatureFlags::from_lookup(|key| vars.get(key).map(|v| v.to_string()));
+
+        assert!(!flags.ack_tracking);
+        assert!(!flags.sse);
+        assert!(flags.cluster);
+        // Unrecognized values and unset variables keep the feature on
+        assert!(flags.rate_limiting);
+        assert!(flags.message_queue);
+        assert_eq!(flags.disabled(), vec!["ack_tracking", "sse"]);
+    }
+
+    #[test]
+    fn test_record_metrics() {
+        let flags = FeatureFlags {
+            message_queue: false,
+            ..Default::default()
+        };
+        flags.record_metrics();
+        assert_eq!(
+            FEATURE_FLAG_DISABLED.with_label_values(&["message_queue"]).get(),
+            1
+        );
+        assert_eq!(FEATURE_FLAG_DISABLED.with_label_values(&["cluster"]).get(), 0);
+    }
+}
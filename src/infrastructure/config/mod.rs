@@ -1,6 +1,10 @@
+mod features;
+mod secrets;
 mod settings;
 
+pub use features::FeatureFlags;
 pub use settings::{
-    AckSettingsConfig, DatabaseConfig, JwtConfig, OtelConfig, QueueConfig, RateLimitConfig,
-    RedisConfig, Settings, WebSocketConfig,
+    AckSettingsConfig, AdminConfig, AuditConfig, CorsConfig, DatabaseConfig, DispatcherConfig, GrpcConfig,
+    HealthConfig, JwtConfig, MetricsConfig, OtelConfig, PropagationFormat, QueueConfig,
+    RateLimitConfig, RedisConfig, Settings, ShutdownSettingsConfig, WebSocketConfig,
 };
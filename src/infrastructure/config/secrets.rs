@@ -0,0 +1,127 @@
+//! Secrets overlay read from a `KEY=VALUE` file, such as a Docker secrets mount.
+//!
+//! Only the keys in [`SECRET_KEYS`] may come from the file; anything else is ignored
+//! and reported so it can be logged once telemetry is up. Values from the file take
+//! priority over environment variables and config files.
+
+use std::path::Path;
+
+use config::ConfigError;
+
+/// Keys that may be set from the secrets file (matched case-insensitively)
+pub const SECRET_KEYS: [&str; 5] = [
+    "jwt_secret",
+    "redis_password",
+    "postgres_password",
+    "admin_api_key",
+    "cluster_secret",
+];
+
+/// Whitelisted entries of a secrets file, plus the keys that were ignored
+#[derive(Debug, Default)]
+pub struct Secrets {
+    entries: Vec<(&'static str, String)>,
+    /// Keys (or `line N` for lines without `=`) that are not allowed in the secrets file
+    pub ignored: Vec<String>,
+}
+
+impl Secrets {
+    /// Read and parse the secrets file at `path`
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        let contents = std::fs::read_to_string(path).map_err(|e| {
+            ConfigError::Message(format!(
+                "failed to read secrets file {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        Ok(Self::parse(&contents))
+    }
+
+    /// Parse `KEY=VALUE` lines; blank lines and `#` comments are skipped, and values
+    /// may be wrapped in single or double quotes
+    pub fn parse(contents: &str) -> Self {
+        let mut secrets = Self::default();
+        for (index, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                secrets.ignored.push(format!("line {}", index + 1));
+                continue;
+            };
+            let key = key.trim().to_ascii_lowercase();
+            match SECRET_KEYS.iter().find(|allowed| **allowed == key) {
+                Some(allowed) => secrets.entries.push((allowed, unquote(value.trim()).to_string())),
+                None => secrets.ignored.push(key),
+            }
+        }
+        secrets
+    }
+
+    /// Value for a whitelisted key; the last occurrence wins
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|(k, _)| *k == key)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+fn unquote(value: &str) -> &str {
+    for quote in ['"', '\''] {
+        if let Some(inner) = value
+            .strip_prefix(quote)
+            .and_then(|v| v.strip_suffix(quote))
+        {
+            return inner;
+        }
+    }
+    value
+}
+
+/// Replace the password of a connection URL such as `redis://host:6379`
+pub fn with_password(url: &str, password: &str, setting: &str) -> Result<String, ConfigError> {
+    let mut parsed = url::Url::parse(url)
+        .map_err(|e| ConfigError::Message(format!("{} is not a valid URL: {}", setting, e)))?;
+    parsed
+        .set_password(Some(password))
+        .map_err(|_| ConfigError::Message(format!("{} cannot carry a password", setting)))?;
+    Ok(parsed.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_keeps_whitelisted_keys_only() {
+        let secrets = Secrets::parse(
+            "# mounted secrets\n\
+             JWT_SECRET=\"quoted secret\"\n\
+             redis_password = s3cret\n\
+             server_port=9000\n\
+             not a pair\n",
+        );
+
+        assert_eq!(secrets.get("jwt_secret"), Some("quoted secret"));
+        assert_eq!(secrets.get("redis_password"), Some("s3cret"));
+        assert_eq!(secrets.get("cluster_secret"), None);
+        assert_eq!(secrets.ignored, vec!["server_port", "line 5"]);
+    }
+
+    #[test]
+    fn test_with_password() {
+        assert_eq!(
+            with_password("redis://localhost:6379", "p@ss", "redis.url").unwrap(),
+            "redis://:p%40ss@localhost:6379"
+        );
+        assert_eq!(
+            with_password("postgres://ara:old@db:5432/ara", "new", "database.url").unwrap(),
+            "postgres://ara:new@db:5432/ara"
+        );
+        assert!(with_password("not a url", "x", "redis.url").is_err());
+    }
+}
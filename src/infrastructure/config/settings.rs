@@ -1,9 +1,15 @@
 use config::{Config, ConfigError, Environment, File};
 use serde::{Deserialize, Deserializer};
 use std::env;
+use std::path::{Path, PathBuf};
 
 use crate::cluster::ClusterConfig;
+use crate::ratelimit::RateLimitRule;
 use crate::tenant::TenantConfig;
+use crate::websocket::PROTOCOL_VERSION;
+
+use super::secrets::{with_password, Secrets};
+use super::FeatureFlags;
 
 /// Deserialize a comma-separated string into a Vec<String>
 fn deserialize_comma_separated<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
@@ -24,16 +30,30 @@ where
 pub struct Settings {
     #[serde(default)]
     pub server: ServerConfig,
+    #[serde(default)]
+    pub cors: CorsConfig,
+    #[serde(default)]
+    pub health: HealthConfig,
+    #[serde(default)]
+    pub metrics: MetricsConfig,
+    #[serde(default)]
+    pub audit: AuditConfig,
     pub jwt: JwtConfig,
     #[serde(default)]
     pub redis: RedisConfig,
     #[serde(default)]
     pub api: ApiConfig,
     #[serde(default)]
+    pub admin: AdminConfig,
+    #[serde(default)]
     pub websocket: WebSocketConfig,
     #[serde(default)]
     pub queue: QueueConfig,
     #[serde(default)]
+    pub dispatcher: DispatcherConfig,
+    #[serde(default)]
+    pub grpc: GrpcConfig,
+    #[serde(default)]
     pub ratelimit: RateLimitConfig,
     #[serde(default)]
     pub ack: AckSettingsConfig,
@@ -45,9 +65,17 @@ pub struct Settings {
     pub database: DatabaseConfig,
     #[serde(default)]
     pub cluster: ClusterConfig,
+    #[serde(default)]
+    pub shutdown: ShutdownSettingsConfig,
     /// Whether the service is running in production mode (derived from RUN_MODE env var)
     #[serde(skip)]
     pub is_production: bool,
+    /// Modules switched off with `ARA_FEATURE_*` env vars (read once at startup)
+    #[serde(skip)]
+    pub features: FeatureFlags,
+    /// Keys in the secrets file that are not allowed there and were ignored
+    #[serde(skip)]
+    pub ignored_secret_keys: Vec<String>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -76,6 +104,25 @@ pub struct RateLimitConfig {
     /// Redis key prefix for rate limit data
     #[serde(default = "default_ratelimit_redis_prefix")]
     pub redis_prefix: String,
+    /// CIDR ranges that bypass rate limiting (comma-separated, IPv4 or IPv6)
+    #[serde(default, deserialize_with = "deserialize_comma_separated")]
+    pub ip_allowlist: Vec<String>,
+    /// CIDR ranges that are always rejected (comma-separated, IPv4 or IPv6)
+    #[serde(default, deserialize_with = "deserialize_comma_separated")]
+    pub ip_blocklist: Vec<String>,
+    /// Share of denied requests and of denied clients above which enforcement is bypassed
+    /// as misconfigured (0, the default, disables the bypass)
+    #[serde(default = "default_emergency_bypass_deny_ratio")]
+    pub emergency_bypass_deny_ratio: f64,
+    /// Window over which the deny ratio is measured, and the longest a bypass lasts (seconds)
+    #[serde(default = "default_emergency_bypass_window_seconds")]
+    pub emergency_bypass_window_seconds: u64,
+    /// Minimum decisions in the window before the deny ratio is trusted
+    #[serde(default = "default_emergency_bypass_min_requests")]
+    pub emergency_bypass_min_requests: u64,
+    /// Path-specific HTTP limits (`pattern=rps:burst`, comma-separated); first match wins
+    #[serde(default, deserialize_with = "crate::ratelimit::deserialize_rules")]
+    pub rules: Vec<RateLimitRule>,
 }
 
 fn default_ratelimit_backend() -> String {
@@ -106,6 +153,18 @@ fn default_ratelimit_cleanup_interval() -> u64 {
     60
 }
 
+fn default_emergency_bypass_deny_ratio() -> f64 {
+    0.0
+}
+
+fn default_emergency_bypass_window_seconds() -> u64 {
+    300
+}
+
+fn default_emergency_bypass_min_requests() -> u64 {
+    100
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct AckSettingsConfig {
     /// Whether ACK tracking is enabled
@@ -167,6 +226,33 @@ pub struct OtelConfig {
     /// Sampling ratio (0.0 to 1.0)
     #[serde(default = "default_otel_sampling_ratio")]
     pub sampling_ratio: f64,
+    /// Trace context header format used to propagate spans across services
+    #[serde(default)]
+    pub propagation_format: PropagationFormat,
+    /// Replace span attributes matching `pii_attribute_patterns` before export
+    #[serde(default)]
+    pub pii_redaction_enabled: bool,
+    /// Regexes matched against span attribute keys (comma-separated in env)
+    #[serde(
+        default = "default_otel_pii_attribute_patterns",
+        deserialize_with = "deserialize_comma_separated"
+    )]
+    pub pii_attribute_patterns: Vec<String>,
+}
+
+/// Trace context propagation format
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PropagationFormat {
+    /// W3C Trace Context (`traceparent` / `tracestate`)
+    #[default]
+    W3cTraceContext,
+    /// Zipkin B3 single header (`b3`)
+    B3Single,
+    /// Zipkin B3 multiple headers (`X-B3-TraceId`, `X-B3-SpanId`, `X-B3-Sampled`)
+    B3Multi,
+    /// Inject W3C and B3 multi headers; extract whichever is present
+    Composite,
 }
 
 fn default_otel_endpoint() -> String {
@@ -181,6 +267,10 @@ fn default_otel_sampling_ratio() -> f64 {
     1.0 // Sample all traces by default
 }
 
+fn default_otel_pii_attribute_patterns() -> Vec<String> {
+    vec![r"^user\.id$".to_string(), r"^user\.email$".to_string()]
+}
+
 impl Default for OtelConfig {
     fn default() -> Self {
         Self {
@@ -188,6 +278,9 @@ impl Default for OtelConfig {
             endpoint: default_otel_endpoint(),
             service_name: default_otel_service_name(),
             sampling_ratio: default_otel_sampling_ratio(),
+            propagation_format: PropagationFormat::default(),
+            pii_redaction_enabled: false,
+            pii_attribute_patterns: default_otel_pii_attribute_patterns(),
         }
     }
 }
@@ -212,12 +305,84 @@ pub struct WebSocketConfig {
     /// Maximum channel subscriptions per connection (0 = unlimited)
     #[serde(default = "default_max_subscriptions")]
     pub max_subscriptions_per_connection: usize,
+    /// Log a warning when a user holds more connections than this (0 = disabled)
+    #[serde(default)]
+    pub connection_alert_threshold: usize,
+    /// Back off the heartbeat interval while no messages are dispatched and all pings succeed
+    #[serde(default)]
+    pub heartbeat_adaptive: bool,
+    /// Upper bound in seconds for the adaptive heartbeat interval
+    #[serde(default = "default_heartbeat_idle_interval")]
+    pub heartbeat_idle_interval: u64,
+    /// Evict a connection after this many heartbeats in a row go unanswered (0 = never)
+    #[serde(default = "default_max_missed_pings")]
+    pub max_missed_pings: u32,
+    /// Maximum concurrent connection sends per broadcast/channel fan-out
+    #[serde(default = "default_max_fanout_concurrency")]
+    pub max_fanout_concurrency: usize,
+    /// Oldest `?protocol_version=` accepted; older clients are closed with code 4000
+    #[serde(default = "default_min_protocol_version")]
+    pub min_client_protocol_version: u8,
+    /// Newest protocol version served; newer clients are negotiated down to it
+    #[serde(default = "default_protocol_version")]
+    pub max_client_protocol_version: u8,
+    /// Timeout in milliseconds for a single Redis pool command, including connecting
+    #[serde(default = "default_redis_command_timeout_ms")]
+    pub redis_command_timeout_ms: u64,
+    /// Evict a connection whose outbound channel does not accept a notification within
+    /// this many milliseconds
+    #[serde(default = "default_per_connection_send_timeout_ms")]
+    pub per_connection_send_timeout_ms: u64,
+    /// Largest WebSocket frame (and message) accepted from a client; connections sending a
+    /// larger one are closed with code 1009
+    #[serde(default = "default_max_frame_size_bytes")]
+    pub max_frame_size_bytes: usize,
+    /// Reconnect delay in milliseconds sent to SSE clients in the `retry:` field
+    #[serde(default = "default_sse_reconnect_retry_ms")]
+    pub sse_reconnect_retry_ms: u64,
 }
 
 fn default_heartbeat_interval() -> u64 {
     30 // 30 seconds
 }
 
+fn default_heartbeat_idle_interval() -> u64 {
+    60 // 1 minute
+}
+
+fn default_max_missed_pings() -> u32 {
+    3
+}
+
+fn default_max_fanout_concurrency() -> usize {
+    1000
+}
+
+/// Version 1 stays accepted until operators raise the minimum
+fn default_min_protocol_version() -> u8 {
+    1
+}
+
+fn default_protocol_version() -> u8 {
+    PROTOCOL_VERSION
+}
+
+fn default_redis_command_timeout_ms() -> u64 {
+    5000 // 5 seconds
+}
+
+fn default_per_connection_send_timeout_ms() -> u64 {
+    500
+}
+
+fn default_max_frame_size_bytes() -> usize {
+    64 * 1024 // 64 KB
+}
+
+fn default_sse_reconnect_retry_ms() -> u64 {
+    5000 // 5 seconds
+}
+
 fn default_connection_timeout() -> u64 {
     120 // 2 minutes
 }
@@ -258,6 +423,9 @@ pub struct QueueConfig {
     /// Redis key prefix for queue data (default: "ara:queue")
     #[serde(default = "default_queue_redis_prefix")]
     pub redis_prefix: String,
+    /// Number of internal shards for the memory backend (default: 64)
+    #[serde(default = "default_queue_shard_count")]
+    pub shard_count: usize,
 }
 
 fn default_queue_max_size() -> usize {
@@ -280,14 +448,161 @@ fn default_queue_redis_prefix() -> String {
     "ara:queue".to_string()
 }
 
+fn default_queue_shard_count() -> usize {
+    64
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct ServerConfig {
     #[serde(default = "default_host")]
     pub host: String,
     #[serde(default = "default_port")]
     pub port: u16,
-    #[serde(default, deserialize_with = "deserialize_comma_separated")]
-    pub cors_origins: Vec<String>,
+    /// Turn off gzip/brotli compression of HTTP responses
+    #[serde(default)]
+    pub disable_response_compression: bool,
+    /// Maximum request body size in bytes for notification and admin endpoints
+    #[serde(default = "default_max_request_body_bytes")]
+    pub max_request_body_bytes: usize,
+    /// Maximum request body size in bytes for the batch endpoint
+    #[serde(default = "default_max_batch_request_body_bytes")]
+    pub max_batch_request_body_bytes: usize,
+    /// Time limit for streaming endpoints such as `/notifications/batch-stream`;
+    /// items not processed in time are reported as skipped
+    #[serde(default = "default_stream_request_timeout_seconds")]
+    pub stream_request_timeout_seconds: u64,
+    /// Time limit for each external dependency (Redis, PostgreSQL, migrations) at startup
+    #[serde(default = "default_startup_timeout_seconds")]
+    pub startup_timeout_seconds: u64,
+}
+
+/// Cross-origin access to the HTTP API and SSE endpoint (never applied to `/ws`)
+#[derive(Debug, Clone, Deserialize)]
+pub struct CorsConfig {
+    /// Origins allowed to make cross-origin requests (`CORS_ORIGINS`, comma-separated)
+    #[serde(default, alias = "origins", deserialize_with = "deserialize_comma_separated")]
+    pub allowed_origins: Vec<String>,
+    /// Allow any origin (development only; rejected in production)
+    #[serde(default)]
+    pub allow_all: bool,
+    /// Send `Access-Control-Allow-Credentials: true` to allowed origins
+    #[serde(default)]
+    pub allow_credentials: bool,
+    /// How long browsers may cache preflight responses
+    #[serde(default = "default_cors_max_age_seconds")]
+    pub max_age_seconds: u64,
+}
+
+fn default_cors_max_age_seconds() -> u64 {
+    3600 // 1 hour
+}
+
+/// Compliance audit trail of notification dispatches
+#[derive(Debug, Clone, Deserialize)]
+pub struct AuditConfig {
+    /// Write an audit entry for every dispatch (`AUDIT_ENABLED`)
+    #[serde(default)]
+    pub enabled: bool,
+    /// Directory holding the daily-rotated audit files (`AUDIT_DIRECTORY`)
+    #[serde(default = "default_audit_directory")]
+    pub directory: String,
+    /// File name prefix; files are named `<prefix>.YYYY-MM-DD` (`AUDIT_PREFIX`)
+    #[serde(default = "default_audit_prefix")]
+    pub prefix: String,
+}
+
+fn default_audit_directory() -> String {
+    "logs/audit".to_string()
+}
+
+fn default_audit_prefix() -> String {
+    "audit.log".to_string()
+}
+
+/// Graceful shutdown reporting
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ShutdownSettingsConfig {
+    /// Write the shutdown result (phase timings) as JSON to this file for post-deploy
+    /// tooling (`SHUTDOWN_RESULT_OUTPUT_PATH`)
+    #[serde(default)]
+    pub result_output_path: Option<PathBuf>,
+    /// Private directory (created 0700, must not be accessible to other users) that
+    /// rate limiter state is saved to on shutdown and restored from on startup
+    /// (`SHUTDOWN_RATE_LIMIT_STATE_DIR`; default: `ara-ratelimit` in the temp directory)
+    #[serde(default)]
+    pub rate_limit_state_dir: Option<PathBuf>,
+}
+
+/// Dependency probes run by `/health` and `/health/ready`
+#[derive(Debug, Clone, Deserialize)]
+pub struct HealthConfig {
+    /// Maximum time a single Redis or PostgreSQL probe may take before it counts as unhealthy
+    #[serde(default = "default_health_probe_timeout_seconds")]
+    pub probe_timeout_seconds: u64,
+}
+
+fn default_health_probe_timeout_seconds() -> u64 {
+    2
+}
+
+/// Periodic refresh of polled Prometheus gauges
+#[derive(Debug, Clone, Deserialize)]
+pub struct MetricsConfig {
+    /// Interval between gauge refreshes (memory, queue size, pending ACKs, cluster counts)
+    #[serde(default = "default_metrics_update_interval_seconds")]
+    pub update_interval_seconds: u64,
+    /// Prometheus Pushgateway base URL (`http://` only); metrics are pushed when set
+    #[serde(default)]
+    pub push_gateway_url: Option<String>,
+    /// Interval between pushes to the Pushgateway
+    #[serde(default = "default_metrics_push_interval_seconds")]
+    pub push_interval_seconds: u64,
+}
+
+fn default_metrics_update_interval_seconds() -> u64 {
+    30
+}
+
+fn default_metrics_push_interval_seconds() -> u64 {
+    15
+}
+
+/// Notification dispatch behavior
+#[derive(Debug, Clone, Deserialize)]
+pub struct DispatcherConfig {
+    /// Record notifications for offline users in the in-memory drop log
+    /// (`GET /admin/dropped-notifications`) when the offline queue is disabled
+    #[serde(default)]
+    pub queue_fallback_on_offline: bool,
+    /// Maximum dispatches in progress at once; further dispatches wait for a slot
+    #[serde(default = "default_max_concurrent_dispatches")]
+    pub max_concurrent_dispatches: usize,
+    /// How long a dispatch waits for a slot before failing with `dispatch_queue_full`
+    #[serde(default = "default_dispatch_timeout_ms")]
+    pub dispatch_timeout_ms: u64,
+}
+
+fn default_max_concurrent_dispatches() -> usize {
+    500
+}
+
+fn default_dispatch_timeout_ms() -> u64 {
+    1000
+}
+
+/// gRPC notification publishing API (`proto/notification.proto`)
+#[derive(Debug, Clone, Deserialize)]
+pub struct GrpcConfig {
+    /// Serve the gRPC API alongside HTTP (`GRPC_ENABLED`)
+    #[serde(default)]
+    pub enabled: bool,
+    /// Port the gRPC server listens on, bound to `server.host` (`GRPC_PORT`)
+    #[serde(default = "default_grpc_port")]
+    pub port: u16,
+}
+
+fn default_grpc_port() -> u16 {
+    50051
 }
 
 #[derive(Clone, Deserialize)]
@@ -317,6 +632,9 @@ pub struct RedisConfig {
     pub url: String,
     #[serde(default, deserialize_with = "deserialize_comma_separated")]
     pub channels: Vec<String>,
+    /// Glob patterns subscribed with `PSUBSCRIBE` (e.g. `ara:notifications:*`)
+    #[serde(default, deserialize_with = "deserialize_comma_separated")]
+    pub subscribe_patterns: Vec<String>,
     /// Circuit breaker failure threshold (consecutive failures before opening)
     #[serde(default = "default_circuit_breaker_failure_threshold")]
     pub circuit_breaker_failure_threshold: u32,
@@ -332,6 +650,10 @@ pub struct RedisConfig {
     /// Maximum backoff delay in milliseconds
     #[serde(default = "default_backoff_max_delay")]
     pub backoff_max_delay_ms: u64,
+    /// Cap on the cumulative reconnect wait in milliseconds before the subscriber
+    /// gives up (unset retries forever)
+    #[serde(default)]
+    pub backoff_max_total_wait_ms: Option<u64>,
 }
 
 fn default_circuit_breaker_failure_threshold() -> u32 {
@@ -357,11 +679,38 @@ fn default_backoff_max_delay() -> u64 {
 #[derive(Debug, Clone, Deserialize)]
 pub struct ApiConfig {
     pub key: Option<String>,
+    /// Bearer token for `/admin/*` routes (`ADMIN_API_KEY`); `key` is used when unset
+    #[serde(default)]
+    pub admin_key: Option<String>,
 }
 
 impl Default for ApiConfig {
     fn default() -> Self {
-        Self { key: None }
+        Self {
+            key: None,
+            admin_key: None,
+        }
+    }
+}
+
+/// Limits for `/admin/*` endpoints
+#[derive(Debug, Clone, Deserialize)]
+pub struct AdminConfig {
+    /// Largest `GET /admin/connections/snapshot` response in bytes; larger snapshots
+    /// are rejected with 413 (`ADMIN_MAX_SNAPSHOT_BYTES`)
+    #[serde(default = "default_max_snapshot_bytes")]
+    pub max_snapshot_bytes: usize,
+}
+
+fn default_max_snapshot_bytes() -> usize {
+    10 * 1024 * 1024
+}
+
+impl Default for AdminConfig {
+    fn default() -> Self {
+        Self {
+            max_snapshot_bytes: default_max_snapshot_bytes(),
+        }
     }
 }
 
@@ -416,6 +765,22 @@ fn default_port() -> u16 {
     8081
 }
 
+fn default_max_request_body_bytes() -> usize {
+    64 * 1024 // 64 KB
+}
+
+fn default_max_batch_request_body_bytes() -> usize {
+    1024 * 1024 // 1 MB
+}
+
+fn default_stream_request_timeout_seconds() -> u64 {
+    300 // 5 minutes
+}
+
+fn default_startup_timeout_seconds() -> u64 {
+    10
+}
+
 fn default_redis_url() -> String {
     "redis://localhost:6379".to_string()
 }
@@ -428,10 +793,27 @@ const VALID_RATELIMIT_BACKENDS: &[&str] = &["local", "redis"];
 const MIN_API_KEY_LENGTH: usize = 16;
 
 impl Settings {
+    /// Load configuration, overlaying secrets from the file named by `SECRETS_FILE` if set
     pub fn new() -> Result<Self, ConfigError> {
+        // Load .env file if exists (it may set SECRETS_FILE)
+        let _ = dotenvy::dotenv();
+
+        let secrets_path = env::var("SECRETS_FILE").ok().map(PathBuf::from);
+        Self::new_with_secrets(secrets_path.as_deref())
+    }
+
+    /// Load configuration, then overlay the whitelisted secrets (`jwt_secret`,
+    /// `redis_password`, `postgres_password`, `admin_api_key`, `cluster_secret`) from a
+    /// `KEY=VALUE` file. Secrets take priority over environment variables and config files.
+    pub fn new_with_secrets(secrets_path: Option<&Path>) -> Result<Self, ConfigError> {
         // Load .env file if exists
         let _ = dotenvy::dotenv();
 
+        let secrets = match secrets_path {
+            Some(path) => Secrets::load(path)?,
+            None => Secrets::default(),
+        };
+
         let run_mode = env::var("RUN_MODE").unwrap_or_else(|_| "development".into());
 
         let builder = Config::builder()
@@ -445,18 +827,35 @@ impl Settings {
             .set_default("websocket.max_connections", 10000)?
             .set_default("websocket.max_connections_per_user", 5)?
             .set_default("websocket.max_subscriptions_per_connection", 50)?
+            .set_default("websocket.max_fanout_concurrency", 1000)?
+            .set_default("websocket.min_client_protocol_version", default_min_protocol_version())?
+            .set_default("websocket.max_client_protocol_version", PROTOCOL_VERSION)?
+            .set_default("websocket.redis_command_timeout_ms", 5000)?
+            .set_default("websocket.per_connection_send_timeout_ms", 500)?
+            .set_default("websocket.max_frame_size_bytes", default_max_frame_size_bytes() as u64)?
+            .set_default("websocket.sse_reconnect_retry_ms", 5000)?
             .set_default("queue.enabled", false)?
             .set_default("queue.max_size_per_user", 100)?
             .set_default("queue.message_ttl_seconds", 3600)?
             .set_default("queue.cleanup_interval_seconds", 300)?
             .set_default("queue.backend", "memory")?
             .set_default("queue.redis_prefix", "ara:queue")?
+            .set_default("queue.shard_count", 64)?
+            .set_default("dispatcher.queue_fallback_on_offline", false)?
+            .set_default("admin.max_snapshot_bytes", default_max_snapshot_bytes() as u64)?
+            .set_default("dispatcher.max_concurrent_dispatches", 500)?
+            .set_default("dispatcher.dispatch_timeout_ms", 1000)?
+            .set_default("grpc.enabled", false)?
+            .set_default("grpc.port", 50051)?
             .set_default("ratelimit.enabled", false)?
             .set_default("ratelimit.http_requests_per_second", 100)?
             .set_default("ratelimit.http_burst_size", 200)?
             .set_default("ratelimit.ws_connections_per_minute", 10)?
             .set_default("ratelimit.ws_messages_per_second", 50)?
             .set_default("ratelimit.cleanup_interval_seconds", 60)?
+            .set_default("ratelimit.emergency_bypass_deny_ratio", 0.0)?
+            .set_default("ratelimit.emergency_bypass_window_seconds", 300)?
+            .set_default("ratelimit.emergency_bypass_min_requests", 100)?
             .set_default("redis.circuit_breaker_failure_threshold", 5)?
             .set_default("redis.circuit_breaker_success_threshold", 2)?
             .set_default("redis.circuit_breaker_reset_timeout_seconds", 30)?
@@ -471,6 +870,7 @@ impl Settings {
             .set_default("otel.endpoint", "http://localhost:4317")?
             .set_default("otel.service_name", "ara-notification-service")?
             .set_default("otel.sampling_ratio", 1.0)?
+            .set_default("otel.propagation_format", "w3c_trace_context")?
             .set_default("tenant.enabled", false)?
             .set_default("tenant.default_limits.max_connections", 1000)?
             .set_default("tenant.default_limits.max_connections_per_user", 5)?
@@ -479,21 +879,145 @@ impl Settings {
             .set_default("database.pool_size", 10)?
             .set_default("database.connect_timeout_seconds", 30)?
             .set_default("database.idle_timeout_seconds", 600)?
+            .set_default("health.probe_timeout_seconds", 2)?
+            .set_default("metrics.update_interval_seconds", 30)?
+            .set_default("metrics.push_interval_seconds", 15)?
             // Cluster mode defaults
             .set_default("cluster.enabled", false)?
             .set_default("cluster.session_prefix", "ara:cluster:sessions")?
             .set_default("cluster.session_ttl_seconds", 60)?
             .set_default("cluster.routing_channel", "ara:cluster:route")?
+            .set_default("cluster.route_strategy", "local_first")?
             // Load config file if exists
             .add_source(File::with_name("config/default").required(false))
             .add_source(File::with_name(&format!("config/{}", run_mode)).required(false))
             // Load from environment variables
             // SERVER_HOST, SERVER_PORT, JWT_SECRET, REDIS_URL, etc.
-            .add_source(Environment::default().separator("_").try_parsing(true));
+            .add_source(Environment::default().separator("_").try_parsing(true))
+            // Multi-word keys would be split at every underscore, so map them explicitly
+            .set_override_option("api.admin_key", env::var("ADMIN_API_KEY").ok())?
+            .set_override_option(
+                "redis.subscribe_patterns",
+                env::var("REDIS_SUBSCRIBE_PATTERNS").ok(),
+            )?
+            .set_override_option(
+                "dispatcher.queue_fallback_on_offline",
+                env::var("DISPATCHER_QUEUE_FALLBACK_ON_OFFLINE").ok(),
+            )?
+            .set_override_option(
+                "admin.max_snapshot_bytes",
+                env::var("ADMIN_MAX_SNAPSHOT_BYTES").ok(),
+            )?
+            .set_override_option(
+                "dispatcher.max_concurrent_dispatches",
+                env::var("DISPATCHER_MAX_CONCURRENT_DISPATCHES").ok(),
+            )?
+            .set_override_option(
+                "dispatcher.dispatch_timeout_ms",
+                env::var("DISPATCHER_DISPATCH_TIMEOUT_MS").ok(),
+            )?
+            .set_override_option(
+                "server.stream_request_timeout_seconds",
+                env::var("SERVER_STREAM_REQUEST_TIMEOUT_SECONDS").ok(),
+            )?
+            .set_override_option(
+                "metrics.update_interval_seconds",
+                env::var("METRICS_UPDATE_INTERVAL_SECONDS").ok(),
+            )?
+            .set_override_option(
+                "metrics.push_gateway_url",
+                env::var("METRICS_PUSH_GATEWAY_URL").ok(),
+            )?
+            .set_override_option(
+                "metrics.push_interval_seconds",
+                env::var("METRICS_PUSH_INTERVAL_SECONDS").ok(),
+            )?
+            .set_override_option(
+                "server.startup_timeout_seconds",
+                env::var("SERVER_STARTUP_TIMEOUT_SECONDS").ok(),
+            )?
+            .set_override_option(
+                "websocket.min_client_protocol_version",
+                env::var("WEBSOCKET_MIN_CLIENT_PROTOCOL_VERSION").ok(),
+            )?
+            .set_override_option(
+                "websocket.max_client_protocol_version",
+                env::var("WEBSOCKET_MAX_CLIENT_PROTOCOL_VERSION").ok(),
+            )?
+            .set_override_option(
+                "ratelimit.emergency_bypass_deny_ratio",
+                env::var("RATELIMIT_EMERGENCY_BYPASS_DENY_RATIO").ok(),
+            )?
+            .set_override_option(
+                "ratelimit.emergency_bypass_window_seconds",
+                env::var("RATELIMIT_EMERGENCY_BYPASS_WINDOW_SECONDS").ok(),
+            )?
+            .set_override_option(
+                "ratelimit.emergency_bypass_min_requests",
+                env::var("RATELIMIT_EMERGENCY_BYPASS_MIN_REQUESTS").ok(),
+            )?
+            .set_override_option(
+                "redis.backoff_max_total_wait_ms",
+                env::var("REDIS_BACKOFF_MAX_TOTAL_WAIT_MS").ok(),
+            )?
+            .set_override_option(
+                "websocket.redis_command_timeout_ms",
+                env::var("WEBSOCKET_REDIS_COMMAND_TIMEOUT_MS").ok(),
+            )?
+            .set_override_option(
+                "websocket.per_connection_send_timeout_ms",
+                env::var("WEBSOCKET_PER_CONNECTION_SEND_TIMEOUT_MS").ok(),
+            )?
+            .set_override_option(
+                "websocket.max_frame_size_bytes",
+                env::var("WEBSOCKET_MAX_FRAME_SIZE_BYTES").ok(),
+            )?
+            .set_override_option(
+                "websocket.sse_reconnect_retry_ms",
+                env::var("WEBSOCKET_SSE_RECONNECT_RETRY_MS").ok(),
+            )?
+            .set_override_option(
+                "cluster.route_strategy",
+                env::var("CLUSTER_ROUTE_STRATEGY").ok(),
+            )?
+            .set_override_option("cluster.cluster_secret", env::var("CLUSTER_SECRET").ok())?
+            .set_override_option("cluster.server_tags", env::var("CLUSTER_SERVER_TAGS").ok())?
+            .set_override_option(
+                "shutdown.result_output_path",
+                env::var("SHUTDOWN_RESULT_OUTPUT_PATH").ok(),
+            )?
+            .set_override_option(
+                "shutdown.rate_limit_state_dir",
+                env::var("SHUTDOWN_RATE_LIMIT_STATE_DIR").ok(),
+            )?
+            .set_override_option(
+                "otel.propagation_format",
+                env::var("OTEL_PROPAGATION_FORMAT").ok(),
+            )?
+            .set_override_option(
+                "otel.pii_redaction_enabled",
+                env::var("OTEL_PII_REDACTION_ENABLED").ok(),
+            )?
+            .set_override_option(
+                "otel.pii_attribute_patterns",
+                env::var("OTEL_PII_ATTRIBUTE_PATTERNS").ok(),
+            )?
+            // Secrets file entries override everything above
+            .set_override_option("jwt.secret", secrets.get("jwt_secret"))?
+            .set_override_option("api.admin_key", secrets.get("admin_api_key"))?
+            .set_override_option("cluster.cluster_secret", secrets.get("cluster_secret"))?;
 
         let mut settings: Self = builder.build()?.try_deserialize()?;
+        if let Some(password) = secrets.get("redis_password") {
+            settings.redis.url = with_password(&settings.redis.url, password, "redis.url")?;
+        }
+        if let Some(password) = secrets.get("postgres_password") {
+            settings.database.url = with_password(&settings.database.url, password, "database.url")?;
+        }
+        settings.ignored_secret_keys = secrets.ignored;
         settings.is_production = run_mode.eq_ignore_ascii_case("production")
             || run_mode.eq_ignore_ascii_case("prod");
+        settings.features = FeatureFlags::from_env();
         settings.validate()?;
         Ok(settings)
     }
@@ -543,6 +1067,19 @@ impl Settings {
             errors.push("Server port must be between 1 and 65535".to_string());
         }
 
+        if self.server.max_request_body_bytes == 0 {
+            errors.push("server.max_request_body_bytes must be greater than 0".to_string());
+        }
+        if self.server.max_batch_request_body_bytes == 0 {
+            errors.push("server.max_batch_request_body_bytes must be greater than 0".to_string());
+        }
+        if self.server.stream_request_timeout_seconds == 0 {
+            errors.push("server.stream_request_timeout_seconds must be greater than 0".to_string());
+        }
+        if self.server.startup_timeout_seconds == 0 {
+            errors.push("server.startup_timeout_seconds must be greater than 0".to_string());
+        }
+
         // Validate timeout values are positive
         if self.websocket.heartbeat_interval == 0 {
             errors.push("websocket.heartbeat_interval must be greater than 0".to_string());
@@ -550,6 +1087,44 @@ impl Settings {
         if self.websocket.connection_timeout == 0 {
             errors.push("websocket.connection_timeout must be greater than 0".to_string());
         }
+        if self.websocket.max_fanout_concurrency == 0 {
+            errors.push("websocket.max_fanout_concurrency must be greater than 0".to_string());
+        }
+        if self.admin.max_snapshot_bytes == 0 {
+            errors.push("admin.max_snapshot_bytes must be greater than 0".to_string());
+        }
+        if self.dispatcher.max_concurrent_dispatches == 0 {
+            errors.push("dispatcher.max_concurrent_dispatches must be greater than 0".to_string());
+        }
+        if self.websocket.redis_command_timeout_ms == 0 {
+            errors.push("websocket.redis_command_timeout_ms must be greater than 0".to_string());
+        }
+        if self.websocket.per_connection_send_timeout_ms == 0 {
+            errors.push(
+                "websocket.per_connection_send_timeout_ms must be greater than 0".to_string(),
+            );
+        }
+        if self.websocket.max_frame_size_bytes == 0 {
+            errors.push("websocket.max_frame_size_bytes must be greater than 0".to_string());
+        }
+        let (min_protocol, max_protocol) = (
+            self.websocket.min_client_protocol_version,
+            self.websocket.max_client_protocol_version,
+        );
+        if min_protocol == 0 || min_protocol > max_protocol || max_protocol > PROTOCOL_VERSION {
+            errors.push(format!(
+                "websocket client protocol versions must satisfy 1 <= min ({}) <= max ({}) <= {}",
+                min_protocol, max_protocol, PROTOCOL_VERSION
+            ));
+        }
+        if self.websocket.heartbeat_adaptive
+            && self.websocket.heartbeat_idle_interval < self.websocket.heartbeat_interval
+        {
+            errors.push(format!(
+                "websocket.heartbeat_idle_interval ({}) must be at least heartbeat_interval ({})",
+                self.websocket.heartbeat_idle_interval, self.websocket.heartbeat_interval
+            ));
+        }
         if self.ack.enabled && self.ack.timeout_seconds == 0 {
             errors
                 .push("ack.timeout_seconds must be greater than 0 when ACK is enabled".to_string());
@@ -580,6 +1155,31 @@ impl Settings {
                 self.ratelimit.backend, VALID_RATELIMIT_BACKENDS
             ));
         }
+        for cidr in self.ratelimit.ip_allowlist.iter().chain(&self.ratelimit.ip_blocklist) {
+            if cidr.parse::<ipnetwork::IpNetwork>().is_err() {
+                errors.push(format!("Invalid CIDR in ratelimit IP list: '{}'", cidr));
+            }
+        }
+        if !(0.0..=1.0).contains(&self.ratelimit.emergency_bypass_deny_ratio) {
+            errors.push(format!(
+                "ratelimit.emergency_bypass_deny_ratio must be between 0.0 and 1.0, got {}",
+                self.ratelimit.emergency_bypass_deny_ratio
+            ));
+        }
+        if self.ratelimit.emergency_bypass_window_seconds == 0 {
+            errors.push("ratelimit.emergency_bypass_window_seconds must be greater than 0".to_string());
+        }
+        for rule in &self.ratelimit.rules {
+            if rule.path_pattern.is_empty()
+                || rule.requests_per_second == 0
+                || rule.burst_size == 0
+            {
+                errors.push(format!(
+                    "Invalid ratelimit rule '{}={}:{}': pattern must be non-empty and limits greater than 0",
+                    rule.path_pattern, rule.requests_per_second, rule.burst_size
+                ));
+            }
+        }
 
         // Validate OTEL sampling ratio (0.0 to 1.0)
         if self.otel.enabled && !(0.0..=1.0).contains(&self.otel.sampling_ratio) {
@@ -588,6 +1188,16 @@ impl Settings {
                 self.otel.sampling_ratio
             ));
         }
+        if self.otel.enabled && self.otel.pii_redaction_enabled {
+            for pattern in &self.otel.pii_attribute_patterns {
+                if let Err(e) = regex::Regex::new(pattern) {
+                    errors.push(format!(
+                        "otel.pii_attribute_patterns contains an invalid regex '{}': {}",
+                        pattern, e
+                    ));
+                }
+            }
+        }
 
         // Validate database pool size
         if self.database.pool_size == 0 {
@@ -605,6 +1215,32 @@ impl Settings {
             ));
         }
 
+        // Adaptive heartbeats may stretch to the idle interval, which must still keep
+        // connections and cluster sessions alive
+        if self.websocket.heartbeat_adaptive
+            && self.websocket.connection_timeout > 0
+            && self.websocket.connection_timeout <= self.websocket.heartbeat_idle_interval
+        {
+            errors.push(format!(
+                "websocket.connection_timeout ({}) must be greater than heartbeat_idle_interval ({})",
+                self.websocket.connection_timeout, self.websocket.heartbeat_idle_interval
+            ));
+        }
+        if self.websocket.heartbeat_adaptive
+            && self.cluster.enabled
+            && self.cluster.session_ttl_seconds > 0
+            && self.cluster.session_ttl_seconds <= self.websocket.heartbeat_idle_interval
+        {
+            errors.push(format!(
+                "cluster.session_ttl_seconds ({}) must be greater than websocket.heartbeat_idle_interval ({})",
+                self.cluster.session_ttl_seconds, self.websocket.heartbeat_idle_interval
+            ));
+        }
+
+        if self.cluster.enabled && self.cluster.cluster_secret.is_empty() {
+            errors.push("cluster.cluster_secret is required when cluster mode is enabled".to_string());
+        }
+
         // Validate cluster session TTL vs heartbeat interval
         if self.cluster.enabled
             && self.websocket.heartbeat_interval > 0
@@ -617,14 +1253,51 @@ impl Settings {
             ));
         }
 
-        // Validate CORS in production mode
-        if is_production && self.server.cors_origins.is_empty() {
+        // Validate CORS
+        for origin in &self.cors.allowed_origins {
+            if origin.parse::<axum::http::HeaderValue>().is_err() {
+                errors.push(format!("Invalid CORS origin: '{}'", origin));
+            }
+        }
+        if self.cors.allow_all && self.cors.allow_credentials {
+            errors.push("cors.allow_credentials cannot be combined with cors.allow_all".to_string());
+        }
+        if is_production && self.cors.allow_all {
             errors.push(
-                "CORS_ORIGINS must be configured in production mode (empty origins allows any origin)"
+                "cors.allow_all must not be enabled in production mode; configure CORS_ORIGINS instead"
                     .to_string(),
             );
         }
 
+        // Validate audit log
+        if self.audit.enabled && (self.audit.directory.trim().is_empty() || self.audit.prefix.trim().is_empty()) {
+            errors.push("audit.directory and audit.prefix must not be empty when audit is enabled".to_string());
+        }
+
+        // Validate gRPC server
+        if self.grpc.enabled && self.grpc.port == self.server.port {
+            errors.push("grpc.port must differ from server.port".to_string());
+        }
+
+        // Validate health probes
+        if self.health.probe_timeout_seconds == 0 {
+            errors.push("health.probe_timeout_seconds must be greater than 0".to_string());
+        }
+        if self.metrics.update_interval_seconds == 0 {
+            errors.push("metrics.update_interval_seconds must be greater than 0".to_string());
+        }
+        if let Some(url) = &self.metrics.push_gateway_url {
+            if !url.starts_with("http://") {
+                errors.push(format!(
+                    "metrics.push_gateway_url must be an http:// URL (current: {})",
+                    url
+                ));
+            }
+            if self.metrics.push_interval_seconds == 0 {
+                errors.push("metrics.push_interval_seconds must be greater than 0".to_string());
+            }
+        }
+
         // Return errors if any
         if errors.is_empty() {
             Ok(())
@@ -647,6 +1320,10 @@ impl Settings {
     pub fn server_addr(&self) -> String {
         format!("{}:{}", self.server.host, self.server.port)
     }
+
+    pub fn grpc_addr(&self) -> String {
+        format!("{}:{}", self.server.host, self.grpc.port)
+    }
 }
 
 impl Default for ServerConfig {
@@ -654,7 +1331,69 @@ impl Default for ServerConfig {
         Self {
             host: default_host(),
             port: default_port(),
-            cors_origins: vec![],
+            disable_response_compression: false,
+            max_request_body_bytes: default_max_request_body_bytes(),
+            max_batch_request_body_bytes: default_max_batch_request_body_bytes(),
+            stream_request_timeout_seconds: default_stream_request_timeout_seconds(),
+            startup_timeout_seconds: default_startup_timeout_seconds(),
+        }
+    }
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        Self {
+            allowed_origins: vec![],
+            allow_all: false,
+            allow_credentials: false,
+            max_age_seconds: default_cors_max_age_seconds(),
+        }
+    }
+}
+
+impl Default for AuditConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            directory: default_audit_directory(),
+            prefix: default_audit_prefix(),
+        }
+    }
+}
+
+impl Default for DispatcherConfig {
+    fn default() -> Self {
+        Self {
+            queue_fallback_on_offline: false,
+            max_concurrent_dispatches: default_max_concurrent_dispatches(),
+            dispatch_timeout_ms: default_dispatch_timeout_ms(),
+        }
+    }
+}
+
+impl Default for GrpcConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: default_grpc_port(),
+        }
+    }
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        Self {
+            probe_timeout_seconds: default_health_probe_timeout_seconds(),
+        }
+    }
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self {
+            update_interval_seconds: default_metrics_update_interval_seconds(),
+            push_gateway_url: None,
+            push_interval_seconds: default_metrics_push_interval_seconds(),
         }
     }
 }
@@ -664,11 +1403,13 @@ impl Default for RedisConfig {
         Self {
             url: default_redis_url(),
             channels: vec![],
+            subscribe_patterns: vec![],
             circuit_breaker_failure_threshold: default_circuit_breaker_failure_threshold(),
             circuit_breaker_success_threshold: default_circuit_breaker_success_threshold(),
             circuit_breaker_reset_timeout_seconds: default_circuit_breaker_reset_timeout(),
             backoff_initial_delay_ms: default_backoff_initial_delay(),
             backoff_max_delay_ms: default_backoff_max_delay(),
+            backoff_max_total_wait_ms: None,
         }
     }
 }
@@ -682,6 +1423,17 @@ impl Default for WebSocketConfig {
             max_connections: default_max_connections(),
             max_connections_per_user: default_max_connections_per_user(),
             max_subscriptions_per_connection: default_max_subscriptions(),
+            connection_alert_threshold: 0,
+            heartbeat_adaptive: false,
+            heartbeat_idle_interval: default_heartbeat_idle_interval(),
+            max_missed_pings: default_max_missed_pings(),
+            max_fanout_concurrency: default_max_fanout_concurrency(),
+            min_client_protocol_version: default_min_protocol_version(),
+            max_client_protocol_version: default_protocol_version(),
+            redis_command_timeout_ms: default_redis_command_timeout_ms(),
+            per_connection_send_timeout_ms: default_per_connection_send_timeout_ms(),
+            max_frame_size_bytes: default_max_frame_size_bytes(),
+            sse_reconnect_retry_ms: default_sse_reconnect_retry_ms(),
         }
     }
 }
@@ -695,6 +1447,7 @@ impl Default for QueueConfig {
             cleanup_interval_seconds: default_queue_cleanup_interval(),
             backend: default_queue_backend(),
             redis_prefix: default_queue_redis_prefix(),
+            shard_count: default_queue_shard_count(),
         }
     }
 }
@@ -710,6 +1463,12 @@ impl Default for RateLimitConfig {
             cleanup_interval_seconds: default_ratelimit_cleanup_interval(),
             backend: default_ratelimit_backend(),
             redis_prefix: default_ratelimit_redis_prefix(),
+            ip_allowlist: Vec::new(),
+            ip_blocklist: Vec::new(),
+            emergency_bypass_deny_ratio: default_emergency_bypass_deny_ratio(),
+            emergency_bypass_window_seconds: default_emergency_bypass_window_seconds(),
+            emergency_bypass_min_requests: default_emergency_bypass_min_requests(),
+            rules: Vec::new(),
         }
     }
 }
@@ -743,7 +1502,13 @@ mod tests {
     fn create_test_settings() -> Settings {
         Settings {
             server: ServerConfig::default(),
+            cors: CorsConfig::default(),
+            health: HealthConfig::default(),
+            metrics: MetricsConfig::default(),
+            audit: AuditConfig::default(),
             jwt: JwtConfig {
+                algorithm: None,
+                publickey: None,
                 secret: "a]vLZ6%BJ1ywJE:*Gj[r=xGMvN!Hs.Q9".to_string(), // 32 chars
                 issuer: None,
                 audience: None,
@@ -752,16 +1517,59 @@ mod tests {
             api: ApiConfig::default(),
             websocket: WebSocketConfig::default(),
             queue: QueueConfig::default(),
+            dispatcher: DispatcherConfig::default(),
+            admin: AdminConfig::default(),
+            grpc: GrpcConfig::default(),
             ratelimit: RateLimitConfig::default(),
             ack: AckSettingsConfig::default(),
             otel: OtelConfig::default(),
             tenant: TenantConfig::default(),
             database: DatabaseConfig::default(),
             cluster: ClusterConfig::default(),
+            shutdown: ShutdownSettingsConfig::default(),
             is_production: false,
+            features: FeatureFlags::default(),
+            ignored_secret_keys: vec![],
         }
     }
 
+    #[test]
+    fn test_new_with_secrets_overlays_whitelisted_keys() {
+        use std::io::Write;
+
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(
+            file,
+            "# Docker secrets\n\
+             JWT_SECRET=secret-from-file-0123456789abcdefghij\n\
+             redis_password=redis-pass\n\
+             postgres_password=pg-pass\n\
+             admin_api_key=admin-key-from-file\n\
+             cluster_secret=cluster-secret-from-file\n\
+             server_port=9999"
+        )
+        .unwrap();
+
+        let settings = Settings::new_with_secrets(Some(file.path())).unwrap();
+
+        assert_eq!(settings.jwt.secret, "secret-from-file-0123456789abcdefghij");
+        assert_eq!(settings.api.admin_key.as_deref(), Some("admin-key-from-file"));
+        assert_eq!(settings.cluster.cluster_secret, "cluster-secret-from-file");
+        assert!(settings.redis.url.contains(":redis-pass@"));
+        assert!(settings.database.url.contains(":pg-pass@"));
+        // Keys outside the whitelist are ignored, not applied
+        assert_ne!(settings.server.port, 9999);
+        assert_eq!(settings.ignored_secret_keys, vec!["server_port"]);
+    }
+
+    #[test]
+    fn test_new_with_missing_secrets_file_fails() {
+        let err = Settings::new_with_secrets(Some(Path::new("/nonexistent/ara-secrets")))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("secrets file"));
+    }
+
     #[test]
     fn test_validate_valid_settings() {
         let settings = create_test_settings();
@@ -778,6 +1586,18 @@ mod tests {
         assert!(err.contains("JWT_SECRET must be at least 32 characters"));
     }
 
+    #[test]
+    fn test_validate_cluster_secret_required() {
+        let mut settings = create_test_settings();
+        settings.cluster.enabled = true;
+        let err = settings.validate().unwrap_err().to_string();
+        assert!(err.contains("cluster.cluster_secret is required"));
+
+        settings.cluster.cluster_secret = "shared-cluster-secret".to_string();
+        assert!(settings.validate().is_ok());
+        assert!(!format!("{:?}", settings.cluster).contains("shared-cluster-secret"));
+    }
+
     #[test]
     fn test_validate_invalid_redis_url() {
         let mut settings = create_test_settings();
@@ -835,6 +1655,54 @@ mod tests {
         assert!(err.contains("Invalid ratelimit.backend"));
     }
 
+    #[test]
+    fn test_validate_ratelimit_ip_lists() {
+        let mut settings = create_test_settings();
+        settings.ratelimit.ip_allowlist = vec!["10.0.0.0/8".to_string(), "fd00::/8".to_string()];
+        settings.ratelimit.ip_blocklist = vec!["203.0.113.7".to_string()];
+        assert!(settings.validate().is_ok());
+
+        settings.ratelimit.ip_blocklist = vec!["10.0.0.0/33".to_string()];
+        let err = settings.validate().unwrap_err().to_string();
+        assert!(err.contains("Invalid CIDR"));
+    }
+
+    #[test]
+    fn test_validate_ratelimit_emergency_bypass() {
+        let mut settings = create_test_settings();
+        settings.ratelimit.emergency_bypass_deny_ratio = 1.5;
+        let err = settings.validate().unwrap_err().to_string();
+        assert!(err.contains("emergency_bypass_deny_ratio"));
+
+        settings.ratelimit.emergency_bypass_deny_ratio = 0.0;
+        settings.ratelimit.emergency_bypass_window_seconds = 0;
+        let err = settings.validate().unwrap_err().to_string();
+        assert!(err.contains("emergency_bypass_window_seconds"));
+    }
+
+    #[test]
+    fn test_validate_zero_max_concurrent_dispatches() {
+        let mut settings = create_test_settings();
+        settings.dispatcher.max_concurrent_dispatches = 0;
+        let err = settings.validate().unwrap_err().to_string();
+        assert!(err.contains("dispatcher.max_concurrent_dispatches"));
+    }
+
+    #[test]
+    fn test_validate_ratelimit_rules() {
+        let mut settings = create_test_settings();
+        settings.ratelimit.rules = vec![RateLimitRule {
+            path_pattern: "/api/v1/notifications/batch".to_string(),
+            requests_per_second: 10,
+            burst_size: 10,
+        }];
+        assert!(settings.validate().is_ok());
+
+        settings.ratelimit.rules[0].requests_per_second = 0;
+        let err = settings.validate().unwrap_err().to_string();
+        assert!(err.contains("Invalid ratelimit rule"));
+    }
+
     #[test]
     fn test_validate_invalid_otel_sampling_ratio() {
         let mut settings = create_test_settings();
@@ -846,6 +1714,20 @@ mod tests {
         assert!(err.contains("otel.sampling_ratio must be between 0.0 and 1.0"));
     }
 
+    #[test]
+    fn test_validate_invalid_otel_pii_attribute_pattern() {
+        let mut settings = create_test_settings();
+        settings.otel.enabled = true;
+        settings.otel.pii_redaction_enabled = true;
+        settings.otel.pii_attribute_patterns = vec![r"^user\.id$".to_string(), "user.(".to_string()];
+        let err = settings.validate().unwrap_err().to_string();
+        assert!(err.contains("otel.pii_attribute_patterns contains an invalid regex 'user.('"));
+
+        // Patterns are not compiled unless redaction is enabled
+        settings.otel.pii_redaction_enabled = false;
+        assert!(settings.validate().is_ok());
+    }
+
     #[test]
     fn test_validate_multiple_errors() {
         let mut settings = create_test_settings();
@@ -876,7 +1758,7 @@ mod tests {
     fn test_validate_production_accepts_valid_api_key() {
         let mut settings = create_test_settings();
         settings.api.key = Some("prod-api-key-1234567890".to_string());
-        settings.server.cors_origins = vec!["https://example.com".to_string()];
+        settings.cors.allowed_origins = vec!["https://example.com".to_string()];
 
         let result = settings.validate_with_run_mode("production");
         assert!(result.is_ok());
@@ -886,11 +1768,98 @@ mod tests {
     fn test_validate_production_rejects_short_api_key() {
         let mut settings = create_test_settings();
         settings.api.key = Some("short-key".to_string());
-        settings.server.cors_origins = vec!["https://example.com".to_string()];
+        settings.cors.allowed_origins = vec!["https://example.com".to_string()];
 
         let result = settings.validate_with_run_mode("production");
         assert!(result.is_err());
         let err = result.unwrap_err().to_string();
         assert!(err.contains("API_KEY must be at least"));
     }
+
+    #[test]
+    fn test_validate_cors() {
+        let mut settings = create_test_settings();
+        settings.cors.allow_all = true;
+        assert!(settings.validate().is_ok());
+
+        settings.cors.allow_credentials = true;
+        let err = settings.validate().unwrap_err().to_string();
+        assert!(err.contains("cors.allow_credentials"));
+
+        settings.cors.allow_credentials = false;
+        settings.api.key = Some("prod-api-key-1234567890".to_string());
+        let err = settings.validate_with_run_mode("production").unwrap_err().to_string();
+        assert!(err.contains("cors.allow_all must not be enabled"));
+
+        settings.cors.allow_all = false;
+        settings.cors.allowed_origins = vec!["https://bad\norigin".to_string()];
+        let err = settings.validate().unwrap_err().to_string();
+        assert!(err.contains("Invalid CORS origin"));
+    }
+
+    #[test]
+    fn test_validate_grpc_port() {
+        let mut settings = create_test_settings();
+        settings.grpc.enabled = true;
+        assert!(settings.validate().is_ok());
+
+        settings.grpc.port = settings.server.port;
+        let err = settings.validate().unwrap_err().to_string();
+        assert!(err.contains("grpc.port"));
+    }
+
+    #[test]
+    fn test_validate_protocol_versions() {
+        let mut settings = create_test_settings();
+        assert_eq!(settings.websocket.min_client_protocol_version, 1);
+        assert_eq!(settings.websocket.max_client_protocol_version, PROTOCOL_VERSION);
+
+        settings.websocket.max_client_protocol_version = PROTOCOL_VERSION + 1;
+        let err = settings.validate().unwrap_err().to_string();
+        assert!(err.contains("protocol versions"));
+
+        settings.websocket.max_client_protocol_version = PROTOCOL_VERSION;
+        settings.websocket.min_client_protocol_version = 0;
+        assert!(settings.validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_audit() {
+        let mut settings = create_test_settings();
+        settings.audit.enabled = true;
+        assert!(settings.validate().is_ok());
+
+        settings.audit.directory = " ".to_string();
+        let err = settings.validate().unwrap_err().to_string();
+        assert!(err.contains("audit.directory"));
+    }
+
+    #[test]
+    fn test_validate_health_probe_timeout() {
+        let mut settings = create_test_settings();
+        assert_eq!(settings.health.probe_timeout_seconds, 2);
+        settings.health.probe_timeout_seconds = 0;
+        let err = settings.validate().unwrap_err().to_string();
+        assert!(err.contains("health.probe_timeout_seconds"));
+
+        settings.health.probe_timeout_seconds = 2;
+        assert_eq!(settings.metrics.update_interval_seconds, 30);
+        settings.metrics.update_interval_seconds = 0;
+        let err = settings.validate().unwrap_err().to_string();
+        assert!(err.contains("metrics.update_interval_seconds"));
+    }
+
+    #[test]
+    fn test_validate_metrics_push_gateway() {
+        let mut settings = create_test_settings();
+        settings.metrics.push_gateway_url = Some("https://pushgateway:9091".to_string());
+        settings.metrics.push_interval_seconds = 0;
+        let err = settings.validate().unwrap_err().to_string();
+        assert!(err.contains("metrics.push_gateway_url must be an http:// URL"));
+        assert!(err.contains("metrics.push_interval_seconds"));
+
+        settings.metrics.push_gateway_url = Some("http://pushgateway:9091".to_string());
+        settings.metrics.push_interval_seconds = 15;
+        assert!(settings.validate().is_ok());
+    }
 }
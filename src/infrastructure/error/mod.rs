@@ -14,6 +14,9 @@ pub enum AppError {
     #[error("Authentication error: {0}")]
     Auth(String),
 
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+
     #[error("Validation error: {0}")]
     Validation(String),
 
@@ -40,6 +43,9 @@ pub enum AppError {
 
     #[error("Cluster error: {0}")]
     ClusterError(String),
+
+    #[error("Payload too large: {0}")]
+    PayloadTooLarge(String),
 }
 
 #[derive(Serialize)]
@@ -78,6 +84,12 @@ impl IntoResponse for AppError {
                 msg.clone(),
                 msg.clone(),
             ),
+            AppError::Forbidden(msg) => (
+                StatusCode::FORBIDDEN,
+                "FORBIDDEN",
+                msg.clone(),
+                msg.clone(),
+            ),
             AppError::Validation(msg) => (
                 StatusCode::BAD_REQUEST,
                 "VALIDATION_ERROR",
@@ -144,6 +156,12 @@ impl IntoResponse for AppError {
                 };
                 (StatusCode::SERVICE_UNAVAILABLE, "CLUSTER_ERROR", client_msg, log_msg)
             }
+            AppError::PayloadTooLarge(msg) => (
+                StatusCode::PAYLOAD_TOO_LARGE,
+                "PAYLOAD_TOO_LARGE",
+                msg.clone(),
+                msg.clone(),
+            ),
         };
 
         // Always log the detailed error server-side
@@ -0,0 +1,61 @@
+//! Label cardinality limiting for metric vectors keyed by user-controlled values
+
+use std::collections::HashSet;
+use std::sync::Mutex;
+
+/// Label value used once a guard's limit has been reached
+pub const OVERFLOW_LABEL: &str = "__other__";
+
+/// Bounds the number of distinct label values a metric vector may create.
+///
+/// The first `max_labels` distinct values are passed through unchanged; any value
+/// seen after that is collapsed into `OVERFLOW_LABEL` so a flood of channel names
+/// cannot grow the Prometheus registry without bound.
+pub struct MetricsCardinalityGuard {
+    max_labels: usize,
+    seen: Mutex<HashSet<String>>,
+}
+
+impl MetricsCardinalityGuard {
+    pub fn new(max_labels: usize) -> Self {
+        Self {
+            max_labels,
+            seen: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Return the label to record for `value`
+    pub fn label<'a>(&self, value: &'a str) -> &'a str {
+        let mut seen = self.seen.lock().unwrap_or_else(|e| e.into_inner());
+        if seen.contains(value) {
+            return value;
+        }
+        if seen.len() < self.max_labels {
+            seen.insert(value.to_string());
+            value
+        } else {
+            OVERFLOW_LABEL
+        }
+    }
+
+    /// Number of distinct label values admitted so far
+    pub fn tracked(&self) -> usize {
+        self.seen.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_guard_collapses_labels_beyond_limit() {
+        let guard = MetricsCardinalityGuard::new(2);
+        assert_eq!(guard.label("a"), "a");
+        assert_eq!(guard.label("b"), "b");
+        assert_eq!(guard.label("c"), OVERFLOW_LABEL);
+        // Already admitted values keep their own label
+        assert_eq!(guard.label("a"), "a");
+        assert_eq!(guard.tracked(), 2);
+    }
+}
@@ -1,15 +1,30 @@
 //! Metrics helper structs for convenient metric recording
 
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
 use prometheus::{Encoder, TextEncoder};
 
+use crate::redis::pool::RedisPool;
+use crate::redis::OperationType;
+
 use super::{
-    ACK_EXPIRED_TOTAL, ACK_LATENCY, ACK_PENDING, ACK_RECEIVED_TOTAL, ACK_TRACKED_TOTAL,
-    BACKEND_ERRORS_TOTAL, BACKEND_OPERATION_LATENCY, CLUSTER_CONNECTIONS_TOTAL, CLUSTER_ENABLED,
-    CLUSTER_MESSAGES_RECEIVED, CLUSTER_MESSAGES_ROUTED, CLUSTER_SESSIONS_REFRESHED,
-    CLUSTER_USERS_TOTAL, CONNECTION_MANAGER_MEMORY_BYTES, HEARTBEAT_DURATION_MS,
-    HEARTBEAT_TIMEOUTS, MESSAGES_DELIVERED_TOTAL, MESSAGES_FAILED_TOTAL, MESSAGES_SENT_TOTAL,
-    PROCESS_MEMORY_BYTES, QUEUE_MEMORY_BYTES, RATELIMIT_ALLOWED_TOTAL, RATELIMIT_DENIED_TOTAL,
-    WS_MESSAGES_RECEIVED,
+    ACK_EVENT_TYPE_LABEL_GUARD, ACK_EXPIRED_TOTAL, ACK_LATENCY, ACK_PENDING, ACK_RATE_BY_TYPE,
+    ACK_RECEIVED_TOTAL, ACK_TRACKED_TOTAL,
+    BACKEND_ERRORS_TOTAL, BACKEND_OPERATION_LATENCY, CHANNEL_LABEL_GUARD,
+    CHANNEL_PATTERN_DISPATCH_TOTAL, CHANNEL_PATTERN_LABEL_GUARD,
+    CHANNEL_PEAK_SUBSCRIBERS, CHANNEL_SUBSCRIPTIONS_REJECTED_TOTAL,
+    CLUSTER_CONNECTIONS_TOTAL, CLUSTER_ENABLED, CLUSTER_IS_METRICS_LEADER,
+    CLUSTER_MESSAGES_RECEIVED, CLUSTER_MESSAGES_ROUTED, CLUSTER_MESSAGES_SIGNATURE_FAILED_TOTAL,
+    CLUSTER_ROUTE_STRATEGY_USED, CLUSTER_ROUTING_FALLBACK_TOTAL, CLUSTER_ROUTING_PREFERRED_MATCH_TOTAL,
+    CLUSTER_SESSIONS_REFRESHED,
+    CLUSTER_USERS_TOTAL, CONNECTION_MANAGER_MEMORY_BYTES, HEARTBEAT_CURRENT_INTERVAL_SECONDS,
+    HEARTBEAT_DURATION_MS, HEARTBEAT_EVICTIONS_TOTAL, HEARTBEAT_TIMEOUTS, MESSAGES_DELIVERED_TOTAL, MESSAGES_FAILED_TOTAL, MESSAGES_SENT_TOTAL,
+    MESSAGE_DELIVERY_LATENCY, NOTIFICATION_E2E_LATENCY_SECONDS,
+    NOTIFICATION_EVENT_TYPES_TRACKED, NOTIFICATION_TAGS_USED_TOTAL, PROCESS_MEMORY_BYTES, QUEUE_MEMORY_BYTES,
+    RATELIMIT_ALLOWED_TOTAL, RATELIMIT_ALLOWLISTED_TOTAL, RATELIMIT_BLOCKLISTED_TOTAL,
+    RATELIMIT_DENIED_TOTAL, RATELIMIT_EMERGENCY_BYPASS_ACTIVE, REDIS_POOL_ACTIVE_CONNECTIONS,
+    REDIS_CIRCUIT_BREAKER_STATE, REDIS_POOL_IDLE_CONNECTIONS, TAG_LABEL_GUARD, WS_MESSAGES_RECEIVED,
 };
 
 /// Encode all metrics to Prometheus text format
@@ -21,6 +36,18 @@ pub fn encode_metrics() -> Result<String, prometheus::Error> {
     Ok(String::from_utf8(buffer).unwrap_or_default())
 }
 
+/// Encode only the metric families whose name starts with `prefix`
+pub fn encode_metrics_filtered(prefix: &str) -> Result<String, prometheus::Error> {
+    let encoder = TextEncoder::new();
+    let metric_families: Vec<_> = prometheus::gather()
+        .into_iter()
+        .filter(|family| family.get_name().starts_with(prefix))
+        .collect();
+    let mut buffer = Vec::new();
+    encoder.encode(&metric_families, &mut buffer)?;
+    Ok(String::from_utf8(buffer).unwrap_or_default())
+}
+
 /// Helper struct for recording message metrics
 pub struct MessageMetrics;
 
@@ -50,6 +77,19 @@ impl MessageMetrics {
         MESSAGES_SENT_TOTAL.with_label_values(&["channels"]).inc();
     }
 
+    /// Record a message sent to the users holding a role
+    pub fn record_role_sent() {
+        MESSAGES_SENT_TOTAL.with_label_values(&["role"]).inc();
+    }
+
+    /// Record a dispatch to a wildcard channel pattern
+    pub fn record_channel_pattern_sent(pattern: &str) {
+        MESSAGES_SENT_TOTAL.with_label_values(&["channel_pattern"]).inc();
+        CHANNEL_PATTERN_DISPATCH_TOTAL
+            .with_label_values(&[CHANNEL_PATTERN_LABEL_GUARD.label(pattern)])
+            .inc();
+    }
+
     /// Record successful deliveries
     pub fn record_delivered(count: u64) {
         MESSAGES_DELIVERED_TOTAL.inc_by(count);
@@ -59,6 +99,58 @@ impl MessageMetrics {
     pub fn record_failed(count: u64) {
         MESSAGES_FAILED_TOTAL.inc_by(count);
     }
+
+    /// Update the number of distinct event types tracked by the dispatcher
+    pub fn set_event_types_tracked(count: usize) {
+        NOTIFICATION_EVENT_TYPES_TRACKED.set(count as i64);
+    }
+
+    /// Record a dispatched notification's tags. Tags beyond the label cap share
+    /// the overflow label.
+    pub fn record_tags(tags: &[String]) {
+        for tag in tags {
+            NOTIFICATION_TAGS_USED_TOTAL
+                .with_label_values(&[TAG_LABEL_GUARD.label(tag)])
+                .inc();
+        }
+    }
+
+    /// Record the latency of a completed dispatch, measured from event occurrence
+    pub fn record_delivery_latency(occurred_at: DateTime<Utc>) {
+        MESSAGE_DELIVERY_LATENCY.observe(seconds_since(occurred_at));
+    }
+
+    /// Record the latency of a notification's first successful delivery on `path`
+    /// ("live" or "replay"), measured from `since`
+    pub fn record_e2e_latency(path: &str, since: DateTime<Utc>) {
+        NOTIFICATION_E2E_LATENCY_SECONDS
+            .with_label_values(&[path])
+            .observe(seconds_since(since));
+    }
+}
+
+/// Seconds elapsed since `time`, or zero if it lies in the future (clock skew
+/// between the event's producer and this server)
+fn seconds_since(time: DateTime<Utc>) -> f64 {
+    (Utc::now() - time)
+        .to_std()
+        .map(|elapsed| elapsed.as_secs_f64())
+        .unwrap_or(0.0)
+}
+
+/// Helper struct for recording per-channel metrics
+pub struct ChannelMetrics;
+
+impl ChannelMetrics {
+    /// Update a channel's peak subscriber gauge. Channels beyond the label cap share
+    /// the overflow label, which reports the highest peak among them.
+    pub fn set_peak_subscribers(channel: &str, peak: usize) {
+        let label = CHANNEL_LABEL_GUARD.label(channel);
+        let gauge = CHANNEL_PEAK_SUBSCRIBERS.with_label_values(&[label]);
+        if label == channel || peak as i64 > gauge.get() {
+            gauge.set(peak as i64);
+        }
+    }
 }
 
 /// Helper struct for recording rate limit metrics
@@ -84,6 +176,21 @@ impl RateLimitMetrics {
     pub fn record_ws_denied() {
         RATELIMIT_DENIED_TOTAL.with_label_values(&["ws"]).inc();
     }
+
+    /// Record a request rejected by the IP blocklist
+    pub fn record_blocklisted() {
+        RATELIMIT_BLOCKLISTED_TOTAL.inc();
+    }
+
+    /// Record a request that bypassed limits via the IP allowlist
+    pub fn record_allowlisted() {
+        RATELIMIT_ALLOWLISTED_TOTAL.inc();
+    }
+
+    /// Set whether enforcement is bypassed after a suspected misconfiguration
+    pub fn set_emergency_bypass(active: bool) {
+        RATELIMIT_EMERGENCY_BYPASS_ACTIVE.set(if active { 1 } else { 0 });
+    }
 }
 
 /// Helper struct for memory metrics
@@ -152,6 +259,16 @@ impl HeartbeatMetrics {
     pub fn record_timeouts(count: u64) {
         HEARTBEAT_TIMEOUTS.inc_by(count);
     }
+
+    /// Record connections evicted for missing heartbeats
+    pub fn record_evictions(count: u64) {
+        HEARTBEAT_EVICTIONS_TOTAL.inc_by(count);
+    }
+
+    /// Record the interval the next heartbeat round will wait
+    pub fn set_current_interval(interval: Duration) {
+        HEARTBEAT_CURRENT_INTERVAL_SECONDS.set(interval.as_secs() as i64);
+    }
 }
 
 /// Helper struct for backend metrics
@@ -192,6 +309,11 @@ impl ClusterMetrics {
         CLUSTER_USERS_TOTAL.set(count as i64);
     }
 
+    /// Set whether this server is the cluster metrics leader
+    pub fn set_metrics_leader(leader: bool) {
+        CLUSTER_IS_METRICS_LEADER.set(if leader { 1 } else { 0 });
+    }
+
     /// Record sessions refreshed
     pub fn record_sessions_refreshed(count: usize) {
         CLUSTER_SESSIONS_REFRESHED.inc_by(count as u64);
@@ -206,6 +328,25 @@ impl ClusterMetrics {
     pub fn record_message_received() {
         CLUSTER_MESSAGES_RECEIVED.inc();
     }
+
+    /// Record a routed message dropped for an invalid signature
+    pub fn record_signature_failed() {
+        CLUSTER_MESSAGES_SIGNATURE_FAILED_TOTAL.inc();
+    }
+
+    /// Record a user route made with the given route strategy
+    pub fn record_route_strategy(strategy: &str) {
+        CLUSTER_ROUTE_STRATEGY_USED.with_label_values(&[strategy]).inc();
+    }
+
+    /// Record a preferred route, by whether the chosen server matched a preferred tag
+    pub fn record_preferred_route(matched: bool) {
+        if matched {
+            CLUSTER_ROUTING_PREFERRED_MATCH_TOTAL.inc();
+        } else {
+            CLUSTER_ROUTING_FALLBACK_TOTAL.inc();
+        }
+    }
 }
 
 /// Helper struct for recording WebSocket message metrics
@@ -229,10 +370,22 @@ impl WsMessageMetrics {
         WS_MESSAGES_RECEIVED.with_label_values(&["ping"]).inc();
     }
 
+    /// Record a pong frame
+    pub fn record_pong() {
+        WS_MESSAGES_RECEIVED.with_label_values(&["pong"]).inc();
+    }
+
     /// Record an ACK message
     pub fn record_ack() {
         WS_MESSAGES_RECEIVED.with_label_values(&["ack"]).inc();
     }
+
+    /// Record a rejected channel subscription
+    pub fn record_subscription_rejected(reason: &str) {
+        CHANNEL_SUBSCRIPTIONS_REJECTED_TOTAL
+            .with_label_values(&[reason])
+            .inc();
+    }
 }
 
 /// Helper struct for ACK metrics
@@ -263,6 +416,30 @@ impl AckMetrics {
     pub fn record_latency(latency_secs: f64) {
         ACK_LATENCY.observe(latency_secs);
     }
+
+    /// Set the ACK rate for an event type
+    pub fn set_rate_by_type(event_type: &str, ack_rate: f64) {
+        ACK_RATE_BY_TYPE
+            .with_label_values(&[ACK_EVENT_TYPE_LABEL_GUARD.label(event_type)])
+            .set(ack_rate);
+    }
+}
+
+/// Helper for Redis pool metrics
+pub struct RedisMetrics;
+
+impl RedisMetrics {
+    /// Refresh the pool utilization and read/write circuit breaker gauges
+    pub fn update_pool_stats(pool: &RedisPool) {
+        let stats = pool.stats();
+        REDIS_POOL_ACTIVE_CONNECTIONS.set(stats.active_connections as i64);
+        REDIS_POOL_IDLE_CONNECTIONS.set(stats.idle_connections as i64);
+        for operation in [OperationType::Read, OperationType::Write] {
+            REDIS_CIRCUIT_BREAKER_STATE
+                .with_label_values(&[operation.as_str()])
+                .set(pool.circuit_state(operation) as i64);
+        }
+    }
 }
 
 #[cfg(test)]
@@ -285,6 +462,8 @@ mod tests {
         RateLimitMetrics::record_http_denied();
         RateLimitMetrics::record_ws_allowed();
         RateLimitMetrics::record_ws_denied();
+        RateLimitMetrics::record_blocklisted();
+        RateLimitMetrics::record_allowlisted();
         // Just verify no panics
     }
 
@@ -294,6 +473,7 @@ mod tests {
         WsMessageMetrics::record_unsubscribe();
         WsMessageMetrics::record_ping();
         WsMessageMetrics::record_ack();
+        WsMessageMetrics::record_subscription_rejected("pattern_mismatch");
         // Just verify no panics
     }
 
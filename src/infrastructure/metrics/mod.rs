@@ -8,23 +8,37 @@
 //! - Queue metrics
 //! - Rate limiting metrics
 
+mod cardinality;
 mod helpers;
 
+pub use cardinality::{MetricsCardinalityGuard, OVERFLOW_LABEL};
 pub use helpers::{
-    encode_metrics, AckMetrics, BackendMetrics, ClusterMetrics, HeartbeatMetrics, MemoryMetrics,
-    MessageMetrics, RateLimitMetrics, WsMessageMetrics,
+    encode_metrics, encode_metrics_filtered, AckMetrics, BackendMetrics, ChannelMetrics, ClusterMetrics, HeartbeatMetrics,
+    MemoryMetrics, MessageMetrics, RateLimitMetrics, RedisMetrics, WsMessageMetrics,
 };
 
 use lazy_static::lazy_static;
 use prometheus::{
-    register_histogram, register_histogram_vec, register_int_counter, register_int_counter_vec,
-    register_int_gauge, Histogram, HistogramVec, IntCounter, IntCounterVec,
-    IntGauge,
+    register_gauge_vec, register_histogram, register_histogram_vec, register_int_counter,
+    register_int_counter_vec, register_int_gauge, register_int_gauge_vec, GaugeVec, Histogram,
+    HistogramVec, IntCounter, IntCounterVec, IntGauge, IntGaugeVec,
 };
 
 /// Prefix for all metrics
 const METRIC_PREFIX: &str = "ara";
 
+/// Maximum distinct `channel` label values on per-channel metrics
+const MAX_CHANNEL_LABELS: usize = 500;
+
+/// Maximum distinct `pattern` label values on channel pattern dispatch metrics
+const MAX_CHANNEL_PATTERN_LABELS: usize = 50;
+
+/// Maximum distinct `tag` label values on notification tag metrics
+const MAX_TAG_LABELS: usize = 100;
+
+/// Maximum distinct `event_type` label values on per-event-type ACK metrics
+const MAX_ACK_EVENT_TYPE_LABELS: usize = 200;
+
 lazy_static! {
     // ============================================================================
     // Connection Metrics
@@ -42,6 +56,18 @@ lazy_static! {
         "Number of unique connected users"
     ).unwrap();
 
+    /// Active connections summed across all tenants tracked by the tenant manager
+    pub static ref TENANT_AGGREGATE_ACTIVE_CONNECTIONS: IntGauge = register_int_gauge!(
+        format!("{}_tenant_aggregate_active_connections", METRIC_PREFIX),
+        "Active connections summed across all tenants"
+    ).unwrap();
+
+    /// Connection IDs addressable via `ConnectionManager::get_connection`
+    pub static ref CONNECTION_IDS_TRACKED: IntGauge = register_int_gauge!(
+        format!("{}_connection_ids_tracked", METRIC_PREFIX),
+        "Number of connection IDs tracked for targeted delivery"
+    ).unwrap();
+
     /// Connections per user (for detecting connection hoarding)
     pub static ref CONNECTIONS_PER_USER: Histogram = register_histogram!(
         format!("{}_connections_per_user", METRIC_PREFIX),
@@ -49,12 +75,36 @@ lazy_static! {
         vec![1.0, 2.0, 3.0, 4.0, 5.0, 10.0]
     ).unwrap();
 
+    /// Connection lifecycle events published on the connection event bus
+    pub static ref CONNECTION_EVENTS_PUBLISHED_TOTAL: IntCounterVec = register_int_counter_vec!(
+        format!("{}_connection_events_published_total", METRIC_PREFIX),
+        "Total connection events published",
+        &["event_type"]
+    ).unwrap();
+
     /// Total channels with subscribers
     pub static ref CHANNELS_ACTIVE: IntGauge = register_int_gauge!(
         format!("{}_channels_active", METRIC_PREFIX),
         "Total number of channels with at least one subscriber"
     ).unwrap();
 
+    /// Channels deleted through the admin API
+    pub static ref CHANNELS_DELETED_TOTAL: IntCounter = register_int_counter!(
+        format!("{}_channels_deleted_total", METRIC_PREFIX),
+        "Total channels deleted, unsubscribing all of their subscribers"
+    ).unwrap();
+
+    /// Highest subscriber count seen per channel (label cardinality capped by `CHANNEL_LABEL_GUARD`)
+    pub static ref CHANNEL_PEAK_SUBSCRIBERS: IntGaugeVec = register_int_gauge_vec!(
+        format!("{}_channel_peak_subscribers", METRIC_PREFIX),
+        "Peak number of subscribers per channel",
+        &["channel"]
+    ).unwrap();
+
+    /// Limits the number of distinct channel labels
+    pub static ref CHANNEL_LABEL_GUARD: MetricsCardinalityGuard =
+        MetricsCardinalityGuard::new(MAX_CHANNEL_LABELS);
+
     // ============================================================================
     // Message Metrics
     // ============================================================================
@@ -78,13 +128,107 @@ lazy_static! {
         "Total message delivery failures"
     ).unwrap();
 
-    /// Message delivery latency (time from dispatch to connection send)
+    /// Number of distinct event types tracked by the dispatcher
+    pub static ref NOTIFICATION_EVENT_TYPES_TRACKED: IntGauge = register_int_gauge!(
+        format!("{}_notification_event_types_tracked", METRIC_PREFIX),
+        "Number of distinct notification event types tracked by the dispatcher"
+    ).unwrap();
+
+    /// Dispatched notifications per tag (label cardinality capped by `TAG_LABEL_GUARD`)
+    pub static ref NOTIFICATION_TAGS_USED_TOTAL: IntCounterVec = register_int_counter_vec!(
+        format!("{}_notification_tags_used_total", METRIC_PREFIX),
+        "Total notifications dispatched per client-side tag",
+        &["tag"]
+    ).unwrap();
+
+    /// Limits the number of distinct tag labels
+    pub static ref TAG_LABEL_GUARD: MetricsCardinalityGuard =
+        MetricsCardinalityGuard::new(MAX_TAG_LABELS);
+
+    /// Dispatches to wildcard channel patterns (label cardinality capped by
+    /// `CHANNEL_PATTERN_LABEL_GUARD`)
+    pub static ref CHANNEL_PATTERN_DISPATCH_TOTAL: IntCounterVec = register_int_counter_vec!(
+        format!("{}_channel_pattern_dispatch_total", METRIC_PREFIX),
+        "Total notifications dispatched to a wildcard channel pattern",
+        &["pattern"]
+    ).unwrap();
+
+    /// Limits the number of distinct channel pattern labels
+    pub static ref CHANNEL_PATTERN_LABEL_GUARD: MetricsCardinalityGuard =
+        MetricsCardinalityGuard::new(MAX_CHANNEL_PATTERN_LABELS);
+
+    /// Notifications for offline users discarded because the offline queue is disabled
+    pub static ref NOTIFICATIONS_DROPPED_QUEUE_DISABLED_TOTAL: IntCounter = register_int_counter!(
+        format!("{}_notifications_dropped_queue_disabled_total", METRIC_PREFIX),
+        "Total notifications for offline users dropped because the queue is disabled"
+    ).unwrap();
+
+    /// Connection deliveries skipped because the notification's TTL passed mid-dispatch
+    pub static ref NOTIFICATIONS_EXPIRED_AT_DELIVERY_TOTAL: IntCounter = register_int_counter!(
+        format!("{}_notifications_expired_at_delivery_total", METRIC_PREFIX),
+        "Total connection deliveries skipped because the notification expired before sending"
+    ).unwrap();
+
+    /// Sends that carried a caller-provided notification ID
+    pub static ref DEDUP_CALLER_PROVIDED_IDS_TOTAL: IntCounter = register_int_counter!(
+        format!("{}_dedup_caller_provided_ids_total", METRIC_PREFIX),
+        "Total notification sends with a caller-provided notification ID"
+    ).unwrap();
+
+    /// Sends skipped because their caller-provided notification ID was already sent
+    pub static ref DEDUP_CALLER_DUPLICATES_TOTAL: IntCounter = register_int_counter!(
+        format!("{}_dedup_caller_duplicates_total", METRIC_PREFIX),
+        "Total notification sends skipped as duplicates of a caller-provided notification ID"
+    ).unwrap();
+
+    /// Batch requests answered as dry runs, without sending anything
+    pub static ref BATCH_DRY_RUNS_TOTAL: IntCounter = register_int_counter!(
+        format!("{}_batch_dry_runs_total", METRIC_PREFIX),
+        "Total batch send requests processed as dry runs"
+    ).unwrap();
+
+    /// Notifications a service sent on behalf of a user (`on_behalf_of`)
+    pub static ref NOTIFICATIONS_IMPERSONATED_TOTAL: IntCounter = register_int_counter!(
+        format!("{}_notifications_impersonated_total", METRIC_PREFIX),
+        "Total notification sends made by a service on behalf of a user"
+    ).unwrap();
+
+    /// Dispatches rejected because no dispatch slot freed up within the dispatch timeout
+    pub static ref DISPATCHER_BACKPRESSURE_TOTAL: IntCounter = register_int_counter!(
+        format!("{}_dispatcher_backpressure_total", METRIC_PREFIX),
+        "Total dispatches rejected because the maximum concurrent dispatches was reached"
+    ).unwrap();
+
+    /// Whether a dispatch circuit shard is open (1) and its connections are skipped
+    pub static ref DISPATCH_CIRCUIT_OPEN: IntGaugeVec = register_int_gauge_vec!(
+        format!("{}_dispatch_circuit_open", METRIC_PREFIX),
+        "Whether the dispatch circuit for a connection shard is open (1=open, 0=closed)",
+        &["shard"]
+    ).unwrap();
+
+    /// Fan-out sends currently in flight across all dispatches
+    pub static ref BROADCAST_FANOUT_INFLIGHT: IntGauge = register_int_gauge!(
+        format!("{}_broadcast_fanout_inflight", METRIC_PREFIX),
+        "Number of concurrent fan-out connection sends in flight"
+    ).unwrap();
+
+    /// Message delivery latency (time from event occurrence until a dispatch has
+    /// finished sending to all connections)
     pub static ref MESSAGE_DELIVERY_LATENCY: Histogram = register_histogram!(
         format!("{}_message_delivery_latency_seconds", METRIC_PREFIX),
         "Message delivery latency in seconds",
         vec![0.0001, 0.0005, 0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0]
     ).unwrap();
 
+    /// Time until a notification's first successful delivery, by path: "live"
+    /// measures from event occurrence, "replay" from when the message was queued
+    pub static ref NOTIFICATION_E2E_LATENCY_SECONDS: HistogramVec = register_histogram_vec!(
+        format!("{}_notification_e2e_latency_seconds", METRIC_PREFIX),
+        "Time until a notification's first successful delivery in seconds",
+        &["path"],
+        vec![0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 2.5, 5.0, 10.0]
+    ).unwrap();
+
     // ============================================================================
     // Redis Metrics
     // ============================================================================
@@ -95,10 +239,12 @@ lazy_static! {
         "Redis connection status (1=connected, 0=disconnected)"
     ).unwrap();
 
-    /// Redis circuit breaker state (0=closed, 1=open, 2=half-open)
-    pub static ref REDIS_CIRCUIT_BREAKER_STATE: IntGauge = register_int_gauge!(
+    /// Redis circuit breaker state (0=closed, 1=open, 2=half-open) by guarded operation
+    /// (`subscribe` for Pub/Sub, `read`/`write` for pool operations)
+    pub static ref REDIS_CIRCUIT_BREAKER_STATE: IntGaugeVec = register_int_gauge_vec!(
         format!("{}_redis_circuit_breaker_state", METRIC_PREFIX),
-        "Redis circuit breaker state (0=closed, 1=open, 2=half-open)"
+        "Redis circuit breaker state (0=closed, 1=open, 2=half-open)",
+        &["operation"]
     ).unwrap();
 
     /// Total Redis reconnection attempts
@@ -113,6 +259,18 @@ lazy_static! {
         "Total messages received from Redis pub/sub"
     ).unwrap();
 
+    /// Active Redis pub/sub pattern (PSUBSCRIBE) subscriptions
+    pub static ref REDIS_PATTERN_SUBSCRIPTIONS: IntGauge = register_int_gauge!(
+        format!("{}_redis_pattern_subscriptions", METRIC_PREFIX),
+        "Number of active Redis pub/sub pattern subscriptions"
+    ).unwrap();
+
+    /// Redis pub/sub messages moved to the quarantine list after failing to parse
+    pub static ref REDIS_MESSAGES_QUARANTINED_TOTAL: IntCounter = register_int_counter!(
+        format!("{}_redis_messages_quarantined_total", METRIC_PREFIX),
+        "Total Redis pub/sub messages quarantined after failing to deserialize"
+    ).unwrap();
+
     // ============================================================================
     // Queue Metrics
     // ============================================================================
@@ -153,6 +311,24 @@ lazy_static! {
         "Total messages dropped due to queue being full"
     ).unwrap();
 
+    /// Enqueues skipped because the notification was already queued for the user
+    pub static ref QUEUE_DEDUP_SKIPPED_TOTAL: IntCounter = register_int_counter!(
+        format!("{}_queue_dedup_skipped_total", METRIC_PREFIX),
+        "Total enqueues skipped because the notification was already queued for the user"
+    ).unwrap();
+
+    /// Queued messages discarded because their event no longer matched its checksum
+    pub static ref QUEUE_CHECKSUM_FAILURES_TOTAL: IntCounter = register_int_counter!(
+        format!("{}_queue_checksum_failures_total", METRIC_PREFIX),
+        "Total queued messages discarded due to a checksum mismatch"
+    ).unwrap();
+
+    /// Replays that found the per-user advisory lock already held
+    pub static ref QUEUE_REPLAY_LOCK_CONTENTION_TOTAL: IntCounter = register_int_counter!(
+        format!("{}_queue_replay_lock_contention_total", METRIC_PREFIX),
+        "Total queue replays that had to wait for another connection's replay lock"
+    ).unwrap();
+
     // ============================================================================
     // Rate Limiting Metrics
     // ============================================================================
@@ -171,6 +347,24 @@ lazy_static! {
         &["type"]
     ).unwrap();
 
+    /// Requests rejected because the client IP is on the blocklist
+    pub static ref RATELIMIT_BLOCKLISTED_TOTAL: IntCounter = register_int_counter!(
+        format!("{}_ratelimit_blocklisted_total", METRIC_PREFIX),
+        "Total requests rejected by the rate limiter IP blocklist"
+    ).unwrap();
+
+    /// Requests that bypassed rate limiting because the client IP is allowlisted
+    pub static ref RATELIMIT_ALLOWLISTED_TOTAL: IntCounter = register_int_counter!(
+        format!("{}_ratelimit_allowlisted_total", METRIC_PREFIX),
+        "Total requests that bypassed the rate limiter via the IP allowlist"
+    ).unwrap();
+
+    /// Whether rate limiting is bypassed after a suspected misconfiguration (1=active, 0=enforcing)
+    pub static ref RATELIMIT_EMERGENCY_BYPASS_ACTIVE: IntGauge = register_int_gauge!(
+        format!("{}_ratelimit_emergency_bypass_active", METRIC_PREFIX),
+        "Rate limit emergency bypass active (1=active, 0=enforcing)"
+    ).unwrap();
+
     // ============================================================================
     // ACK Metrics
     // ============================================================================
@@ -206,6 +400,20 @@ lazy_static! {
         vec![0.01, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0]
     ).unwrap();
 
+    /// ACK rate per event type (label cardinality capped by `ACK_EVENT_TYPE_LABEL_GUARD`)
+    pub static ref ACK_RATE_BY_TYPE: GaugeVec = register_gauge_vec!(
+        format!("{}_ack_rate_by_type", METRIC_PREFIX),
+        "ACK rate (acked / (acked + expired)) per notification event type",
+        &["event_type"]
+    ).unwrap();
+
+    /// Limits the number of distinct event type labels on ACK metrics
+    pub static ref ACK_EVENT_TYPE_LABEL_GUARD: MetricsCardinalityGuard =
+        MetricsCardinalityGuard::new(MAX_ACK_EVENT_TYPE_LABELS);
+}
+
+// Split across blocks to stay within the macro recursion limit
+lazy_static! {
     // ============================================================================
     // HTTP API Metrics
     // ============================================================================
@@ -217,6 +425,25 @@ lazy_static! {
         &["method", "path", "status"]
     ).unwrap();
 
+    /// Requests rejected for exceeding the body size limit
+    pub static ref HTTP_REQUEST_BODY_TOO_LARGE_TOTAL: IntCounter = register_int_counter!(
+        format!("{}_http_request_body_too_large_total", METRIC_PREFIX),
+        "Total HTTP requests rejected with 413 Payload Too Large"
+    ).unwrap();
+
+    /// Requests to list a user's active connections (`GET /users/{user_id}/connections`)
+    pub static ref USER_CONNECTION_QUERIES_TOTAL: IntCounter = register_int_counter!(
+        format!("{}_user_connection_queries_total", METRIC_PREFIX),
+        "Total requests listing a user's active connections"
+    ).unwrap();
+
+    /// Admin API requests rejected by `AdminAuth`
+    pub static ref ADMIN_AUTH_FAILURES_TOTAL: IntCounterVec = register_int_counter_vec!(
+        format!("{}_admin_auth_failures_total", METRIC_PREFIX),
+        "Total admin API requests rejected for a missing or invalid admin API key",
+        &["reason"]  // missing, invalid, not_configured
+    ).unwrap();
+
     /// HTTP request latency
     pub static ref HTTP_REQUEST_LATENCY: HistogramVec = register_histogram_vec!(
         format!("{}_http_request_latency_seconds", METRIC_PREFIX),
@@ -225,6 +452,17 @@ lazy_static! {
         vec![0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0]
     ).unwrap();
 
+    // ============================================================================
+    // gRPC API Metrics
+    // ============================================================================
+
+    /// gRPC requests by method
+    pub static ref GRPC_REQUESTS_TOTAL: IntCounterVec = register_int_counter_vec!(
+        format!("{}_grpc_requests_total", METRIC_PREFIX),
+        "Total gRPC API requests",
+        &["method"]
+    ).unwrap();
+
     // ============================================================================
     // WebSocket Metrics
     // ============================================================================
@@ -248,6 +486,31 @@ lazy_static! {
         &["type"]
     ).unwrap();
 
+    /// MessagePack notifications sent as binary WebSocket frames
+    pub static ref WS_BINARY_MESSAGES_SENT_TOTAL: IntCounter = register_int_counter!(
+        format!("{}_ws_binary_messages_sent_total", METRIC_PREFIX),
+        "Total notifications sent as binary MessagePack WebSocket frames"
+    ).unwrap();
+
+    /// WebSocket connections closed for sending a frame above `websocket.max_frame_size_bytes`
+    pub static ref WS_FRAME_TOO_LARGE_TOTAL: IntCounter = register_int_counter!(
+        format!("{}_ws_frame_too_large_total", METRIC_PREFIX),
+        "Total WebSocket connections closed with 1009 for an oversized frame"
+    ).unwrap();
+
+    /// Connection sends that exceeded the per-connection send timeout
+    pub static ref WS_SEND_TIMEOUTS_TOTAL: IntCounter = register_int_counter!(
+        format!("{}_ws_send_timeouts_total", METRIC_PREFIX),
+        "Total notification sends that timed out and evicted the connection"
+    ).unwrap();
+
+    /// Channel subscriptions rejected by validation or tenant policy
+    pub static ref CHANNEL_SUBSCRIPTIONS_REJECTED_TOTAL: IntCounterVec = register_int_counter_vec!(
+        format!("{}_channel_subscriptions_rejected_total", METRIC_PREFIX),
+        "Total channel subscriptions rejected",
+        &["reason"]
+    ).unwrap();
+
     /// WebSocket connection duration
     pub static ref WS_CONNECTION_DURATION: Histogram = register_histogram!(
         format!("{}_ws_connection_duration_seconds", METRIC_PREFIX),
@@ -272,6 +535,22 @@ lazy_static! {
         vec![1.0, 5.0, 10.0, 25.0, 50.0, 75.0, 100.0]
     ).unwrap();
 
+    // ============================================================================
+    // Template Metrics
+    // ============================================================================
+
+    /// Template preview renders
+    pub static ref TEMPLATE_PREVIEWS_TOTAL: IntCounter = register_int_counter!(
+        format!("{}_template_previews_total", METRIC_PREFIX),
+        "Total template preview requests"
+    ).unwrap();
+
+    /// Template searches (`GET /api/v1/templates?q=`)
+    pub static ref TEMPLATE_SEARCHES_TOTAL: IntCounter = register_int_counter!(
+        format!("{}_template_searches_total", METRIC_PREFIX),
+        "Total template search requests"
+    ).unwrap();
+
     // ============================================================================
     // Process & Memory Metrics
     // ============================================================================
@@ -301,6 +580,18 @@ lazy_static! {
         vec![10.0, 50.0, 100.0, 250.0, 500.0, 1000.0, 2500.0, 5000.0, 10000.0]
     ).unwrap();
 
+    /// Current heartbeat interval (changes when adaptive heartbeats back off)
+    pub static ref HEARTBEAT_CURRENT_INTERVAL_SECONDS: IntGauge = register_int_gauge!(
+        format!("{}_heartbeat_current_interval_seconds", METRIC_PREFIX),
+        "Current heartbeat interval in seconds"
+    ).unwrap();
+
+    /// Connections evicted after too many missed heartbeats
+    pub static ref HEARTBEAT_EVICTIONS_TOTAL: IntCounter = register_int_counter!(
+        format!("{}_heartbeat_evictions_total", METRIC_PREFIX),
+        "Total connections evicted for missing heartbeats"
+    ).unwrap();
+
     /// Heartbeat timeouts per round
     pub static ref HEARTBEAT_TIMEOUTS: IntCounter = register_int_counter!(
         format!("{}_heartbeat_timeouts_total", METRIC_PREFIX),
@@ -365,6 +656,120 @@ lazy_static! {
         format!("{}_cluster_messages_received_total", METRIC_PREFIX),
         "Total messages received from other servers"
     ).unwrap();
+
+    /// Routed messages dropped because their signature did not verify
+    pub static ref CLUSTER_MESSAGES_SIGNATURE_FAILED_TOTAL: IntCounter = register_int_counter!(
+        format!("{}_cluster_messages_signature_failed_total", METRIC_PREFIX),
+        "Total routed messages dropped for an invalid signature"
+    ).unwrap();
+
+    /// User routes by cluster route strategy
+    pub static ref CLUSTER_ROUTE_STRATEGY_USED: IntCounterVec = register_int_counter_vec!(
+        format!("{}_cluster_route_strategy_used_total", METRIC_PREFIX),
+        "Total user notifications routed, by cluster route strategy",
+        &["strategy"]
+    ).unwrap();
+
+    /// Preferred routes delivered to a server matching at least one preferred tag
+    pub static ref CLUSTER_ROUTING_PREFERRED_MATCH_TOTAL: IntCounter = register_int_counter!(
+        format!("{}_cluster_routing_preferred_match_total", METRIC_PREFIX),
+        "Total preferred routes sent to a server matching the preferred tags"
+    ).unwrap();
+
+    /// Preferred routes that fell back to a server matching none of the preferred tags
+    pub static ref CLUSTER_ROUTING_FALLBACK_TOTAL: IntCounter = register_int_counter!(
+        format!("{}_cluster_routing_fallback_total", METRIC_PREFIX),
+        "Total preferred routes that fell back to a server matching no preferred tag"
+    ).unwrap();
+
+    /// Whether this server holds the cluster metrics leadership (1=leader, 0=not)
+    pub static ref CLUSTER_IS_METRICS_LEADER: IntGauge = register_int_gauge!(
+        format!("{}_cluster_is_metrics_leader", METRIC_PREFIX),
+        "Whether this server refreshes cluster-wide metrics (1=leader, 0=not)"
+    ).unwrap();
+
+    // ============================================================================
+    // Feature Flag Metrics
+    // ============================================================================
+
+    /// Modules switched off with `ARA_FEATURE_*` (1=disabled, 0=enabled)
+    pub static ref FEATURE_FLAG_DISABLED: IntGaugeVec = register_int_gauge_vec!(
+        format!("{}_feature_flag_disabled", METRIC_PREFIX),
+        "Feature disabled by ARA_FEATURE_* flag (1=disabled, 0=enabled)",
+        &["feature"]
+    ).unwrap();
+
+    // ============================================================================
+    // Cleanup Metrics
+    // ============================================================================
+
+    /// Expired entries removed by the cleanup task (by component)
+    pub static ref CLEANUP_ITEMS_REMOVED_TOTAL: IntCounterVec = register_int_counter_vec!(
+        format!("{}_cleanup_items_removed_total", METRIC_PREFIX),
+        "Total expired entries removed by the cleanup task",
+        &["component"]
+    ).unwrap();
+
+    // ============================================================================
+    // Push Gateway Metrics
+    // ============================================================================
+
+    /// Pushes to the Prometheus Pushgateway that failed after all retries
+    pub static ref METRICS_PUSH_FAILURES_TOTAL: IntCounter = register_int_counter!(
+        format!("{}_metrics_push_failures_total", METRIC_PREFIX),
+        "Total pushes to the Prometheus Pushgateway that failed after all retries"
+    ).unwrap();
+
+    // ============================================================================
+    // Redis Pool Metrics
+    // ============================================================================
+
+    /// Established pool connections with no operation in flight
+    pub static ref REDIS_POOL_IDLE_CONNECTIONS: IntGauge = register_int_gauge!(
+        format!("{}_redis_pool_idle_connections", METRIC_PREFIX),
+        "Established Redis pool connections with no operation in flight"
+    ).unwrap();
+
+    /// Operations currently holding a pool connection
+    pub static ref REDIS_POOL_ACTIVE_CONNECTIONS: IntGauge = register_int_gauge!(
+        format!("{}_redis_pool_active_connections", METRIC_PREFIX),
+        "Redis pool operations currently holding a connection"
+    ).unwrap();
+
+    /// Time spent obtaining a pool connection
+    pub static ref REDIS_POOL_CHECKOUT_WAIT_SECONDS: Histogram = register_histogram!(
+        format!("{}_redis_pool_checkout_wait_seconds", METRIC_PREFIX),
+        "Time spent obtaining a Redis pool connection in seconds",
+        vec![0.0001, 0.0005, 0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0]
+    ).unwrap();
+
+    /// Operations that timed out before obtaining a connection
+    pub static ref REDIS_POOL_OVERFLOW_TOTAL: IntCounter = register_int_counter!(
+        format!("{}_redis_pool_overflow_total", METRIC_PREFIX),
+        "Total Redis pool operations that timed out waiting for a connection"
+    ).unwrap();
+
+    // ============================================================================
+    // PostgreSQL Metrics
+    // ============================================================================
+
+    /// PostgreSQL connection status (1 = connected, 0 = disconnected)
+    pub static ref POSTGRES_CONNECTION_STATUS: IntGauge = register_int_gauge!(
+        format!("{}_postgres_connection_status", METRIC_PREFIX),
+        "PostgreSQL connection status (1=connected, 0=disconnected)"
+    ).unwrap();
+
+    /// PostgreSQL circuit breaker state (0=closed, 1=open, 2=half-open)
+    pub static ref POSTGRES_CIRCUIT_BREAKER_STATE: IntGauge = register_int_gauge!(
+        format!("{}_postgres_circuit_breaker_state", METRIC_PREFIX),
+        "PostgreSQL circuit breaker state (0=closed, 1=open, 2=half-open)"
+    ).unwrap();
+
+    /// PostgreSQL recoveries after failed operations
+    pub static ref POSTGRES_RECONNECTIONS_TOTAL: IntCounter = register_int_counter!(
+        format!("{}_postgres_reconnections_total", METRIC_PREFIX),
+        "Total PostgreSQL reconnections after failed operations"
+    ).unwrap();
 }
 
 #[cfg(test)]
@@ -383,21 +788,61 @@ mod tests {
         assert!(output.contains("ara_connections_total"));
     }
 
+    #[test]
+    fn test_encode_metrics_filtered() {
+        CONNECTIONS_TOTAL.set(1);
+        QUEUE_SIZE_TOTAL.set(1);
+
+        let output = encode_metrics_filtered("ara_queue_").unwrap();
+        assert!(output.contains("ara_queue_size_total"));
+        assert!(!output.contains("ara_connections_total"));
+
+        assert!(encode_metrics_filtered("no_such_prefix_").unwrap().is_empty());
+    }
+
     #[test]
     fn test_connection_metrics() {
         CONNECTIONS_TOTAL.set(100);
         USERS_CONNECTED.set(50);
+        CONNECTION_IDS_TRACKED.set(100);
         CONNECTIONS_PER_USER.observe(2.0);
         CHANNELS_ACTIVE.set(10);
+        CHANNELS_DELETED_TOTAL.inc();
+        CHANNEL_PEAK_SUBSCRIBERS.with_label_values(&["test-channel"]).set(3);
+        // Just verify no panics
+    }
+
+    #[test]
+    fn test_message_metrics() {
+        MESSAGES_DELIVERED_TOTAL.inc();
+        MESSAGES_FAILED_TOTAL.inc();
+        BROADCAST_FANOUT_INFLIGHT.inc();
+        BROADCAST_FANOUT_INFLIGHT.dec();
+        DISPATCH_CIRCUIT_OPEN.with_label_values(&["0"]).set(0);
+        NOTIFICATIONS_DROPPED_QUEUE_DISABLED_TOTAL.inc();
+        WS_BINARY_MESSAGES_SENT_TOTAL.inc();
+        WS_FRAME_TOO_LARGE_TOTAL.inc();
+        WS_SEND_TIMEOUTS_TOTAL.inc();
+        DEDUP_CALLER_PROVIDED_IDS_TOTAL.inc();
+        DEDUP_CALLER_DUPLICATES_TOTAL.inc();
+        NOTIFICATIONS_EXPIRED_AT_DELIVERY_TOTAL.inc();
+        BATCH_DRY_RUNS_TOTAL.inc();
+        NOTIFICATIONS_IMPERSONATED_TOTAL.inc();
+        MessageMetrics::record_tags(&["badge_count".to_string()]);
+        MessageMetrics::record_delivery_latency(chrono::Utc::now());
+        MessageMetrics::record_e2e_latency("replay", chrono::Utc::now());
         // Just verify no panics
     }
 
     #[test]
     fn test_redis_metrics() {
         REDIS_CONNECTION_STATUS.set(1);
-        REDIS_CIRCUIT_BREAKER_STATE.set(0);
+        REDIS_CIRCUIT_BREAKER_STATE.with_label_values(&["subscribe"]).set(0);
+        REDIS_CIRCUIT_BREAKER_STATE.with_label_values(&["read"]).set(1);
         REDIS_RECONNECTIONS_TOTAL.inc();
         REDIS_MESSAGES_RECEIVED.inc();
+        REDIS_MESSAGES_QUARANTINED_TOTAL.inc();
+        REDIS_PATTERN_SUBSCRIPTIONS.set(2);
         // Just verify no panics
     }
 
@@ -408,7 +853,10 @@ mod tests {
         QUEUE_ENQUEUED_TOTAL.inc();
         QUEUE_REPLAYED_TOTAL.inc();
         QUEUE_EXPIRED_TOTAL.inc();
+        QUEUE_CHECKSUM_FAILURES_TOTAL.inc();
         QUEUE_DROPPED_TOTAL.inc();
+        QUEUE_DEDUP_SKIPPED_TOTAL.inc();
+        QUEUE_REPLAY_LOCK_CONTENTION_TOTAL.inc();
         // Just verify no panics
     }
 
@@ -419,6 +867,85 @@ mod tests {
         ACK_EXPIRED_TOTAL.inc();
         ACK_PENDING.set(5);
         ACK_LATENCY.observe(0.1);
+        ACK_RATE_BY_TYPE.with_label_values(&["order.created"]).set(0.95);
+        // Just verify no panics
+    }
+
+    #[test]
+    fn test_http_metrics() {
+        HTTP_REQUESTS_TOTAL.with_label_values(&["POST", "/api/v1/notifications/send", "200"]).inc();
+        HTTP_REQUEST_LATENCY.with_label_values(&["POST", "/api/v1/notifications/send"]).observe(0.01);
+        HTTP_REQUEST_BODY_TOO_LARGE_TOTAL.inc();
+        ADMIN_AUTH_FAILURES_TOTAL.with_label_values(&["invalid"]).inc();
+        USER_CONNECTION_QUERIES_TOTAL.inc();
+        GRPC_REQUESTS_TOTAL.with_label_values(&["SendToUser"]).inc();
+        // Just verify no panics
+    }
+
+    #[test]
+    fn test_heartbeat_metrics() {
+        HEARTBEAT_DURATION_MS.observe(12.0);
+        HEARTBEAT_CURRENT_INTERVAL_SECONDS.set(30);
+        HEARTBEAT_TIMEOUTS.inc();
+        HEARTBEAT_EVICTIONS_TOTAL.inc();
+        // Just verify no panics
+    }
+
+    #[test]
+    fn test_template_metrics() {
+        TEMPLATE_PREVIEWS_TOTAL.inc();
+        TEMPLATE_SEARCHES_TOTAL.inc();
+        // Just verify no panics
+    }
+
+    #[test]
+    fn test_feature_flag_metrics() {
+        FEATURE_FLAG_DISABLED.with_label_values(&["sse"]).set(0);
+        // Just verify no panics
+    }
+
+    #[test]
+    fn test_cluster_leader_metrics() {
+        CLUSTER_IS_METRICS_LEADER.set(0);
+        CLUSTER_ROUTE_STRATEGY_USED.with_label_values(&["local_first"]).inc();
+        CLUSTER_ROUTING_PREFERRED_MATCH_TOTAL.inc();
+        CLUSTER_ROUTING_FALLBACK_TOTAL.inc();
+        CLUSTER_MESSAGES_SIGNATURE_FAILED_TOTAL.inc();
+        // Just verify no panics
+    }
+
+    #[test]
+    fn test_cleanup_metrics() {
+        CLEANUP_ITEMS_REMOVED_TOTAL.with_label_values(&["queue"]).inc();
+        // Just verify no panics
+    }
+
+    #[test]
+    fn test_metrics_push_metrics() {
+        METRICS_PUSH_FAILURES_TOTAL.inc();
+        // Just verify no panics
+    }
+
+    #[test]
+    fn test_ratelimit_bypass_metrics() {
+        RATELIMIT_EMERGENCY_BYPASS_ACTIVE.get();
+        // Just verify no panics
+    }
+
+    #[test]
+    fn test_redis_pool_metrics() {
+        REDIS_POOL_IDLE_CONNECTIONS.get();
+        REDIS_POOL_ACTIVE_CONNECTIONS.get();
+        REDIS_POOL_CHECKOUT_WAIT_SECONDS.observe(0.001);
+        REDIS_POOL_OVERFLOW_TOTAL.get();
+        // Just verify no panics
+    }
+
+    #[test]
+    fn test_postgres_metrics() {
+        POSTGRES_CONNECTION_STATUS.set(1);
+        POSTGRES_CIRCUIT_BREAKER_STATE.set(0);
+        POSTGRES_RECONNECTIONS_TOTAL.get();
         // Just verify no panics
     }
 }
@@ -1,6 +1,7 @@
 //! Infrastructure layer modules
 //!
 //! This module contains shared infrastructure components:
+//! - `audit`: Compliance audit trail of notification dispatches
 //! - `auth`: JWT authentication and validation
 //! - `config`: Application configuration and settings
 //! - `error`: Unified error types
@@ -8,6 +9,7 @@
 //! - `postgres`: PostgreSQL connection pool
 //! - `redis`: Redis connection pool, circuit breaker, and health checks
 
+pub mod audit;
 pub mod auth;
 pub mod config;
 pub mod error;
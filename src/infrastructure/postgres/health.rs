@@ -0,0 +1,167 @@
+//! PostgreSQL connection health tracking
+
+use std::sync::atomic::{AtomicI64, AtomicU32, AtomicU8, Ordering};
+
+use crate::redis::current_time_ms;
+
+/// PostgreSQL connection health status
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum PostgresHealthStatus {
+    /// The last pool operation succeeded
+    Healthy,
+    /// The last pool operation failed; the next one will retry
+    Reconnecting,
+    /// Circuit breaker is open, operations are rejected without trying
+    CircuitOpen,
+    /// PostgreSQL is not required by current runtime configuration
+    Disabled,
+}
+
+impl PostgresHealthStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PostgresHealthStatus::Healthy => "healthy",
+            PostgresHealthStatus::Reconnecting => "reconnecting",
+            PostgresHealthStatus::CircuitOpen => "circuit_open",
+            PostgresHealthStatus::Disabled => "disabled",
+        }
+    }
+}
+
+/// PostgreSQL health tracker
+pub struct PostgresHealth {
+    status: AtomicU8,
+    last_connected: AtomicI64,
+    reconnection_attempts: AtomicU32,
+    total_reconnections: AtomicU32,
+}
+
+impl PostgresHealth {
+    pub fn new() -> Self {
+        Self::new_with_enabled(true)
+    }
+
+    pub fn new_with_enabled(enabled: bool) -> Self {
+        Self {
+            status: AtomicU8::new(if enabled {
+                PostgresHealthStatus::Reconnecting as u8
+            } else {
+                PostgresHealthStatus::Disabled as u8
+            }),
+            last_connected: AtomicI64::new(0),
+            reconnection_attempts: AtomicU32::new(0),
+            total_reconnections: AtomicU32::new(0),
+        }
+    }
+
+    /// Mark PostgreSQL as connected, returning whether this recovered from a failure
+    pub fn set_connected(&self) -> bool {
+        let was_reconnecting =
+            self.status.load(Ordering::Acquire) != PostgresHealthStatus::Healthy as u8;
+        self.status
+            .store(PostgresHealthStatus::Healthy as u8, Ordering::Release);
+        self.last_connected
+            .store(current_time_ms(), Ordering::Release);
+
+        if was_reconnecting {
+            self.total_reconnections.fetch_add(1, Ordering::AcqRel);
+        }
+        self.reconnection_attempts.store(0, Ordering::Release);
+        was_reconnecting
+    }
+
+    /// Mark PostgreSQL as reconnecting
+    pub fn set_reconnecting(&self) {
+        self.status
+            .store(PostgresHealthStatus::Reconnecting as u8, Ordering::Release);
+        self.reconnection_attempts.fetch_add(1, Ordering::AcqRel);
+    }
+
+    /// Mark circuit as open
+    pub fn set_circuit_open(&self) {
+        self.status
+            .store(PostgresHealthStatus::CircuitOpen as u8, Ordering::Release);
+    }
+
+    /// Get current status
+    pub fn status(&self) -> PostgresHealthStatus {
+        match self.status.load(Ordering::Acquire) {
+            0 => PostgresHealthStatus::Healthy,
+            1 => PostgresHealthStatus::Reconnecting,
+            2 => PostgresHealthStatus::CircuitOpen,
+            _ => PostgresHealthStatus::Disabled,
+        }
+    }
+
+    /// Check if PostgreSQL is healthy
+    pub fn is_healthy(&self) -> bool {
+        self.status() == PostgresHealthStatus::Healthy
+    }
+
+    /// Get statistics snapshot
+    pub fn stats(&self) -> PostgresHealthStats {
+        PostgresHealthStats {
+            status: self.status(),
+            last_connected_ms: self.last_connected.load(Ordering::Acquire),
+            reconnection_attempts: self.reconnection_attempts.load(Ordering::Acquire),
+            total_reconnections: self.total_reconnections.load(Ordering::Acquire),
+        }
+    }
+}
+
+impl Default for PostgresHealth {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// PostgreSQL health statistics
+#[derive(Debug, Clone)]
+pub struct PostgresHealthStats {
+    pub status: PostgresHealthStatus,
+    pub last_connected_ms: i64,
+    pub reconnection_attempts: u32,
+    pub total_reconnections: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_postgres_health_status() {
+        let health = PostgresHealth::new();
+        assert_eq!(health.status(), PostgresHealthStatus::Reconnecting);
+        assert!(!health.is_healthy());
+
+        assert!(health.set_connected());
+        assert_eq!(health.status(), PostgresHealthStatus::Healthy);
+        assert!(health.is_healthy());
+        assert!(!health.set_connected());
+
+        health.set_reconnecting();
+        assert_eq!(health.status(), PostgresHealthStatus::Reconnecting);
+    }
+
+    #[test]
+    fn test_postgres_health_disabled_status() {
+        let health = PostgresHealth::new_with_enabled(false);
+        assert_eq!(health.status(), PostgresHealthStatus::Disabled);
+        assert!(!health.is_healthy());
+    }
+
+    #[test]
+    fn test_postgres_health_stats() {
+        let health = PostgresHealth::new();
+
+        health.set_reconnecting();
+        health.set_reconnecting();
+        health.set_connected();
+
+        let stats = health.stats();
+        assert_eq!(stats.status, PostgresHealthStatus::Healthy);
+        assert_eq!(stats.total_reconnections, 1);
+        assert_eq!(stats.reconnection_attempts, 0); // Reset on connect
+    }
+}
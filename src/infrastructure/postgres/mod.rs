@@ -2,6 +2,14 @@
 //!
 //! Provides connection pooling and health tracking for PostgreSQL backend.
 
+mod health;
 pub mod pool;
 
+pub use health::{PostgresHealth, PostgresHealthStats, PostgresHealthStatus};
 pub use pool::{PostgresPool, PostgresPoolError};
+
+/// Circuit breaker guarding PostgreSQL.
+///
+/// The breaker itself is backend-agnostic; PostgreSQL gets its own instance so a
+/// Redis outage never rejects database operations and vice versa.
+pub type PostgresCircuitBreaker = crate::redis::CircuitBreaker;
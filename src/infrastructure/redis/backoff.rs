@@ -15,6 +15,8 @@ pub struct BackoffConfig {
     pub multiplier: f64,
     /// Jitter factor (0.0 to 1.0)
     pub jitter_factor: f64,
+    /// Cap on the cumulative delay across all attempts (`None` retries forever)
+    pub max_total_wait_ms: Option<u64>,
 }
 
 impl Default for BackoffConfig {
@@ -24,6 +26,7 @@ impl Default for BackoffConfig {
             max_delay_ms: 30_000, // 30 seconds
             multiplier: 2.0,
             jitter_factor: 0.1, // 10% jitter
+            max_total_wait_ms: None,
         }
     }
 }
@@ -33,6 +36,10 @@ pub struct ExponentialBackoff {
     config: BackoffConfig,
     current_delay_ms: u64,
     attempt: u32,
+    /// Sum of all delays handed out since the last reset
+    total_wait_ms: u64,
+    /// Set once `max_total_wait_ms` has been used up
+    is_exhausted: bool,
 }
 
 impl ExponentialBackoff {
@@ -48,10 +55,16 @@ impl ExponentialBackoff {
             config,
             current_delay_ms: initial,
             attempt: 0,
+            total_wait_ms: 0,
+            is_exhausted: false,
         }
     }
 
-    /// Get the next delay duration
+    /// Get the next delay duration.
+    ///
+    /// With `max_total_wait_ms` set, a delay that would overrun the budget is clamped
+    /// to what remains and the backoff becomes exhausted; callers should stop retrying
+    /// once [`is_exhausted`](Self::is_exhausted) returns `true`.
     pub fn next_delay(&mut self) -> Duration {
         self.attempt += 1;
 
@@ -70,13 +83,35 @@ impl ExponentialBackoff {
 
         self.current_delay_ms = final_delay;
 
-        Duration::from_millis(final_delay)
+        let mut delay = final_delay;
+        if let Some(max_total) = self.config.max_total_wait_ms {
+            let remaining = max_total.saturating_sub(self.total_wait_ms);
+            if delay > remaining {
+                delay = remaining;
+                self.is_exhausted = true;
+            }
+        }
+        self.total_wait_ms += delay;
+
+        Duration::from_millis(delay)
     }
 
     /// Reset the backoff to initial state
     pub fn reset(&mut self) {
         self.current_delay_ms = self.config.initial_delay_ms;
         self.attempt = 0;
+        self.total_wait_ms = 0;
+        self.is_exhausted = false;
+    }
+
+    /// Whether the total wait budget has been used up
+    pub fn is_exhausted(&self) -> bool {
+        self.is_exhausted
+    }
+
+    /// Cumulative delay handed out since the last reset, in milliseconds
+    pub fn total_wait_ms(&self) -> u64 {
+        self.total_wait_ms
     }
 
     /// Get the current attempt number
@@ -102,6 +137,7 @@ mod tests {
             max_delay_ms: 10000,
             multiplier: 2.0,
             jitter_factor: 0.0, // No jitter for predictable testing
+            max_total_wait_ms: None,
         };
         let mut backoff = ExponentialBackoff::with_config(config);
 
@@ -121,6 +157,7 @@ mod tests {
             max_delay_ms: 5000,
             multiplier: 10.0,
             jitter_factor: 0.0,
+            max_total_wait_ms: None,
         };
         let mut backoff = ExponentialBackoff::with_config(config);
 
@@ -140,6 +177,7 @@ mod tests {
             max_delay_ms: 10000,
             multiplier: 2.0,
             jitter_factor: 0.0,
+            max_total_wait_ms: None,
         };
         let mut backoff = ExponentialBackoff::with_config(config);
 
@@ -150,4 +188,64 @@ mod tests {
         backoff.reset();
         assert_eq!(backoff.attempt(), 0);
     }
+
+    fn capped_backoff(max_total_wait_ms: u64) -> ExponentialBackoff {
+        ExponentialBackoff::with_config(BackoffConfig {
+            initial_delay_ms: 100,
+            max_delay_ms: 10_000,
+            multiplier: 2.0,
+            jitter_factor: 0.0,
+            max_total_wait_ms: Some(max_total_wait_ms),
+        })
+    }
+
+    /// Drain the backoff until exhausted, returning every delay in milliseconds
+    fn delays_until_exhausted(backoff: &mut ExponentialBackoff) -> Vec<u64> {
+        let mut delays = Vec::new();
+        while !backoff.is_exhausted() {
+            delays.push(backoff.next_delay().as_millis() as u64);
+            assert!(delays.len() < 100, "backoff never exhausted");
+        }
+        delays
+    }
+
+    #[test]
+    fn test_backoff_exhausts_at_total_wait_cap() {
+        // Delays: 200, 400, 800, 1600, ...
+        let mut backoff = capped_backoff(1000);
+        assert_eq!(delays_until_exhausted(&mut backoff), vec![200, 400, 400]);
+        assert_eq!(backoff.total_wait_ms(), 1000);
+
+        let mut backoff = capped_backoff(600);
+        assert_eq!(delays_until_exhausted(&mut backoff), vec![200, 400, 0]);
+        assert_eq!(backoff.total_wait_ms(), 600);
+
+        let mut backoff = capped_backoff(0);
+        assert_eq!(delays_until_exhausted(&mut backoff), vec![0]);
+
+        let mut backoff = capped_backoff(60_000);
+        let delays = delays_until_exhausted(&mut backoff);
+        assert_eq!(delays.iter().sum::<u64>(), 60_000);
+        assert!(delays.iter().all(|&d| d <= 10_000));
+    }
+
+    #[test]
+    fn test_backoff_without_cap_never_exhausts() {
+        let mut backoff = ExponentialBackoff::new();
+        for _ in 0..50 {
+            backoff.next_delay();
+        }
+        assert!(!backoff.is_exhausted());
+    }
+
+    #[test]
+    fn test_backoff_reset_restores_wait_budget() {
+        let mut backoff = capped_backoff(300);
+        delays_until_exhausted(&mut backoff);
+
+        backoff.reset();
+        assert!(!backoff.is_exhausted());
+        assert_eq!(backoff.total_wait_ms(), 0);
+        assert_eq!(backoff.next_delay(), Duration::from_millis(200));
+    }
 }
@@ -0,0 +1,145 @@
+//! Bulkhead circuit breaker: separate breakers for Redis reads and writes
+//!
+//! A burst of failing reads (e.g. HGETALL on an oversized ACK hash) should not stop
+//! writes such as session registration, and vice versa. Each operation type gets its
+//! own [`CircuitBreaker`] with the same configuration, so one can trip while the
+//! other keeps serving.
+
+use super::{CircuitBreaker, CircuitBreakerConfig, CircuitState};
+
+/// Kind of Redis operation, selecting which breaker guards it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperationType {
+    /// Commands that only read data
+    Read,
+    /// Commands that modify data (including scripts and publishes)
+    Write,
+}
+
+impl OperationType {
+    /// Metric label value
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OperationType::Read => "read",
+            OperationType::Write => "write",
+        }
+    }
+}
+
+/// Independent circuit breakers for read and write operations
+#[derive(Default)]
+pub struct BulkheadCircuitBreaker {
+    read: CircuitBreaker,
+    write: CircuitBreaker,
+}
+
+impl BulkheadCircuitBreaker {
+    /// Create read and write breakers with default configuration
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create read and write breakers sharing `config`
+    pub fn with_config(config: CircuitBreakerConfig) -> Self {
+        Self {
+            read: CircuitBreaker::with_config(config.clone()),
+            write: CircuitBreaker::with_config(config),
+        }
+    }
+
+    /// Breaker guarding `operation`
+    pub fn breaker(&self, operation: OperationType) -> &CircuitBreaker {
+        match operation {
+            OperationType::Read => &self.read,
+            OperationType::Write => &self.write,
+        }
+    }
+
+    /// Whether a read should be allowed
+    pub fn allow_read(&self) -> bool {
+        self.read.allow_request()
+    }
+
+    /// Whether a write should be allowed
+    pub fn allow_write(&self) -> bool {
+        self.write.allow_request()
+    }
+
+    /// Record a successful read
+    pub fn record_read_success(&self) {
+        self.read.record_success();
+    }
+
+    /// Record a failed read
+    pub fn record_read_failure(&self) {
+        self.read.record_failure();
+    }
+
+    /// Record a successful write
+    pub fn record_write_success(&self) {
+        self.write.record_success();
+    }
+
+    /// Record a failed write
+    pub fn record_write_failure(&self) {
+        self.write.record_failure();
+    }
+
+    /// Current state of the breaker guarding `operation`
+    pub fn state(&self, operation: OperationType) -> CircuitState {
+        self.breaker(operation).state()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bulkhead() -> BulkheadCircuitBreaker {
+        BulkheadCircuitBreaker::with_config(CircuitBreakerConfig {
+            failure_threshold: 3,
+            success_threshold: 1,
+            reset_timeout_ms: 10_000,
+        })
+    }
+
+    #[test]
+    fn test_read_failures_do_not_trip_writes() {
+        let cb = bulkhead();
+        for _ in 0..3 {
+            cb.record_read_failure();
+        }
+
+        assert_eq!(cb.state(OperationType::Read), CircuitState::Open);
+        assert!(!cb.allow_read());
+        assert_eq!(cb.state(OperationType::Write), CircuitState::Closed);
+        assert!(cb.allow_write());
+    }
+
+    #[test]
+    fn test_write_failures_do_not_trip_reads() {
+        let cb = bulkhead();
+        for _ in 0..3 {
+            cb.record_write_failure();
+        }
+
+        assert!(!cb.allow_write());
+        assert!(cb.allow_read());
+
+        // Successes on the other side don't reset the tripped breaker
+        cb.record_read_success();
+        assert_eq!(cb.state(OperationType::Write), CircuitState::Open);
+    }
+
+    #[test]
+    fn test_success_resets_only_its_own_failures() {
+        let cb = bulkhead();
+        cb.record_read_failure();
+        cb.record_read_failure();
+        cb.record_write_failure();
+        cb.record_write_success();
+
+        assert_eq!(cb.breaker(OperationType::Read).stats().failure_count, 2);
+        assert_eq!(cb.breaker(OperationType::Write).stats().failure_count, 0);
+    }
+}
@@ -1,6 +1,6 @@
 //! Circuit breaker pattern implementation for Redis connections
 
-use std::sync::atomic::{AtomicI64, AtomicU32, AtomicU8, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicI64, AtomicU32, AtomicU8, Ordering};
 
 use super::current_time_ms;
 
@@ -12,7 +12,7 @@ pub enum CircuitState {
     Closed = 0,
     /// Circuit is open, requests are rejected
     Open = 1,
-    /// Circuit is half-open, allowing test requests
+    /// Circuit is half-open, allowing one test request at a time
     HalfOpen = 2,
 }
 
@@ -61,6 +61,10 @@ pub struct CircuitBreaker {
     success_count: AtomicU32,
     /// Timestamp of last state change (ms since epoch)
     last_state_change: AtomicI64,
+    /// Whether a half-open test request is outstanding
+    probe_in_progress: AtomicBool,
+    /// When the outstanding test request started (ms since epoch)
+    probe_started_at: AtomicI64,
     /// Configuration
     config: CircuitBreakerConfig,
 }
@@ -78,6 +82,8 @@ impl CircuitBreaker {
             failure_count: AtomicU32::new(0),
             success_count: AtomicU32::new(0),
             last_state_change: AtomicI64::new(current_time_ms()),
+            probe_in_progress: AtomicBool::new(false),
+            probe_started_at: AtomicI64::new(current_time_ms()),
             config,
         }
     }
@@ -88,15 +94,46 @@ impl CircuitBreaker {
         CircuitState::from(self.state.load(Ordering::Acquire))
     }
 
-    /// Check if requests should be allowed
+    /// Check if requests should be allowed.
+    ///
+    /// In half-open state only one caller at a time is let through as a test request;
+    /// everyone else is rejected until its outcome is recorded.
     pub fn allow_request(&self) -> bool {
         match self.state() {
             CircuitState::Closed => true,
             CircuitState::Open => false,
-            CircuitState::HalfOpen => true, // Allow test requests
+            CircuitState::HalfOpen => self.try_start_probe(),
         }
     }
 
+    /// Claim the half-open test request slot
+    fn try_start_probe(&self) -> bool {
+        let now = current_time_ms();
+        if self
+            .probe_in_progress
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .is_ok()
+        {
+            self.probe_started_at.store(now, Ordering::Release);
+            return true;
+        }
+
+        // A test request whose outcome is never recorded (e.g. its future was dropped)
+        // must not keep the circuit half-open forever; take over after the reset timeout
+        let started = self.probe_started_at.load(Ordering::Acquire);
+        now - started >= self.config.reset_timeout_ms as i64
+            && self
+                .probe_started_at
+                .compare_exchange(started, now, Ordering::AcqRel, Ordering::Acquire)
+                .is_ok()
+    }
+
+    /// Free the half-open test request slot
+    fn end_probe(&self) {
+        self.probe_started_at.store(current_time_ms(), Ordering::Release);
+        self.probe_in_progress.store(false, Ordering::Release);
+    }
+
     /// Record a successful operation
     pub fn record_success(&self) {
         let state = CircuitState::from(self.state.load(Ordering::Acquire));
@@ -107,6 +144,7 @@ impl CircuitBreaker {
                 self.failure_count.store(0, Ordering::Release);
             }
             CircuitState::HalfOpen => {
+                self.end_probe();
                 let success_count = self.success_count.fetch_add(1, Ordering::AcqRel) + 1;
                 if success_count >= self.config.success_threshold {
                     self.transition_to(CircuitState::Closed);
@@ -136,6 +174,7 @@ impl CircuitBreaker {
             }
             CircuitState::HalfOpen => {
                 // Any failure in half-open state reopens the circuit
+                self.end_probe();
                 self.transition_to(CircuitState::Open);
                 tracing::warn!("Circuit breaker reopened after failure in half-open state");
             }
@@ -168,6 +207,7 @@ impl CircuitBreaker {
                     .is_ok()
                 {
                     self.success_count.store(0, Ordering::Release);
+                    self.end_probe();
                     self.last_state_change
                         .store(current_time_ms(), Ordering::Release);
                     tracing::info!("Circuit breaker transitioning to half-open state");
@@ -192,6 +232,7 @@ impl CircuitBreaker {
             }
             CircuitState::HalfOpen => {
                 self.success_count.store(0, Ordering::Release);
+                self.end_probe();
             }
         }
     }
@@ -331,4 +372,70 @@ mod tests {
         let state_raw = CircuitState::from(cb.state.load(std::sync::atomic::Ordering::Acquire));
         assert_eq!(state_raw, CircuitState::Open);
     }
+
+    fn half_open_breaker(reset_timeout_ms: u64) -> CircuitBreaker {
+        let cb = CircuitBreaker::with_config(CircuitBreakerConfig {
+            failure_threshold: 1,
+            success_threshold: 2,
+            reset_timeout_ms,
+        });
+        cb.transition_to(CircuitState::HalfOpen);
+        cb
+    }
+
+    #[test]
+    fn test_half_open_allows_single_concurrent_probe() {
+        let cb = std::sync::Arc::new(half_open_breaker(10_000));
+
+        let barrier = std::sync::Arc::new(std::sync::Barrier::new(100));
+        let handles: Vec<_> = (0..100)
+            .map(|_| {
+                let cb = cb.clone();
+                let barrier = barrier.clone();
+                std::thread::spawn(move || {
+                    barrier.wait();
+                    cb.allow_request()
+                })
+            })
+            .collect();
+
+        let allowed = handles
+            .into_iter()
+            .map(|h| h.join().unwrap())
+            .filter(|&allowed| allowed)
+            .count();
+        assert_eq!(allowed, 1);
+    }
+
+    #[test]
+    fn test_half_open_probe_slot_freed_by_outcome() {
+        let cb = half_open_breaker(10_000);
+
+        assert!(cb.allow_request());
+        assert!(!cb.allow_request());
+
+        // A successful probe below the success threshold lets the next probe through
+        cb.record_success();
+        assert_eq!(cb.state(), CircuitState::HalfOpen);
+        assert!(cb.allow_request());
+        assert!(!cb.allow_request());
+
+        cb.record_success();
+        assert_eq!(cb.state(), CircuitState::Closed);
+        assert!(cb.allow_request());
+        assert!(cb.allow_request());
+    }
+
+    #[test]
+    fn test_unrecorded_half_open_probe_expires() {
+        let cb = half_open_breaker(20);
+
+        assert!(cb.allow_request());
+        assert!(!cb.allow_request());
+
+        // The first probe never reports back
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(cb.allow_request());
+        assert!(!cb.allow_request());
+    }
 }
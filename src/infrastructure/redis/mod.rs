@@ -5,16 +5,19 @@
 //! # Modules
 //!
 //! - `CircuitBreaker`: Prevents cascading failures when Redis is unavailable
+//! - `BulkheadCircuitBreaker`: Separate breakers for read and write operations
 //! - `ExponentialBackoff`: Provides backoff delays for reconnection attempts
 //! - `RedisHealth`: Tracks Redis connection health status
 //! - `pool`: Connection pool for data persistence operations
 
 mod backoff;
+mod bulkhead;
 mod circuit_breaker;
 mod health;
 pub mod pool;
 
 pub use backoff::{BackoffConfig, ExponentialBackoff};
+pub use bulkhead::{BulkheadCircuitBreaker, OperationType};
 pub use circuit_breaker::{CircuitBreaker, CircuitBreakerConfig, CircuitBreakerStats, CircuitState};
 pub use health::{RedisHealth, RedisHealthStats, RedisHealthStatus};
 
@@ -3,15 +3,22 @@
 //! Provides a managed Redis connection pool with circuit breaker
 //! integration for resilient data operations.
 
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use redis::aio::MultiplexedConnection;
 use redis::{AsyncCommands, Client, RedisError, RedisResult};
 use tokio::sync::RwLock;
 
 use crate::config::RedisConfig;
+use crate::metrics::{REDIS_POOL_CHECKOUT_WAIT_SECONDS, REDIS_POOL_OVERFLOW_TOTAL};
 
-use super::{CircuitBreaker, CircuitState, RedisHealth};
+use super::{BulkheadCircuitBreaker, CircuitState, OperationType, RedisHealth};
+
+/// Default timeout for a single pool operation, including connecting
+const DEFAULT_COMMAND_TIMEOUT_MS: u64 = 5000;
 
 /// Error type for Redis pool operations.
 #[derive(Debug, thiserror::Error)]
@@ -27,6 +34,28 @@ pub enum PoolError {
     /// Connection not available
     #[error("Connection not available: {0}")]
     ConnectionUnavailable(String),
+
+    /// Operation did not complete within the command timeout
+    #[error("Redis command timed out after {0}ms")]
+    Timeout(u64),
+}
+
+/// Connection utilization snapshot of a [`RedisPool`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolStats {
+    /// Operations currently holding the connection
+    pub active_connections: usize,
+    /// Established connections with no operation in flight
+    pub idle_connections: usize,
+}
+
+/// Releases a checked out connection slot when the operation finishes or is dropped.
+struct Checkout<'a>(&'a AtomicUsize);
+
+impl Drop for Checkout<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
 }
 
 /// Redis connection pool for data operations.
@@ -44,21 +73,27 @@ pub struct RedisPool {
     /// Multiplexed connection (shared across tasks)
     connection: RwLock<Option<MultiplexedConnection>>,
 
-    /// Circuit breaker for fault tolerance
-    circuit_breaker: Arc<CircuitBreaker>,
+    /// Separate circuit breakers for reads and writes
+    circuit_breaker: Arc<BulkheadCircuitBreaker>,
 
     /// Health tracker
     health: Arc<RedisHealth>,
 
     /// Configuration
     config: RedisConfig,
+
+    /// Upper bound for a single operation, including connecting
+    command_timeout: Duration,
+
+    /// Operations currently holding the connection
+    active: AtomicUsize,
 }
 
 impl RedisPool {
     /// Create a new Redis pool.
     pub fn new(
         config: RedisConfig,
-        circuit_breaker: Arc<CircuitBreaker>,
+        circuit_breaker: Arc<BulkheadCircuitBreaker>,
         health: Arc<RedisHealth>,
     ) -> Result<Self, PoolError> {
         let client = Client::open(config.url.as_str())?;
@@ -69,20 +104,22 @@ impl RedisPool {
             circuit_breaker,
             health,
             config,
+            command_timeout: Duration::from_millis(DEFAULT_COMMAND_TIMEOUT_MS),
+            active: AtomicUsize::new(0),
         })
     }
 
-    /// Get a connection from the pool.
-    ///
-    /// This will establish a new connection if none exists.
-    /// Returns an error if the circuit breaker is open.
-    pub async fn get_connection(&self) -> Result<MultiplexedConnection, PoolError> {
-        // Check circuit breaker first
-        if !self.circuit_breaker.allow_request() {
-            self.health.set_circuit_open();
-            return Err(PoolError::CircuitOpen);
-        }
+    /// Set the timeout applied to every operation (default 5s).
+    pub fn with_command_timeout(mut self, timeout: Duration) -> Self {
+        self.command_timeout = timeout;
+        self
+    }
 
+    /// Get the shared connection, establishing it if none exists.
+    ///
+    /// Does not consult the circuit breaker; run it inside
+    /// [`execute_with_circuit_breaker`](Self::execute_with_circuit_breaker).
+    async fn connection(&self) -> RedisResult<MultiplexedConnection> {
         // Try to get existing connection
         {
             let conn = self.connection.read().await;
@@ -96,7 +133,7 @@ impl RedisPool {
     }
 
     /// Establish a new connection.
-    async fn connect(&self) -> Result<MultiplexedConnection, PoolError> {
+    async fn connect(&self) -> RedisResult<MultiplexedConnection> {
         let mut conn_guard = self.connection.write().await;
 
         // Double-check in case another task connected while we waited
@@ -109,56 +146,125 @@ impl RedisPool {
         match self.client.get_multiplexed_tokio_connection().await {
             Ok(conn) => {
                 *conn_guard = Some(conn.clone());
-                self.circuit_breaker.record_success();
                 self.health.set_connected();
                 tracing::info!("Redis pool connection established");
                 Ok(conn)
             }
             Err(e) => {
-                self.circuit_breaker.record_failure();
                 tracing::error!(error = %e, "Failed to connect to Redis");
-                Err(PoolError::Redis(e))
+                Err(e)
             }
         }
     }
 
-    /// Execute a Redis command with circuit breaker protection.
+    /// Get the shared connection for one operation, recording how long it took.
+    async fn checkout(&self) -> RedisResult<(MultiplexedConnection, Checkout<'_>)> {
+        let started = Instant::now();
+        let conn = self.connection().await?;
+        REDIS_POOL_CHECKOUT_WAIT_SECONDS.observe(started.elapsed().as_secs_f64());
+
+        self.active.fetch_add(1, Ordering::Relaxed);
+        Ok((conn, Checkout(&self.active)))
+    }
+
+    /// Drop the shared connection so the next operation reconnects.
+    async fn reset_connection(&self) {
+        *self.connection.write().await = None;
+    }
+
+    /// Run a Redis operation under the circuit breaker for its operation type.
     ///
-    /// This is a helper that handles connection management and
-    /// circuit breaker recording automatically.
-    pub async fn execute<F, T, Fut>(&self, f: F) -> Result<T, PoolError>
+    /// Rejects the operation while that breaker is open, bounds it by the command
+    /// timeout and records the outcome on the same breaker. Dropped connections and
+    /// timeouts reset the shared connection so the next operation reconnects.
+    pub async fn execute_with_circuit_breaker<F, R>(
+        &self,
+        operation: OperationType,
+        f: F,
+    ) -> Result<R, PoolError>
     where
-        F: FnOnce(MultiplexedConnection) -> Fut,
-        Fut: std::future::Future<Output = RedisResult<T>>,
+        F: Future<Output = RedisResult<R>>,
     {
-        let conn = self.get_connection().await?;
+        let circuit_breaker = self.circuit_breaker.breaker(operation);
+        if !circuit_breaker.allow_request() {
+            self.health.set_circuit_open();
+            return Err(PoolError::CircuitOpen);
+        }
 
-        match f(conn).await {
-            Ok(result) => {
-                self.circuit_breaker.record_success();
+        match tokio::time::timeout(self.command_timeout, f).await {
+            Ok(Ok(result)) => {
+                circuit_breaker.record_success();
                 Ok(result)
             }
-            Err(e) => {
-                // Check if this is a connection error that should trigger reconnect
+            Ok(Err(e)) => {
                 if e.is_connection_dropped() || e.is_io_error() {
-                    // Clear the connection so next call will reconnect
-                    let mut conn_guard = self.connection.write().await;
-                    *conn_guard = None;
+                    self.reset_connection().await;
                 }
-                self.circuit_breaker.record_failure();
+                circuit_breaker.record_failure();
                 Err(PoolError::Redis(e))
             }
+            Err(_) => {
+                // Don't wait behind a connect that is still in progress; it replaces
+                // the connection anyway
+                if let Ok(mut conn) = self.connection.try_write() {
+                    *conn = None;
+                }
+                circuit_breaker.record_failure();
+                Err(PoolError::Timeout(self.command_timeout.as_millis() as u64))
+            }
+        }
+    }
+
+    /// Run a Redis operation on the shared connection under the circuit breaker for
+    /// its operation type.
+    ///
+    /// Connecting counts towards the same timeout and outcome as the operation. A
+    /// timeout before the connection was obtained is counted as a pool overflow.
+    pub async fn execute<F, T, Fut>(&self, operation: OperationType, f: F) -> Result<T, PoolError>
+    where
+        F: FnOnce(MultiplexedConnection) -> Fut,
+        Fut: Future<Output = RedisResult<T>>,
+    {
+        let checked_out = AtomicBool::new(false);
+        let result = self
+            .execute_with_circuit_breaker(operation, async {
+                let (conn, _checkout) = self.checkout().await?;
+                checked_out.store(true, Ordering::Relaxed);
+                f(conn).await
+            })
+            .await;
+
+        if matches!(result, Err(PoolError::Timeout(_))) && !checked_out.load(Ordering::Relaxed) {
+            REDIS_POOL_OVERFLOW_TOTAL.inc();
         }
+        result
     }
 
-    /// Check if the pool is healthy (circuit breaker closed and connected).
+    /// Current connection utilization.
+    pub fn stats(&self) -> PoolStats {
+        let active = self.active.load(Ordering::Relaxed);
+        // The write lock is only held while (re)connecting, when nothing is idle
+        let connected = self
+            .connection
+            .try_read()
+            .map(|conn| conn.is_some())
+            .unwrap_or(false);
+        PoolStats {
+            active_connections: active,
+            idle_connections: usize::from(connected && active == 0),
+        }
+    }
+
+    /// Check if the pool is healthy (both circuit breakers closed and connected).
     pub fn is_healthy(&self) -> bool {
-        self.health.is_healthy() && self.circuit_breaker.state() == CircuitState::Closed
+        self.health.is_healthy()
+            && self.circuit_breaker.state(OperationType::Read) == CircuitState::Closed
+            && self.circuit_breaker.state(OperationType::Write) == CircuitState::Closed
     }
 
-    /// Get the circuit breaker state.
-    pub fn circuit_state(&self) -> CircuitState {
-        self.circuit_breaker.state()
+    /// Get the state of the circuit breaker guarding `operation`.
+    pub fn circuit_state(&self, operation: OperationType) -> CircuitState {
+        self.circuit_breaker.state(operation)
     }
 
     /// Get the Redis URL (for debugging).
@@ -168,8 +274,10 @@ impl RedisPool {
 
     /// Ping Redis to check connectivity.
     pub async fn ping(&self) -> Result<(), PoolError> {
-        let mut conn = self.get_connection().await?;
-        let _: String = redis::cmd("PING").query_async(&mut conn).await?;
+        self.execute(OperationType::Read, |mut conn| async move {
+            redis::cmd("PING").query_async::<String>(&mut conn).await
+        })
+        .await?;
         Ok(())
     }
 }
@@ -178,21 +286,25 @@ impl RedisPool {
 ///
 /// Provides typed helper methods for common Redis commands used by
 /// the persistence backends.
+#[cfg_attr(test, mockall::automock)]
 #[async_trait::async_trait]
 pub trait RedisPoolExt {
     // Stream operations (for queue)
 
     /// Add entry to a stream with MAXLEN trimming.
-    async fn xadd_maxlen(
+    async fn xadd_maxlen<'a>(
         &self,
         key: &str,
         maxlen: usize,
-        fields: &[(&str, &str)],
+        fields: &[(&'a str, &'a str)],
     ) -> Result<String, PoolError>;
 
     /// Read all entries from a stream.
     async fn xrange_all(&self, key: &str) -> Result<Vec<(String, Vec<(String, String)>)>, PoolError>;
 
+    /// Delete entries from a stream by entry ID. Returns the number deleted.
+    async fn xdel(&self, key: &str, ids: &[String]) -> Result<usize, PoolError>;
+
     /// Delete a stream key.
     async fn del(&self, key: &str) -> Result<(), PoolError>;
 
@@ -202,7 +314,7 @@ pub trait RedisPoolExt {
     // Hash operations (for ACK tracking)
 
     /// Set multiple hash fields.
-    async fn hset_multiple(&self, key: &str, fields: &[(&str, &str)]) -> Result<(), PoolError>;
+    async fn hset_multiple<'a>(&self, key: &str, fields: &[(&'a str, &'a str)]) -> Result<(), PoolError>;
 
     /// Get a hash field.
     async fn hget(&self, key: &str, field: &str) -> Result<Option<String>, PoolError>;
@@ -210,6 +322,9 @@ pub trait RedisPoolExt {
     /// Get all hash fields and values.
     async fn hgetall(&self, key: &str) -> Result<Vec<(String, String)>, PoolError>;
 
+    /// Collect all hash fields and values using HSCAN (non-blocking alternative to HGETALL).
+    async fn hscan_all(&self, key: &str) -> Result<Vec<(String, String)>, PoolError>;
+
     /// Delete a hash key.
     async fn hdel(&self, key: &str) -> Result<(), PoolError>;
 
@@ -227,30 +342,79 @@ pub trait RedisPoolExt {
     /// Get members with scores less than max.
     async fn zrangebyscore(&self, key: &str, min: f64, max: f64) -> Result<Vec<String>, PoolError>;
 
+    /// Get at most `count` members with scores between min and max, skipping the
+    /// first `offset`.
+    async fn zrangebyscore_limit(
+        &self,
+        key: &str,
+        min: f64,
+        max: f64,
+        offset: usize,
+        count: usize,
+    ) -> Result<Vec<String>, PoolError>;
+
+    /// Like `zrangebyscore_limit`, returning each member with its score.
+    async fn zrangebyscore_limit_withscores(
+        &self,
+        key: &str,
+        min: f64,
+        max: f64,
+        offset: usize,
+        count: usize,
+    ) -> Result<Vec<(String, f64)>, PoolError>;
+
     /// Get cardinality of a sorted set (O(1)).
     async fn zcard(&self, key: &str) -> Result<usize, PoolError>;
 
+    /// Get the score of a sorted set member, if present.
+    async fn zscore(&self, key: &str, member: &str) -> Result<Option<f64>, PoolError>;
+
     // Stream operations
 
     /// Get the length of a Redis stream (O(1), much faster than XRANGE for counting).
     async fn xlen(&self, key: &str) -> Result<usize, PoolError>;
 
+    // List operations (for message quarantine)
+
+    /// Push a value to the head of a list and trim the list to `maxlen` entries (atomic).
+    async fn lpush_trim(&self, key: &str, value: &str, maxlen: usize) -> Result<(), PoolError>;
+
+    /// Get list entries between `start` and `stop` (inclusive, negative indexes count from the tail).
+    async fn lrange(&self, key: &str, start: isize, stop: isize) -> Result<Vec<String>, PoolError>;
+
+    /// Get the list entry at `index`.
+    async fn lindex(&self, key: &str, index: isize) -> Result<Option<String>, PoolError>;
+
+    /// Remove up to `count` occurrences of `value` from a list, returning the number removed.
+    async fn lrem(&self, key: &str, count: isize, value: &str) -> Result<usize, PoolError>;
+
     // Key operations
 
     /// Set key expiration.
     async fn expire(&self, key: &str, seconds: i64) -> Result<(), PoolError>;
+
+    /// Collect all keys matching a glob pattern using SCAN (non-blocking alternative to KEYS).
+    async fn scan_keys(&self, pattern: &str) -> Result<Vec<String>, PoolError>;
+
+    // Scripting
+
+    /// Run a Lua script atomically (EVALSHA, falling back to EVAL on cache miss).
+    async fn eval_script<'a>(
+        &self,
+        script: &redis::Script,
+        keys: &[&'a str],
+        args: &[&'a str],
+    ) -> Result<redis::Value, PoolError>;
 }
 
 #[async_trait::async_trait]
 impl RedisPoolExt for RedisPool {
-    async fn xadd_maxlen(
+    async fn xadd_maxlen<'a>(
         &self,
         key: &str,
         maxlen: usize,
-        fields: &[(&str, &str)],
+        fields: &[(&'a str, &'a str)],
     ) -> Result<String, PoolError> {
-        let mut conn = self.get_connection().await?;
-
         // Build XADD command: XADD key MAXLEN ~ maxlen * field value ...
         let mut cmd = redis::cmd("XADD");
         cmd.arg(key)
@@ -263,112 +427,55 @@ impl RedisPoolExt for RedisPool {
             cmd.arg(*field).arg(*value);
         }
 
-        match cmd.query_async(&mut conn).await {
-            Ok(id) => {
-                self.circuit_breaker.record_success();
-                Ok(id)
-            }
-            Err(e) => {
-                self.circuit_breaker.record_failure();
-                Err(PoolError::Redis(e))
-            }
-        }
+        self.execute(OperationType::Write, |mut conn| async move { cmd.query_async(&mut conn).await })
+            .await
     }
 
     async fn xrange_all(&self, key: &str) -> Result<Vec<(String, Vec<(String, String)>)>, PoolError> {
-        let mut conn = self.get_connection().await?;
+        self.execute(OperationType::Read, |mut conn| async move {
+            redis::cmd("XRANGE")
+                .arg(key)
+                .arg("-")
+                .arg("+")
+                .query_async(&mut conn)
+                .await
+        })
+        .await
+    }
 
-        match redis::cmd("XRANGE")
-            .arg(key)
-            .arg("-")
-            .arg("+")
-            .query_async(&mut conn)
-            .await
-        {
-            Ok(result) => {
-                self.circuit_breaker.record_success();
-                Ok(result)
-            }
-            Err(e) => {
-                self.circuit_breaker.record_failure();
-                Err(PoolError::Redis(e))
-            }
+    async fn xdel(&self, key: &str, ids: &[String]) -> Result<usize, PoolError> {
+        if ids.is_empty() {
+            return Ok(0);
         }
+        self.execute(OperationType::Write, |mut conn| async move {
+            redis::cmd("XDEL").arg(key).arg(ids).query_async(&mut conn).await
+        })
+        .await
     }
 
     async fn del(&self, key: &str) -> Result<(), PoolError> {
-        let mut conn = self.get_connection().await?;
-
-        match conn.del::<_, ()>(key).await {
-            Ok(_) => {
-                self.circuit_breaker.record_success();
-                Ok(())
-            }
-            Err(e) => {
-                self.circuit_breaker.record_failure();
-                Err(PoolError::Redis(e))
-            }
-        }
+        self.execute(OperationType::Write, |mut conn| async move { conn.del(key).await })
+            .await
     }
 
     async fn exists(&self, key: &str) -> Result<bool, PoolError> {
-        let mut conn = self.get_connection().await?;
-
-        match conn.exists(key).await {
-            Ok(result) => {
-                self.circuit_breaker.record_success();
-                Ok(result)
-            }
-            Err(e) => {
-                self.circuit_breaker.record_failure();
-                Err(PoolError::Redis(e))
-            }
-        }
+        self.execute(OperationType::Read, |mut conn| async move { conn.exists(key).await })
+            .await
     }
 
-    async fn hset_multiple(&self, key: &str, fields: &[(&str, &str)]) -> Result<(), PoolError> {
-        let mut conn = self.get_connection().await?;
-
-        match conn.hset_multiple::<_, _, _, ()>(key, fields).await {
-            Ok(_) => {
-                self.circuit_breaker.record_success();
-                Ok(())
-            }
-            Err(e) => {
-                self.circuit_breaker.record_failure();
-                Err(PoolError::Redis(e))
-            }
-        }
+    async fn hset_multiple<'a>(&self, key: &str, fields: &[(&'a str, &'a str)]) -> Result<(), PoolError> {
+        self.execute(OperationType::Write, |mut conn| async move { conn.hset_multiple(key, fields).await })
+            .await
     }
 
     async fn hget(&self, key: &str, field: &str) -> Result<Option<String>, PoolError> {
-        let mut conn = self.get_connection().await?;
-
-        match conn.hget(key, field).await {
-            Ok(result) => {
-                self.circuit_breaker.record_success();
-                Ok(result)
-            }
-            Err(e) => {
-                self.circuit_breaker.record_failure();
-                Err(PoolError::Redis(e))
-            }
-        }
+        self.execute(OperationType::Read, |mut conn| async move { conn.hget(key, field).await })
+            .await
     }
 
     async fn hgetall(&self, key: &str) -> Result<Vec<(String, String)>, PoolError> {
-        let mut conn = self.get_connection().await?;
-
-        match conn.hgetall(key).await {
-            Ok(result) => {
-                self.circuit_breaker.record_success();
-                Ok(result)
-            }
-            Err(e) => {
-                self.circuit_breaker.record_failure();
-                Err(PoolError::Redis(e))
-            }
-        }
+        self.execute(OperationType::Read, |mut conn| async move { conn.hgetall(key).await })
+            .await
     }
 
     async fn hdel(&self, key: &str) -> Result<(), PoolError> {
@@ -376,112 +483,174 @@ impl RedisPoolExt for RedisPool {
     }
 
     async fn hincrby(&self, key: &str, field: &str, increment: i64) -> Result<i64, PoolError> {
-        let mut conn = self.get_connection().await?;
-
-        match conn.hincr(key, field, increment).await {
-            Ok(result) => {
-                self.circuit_breaker.record_success();
-                Ok(result)
-            }
-            Err(e) => {
-                self.circuit_breaker.record_failure();
-                Err(PoolError::Redis(e))
-            }
-        }
+        self.execute(OperationType::Write, |mut conn| async move { conn.hincr(key, field, increment).await })
+            .await
     }
 
     async fn zadd(&self, key: &str, score: f64, member: &str) -> Result<(), PoolError> {
-        let mut conn = self.get_connection().await?;
-
-        match conn.zadd::<_, _, _, ()>(key, member, score).await {
-            Ok(_) => {
-                self.circuit_breaker.record_success();
-                Ok(())
-            }
-            Err(e) => {
-                self.circuit_breaker.record_failure();
-                Err(PoolError::Redis(e))
-            }
-        }
+        self.execute(OperationType::Write, |mut conn| async move { conn.zadd(key, member, score).await })
+            .await
     }
 
     async fn zrem(&self, key: &str, member: &str) -> Result<(), PoolError> {
-        let mut conn = self.get_connection().await?;
-
-        match conn.zrem::<_, _, ()>(key, member).await {
-            Ok(_) => {
-                self.circuit_breaker.record_success();
-                Ok(())
-            }
-            Err(e) => {
-                self.circuit_breaker.record_failure();
-                Err(PoolError::Redis(e))
-            }
-        }
+        self.execute(OperationType::Write, |mut conn| async move { conn.zrem(key, member).await })
+            .await
     }
 
     async fn zrangebyscore(&self, key: &str, min: f64, max: f64) -> Result<Vec<String>, PoolError> {
-        let mut conn = self.get_connection().await?;
+        self.execute(OperationType::Read, |mut conn| async move { conn.zrangebyscore(key, min, max).await })
+            .await
+    }
 
-        match conn.zrangebyscore(key, min, max).await {
-            Ok(result) => {
-                self.circuit_breaker.record_success();
-                Ok(result)
-            }
-            Err(e) => {
-                self.circuit_breaker.record_failure();
-                Err(PoolError::Redis(e))
-            }
-        }
+    async fn zrangebyscore_limit(
+        &self,
+        key: &str,
+        min: f64,
+        max: f64,
+        offset: usize,
+        count: usize,
+    ) -> Result<Vec<String>, PoolError> {
+        let (offset, count) = (offset as isize, count as isize);
+        self.execute(OperationType::Read, |mut conn| async move {
+            conn.zrangebyscore_limit(key, min, max, offset, count).await
+        })
+        .await
+    }
+
+    async fn zrangebyscore_limit_withscores(
+        &self,
+        key: &str,
+        min: f64,
+        max: f64,
+        offset: usize,
+        count: usize,
+    ) -> Result<Vec<(String, f64)>, PoolError> {
+        let (offset, count) = (offset as isize, count as isize);
+        self.execute(OperationType::Read, |mut conn| async move {
+            conn.zrangebyscore_limit_withscores(key, min, max, offset, count).await
+        })
+        .await
     }
 
     async fn zcard(&self, key: &str) -> Result<usize, PoolError> {
-        let mut conn = self.get_connection().await?;
+        self.execute(OperationType::Read, |mut conn| async move { conn.zcard(key).await })
+            .await
+    }
 
-        match conn.zcard(key).await {
-            Ok(result) => {
-                self.circuit_breaker.record_success();
-                Ok(result)
-            }
-            Err(e) => {
-                self.circuit_breaker.record_failure();
-                Err(PoolError::Redis(e))
-            }
-        }
+    async fn zscore(&self, key: &str, member: &str) -> Result<Option<f64>, PoolError> {
+        self.execute(OperationType::Read, |mut conn| async move { conn.zscore(key, member).await })
+            .await
     }
 
     async fn xlen(&self, key: &str) -> Result<usize, PoolError> {
-        let mut conn = self.get_connection().await?;
+        self.execute(OperationType::Read, |mut conn| async move {
+            redis::cmd("XLEN").arg(key).query_async(&mut conn).await
+        })
+        .await
+    }
+
+    async fn lpush_trim(&self, key: &str, value: &str, maxlen: usize) -> Result<(), PoolError> {
+        self.execute(OperationType::Write, |mut conn| async move {
+            // MULTI/EXEC so readers never observe the list above its cap
+            redis::pipe()
+                .atomic()
+                .lpush(key, value)
+                .ignore()
+                .ltrim(key, 0, maxlen.saturating_sub(1) as isize)
+                .ignore()
+                .query_async(&mut conn)
+                .await
+        })
+        .await
+    }
 
-        match redis::cmd("XLEN")
-            .arg(key)
-            .query_async(&mut conn)
+    async fn lrange(&self, key: &str, start: isize, stop: isize) -> Result<Vec<String>, PoolError> {
+        self.execute(OperationType::Read, |mut conn| async move { conn.lrange(key, start, stop).await })
+            .await
+    }
+
+    async fn lindex(&self, key: &str, index: isize) -> Result<Option<String>, PoolError> {
+        self.execute(OperationType::Read, |mut conn| async move { conn.lindex(key, index).await })
+            .await
+    }
+
+    async fn lrem(&self, key: &str, count: isize, value: &str) -> Result<usize, PoolError> {
+        self.execute(OperationType::Write, |mut conn| async move { conn.lrem(key, count, value).await })
             .await
-        {
-            Ok(result) => {
-                self.circuit_breaker.record_success();
-                Ok(result)
-            }
-            Err(e) => {
-                self.circuit_breaker.record_failure();
-                Err(PoolError::Redis(e))
-            }
-        }
     }
 
     async fn expire(&self, key: &str, seconds: i64) -> Result<(), PoolError> {
-        let mut conn = self.get_connection().await?;
+        self.execute(OperationType::Write, |mut conn| async move { conn.expire(key, seconds).await })
+            .await
+    }
 
-        match conn.expire::<_, ()>(key, seconds).await {
-            Ok(_) => {
-                self.circuit_breaker.record_success();
-                Ok(())
+    async fn scan_keys(&self, pattern: &str) -> Result<Vec<String>, PoolError> {
+        self.execute(OperationType::Read, |mut conn| async move {
+            let mut keys = Vec::new();
+            let mut cursor: u64 = 0;
+
+            loop {
+                let (next_cursor, batch): (u64, Vec<String>) = redis::cmd("SCAN")
+                    .arg(cursor)
+                    .arg("MATCH")
+                    .arg(pattern)
+                    .arg("COUNT")
+                    .arg(100)
+                    .query_async(&mut conn)
+                    .await?;
+
+                keys.extend(batch);
+                cursor = next_cursor;
+
+                if cursor == 0 {
+                    return Ok(keys);
+                }
             }
-            Err(e) => {
-                self.circuit_breaker.record_failure();
-                Err(PoolError::Redis(e))
+        })
+        .await
+    }
+
+    async fn hscan_all(&self, key: &str) -> Result<Vec<(String, String)>, PoolError> {
+        self.execute(OperationType::Read, |mut conn| async move {
+            let mut entries = Vec::new();
+            let mut cursor: u64 = 0;
+
+            loop {
+                let (next_cursor, batch): (u64, Vec<(String, String)>) = redis::cmd("HSCAN")
+                    .arg(key)
+                    .arg(cursor)
+                    .arg("COUNT")
+                    .arg(100)
+                    .query_async(&mut conn)
+                    .await?;
+
+                entries.extend(batch);
+                cursor = next_cursor;
+
+                if cursor == 0 {
+                    return Ok(entries);
+                }
             }
+        })
+        .await
+    }
+
+    async fn eval_script<'a>(
+        &self,
+        script: &redis::Script,
+        keys: &[&'a str],
+        args: &[&'a str],
+    ) -> Result<redis::Value, PoolError> {
+        let mut invocation = script.prepare_invoke();
+        for key in keys {
+            invocation.key(*key);
         }
+        for arg in args {
+            invocation.arg(*arg);
+        }
+
+        self.execute(OperationType::Write, |mut conn| async move { invocation.invoke_async(&mut conn).await })
+            .await
     }
 }
 
@@ -493,18 +662,20 @@ mod tests {
         RedisConfig {
             url: "redis://localhost:6379".to_string(),
             channels: vec![],
+            subscribe_patterns: vec![],
             circuit_breaker_failure_threshold: 5,
             circuit_breaker_success_threshold: 2,
             circuit_breaker_reset_timeout_seconds: 30,
             backoff_initial_delay_ms: 100,
             backoff_max_delay_ms: 30000,
+            backoff_max_total_wait_ms: None,
         }
     }
 
     #[test]
     fn test_pool_creation() {
         let config = create_test_config();
-        let cb = Arc::new(CircuitBreaker::new());
+        let cb = Arc::new(BulkheadCircuitBreaker::new());
         let health = Arc::new(RedisHealth::new());
 
         let pool = RedisPool::new(config, cb.clone(), health.clone());
@@ -517,23 +688,127 @@ mod tests {
     #[test]
     fn test_pool_circuit_breaker_integration() {
         let config = create_test_config();
-        let cb = Arc::new(CircuitBreaker::new());
+        let cb = Arc::new(BulkheadCircuitBreaker::new());
         let health = Arc::new(RedisHealth::new());
 
         let pool = RedisPool::new(config, cb.clone(), health.clone()).unwrap();
 
         // Initially circuit should be closed
-        assert_eq!(pool.circuit_state(), CircuitState::Closed);
+        assert_eq!(pool.circuit_state(OperationType::Read), CircuitState::Closed);
 
         // Record failures to open circuit
         for _ in 0..5 {
-            cb.record_failure();
+            cb.record_read_failure();
         }
 
-        assert_eq!(pool.circuit_state(), CircuitState::Open);
+        assert_eq!(pool.circuit_state(OperationType::Read), CircuitState::Open);
+        assert_eq!(pool.circuit_state(OperationType::Write), CircuitState::Closed);
         assert!(!pool.is_healthy());
     }
 
+    fn test_pool(timeout: Duration) -> (RedisPool, Arc<BulkheadCircuitBreaker>) {
+        let cb = Arc::new(BulkheadCircuitBreaker::new());
+        let pool = RedisPool::new(create_test_config(), cb.clone(), Arc::new(RedisHealth::new()))
+            .unwrap()
+            .with_command_timeout(timeout);
+        (pool, cb)
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_circuit_breaker_records_success() {
+        let (pool, cb) = test_pool(Duration::from_secs(1));
+        cb.record_write_failure();
+
+        let result = pool
+            .execute_with_circuit_breaker(OperationType::Write, async { Ok(42) })
+            .await;
+
+        assert_eq!(result.unwrap(), 42);
+        assert_eq!(cb.breaker(OperationType::Write).stats().failure_count, 0);
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_circuit_breaker_rejects_when_open() {
+        let (pool, cb) = test_pool(Duration::from_secs(1));
+        for _ in 0..5 {
+            cb.record_read_failure();
+        }
+
+        let ran = std::sync::atomic::AtomicBool::new(false);
+        let result = pool
+            .execute_with_circuit_breaker(OperationType::Read, async {
+                ran.store(true, std::sync::atomic::Ordering::SeqCst);
+                Ok(())
+            })
+            .await;
+
+        assert!(matches!(result, Err(PoolError::CircuitOpen)));
+        assert!(!ran.load(std::sync::atomic::Ordering::SeqCst));
+
+        // Writes are guarded by their own breaker and still go through
+        let result = pool
+            .execute_with_circuit_breaker(OperationType::Write, async { Ok(()) })
+            .await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_circuit_breaker_times_out() {
+        let (pool, cb) = test_pool(Duration::from_millis(10));
+
+        let result = pool
+            .execute_with_circuit_breaker(OperationType::Read, async {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                Ok(())
+            })
+            .await;
+
+        assert!(matches!(result, Err(PoolError::Timeout(10))));
+        assert_eq!(cb.breaker(OperationType::Read).stats().failure_count, 1);
+        assert_eq!(cb.breaker(OperationType::Write).stats().failure_count, 0);
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_circuit_breaker_records_command_error() {
+        let (pool, cb) = test_pool(Duration::from_secs(1));
+
+        let result: Result<(), _> = pool
+            .execute_with_circuit_breaker(OperationType::Write, async {
+                Err(RedisError::from((redis::ErrorKind::ResponseError, "WRONGTYPE")))
+            })
+            .await;
+
+        assert!(matches!(result, Err(PoolError::Redis(_))));
+        assert_eq!(cb.breaker(OperationType::Write).stats().failure_count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_execute_counts_overflow_when_connection_unavailable() {
+        let (pool, _cb) = test_pool(Duration::from_millis(20));
+
+        // Hold the connection slot as a task stuck mid-connect would
+        let connecting = pool.connection.write().await;
+        let overflow_before = REDIS_POOL_OVERFLOW_TOTAL.get();
+
+        let result = pool
+            .execute(OperationType::Read, |mut conn| async move {
+                redis::cmd("PING").query_async::<String>(&mut conn).await
+            })
+            .await;
+
+        assert!(matches!(result, Err(PoolError::Timeout(20))));
+        assert!(REDIS_POOL_OVERFLOW_TOTAL.get() > overflow_before);
+        assert_eq!(pool.stats().active_connections, 0);
+        drop(connecting);
+        assert_eq!(
+            pool.stats(),
+            PoolStats {
+                active_connections: 0,
+                idle_connections: 0,
+            }
+        );
+    }
+
     #[test]
     fn test_pool_error_display() {
         let circuit_err = PoolError::CircuitOpen;
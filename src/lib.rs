@@ -2,6 +2,7 @@
 pub mod infrastructure;
 
 // Re-export infrastructure modules for backward compatibility
+pub use infrastructure::audit;
 pub use infrastructure::auth;
 pub use infrastructure::config;
 pub use infrastructure::error;
@@ -9,11 +9,17 @@ use tokio::time::timeout;
 
 use ara_notification_service::cluster::RoutedMessageSubscriber;
 use ara_notification_service::config::Settings;
+use ara_notification_service::ratelimit::{default_state_path, state_path_in};
 use ara_notification_service::server::{create_app, AppState};
-use ara_notification_service::shutdown::GracefulShutdown;
-use ara_notification_service::tasks::HeartbeatTask;
+use ara_notification_service::shutdown::{GracefulShutdown, ShutdownConfig};
+use ara_notification_service::tasks::{
+    CleanupTask, HeartbeatTask, MetricsPushTask, MetricsSamplerTask, MetricsUpdateTask,
+};
 use ara_notification_service::telemetry::init_telemetry;
-use ara_notification_service::triggers::RedisSubscriber;
+use ara_notification_service::triggers::{GrpcNotificationService, RedisSubscriber};
+
+/// Saved rate limiter state older than this is ignored on startup
+const RATE_LIMIT_STATE_MAX_AGE: Duration = Duration::from_secs(60);
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -21,22 +27,47 @@ async fn main() -> Result<()> {
     let settings = Settings::new()?;
 
     // Initialize telemetry (tracing + optional OpenTelemetry)
-    let _telemetry_guard = init_telemetry(&settings.otel)
+    let _telemetry_guard = init_telemetry(&settings.otel, &settings.audit)
         .expect("Failed to initialize telemetry");
 
     tracing::info!("Configuration loaded");
+    if !settings.ignored_secret_keys.is_empty() {
+        tracing::warn!(
+            keys = ?settings.ignored_secret_keys,
+            "Ignored entries in the secrets file that are not allowed there"
+        );
+    }
 
     // Create application state
     let state = AppState::new(settings.clone()).await?;
     tracing::info!("Application state initialized");
 
+    // Restore rate limiter buckets saved by the previous process (rolling restart)
+    let rate_limit_state_path = settings
+        .shutdown
+        .rate_limit_state_dir
+        .as_deref()
+        .map(state_path_in)
+        .unwrap_or_else(default_state_path);
+    if state.rate_limiter.is_enabled() {
+        match state
+            .rate_limiter
+            .restore_state(&rate_limit_state_path, RATE_LIMIT_STATE_MAX_AGE)
+        {
+            Ok(0) => {}
+            Ok(restored) => tracing::info!(buckets = restored, "Rate limiter state restored"),
+            Err(e) => tracing::warn!(error = %e, "Failed to restore rate limiter state"),
+        }
+    }
+
     // Create Redis subscriber with circuit breaker and health from state
     let redis_subscriber = Arc::new(RedisSubscriber::new(
         settings.redis.clone(),
         state.dispatcher.clone(),
         state.redis_circuit_breaker.clone(),
         state.redis_health.clone(),
-    ));
+    )
+    .with_quarantine(state.quarantine.clone()));
     let shutdown_signal = redis_subscriber.shutdown_signal();
 
     // Start Redis subscriber in background
@@ -53,13 +84,62 @@ async fn main() -> Result<()> {
         state.connection_manager.clone(),
         state.session_store.clone(),
         shutdown_signal.subscribe(),
-    );
+    )
+    .with_dispatch_counter(state.dispatcher.sent_counter())
+    .with_leader_election(state.session_store.clone());
     let heartbeat_handle = tokio::spawn(async move {
         heartbeat_task.run().await;
     });
 
-    // Start cluster routed message subscriber in background (if cluster mode is enabled and Redis is available)
-    let cluster_handle = if settings.cluster.enabled {
+    // Start polled gauge refresh in background
+    let metrics_update_task = MetricsUpdateTask::new(
+        Duration::from_secs(settings.metrics.update_interval_seconds),
+        state.connection_manager.clone(),
+        state.queue_backend.clone(),
+        state.ack_backend.clone(),
+        state.session_store.clone(),
+        shutdown_signal.subscribe(),
+    )
+    .with_redis_pool(state.redis_pool.clone())
+    .with_tenant_manager(state.tenant_manager.clone());
+    let metrics_update_handle = tokio::spawn(async move {
+        metrics_update_task.run().await;
+    });
+
+    // Start expired entry cleanup in background
+    let cleanup_task = CleanupTask::new(state.cleanables.clone(), shutdown_signal.subscribe());
+    let cleanup_handle = tokio::spawn(async move {
+        cleanup_task.run().await;
+    });
+
+    // Start tenant metrics sampler in background
+    let sampler_task = MetricsSamplerTask::new(
+        state.tenant_manager.clone(),
+        shutdown_signal.subscribe(),
+    );
+    let sampler_handle = tokio::spawn(async move {
+        sampler_task.run().await;
+    });
+
+    // Start Prometheus Pushgateway pusher in background (if a push gateway is configured)
+    let push_handle = settings.metrics.push_gateway_url.as_deref().map(|url| {
+        let push_task = MetricsPushTask::new(
+            Duration::from_secs(settings.metrics.push_interval_seconds),
+            url,
+            &settings.cluster.server_id,
+            shutdown_signal.subscribe(),
+        );
+        tokio::spawn(async move {
+            push_task.run().await;
+        })
+    });
+
+    // Start cluster routed message subscriber in background (if cluster mode is enabled,
+    // not switched off with ARA_FEATURE_CLUSTER, and Redis is available)
+    let cluster_handle = if settings.cluster.enabled && !settings.features.cluster {
+        tracing::warn!("Cluster feature disabled, skipping routed message subscriber");
+        None
+    } else if settings.cluster.enabled {
         if let Some(ref redis_pool) = state.redis_pool {
             let subscriber = RoutedMessageSubscriber::new(
                 settings.cluster.clone(),
@@ -79,11 +159,38 @@ async fn main() -> Result<()> {
     };
 
     // Create graceful shutdown handler (before moving state to app)
-    let graceful_shutdown = GracefulShutdown::new(
+    let graceful_shutdown = GracefulShutdown::with_config(
         state.connection_manager.clone(),
         state.queue_backend.clone(),
         shutdown_signal.clone(),
-    );
+        ShutdownConfig {
+            result_output_path: settings.shutdown.result_output_path.clone(),
+            rate_limit_state_path,
+            ..Default::default()
+        },
+    )
+    .with_rate_limiter(state.rate_limiter.clone());
+
+    // Start gRPC server in background (shares state with the HTTP server)
+    let grpc_handle = if settings.grpc.enabled {
+        let addr: SocketAddr = settings.grpc_addr().parse()?;
+        let service = GrpcNotificationService::new(state.clone()).into_server();
+        let mut shutdown_rx = shutdown_signal.subscribe();
+        tracing::info!("gRPC server listening on {}", addr);
+        Some(tokio::spawn(async move {
+            if let Err(e) = tonic::transport::Server::builder()
+                .add_service(service)
+                .serve_with_shutdown(addr, async move {
+                    let _ = shutdown_rx.recv().await;
+                })
+                .await
+            {
+                tracing::error!(error = %e, "gRPC server failed");
+            }
+        }))
+    } else {
+        None
+    };
 
     // Create Axum app
     let app = create_app(state);
@@ -108,6 +215,7 @@ async fn main() -> Result<()> {
         clients_notified = shutdown_result.clients_notified,
         connections_closed = shutdown_result.connections_closed,
         queue_drained = shutdown_result.queue_drained,
+        phases_skipped = shutdown_result.phases_skipped,
         duration_ms = shutdown_result.duration.as_millis(),
         "Graceful shutdown phase completed"
     );
@@ -120,10 +228,22 @@ async fn main() -> Result<()> {
     );
 
     let shutdown_future = async {
-        let _ = tokio::join!(redis_handle, heartbeat_handle);
+        let _ = tokio::join!(
+            redis_handle,
+            heartbeat_handle,
+            metrics_update_handle,
+            cleanup_handle,
+            sampler_handle
+        );
         if let Some(handle) = cluster_handle {
             let _ = handle.await;
         }
+        if let Some(handle) = grpc_handle {
+            let _ = handle.await;
+        }
+        if let Some(handle) = push_handle {
+            let _ = handle.await;
+        }
     };
 
     match timeout(Duration::from_secs(SHUTDOWN_TIMEOUT_SECS), shutdown_future).await {
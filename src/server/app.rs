@@ -1,51 +1,74 @@
+use std::time::Duration;
+
 use axum::{middleware, routing::get, Router};
 use tower_http::{
+    compression::{
+        predicate::{DefaultPredicate, NotForContentType, Predicate, SizeAbove},
+        CompressionLayer,
+    },
     cors::{Any, CorsLayer},
     limit::RequestBodyLimitLayer,
     trace::TraceLayer,
 };
 
+use crate::auth::AdminAuth;
+use crate::config::CorsConfig;
 use crate::sse::sse_handler;
 use crate::websocket::ws_handler;
 
-use super::middleware::{api_key_auth, rate_limit_middleware, ws_rate_limit_middleware};
+use super::middleware::{
+    api_key_auth, payload_too_large_response, rate_limit_middleware, ws_rate_limit_middleware,
+};
 use super::AppState;
 
-/// Maximum request body size for regular endpoints (64 KB)
-const MAX_BODY_SIZE: usize = 64 * 1024;
-
-/// Maximum request body size for batch endpoint (1 MB)
-const MAX_BATCH_BODY_SIZE: usize = 1024 * 1024;
+/// Responses smaller than this are sent uncompressed
+const MIN_COMPRESSION_SIZE: u16 = 512;
 
 pub fn create_app(state: AppState) -> Router {
     // NOTE: `src/server/app.rs::create_app` is the single entry point for all HTTP route composition.
-    // CORS configuration - use configured origins or allow any in development
-    let cors = build_cors_layer(&state.settings.server.cors_origins);
+    // CORS configuration - configured origins, or any origin when cors.allow_all is set
+    let cors = build_cors_layer(&state.settings.cors);
+    let max_body_bytes = state.settings.server.max_request_body_bytes;
+    let max_batch_body_bytes = state.settings.server.max_batch_request_body_bytes;
 
-    // WebSocket and SSE routes with connection rate limiting
+    // WebSocket route with connection rate limiting (no CORS: browsers don't preflight upgrades)
     let ws_routes = Router::new()
         .route("/ws", get(ws_handler))
+        .layer(middleware::from_fn_with_state(state.clone(), ws_rate_limit_middleware));
+
+    // SSE route with connection rate limiting
+    let sse_routes = Router::new()
         .route("/sse", get(sse_handler))
         .layer(middleware::from_fn_with_state(state.clone(), ws_rate_limit_middleware));
 
-    // Health check and metrics (no rate limiting, no auth)
+    // Health checks and Kubernetes liveness/readiness probes (no rate limiting, no auth)
     let health_routes = Router::new()
         .route("/health", get(crate::api::health))
-        .route("/metrics", get(crate::api::prometheus_metrics));
+        .route("/health/live", get(crate::api::health_live))
+        .route("/health/ready", get(crate::api::health_ready));
 
-    // Regular notification routes (64KB limit)
+    // Prometheus metrics (no rate limiting, no auth, never compressed)
+    let metrics_routes = Router::new()
+        .route("/metrics", get(crate::api::prometheus_metrics))
+        .route("/metrics/connections", get(crate::api::prometheus_connection_metrics))
+        .route("/metrics/queue", get(crate::api::prometheus_queue_metrics))
+        .route("/metrics/redis", get(crate::api::prometheus_redis_metrics));
+
+    // Regular notification routes (server.max_request_body_bytes, 64KB by default)
     let notification_routes = Router::new()
         .route("/notifications/send", axum::routing::post(crate::triggers::send_notification))
         .route("/notifications/send-to-users", axum::routing::post(crate::triggers::send_to_users))
         .route("/notifications/broadcast", axum::routing::post(crate::triggers::broadcast_notification))
         .route("/notifications/channel", axum::routing::post(crate::triggers::channel_notification))
         .route("/notifications/channels", axum::routing::post(crate::triggers::multi_channel_notification))
-        .layer(RequestBodyLimitLayer::new(MAX_BODY_SIZE));
+        .route("/channels/{name}/notifications", axum::routing::post(crate::triggers::channel_path_notification))
+        .layer(RequestBodyLimitLayer::new(max_body_bytes));
 
-    // Batch notification route (1MB limit)
+    // Batch notification routes (server.max_batch_request_body_bytes, 1MB by default)
     let batch_routes = Router::new()
         .route("/notifications/batch", axum::routing::post(crate::triggers::batch_send))
-        .layer(RequestBodyLimitLayer::new(MAX_BATCH_BODY_SIZE));
+        .route("/notifications/batch-stream", axum::routing::post(crate::triggers::batch_send_stream))
+        .layer(RequestBodyLimitLayer::new(max_batch_body_bytes));
 
     // Channel info routes (read-only, no body limit needed)
     let channel_routes = Router::new()
@@ -57,9 +80,12 @@ pub fn create_app(state: AppState) -> Router {
     let template_routes = Router::new()
         .route("/templates", axum::routing::post(crate::api::create_template))
         .route("/templates", get(crate::api::list_templates))
+        .route("/templates/export", get(crate::api::export_templates))
+        .route("/templates/import", axum::routing::post(crate::api::import_templates))
         .route("/templates/{id}", get(crate::api::get_template))
         .route("/templates/{id}", axum::routing::put(crate::api::update_template))
-        .route("/templates/{id}", axum::routing::delete(crate::api::delete_template));
+        .route("/templates/{id}", axum::routing::delete(crate::api::delete_template))
+        .route("/templates/{id}/preview", get(crate::api::preview_template));
 
     // Tenant management routes
     let tenant_routes = Router::new()
@@ -71,52 +97,416 @@ pub fn create_app(state: AppState) -> Router {
         .route("/cluster/status", get(crate::api::cluster_status))
         .route("/cluster/users/{user_id}", get(crate::api::cluster_user_location));
 
+    // Admin routes (tenant provisioning, queue maintenance, message quarantine; admin API key bearer auth) with rate limiting
+    let admin_routes = Router::new()
+        .route("/admin/tenants", axum::routing::post(crate::api::register_tenant))
+        .route("/admin/tenants/{id}", get(crate::api::get_tenant_detail))
+        .route("/admin/tenants/{id}", axum::routing::delete(crate::api::unregister_tenant))
+        .route("/admin/tenants/{id}/metrics", get(crate::api::get_tenant_metrics))
+        .route("/admin/queue/migrate", axum::routing::post(crate::api::start_queue_migration))
+        .route("/admin/queue/migrate/status", get(crate::api::queue_migration_status))
+        .route("/admin/dropped-notifications", get(crate::api::dropped_notifications))
+        .route("/admin/ack/summary", get(crate::api::ack_summary))
+        .route("/admin/connections/snapshot", get(crate::api::connections_snapshot))
+        .route("/admin/connections/{id}/send", axum::routing::post(crate::api::send_to_connection))
+        .route("/admin/channels/{name}", axum::routing::delete(crate::api::delete_channel))
+        .route("/admin/users/{id}/sessions", get(crate::api::list_user_sessions))
+        .route("/admin/quarantine", get(crate::api::list_quarantine))
+        .route("/admin/quarantine/{index}/reprocess", axum::routing::post(crate::api::reprocess_quarantined))
+        .layer(RequestBodyLimitLayer::new(max_body_bytes))
+        .layer(middleware::map_response(payload_too_large_response))
+        .layer(middleware::from_extractor_with_state::<AdminAuth, _>(state.clone()))
+        .layer(middleware::from_fn_with_state(state.clone(), rate_limit_middleware));
+
+    // User-facing routes (JWT or admin auth in handler) with rate limiting
+    let user_routes = Router::new()
+        .route("/users/{user_id}/pending-acks", get(crate::api::get_user_pending_acks))
+        .route("/users/{user_id}/connections", get(crate::api::get_user_connections))
+        .layer(middleware::from_fn_with_state(state.clone(), rate_limit_middleware));
+
     // Protected API routes (require API key) with rate limiting
     let protected_routes = Router::new()
         .route("/stats", get(crate::api::stats))
+        .route("/health/stats", get(crate::api::connection_stats))
         .nest("/api/v1", notification_routes.merge(batch_routes).merge(channel_routes).merge(template_routes).merge(tenant_routes).merge(cluster_routes))
+        .layer(middleware::map_response(payload_too_large_response))
         .layer(middleware::from_fn_with_state(state.clone(), api_key_auth))
         .layer(middleware::from_fn_with_state(state.clone(), rate_limit_middleware));
 
-    Router::new()
-        .merge(ws_routes)
+    let mut app = Router::new();
+    // The SSE endpoint can be switched off with ARA_FEATURE_SSE
+    if state.settings.features.sse {
+        app = app.merge(sse_routes);
+    }
+    let mut app = app
         .merge(health_routes)
-        .merge(protected_routes)
-        .layer(TraceLayer::new_for_http())
+        .merge(admin_routes)
+        .merge(user_routes)
+        .merge(protected_routes);
+
+    // Compress responses by Accept-Encoding; DefaultPredicate already skips SSE streams, and
+    // NDJSON streams are excluded so each line reaches the client as soon as it is written
+    if !state.settings.server.disable_response_compression {
+        app = app.layer(
+            CompressionLayer::new()
+                .gzip(true)
+                .br(true)
+                .compress_when(
+                    DefaultPredicate::new()
+                        .and(SizeAbove::new(MIN_COMPRESSION_SIZE))
+                        .and(NotForContentType::const_new(crate::triggers::NDJSON_CONTENT_TYPE)),
+                ),
+        );
+    }
+
+    app.merge(metrics_routes)
         .layer(cors)
+        .merge(ws_routes)
+        .layer(TraceLayer::new_for_http())
         .with_state(state)
 }
 
 /// Build CORS layer from configured origins
-fn build_cors_layer(origins: &[String]) -> CorsLayer {
+fn build_cors_layer(config: &CorsConfig) -> CorsLayer {
     use tower_http::cors::AllowOrigin;
 
-    if origins.is_empty() {
+    let max_age = Duration::from_secs(config.max_age_seconds);
+
+    if config.allow_all {
         // Development mode: allow any origin (with warning)
-        tracing::warn!("CORS: No origins configured, allowing any origin. Configure CORS_ORIGINS for production.");
-        CorsLayer::new()
+        tracing::warn!("CORS: cors.allow_all is set, allowing any origin. Configure CORS_ORIGINS for production.");
+        return CorsLayer::new()
             .allow_origin(Any)
             .allow_methods(Any)
             .allow_headers(Any)
-    } else {
-        // Production mode: restrict to configured origins
-        let origins: Vec<_> = origins
-            .iter()
-            .filter_map(|o| o.parse().ok())
-            .collect();
+            .max_age(max_age);
+    }
 
+    let origins: Vec<_> = config
+        .allowed_origins
+        .iter()
+        .filter_map(|o| o.parse().ok())
+        .collect();
+    if origins.is_empty() {
+        tracing::info!("CORS: No origins configured, cross-origin requests are not allowed");
+    } else {
         tracing::info!("CORS: Restricting to {} configured origins", origins.len());
-        CorsLayer::new()
-            .allow_origin(AllowOrigin::list(origins))
-            .allow_methods([
-                axum::http::Method::GET,
-                axum::http::Method::POST,
-                axum::http::Method::OPTIONS,
-            ])
-            .allow_headers([
-                axum::http::header::CONTENT_TYPE,
-                axum::http::header::AUTHORIZATION,
-                axum::http::header::HeaderName::from_static("x-api-key"),
-            ])
+    }
+
+    CorsLayer::new()
+        .allow_origin(AllowOrigin::list(origins))
+        .allow_methods([
+            axum::http::Method::GET,
+            axum::http::Method::POST,
+            axum::http::Method::OPTIONS,
+        ])
+        .allow_headers([
+            axum::http::header::CONTENT_TYPE,
+            axum::http::header::AUTHORIZATION,
+            axum::http::header::HeaderName::from_static("x-api-key"),
+        ])
+        .allow_credentials(config.allow_credentials)
+        .max_age(max_age)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{header, StatusCode};
+    use serde_json::json;
+    use tower::ServiceExt;
+
+    use super::create_app;
+    use crate::api::test_support::{json_request, response_json, test_state, test_state_with};
+
+    const BODY_LIMIT: usize = 1024;
+    const ADMIN_KEY: &str = "admin-key-for-app-tests-0123456789";
+
+    /// A valid send request whose serialized body is exactly `size` bytes
+    fn send_body_of_size(size: usize) -> serde_json::Value {
+        let mut body = json!({
+            "target_user_id": "user-1",
+            "event_type": "test.event",
+            "payload": { "pad": "" }
+        });
+        let padding = size - body.to_string().len();
+        body["payload"]["pad"] = json!("x".repeat(padding));
+        assert_eq!(body.to_string().len(), size);
+        body
+    }
+
+    #[tokio::test]
+    async fn test_channel_path_notification_uses_path_channel() {
+        let state = test_state().await;
+        let mut receivers = Vec::new();
+        for channel in ["orders", "alerts"] {
+            let (tx, rx) = tokio::sync::mpsc::channel(8);
+            receivers.push(rx);
+            let handle = state
+                .connection_manager
+                .register(format!("user-{}", channel), "default".to_string(), vec![], tx)
+                .unwrap();
+            state
+                .connection_manager
+                .subscribe_to_channel(handle.id, channel)
+                .await
+                .unwrap();
+        }
+        let app = create_app(state);
+
+        let response = app
+            .oneshot(json_request(
+                "POST",
+                "/api/v1/channels/orders/notifications",
+                json!({ "event_type": "order.created", "payload": { "order_id": 1 } }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response_json(response).await;
+        assert_eq!(body["success"], true);
+        assert_eq!(body["delivered_to"], 1);
+        assert!(receivers[0].try_recv().is_ok());
+        assert!(receivers[1].try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_sse_route_follows_feature_flag() {
+        let mut state = test_state().await;
+        let request = || json_request("GET", "/sse", json!({}));
+
+        // Enabled: the route exists (and rejects the missing token)
+        let response = create_app(state.clone()).oneshot(request()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let mut settings = (*state.settings).clone();
+        settings.features.sse = false;
+        state.settings = std::sync::Arc::new(settings);
+        let response = create_app(state).oneshot(request()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_large_responses_are_gzip_compressed() {
+        let state = test_state().await;
+        let app = create_app(state);
+
+        let response = app
+            .clone()
+            .oneshot(json_request(
+                "POST",
+                "/api/v1/templates",
+                json!({
+                    "id": "digest",
+                    "name": "Daily Digest",
+                    "event_type": "digest.daily",
+                    "payload_template": { "body": "x".repeat(2048) }
+                }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        let mut request = json_request("GET", "/api/v1/templates/digest", json!({}));
+        request
+            .headers_mut()
+            .insert(header::ACCEPT_ENCODING, "gzip".parse().unwrap());
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_ENCODING], "gzip");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        // gzip magic number
+        assert_eq!(&body[..2], &[0x1f, 0x8b]);
+
+        // Prometheus scrapes are never compressed
+        let mut request = json_request("GET", "/metrics", json!({}));
+        request
+            .headers_mut()
+            .insert(header::ACCEPT_ENCODING, "gzip".parse().unwrap());
+        let response = app.oneshot(request).await.unwrap();
+        assert!(response.headers().get(header::CONTENT_ENCODING).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_filtered_metrics_endpoints() {
+        let app = create_app(test_state().await);
+        crate::metrics::QUEUE_SIZE_TOTAL.set(0);
+
+        for (path, prefix, expected) in [
+            ("/metrics/connections", "ara_connections_", "ara_connections_total"),
+            ("/metrics/queue", "ara_queue_", "ara_queue_size_total"),
+            ("/metrics/redis", "ara_redis_", "ara_redis_connection_status"),
+        ] {
+            let response = app
+                .clone()
+                .oneshot(json_request("GET", path, json!({})))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            let body = String::from_utf8(body.to_vec()).unwrap();
+
+            assert!(body.contains(&format!("# TYPE {} ", expected)), "{path}");
+            for family in body.lines().filter_map(|line| line.strip_prefix("# TYPE ")) {
+                assert!(family.starts_with(prefix), "{path} exposed {family}");
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_request_body_limit() {
+        let state = test_state_with(json!({
+            "server": { "max_request_body_bytes": BODY_LIMIT }
+        }))
+        .await;
+        let app = create_app(state);
+
+        for (size, expected) in [
+            (BODY_LIMIT - 1, StatusCode::OK),
+            (BODY_LIMIT, StatusCode::OK),
+            (BODY_LIMIT + 1, StatusCode::PAYLOAD_TOO_LARGE),
+        ] {
+            let response = app
+                .clone()
+                .oneshot(json_request(
+                    "POST",
+                    "/api/v1/notifications/send",
+                    send_body_of_size(size),
+                ))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), expected, "body of {} bytes", size);
+        }
+
+        let before = crate::metrics::HTTP_REQUEST_BODY_TOO_LARGE_TOTAL.get();
+        let response = app
+            .oneshot(json_request(
+                "POST",
+                "/api/v1/notifications/send",
+                send_body_of_size(BODY_LIMIT * 2),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        assert!(crate::metrics::HTTP_REQUEST_BODY_TOO_LARGE_TOTAL.get() > before);
+        let body = response_json(response).await;
+        assert_eq!(body["error"]["code"], "PAYLOAD_TOO_LARGE");
+    }
+
+    #[tokio::test]
+    async fn test_batch_body_limit_is_separate() {
+        let state = test_state_with(json!({
+            "server": {
+                "max_request_body_bytes": BODY_LIMIT,
+                "max_batch_request_body_bytes": BODY_LIMIT * 4
+            }
+        }))
+        .await;
+        let app = create_app(state);
+
+        let notification = send_body_of_size(BODY_LIMIT);
+        let response = app
+            .oneshot(json_request(
+                "POST",
+                "/api/v1/notifications/batch",
+                json!({
+                    "notifications": [{
+                        "target": { "type": "user", "value": "user-1" },
+                        "event_type": "test.event",
+                        "payload": notification["payload"]
+                    }]
+                }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    fn get_with_origin(uri: &str, origin: &str) -> axum::http::Request<axum::body::Body> {
+        let mut request = json_request("GET", uri, json!({}));
+        request
+            .headers_mut()
+            .insert(header::ORIGIN, origin.parse().unwrap());
+        request
+    }
+
+    #[tokio::test]
+    async fn test_cors_allowlist() {
+        let state = test_state_with(json!({
+            "cors": { "allowed_origins": "https://app.example.com" }
+        }))
+        .await;
+        let app = create_app(state);
+
+        let response = app
+            .clone()
+            .oneshot(get_with_origin("/health", "https://app.example.com"))
+            .await
+            .unwrap();
+        assert_eq!(
+            response.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN],
+            "https://app.example.com"
+        );
+
+        let response = app
+            .oneshot(get_with_origin("/health", "https://evil.example.com"))
+            .await
+            .unwrap();
+        assert!(response
+            .headers()
+            .get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_cors_not_applied_to_websocket() {
+        let state = test_state_with(json!({ "cors": { "allow_all": true } })).await;
+        let app = create_app(state);
+
+        let response = app
+            .clone()
+            .oneshot(get_with_origin("/health", "https://any.example.com"))
+            .await
+            .unwrap();
+        assert_eq!(response.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN], "*");
+
+        let response = app
+            .oneshot(get_with_origin("/ws", "https://any.example.com"))
+            .await
+            .unwrap();
+        assert_ne!(response.status(), StatusCode::NOT_FOUND);
+        assert!(response
+            .headers()
+            .get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_admin_routes_require_admin_key() {
+        let state = test_state_with(json!({
+            "api": { "key": "regular-api-key-0123456789", "admin_key": ADMIN_KEY }
+        }))
+        .await;
+        let app = create_app(state);
+
+        // The regular API key does not grant admin access
+        let mut request = json_request("GET", "/admin/queue/migrate/status", json!({}));
+        request
+            .headers_mut()
+            .insert("X-API-Key", "regular-api-key-0123456789".parse().unwrap());
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(response_json(response).await["error"]["code"], "UNAUTHORIZED");
+
+        let mut request = json_request("GET", "/admin/queue/migrate/status", json!({}));
+        request.headers_mut().insert(
+            header::AUTHORIZATION,
+            format!("Bearer {}", ADMIN_KEY).parse().unwrap(),
+        );
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
     }
 }
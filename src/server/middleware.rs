@@ -11,7 +11,8 @@ use axum::{
 use serde_json::json;
 
 use super::AppState;
-use crate::metrics::RateLimitMetrics;
+use crate::error::AppError;
+use crate::metrics::{RateLimitMetrics, HTTP_REQUEST_BODY_TOO_LARGE_TOTAL};
 use crate::ratelimit::RateLimitResult;
 use crate::tenant::TenantContext;
 
@@ -29,6 +30,11 @@ impl RequestTenantContext {
     pub fn namespace_channel(&self, channel: &str) -> String {
         self.0.namespace_channel(channel)
     }
+
+    /// Namespace a user ID for tenant isolation
+    pub fn namespace_user_id(&self, user_id: &str) -> String {
+        self.0.namespace_user_id(user_id)
+    }
 }
 
 /// Constant-time string comparison to prevent timing attacks.
@@ -124,11 +130,14 @@ pub async fn rate_limit_middleware(
     if !state.rate_limiter.is_enabled() {
         return next.run(req).await;
     }
+    calibrate_rate_limiter(&state);
 
     // Get API key from header or use IP address
     let api_key = req.headers().get("X-API-Key").and_then(|v| v.to_str().ok());
 
-    let result = state.rate_limiter.check_http(api_key, addr.ip());
+    let result = state
+        .rate_limiter
+        .check_http_path(api_key, addr.ip(), req.uri().path());
 
     match result {
         RateLimitResult::Allowed {
@@ -184,6 +193,7 @@ pub async fn ws_rate_limit_middleware(
     if !state.rate_limiter.is_enabled() {
         return next.run(req).await;
     }
+    calibrate_rate_limiter(&state);
 
     let result = state.rate_limiter.check_ip(addr.ip());
 
@@ -209,6 +219,12 @@ pub async fn ws_rate_limit_middleware(
     }
 }
 
+/// Bypass enforcement if the limits are denying most traffic (runs at most once per minute)
+fn calibrate_rate_limiter(state: &AppState) {
+    let window = state.rate_limiter.config().emergency_bypass_window_seconds;
+    state.rate_limiter.self_calibrate(window);
+}
+
 /// Build a rate limit error response with proper headers
 fn rate_limit_response(retry_after: u64, limit: u32, reset_at: i64) -> Response {
     let body = json!({
@@ -240,7 +256,7 @@ fn rate_limit_response(retry_after: u64, limit: u32, reset_at: i64) -> Response
 /// Validate tenant ID format.
 /// Must be non-empty, max 64 chars, alphanumeric + dash + underscore + dot (no colon).
 /// Colon is excluded to prevent channel namespace collisions.
-fn is_valid_tenant_id(id: &str) -> bool {
+pub(crate) fn is_valid_tenant_id(id: &str) -> bool {
     let trimmed = id.trim();
     if trimmed.is_empty() || trimmed.len() > 64 {
         return false;
@@ -249,3 +265,24 @@ fn is_valid_tenant_id(id: &str) -> bool {
         .chars()
         .all(|c| c.is_alphanumeric() || c == '-' || c == '_' || c == '.')
 }
+
+/// Rewrite 413 responses produced by the body limit layer (plain text) into the
+/// standard `AppError` JSON body, and count them.
+pub async fn payload_too_large_response(response: Response) -> Response {
+    if response.status() != StatusCode::PAYLOAD_TOO_LARGE {
+        return response;
+    }
+
+    HTTP_REQUEST_BODY_TOO_LARGE_TOTAL.inc();
+
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .is_some_and(|v| v.as_bytes().starts_with(b"application/json"));
+    if is_json {
+        return response;
+    }
+
+    AppError::PayloadTooLarge("Request body exceeds the maximum allowed size".to_string())
+        .into_response()
+}
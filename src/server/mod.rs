@@ -1,6 +1,8 @@
 mod app;
 pub mod middleware;
+mod startup;
 mod state;
 
 pub use app::create_app;
+pub use startup::{StartupError, StartupFailure, StartupHealthReport, StartupStatus};
 pub use state::AppState;
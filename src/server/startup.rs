@@ -0,0 +1,131 @@
+//! Startup dependency reporting.
+//!
+//! `AppState::new` connects to each optional external dependency under a timeout.
+//! Failures degrade the service to memory backends outside production; in production
+//! they are collected into a [`StartupError`] so every broken dependency is reported
+//! at once instead of only the first.
+
+use std::fmt;
+use std::future::Future;
+use std::time::Duration;
+
+/// Outcome of initializing one external dependency
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StartupStatus {
+    /// Not required by the configured backends
+    Disabled,
+    /// Connected and usable
+    Ready,
+    /// Required but unavailable; the service runs on fallbacks
+    Degraded(String),
+}
+
+impl StartupStatus {
+    pub fn is_degraded(&self) -> bool {
+        matches!(self, Self::Degraded(_))
+    }
+}
+
+impl fmt::Display for StartupStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Disabled => write!(f, "disabled"),
+            Self::Ready => write!(f, "ready"),
+            Self::Degraded(reason) => write!(f, "degraded ({})", reason),
+        }
+    }
+}
+
+/// Dependency status recorded while building `AppState`
+#[derive(Debug, Clone)]
+pub struct StartupHealthReport {
+    pub redis: StartupStatus,
+    pub postgres: StartupStatus,
+    pub cluster: StartupStatus,
+}
+
+impl StartupHealthReport {
+    /// Whether any required dependency is unavailable
+    pub fn is_degraded(&self) -> bool {
+        self.redis.is_degraded() || self.postgres.is_degraded() || self.cluster.is_degraded()
+    }
+
+    /// Log the report at INFO level
+    pub fn log(&self) {
+        tracing::info!(
+            redis = %self.redis,
+            postgres = %self.postgres,
+            cluster = %self.cluster,
+            degraded = self.is_degraded(),
+            "Startup dependency report"
+        );
+    }
+}
+
+/// A dependency that failed to initialize
+#[derive(Debug, Clone)]
+pub struct StartupFailure {
+    /// Dependency name (`redis`, `postgres`, `postgres_migrations`)
+    pub dependency: &'static str,
+    pub reason: String,
+}
+
+/// Startup failed because required dependencies were unavailable (production mode)
+#[derive(Debug, Clone)]
+pub struct StartupError {
+    pub failures: Vec<StartupFailure>,
+}
+
+impl fmt::Display for StartupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Startup failed, unavailable dependencies: ")?;
+        for (i, failure) in self.failures.iter().enumerate() {
+            if i > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{} ({})", failure.dependency, failure.reason)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for StartupError {}
+
+/// Collects dependency failures during startup
+#[derive(Debug, Default)]
+pub(super) struct StartupFailures {
+    failures: Vec<StartupFailure>,
+}
+
+impl StartupFailures {
+    pub(super) fn record(&mut self, dependency: &'static str, reason: impl Into<String>) {
+        self.failures.push(StartupFailure {
+            dependency,
+            reason: reason.into(),
+        });
+    }
+
+    /// Fail startup if anything was recorded
+    pub(super) fn into_result(self) -> Result<(), StartupError> {
+        if self.failures.is_empty() {
+            Ok(())
+        } else {
+            Err(StartupError {
+                failures: self.failures,
+            })
+        }
+    }
+}
+
+/// Run a startup step under the startup timeout, flattening timeout and step errors
+pub(super) async fn with_startup_timeout<T, E, F>(timeout: Duration, step: F) -> Result<T, String>
+where
+    E: fmt::Display,
+    F: Future<Output = Result<T, E>>,
+{
+    match tokio::time::timeout(timeout, step).await {
+        Ok(Ok(value)) => Ok(value),
+        Ok(Err(e)) => Err(e.to_string()),
+        Err(_) => Err(format!("timed out after {}s", timeout.as_secs())),
+    }
+}
@@ -1,48 +1,93 @@
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
-use anyhow::{bail, Result};
+use axum::extract::FromRef;
+use tokio::sync::mpsc;
 
 use crate::auth::JwtValidator;
 use crate::cluster::{create_session_store, ClusterRouter, SessionStore};
 use crate::config::Settings;
-use crate::connection_manager::{ConnectionLimits, ConnectionManager};
-use crate::notification::{create_ack_backend, AckTrackerBackend, NotificationDispatcher};
-use crate::postgres::PostgresPool;
-use crate::queue::{create_queue_backend, MessageQueueBackend};
+use crate::connection_manager::{ConnectionEventBus, ConnectionLimits, ConnectionManager};
+use crate::notification::{
+    create_ack_backend, AckTrackerBackend, DropLog, NotificationDispatcher, NoopAckBackend,
+    PostgresAckBackend,
+};
+use crate::postgres::{PostgresCircuitBreaker, PostgresHealth, PostgresPool};
+use crate::queue::{create_queue_backend, MessageQueueBackend, MigrationProgress};
 use crate::ratelimit::RateLimiter;
-use crate::redis::pool::RedisPool;
-use crate::redis::{CircuitBreaker, CircuitBreakerConfig, RedisHealth};
+use crate::redis::pool::{PoolError, RedisPool};
+use crate::redis::{BulkheadCircuitBreaker, CircuitBreaker, CircuitBreakerConfig, RedisHealth};
+use crate::tasks::{AckCleanup, Cleanable, QueueCleanup};
 use crate::template::TemplateStore;
 use crate::tenant::TenantManager;
+use crate::triggers::{create_quarantine_store, QuarantineStore};
+
+use super::startup::{
+    with_startup_timeout, StartupError, StartupFailures, StartupHealthReport, StartupStatus,
+};
 
 #[derive(Clone)]
 pub struct AppState {
     pub settings: Arc<Settings>,
     pub jwt_validator: Arc<JwtValidator>,
     pub connection_manager: Arc<ConnectionManager>,
+    /// Connection lifecycle events published by `connection_manager`
+    pub connection_events: ConnectionEventBus,
     pub dispatcher: Arc<NotificationDispatcher>,
     pub rate_limiter: Arc<RateLimiter>,
     pub redis_circuit_breaker: Arc<CircuitBreaker>,
     pub redis_health: Arc<RedisHealth>,
     pub redis_pool: Option<Arc<RedisPool>>,
+    pub postgres_circuit_breaker: Arc<PostgresCircuitBreaker>,
+    pub postgres_health: Arc<PostgresHealth>,
     pub postgres_pool: Option<Arc<PostgresPool>>,
     pub template_store: Arc<TemplateStore>,
     pub tenant_manager: Arc<TenantManager>,
     /// Backend for persistent queue storage (memory, Redis, or PostgreSQL)
     pub queue_backend: Arc<dyn MessageQueueBackend>,
+    /// Progress of the current/last queue backend migration
+    pub queue_migration: Arc<MigrationProgress>,
     /// Backend for persistent ACK tracking (memory, Redis, or PostgreSQL)
     pub ack_backend: Arc<dyn AckTrackerBackend>,
     /// Session store for distributed cluster mode
     pub session_store: Arc<dyn SessionStore>,
     /// Cluster router for cross-server message delivery
     pub cluster_router: Arc<ClusterRouter>,
+    /// Notifications for offline users dropped while the queue is disabled
+    /// (only recorded with `dispatcher.queue_fallback_on_offline`)
+    pub drop_log: Arc<DropLog>,
+    /// Redis Pub/Sub messages that failed to deserialize
+    pub quarantine: Arc<dyn QuarantineStore>,
+    /// Components swept periodically by the cleanup task
+    pub cleanables: Vec<Arc<dyn Cleanable>>,
+    /// Dependency status recorded at startup
+    pub startup_report: StartupHealthReport,
     /// Server start time for uptime calculation
     pub start_time: Instant,
 }
 
 impl AppState {
-    pub async fn new(settings: Settings) -> Result<Self> {
+    /// Build the application state.
+    ///
+    /// Redis, PostgreSQL and the ACK migrations are each given
+    /// `server.startup_timeout_seconds`. Outside production an unavailable dependency
+    /// is logged and the service starts in degraded mode on memory backends; in
+    /// production all failures are returned together as a [`StartupError`].
+    pub async fn new(settings: Settings) -> Result<Self, StartupError> {
+        // Feature flags override module configuration
+        let features = settings.features;
+        features.record_metrics();
+        let disabled = features.disabled();
+        if !disabled.is_empty() {
+            tracing::warn!(features = ?disabled, "Features disabled by ARA_FEATURE_* flags");
+        }
+        let ack_enabled = settings.ack.enabled && features.ack_tracking;
+        let cluster_enabled = settings.cluster.enabled && features.cluster;
+        let mut queue_config = settings.queue.clone();
+        queue_config.enabled &= features.message_queue;
+        let mut cluster_config = settings.cluster.clone();
+        cluster_config.enabled = cluster_enabled;
+
         let jwt_validator = Arc::new(JwtValidator::new(&settings.jwt));
 
         // Create connection manager with limits from config
@@ -52,110 +97,189 @@ impl AppState {
             max_subscriptions_per_connection: settings.websocket.max_subscriptions_per_connection,
         };
         let connection_manager = Arc::new(ConnectionManager::with_limits(limits));
+        if settings.websocket.connection_alert_threshold > 0 {
+            let (alert_tx, mut alert_rx) = mpsc::channel(64);
+            connection_manager
+                .set_connection_count_alert(settings.websocket.connection_alert_threshold, alert_tx);
+            tokio::spawn(async move {
+                while let Some(alert) = alert_rx.recv().await {
+                    tracing::warn!(
+                        user_id = %alert.user_id,
+                        connections = alert.count,
+                        timestamp = %alert.timestamp,
+                        "User connection count exceeds alert threshold"
+                    );
+                }
+            });
+        }
 
-        // Create Redis circuit breaker and health tracker (shared across all Redis operations)
+        // Create Redis circuit breakers and health tracker: one breaker for the Pub/Sub
+        // subscriber, and separate read and write breakers for pool operations
         let cb_config = CircuitBreakerConfig {
             failure_threshold: settings.redis.circuit_breaker_failure_threshold,
             success_threshold: settings.redis.circuit_breaker_success_threshold,
             reset_timeout_ms: settings.redis.circuit_breaker_reset_timeout_seconds * 1000,
         };
-        let redis_circuit_breaker = Arc::new(CircuitBreaker::with_config(cb_config));
+        let redis_circuit_breaker = Arc::new(CircuitBreaker::with_config(cb_config.clone()));
+        let redis_pool_circuit_breaker = Arc::new(BulkheadCircuitBreaker::with_config(cb_config));
 
         // Create Redis pool if Redis backend is needed for queue, ACK tracking, or cluster mode
-        let needs_redis = (settings.queue.enabled && settings.queue.backend == "redis")
-            || (settings.ack.enabled && settings.ack.backend == "redis")
-            || settings.cluster.enabled;
+        let needs_redis = (queue_config.enabled && queue_config.backend == "redis")
+            || (ack_enabled && settings.ack.backend == "redis")
+            || cluster_enabled;
         let redis_health = Arc::new(RedisHealth::new_with_enabled(needs_redis));
-        let redis_pool = if needs_redis {
-            match RedisPool::new(
-                settings.redis.clone(),
-                redis_circuit_breaker.clone(),
-                redis_health.clone(),
-            ) {
+        let startup_timeout = Duration::from_secs(settings.server.startup_timeout_seconds);
+        let redis_command_timeout = Duration::from_millis(settings.websocket.redis_command_timeout_ms);
+        let mut failures = StartupFailures::default();
+
+        // Connect eagerly so an unreachable Redis is detected now rather than on first use
+        let (redis_pool, redis_status) = if needs_redis {
+            let connected = with_startup_timeout(startup_timeout, async {
+                let pool = RedisPool::new(
+                    settings.redis.clone(),
+                    redis_pool_circuit_breaker.clone(),
+                    redis_health.clone(),
+                )?
+                .with_command_timeout(redis_command_timeout);
+                pool.ping().await?;
+                Ok::<_, PoolError>(pool)
+            })
+            .await;
+            match connected {
                 Ok(pool) => {
                     tracing::info!(url = %settings.redis.url, "Redis pool created for persistence backends");
-                    Some(Arc::new(pool))
+                    (Some(Arc::new(pool)), StartupStatus::Ready)
                 }
                 Err(e) => {
-                    if settings.is_production {
-                        bail!(
-                            "Redis pool creation failed in production mode (required by configured backends): {}",
-                            e
-                        );
-                    }
-                    tracing::error!(
+                    tracing::warn!(
                         error = %e,
-                        "Failed to create Redis pool, falling back to memory backends"
+                        "Redis unavailable at startup, falling back to memory backends"
                     );
-                    None
+                    failures.record("redis", e.clone());
+                    (None, StartupStatus::Degraded(e))
                 }
             }
         } else {
-            None
+            (None, StartupStatus::Disabled)
         };
 
         // Create PostgreSQL pool if PostgreSQL backend is needed for queue or ACK tracking
-        let needs_postgres =
-            settings.queue.backend == "postgres" || settings.ack.backend == "postgres";
-        let postgres_pool = if needs_postgres && !settings.database.url.is_empty() {
-            match PostgresPool::new(&settings.database, redis_circuit_breaker.clone()).await {
-                Ok(pool) => {
-                    tracing::info!("PostgreSQL pool created for persistence backends");
-                    Some(Arc::new(pool))
-                }
-                Err(e) => {
-                    if settings.is_production {
-                        bail!(
-                            "PostgreSQL pool creation failed in production mode (required by configured backends): {}",
-                            e
+        let needs_postgres = (features.message_queue && queue_config.backend == "postgres")
+            || (features.ack_tracking && settings.ack.backend == "postgres");
+        let postgres_circuit_breaker = Arc::new(PostgresCircuitBreaker::new());
+        let postgres_health = Arc::new(PostgresHealth::new_with_enabled(needs_postgres));
+        let (postgres_pool, mut postgres_status) =
+            if needs_postgres && !settings.database.url.is_empty() {
+                let connected = with_startup_timeout(
+                    startup_timeout,
+                    PostgresPool::new(
+                        &settings.database,
+                        postgres_circuit_breaker.clone(),
+                        postgres_health.clone(),
+                    ),
+                )
+                .await;
+                match connected {
+                    Ok(pool) => {
+                        tracing::info!("PostgreSQL pool created for persistence backends");
+                        (Some(Arc::new(pool)), StartupStatus::Ready)
+                    }
+                    Err(e) => {
+                        tracing::warn!(
+                            error = %e,
+                            "PostgreSQL unavailable at startup, falling back to memory backends"
                         );
+                        failures.record("postgres", e.clone());
+                        (None, StartupStatus::Degraded(e))
                     }
-                    tracing::error!(
-                        error = %e,
-                        "Failed to create PostgreSQL pool, falling back to memory backends"
-                    );
-                    None
                 }
-            }
-        } else {
-            None
-        };
+            } else {
+                (None, StartupStatus::Disabled)
+            };
 
         // Create persistent queue backend (memory, Redis, or PostgreSQL)
         let queue_backend = create_queue_backend(
-            &settings.queue,
+            &queue_config,
             redis_pool.clone(),
             postgres_pool.clone(),
             None,
         );
 
+        // Bring the schema up to date before the PostgreSQL ACK backend uses it
+        if ack_enabled && settings.ack.backend == "postgres" {
+            if let Some(ref pool) = postgres_pool {
+                let migrated = with_startup_timeout(
+                    startup_timeout,
+                    PostgresAckBackend::run_migrations(pool.pool()),
+                )
+                .await;
+                if let Err(e) = migrated {
+                    tracing::error!(error = %e, "Failed to run ACK schema migrations");
+                    failures.record("postgres_migrations", e.clone());
+                    postgres_status = StartupStatus::Degraded(format!("migrations: {}", e));
+                }
+            }
+        }
+
+        if settings.is_production {
+            failures.into_result()?;
+        }
+
         // Create persistent ACK backend (memory, Redis, or PostgreSQL)
-        let ack_backend = create_ack_backend(
-            &settings.ack,
-            redis_pool.clone(),
-            postgres_pool.clone(),
-            None,
-        );
+        let ack_backend: Arc<dyn AckTrackerBackend> = if features.ack_tracking {
+            create_ack_backend(&settings.ack, redis_pool.clone(), postgres_pool.clone(), None)
+        } else {
+            Arc::new(NoopAckBackend::new())
+        };
 
         // Create session store for cluster mode
-        let session_store = create_session_store(&settings.cluster, redis_pool.clone());
+        let session_store = create_session_store(&cluster_config, redis_pool.clone());
+        let cluster_status = match (cluster_enabled, &redis_pool) {
+            (false, _) => StartupStatus::Disabled,
+            (true, Some(_)) => StartupStatus::Ready,
+            (true, None) => StartupStatus::Degraded("Redis unavailable, local mode".to_string()),
+        };
+
+        // Quarantine malformed Pub/Sub messages in the Redis instance the subscriber reads from
+        // (connects lazily; falls back to memory only if the Redis URL is invalid)
+        let quarantine_pool = redis_pool.clone().or_else(|| {
+            RedisPool::new(
+                settings.redis.clone(),
+                redis_pool_circuit_breaker.clone(),
+                redis_health.clone(),
+            )
+            .ok()
+            .map(|pool| Arc::new(pool.with_command_timeout(redis_command_timeout)))
+        });
+        let quarantine = create_quarantine_store(quarantine_pool);
 
         // Create cluster router for cross-server message delivery
-        let cluster_router = Arc::new(ClusterRouter::new(
-            connection_manager.clone(),
-            session_store.clone(),
-        ));
+        let cluster_router = Arc::new(
+            ClusterRouter::new(connection_manager.clone(), session_store.clone())
+                .with_route_strategy(settings.cluster.route_strategy),
+        );
 
         // Create dispatcher with backend abstractions
-        let dispatcher = Arc::new(NotificationDispatcher::with_backends(
+        let drop_log = Arc::new(DropLog::new());
+        let mut dispatcher = NotificationDispatcher::with_backends(
             connection_manager.clone(),
             queue_backend.clone(),
             ack_backend.clone(),
-        ));
+        )
+        .with_max_fanout_concurrency(settings.websocket.max_fanout_concurrency)
+        .with_send_timeout(Duration::from_millis(
+            settings.websocket.per_connection_send_timeout_ms,
+        ))
+        .with_max_concurrent_dispatches(settings.dispatcher.max_concurrent_dispatches)
+        .with_dispatch_timeout(Duration::from_millis(settings.dispatcher.dispatch_timeout_ms));
+        if settings.dispatcher.queue_fallback_on_offline {
+            dispatcher = dispatcher.with_drop_log(drop_log.clone());
+        }
+        let dispatcher = Arc::new(dispatcher);
 
         // Create rate limiter from config
         let rate_limiter = Arc::new(RateLimiter::new(crate::ratelimit::RateLimitConfig {
-            enabled: settings.ratelimit.enabled,
+            enabled: settings.ratelimit.enabled && features.rate_limiting,
             http_requests_per_second: settings.ratelimit.http_requests_per_second,
             http_burst_size: settings.ratelimit.http_burst_size,
             ws_connections_per_minute: settings.ratelimit.ws_connections_per_minute,
@@ -164,31 +288,196 @@ impl AppState {
             bucket_ttl_seconds: 300, // 5 minutes default
             backend: settings.ratelimit.backend.clone(),
             redis_prefix: settings.ratelimit.redis_prefix.clone(),
+            // Entries are validated in Settings::validate
+            ip_allowlist: parse_cidrs(&settings.ratelimit.ip_allowlist),
+            ip_blocklist: parse_cidrs(&settings.ratelimit.ip_blocklist),
+            emergency_bypass_deny_ratio: settings.ratelimit.emergency_bypass_deny_ratio,
+            emergency_bypass_window_seconds: settings.ratelimit.emergency_bypass_window_seconds,
+            emergency_bypass_min_requests: settings.ratelimit.emergency_bypass_min_requests,
+            rules: settings.ratelimit.rules.clone(),
         }));
 
+        // Register components with expiring entries for the cleanup task
+        let mut cleanables: Vec<Arc<dyn Cleanable>> = vec![dispatcher.dedup_cache()];
+        if rate_limiter.is_enabled() {
+            cleanables.push(rate_limiter.clone());
+        }
+        if queue_backend.is_enabled() {
+            cleanables.push(Arc::new(QueueCleanup::new(
+                queue_backend.clone(),
+                Duration::from_secs(queue_config.cleanup_interval_seconds),
+            )));
+        }
+        if ack_backend.is_enabled() {
+            cleanables.push(Arc::new(AckCleanup::new(ack_backend.clone())));
+        }
+
         // Create template store
         let template_store = Arc::new(TemplateStore::new());
 
         // Create tenant manager
         let tenant_manager = Arc::new(TenantManager::new(settings.tenant.clone()));
 
+        let startup_report = StartupHealthReport {
+            redis: redis_status,
+            postgres: postgres_status,
+            cluster: cluster_status,
+        };
+        startup_report.log();
+
         Ok(Self {
             settings: Arc::new(settings),
             jwt_validator,
+            connection_events: connection_manager.events().clone(),
             connection_manager,
             dispatcher,
             rate_limiter,
             redis_circuit_breaker,
             redis_health,
             redis_pool,
+            postgres_circuit_breaker,
+            postgres_health,
             postgres_pool,
             template_store,
             tenant_manager,
             queue_backend,
+            queue_migration: Arc::new(MigrationProgress::new()),
             ack_backend,
             session_store,
             cluster_router,
+            drop_log,
+            quarantine,
+            cleanables,
+            startup_report,
             start_time: Instant::now(),
         })
     }
 }
+
+/// Lets settings-only extractors (e.g. `AdminAuth`) run against `AppState`
+impl FromRef<AppState> for Arc<Settings> {
+    fn from_ref(state: &AppState) -> Self {
+        state.settings.clone()
+    }
+}
+
+/// Parse configured CIDR strings, skipping invalid entries
+fn parse_cidrs(cidrs: &[String]) -> Vec<ipnetwork::IpNetwork> {
+    cidrs.iter().filter_map(|cidr| cidr.parse().ok()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+
+    use crate::config::FeatureFlags;
+    use tokio::net::TcpListener;
+
+    /// Address of a port nothing listens on, so connections are refused
+    async fn refused_addr() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+        addr.to_string()
+    }
+
+    fn settings_with(overrides: serde_json::Value) -> Settings {
+        let mut config = json!({
+            "jwt": { "secret": "test-secret-key-for-startup-tests" },
+            "server": { "startup_timeout_seconds": 1 }
+        });
+        config
+            .as_object_mut()
+            .unwrap()
+            .extend(overrides.as_object().unwrap().clone());
+        serde_json::from_value(config).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_starts_degraded_when_redis_refuses_connections() {
+        let addr = refused_addr().await;
+        let settings = settings_with(json!({
+            "redis": { "url": format!("redis://{}", addr) },
+            "queue": { "enabled": true, "backend": "redis" },
+            "cluster": { "enabled": true }
+        }));
+
+        let state = AppState::new(settings).await.unwrap();
+
+        assert!(state.redis_pool.is_none());
+        assert!(state.startup_report.redis.is_degraded());
+        assert!(state.startup_report.cluster.is_degraded());
+        assert_eq!(state.startup_report.postgres, StartupStatus::Disabled);
+        assert!(!state.session_store.is_enabled());
+    }
+
+    #[tokio::test]
+    async fn test_unresponsive_redis_hits_startup_timeout() {
+        // Accepts TCP connections but never answers
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let settings = settings_with(json!({
+            "redis": { "url": format!("redis://{}", listener.local_addr().unwrap()) },
+            "ack": { "enabled": true, "backend": "redis" }
+        }));
+
+        let started = Instant::now();
+        let state = AppState::new(settings).await.unwrap();
+
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert_eq!(
+            state.startup_report.redis,
+            StartupStatus::Degraded("timed out after 1s".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_feature_flags_override_configuration() {
+        let mut settings = settings_with(json!({
+            "ack": { "enabled": true },
+            "queue": { "enabled": true },
+            "ratelimit": { "enabled": true },
+            "cluster": { "enabled": true }
+        }));
+        settings.features = FeatureFlags {
+            ack_tracking: false,
+            cluster: false,
+            rate_limiting: false,
+            message_queue: false,
+            sse: true,
+        };
+
+        let state = AppState::new(settings).await.unwrap();
+
+        assert!(!state.ack_backend.is_enabled());
+        assert_eq!(state.ack_backend.stats().await.backend_type, "noop");
+        assert!(!state.queue_backend.is_enabled());
+        assert!(!state.rate_limiter.is_enabled());
+        assert!(!state.session_store.is_enabled());
+        // Only the always-on send deduplication cache needs sweeping
+        let components: Vec<_> = state.cleanables.iter().map(|c| c.component()).collect();
+        assert_eq!(components, vec!["dedup"]);
+        // Cluster was switched off, so Redis was never required
+        assert_eq!(state.startup_report.redis, StartupStatus::Disabled);
+        assert_eq!(state.startup_report.cluster, StartupStatus::Disabled);
+    }
+
+    #[tokio::test]
+    async fn test_production_reports_every_failed_dependency() {
+        let redis_addr = refused_addr().await;
+        let postgres_addr = refused_addr().await;
+        let mut settings = settings_with(json!({
+            "redis": { "url": format!("redis://{}", redis_addr) },
+            "queue": { "enabled": true, "backend": "redis" },
+            "ack": { "enabled": true, "backend": "postgres" },
+            "database": { "url": format!("postgres://ara@{}/ara", postgres_addr) }
+        }));
+        settings.is_production = true;
+
+        let err = AppState::new(settings).await.err().expect("startup should fail");
+        let dependencies: Vec<_> = err.failures.iter().map(|f| f.dependency).collect();
+        assert_eq!(dependencies, vec!["redis", "postgres"]);
+        assert!(err.to_string().contains("redis"));
+    }
+}
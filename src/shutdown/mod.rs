@@ -1,20 +1,28 @@
 //! Graceful shutdown handling for the notification service.
 //!
 //! This module provides coordinated shutdown functionality that:
+//! 0. Runs pre-shutdown hooks registered by other modules
 //! 1. Notifies all connected clients about the impending shutdown
 //! 2. Waits for in-flight messages to be processed
 //! 3. Flushes queued messages to persistent storage (if enabled)
-//! 4. Cleans up resources in the correct order
+//! 4. Persists rate limiter buckets so clients cannot burst right after a restart
+//! 5. Cleans up resources in the correct order
+//!
+//! Each phase is timed, and the result can be written as JSON for post-mortems.
 
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
+use async_trait::async_trait;
 use futures::stream::{FuturesUnordered, StreamExt};
+use serde::{Serialize, Serializer};
 use tokio::sync::broadcast;
 use tokio::time::timeout;
 
 use crate::connection_manager::ConnectionManager;
 use crate::queue::MessageQueueBackend;
+use crate::ratelimit::{default_state_path, RateLimiter};
 use crate::websocket::ServerMessage;
 
 /// Configuration for graceful shutdown behavior
@@ -28,6 +36,13 @@ pub struct ShutdownConfig {
     pub queue_flush_timeout: Duration,
     /// Suggested reconnect delay to send to clients (default: 5 seconds)
     pub reconnect_after_seconds: u64,
+    /// Where rate limiter state is written (default: a private directory under the
+    /// system temp directory, see `default_state_path`)
+    pub rate_limit_state_path: PathBuf,
+    /// Where the shutdown result is written as JSON, if anywhere (default: not written)
+    pub result_output_path: Option<PathBuf>,
+    /// Time each pre-shutdown hook may take (default: 5 seconds)
+    pub hook_timeout: Duration,
 }
 
 impl Default for ShutdownConfig {
@@ -37,14 +52,27 @@ impl Default for ShutdownConfig {
             drain_timeout: Duration::from_secs(10),
             queue_flush_timeout: Duration::from_secs(15),
             reconnect_after_seconds: 5,
+            rate_limit_state_path: default_state_path(),
+            result_output_path: None,
+            hook_timeout: Duration::from_secs(5),
         }
     }
 }
 
+/// Cleanup that a module needs to run before clients are told about the shutdown,
+/// such as flushing buffered state
+#[async_trait]
+pub trait ShutdownHook: Send + Sync {
+    async fn on_shutdown(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+}
+
 /// Handles graceful shutdown of the notification service
 pub struct GracefulShutdown {
     connection_manager: Arc<ConnectionManager>,
     queue_backend: Arc<dyn MessageQueueBackend>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    /// Pre-shutdown hooks in registration order
+    hooks: Vec<(String, Arc<dyn ShutdownHook>)>,
     shutdown_tx: broadcast::Sender<()>,
     config: ShutdownConfig,
 }
@@ -59,6 +87,8 @@ impl GracefulShutdown {
         Self {
             connection_manager,
             queue_backend,
+            rate_limiter: None,
+            hooks: Vec::new(),
             shutdown_tx,
             config: ShutdownConfig::default(),
         }
@@ -74,11 +104,25 @@ impl GracefulShutdown {
         Self {
             connection_manager,
             queue_backend,
+            rate_limiter: None,
+            hooks: Vec::new(),
             shutdown_tx,
             config,
         }
     }
 
+    /// Persist the rate limiter's buckets during shutdown
+    pub fn with_rate_limiter(mut self, rate_limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    /// Run `hook` before clients are notified. Hooks run in registration order, each
+    /// limited to `hook_timeout`; a failing hook is logged and does not stop shutdown.
+    pub fn register_pre_shutdown_hook(&mut self, name: String, hook: Arc<dyn ShutdownHook>) {
+        self.hooks.push((name, hook));
+    }
+
     /// Execute graceful shutdown sequence
     ///
     /// Returns a ShutdownResult with details about the shutdown process
@@ -90,24 +134,48 @@ impl GracefulShutdown {
         )
     )]
     pub async fn execute(&self, reason: &str) -> ShutdownResult {
-        let start = std::time::Instant::now();
+        let start = Instant::now();
         let mut result = ShutdownResult::default();
 
+        // Phase 0: Let registered modules clean up while everything is still running
+        if !self.hooks.is_empty() {
+            tracing::info!(hooks = self.hooks.len(), "Phase 0: Running pre-shutdown hooks");
+        }
+        let phase_start = Instant::now();
+        let timed_out = self.run_hooks(&mut result).await;
+        result.record_phase("pre_shutdown_hooks", phase_start, !timed_out);
+
         // Phase 1: Notify all connected clients
         tracing::info!(reason = %reason, "Starting graceful shutdown - Phase 1: Notifying clients");
-        result.clients_notified = self.notify_clients(reason).await;
+        let phase_start = Instant::now();
+        let (clients_notified, completed) = self.notify_clients(reason).await;
+        result.clients_notified = clients_notified;
+        result.record_phase("notify_clients", phase_start, completed);
 
         // Phase 2: Signal background tasks to stop
         tracing::info!("Phase 2: Signaling background tasks to stop");
+        let phase_start = Instant::now();
         let _ = self.shutdown_tx.send(());
+        result.record_phase("signal_tasks", phase_start, true);
 
         // Phase 3: Wait for message queues to drain
         tracing::info!("Phase 3: Draining message queues");
+        let phase_start = Instant::now();
         result.queue_drained = self.drain_queues().await;
+        result.record_phase("drain_queues", phase_start, result.queue_drained);
 
         // Phase 4: Wait briefly for connections to close gracefully
         tracing::info!("Phase 4: Waiting for connections to close");
-        result.connections_closed = self.wait_for_connections_to_close().await;
+        let phase_start = Instant::now();
+        let (connections_closed, completed) = self.wait_for_connections_to_close().await;
+        result.connections_closed = connections_closed;
+        result.record_phase("close_connections", phase_start, completed);
+
+        // Phase 5: Persist rate limiter state for the next process
+        tracing::info!("Phase 5: Persisting rate limiter state");
+        let phase_start = Instant::now();
+        result.rate_limit_buckets_saved = self.save_rate_limiter_state();
+        result.record_phase("save_rate_limiter_state", phase_start, true);
 
         result.duration = start.elapsed();
         result.success = true;
@@ -116,20 +184,65 @@ impl GracefulShutdown {
             clients_notified = result.clients_notified,
             connections_closed = result.connections_closed,
             queue_drained = result.queue_drained,
+            rate_limit_buckets_saved = result.rate_limit_buckets_saved,
+            hooks_completed = result.hooks_completed,
+            hooks_failed = result.hooks_failed,
+            phases_completed = result.phases_completed,
+            phases_skipped = result.phases_skipped,
             duration_ms = result.duration.as_millis(),
             "Graceful shutdown completed"
         );
 
+        if let Some(path) = &self.config.result_output_path {
+            match result.write_json(path) {
+                Ok(()) => tracing::info!(path = %path.display(), "Shutdown result written"),
+                Err(e) => tracing::warn!(
+                    error = %e,
+                    path = %path.display(),
+                    "Failed to write shutdown result"
+                ),
+            }
+        }
+
         result
     }
 
-    /// Notify all connected clients about shutdown
-    async fn notify_clients(&self, reason: &str) -> usize {
+    /// Run the pre-shutdown hooks in order, counting outcomes in `result`.
+    /// Returns whether any hook timed out.
+    async fn run_hooks(&self, result: &mut ShutdownResult) -> bool {
+        let mut timed_out = false;
+        for (name, hook) in &self.hooks {
+            match timeout(self.config.hook_timeout, hook.on_shutdown()).await {
+                Ok(Ok(())) => {
+                    tracing::debug!(hook = %name, "Pre-shutdown hook completed");
+                    result.hooks_completed += 1;
+                }
+                Ok(Err(e)) => {
+                    tracing::warn!(hook = %name, error = %e, "Pre-shutdown hook failed");
+                    result.hooks_failed += 1;
+                }
+                Err(_) => {
+                    tracing::warn!(
+                        hook = %name,
+                        timeout_ms = self.config.hook_timeout.as_millis() as u64,
+                        "Pre-shutdown hook timed out"
+                    );
+                    result.hooks_failed += 1;
+                    timed_out = true;
+                }
+            }
+        }
+        timed_out
+    }
+
+    /// Notify all connected clients about shutdown.
+    /// Returns how many were notified and whether all sends finished before the timeout.
+    async fn notify_clients(&self, reason: &str) -> (usize, bool) {
         let connections = self.connection_manager.get_all_connections();
         let total = connections.len();
 
         if total == 0 {
-            return 0;
+            return (0, true);
         }
 
         tracing::info!(
@@ -174,7 +287,9 @@ impl GracefulShutdown {
             }
         };
 
-        let _ = timeout(self.config.client_notification_timeout, notify_future).await;
+        let completed = timeout(self.config.client_notification_timeout, notify_future)
+            .await
+            .is_ok();
 
         tracing::info!(
             notified = notified,
@@ -182,7 +297,7 @@ impl GracefulShutdown {
             "Shutdown notifications sent"
         );
 
-        notified
+        (notified, completed)
     }
 
     /// Drain message queues
@@ -232,11 +347,34 @@ impl GracefulShutdown {
         }
     }
 
-    /// Wait for connections to close gracefully
-    async fn wait_for_connections_to_close(&self) -> usize {
+    /// Write rate limiter buckets to disk, returning how many were saved
+    fn save_rate_limiter_state(&self) -> usize {
+        let Some(rate_limiter) = self.rate_limiter.as_ref().filter(|r| r.is_enabled()) else {
+            return 0;
+        };
+
+        match rate_limiter.save_state(&self.config.rate_limit_state_path) {
+            Ok(saved) => {
+                tracing::info!(
+                    buckets = saved,
+                    path = %self.config.rate_limit_state_path.display(),
+                    "Rate limiter state saved"
+                );
+                saved
+            }
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to save rate limiter state");
+                0
+            }
+        }
+    }
+
+    /// Wait for connections to close gracefully.
+    /// Returns how many closed and whether all closed before the timeout.
+    async fn wait_for_connections_to_close(&self) -> (usize, bool) {
         let initial = self.connection_manager.stats().total_connections;
         if initial == 0 {
-            return 0;
+            return (0, true);
         }
 
         let closed = std::sync::atomic::AtomicUsize::new(0);
@@ -251,7 +389,7 @@ impl GracefulShutdown {
             }
         };
 
-        let _ = timeout(self.config.drain_timeout, wait_future).await;
+        let completed = timeout(self.config.drain_timeout, wait_future).await.is_ok();
 
         let final_count = self.connection_manager.stats().total_connections;
         let total_closed = initial - final_count;
@@ -263,12 +401,12 @@ impl GracefulShutdown {
             );
         }
 
-        total_closed
+        (total_closed, completed)
     }
 }
 
 /// Result of a graceful shutdown operation
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Serialize)]
 pub struct ShutdownResult {
     /// Whether shutdown completed successfully
     pub success: bool,
@@ -278,8 +416,62 @@ pub struct ShutdownResult {
     pub connections_closed: usize,
     /// Whether the message queue was fully drained
     pub queue_drained: bool,
+    /// Number of rate limiter buckets written for the next start
+    pub rate_limit_buckets_saved: usize,
+    /// Pre-shutdown hooks that finished successfully
+    pub hooks_completed: usize,
+    /// Pre-shutdown hooks that returned an error or timed out
+    pub hooks_failed: usize,
     /// Total time taken for shutdown
+    #[serde(rename = "duration_ms", serialize_with = "serialize_millis")]
     pub duration: Duration,
+    /// Name and duration of each phase, in execution order
+    #[serde(serialize_with = "serialize_phase_timings")]
+    pub phase_timings: Vec<(String, Duration)>,
+    /// Phases that finished within their timeout
+    pub phases_completed: usize,
+    /// Phases cut short by their timeout
+    pub phases_skipped: usize,
+}
+
+impl ShutdownResult {
+    /// Record a finished phase that started at `started`
+    fn record_phase(&mut self, name: &str, started: Instant, completed: bool) {
+        self.phase_timings.push((name.to_string(), started.elapsed()));
+        if completed {
+            self.phases_completed += 1;
+        } else {
+            self.phases_skipped += 1;
+        }
+    }
+
+    /// Write the result as pretty-printed JSON
+    pub fn write_json(&self, path: &Path) -> std::io::Result<()> {
+        let json = serde_json::to_vec_pretty(self).map_err(std::io::Error::other)?;
+        std::fs::write(path, json)
+    }
+}
+
+fn serialize_millis<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f64(duration.as_secs_f64() * 1000.0)
+}
+
+/// Phase timings as `[{"phase": ..., "duration_ms": ...}]`
+fn serialize_phase_timings<S: Serializer>(
+    timings: &[(String, Duration)],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    #[derive(Serialize)]
+    struct PhaseTiming<'a> {
+        phase: &'a str,
+        #[serde(serialize_with = "serialize_millis")]
+        duration_ms: &'a Duration,
+    }
+
+    serializer.collect_seq(timings.iter().map(|(phase, duration)| PhaseTiming {
+        phase,
+        duration_ms: duration,
+    }))
 }
 
 #[cfg(test)]
@@ -309,6 +501,147 @@ mod tests {
         assert_eq!(result.connections_closed, 0);
     }
 
+    #[tokio::test]
+    async fn test_shutdown_saves_rate_limiter_state() {
+        let (cm, queue_backend, tx) = create_test_components();
+        let rate_limiter = Arc::new(RateLimiter::new(crate::ratelimit::RateLimitConfig {
+            enabled: true,
+            ..Default::default()
+        }));
+        rate_limiter.check_key("api-key");
+
+        let dir = std::env::temp_dir()
+            .join(format!("ara-shutdown-ratelimit-{}", uuid::Uuid::new_v4()));
+        let path = crate::ratelimit::state_path_in(&dir);
+        let config = ShutdownConfig {
+            rate_limit_state_path: path.clone(),
+            ..Default::default()
+        };
+        let shutdown = GracefulShutdown::with_config(cm, queue_backend, tx, config)
+            .with_rate_limiter(rate_limiter);
+
+        let result = shutdown.execute("test shutdown").await;
+        assert_eq!(result.rate_limit_buckets_saved, 1);
+        assert!(path.exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_records_phase_timings() {
+        let (cm, queue_backend, tx) = create_test_components();
+        let shutdown = GracefulShutdown::new(cm, queue_backend, tx);
+
+        let result = shutdown.execute("test shutdown").await;
+
+        let phases: Vec<&str> = result.phase_timings.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(
+            phases,
+            vec![
+                "pre_shutdown_hooks",
+                "notify_clients",
+                "signal_tasks",
+                "drain_queues",
+                "close_connections",
+                "save_rate_limiter_state"
+            ]
+        );
+        assert_eq!(result.phases_completed, 6);
+        assert_eq!(result.phases_skipped, 0);
+
+        // Phases run back to back, so their sum accounts for nearly all of the duration
+        let phase_total: Duration = result.phase_timings.iter().map(|(_, d)| *d).sum();
+        assert!(phase_total <= result.duration);
+        assert!(result.duration - phase_total < Duration::from_millis(50));
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_skips_timed_out_phases_and_writes_result() {
+        let (cm, queue_backend, tx) = create_test_components();
+        // A connection that never closes makes the close phase time out
+        let (sender, _receiver) = tokio::sync::mpsc::channel(8);
+        cm.register("user-1".to_string(), "default".to_string(), vec![], sender)
+            .unwrap();
+
+        let path = std::env::temp_dir()
+            .join(format!("ara-shutdown-result-{}.json", uuid::Uuid::new_v4()));
+        let config = ShutdownConfig {
+            drain_timeout: Duration::from_millis(200),
+            result_output_path: Some(path.clone()),
+            ..Default::default()
+        };
+        let shutdown = GracefulShutdown::with_config(cm, queue_backend, tx, config);
+
+        let result = shutdown.execute("test shutdown").await;
+        assert_eq!(result.phases_completed, 5);
+        assert_eq!(result.phases_skipped, 1);
+
+        let written: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(written["success"], true);
+        assert_eq!(written["phases_skipped"], 1);
+        assert_eq!(written["phase_timings"].as_array().unwrap().len(), 6);
+        assert_eq!(written["phase_timings"][4]["phase"], "close_connections");
+        assert!(written["phase_timings"][4]["duration_ms"].as_f64().unwrap() >= 200.0);
+        assert!(written["duration_ms"].as_f64().unwrap() >= 200.0);
+    }
+
+    /// Hook that records its name when run, then fails or stalls as configured
+    struct RecordingHook {
+        name: &'static str,
+        order: Arc<std::sync::Mutex<Vec<&'static str>>>,
+        fail: bool,
+        delay: Duration,
+    }
+
+    #[async_trait]
+    impl ShutdownHook for RecordingHook {
+        async fn on_shutdown(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+            self.order.lock().unwrap().push(self.name);
+            tokio::time::sleep(self.delay).await;
+            if self.fail {
+                return Err("flush failed".into());
+            }
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pre_shutdown_hooks_run_in_order_before_clients_are_notified() {
+        let (cm, queue_backend, tx) = create_test_components();
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(8);
+        cm.register("user-1".to_string(), "default".to_string(), vec![], sender)
+            .unwrap();
+
+        let config = ShutdownConfig {
+            hook_timeout: Duration::from_millis(100),
+            drain_timeout: Duration::from_millis(100),
+            ..Default::default()
+        };
+        let mut shutdown = GracefulShutdown::with_config(cm, queue_backend, tx, config);
+        let order = Arc::new(std::sync::Mutex::new(Vec::new()));
+        for (name, fail, delay) in [
+            ("audit", false, Duration::ZERO),
+            ("broken", true, Duration::ZERO),
+            ("stalled", false, Duration::from_secs(10)),
+            ("metrics", false, Duration::ZERO),
+        ] {
+            let hook = RecordingHook { name, order: order.clone(), fail, delay };
+            shutdown.register_pre_shutdown_hook(name.to_string(), Arc::new(hook));
+        }
+
+        let result = shutdown.execute("test shutdown").await;
+
+        // Failing and timed out hooks don't stop the remaining hooks or the shutdown
+        assert_eq!(*order.lock().unwrap(), vec!["audit", "broken", "stalled", "metrics"]);
+        assert_eq!(result.hooks_completed, 2);
+        assert_eq!(result.hooks_failed, 2);
+        assert!(result.success);
+        assert_eq!(result.clients_notified, 1);
+        assert!(receiver.try_recv().is_ok());
+        assert_eq!(result.phase_timings[0].0, "pre_shutdown_hooks");
+    }
+
     #[test]
     fn test_shutdown_config_defaults() {
         let config = ShutdownConfig::default();
@@ -0,0 +1,256 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use tokio::sync::broadcast;
+
+use crate::metrics::CLEANUP_ITEMS_REMOVED_TOTAL;
+use crate::notification::{AckTrackerBackend, DeduplicationCache};
+use crate::queue::MessageQueueBackend;
+use crate::ratelimit::RateLimiter;
+
+/// A component holding entries that expire and must be swept periodically
+#[async_trait]
+pub trait Cleanable: Send + Sync {
+    /// Component label used in metrics and logs
+    fn component(&self) -> &'static str;
+
+    /// How often the component wants to be swept
+    fn cleanup_interval(&self) -> Duration;
+
+    /// Remove expired entries, returning how many were removed
+    async fn cleanup(&self) -> usize;
+}
+
+#[async_trait]
+impl Cleanable for RateLimiter {
+    fn component(&self) -> &'static str {
+        "ratelimit"
+    }
+
+    fn cleanup_interval(&self) -> Duration {
+        Duration::from_secs(self.config().cleanup_interval_seconds)
+    }
+
+    async fn cleanup(&self) -> usize {
+        self.cleanup_stale()
+    }
+}
+
+/// Sweeps expired messages from the offline message queue
+pub struct QueueCleanup {
+    backend: Arc<dyn MessageQueueBackend>,
+    interval: Duration,
+}
+
+impl QueueCleanup {
+    pub fn new(backend: Arc<dyn MessageQueueBackend>, interval: Duration) -> Self {
+        Self { backend, interval }
+    }
+}
+
+#[async_trait]
+impl Cleanable for QueueCleanup {
+    fn component(&self) -> &'static str {
+        "queue"
+    }
+
+    fn cleanup_interval(&self) -> Duration {
+        self.interval
+    }
+
+    async fn cleanup(&self) -> usize {
+        match self.backend.cleanup_expired().await {
+            Ok(removed) => removed,
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to clean up expired queued messages");
+                0
+            }
+        }
+    }
+}
+
+/// Expires pending ACKs that were never acknowledged
+pub struct AckCleanup {
+    backend: Arc<dyn AckTrackerBackend>,
+}
+
+impl AckCleanup {
+    pub fn new(backend: Arc<dyn AckTrackerBackend>) -> Self {
+        Self { backend }
+    }
+}
+
+#[async_trait]
+impl Cleanable for AckCleanup {
+    fn component(&self) -> &'static str {
+        "ack"
+    }
+
+    fn cleanup_interval(&self) -> Duration {
+        Duration::from_secs(self.backend.cleanup_interval_seconds())
+    }
+
+    async fn cleanup(&self) -> usize {
+        self.backend.cleanup_expired().await
+    }
+}
+
+#[async_trait]
+impl Cleanable for DeduplicationCache {
+    fn component(&self) -> &'static str {
+        "dedup"
+    }
+
+    fn cleanup_interval(&self) -> Duration {
+        Duration::from_secs(60)
+    }
+
+    async fn cleanup(&self) -> usize {
+        self.cleanup_expired()
+    }
+}
+
+/// Background task that sweeps every registered [`Cleanable`] component
+pub struct CleanupTask {
+    components: Vec<Arc<dyn Cleanable>>,
+    shutdown: broadcast::Receiver<()>,
+}
+
+impl CleanupTask {
+    pub fn new(components: Vec<Arc<dyn Cleanable>>, shutdown: broadcast::Receiver<()>) -> Self {
+        Self {
+            components,
+            shutdown,
+        }
+    }
+
+    /// Shortest interval requested by any component (at least one second)
+    fn interval(&self) -> Option<Duration> {
+        self.components
+            .iter()
+            .map(|c| c.cleanup_interval().max(Duration::from_secs(1)))
+            .min()
+    }
+
+    /// Run the sweeps until shutdown
+    pub async fn run(mut self) {
+        let Some(interval) = self.interval() else {
+            tracing::info!("No cleanable components registered, cleanup task not started");
+            return;
+        };
+
+        let mut timer = tokio::time::interval(interval);
+        // Skip immediate first tick
+        timer.tick().await;
+
+        tracing::info!(
+            interval_secs = interval.as_secs(),
+            components = ?self.components.iter().map(|c| c.component()).collect::<Vec<_>>(),
+            "Cleanup task started"
+        );
+
+        loop {
+            tokio::select! {
+                _ = self.shutdown.recv() => {
+                    tracing::info!("Cleanup task received shutdown signal");
+                    break;
+                }
+                _ = timer.tick() => {
+                    self.cleanup_all().await;
+                }
+            }
+        }
+
+        tracing::info!("Cleanup task stopped");
+    }
+
+    /// Sweep every component once, returning the total number of removed items
+    async fn cleanup_all(&self) -> usize {
+        let mut total = 0;
+        for component in &self.components {
+            let removed = component.cleanup().await;
+            CLEANUP_ITEMS_REMOVED_TOTAL
+                .with_label_values(&[component.component()])
+                .inc_by(removed as u64);
+            tracing::debug!(
+                component = component.component(),
+                removed = removed,
+                "Cleanup sweep completed"
+            );
+            total += removed;
+        }
+        total
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::notification::{AckConfig, MemoryAckBackend, NotificationBuilder};
+    use crate::queue::{MemoryQueueBackend, QueueConfig};
+    use crate::ratelimit::RateLimitConfig;
+
+    #[tokio::test]
+    async fn test_cleanup_all_sweeps_every_component() {
+        let queue_backend = Arc::new(MemoryQueueBackend::new(QueueConfig {
+            enabled: true,
+            message_ttl_seconds: 0, // Immediate expiry
+            ..Default::default()
+        }));
+        queue_backend
+            .enqueue("offline-user", NotificationBuilder::new("test.event", "test").build())
+            .await
+            .unwrap();
+
+        let ack_backend = Arc::new(MemoryAckBackend::new(AckConfig {
+            enabled: true,
+            timeout_seconds: 0,
+            cleanup_interval_seconds: 45,
+        }));
+        ack_backend
+            .track(uuid::Uuid::new_v4(), "user-1", uuid::Uuid::new_v4(), "order.created")
+            .await;
+
+        let rate_limiter = Arc::new(RateLimiter::new(RateLimitConfig {
+            cleanup_interval_seconds: 90,
+            ..Default::default()
+        }));
+
+        let (_shutdown_tx, shutdown_rx) = broadcast::channel(1);
+        let queue_removed_before = CLEANUP_ITEMS_REMOVED_TOTAL
+            .with_label_values(&["queue"])
+            .get();
+        let task = CleanupTask::new(
+            vec![
+                rate_limiter,
+                Arc::new(QueueCleanup::new(queue_backend.clone(), Duration::from_secs(300))),
+                Arc::new(AckCleanup::new(ack_backend.clone())),
+            ],
+            shutdown_rx,
+        );
+
+        assert_eq!(task.interval(), Some(Duration::from_secs(45)));
+        assert_eq!(task.cleanup_all().await, 2);
+        assert_eq!(queue_backend.stats().await.total_messages, 0);
+        assert_eq!(ack_backend.pending_count().await, 0);
+        assert!(
+            CLEANUP_ITEMS_REMOVED_TOTAL
+                .with_label_values(&["queue"])
+                .get()
+                > queue_removed_before
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_task_without_components_exits() {
+        let (_shutdown_tx, shutdown_rx) = broadcast::channel(1);
+        let task = CleanupTask::new(Vec::new(), shutdown_rx);
+
+        assert_eq!(task.interval(), None);
+        tokio::time::timeout(Duration::from_secs(1), task.run())
+            .await
+            .expect("cleanup task without components should return immediately");
+    }
+}
@@ -1,5 +1,5 @@
 use std::sync::Arc;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::time::{Duration, Instant};
 
 use futures::future::join_all;
@@ -7,19 +7,26 @@ use tokio::sync::broadcast;
 
 use crate::cluster::SessionStore;
 use crate::config::WebSocketConfig;
-use crate::connection_manager::ConnectionManager;
-use crate::metrics::{HeartbeatMetrics, MemoryMetrics};
+use crate::connection_manager::{ConnectionHandle, ConnectionManager, Transport};
+use crate::metrics::{ClusterMetrics, HeartbeatMetrics, MemoryMetrics};
 use crate::websocket::{OutboundMessage, ServerMessage};
 
 /// Maximum concurrent heartbeat sends to avoid overwhelming the system
 const MAX_CONCURRENT_HEARTBEATS: usize = 1000;
 
+/// Minimum lifetime of the cluster metrics leader lock
+const METRICS_LEADER_TTL_SECS: u64 = 60;
+
 /// Background task for heartbeat and connection cleanup
 pub struct HeartbeatTask {
     config: WebSocketConfig,
     connection_manager: Arc<ConnectionManager>,
     session_store: Arc<dyn SessionStore>,
     shutdown: broadcast::Receiver<()>,
+    /// Dispatcher's sent-notification counter, used to detect idle periods
+    dispatch_counter: Option<Arc<AtomicU64>>,
+    /// Store used to elect the single server that refreshes cluster-wide metrics
+    leader_election: Option<Arc<dyn SessionStore>>,
 }
 
 impl HeartbeatTask {
@@ -34,12 +41,29 @@ impl HeartbeatTask {
             connection_manager,
             session_store,
             shutdown,
+            dispatch_counter: None,
+            leader_election: None,
         }
     }
 
+    /// Observe dispatch activity so adaptive heartbeats reset when messages are sent
+    pub fn with_dispatch_counter(mut self, counter: Arc<AtomicU64>) -> Self {
+        self.dispatch_counter = Some(counter);
+        self
+    }
+
+    /// Only refresh cluster-wide metrics while this server holds the metrics leader
+    /// lock in `session_store`, so the cluster is scanned once per round instead of
+    /// once per server
+    pub fn with_leader_election(mut self, session_store: Arc<dyn SessionStore>) -> Self {
+        self.leader_election = Some(session_store);
+        self
+    }
+
     /// Run the heartbeat and cleanup tasks
     pub async fn run(mut self) {
         let heartbeat_interval = Duration::from_secs(self.config.heartbeat_interval);
+        let idle_interval = Duration::from_secs(self.config.heartbeat_idle_interval);
         let cleanup_interval = Duration::from_secs(self.config.cleanup_interval);
         let connection_timeout = self.config.connection_timeout;
 
@@ -54,9 +78,14 @@ impl HeartbeatTask {
             heartbeat_interval_secs = self.config.heartbeat_interval,
             cleanup_interval_secs = self.config.cleanup_interval,
             connection_timeout_secs = connection_timeout,
+            adaptive = self.config.heartbeat_adaptive,
             "Heartbeat task started"
         );
 
+        let mut current_interval = heartbeat_interval;
+        let mut last_dispatched = self.dispatched_count();
+        HeartbeatMetrics::set_current_interval(current_interval);
+
         loop {
             tokio::select! {
                 _ = self.shutdown.recv() => {
@@ -64,8 +93,35 @@ impl HeartbeatTask {
                     break;
                 }
                 _ = heartbeat_timer.tick() => {
-                    self.send_heartbeats().await;
+                    let failed = self.send_heartbeats().await;
                     self.refresh_cluster_sessions().await;
+                    self.refresh_cluster_metrics().await;
+
+                    if self.config.heartbeat_adaptive {
+                        let dispatched = self.dispatched_count();
+                        let idle = failed == 0 && dispatched == last_dispatched;
+                        last_dispatched = dispatched;
+
+                        let next = next_heartbeat_interval(
+                            current_interval,
+                            heartbeat_interval,
+                            idle_interval,
+                            idle,
+                        );
+                        if next != current_interval {
+                            tracing::debug!(
+                                from_secs = current_interval.as_secs(),
+                                to_secs = next.as_secs(),
+                                "Adjusting heartbeat interval"
+                            );
+                            current_interval = next;
+                            heartbeat_timer = tokio::time::interval_at(
+                                tokio::time::Instant::now() + current_interval,
+                                current_interval,
+                            );
+                            HeartbeatMetrics::set_current_interval(current_interval);
+                        }
+                    }
                 }
                 _ = cleanup_timer.tick() => {
                     self.cleanup_stale_connections(connection_timeout).await;
@@ -76,13 +132,22 @@ impl HeartbeatTask {
         tracing::info!("Heartbeat task stopped");
     }
 
-    /// Send heartbeat (ping) to all connections in parallel with batching
-    async fn send_heartbeats(&self) {
+    /// Total notifications dispatched so far (0 when no counter is attached)
+    fn dispatched_count(&self) -> u64 {
+        self.dispatch_counter
+            .as_ref()
+            .map(|c| c.load(Ordering::Relaxed))
+            .unwrap_or(0)
+    }
+
+    /// Send heartbeat (ping) to all connections in parallel with batching.
+    /// Returns the number of connections that could not be pinged.
+    async fn send_heartbeats(&self) -> usize {
         let connections = self.connection_manager.get_all_connections();
         let total_count = connections.len();
 
         if total_count == 0 {
-            return;
+            return 0;
         }
 
         let start = Instant::now();
@@ -104,15 +169,23 @@ impl HeartbeatTask {
                     let msg = heartbeat_msg.clone();
 
                     async move {
+                        // Counted before sending so a fast pong can't be overtaken; WebSocket
+                        // clients clear the count by answering (see `update_activity`)
+                        let missed = handle.record_missed_ping();
                         // send_preserialized() already has an internal 5s timeout
                         match handle.send_preserialized(msg).await {
                             Ok(_) => {
                                 sent.fetch_add(1, Ordering::Relaxed);
+                                // SSE clients can't answer, so delivery is the only signal
+                                if handle.metadata.transport == Transport::Sse {
+                                    handle.reset_missed_pings();
+                                }
                             }
                             Err(_) => {
                                 failed.fetch_add(1, Ordering::Relaxed);
                                 tracing::debug!(
                                     connection_id = %handle.id,
+                                    missed = missed,
                                     "Failed to send heartbeat, connection may be dead or timed out"
                                 );
                             }
@@ -129,6 +202,8 @@ impl HeartbeatTask {
         let sent_count = sent.load(Ordering::Relaxed);
         let failed_count = failed.load(Ordering::Relaxed);
 
+        self.evict_unresponsive(&connections).await;
+
         // Record metrics
         HeartbeatMetrics::record_duration_ms(elapsed_ms);
 
@@ -156,6 +231,36 @@ impl HeartbeatTask {
                 "Heartbeat round took more than 50% of interval"
             );
         }
+
+        failed_count
+    }
+
+    /// Evict connections that have left `max_missed_pings` heartbeats in a row unanswered
+    async fn evict_unresponsive(&self, connections: &[Arc<ConnectionHandle>]) {
+        let max_missed = self.config.max_missed_pings;
+        if max_missed == 0 {
+            return;
+        }
+
+        let mut evicted = 0;
+        for handle in connections.iter().filter(|h| h.missed_pings() >= max_missed) {
+            if self
+                .connection_manager
+                .evict_connection(handle.id, "heartbeat_timeout")
+                .await
+            {
+                evicted += 1;
+            }
+        }
+
+        if evicted > 0 {
+            HeartbeatMetrics::record_evictions(evicted);
+            tracing::info!(
+                evicted = evicted,
+                max_missed_pings = max_missed,
+                "Evicted connections that stopped answering heartbeats"
+            );
+        }
     }
 
     /// Clean up stale connections
@@ -195,12 +300,76 @@ impl HeartbeatTask {
             }
         }
     }
+
+    /// Refresh cluster-wide connection and user counts, skipped when leader election
+    /// is enabled and another server holds the lock
+    async fn refresh_cluster_metrics(&self) {
+        if !self.session_store.is_enabled() {
+            return;
+        }
+
+        if let Some(store) = &self.leader_election {
+            if !self.elect_metrics_leader(store.as_ref()).await {
+                return;
+            }
+        }
+
+        match self.session_store.cluster_connection_count().await {
+            Ok(count) => ClusterMetrics::set_cluster_connections(count),
+            Err(e) => tracing::warn!(error = %e, "Failed to refresh cluster connection count"),
+        }
+        match self.session_store.cluster_user_count().await {
+            Ok(count) => ClusterMetrics::set_cluster_users(count),
+            Err(e) => tracing::warn!(error = %e, "Failed to refresh cluster user count"),
+        }
+    }
+
+    /// Claim or renew the metrics leader lock, returning whether this server holds it
+    async fn elect_metrics_leader(&self, store: &dyn SessionStore) -> bool {
+        let leader = match store.try_acquire_metrics_leader(self.metrics_leader_ttl()).await {
+            Ok(leader) => leader,
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to run cluster metrics leader election");
+                false
+            }
+        };
+
+        ClusterMetrics::set_metrics_leader(leader);
+        leader
+    }
+
+    /// Lock lifetime; covers two of the longest heartbeat rounds so the leader renews
+    /// it before it expires, even when adaptive heartbeats have backed off
+    fn metrics_leader_ttl(&self) -> u64 {
+        let longest_round = if self.config.heartbeat_adaptive {
+            self.config.heartbeat_idle_interval.max(self.config.heartbeat_interval)
+        } else {
+            self.config.heartbeat_interval
+        };
+        METRICS_LEADER_TTL_SECS.max(longest_round * 2)
+    }
+}
+
+/// Compute the next heartbeat interval: double it (up to `idle`) after an idle round,
+/// otherwise fall back to the `base` interval.
+fn next_heartbeat_interval(
+    current: Duration,
+    base: Duration,
+    idle: Duration,
+    idle_round: bool,
+) -> Duration {
+    if idle_round {
+        (current * 2).min(idle.max(base))
+    } else {
+        base
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::cluster::{create_session_store, ClusterConfig};
+    use crate::connection_manager::ConnectionMetadata;
     use crate::websocket::OutboundMessage;
     use tokio::sync::mpsc;
 
@@ -276,4 +445,146 @@ mod tests {
         shutdown_tx.send(()).unwrap();
         let _ = task_handle.await;
     }
+
+    #[test]
+    fn test_next_heartbeat_interval() {
+        let base = Duration::from_secs(30);
+        let idle = Duration::from_secs(100);
+
+        // Idle rounds double the interval up to the idle cap
+        let next = next_heartbeat_interval(base, base, idle, true);
+        assert_eq!(next, Duration::from_secs(60));
+        let next = next_heartbeat_interval(next, base, idle, true);
+        assert_eq!(next, idle);
+        assert_eq!(next_heartbeat_interval(next, base, idle, true), idle);
+
+        // Any activity or failed ping resets to the base interval
+        assert_eq!(next_heartbeat_interval(idle, base, idle, false), base);
+    }
+
+    #[tokio::test]
+    async fn test_unresponsive_connection_evicted_after_missed_pings() {
+        let config = WebSocketConfig {
+            max_missed_pings: 3,
+            ..Default::default()
+        };
+        let connection_manager = Arc::new(ConnectionManager::new());
+        let session_store = create_test_session_store();
+        let (_shutdown_tx, shutdown_rx) = broadcast::channel(1);
+
+        // A client that has gone away: every heartbeat send fails
+        let (tx, rx) = mpsc::channel::<OutboundMessage>(10);
+        drop(rx);
+        let handle = connection_manager
+            .register("user1".to_string(), "default".to_string(), vec![], tx)
+            .unwrap();
+
+        let task = HeartbeatTask::new(config, connection_manager.clone(), session_store, shutdown_rx);
+        let evictions_before = crate::metrics::HEARTBEAT_EVICTIONS_TOTAL.get();
+
+        for expected_misses in 1..3 {
+            assert_eq!(task.send_heartbeats().await, 1);
+            assert_eq!(handle.missed_pings(), expected_misses);
+            assert!(connection_manager.get_connection(handle.id).is_some());
+        }
+
+        task.send_heartbeats().await;
+        assert!(connection_manager.get_connection(handle.id).is_none());
+        assert!(crate::metrics::HEARTBEAT_EVICTIONS_TOTAL.get() > evictions_before);
+    }
+
+    #[tokio::test]
+    async fn test_silent_websocket_evicted_despite_delivered_heartbeats() {
+        let config = WebSocketConfig {
+            max_missed_pings: 3,
+            ..Default::default()
+        };
+        let connection_manager = Arc::new(ConnectionManager::new());
+        let (_shutdown_tx, shutdown_rx) = broadcast::channel(1);
+
+        // Both clients keep their receivers open, so every heartbeat is accepted
+        let (ws_tx, _ws_rx) = mpsc::channel::<OutboundMessage>(10);
+        let silent = connection_manager
+            .register("user1".to_string(), "default".to_string(), vec![], ws_tx)
+            .unwrap();
+        let (sse_tx, _sse_rx) = mpsc::channel::<OutboundMessage>(10);
+        let sse = connection_manager
+            .register_with_metadata(
+                "user2".to_string(),
+                "default".to_string(),
+                vec![],
+                sse_tx,
+                ConnectionMetadata {
+                    transport: Transport::Sse,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        let task = HeartbeatTask::new(
+            config,
+            connection_manager.clone(),
+            create_test_session_store(),
+            shutdown_rx,
+        );
+
+        assert_eq!(task.send_heartbeats().await, 0);
+        assert_eq!(task.send_heartbeats().await, 0);
+        assert_eq!(silent.missed_pings(), 2);
+        assert_eq!(sse.missed_pings(), 0);
+
+        // A pong (or any client frame) clears the count
+        silent.update_activity();
+        assert_eq!(silent.missed_pings(), 0);
+
+        for _ in 0..3 {
+            task.send_heartbeats().await;
+        }
+        assert!(connection_manager.get_connection(silent.id).is_none());
+        assert!(connection_manager.get_connection(sse.id).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_single_server_is_metrics_leader() {
+        let session_store = create_test_session_store();
+        let (_shutdown_tx, shutdown_rx) = broadcast::channel(1);
+        let task = HeartbeatTask::new(
+            WebSocketConfig::default(),
+            Arc::new(ConnectionManager::new()),
+            session_store.clone(),
+            shutdown_rx,
+        )
+        .with_leader_election(session_store.clone());
+
+        assert!(task.elect_metrics_leader(session_store.as_ref()).await);
+        assert_eq!(crate::metrics::CLUSTER_IS_METRICS_LEADER.get(), 1);
+    }
+
+    #[test]
+    fn test_metrics_leader_ttl_outlives_heartbeat_rounds() {
+        let (_shutdown_tx, shutdown_rx) = broadcast::channel(1);
+        let task = |config: WebSocketConfig| {
+            HeartbeatTask::new(
+                config,
+                Arc::new(ConnectionManager::new()),
+                create_test_session_store(),
+                shutdown_rx.resubscribe(),
+            )
+        };
+
+        let fixed = WebSocketConfig {
+            heartbeat_interval: 30,
+            heartbeat_adaptive: false,
+            ..Default::default()
+        };
+        assert_eq!(task(fixed).metrics_leader_ttl(), METRICS_LEADER_TTL_SECS);
+
+        let adaptive = WebSocketConfig {
+            heartbeat_interval: 30,
+            heartbeat_idle_interval: 120,
+            heartbeat_adaptive: true,
+            ..Default::default()
+        };
+        assert_eq!(task(adaptive).metrics_leader_ttl(), 240);
+    }
 }
@@ -0,0 +1,248 @@
+use std::time::Duration;
+
+use bytes::Bytes;
+use http_body_util::Full;
+use hyper::header::CONTENT_TYPE;
+use hyper::{Method, Request};
+use hyper_util::client::legacy::connect::HttpConnector;
+use hyper_util::client::legacy::Client;
+use hyper_util::rt::TokioExecutor;
+use tokio::sync::broadcast;
+
+use crate::metrics::{encode_metrics, METRICS_PUSH_FAILURES_TOTAL};
+use crate::redis::{BackoffConfig, ExponentialBackoff};
+
+/// Job name under which this service pushes its metrics
+const PUSH_JOB: &str = "ara-notification-service";
+
+/// Prometheus text exposition format
+const PUSH_CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+/// Retries after the first failed push attempt
+const MAX_PUSH_RETRIES: u32 = 3;
+
+/// Timeout for a single push attempt
+const PUSH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Background task that pushes the encoded metrics to a Prometheus Pushgateway,
+/// for deployments where the service cannot be scraped directly
+pub struct MetricsPushTask {
+    interval: Duration,
+    push_url: String,
+    client: Client<HttpConnector, Full<Bytes>>,
+    backoff: BackoffConfig,
+    shutdown: broadcast::Receiver<()>,
+}
+
+impl MetricsPushTask {
+    pub fn new(
+        interval: Duration,
+        push_gateway_url: &str,
+        server_id: &str,
+        shutdown: broadcast::Receiver<()>,
+    ) -> Self {
+        Self {
+            interval,
+            push_url: format!(
+                "{}/metrics/job/{}/instance/{}",
+                push_gateway_url.trim_end_matches('/'),
+                PUSH_JOB,
+                server_id
+            ),
+            client: Client::builder(TokioExecutor::new()).build_http(),
+            backoff: BackoffConfig {
+                initial_delay_ms: 250,
+                max_delay_ms: 5_000,
+                ..Default::default()
+            },
+            shutdown,
+        }
+    }
+
+    /// Override the delays between retries
+    pub fn with_backoff(mut self, backoff: BackoffConfig) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Run the pusher until shutdown, pushing immediately and then every interval
+    pub async fn run(mut self) {
+        let mut timer = tokio::time::interval(self.interval);
+
+        tracing::info!(
+            interval_secs = self.interval.as_secs(),
+            url = %self.push_url,
+            "Metrics push task started"
+        );
+
+        loop {
+            tokio::select! {
+                _ = self.shutdown.recv() => {
+                    tracing::info!("Metrics push task received shutdown signal");
+                    break;
+                }
+                _ = timer.tick() => {
+                    self.push_with_retry().await;
+                }
+            }
+        }
+
+        tracing::info!("Metrics push task stopped");
+    }
+
+    /// Push once, retrying with exponential backoff. Returns whether the push succeeded.
+    async fn push_with_retry(&self) -> bool {
+        let body = match encode_metrics() {
+            Ok(body) => body,
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to encode metrics for push");
+                METRICS_PUSH_FAILURES_TOTAL.inc();
+                return false;
+            }
+        };
+
+        let mut backoff = ExponentialBackoff::with_config(self.backoff.clone());
+        loop {
+            let error = match self.push(body.clone()).await {
+                Ok(()) => return true,
+                Err(error) => error,
+            };
+            if backoff.attempt() >= MAX_PUSH_RETRIES {
+                tracing::warn!(
+                    error = %error,
+                    attempts = backoff.attempt() + 1,
+                    "Failed to push metrics to the Pushgateway"
+                );
+                METRICS_PUSH_FAILURES_TOTAL.inc();
+                return false;
+            }
+            let delay = backoff.next_delay();
+            tracing::debug!(
+                error = %error,
+                attempt = backoff.attempt(),
+                delay_ms = delay.as_millis() as u64,
+                "Metrics push failed, retrying"
+            );
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    /// Single push attempt
+    async fn push(&self, body: String) -> Result<(), String> {
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri(&self.push_url)
+            .header(CONTENT_TYPE, PUSH_CONTENT_TYPE)
+            .body(Full::new(Bytes::from(body)))
+            .map_err(|e| e.to_string())?;
+
+        let response = tokio::time::timeout(PUSH_TIMEOUT, self.client.request(request))
+            .await
+            .map_err(|_| "request timed out".to_string())?
+            .map_err(|e| e.to_string())?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(format!("unexpected status {}", response.status()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::{Arc, Mutex};
+
+    use axum::extract::State;
+    use axum::http::{HeaderMap, StatusCode};
+    use axum::routing::post;
+    use axum::Router;
+
+    #[derive(Debug, Default)]
+    struct Received {
+        path: String,
+        content_type: String,
+        body: String,
+        attempts: u32,
+    }
+
+    /// Start a stub Pushgateway answering every push with `status`
+    async fn stub_gateway(status: StatusCode) -> (String, Arc<Mutex<Received>>) {
+        let received = Arc::new(Mutex::new(Received::default()));
+        let app = Router::new()
+            .route(
+                "/{*path}",
+                post(
+                    move |State(received): State<Arc<Mutex<Received>>>,
+                          uri: axum::http::Uri,
+                          headers: HeaderMap,
+                          body: String| async move {
+                        let mut received = received.lock().unwrap();
+                        received.path = uri.path().to_string();
+                        received.content_type = headers
+                            .get(CONTENT_TYPE)
+                            .and_then(|v| v.to_str().ok())
+                            .unwrap_or_default()
+                            .to_string();
+                        received.body = body;
+                        received.attempts += 1;
+                        status
+                    },
+                ),
+            )
+            .with_state(received.clone());
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (format!("http://{}", addr), received)
+    }
+
+    fn task(url: &str) -> MetricsPushTask {
+        let (_shutdown_tx, shutdown_rx) = broadcast::channel(1);
+        MetricsPushTask::new(Duration::from_secs(15), url, "server-1", shutdown_rx).with_backoff(
+            BackoffConfig {
+                initial_delay_ms: 1,
+                max_delay_ms: 5,
+                jitter_factor: 0.0,
+                ..Default::default()
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn test_push_sends_prometheus_text() {
+        crate::metrics::CONNECTIONS_TOTAL.set(1);
+        let (url, received) = stub_gateway(StatusCode::OK).await;
+
+        assert!(task(&url).push_with_retry().await);
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.attempts, 1);
+        assert_eq!(
+            received.path,
+            "/metrics/job/ara-notification-service/instance/server-1"
+        );
+        assert_eq!(received.content_type, "text/plain; version=0.0.4");
+        assert!(received.body.contains("# HELP ara_connections_total "));
+        assert!(received.body.contains("# TYPE ara_connections_total gauge"));
+        // Every non-comment line is a `name{labels} value` sample
+        for line in received.body.lines().filter(|l| !l.is_empty() && !l.starts_with('#')) {
+            let (_, value) = line.rsplit_once(' ').unwrap();
+            assert!(value.parse::<f64>().is_ok(), "invalid sample line: {line}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failed_push_is_retried_and_counted() {
+        let (url, received) = stub_gateway(StatusCode::SERVICE_UNAVAILABLE).await;
+        let failures_before = METRICS_PUSH_FAILURES_TOTAL.get();
+
+        assert!(!task(&url).push_with_retry().await);
+
+        assert_eq!(received.lock().unwrap().attempts, 1 + MAX_PUSH_RETRIES);
+        assert!(METRICS_PUSH_FAILURES_TOTAL.get() > failures_before);
+    }
+}
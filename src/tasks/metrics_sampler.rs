@@ -0,0 +1,89 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::broadcast;
+
+use crate::tenant::TenantManager;
+
+/// Interval between tenant metrics samples
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Background task that snapshots tenant stats into their metrics history once per minute
+pub struct MetricsSamplerTask {
+    tenant_manager: Arc<TenantManager>,
+    shutdown: broadcast::Receiver<()>,
+}
+
+impl MetricsSamplerTask {
+    pub fn new(tenant_manager: Arc<TenantManager>, shutdown: broadcast::Receiver<()>) -> Self {
+        Self {
+            tenant_manager,
+            shutdown,
+        }
+    }
+
+    /// Run the sampler until shutdown
+    pub async fn run(mut self) {
+        let mut timer = tokio::time::interval(SAMPLE_INTERVAL);
+
+        // Skip immediate first tick
+        timer.tick().await;
+
+        tracing::info!(
+            interval_secs = SAMPLE_INTERVAL.as_secs(),
+            "Tenant metrics sampler started"
+        );
+
+        loop {
+            tokio::select! {
+                _ = self.shutdown.recv() => {
+                    tracing::info!("Tenant metrics sampler received shutdown signal");
+                    break;
+                }
+                _ = timer.tick() => {
+                    self.sample();
+                }
+            }
+        }
+
+        tracing::info!("Tenant metrics sampler stopped");
+    }
+
+    /// Take one sample of all tenants
+    fn sample(&self) {
+        let sampled = self
+            .tenant_manager
+            .sample_metrics(chrono::Utc::now().timestamp());
+        tracing::trace!(tenants = sampled, "Sampled tenant metrics");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tenant::TenantConfig;
+
+    #[tokio::test]
+    async fn test_sampler_stops_on_shutdown() {
+        let manager = Arc::new(TenantManager::new(TenantConfig::default()));
+        let (tx, rx) = broadcast::channel(1);
+        let handle = tokio::spawn(MetricsSamplerTask::new(manager, rx).run());
+
+        tx.send(()).unwrap();
+        tokio::time::timeout(Duration::from_secs(1), handle)
+            .await
+            .expect("sampler should stop on shutdown")
+            .unwrap();
+    }
+
+    #[test]
+    fn test_sample_records_tenants() {
+        let manager = Arc::new(TenantManager::new(TenantConfig::default()));
+        manager.record_connection("acme");
+        let (_tx, rx) = broadcast::channel(1);
+        let task = MetricsSamplerTask::new(manager.clone(), rx);
+
+        task.sample();
+        assert_eq!(manager.metrics_history("acme", 0, i64::MAX).len(), 1);
+    }
+}
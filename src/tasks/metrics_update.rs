@@ -0,0 +1,233 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::broadcast;
+
+use crate::cluster::SessionStore;
+use crate::connection_manager::ConnectionManager;
+use crate::metrics::{
+    AckMetrics, ClusterMetrics, MemoryMetrics, RedisMetrics, ACK_PENDING, CONNECTIONS_TOTAL, QUEUE_SIZE_TOTAL,
+    QUEUE_USERS_TOTAL, TENANT_AGGREGATE_ACTIVE_CONNECTIONS,
+};
+use crate::notification::AckTrackerBackend;
+use crate::queue::MessageQueueBackend;
+use crate::redis::pool::RedisPool;
+use crate::tenant::TenantManager;
+
+/// Background task that refreshes gauges which are otherwise only updated when
+/// the relevant operation happens or `/metrics` is scraped
+pub struct MetricsUpdateTask {
+    interval: Duration,
+    connection_manager: Arc<ConnectionManager>,
+    queue_backend: Arc<dyn MessageQueueBackend>,
+    ack_backend: Arc<dyn AckTrackerBackend>,
+    session_store: Arc<dyn SessionStore>,
+    redis_pool: Option<Arc<RedisPool>>,
+    tenant_manager: Option<Arc<TenantManager>>,
+    shutdown: broadcast::Receiver<()>,
+}
+
+impl MetricsUpdateTask {
+    pub fn new(
+        interval: Duration,
+        connection_manager: Arc<ConnectionManager>,
+        queue_backend: Arc<dyn MessageQueueBackend>,
+        ack_backend: Arc<dyn AckTrackerBackend>,
+        session_store: Arc<dyn SessionStore>,
+        shutdown: broadcast::Receiver<()>,
+    ) -> Self {
+        Self {
+            interval,
+            connection_manager,
+            queue_backend,
+            ack_backend,
+            session_store,
+            redis_pool: None,
+            tenant_manager: None,
+            shutdown,
+        }
+    }
+
+    /// Report utilization of the shared Redis pool, if one is in use
+    pub fn with_redis_pool(mut self, redis_pool: Option<Arc<RedisPool>>) -> Self {
+        self.redis_pool = redis_pool;
+        self
+    }
+
+    /// Report connections summed across the tenants tracked by `tenant_manager`
+    pub fn with_tenant_manager(mut self, tenant_manager: Arc<TenantManager>) -> Self {
+        self.tenant_manager = Some(tenant_manager);
+        self
+    }
+
+    /// Run the updater until shutdown, refreshing immediately and then every interval
+    pub async fn run(mut self) {
+        let mut timer = tokio::time::interval(self.interval);
+
+        tracing::info!(
+            interval_secs = self.interval.as_secs(),
+            "Metrics update task started"
+        );
+
+        loop {
+            tokio::select! {
+                _ = self.shutdown.recv() => {
+                    tracing::info!("Metrics update task received shutdown signal");
+                    break;
+                }
+                _ = timer.tick() => {
+                    self.update().await;
+                }
+            }
+        }
+
+        tracing::info!("Metrics update task stopped");
+    }
+
+    /// Refresh every polled gauge once
+    async fn update(&self) {
+        MemoryMetrics::update_process_memory();
+        if let Some(pool) = &self.redis_pool {
+            RedisMetrics::update_pool_stats(pool);
+        }
+
+        let connections = self.connection_manager.tracked_connection_ids();
+        CONNECTIONS_TOTAL.set(connections as i64);
+        MemoryMetrics::update_connection_manager_memory(
+            connections,
+            self.connection_manager.total_subscriptions(),
+        );
+        if let Some(tenant_manager) = &self.tenant_manager {
+            let aggregate = tenant_manager.get_aggregate_stats();
+            TENANT_AGGREGATE_ACTIVE_CONNECTIONS.set(aggregate.active_connections as i64);
+        }
+
+        if self.queue_backend.is_enabled() {
+            let queue_stats = self.queue_backend.stats().await;
+            QUEUE_SIZE_TOTAL.set(queue_stats.total_messages as i64);
+            QUEUE_USERS_TOTAL.set(queue_stats.users_with_queue as i64);
+        }
+
+        if self.ack_backend.is_enabled() {
+            ACK_PENDING.set(self.ack_backend.pending_count().await as i64);
+            match self.ack_backend.summary_by_event_type().await {
+                Ok(summary) => {
+                    for entry in &summary {
+                        AckMetrics::set_rate_by_type(&entry.event_type, entry.ack_rate);
+                    }
+                }
+                Err(e) => tracing::debug!(error = %e, "Failed to load per-event-type ACK rates"),
+            }
+        }
+
+        self.update_cluster();
+    }
+
+    /// Without cluster mode this server is the whole cluster, so the local counts are
+    /// reported. In cluster mode the heartbeat task's metrics leader refreshes them.
+    fn update_cluster(&self) {
+        let enabled = self.session_store.is_enabled();
+        ClusterMetrics::set_enabled(enabled);
+
+        if !enabled {
+            let manager = &self.connection_manager;
+            ClusterMetrics::set_cluster_connections(manager.tracked_connection_ids());
+            ClusterMetrics::set_cluster_users(manager.user_count());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tokio::sync::mpsc;
+
+    use crate::cluster::LocalSessionStore;
+    use crate::metrics::{
+        ACK_RATE_BY_TYPE, CLUSTER_CONNECTIONS_TOTAL, CLUSTER_USERS_TOTAL, PROCESS_MEMORY_BYTES,
+    };
+    use crate::notification::{AckConfig, MemoryAckBackend, NotificationBuilder};
+    use crate::queue::{MemoryQueueBackend, QueueConfig};
+
+    fn task(
+        connection_manager: Arc<ConnectionManager>,
+        queue_backend: Arc<dyn MessageQueueBackend>,
+        ack_backend: Arc<dyn AckTrackerBackend>,
+        shutdown: broadcast::Receiver<()>,
+    ) -> MetricsUpdateTask {
+        MetricsUpdateTask::new(
+            Duration::from_secs(30),
+            connection_manager,
+            queue_backend,
+            ack_backend,
+            Arc::new(LocalSessionStore::new("test-server".to_string())),
+            shutdown,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_update_sets_gauges_from_seeded_state() {
+        let connection_manager = Arc::new(ConnectionManager::new());
+        let (tx, _rx) = mpsc::channel(8);
+        connection_manager
+            .register("user-1".to_string(), "default".to_string(), vec![], tx)
+            .unwrap();
+
+        let queue_backend = Arc::new(MemoryQueueBackend::new(QueueConfig {
+            enabled: true,
+            ..Default::default()
+        }));
+        queue_backend
+            .enqueue("offline-user", NotificationBuilder::new("test.event", "test").build())
+            .await
+            .unwrap();
+
+        let ack_backend = Arc::new(MemoryAckBackend::new(AckConfig {
+            enabled: true,
+            ..Default::default()
+        }));
+        ack_backend
+            .track(uuid::Uuid::new_v4(), "user-1", uuid::Uuid::new_v4(), "order.created")
+            .await;
+
+        let tenant_manager = Arc::new(TenantManager::default());
+        tenant_manager.record_connection("acme");
+
+        let (_shutdown_tx, shutdown_rx) = broadcast::channel(1);
+        task(connection_manager, queue_backend, ack_backend, shutdown_rx)
+            .with_tenant_manager(tenant_manager)
+            .update()
+            .await;
+
+        // Gauges are process-global, so only assert they were populated
+        #[cfg(target_os = "linux")]
+        assert!(PROCESS_MEMORY_BYTES.get() > 0);
+        assert!(QUEUE_SIZE_TOTAL.get() > 0);
+        assert!(ACK_PENDING.get() > 0);
+        assert!(ACK_RATE_BY_TYPE.with_label_values(&["order.created"]).get() > 0.0);
+        assert!(CLUSTER_CONNECTIONS_TOTAL.get() > 0);
+        assert!(CLUSTER_USERS_TOTAL.get() > 0);
+        assert!(TENANT_AGGREGATE_ACTIVE_CONNECTIONS.get() > 0);
+    }
+
+    #[tokio::test]
+    async fn test_update_task_stops_on_shutdown() {
+        let (tx, rx) = broadcast::channel(1);
+        let handle = tokio::spawn(
+            task(
+                Arc::new(ConnectionManager::new()),
+                Arc::new(MemoryQueueBackend::new(QueueConfig::default())),
+                Arc::new(MemoryAckBackend::new(AckConfig::default())),
+                rx,
+            )
+            .run(),
+        );
+
+        tx.send(()).unwrap();
+        tokio::time::timeout(Duration::from_secs(1), handle)
+            .await
+            .expect("metrics update task should stop on shutdown")
+            .unwrap();
+    }
+}
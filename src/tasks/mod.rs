@@ -1,3 +1,11 @@
+mod cleanup;
 mod heartbeat;
+mod metrics_push;
+mod metrics_sampler;
+mod metrics_update;
 
+pub use cleanup::{AckCleanup, Cleanable, CleanupTask, QueueCleanup};
 pub use heartbeat::HeartbeatTask;
+pub use metrics_push::MetricsPushTask;
+pub use metrics_sampler::MetricsSamplerTask;
+pub use metrics_update::MetricsUpdateTask;
@@ -4,6 +4,8 @@
 //! - OTLP exporter configuration for sending traces to collectors like Jaeger, Zipkin, or Tempo
 //! - Integration with the `tracing` crate for seamless span creation
 //! - Configurable sampling for production environments
+//! - Trace context propagation in W3C Trace Context or B3 format
+//! - Optional redaction of PII span attributes before export
 //!
 //! # Environment Variables
 //!
@@ -13,17 +15,27 @@
 //! | `OTEL_ENDPOINT` | OTLP gRPC endpoint | `http://localhost:4317` |
 //! | `OTEL_SERVICE_NAME` | Service name in traces | `ara-notification-service` |
 //! | `OTEL_SAMPLING_RATIO` | Trace sampling ratio (0.0-1.0) | `1.0` |
+//! | `OTEL_PROPAGATION_FORMAT` | `w3c_trace_context`, `b3_single`, `b3_multi` or `composite` | `w3c_trace_context` |
+//! | `OTEL_PII_REDACTION_ENABLED` | Redact span attributes matching `OTEL_PII_ATTRIBUTE_PATTERNS` | `false` |
+//! | `OTEL_PII_ATTRIBUTE_PATTERNS` | Comma-separated regexes for attribute keys | `^user\.id$,^user\.email$` |
+
+pub mod propagation;
+mod redaction;
+
+pub use redaction::{RedactingSpanProcessor, REDACTED};
 
 use opentelemetry::trace::TracerProvider;
 use opentelemetry_otlp::WithExportConfig;
 use opentelemetry_sdk::{
     runtime,
-    trace::{RandomIdGenerator, Sampler, TracerProvider as SdkTracerProvider},
+    trace::{BatchSpanProcessor, RandomIdGenerator, Sampler, TracerProvider as SdkTracerProvider},
     Resource,
 };
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer};
 
-use crate::config::OtelConfig;
+use crate::audit::{AuditLogger, AUDIT_TARGET};
+use crate::config::{AuditConfig, OtelConfig};
 
 /// Result type for telemetry operations
 pub type TelemetryResult<T> = Result<T, TelemetryError>;
@@ -35,11 +47,15 @@ pub enum TelemetryError {
     TracerInit(String),
     #[error("Failed to build OTLP exporter: {0}")]
     ExporterBuild(String),
+    #[error("Failed to open audit log: {0}")]
+    AuditInit(String),
 }
 
 /// Telemetry guard that ensures proper shutdown of OpenTelemetry on drop.
 pub struct TelemetryGuard {
     _provider: Option<SdkTracerProvider>,
+    /// Flushes pending audit entries when dropped
+    _audit_guard: Option<WorkerGuard>,
 }
 
 impl Drop for TelemetryGuard {
@@ -51,33 +67,61 @@ impl Drop for TelemetryGuard {
     }
 }
 
+/// `RUST_LOG` filter for console and OpenTelemetry output; audit events are kept out of
+/// the application log and are filtered separately by the audit layer.
+fn env_filter() -> EnvFilter {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    match format!("{}=off", AUDIT_TARGET).parse() {
+        Ok(directive) => filter.add_directive(directive),
+        Err(_) => filter,
+    }
+}
+
 /// Initialize the telemetry system with the given configuration.
 ///
 /// This function sets up the tracing subscriber with:
 /// - Console output for local debugging
 /// - OpenTelemetry layer for distributed tracing (if enabled)
+/// - Audit log layer writing dispatch events to a daily file (if `audit.enabled`)
+///
+/// `RUST_LOG` is applied per layer, so it never filters the audit layer.
 ///
 /// # Arguments
 ///
 /// * `config` - OpenTelemetry configuration
+/// * `audit_config` - Audit log configuration
 ///
 /// # Returns
 ///
 /// A `TelemetryGuard` that should be kept alive for the duration of the application.
-/// When dropped, it ensures proper shutdown of the OpenTelemetry tracer.
-pub fn init_telemetry(config: &OtelConfig) -> TelemetryResult<TelemetryGuard> {
-    let env_filter = EnvFilter::try_from_default_env()
-        .unwrap_or_else(|_| EnvFilter::new("info"));
+/// When dropped, it ensures proper shutdown of the OpenTelemetry tracer and flushes
+/// the audit log.
+pub fn init_telemetry(
+    config: &OtelConfig,
+    audit_config: &AuditConfig,
+) -> TelemetryResult<TelemetryGuard> {
+    let (audit_layer, audit_guard) = if audit_config.enabled {
+        let (logger, guard) =
+            AuditLogger::daily(audit_config).map_err(TelemetryError::AuditInit)?;
+        (Some(logger.with_filter(AuditLogger::filter())), Some(guard))
+    } else {
+        (None, None)
+    };
+
+    // Installed even without an exporter so incoming trace context is still understood
+    propagation::install(config.propagation_format);
 
     if config.enabled {
         // Initialize OpenTelemetry with OTLP exporter
         let provider = init_otel_tracer(config)?;
         let tracer = provider.tracer("ara-notification-service");
-        let otel_layer = tracing_opentelemetry::layer().with_tracer(tracer);
+        let otel_layer = tracing_opentelemetry::layer()
+            .with_tracer(tracer)
+            .with_filter(env_filter());
 
         tracing_subscriber::registry()
-            .with(env_filter)
-            .with(tracing_subscriber::fmt::layer())
+            .with(audit_layer)
+            .with(tracing_subscriber::fmt::layer().with_filter(env_filter()))
             .with(otel_layer)
             .init();
 
@@ -85,22 +129,27 @@ pub fn init_telemetry(config: &OtelConfig) -> TelemetryResult<TelemetryGuard> {
             endpoint = %config.endpoint,
             service_name = %config.service_name,
             sampling_ratio = %config.sampling_ratio,
+            propagation_format = ?config.propagation_format,
             "OpenTelemetry tracing initialized"
         );
 
         Ok(TelemetryGuard {
             _provider: Some(provider),
+            _audit_guard: audit_guard,
         })
     } else {
         // Standard logging without OpenTelemetry
         tracing_subscriber::registry()
-            .with(env_filter)
-            .with(tracing_subscriber::fmt::layer())
+            .with(audit_layer)
+            .with(tracing_subscriber::fmt::layer().with_filter(env_filter()))
             .init();
 
         tracing::info!("Tracing initialized (OpenTelemetry disabled)");
 
-        Ok(TelemetryGuard { _provider: None })
+        Ok(TelemetryGuard {
+            _provider: None,
+            _audit_guard: audit_guard,
+        })
     }
 }
 
@@ -124,9 +173,18 @@ fn init_otel_tracer(config: &OtelConfig) -> TelemetryResult<SdkTracerProvider> {
         Sampler::TraceIdRatioBased(config.sampling_ratio)
     };
 
+    // Redaction wraps the exporting processor so attributes are replaced before export
+    let batch = BatchSpanProcessor::builder(exporter, runtime::Tokio).build();
+    let builder = if config.pii_redaction_enabled {
+        let redacting = RedactingSpanProcessor::new(batch, &config.pii_attribute_patterns)
+            .map_err(|e| TelemetryError::TracerInit(e.to_string()))?;
+        SdkTracerProvider::builder().with_span_processor(redacting)
+    } else {
+        SdkTracerProvider::builder().with_span_processor(batch)
+    };
+
     // Build the tracer provider
-    let provider = SdkTracerProvider::builder()
-        .with_batch_exporter(exporter, runtime::Tokio)
+    let provider = builder
         .with_sampler(sampler)
         .with_id_generator(RandomIdGenerator::default())
         .with_resource(Resource::new(vec![
@@ -220,6 +278,7 @@ mod tests {
         assert_eq!(config.endpoint, "http://localhost:4317");
         assert_eq!(config.service_name, "ara-notification-service");
         assert_eq!(config.sampling_ratio, 1.0);
+        assert_eq!(config.propagation_format, crate::config::PropagationFormat::W3cTraceContext);
     }
 
     #[test]
@@ -236,7 +295,10 @@ mod tests {
 
     #[test]
     fn test_telemetry_guard_creation() {
-        let guard = TelemetryGuard { _provider: None };
+        let guard = TelemetryGuard {
+            _provider: None,
+            _audit_guard: None,
+        };
         drop(guard); // Should not panic
     }
 }
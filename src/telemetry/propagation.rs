@@ -0,0 +1,279 @@
+//! Trace context propagation across service boundaries.
+//!
+//! The format is chosen at startup with `otel.propagation_format` and installed as the
+//! global text map propagator, so `inject` and `extract` always use the configured
+//! headers. B3 (single and multi header) is implemented here for Zipkin and AWS X-Ray
+//! compatibility; W3C Trace Context comes from `opentelemetry_sdk`.
+
+use std::collections::HashMap;
+
+use opentelemetry::propagation::text_map_propagator::FieldIter;
+use opentelemetry::propagation::{
+    Extractor, Injector, TextMapCompositePropagator, TextMapPropagator,
+};
+use opentelemetry::trace::{SpanContext, SpanId, TraceContextExt, TraceFlags, TraceId, TraceState};
+use opentelemetry::Context;
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+
+use crate::config::PropagationFormat;
+
+const B3_SINGLE_HEADER: &str = "b3";
+const B3_TRACE_ID_HEADER: &str = "x-b3-traceid";
+const B3_SPAN_ID_HEADER: &str = "x-b3-spanid";
+const B3_SAMPLED_HEADER: &str = "x-b3-sampled";
+const B3_FLAGS_HEADER: &str = "x-b3-flags";
+
+/// Zipkin B3 propagator.
+///
+/// Injects either the single `b3` header or the `X-B3-*` headers; extraction accepts
+/// both, preferring the single header when present.
+#[derive(Debug)]
+pub struct B3Propagator {
+    single_header: bool,
+    fields: Vec<String>,
+}
+
+impl B3Propagator {
+    /// Propagate with the single `b3` header
+    pub fn single_header() -> Self {
+        Self {
+            single_header: true,
+            fields: vec![B3_SINGLE_HEADER.to_string()],
+        }
+    }
+
+    /// Propagate with the `X-B3-TraceId`, `X-B3-SpanId` and `X-B3-Sampled` headers
+    pub fn multiple_headers() -> Self {
+        Self {
+            single_header: false,
+            fields: [B3_TRACE_ID_HEADER, B3_SPAN_ID_HEADER, B3_SAMPLED_HEADER, B3_FLAGS_HEADER]
+                .iter()
+                .map(|field| field.to_string())
+                .collect(),
+        }
+    }
+
+    fn extract_single(extractor: &dyn Extractor) -> Option<SpanContext> {
+        let mut parts = extractor.get(B3_SINGLE_HEADER)?.trim().split('-');
+        let trace_id = parts.next()?;
+        let span_id = parts.next()?;
+        let sampled = parts.next().map_or(Some(TraceFlags::SAMPLED), parse_sampled)?;
+        span_context(trace_id, span_id, sampled)
+    }
+
+    fn extract_multi(extractor: &dyn Extractor) -> Option<SpanContext> {
+        let trace_id = extractor.get(B3_TRACE_ID_HEADER)?.trim();
+        let span_id = extractor.get(B3_SPAN_ID_HEADER)?.trim();
+        // The debug flag implies sampling; without either header the trace is sampled
+        let sampled = if extractor.get(B3_FLAGS_HEADER).map(str::trim) == Some("1") {
+            TraceFlags::SAMPLED
+        } else {
+            match extractor.get(B3_SAMPLED_HEADER) {
+                Some(value) => parse_sampled(value.trim())?,
+                None => TraceFlags::SAMPLED,
+            }
+        };
+        span_context(trace_id, span_id, sampled)
+    }
+}
+
+/// Parse a B3 sampling state: `1`/`true`/`d` (debug) are sampled, `0`/`false` are not
+fn parse_sampled(value: &str) -> Option<TraceFlags> {
+    match value {
+        "1" | "true" | "d" => Some(TraceFlags::SAMPLED),
+        "0" | "false" => Some(TraceFlags::default()),
+        _ => None,
+    }
+}
+
+/// Build a remote span context from B3 hex IDs (64- or 128-bit trace ID, 64-bit span ID)
+fn span_context(trace_id: &str, span_id: &str, flags: TraceFlags) -> Option<SpanContext> {
+    if !matches!(trace_id.len(), 16 | 32) || span_id.len() != 16 {
+        return None;
+    }
+    let trace_id = TraceId::from_hex(trace_id).ok()?;
+    let span_id = SpanId::from_hex(span_id).ok()?;
+    let context = SpanContext::new(trace_id, span_id, flags, true, TraceState::default());
+    context.is_valid().then_some(context)
+}
+
+impl TextMapPropagator for B3Propagator {
+    fn inject_context(&self, cx: &Context, injector: &mut dyn Injector) {
+        let span = cx.span();
+        let context = span.span_context();
+        if !context.is_valid() {
+            return;
+        }
+        let sampled = if context.is_sampled() { "1" } else { "0" };
+
+        if self.single_header {
+            injector.set(
+                B3_SINGLE_HEADER,
+                format!("{}-{}-{}", context.trace_id(), context.span_id(), sampled),
+            );
+        } else {
+            injector.set(B3_TRACE_ID_HEADER, context.trace_id().to_string());
+            injector.set(B3_SPAN_ID_HEADER, context.span_id().to_string());
+            injector.set(B3_SAMPLED_HEADER, sampled.to_string());
+        }
+    }
+
+    fn extract_with_context(&self, cx: &Context, extractor: &dyn Extractor) -> Context {
+        Self::extract_single(extractor)
+            .or_else(|| Self::extract_multi(extractor))
+            .map(|context| cx.with_remote_span_context(context))
+            .unwrap_or_else(|| cx.clone())
+    }
+
+    fn fields(&self) -> FieldIter<'_> {
+        FieldIter::new(&self.fields)
+    }
+}
+
+/// Build the propagator for a configured format
+pub fn propagator(format: PropagationFormat) -> Box<dyn TextMapPropagator + Send + Sync> {
+    match format {
+        PropagationFormat::W3cTraceContext => Box::new(TraceContextPropagator::new()),
+        PropagationFormat::B3Single => Box::new(B3Propagator::single_header()),
+        PropagationFormat::B3Multi => Box::new(B3Propagator::multiple_headers()),
+        PropagationFormat::Composite => Box::new(composite()),
+    }
+}
+
+/// W3C Trace Context plus B3 multi header
+fn composite() -> TextMapCompositePropagator {
+    TextMapCompositePropagator::new(vec![
+        Box::new(TraceContextPropagator::new()),
+        Box::new(B3Propagator::multiple_headers()),
+    ])
+}
+
+/// Install the propagator for `format` as the global text map propagator
+pub fn install(format: PropagationFormat) {
+    match format {
+        PropagationFormat::W3cTraceContext => {
+            opentelemetry::global::set_text_map_propagator(TraceContextPropagator::new())
+        }
+        PropagationFormat::B3Single => {
+            opentelemetry::global::set_text_map_propagator(B3Propagator::single_header())
+        }
+        PropagationFormat::B3Multi => {
+            opentelemetry::global::set_text_map_propagator(B3Propagator::multiple_headers())
+        }
+        PropagationFormat::Composite => opentelemetry::global::set_text_map_propagator(composite()),
+    }
+}
+
+/// Write the span context of `cx` into `carrier` using the configured format
+pub fn inject(cx: &Context, carrier: &mut HashMap<String, String>) {
+    opentelemetry::global::get_text_map_propagator(|propagator| {
+        propagator.inject_context(cx, carrier)
+    });
+}
+
+/// Read a remote span context from `carrier` using the configured format
+pub fn extract(carrier: &HashMap<String, String>) -> Context {
+    opentelemetry::global::get_text_map_propagator(|propagator| propagator.extract(carrier))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn remote_context(sampled: bool) -> Context {
+        let flags = if sampled {
+            TraceFlags::SAMPLED
+        } else {
+            TraceFlags::default()
+        };
+        Context::new().with_remote_span_context(SpanContext::new(
+            TraceId::from_hex("4bf92f3577b34da6a3ce929d0e0e4736").unwrap(),
+            SpanId::from_hex("00f067aa0ba902b7").unwrap(),
+            flags,
+            true,
+            TraceState::default(),
+        ))
+    }
+
+    fn extracted(cx: &Context) -> SpanContext {
+        cx.span().span_context().clone()
+    }
+
+    #[test]
+    fn test_w3c_inject_extract_round_trip() {
+        install(PropagationFormat::W3cTraceContext);
+        let cx = remote_context(true);
+
+        let mut carrier = HashMap::new();
+        inject(&cx, &mut carrier);
+        assert_eq!(
+            carrier.get("traceparent").map(String::as_str),
+            Some("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01")
+        );
+
+        let context = extracted(&extract(&carrier));
+        assert!(context.is_valid());
+        assert!(context.is_remote());
+        assert!(context.is_sampled());
+        assert_eq!(context.trace_id(), cx.span().span_context().trace_id());
+        assert_eq!(context.span_id(), cx.span().span_context().span_id());
+    }
+
+    #[test]
+    fn test_b3_single_and_multi_round_trip() {
+        let cx = remote_context(false);
+
+        let single = propagator(PropagationFormat::B3Single);
+        let mut carrier = HashMap::new();
+        single.inject_context(&cx, &mut carrier);
+        assert_eq!(
+            carrier.get("b3").map(String::as_str),
+            Some("4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-0")
+        );
+        let context = extracted(&single.extract(&carrier));
+        assert!(context.is_valid());
+        assert!(!context.is_sampled());
+
+        let multi = propagator(PropagationFormat::B3Multi);
+        let mut carrier = HashMap::new();
+        multi.inject_context(&cx, &mut carrier);
+        assert_eq!(
+            carrier.get("x-b3-traceid").map(String::as_str),
+            Some("4bf92f3577b34da6a3ce929d0e0e4736")
+        );
+        assert_eq!(carrier.get("x-b3-sampled").map(String::as_str), Some("0"));
+        let context = extracted(&multi.extract(&carrier));
+        assert_eq!(context.span_id(), cx.span().span_context().span_id());
+    }
+
+    #[test]
+    fn test_b3_extract_accepts_64_bit_trace_ids_and_rejects_garbage() {
+        let b3 = B3Propagator::multiple_headers();
+
+        let carrier = HashMap::from([("b3".to_string(), "a3ce929d0e0e4736-00f067aa0ba902b7-d".to_string())]);
+        let context = extracted(&b3.extract(&carrier));
+        assert!(context.is_valid());
+        assert!(context.is_sampled());
+        assert_eq!(context.trace_id().to_string(), "0000000000000000a3ce929d0e0e4736");
+
+        for header in ["0", "not-hex-at-all", "a3ce929d0e0e4736-00f067aa0ba902b7-x"] {
+            let carrier = HashMap::from([("b3".to_string(), header.to_string())]);
+            assert!(!extracted(&b3.extract(&carrier)).is_valid(), "{header}");
+        }
+    }
+
+    #[test]
+    fn test_composite_injects_both_formats() {
+        let composite = propagator(PropagationFormat::Composite);
+        let mut carrier = HashMap::new();
+        composite.inject_context(&remote_context(true), &mut carrier);
+        assert!(carrier.contains_key("traceparent"));
+        assert!(carrier.contains_key("x-b3-traceid"));
+
+        let b3_only = HashMap::from([
+            ("x-b3-traceid".to_string(), "4bf92f3577b34da6a3ce929d0e0e4736".to_string()),
+            ("x-b3-spanid".to_string(), "00f067aa0ba902b7".to_string()),
+        ]);
+        assert!(extracted(&composite.extract(&b3_only)).is_valid());
+    }
+}
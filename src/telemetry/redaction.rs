@@ -0,0 +1,139 @@
+//! Redaction of personally identifiable span attributes before export.
+
+use opentelemetry::{Context, Value};
+use opentelemetry_sdk::export::trace::SpanData;
+use opentelemetry_sdk::trace::{Span, SpanProcessor};
+use opentelemetry_sdk::Resource;
+use regex::RegexSet;
+
+/// Replacement value for redacted attributes
+pub const REDACTED: &str = "<redacted>";
+
+/// Span processor that replaces the values of attributes whose keys match any of the
+/// configured patterns with `REDACTED`, then hands the span to the wrapped processor.
+#[derive(Debug)]
+pub struct RedactingSpanProcessor<P> {
+    inner: P,
+    patterns: RegexSet,
+}
+
+impl<P: SpanProcessor> RedactingSpanProcessor<P> {
+    /// Wrap `inner`, redacting attributes whose keys match any of `patterns`
+    pub fn new<I, S>(inner: P, patterns: I) -> Result<Self, regex::Error>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Ok(Self {
+            inner,
+            patterns: RegexSet::new(patterns)?,
+        })
+    }
+}
+
+impl<P: SpanProcessor> SpanProcessor for RedactingSpanProcessor<P> {
+    fn on_start(&self, span: &mut Span, cx: &Context) {
+        self.inner.on_start(span, cx);
+    }
+
+    fn on_end(&self, mut span: SpanData) {
+        for attribute in &mut span.attributes {
+            if self.patterns.is_match(attribute.key.as_str()) {
+                attribute.value = Value::from(REDACTED);
+            }
+        }
+        self.inner.on_end(span);
+    }
+
+    fn force_flush(&self) -> opentelemetry::trace::TraceResult<()> {
+        self.inner.force_flush()
+    }
+
+    fn shutdown(&self) -> opentelemetry::trace::TraceResult<()> {
+        self.inner.shutdown()
+    }
+
+    fn set_resource(&mut self, resource: &Resource) {
+        self.inner.set_resource(resource);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::{Arc, Mutex};
+
+    use opentelemetry::trace::{Span as _, Tracer, TracerProvider as _};
+    use opentelemetry::KeyValue;
+    use opentelemetry_sdk::trace::TracerProvider;
+
+    /// Records finished spans in place of an exporter
+    #[derive(Debug, Clone, Default)]
+    struct RecordingProcessor {
+        spans: Arc<Mutex<Vec<SpanData>>>,
+    }
+
+    impl SpanProcessor for RecordingProcessor {
+        fn on_start(&self, _span: &mut Span, _cx: &Context) {}
+
+        fn on_end(&self, span: SpanData) {
+            self.spans.lock().unwrap().push(span);
+        }
+
+        fn force_flush(&self) -> opentelemetry::trace::TraceResult<()> {
+            Ok(())
+        }
+
+        fn shutdown(&self) -> opentelemetry::trace::TraceResult<()> {
+            Ok(())
+        }
+    }
+
+    fn exported_attributes(patterns: &[&str]) -> Vec<KeyValue> {
+        let recorder = RecordingProcessor::default();
+        let processor = RedactingSpanProcessor::new(recorder.clone(), patterns).unwrap();
+        let provider = TracerProvider::builder()
+            .with_span_processor(processor)
+            .build();
+
+        let mut span = provider.tracer("test").start("send_notification");
+        span.set_attribute(KeyValue::new("user.id", "user-123"));
+        span.set_attribute(KeyValue::new("user.email", "someone@example.com"));
+        span.set_attribute(KeyValue::new("notification.event_type", "order.created"));
+        span.end();
+
+        let spans = recorder.spans.lock().unwrap();
+        assert_eq!(spans.len(), 1);
+        spans[0].attributes.clone()
+    }
+
+    fn value_of(attributes: &[KeyValue], key: &str) -> String {
+        attributes
+            .iter()
+            .find(|kv| kv.key.as_str() == key)
+            .map(|kv| kv.value.to_string())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_matching_attributes_are_redacted() {
+        let attributes = exported_attributes(&[r"^user\.id$", r"^user\.email$"]);
+
+        assert_eq!(value_of(&attributes, "user.id"), REDACTED);
+        assert_eq!(value_of(&attributes, "user.email"), REDACTED);
+    }
+
+    #[test]
+    fn test_non_matching_attributes_are_kept() {
+        let attributes = exported_attributes(&[r"^user\.id$"]);
+
+        assert_eq!(value_of(&attributes, "user.email"), "someone@example.com");
+        assert_eq!(value_of(&attributes, "notification.event_type"), "order.created");
+    }
+
+    #[test]
+    fn test_invalid_pattern_rejected() {
+        assert!(RedactingSpanProcessor::new(RecordingProcessor::default(), ["user.("]).is_err());
+    }
+}
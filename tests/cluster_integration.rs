@@ -3,12 +3,13 @@
 //! These tests verify cross-component interactions without requiring
 //! actual Redis or server startup.
 
+use std::collections::HashMap;
 use std::sync::Arc;
 use uuid::Uuid;
 
 use ara_notification_service::cluster::{
-    create_session_store, ClusterConfig, ClusterRouter, RouteResult, RoutedMessage,
-    SessionInfo, SessionStore, SessionStoreBackend,
+    create_session_store, ClusterConfig, ClusterRouter, RouteResult, RouteStrategy,
+    RoutedMessage, SessionInfo, SessionStore, SessionStoreBackend,
 };
 use ara_notification_service::connection_manager::{ConnectionLimits, ConnectionManager};
 
@@ -27,6 +28,9 @@ fn create_test_components() -> (Arc<ConnectionManager>, Arc<dyn SessionStore>, C
         session_prefix: "test:cluster:sessions".to_string(),
         session_ttl_seconds: 60,
         routing_channel: "test:cluster:route".to_string(),
+        route_strategy: RouteStrategy::default(),
+        cluster_secret: "test-secret".to_string(),
+        server_tags: HashMap::new(),
     };
 
     let session_store = create_session_store(&config, None);
@@ -42,6 +46,22 @@ fn create_cluster_config(server_id: &str, enabled: bool) -> ClusterConfig {
         session_prefix: "test:cluster:sessions".to_string(),
         session_ttl_seconds: 60,
         routing_channel: "test:cluster:route".to_string(),
+        route_strategy: RouteStrategy::default(),
+        cluster_secret: "test-secret".to_string(),
+        server_tags: HashMap::new(),
+    }
+}
+
+/// Helper to create a session on "server-1" with a fresh connection ID
+fn local_session(user_id: &str, channels: Vec<String>) -> SessionInfo {
+    SessionInfo {
+        connection_id: Uuid::new_v4(),
+        user_id: user_id.to_string(),
+        tenant_id: "tenant-1".to_string(),
+        server_id: "server-1".to_string(),
+        connected_at: chrono::Utc::now().timestamp(),
+        channels,
+        server_tags: HashMap::new(),
     }
 }
 
@@ -85,13 +105,23 @@ mod session_store_tests {
             server_id: "server-1".to_string(),
             connected_at: chrono::Utc::now().timestamp(),
             channels: vec!["orders".to_string()],
+            server_tags: HashMap::new(),
         };
 
-        // Register should succeed (no-op)
         assert!(store.register_session(&session).await.is_ok());
 
-        // Unregister should succeed (no-op)
+        let all_sessions = store.get_all_sessions().await.unwrap();
+        assert_eq!(all_sessions.len(), 1);
+        assert_eq!(all_sessions[0].connection_id, session.connection_id);
+        assert_eq!(all_sessions[0].channels, vec!["orders"]);
+        assert_eq!(store.cluster_connection_count().await.unwrap(), 1);
+        assert_eq!(store.cluster_user_count().await.unwrap(), 1);
+
         assert!(store.unregister_session(session.connection_id).await.is_ok());
+
+        assert!(store.get_all_sessions().await.unwrap().is_empty());
+        assert_eq!(store.cluster_connection_count().await.unwrap(), 0);
+        assert_eq!(store.cluster_user_count().await.unwrap(), 0);
     }
 
     #[tokio::test]
@@ -99,12 +129,23 @@ mod session_store_tests {
         let config = create_cluster_config("server-1", false);
         let store = create_session_store(&config, None);
 
-        // Local store always returns its own server
-        let servers = store.find_user_servers("any-user").await.unwrap();
+        // Nothing registered yet
+        assert!(store.find_user_servers("user-1").await.unwrap().is_empty());
+        assert!(store.find_channel_servers("orders").await.unwrap().is_empty());
+
+        store
+            .register_session(&local_session("user-1", vec!["orders".to_string()]))
+            .await
+            .unwrap();
+
+        // Only users and channels with a session resolve to this server
+        let servers = store.find_user_servers("user-1").await.unwrap();
         assert_eq!(servers, vec!["server-1"]);
+        assert!(store.find_user_servers("user-2").await.unwrap().is_empty());
 
-        let channel_servers = store.find_channel_servers("any-channel").await.unwrap();
+        let channel_servers = store.find_channel_servers("orders").await.unwrap();
         assert_eq!(channel_servers, vec!["server-1"]);
+        assert!(store.find_channel_servers("alerts").await.unwrap().is_empty());
     }
 
     #[tokio::test]
@@ -119,6 +160,7 @@ mod session_store_tests {
             payload: "{}".to_string(),
             from_server: "server-1".to_string(),
             to_server: Some("server-2".to_string()),
+            signature: String::new(),
         };
 
         // Routing should fail in local mode
@@ -127,16 +169,20 @@ mod session_store_tests {
     }
 
     #[tokio::test]
-    async fn test_local_session_store_cluster_counts_disabled() {
+    async fn test_local_session_store_cluster_counts() {
         let config = create_cluster_config("server-1", false);
         let store = create_session_store(&config, None);
 
-        // Cluster counts should fail in local mode
-        let conn_count = store.cluster_connection_count().await;
-        assert!(conn_count.is_err());
+        assert_eq!(store.cluster_connection_count().await.unwrap(), 0);
+        assert_eq!(store.cluster_user_count().await.unwrap(), 0);
 
-        let user_count = store.cluster_user_count().await;
-        assert!(user_count.is_err());
+        // Two connections for one user, one for another
+        store.register_session(&local_session("user-1", vec![])).await.unwrap();
+        store.register_session(&local_session("user-1", vec![])).await.unwrap();
+        store.register_session(&local_session("user-2", vec![])).await.unwrap();
+
+        assert_eq!(store.cluster_connection_count().await.unwrap(), 3);
+        assert_eq!(store.cluster_user_count().await.unwrap(), 2);
     }
 
     #[tokio::test]
@@ -144,12 +190,69 @@ mod session_store_tests {
         let config = create_cluster_config("server-1", false);
         let store = create_session_store(&config, None);
 
-        // Local store returns empty for distributed queries
-        let all_sessions = store.get_all_sessions().await.unwrap();
-        assert!(all_sessions.is_empty());
+        assert!(store.get_all_sessions().await.unwrap().is_empty());
+        assert!(store.get_user_sessions("user-1").await.unwrap().is_empty());
 
-        let user_sessions = store.get_user_sessions("any-user").await.unwrap();
-        assert!(user_sessions.is_empty());
+        let first = local_session("user-1", vec![]);
+        let second = local_session("user-2", vec![]);
+        store.register_session(&first).await.unwrap();
+        store.register_session(&second).await.unwrap();
+
+        assert_eq!(store.get_all_sessions().await.unwrap().len(), 2);
+
+        let user_sessions = store.get_user_sessions("user-1").await.unwrap();
+        assert_eq!(user_sessions.len(), 1);
+        assert_eq!(user_sessions[0].connection_id, first.connection_id);
+        assert_eq!(user_sessions[0].tenant_id, "tenant-1");
+        assert!(store.get_user_sessions("user-3").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_local_session_store_user_sessions_single_page() {
+        let config = create_cluster_config("server-1", false);
+        let store = create_session_store(&config, None);
+
+        for _ in 0..3 {
+            store.register_session(&local_session("user-1", vec![])).await.unwrap();
+        }
+
+        let (sessions, next_cursor) = store.get_user_sessions_page("user-1", 0, 10).await.unwrap();
+        assert_eq!(sessions.len(), 3);
+        assert_eq!(next_cursor, 0);
+
+        let (sessions, next_cursor) = store.get_user_sessions_page("user-2", 0, 10).await.unwrap();
+        assert!(sessions.is_empty());
+        assert_eq!(next_cursor, 0);
+    }
+
+    #[tokio::test]
+    async fn test_local_session_store_user_sessions_multi_page() {
+        let config = create_cluster_config("server-1", false);
+        let store = create_session_store(&config, None);
+
+        for _ in 0..250 {
+            store.register_session(&local_session("user-1", vec![])).await.unwrap();
+        }
+
+        let mut seen = std::collections::HashSet::new();
+        let mut cursor = 0;
+        let mut pages = 0;
+        loop {
+            let (sessions, next_cursor) =
+                store.get_user_sessions_page("user-1", cursor, 100).await.unwrap();
+            assert!(sessions.len() <= 100);
+            seen.extend(sessions.into_iter().map(|s| s.connection_id));
+            pages += 1;
+            if next_cursor == 0 {
+                break;
+            }
+            cursor = next_cursor;
+        }
+        assert_eq!(pages, 3);
+        assert_eq!(seen.len(), 250);
+
+        // The convenience wrapper follows every page
+        assert_eq!(store.get_user_sessions("user-1").await.unwrap().len(), 250);
     }
 
     #[tokio::test]
@@ -157,12 +260,20 @@ mod session_store_tests {
         let config = create_cluster_config("server-1", false);
         let store = create_session_store(&config, None);
 
-        let connection_id = Uuid::new_v4();
-        let channels = vec!["orders".to_string(), "alerts".to_string()];
+        let session = local_session("user-1", vec![]);
+        store.register_session(&session).await.unwrap();
 
-        // Update should succeed (no-op)
-        let result = store.update_session_channels(connection_id, channels).await;
+        let channels = vec!["orders".to_string(), "alerts".to_string()];
+        let result = store.update_session_channels(session.connection_id, channels.clone()).await;
         assert!(result.is_ok());
+
+        let user_sessions = store.get_user_sessions("user-1").await.unwrap();
+        assert_eq!(user_sessions[0].channels, channels);
+        assert_eq!(store.find_channel_servers("alerts").await.unwrap(), vec!["server-1"]);
+
+        // Updating an unknown connection is ignored
+        assert!(store.update_session_channels(Uuid::new_v4(), vec![]).await.is_ok());
+        assert_eq!(store.get_all_sessions().await.unwrap().len(), 1);
     }
 
     #[tokio::test]
@@ -229,6 +340,7 @@ mod router_tests {
             payload: r#"{"type":"Heartbeat"}"#.to_string(),
             from_server: "other-server".to_string(),
             to_server: None,
+            signature: String::new(),
         };
 
         let delivered = router.handle_routed_message(message).await;
@@ -250,6 +362,7 @@ mod router_tests {
             payload: r#"{"type":"Heartbeat"}"#.to_string(),
             from_server: "server-2".to_string(),
             to_server: Some("server-3".to_string()), // Not our server
+            signature: String::new(),
         };
 
         // Should return 0 because message is not for this server
@@ -269,6 +382,7 @@ mod router_tests {
             payload: "invalid json".to_string(),
             from_server: "other-server".to_string(),
             to_server: None,
+            signature: String::new(),
         };
 
         // Should return 0 due to parse failure
@@ -336,6 +450,7 @@ mod session_info_tests {
             server_id: "server-1".to_string(),
             connected_at: 1234567890,
             channels: vec!["orders".to_string(), "alerts".to_string()],
+            server_tags: HashMap::new(),
         };
 
         let json = serde_json::to_string(&session).unwrap();
@@ -358,6 +473,7 @@ mod session_info_tests {
             server_id: "server-1".to_string(),
             connected_at: 0,
             channels: vec![],
+            server_tags: HashMap::new(),
         };
 
         let json = serde_json::to_string(&session).unwrap();
@@ -376,6 +492,7 @@ mod session_info_tests {
             server_id: "server-1".to_string(),
             connected_at: 0,
             channels: channels.clone(),
+            server_tags: HashMap::new(),
         };
 
         let json = serde_json::to_string(&session).unwrap();
@@ -400,6 +517,7 @@ mod routed_message_tests {
             payload: r#"{"type":"notification","data":{"title":"Hello"}}"#.to_string(),
             from_server: "server-1".to_string(),
             to_server: Some("server-2".to_string()),
+            signature: String::new(),
         };
 
         let json = serde_json::to_string(&message).unwrap();
@@ -422,6 +540,7 @@ mod routed_message_tests {
             payload: "{}".to_string(),
             from_server: "server-1".to_string(),
             to_server: None,
+            signature: String::new(),
         };
 
         let json = serde_json::to_string(&message).unwrap();
@@ -440,6 +559,7 @@ mod routed_message_tests {
             payload: r#"{"type":"broadcast"}"#.to_string(),
             from_server: "server-1".to_string(),
             to_server: None, // Broadcast goes to all servers
+            signature: String::new(),
         };
 
         assert!(message.to_server.is_none());
@@ -455,6 +575,7 @@ mod routed_message_tests {
             payload: "test".to_string(),
             from_server: "server-1".to_string(),
             to_server: None,
+            signature: String::new(),
         };
 
         let cloned = message.clone();
@@ -489,6 +610,9 @@ mod config_tests {
             session_prefix: "custom:prefix".to_string(),
             session_ttl_seconds: 120,
             routing_channel: "custom:route".to_string(),
+            route_strategy: RouteStrategy::default(),
+            cluster_secret: "test-secret".to_string(),
+            server_tags: HashMap::new(),
         };
 
         assert!(config.enabled);
@@ -506,6 +630,9 @@ mod config_tests {
             session_prefix: "prefix".to_string(),
             session_ttl_seconds: 30,
             routing_channel: "route".to_string(),
+            route_strategy: RouteStrategy::default(),
+            cluster_secret: "test-secret".to_string(),
+            server_tags: HashMap::new(),
         };
 
         let cloned = config.clone();
@@ -611,7 +738,7 @@ mod cross_component_tests {
 
         // Session store operations
         let servers = session_store.find_user_servers("user-1").await.unwrap();
-        assert_eq!(servers, vec!["server-1"]);
+        assert!(servers.is_empty());
 
         let refreshed = session_store.refresh_sessions().await.unwrap();
         assert_eq!(refreshed, 0);
@@ -662,6 +789,7 @@ mod error_handling_tests {
             payload: "{}".to_string(),
             from_server: "server-1".to_string(),
             to_server: None,
+            signature: String::new(),
         };
 
         let result = store.publish_routed_message(&message).await;
@@ -669,15 +797,13 @@ mod error_handling_tests {
     }
 
     #[tokio::test]
-    async fn test_local_store_returns_disabled_for_cluster_counts() {
+    async fn test_local_store_counts_without_redis() {
         let config = create_cluster_config("server-1", false);
         let store = create_session_store(&config, None);
 
-        let conn_result = store.cluster_connection_count().await;
-        assert!(matches!(conn_result, Err(SessionStoreError::Disabled)));
-
-        let user_result = store.cluster_user_count().await;
-        assert!(matches!(user_result, Err(SessionStoreError::Disabled)));
+        // Counts come from the in-memory sessions rather than failing as disabled
+        assert!(matches!(store.cluster_connection_count().await, Ok(0)));
+        assert!(matches!(store.cluster_user_count().await, Ok(0)));
     }
 }
 
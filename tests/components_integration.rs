@@ -12,7 +12,9 @@ use chrono::Utc;
 use serde_json::json;
 use uuid::Uuid;
 
-use ara_notification_service::cluster::{create_session_store, ClusterConfig, ClusterRouter};
+use ara_notification_service::cluster::{
+    create_session_store, ClusterConfig, ClusterRouter, RouteStrategy, SessionInfo,
+};
 use ara_notification_service::config::{AckSettingsConfig, QueueConfig as SettingsQueueConfig};
 use ara_notification_service::connection_manager::{ConnectionLimits, ConnectionManager};
 use ara_notification_service::notification::{
@@ -41,6 +43,7 @@ fn create_full_test_environment() -> TestEnvironment {
         message_ttl_seconds: 3600,
         cleanup_interval_seconds: 300,
         redis_prefix: "".to_string(),
+        shard_count: 64,
     };
     let queue_backend = create_queue_backend(&queue_config, None, None, None);
 
@@ -66,6 +69,9 @@ fn create_full_test_environment() -> TestEnvironment {
         session_prefix: "test:sessions".to_string(),
         session_ttl_seconds: 60,
         routing_channel: "test:route".to_string(),
+        route_strategy: RouteStrategy::default(),
+        cluster_secret: "test-secret".to_string(),
+        server_tags: Default::default(),
     };
     let session_store = create_session_store(&cluster_config, None);
     let cluster_router = Arc::new(ClusterRouter::new(
@@ -88,6 +94,12 @@ fn create_full_test_environment() -> TestEnvironment {
         bucket_ttl_seconds: 300,
         backend: "local".to_string(),
         redis_prefix: "test:ratelimit".to_string(),
+        ip_allowlist: Vec::new(),
+        ip_blocklist: Vec::new(),
+        emergency_bypass_deny_ratio: 0.5,
+        emergency_bypass_window_seconds: 300,
+        emergency_bypass_min_requests: 100,
+        rules: Vec::new(),
     };
     let rate_limiter = Arc::new(RateLimiter::new(rate_limit_config));
 
@@ -211,6 +223,40 @@ mod dispatcher_tests {
         assert_eq!(stats.broadcast_notifications, 5);
     }
 
+    #[tokio::test]
+    async fn test_dispatch_stats_per_event_type() {
+        let env = create_full_test_environment();
+
+        let (tx1, _rx1) = tokio::sync::mpsc::channel(32);
+        let (tx2, _rx2) = tokio::sync::mpsc::channel(32);
+        env.connection_manager
+            .register("user-1".to_string(), "default".to_string(), vec![], tx1)
+            .unwrap();
+        env.connection_manager
+            .register("user-2".to_string(), "default".to_string(), vec![], tx2)
+            .unwrap();
+
+        let dispatches = [
+            ("order.created", NotificationTarget::User("user-1".to_string())),
+            ("order.created", NotificationTarget::User("user-2".to_string())),
+            ("system.alert", NotificationTarget::Broadcast),
+            ("user.updated", NotificationTarget::User("offline-user".to_string())),
+        ];
+        for (event_type, target) in dispatches {
+            let event = NotificationBuilder::new(event_type, "integration-test").build();
+            let _ = env.dispatcher.dispatch(target, event).await;
+        }
+
+        let stats = env.dispatcher.stats();
+        assert_eq!(stats.event_type_counts.len(), 3);
+        assert_eq!(stats.event_type_counts["order.created"], 2);
+        assert_eq!(stats.event_type_counts["system.alert"], 1);
+        assert_eq!(stats.event_type_counts["user.updated"], 1);
+        assert_eq!(stats.event_type_delivered["order.created"], 2);
+        assert_eq!(stats.event_type_delivered["system.alert"], 2);
+        assert_eq!(stats.event_type_delivered["user.updated"], 0);
+    }
+
     #[tokio::test]
     async fn test_dispatch_to_multiple_channels() {
         let env = create_full_test_environment();
@@ -251,6 +297,9 @@ mod template_tests {
             default_priority: Priority::High,
             default_ttl: Some(86400),
             description: None,
+            strict_mode: false,
+            extends: None,
+            variable_schema: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
@@ -287,6 +336,9 @@ mod template_tests {
                 default_priority: Priority::Normal,
                 default_ttl: None,
                 description: None,
+                strict_mode: false,
+                extends: None,
+                variable_schema: None,
                 created_at: Utc::now(),
                 updated_at: Utc::now(),
             };
@@ -318,6 +370,9 @@ mod template_tests {
             default_priority: Priority::Normal,
             default_ttl: None,
             description: None,
+            strict_mode: false,
+            extends: None,
+            variable_schema: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
@@ -334,6 +389,9 @@ mod template_tests {
             default_priority: Priority::Normal,
             default_ttl: None,
             description: None,
+            strict_mode: false,
+            extends: None,
+            variable_schema: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
@@ -501,6 +559,7 @@ mod queue_tests {
             message_ttl_seconds: 3600,
             cleanup_interval_seconds: 300,
             redis_prefix: "".to_string(),
+            shard_count: 64,
         };
         let queue = create_queue_backend(&config, None, None, None);
 
@@ -519,6 +578,7 @@ mod queue_tests {
             message_ttl_seconds: 3600,
             cleanup_interval_seconds: 300,
             redis_prefix: "".to_string(),
+            shard_count: 64,
         };
         let queue = create_queue_backend(&config, None, None, None);
 
@@ -541,7 +601,7 @@ mod ack_tracker_tests {
         // Track a notification
         let notification_id = Uuid::new_v4();
         let connection_id = Uuid::new_v4();
-        env.ack_backend.track(notification_id, "user-1", connection_id).await;
+        env.ack_backend.track(notification_id, "user-1", connection_id, "order.created").await;
 
         // Acknowledge it
         let result = env.ack_backend.acknowledge(notification_id, "user-1").await;
@@ -558,7 +618,7 @@ mod ack_tracker_tests {
 
         let notification_id = Uuid::new_v4();
         let connection_id = Uuid::new_v4();
-        env.ack_backend.track(notification_id, "user-1", connection_id).await;
+        env.ack_backend.track(notification_id, "user-1", connection_id, "order.created").await;
 
         // Wrong user should not be able to acknowledge
         let result = env.ack_backend.acknowledge(notification_id, "user-2").await;
@@ -577,7 +637,7 @@ mod ack_tracker_tests {
         for i in 0..5 {
             let notif_id = Uuid::new_v4();
             let conn_id = Uuid::new_v4();
-            env.ack_backend.track(notif_id, "user-1", conn_id).await;
+            env.ack_backend.track(notif_id, "user-1", conn_id, "order.created").await;
 
             // Acknowledge only first 3
             if i < 3 {
@@ -605,7 +665,7 @@ mod ack_tracker_tests {
         let connection_id = Uuid::new_v4();
 
         // Should be no-op when disabled
-        tracker.track(notification_id, "user-1", connection_id).await;
+        tracker.track(notification_id, "user-1", connection_id, "order.created").await;
 
         let stats = tracker.stats().await;
         assert_eq!(stats.total_tracked, 0);
@@ -635,7 +695,21 @@ mod cluster_integration_tests {
         // Session store should report correct server ID
         assert_eq!(env.session_store.server_id(), "test-server");
 
-        // Router should use same session store
+        // Unknown users are not located on any server
+        let servers = env.session_store.find_user_servers("user-1").await.unwrap();
+        assert!(servers.is_empty());
+
+        // A registered session locates the user on this server
+        let session = SessionInfo {
+            connection_id: Uuid::new_v4(),
+            user_id: "user-1".to_string(),
+            tenant_id: "default".to_string(),
+            server_id: env.session_store.server_id().to_string(),
+            connected_at: Utc::now().timestamp(),
+            channels: vec![],
+            server_tags: Default::default(),
+        };
+        env.session_store.register_session(&session).await.unwrap();
         let servers = env.session_store.find_user_servers("user-1").await.unwrap();
         assert_eq!(servers, vec!["test-server"]);
     }
@@ -656,6 +730,9 @@ mod cluster_integration_tests {
             default_priority: Priority::High,
             default_ttl: None,
             description: None,
+            strict_mode: false,
+            extends: None,
+            variable_schema: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
@@ -0,0 +1,211 @@
+//! gRPC API integration tests
+//!
+//! Starts the gRPC server on a fixed local port over a real `AppState` (in-memory
+//! backends) and drives it with the generated client.
+
+use std::time::Duration;
+
+use jsonwebtoken::{encode, EncodingKey, Header};
+use serde_json::json;
+use tonic::metadata::MetadataValue;
+use tonic::transport::server::TcpIncoming;
+use tonic::transport::{Channel, Server};
+use tonic::{Code, Request};
+
+use ara_notification_service::auth::Claims;
+use ara_notification_service::config::Settings;
+use ara_notification_service::metrics::GRPC_REQUESTS_TOTAL;
+use ara_notification_service::server::AppState;
+use ara_notification_service::triggers::grpc_proto::notification_service_client::NotificationServiceClient;
+use ara_notification_service::triggers::grpc_proto::{
+    batch_target, notification_content, BatchItem, BatchSendRequest, BatchTarget, DirectContent,
+    NotificationContent, Priority, SendOptions, SendToUserRequest,
+};
+use ara_notification_service::triggers::GrpcNotificationService;
+
+const JWT_SECRET: &str = "test-secret-key-for-grpc-integration";
+const API_KEY: &str = "test-api-key-for-grpc-integration";
+const GRPC_ADDR: &str = "127.0.0.1:50951";
+
+async fn start_server() -> AppState {
+    let settings: Settings = serde_json::from_value(json!({
+        "jwt": { "secret": JWT_SECRET },
+        "api": { "key": API_KEY }
+    }))
+    .unwrap();
+    let state = AppState::new(settings).await.unwrap();
+
+    // Bind before spawning so the client never races the listener
+    let incoming = TcpIncoming::new(GRPC_ADDR.parse().unwrap(), true, None).unwrap();
+    let service = GrpcNotificationService::new(state.clone()).into_server();
+    tokio::spawn(async move {
+        Server::builder()
+            .add_service(service)
+            .serve_with_incoming(incoming)
+            .await
+            .unwrap();
+    });
+
+    state
+}
+
+fn bearer_token(user_id: &str, scope: Option<&str>) -> String {
+    let now = chrono::Utc::now().timestamp();
+    let claims = Claims {
+        sub: user_id.to_string(),
+        exp: now + 3600,
+        iat: now,
+        roles: vec![],
+        tenant_id: None,
+        scope: scope.map(str::to_string),
+        extra: Default::default(),
+    };
+    let token = encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(JWT_SECRET.as_bytes()),
+    )
+    .unwrap();
+    format!("Bearer {}", token)
+}
+
+fn authorized<T>(message: T) -> Request<T> {
+    let mut request = Request::new(message);
+    let token: MetadataValue<_> = bearer_token("publisher", Some("publish")).parse().unwrap();
+    request.metadata_mut().insert("authorization", token);
+    request
+}
+
+fn direct(event_type: &str, payload: serde_json::Value) -> Option<NotificationContent> {
+    Some(NotificationContent {
+        content: Some(notification_content::Content::Direct(DirectContent {
+            event_type: event_type.to_string(),
+            payload_json: payload.to_string(),
+        })),
+    })
+}
+
+#[tokio::test]
+async fn test_grpc_send_and_batch() {
+    let state = start_server().await;
+    let (tx, mut rx) = tokio::sync::mpsc::channel(32);
+    state
+        .connection_manager
+        .register("user-1".to_string(), "default".to_string(), vec![], tx)
+        .unwrap();
+
+    let channel = Channel::from_static("http://127.0.0.1:50951")
+        .connect_timeout(Duration::from_secs(5))
+        .connect()
+        .await
+        .unwrap();
+    let mut client = NotificationServiceClient::new(channel);
+
+    // Missing token is rejected
+    let err = client
+        .send_to_user(SendToUserRequest {
+            target_user_id: "user-1".to_string(),
+            content: direct("order.created", json!({})),
+            options: None,
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Code::Unauthenticated);
+
+    // An end-user token without the publish scope cannot publish
+    let mut request = Request::new(SendToUserRequest {
+        target_user_id: "user-1".to_string(),
+        content: direct("order.created", json!({})),
+        options: None,
+    });
+    let token: MetadataValue<_> = bearer_token("user-2", None).parse().unwrap();
+    request.metadata_mut().insert("authorization", token);
+    let err = client.send_to_user(request).await.unwrap_err();
+    assert_eq!(err.code(), Code::PermissionDenied);
+
+    // The service API key can publish, a wrong one cannot
+    for (key, expected) in [("wrong-key", Err(Code::Unauthenticated)), (API_KEY, Ok(1))] {
+        let mut request = Request::new(SendToUserRequest {
+            target_user_id: "user-1".to_string(),
+            content: direct("order.created", json!({})),
+            options: None,
+        });
+        request.metadata_mut().insert("x-api-key", key.parse().unwrap());
+        let result = client.send_to_user(request).await;
+        assert_eq!(
+            result.map(|r| r.into_inner().delivered_to).map_err(|e| e.code()),
+            expected
+        );
+    }
+    let _ = rx.recv().await;
+
+    // Authenticated send reaches the connected user
+    let before = GRPC_REQUESTS_TOTAL.with_label_values(&["SendToUser"]).get();
+    let notification_id = uuid::Uuid::new_v4().to_string();
+    let response = client
+        .send_to_user(authorized(SendToUserRequest {
+            target_user_id: "user-1".to_string(),
+            content: direct("order.created", json!({"order_id": 42})),
+            options: Some(SendOptions {
+                priority: Priority::High as i32,
+                ttl: Some(60),
+                correlation_id: Some("req-1".to_string()),
+                notification_id: Some(notification_id.clone()),
+            }),
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(response.success);
+    assert_eq!(response.delivered_to, 1);
+    assert_eq!(response.notification_id, notification_id);
+    assert!(GRPC_REQUESTS_TOTAL.with_label_values(&["SendToUser"]).get() > before);
+
+    let delivered = tokio::time::timeout(Duration::from_secs(1), rx.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(format!("{:?}", delivered).contains("order.created"));
+
+    // Malformed payload JSON is an invalid argument
+    let mut bad = direct("order.created", json!({}));
+    if let Some(notification_content::Content::Direct(d)) =
+        bad.as_mut().and_then(|c| c.content.as_mut())
+    {
+        d.payload_json = "{oops".to_string();
+    }
+    let err = client
+        .send_to_user(authorized(SendToUserRequest {
+            target_user_id: "user-1".to_string(),
+            content: bad,
+            options: None,
+        }))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Code::InvalidArgument);
+
+    // Batch send with deduplication
+    let item = |user: &str| BatchItem {
+        target: Some(BatchTarget {
+            target: Some(batch_target::Target::User(user.to_string())),
+        }),
+        content: direct("order.shipped", json!({})),
+        options: None,
+    };
+    let response = client
+        .batch_send(authorized(BatchSendRequest {
+            notifications: vec![item("user-1"), item("user-1"), item("user-2")],
+            stop_on_error: false,
+            deduplicate: true,
+            dry_run: false,
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    let summary = response.summary.unwrap();
+    assert_eq!(summary.total, 3);
+    assert_eq!(summary.skipped, 1);
+    assert_eq!(summary.total_delivered, 1);
+    assert_eq!(response.results.len(), 3);
+    assert!(response.results[1].skipped);
+}